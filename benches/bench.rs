@@ -1,4 +1,4 @@
-//!
+//! Read/write benchmarks.
 
 use criterion::{criterion_group, criterion_main, Criterion};
 use icu_locid::locale;
@@ -48,6 +48,7 @@ fn create_wb(rows: u32, cols: u32) -> Result<WorkBook, OdsError> {
     Ok(wb)
 }
 
+#[allow(dead_code)]
 fn write_wb<'a>(wb: &'a mut WorkBook) -> impl FnMut() -> Result<(), OdsError> + 'a {
     move || {
         let _buf = write_ods_buf_uncompressed(wb, Vec::new())?;
@@ -68,7 +69,5 @@ fn criterion_write(c: &mut Criterion) {
     });
 }
 
-///
 criterion_group!(benches, criterion_read, criterion_write);
-///
 criterion_main!(benches);
@@ -11,7 +11,6 @@ use spreadsheet_ods::style::CellStyle;
 use spreadsheet_ods::{Sheet, Value, WorkBook};
 use std::fs;
 
-///
 pub fn main() {
     fs::create_dir_all("test_out").expect("create_dir");
 
@@ -52,7 +51,7 @@ pub fn main() {
     let sheet = wb.sheet_mut(1);
     sheet.set_value(0, 0, 21.4f32);
     sheet.set_value(0, 1, "foo");
-    sheet.set_styled_value(0, 2, NaiveDate::from_ymd_opt(2020, 03, 01), &date_style_ref);
+    sheet.set_styled_value(0, 2, NaiveDate::from_ymd_opt(2020, 3, 1), &date_style_ref);
     sheet.set_formula(0, 3, format!("of:={}+1", formula::fcellref(0, 0)));
 
     let mut sheet = Sheet::new("sample");
@@ -1,6 +1,4 @@
-//!
-//!
-//!
+//! Value formatting.
 
 use std::fs;
 
@@ -12,7 +10,6 @@ use spreadsheet_ods::{
     write_ods, CellStyle, OdsResult, Sheet, ValueFormatNumber, WorkBook,
 };
 
-///
 pub fn main() -> OdsResult<()> {
     let _ = fs::create_dir_all("examples_out");
 
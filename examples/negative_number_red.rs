@@ -1,6 +1,4 @@
-//!
-//!
-//!
+//! Negative numbers in red via a style-map.
 
 use color::Rgb;
 use icu_locid::locale;
@@ -9,7 +7,6 @@ use spreadsheet_ods::format::{ValueFormatTrait, ValueStyleMap};
 use spreadsheet_ods::*;
 use std::fs;
 
-///
 pub fn main() -> Result<(), OdsError> {
     let _ = fs::create_dir_all("examples_out");
 
@@ -1,11 +1,8 @@
-//!
 //! Read-only access.
-//!
 use std::{fs::File, io::BufReader};
 
 use spreadsheet_ods::{OdsOptions, OdsResult};
 
-///
 pub fn main() -> OdsResult<()> {
     read_only()?;
     iterate()?;
@@ -63,16 +60,21 @@ fn read_only() -> OdsResult<()> {
                 spreadsheet_ods::Value::Empty => {}
                 spreadsheet_ods::Value::Boolean(v) => println!("({},{}) = bool {}", r, c, v),
                 spreadsheet_ods::Value::Number(v) => println!("({},{}) = number {}", r, c, v),
+                #[cfg(feature = "rust_decimal")]
+                spreadsheet_ods::Value::Decimal(v) => println!("({},{}) = decimal {}", r, c, v),
                 spreadsheet_ods::Value::Percentage(v) => println!("({},{}) = percent {}", r, c, v),
                 spreadsheet_ods::Value::Currency(v, cur) => {
                     println!("({},{}) = currency {} {}", r, c, v, cur)
                 }
                 spreadsheet_ods::Value::Text(v) => println!("({},{}) = text {}", r, c, v),
                 spreadsheet_ods::Value::TextXml(v) => println!("({},{}) = xml {:?}", r, c, v),
-                spreadsheet_ods::Value::DateTime(v) => println!("({},{}) = date {}", r, c, v),
+                spreadsheet_ods::Value::DateTime(v) => println!("({},{}) = datetime {}", r, c, v),
+                spreadsheet_ods::Value::Date(v) => println!("({},{}) = date {}", r, c, v),
+                spreadsheet_ods::Value::Time(v) => println!("({},{}) = time {}", r, c, v),
                 spreadsheet_ods::Value::TimeDuration(v) => {
                     println!("({},{}) = duration {}", r, c, v)
                 }
+                spreadsheet_ods::Value::Error(v) => println!("({},{}) = error {}", r, c, v),
             }
         }
     }
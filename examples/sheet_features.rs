@@ -8,7 +8,6 @@ use spreadsheet_ods::text::{
 };
 use spreadsheet_ods::{cm, pt, write_ods, CellRange, OdsResult, Sheet, WorkBook};
 
-///
 pub fn main() -> OdsResult<()> {
     printing()?;
     cell_grid()?;
@@ -106,9 +105,9 @@ fn printing() -> OdsResult<()> {
     sheet_data(&mut sh);
 
     // define print header
-    sh.set_header_rows(0, 1);
+    sh.set_repeat_header_rows(0, 1)?;
     // restriction on the print-data.
-    sh.add_print_range(CellRange::local(1, 0, 1001, 3));
+    sh.add_print_range(CellRange::local(1, 0, 1001, 3))?;
 
     wb.push_sheet(sh);
 
@@ -0,0 +1,195 @@
+//!
+//! Basic macros stored in the document.
+//!
+//! LibreOffice stores the Basic libraries of a document in the directory
+//! Basic/ of the package. Each library has its own sub-directory with one
+//! xml file per module.
+//!
+//! ```
+//! use spreadsheet_ods::basic::BasicModule;
+//! use spreadsheet_ods::WorkBook;
+//!
+//! let mut book = WorkBook::new_empty();
+//! book.add_script_module(
+//!     "Standard",
+//!     BasicModule::new("Module1", "Sub Main\n  MsgBox \"Hello\"\nEnd Sub\n"),
+//! );
+//! ```
+//!
+//! Password protected libraries are kept as they are, but their modules
+//! can't be accessed.
+//!
+
+use get_size2::GetSize;
+
+/// One Basic library of the document.
+#[derive(Clone, Debug, Default, PartialEq, GetSize)]
+pub struct BasicLibrary {
+    name: String,
+    readonly: bool,
+    password_protected: bool,
+    /// Linked library instead of an embedded one.
+    link: Option<String>,
+    modules: Vec<BasicModule>,
+}
+
+impl BasicLibrary {
+    /// New empty library.
+    pub fn new<S: Into<String>>(name: S) -> Self {
+        Self {
+            name: name.into(),
+            ..Default::default()
+        }
+    }
+
+    /// New library that is linked to an external library container,
+    /// e.g. "vnd.sun.star.expand:$UNO_USER_PACKAGES_CACHE/...".
+    pub fn new_link<S: Into<String>, T: Into<String>>(name: S, href: T) -> Self {
+        Self {
+            name: name.into(),
+            link: Some(href.into()),
+            ..Default::default()
+        }
+    }
+
+    pub(crate) fn new_protected<S: Into<String>>(name: S, readonly: bool) -> Self {
+        Self {
+            name: name.into(),
+            readonly,
+            password_protected: true,
+            ..Default::default()
+        }
+    }
+
+    /// Name of the library.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Readonly library.
+    pub fn set_readonly(&mut self, readonly: bool) {
+        self.readonly = readonly;
+    }
+
+    /// Readonly library.
+    pub fn readonly(&self) -> bool {
+        self.readonly
+    }
+
+    /// The library is password protected. The modules are encrypted and
+    /// not available. The library is written back unchanged.
+    pub fn password_protected(&self) -> bool {
+        self.password_protected
+    }
+
+    /// Link to an external library.
+    pub fn link(&self) -> Option<&str> {
+        self.link.as_deref()
+    }
+
+    /// Adds a module, replaces an existing one with the same name.
+    pub fn add_module(&mut self, module: BasicModule) {
+        if let Some(v) = self.modules.iter_mut().find(|v| v.name == module.name) {
+            *v = module;
+        } else {
+            self.modules.push(module);
+        }
+    }
+
+    /// Removes a module.
+    pub fn remove_module(&mut self, name: &str) -> Option<BasicModule> {
+        let pos = self.modules.iter().position(|v| v.name == name)?;
+        Some(self.modules.remove(pos))
+    }
+
+    /// Module by name.
+    pub fn module(&self, name: &str) -> Option<&BasicModule> {
+        self.modules.iter().find(|v| v.name == name)
+    }
+
+    /// Module by name.
+    pub fn module_mut(&mut self, name: &str) -> Option<&mut BasicModule> {
+        self.modules.iter_mut().find(|v| v.name == name)
+    }
+
+    /// All modules.
+    pub fn modules(&self) -> &Vec<BasicModule> {
+        &self.modules
+    }
+
+    /// All modules.
+    pub fn modules_mut(&mut self) -> &mut Vec<BasicModule> {
+        &mut self.modules
+    }
+}
+
+/// One Basic module.
+#[derive(Clone, Debug, PartialEq, GetSize)]
+pub struct BasicModule {
+    name: String,
+    language: String,
+    module_type: String,
+    source: String,
+}
+
+impl Default for BasicModule {
+    fn default() -> Self {
+        Self {
+            name: Default::default(),
+            language: "StarBasic".to_string(),
+            module_type: "normal".to_string(),
+            source: Default::default(),
+        }
+    }
+}
+
+impl BasicModule {
+    /// New StarBasic module.
+    pub fn new<S: Into<String>, T: Into<String>>(name: S, source: T) -> Self {
+        Self {
+            name: name.into(),
+            source: source.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Name of the module.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Name of the module.
+    pub fn set_name<S: Into<String>>(&mut self, name: S) {
+        self.name = name.into();
+    }
+
+    /// Language, usually "StarBasic".
+    pub fn language(&self) -> &str {
+        &self.language
+    }
+
+    /// Language, usually "StarBasic".
+    pub fn set_language<S: Into<String>>(&mut self, language: S) {
+        self.language = language.into();
+    }
+
+    /// Module type: "normal", "class", "form" or "document".
+    pub fn module_type(&self) -> &str {
+        &self.module_type
+    }
+
+    /// Module type: "normal", "class", "form" or "document".
+    pub fn set_module_type<S: Into<String>>(&mut self, module_type: S) {
+        self.module_type = module_type.into();
+    }
+
+    /// Source code.
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    /// Source code.
+    pub fn set_source<S: Into<String>>(&mut self, source: S) {
+        self.source = source.into();
+    }
+}
@@ -1,6 +1,8 @@
+use crate::attrmap2::AttrMap2;
 use crate::draw::{Annotation, DrawFrame};
 use crate::validation::ValidationRef;
-use crate::value_::Value;
+use crate::value_::{Value, ValueType};
+use crate::xmltree::XmlTag;
 use crate::CellStyleRef;
 use get_size2::GetSize;
 use std::fmt::{Display, Formatter};
@@ -107,6 +109,15 @@ pub(crate) struct CellDataExt {
     pub(crate) annotation: Option<Box<Annotation>>,
     // Draw
     pub(crate) draw_frames: Vec<DrawFrame>,
+    // Declared result type of the formula.
+    pub(crate) formula_type: Option<ValueType>,
+    // Displayed text as read from the file.
+    pub(crate) display_text: Option<String>,
+    // Unknown attributes, kept for round-tripping. These alone
+    // don't keep an otherwise empty cell.
+    pub(crate) attr: AttrMap2,
+    // Unknown child elements, kept for round-tripping.
+    pub(crate) xml: Vec<XmlTag>,
 }
 
 impl Default for CellData {
@@ -166,6 +177,9 @@ impl CellData {
             if !extra.matrix_span.is_empty() {
                 return false;
             }
+            if !extra.xml.is_empty() {
+                return false;
+            }
         }
         true
     }
@@ -194,84 +208,65 @@ impl CellData {
     }
 
     pub(crate) fn cloned_cell_content(&self) -> CellContent {
-        let (validation_name, span, matrix_span, annotation, draw_frames) =
-            if let Some(extra) = &self.extra {
-                (
-                    extra.validation_name.clone(),
-                    extra.span,
-                    extra.matrix_span,
-                    extra.annotation.clone(),
-                    extra.draw_frames.clone(),
-                )
-            } else {
-                (
-                    None,
-                    Default::default(),
-                    Default::default(),
-                    None,
-                    Vec::new(),
-                )
-            };
-
-        CellContent {
-            value: self.value.clone(),
-            style: self.style.clone(),
-            formula: self.formula.clone(),
-            repeat: self.repeat,
-            validation_name,
-            span,
-            matrix_span,
-            annotation,
-            draw_frames,
-        }
+        self.clone().into_cell_content()
     }
 
     pub(crate) fn into_cell_content(self) -> CellContent {
-        let (validation_name, span, matrix_span, annotation, draw_frames) =
-            if let Some(extra) = self.extra {
-                (
-                    extra.validation_name,
-                    extra.span,
-                    extra.matrix_span,
-                    extra.annotation,
-                    extra.draw_frames,
-                )
-            } else {
-                (
-                    None,
-                    Default::default(),
-                    Default::default(),
-                    None,
-                    Vec::new(),
-                )
-            };
+        let extra = self.extra.map(|v| *v).unwrap_or_default();
 
         CellContent {
             value: self.value,
             style: self.style,
             formula: self.formula,
             repeat: self.repeat,
+            validation_name: extra.validation_name,
+            span: extra.span,
+            matrix_span: extra.matrix_span,
+            annotation: extra.annotation,
+            draw_frames: extra.draw_frames,
+            display_text: extra.display_text,
+            formula_type: extra.formula_type,
+            attr: extra.attr,
+            xml: extra.xml,
+        }
+    }
+
+    pub(crate) fn cell_content_ref(&self) -> CellContentRef<'_> {
+        let (
             validation_name,
             span,
             matrix_span,
             annotation,
             draw_frames,
-        }
-    }
-
-    pub(crate) fn cell_content_ref(&self) -> CellContentRef<'_> {
-        let (validation_name, span, matrix_span, annotation, draw_frames) =
-            if let Some(extra) = &self.extra {
-                (
-                    extra.validation_name.as_ref(),
-                    extra.span,
-                    extra.matrix_span,
-                    extra.annotation.as_ref(),
-                    Some(&extra.draw_frames),
-                )
-            } else {
-                (None, CellSpan::default(), CellSpan::default(), None, None)
-            };
+            display_text,
+            formula_type,
+            attr,
+            xml,
+        ) = if let Some(extra) = &self.extra {
+            (
+                extra.validation_name.as_ref(),
+                extra.span,
+                extra.matrix_span,
+                extra.annotation.as_ref(),
+                Some(&extra.draw_frames),
+                extra.display_text.as_ref(),
+                extra.formula_type,
+                Some(&extra.attr),
+                Some(&extra.xml),
+            )
+        } else {
+            (
+                None,
+                CellSpan::default(),
+                CellSpan::default(),
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+            )
+        };
 
         CellContentRef {
             value: &self.value,
@@ -283,6 +278,10 @@ impl CellData {
             matrix_span,
             annotation: annotation.map(|v| v.as_ref()),
             draw_frames,
+            display_text,
+            formula_type,
+            attr,
+            xml,
         }
     }
 }
@@ -309,9 +308,36 @@ pub struct CellContentRef<'a> {
     pub annotation: Option<&'a Annotation>,
     /// Reference to draw-frames.
     pub draw_frames: Option<&'a Vec<DrawFrame>>,
+    /// Reference to the displayed text.
+    display_text: Option<&'a String>,
+    /// Declared result type of the formula.
+    formula_type: Option<ValueType>,
+    /// Unknown attributes, kept for round-tripping.
+    attr: Option<&'a AttrMap2>,
+    /// Unknown child elements, kept for round-tripping.
+    xml: Option<&'a Vec<XmlTag>>,
 }
 
 impl<'a> CellContentRef<'a> {
+    /// Content of an empty cell.
+    pub(crate) fn empty() -> Self {
+        CellContentRef {
+            value: &Value::Empty,
+            style: None,
+            formula: None,
+            repeat: 1,
+            validation_name: None,
+            span: Default::default(),
+            matrix_span: Default::default(),
+            annotation: None,
+            draw_frames: None,
+            display_text: None,
+            formula_type: None,
+            attr: None,
+            xml: None,
+        }
+    }
+
     /// Returns the value.
     #[inline]
     pub fn value(&self) -> &'a Value {
@@ -324,6 +350,12 @@ impl<'a> CellContentRef<'a> {
         self.formula
     }
 
+    /// Returns the declared result type of the formula.
+    #[inline]
+    pub fn formula_type(&self) -> Option<ValueType> {
+        self.formula_type
+    }
+
     /// Returns the cell style.
     #[inline]
     pub fn style(&self) -> Option<&'a CellStyleRef> {
@@ -378,6 +410,13 @@ impl<'a> CellContentRef<'a> {
         self.draw_frames
     }
 
+    /// Returns the text that was displayed for the cell, as read from
+    /// the file. Only available with [OdsOptions::display_text](crate::OdsOptions::display_text).
+    #[inline]
+    pub fn display_text(&self) -> Option<&'a String> {
+        self.display_text
+    }
+
     /// Creates a owned CellContent.
     pub fn to_owned(&self) -> CellContent {
         CellContent {
@@ -390,6 +429,10 @@ impl<'a> CellContentRef<'a> {
             matrix_span: self.matrix_span,
             annotation: self.annotation.map(|v| Box::new(v.clone())),
             draw_frames: self.draw_frames.cloned().unwrap_or_default(),
+            display_text: self.display_text.cloned(),
+            formula_type: self.formula_type,
+            attr: self.attr.cloned().unwrap_or_default(),
+            xml: self.xml.cloned().unwrap_or_default(),
         }
     }
 }
@@ -415,6 +458,14 @@ pub struct CellContent {
     pub annotation: Option<Box<Annotation>>,
     /// DrawFrames
     pub draw_frames: Vec<DrawFrame>,
+    /// Displayed text as read from the file.
+    display_text: Option<String>,
+    /// Declared result type of the formula.
+    formula_type: Option<ValueType>,
+    /// Unknown attributes, kept for round-tripping.
+    attr: AttrMap2,
+    /// Unknown child elements, kept for round-tripping.
+    xml: Vec<XmlTag>,
 }
 
 impl CellContent {
@@ -444,6 +495,10 @@ impl CellContent {
             || !self.matrix_span.is_empty()
             || self.annotation.is_some()
             || !self.draw_frames.is_empty()
+            || self.display_text.is_some()
+            || self.formula_type.is_some()
+            || !self.attr.is_empty()
+            || !self.xml.is_empty()
         {
             Some(Box::new(CellDataExt {
                 validation_name: self.validation_name.take(),
@@ -451,6 +506,10 @@ impl CellContent {
                 matrix_span: self.matrix_span,
                 annotation: self.annotation.take(),
                 draw_frames: std::mem::take(&mut self.draw_frames),
+                formula_type: self.formula_type.take(),
+                display_text: self.display_text.take(),
+                attr: std::mem::take(&mut self.attr),
+                xml: std::mem::take(&mut self.xml),
             }))
         } else {
             None
@@ -481,10 +540,25 @@ impl CellContent {
         self.formula = Some(formula.into());
     }
 
+    /// Sets a formula with a declared result type.
+    /// See [Sheet::set_formula_typed](crate::Sheet::set_formula_typed).
+    #[inline]
+    pub fn set_formula_typed<V: Into<String>>(&mut self, formula: V, value_type: ValueType) {
+        self.formula = Some(formula.into());
+        self.formula_type = Some(value_type);
+    }
+
+    /// Returns the declared result type of the formula.
+    #[inline]
+    pub fn formula_type(&self) -> Option<ValueType> {
+        self.formula_type
+    }
+
     /// Resets the formula.
     #[inline]
     pub fn clear_formula(&mut self) {
         self.formula = None;
+        self.formula_type = None;
     }
 
     /// Returns the cell style.
@@ -622,4 +696,18 @@ impl CellContent {
     pub fn draw_frames(&self) -> &Vec<DrawFrame> {
         &self.draw_frames
     }
+
+    /// Returns the text that was displayed for the cell, as read from
+    /// the file. Only available with [OdsOptions::display_text](crate::OdsOptions::display_text).
+    #[inline]
+    pub fn display_text(&self) -> Option<&String> {
+        self.display_text.as_ref()
+    }
+
+    /// Sets the displayed text. It is not written, this is only
+    /// information read from the file.
+    #[inline]
+    pub fn set_display_text(&mut self, display_text: Option<String>) {
+        self.display_text = display_text;
+    }
 }
@@ -3,7 +3,7 @@
 use get_size2::GetSize;
 use std::fmt::{Display, Formatter};
 
-use crate::CellRange;
+use crate::{CellRange, OdsError};
 
 /// A value that is used in a comparison.
 #[derive(Clone, Debug)]
@@ -173,122 +173,87 @@ impl Condition {
         Self { cond: str.into() }
     }
 
+    /// Parses the condition into its typed form.
+    pub fn parse(&self) -> Result<ValidationCondition, OdsError> {
+        ValidationCondition::parse(&self.cond)
+    }
+
     /// Compares the cell-content with a value.
     pub fn content_eq<V: Into<Value>>(value: V) -> Condition {
-        let mut buf = String::new();
-        buf.push_str("cell-content()=");
-        buf.push_str(value.into().to_string().as_str());
-        Condition { cond: buf }
+        ValidationCondition::Content(ValueCheck::Compare(CompareOp::Eq, value.into().to_string()))
+            .into()
     }
 
     /// Compares the cell-content with a value.
     pub fn content_ne<V: Into<Value>>(value: V) -> Condition {
-        let mut buf = String::new();
-        buf.push_str("cell-content()!=");
-        buf.push_str(value.into().to_string().as_str());
-        Condition { cond: buf }
+        ValidationCondition::Content(ValueCheck::Compare(CompareOp::Ne, value.into().to_string()))
+            .into()
     }
 
     /// Compares the cell-content with a value.
     pub fn content_lt<V: Into<Value>>(value: V) -> Condition {
-        let mut buf = String::new();
-        buf.push_str("cell-content()<");
-        buf.push_str(value.into().to_string().as_str());
-        Condition { cond: buf }
+        ValidationCondition::Content(ValueCheck::Compare(CompareOp::Lt, value.into().to_string()))
+            .into()
     }
 
     /// Compares the cell-content with a value.
     pub fn content_gt<V: Into<Value>>(value: V) -> Condition {
-        let mut buf = String::new();
-        buf.push_str("cell-content()>");
-        buf.push_str(value.into().to_string().as_str());
-        Condition { cond: buf }
+        ValidationCondition::Content(ValueCheck::Compare(CompareOp::Gt, value.into().to_string()))
+            .into()
     }
 
     /// Compares the cell-content with a value.
     pub fn content_le<V: Into<Value>>(value: V) -> Condition {
-        let mut buf = String::new();
-        buf.push_str("cell-content()<=");
-        buf.push_str(value.into().to_string().as_str());
-        Condition { cond: buf }
+        ValidationCondition::Content(ValueCheck::Compare(CompareOp::Le, value.into().to_string()))
+            .into()
     }
 
     /// Compares the cell-content with a value.
     pub fn content_ge<V: Into<Value>>(value: V) -> Condition {
-        let mut buf = String::new();
-        buf.push_str("cell-content()>=");
-        buf.push_str(value.into().to_string().as_str());
-        Condition { cond: buf }
+        ValidationCondition::Content(ValueCheck::Compare(CompareOp::Ge, value.into().to_string()))
+            .into()
     }
 
     /// Compares the content length to a value.
     pub fn content_text_length_eq(len: u32) -> Condition {
-        let mut buf = String::new();
-        buf.push_str("cell-content-text-length()=");
-        buf.push_str(len.to_string().as_str());
-        Condition { cond: buf }
+        ValidationCondition::TextLength(ValueCheck::Compare(CompareOp::Eq, len.to_string())).into()
     }
 
     /// Compares the content length to a value.
     pub fn content_text_length_ne(len: u32) -> Condition {
-        let mut buf = String::new();
-        buf.push_str("cell-content-text-length()!=");
-        buf.push_str(len.to_string().as_str());
-        Condition { cond: buf }
+        ValidationCondition::TextLength(ValueCheck::Compare(CompareOp::Ne, len.to_string())).into()
     }
 
     /// Compares the content length to a value.
     pub fn content_text_length_lt(len: u32) -> Condition {
-        let mut buf = String::new();
-        buf.push_str("cell-content-text-length()<");
-        buf.push_str(len.to_string().as_str());
-        Condition { cond: buf }
+        ValidationCondition::TextLength(ValueCheck::Compare(CompareOp::Lt, len.to_string())).into()
     }
 
     /// Compares the content length to a value.
     pub fn content_text_length_gt(len: u32) -> Condition {
-        let mut buf = String::new();
-        buf.push_str("cell-content-text-length()>");
-        buf.push_str(len.to_string().as_str());
-        Condition { cond: buf }
+        ValidationCondition::TextLength(ValueCheck::Compare(CompareOp::Gt, len.to_string())).into()
     }
 
     /// Compares the content length to a value.
     pub fn content_text_length_le(len: u32) -> Condition {
-        let mut buf = String::new();
-        buf.push_str("cell-content-text-length()<=");
-        buf.push_str(len.to_string().as_str());
-        Condition { cond: buf }
+        ValidationCondition::TextLength(ValueCheck::Compare(CompareOp::Le, len.to_string())).into()
     }
 
     /// Compares the content length to a value.
     pub fn content_text_length_ge(len: u32) -> Condition {
-        let mut buf = String::new();
-        buf.push_str("cell-content-text-length()>=");
-        buf.push_str(len.to_string().as_str());
-        Condition { cond: buf }
+        ValidationCondition::TextLength(ValueCheck::Compare(CompareOp::Ge, len.to_string())).into()
     }
 
     /// Compares the content length to a range of values.
     pub fn content_text_length_is_between(from: u32, to: u32) -> Condition {
-        let mut buf = String::new();
-        buf.push_str("cell-content-text-length-is-between(");
-        buf.push_str(from.to_string().as_str());
-        buf.push_str(", ");
-        buf.push_str(to.to_string().as_str());
-        buf.push(')');
-        Condition { cond: buf }
+        ValidationCondition::TextLength(ValueCheck::Between(from.to_string(), to.to_string()))
+            .into()
     }
 
     /// Range check.
     pub fn content_text_length_is_not_between(from: u32, to: u32) -> Condition {
-        let mut buf = String::new();
-        buf.push_str("cell-content-text-length-is-not-between(");
-        buf.push_str(from.to_string().as_str());
-        buf.push_str(", ");
-        buf.push_str(to.to_string().as_str());
-        buf.push(')');
-        Condition { cond: buf }
+        ValidationCondition::TextLength(ValueCheck::NotBetween(from.to_string(), to.to_string()))
+            .into()
     }
 
     /// The value is in this list.
@@ -296,28 +261,18 @@ impl Condition {
     where
         Value: From<&'a V>,
     {
-        let mut buf = String::new();
-        buf.push_str("cell-content-is-in-list(");
-
-        let mut sep = false;
-        for v in list {
-            if sep {
-                buf.push(';');
-            }
-            let vv: Value = v.into();
-            let vstr = vv.to_string();
-            if !vstr.starts_with('"') {
-                buf.push('"');
-            }
-            buf.push_str(vstr.as_str());
-            if !vstr.starts_with('"') {
-                buf.push('"');
-            }
-            sep = true;
-        }
-
-        buf.push(')');
-        Condition { cond: buf }
+        let list = list
+            .iter()
+            .map(|v| {
+                let vstr = Value::from(v).to_string();
+                if vstr.starts_with('"') {
+                    vstr
+                } else {
+                    format!("\"{}\"", vstr)
+                }
+            })
+            .collect();
+        ValidationCondition::InList(list).into()
     }
 
     /// The choices are made up from the values in the cellrange.
@@ -328,65 +283,336 @@ impl Condition {
     /// You may want to use an absolute cell-reference to avoid this..
     ///
     pub fn content_is_in_cellrange(range: CellRange) -> Condition {
-        let mut buf = String::new();
-        buf.push_str("cell-content-is-in-list(");
-        buf.push_str(range.to_formula().as_str());
-        buf.push(')');
-        Condition { cond: buf }
+        ValidationCondition::InList(vec![range.to_formula()]).into()
     }
 
     /// Content is a date and matches a comparison.
     /// The date is an integer value that amounts to the days since
     /// 30.12.1899.
     pub fn content_is_date_and(vcond: Condition) -> Condition {
-        let mut buf = String::new();
-        buf.push_str("cell-content-is-date()");
-        buf.push_str(" and ");
-        buf.push_str(vcond.to_string().as_str());
-        Condition { cond: buf }
+        match vcond.parse() {
+            Ok(ValidationCondition::Content(check)) => ValidationCondition::Date(check).into(),
+            _ => Condition {
+                cond: format!("cell-content-is-date() and {}", vcond),
+            },
+        }
     }
 
     /// Content is a time and matches a comparison.
     /// The time is given as a fraction of a day.
     pub fn content_is_time_and(vcond: Condition) -> Condition {
-        let mut buf = String::new();
-        buf.push_str("cell-content-is-time()");
-        buf.push_str(" and ");
-        buf.push_str(vcond.to_string().as_str());
-        Condition { cond: buf }
+        match vcond.parse() {
+            Ok(ValidationCondition::Content(check)) => ValidationCondition::Time(check).into(),
+            _ => Condition {
+                cond: format!("cell-content-is-time() and {}", vcond),
+            },
+        }
     }
 
     /// Content is a number and matches the comparison.
     pub fn content_is_decimal_number_and(vcond: Condition) -> Condition {
-        let mut buf = String::new();
-        buf.push_str("cell-content-is-decimal-number()");
-        buf.push_str(" and ");
-        buf.push_str(vcond.to_string().as_str());
-        Condition { cond: buf }
+        match vcond.parse() {
+            Ok(ValidationCondition::Content(check)) => {
+                ValidationCondition::DecimalNumber(check).into()
+            }
+            _ => Condition {
+                cond: format!("cell-content-is-decimal-number() and {}", vcond),
+            },
+        }
     }
 
     /// Content is a whole number and matches the comparison.
     pub fn content_is_whole_number_and(vcond: Condition) -> Condition {
+        match vcond.parse() {
+            Ok(ValidationCondition::Content(check)) => {
+                ValidationCondition::WholeNumber(check).into()
+            }
+            _ => Condition {
+                cond: format!("cell-content-is-whole-number() and {}", vcond),
+            },
+        }
+    }
+
+    /// Condition for paragraph and text styles. The paragraph is
+    /// in a list with this level.
+    pub fn list_level_eq(level: u32) -> Condition {
         let mut buf = String::new();
-        buf.push_str("cell-content-is-whole-number()");
-        buf.push_str(" and ");
-        buf.push_str(vcond.to_string().as_str());
+        buf.push_str("list-level()=");
+        buf.push_str(level.to_string().as_str());
         Condition { cond: buf }
     }
 
-    /// Evaluates a formula.
-    pub fn is_true_formula<S: AsRef<str>>(formula: S) -> Condition {
+    /// Condition for paragraph and text styles. The paragraph is a
+    /// heading with this outline level.
+    pub fn outline_level_eq(level: u32) -> Condition {
         let mut buf = String::new();
-        buf.push_str("is-true-formula(");
-        buf.push_str(formula.as_ref());
-        buf.push(')');
+        buf.push_str("outline-level()=");
+        buf.push_str(level.to_string().as_str());
         Condition { cond: buf }
     }
+
+    /// Evaluates a formula.
+    pub fn is_true_formula<S: AsRef<str>>(formula: S) -> Condition {
+        ValidationCondition::Formula(formula.as_ref().to_string()).into()
+    }
+}
+
+/// Comparison operator for a validation condition.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompareOp {
+    /// =
+    Eq,
+    /// !=
+    Ne,
+    /// <
+    Lt,
+    /// >
+    Gt,
+    /// <=
+    Le,
+    /// >=
+    Ge,
+}
+
+impl Display for CompareOp {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CompareOp::Eq => write!(f, "="),
+            CompareOp::Ne => write!(f, "!="),
+            CompareOp::Lt => write!(f, "<"),
+            CompareOp::Gt => write!(f, ">"),
+            CompareOp::Le => write!(f, "<="),
+            CompareOp::Ge => write!(f, ">="),
+        }
+    }
+}
+
+/// Check of a value against a comparison or a range.
+///
+/// The operands are formula expressions, e.g. a number, a quoted
+/// string or a cell-reference.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ValueCheck {
+    /// Compares with a value.
+    Compare(CompareOp, String),
+    /// Value is between the two limits, inclusive.
+    Between(String, String),
+    /// Value is outside the two limits.
+    NotBetween(String, String),
+}
+
+impl ValueCheck {
+    fn fmt_with(&self, f: &mut Formatter<'_>, func: &str) -> std::fmt::Result {
+        match self {
+            ValueCheck::Compare(op, v) => write!(f, "{}(){}{}", func, op, v),
+            ValueCheck::Between(a, b) => write!(f, "{}-is-between({}, {})", func, a, b),
+            ValueCheck::NotBetween(a, b) => write!(f, "{}-is-not-between({}, {})", func, a, b),
+        }
+    }
+
+    fn parse(func: &str, s: &str) -> Option<ValueCheck> {
+        let s = s.trim();
+        if let Some(args) = s
+            .strip_prefix(func)
+            .and_then(|v| v.strip_prefix("-is-between("))
+            .and_then(|v| v.strip_suffix(')'))
+        {
+            let (a, b) = split_args2(args)?;
+            Some(ValueCheck::Between(a, b))
+        } else if let Some(args) = s
+            .strip_prefix(func)
+            .and_then(|v| v.strip_prefix("-is-not-between("))
+            .and_then(|v| v.strip_suffix(')'))
+        {
+            let (a, b) = split_args2(args)?;
+            Some(ValueCheck::NotBetween(a, b))
+        } else if let Some(cmp) = s.strip_prefix(func).and_then(|v| v.strip_prefix("()")) {
+            let cmp = cmp.trim_start();
+            let (op, v) = if let Some(v) = cmp.strip_prefix("<=") {
+                (CompareOp::Le, v)
+            } else if let Some(v) = cmp.strip_prefix(">=") {
+                (CompareOp::Ge, v)
+            } else if let Some(v) = cmp.strip_prefix("!=") {
+                (CompareOp::Ne, v)
+            } else if let Some(v) = cmp.strip_prefix("<>") {
+                (CompareOp::Ne, v)
+            } else if let Some(v) = cmp.strip_prefix('=') {
+                (CompareOp::Eq, v)
+            } else if let Some(v) = cmp.strip_prefix('<') {
+                (CompareOp::Lt, v)
+            } else if let Some(v) = cmp.strip_prefix('>') {
+                (CompareOp::Gt, v)
+            } else {
+                return None;
+            };
+            Some(ValueCheck::Compare(op, v.trim().to_string()))
+        } else {
+            None
+        }
+    }
+}
+
+/// Typed form of a validation condition.
+///
+/// This can be converted into a [Condition] and parsed from one.
+///
+/// ```
+/// use spreadsheet_ods::condition::{CompareOp, Condition, ValidationCondition, ValueCheck};
+///
+/// let c = ValidationCondition::DecimalNumber(ValueCheck::Between("0".into(), "100".into()));
+/// let cond = Condition::from(c.clone());
+/// assert_eq!(cond.to_string(), "cell-content-is-decimal-number() and cell-content-is-between(0, 100)");
+/// assert_eq!(ValidationCondition::parse(&cond.to_string()).unwrap(), c);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ValidationCondition {
+    /// Checks the cell-content.
+    Content(ValueCheck),
+    /// Content is a whole number and matches the check.
+    WholeNumber(ValueCheck),
+    /// Content is a decimal number and matches the check.
+    DecimalNumber(ValueCheck),
+    /// Content is a date and matches the check.
+    /// A date is given as the days since 30.12.1899.
+    Date(ValueCheck),
+    /// Content is a time and matches the check.
+    Time(ValueCheck),
+    /// Checks the length of the text.
+    TextLength(ValueCheck),
+    /// Content is in the list. The entries are formula expressions,
+    /// either quoted strings, numbers or a single cell-range.
+    InList(Vec<String>),
+    /// Custom formula.
+    Formula(String),
+}
+
+impl Display for ValidationCondition {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ValidationCondition::Content(c) => c.fmt_with(f, "cell-content"),
+            ValidationCondition::WholeNumber(c) => {
+                write!(f, "cell-content-is-whole-number() and ")?;
+                c.fmt_with(f, "cell-content")
+            }
+            ValidationCondition::DecimalNumber(c) => {
+                write!(f, "cell-content-is-decimal-number() and ")?;
+                c.fmt_with(f, "cell-content")
+            }
+            ValidationCondition::Date(c) => {
+                write!(f, "cell-content-is-date() and ")?;
+                c.fmt_with(f, "cell-content")
+            }
+            ValidationCondition::Time(c) => {
+                write!(f, "cell-content-is-time() and ")?;
+                c.fmt_with(f, "cell-content")
+            }
+            ValidationCondition::TextLength(c) => c.fmt_with(f, "cell-content-text-length"),
+            ValidationCondition::InList(v) => write!(f, "cell-content-is-in-list({})", v.join(";")),
+            ValidationCondition::Formula(v) => write!(f, "is-true-formula({})", v),
+        }
+    }
+}
+
+impl From<ValidationCondition> for Condition {
+    fn from(value: ValidationCondition) -> Self {
+        Condition::new(value.to_string())
+    }
+}
+
+impl ValidationCondition {
+    /// Parses a condition as used in table:condition.
+    /// A namespace prefix like "of:" must be removed before.
+    pub fn parse(s: &str) -> Result<ValidationCondition, OdsError> {
+        let s = s.trim();
+
+        let typed = [
+            (
+                "cell-content-is-whole-number()",
+                ValidationCondition::WholeNumber as fn(_) -> _,
+            ),
+            (
+                "cell-content-is-decimal-number()",
+                ValidationCondition::DecimalNumber,
+            ),
+            ("cell-content-is-date()", ValidationCondition::Date),
+            ("cell-content-is-time()", ValidationCondition::Time),
+        ];
+        for (prefix, variant) in typed {
+            if let Some(check) = s
+                .strip_prefix(prefix)
+                .and_then(|v| v.trim_start().strip_prefix("and "))
+                .and_then(|v| ValueCheck::parse("cell-content", v))
+            {
+                return Ok(variant(check));
+            }
+        }
+
+        if let Some(args) = s
+            .strip_prefix("cell-content-is-in-list(")
+            .and_then(|v| v.strip_suffix(')'))
+        {
+            return Ok(ValidationCondition::InList(split_args(args, ';')));
+        }
+        if let Some(f) = s
+            .strip_prefix("is-true-formula(")
+            .and_then(|v| v.strip_suffix(')'))
+        {
+            return Ok(ValidationCondition::Formula(f.to_string()));
+        }
+        if let Some(check) = ValueCheck::parse("cell-content-text-length", s) {
+            return Ok(ValidationCondition::TextLength(check));
+        }
+        if let Some(check) = ValueCheck::parse("cell-content", s) {
+            return Ok(ValidationCondition::Content(check));
+        }
+
+        Err(OdsError::Parse(
+            "invalid validation condition",
+            Some(s.to_string()),
+        ))
+    }
+}
+
+// Splits exactly two arguments.
+fn split_args2(s: &str) -> Option<(String, String)> {
+    let mut args = split_args(s, ',');
+    if args.len() == 2 {
+        let b = args.pop()?;
+        let a = args.pop()?;
+        Some((a, b))
+    } else {
+        None
+    }
+}
+
+// Splits at the separator, but not within strings, references or
+// function calls.
+fn split_args(s: &str, sep: char) -> Vec<String> {
+    let mut args = Vec::new();
+    let mut depth = 0;
+    let mut quoted = false;
+    let mut start = 0;
+    for (i, c) in s.char_indices() {
+        match c {
+            '"' => quoted = !quoted,
+            '(' | '[' if !quoted => depth += 1,
+            ')' | ']' if !quoted => depth -= 1,
+            c if c == sep && !quoted && depth == 0 => {
+                args.push(s[start..i].trim().to_string());
+                start = i + c.len_utf8();
+            }
+            _ => {}
+        }
+    }
+    if !s.trim().is_empty() {
+        args.push(s[start..].trim().to_string());
+    }
+    args
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::condition::{Condition, ValueCondition};
+    use crate::condition::{CompareOp, Condition, ValidationCondition, ValueCheck, ValueCondition};
     use crate::CellRange;
 
     #[test]
@@ -444,4 +670,47 @@ mod tests {
         let c = Condition::is_true_formula("formula");
         assert_eq!(c.to_string(), "is-true-formula(formula)");
     }
+
+    #[test]
+    fn test_validationcondition() {
+        let conds = [
+            Condition::content_eq(5),
+            Condition::content_text_length_lt(7),
+            Condition::content_text_length_is_between(5, 7),
+            Condition::content_text_length_is_not_between(5, 7),
+            Condition::content_is_in_list(&["a;b", "c\"d"]),
+            Condition::content_is_in_cellrange(CellRange::remote("other", 0, 0, 10, 0)),
+            Condition::content_is_date_and(Condition::content_ge(45000)),
+            Condition::content_is_time_and(Condition::content_ne(0)),
+            Condition::content_is_decimal_number_and(Condition::content_le(0.5)),
+            Condition::content_is_whole_number_and(Condition::content_gt(0)),
+            Condition::is_true_formula("AND([.A1]>0;[.B1]>0)"),
+        ];
+        for c in conds {
+            let v = c.parse().expect("parse");
+            assert_eq!(Condition::from(v).to_string(), c.to_string());
+        }
+
+        let v = Condition::content_is_in_list(&["a;b", "c"])
+            .parse()
+            .unwrap();
+        assert_eq!(
+            v,
+            ValidationCondition::InList(vec![r#""a;b""#.into(), r#""c""#.into()])
+        );
+        let v = ValidationCondition::parse(
+            "cell-content-is-date() and cell-content-is-between(1, [.A1])",
+        )
+        .unwrap();
+        assert_eq!(
+            v,
+            ValidationCondition::Date(ValueCheck::Between("1".into(), "[.A1]".into()))
+        );
+        let v = ValidationCondition::parse("cell-content-text-length() >= 3").unwrap();
+        assert_eq!(
+            v,
+            ValidationCondition::TextLength(ValueCheck::Compare(CompareOp::Ge, "3".into()))
+        );
+        assert!(ValidationCondition::parse("something-else()").is_err());
+    }
 }
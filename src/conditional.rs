@@ -0,0 +1,398 @@
+//!
+//! Data bars and color scales. These are LibreOffice extensions and
+//! are written as calcext:conditional-formats.
+//!
+//! ```
+//! use spreadsheet_ods::conditional::{ColorScale, ConditionalFormat, DataBar};
+//! use spreadsheet_ods::color::Rgb;
+//! use spreadsheet_ods::{CellRange, Sheet};
+//!
+//! let mut sheet = Sheet::new("1");
+//! sheet.add_conditional_format(ConditionalFormat::new_data_bar(
+//!     CellRange::local(0, 0, 9, 0),
+//!     DataBar::new(Rgb::new(99, 142, 198)),
+//! ));
+//! sheet.add_conditional_format(ConditionalFormat::new_color_scale(
+//!     CellRange::local(0, 1, 9, 1),
+//!     ColorScale::new_3(
+//!         Rgb::new(255, 0, 0),
+//!         Rgb::new(255, 255, 0),
+//!         Rgb::new(0, 169, 51),
+//!     ),
+//! ));
+//! ```
+//!
+
+use crate::color::Rgb;
+use crate::{CellRange, OdsError};
+use get_size2::GetSize;
+use std::fmt::{Display, Formatter};
+
+/// Threshold of a color scale or a data bar.
+#[derive(Debug, Clone, PartialEq, GetSize)]
+pub enum ScaleValue {
+    /// Minimum of the range.
+    Minimum,
+    /// Maximum of the range.
+    Maximum,
+    /// Minimum of the range, but at most 0.
+    AutoMinimum,
+    /// Maximum of the range, but at least 0.
+    AutoMaximum,
+    /// Fixed value.
+    Number(f64),
+    /// Percentage of the difference between minimum and maximum.
+    Percent(f64),
+    /// Percentile of the values.
+    Percentile(f64),
+    /// Result of a formula.
+    Formula(String),
+}
+
+impl ScaleValue {
+    /// Value of calcext:type
+    pub(crate) fn type_str(&self) -> &'static str {
+        match self {
+            ScaleValue::Minimum => "minimum",
+            ScaleValue::Maximum => "maximum",
+            ScaleValue::AutoMinimum => "auto-minimum",
+            ScaleValue::AutoMaximum => "auto-maximum",
+            ScaleValue::Number(_) => "number",
+            ScaleValue::Percent(_) => "percent",
+            ScaleValue::Percentile(_) => "percentile",
+            ScaleValue::Formula(_) => "formula",
+        }
+    }
+
+    /// Value of calcext:value
+    pub(crate) fn value_str(&self) -> String {
+        match self {
+            ScaleValue::Minimum
+            | ScaleValue::Maximum
+            | ScaleValue::AutoMinimum
+            | ScaleValue::AutoMaximum => "0".to_string(),
+            ScaleValue::Number(v) | ScaleValue::Percent(v) | ScaleValue::Percentile(v) => {
+                v.to_string()
+            }
+            ScaleValue::Formula(v) => v.clone(),
+        }
+    }
+
+    /// Parses calcext:type and calcext:value.
+    pub(crate) fn parse(type_str: &str, value: Option<&str>) -> Result<Self, OdsError> {
+        let number = || -> Result<f64, OdsError> {
+            match value {
+                Some(v) => Ok(v.parse()?),
+                None => Err(OdsError::Parse("missing calcext:value", None)),
+            }
+        };
+        match type_str {
+            "minimum" => Ok(ScaleValue::Minimum),
+            "maximum" => Ok(ScaleValue::Maximum),
+            "auto-minimum" => Ok(ScaleValue::AutoMinimum),
+            "auto-maximum" => Ok(ScaleValue::AutoMaximum),
+            "number" => Ok(ScaleValue::Number(number()?)),
+            "percent" => Ok(ScaleValue::Percent(number()?)),
+            "percentile" => Ok(ScaleValue::Percentile(number()?)),
+            "formula" => Ok(ScaleValue::Formula(value.unwrap_or_default().to_string())),
+            _ => Err(OdsError::Parse(
+                "invalid calcext:type",
+                Some(type_str.to_string()),
+            )),
+        }
+    }
+}
+
+/// One color of a color scale.
+#[derive(Debug, Clone, PartialEq, GetSize)]
+pub struct ColorScaleEntry {
+    value: ScaleValue,
+    #[get_size(ignore)]
+    color: Rgb<u8>,
+}
+
+impl ColorScaleEntry {
+    /// New entry.
+    pub fn new(value: ScaleValue, color: Rgb<u8>) -> Self {
+        Self { value, color }
+    }
+
+    /// Threshold.
+    pub fn value(&self) -> &ScaleValue {
+        &self.value
+    }
+
+    /// Color at the threshold.
+    pub fn color(&self) -> Rgb<u8> {
+        self.color
+    }
+}
+
+/// Colors the cells with a gradient between two or three colors.
+#[derive(Debug, Clone, Default, PartialEq, GetSize)]
+pub struct ColorScale {
+    entries: Vec<ColorScaleEntry>,
+}
+
+impl ColorScale {
+    /// Empty color scale.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Two colors from minimum to maximum.
+    pub fn new_2(min: Rgb<u8>, max: Rgb<u8>) -> Self {
+        let mut v = Self::new();
+        v.push_entry(ScaleValue::Minimum, min);
+        v.push_entry(ScaleValue::Maximum, max);
+        v
+    }
+
+    /// Three colors from minimum via the median to maximum.
+    pub fn new_3(min: Rgb<u8>, mid: Rgb<u8>, max: Rgb<u8>) -> Self {
+        let mut v = Self::new();
+        v.push_entry(ScaleValue::Minimum, min);
+        v.push_entry(ScaleValue::Percentile(50.0), mid);
+        v.push_entry(ScaleValue::Maximum, max);
+        v
+    }
+
+    /// Adds a color.
+    pub fn push_entry(&mut self, value: ScaleValue, color: Rgb<u8>) {
+        self.entries.push(ColorScaleEntry::new(value, color));
+    }
+
+    /// Colors.
+    pub fn entries(&self) -> &[ColorScaleEntry] {
+        &self.entries
+    }
+}
+
+/// Position of the axis of a data bar.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, GetSize)]
+pub enum DataBarAxis {
+    /// Depends on the values.
+    #[default]
+    Automatic,
+    /// Middle of the cell.
+    Middle,
+    /// No axis.
+    None,
+}
+
+impl Display for DataBarAxis {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DataBarAxis::Automatic => write!(f, "automatic"),
+            DataBarAxis::Middle => write!(f, "middle"),
+            DataBarAxis::None => write!(f, "none"),
+        }
+    }
+}
+
+impl TryFrom<&str> for DataBarAxis {
+    type Error = OdsError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value {
+            "automatic" => Ok(DataBarAxis::Automatic),
+            "middle" => Ok(DataBarAxis::Middle),
+            "none" => Ok(DataBarAxis::None),
+            _ => Err(OdsError::Parse(
+                "invalid calcext:axis-position",
+                Some(value.to_string()),
+            )),
+        }
+    }
+}
+
+/// Draws a bar in each cell, the length is relative to the value.
+#[derive(Debug, Clone, PartialEq, GetSize)]
+pub struct DataBar {
+    min: ScaleValue,
+    max: ScaleValue,
+    #[get_size(ignore)]
+    positive_color: Rgb<u8>,
+    #[get_size(ignore)]
+    negative_color: Rgb<u8>,
+    #[get_size(ignore)]
+    axis_color: Rgb<u8>,
+    axis_position: DataBarAxis,
+    gradient: bool,
+    show_value: bool,
+    min_length: f64,
+    max_length: f64,
+}
+
+impl DataBar {
+    /// Data bar with the given color, automatic minimum and maximum
+    /// and a red bar for negative values.
+    pub fn new(color: Rgb<u8>) -> Self {
+        Self {
+            min: ScaleValue::AutoMinimum,
+            max: ScaleValue::AutoMaximum,
+            positive_color: color,
+            negative_color: Rgb::new(255, 0, 0),
+            axis_color: Rgb::new(0, 0, 0),
+            axis_position: Default::default(),
+            gradient: true,
+            show_value: true,
+            min_length: 0.0,
+            max_length: 100.0,
+        }
+    }
+
+    /// Value for the shortest bar.
+    pub fn set_min(&mut self, min: ScaleValue) {
+        self.min = min;
+    }
+
+    /// Value for the shortest bar.
+    pub fn min(&self) -> &ScaleValue {
+        &self.min
+    }
+
+    /// Value for the longest bar.
+    pub fn set_max(&mut self, max: ScaleValue) {
+        self.max = max;
+    }
+
+    /// Value for the longest bar.
+    pub fn max(&self) -> &ScaleValue {
+        &self.max
+    }
+
+    /// Color for positive values.
+    pub fn set_positive_color(&mut self, color: Rgb<u8>) {
+        self.positive_color = color;
+    }
+
+    /// Color for positive values.
+    pub fn positive_color(&self) -> Rgb<u8> {
+        self.positive_color
+    }
+
+    /// Color for negative values.
+    pub fn set_negative_color(&mut self, color: Rgb<u8>) {
+        self.negative_color = color;
+    }
+
+    /// Color for negative values.
+    pub fn negative_color(&self) -> Rgb<u8> {
+        self.negative_color
+    }
+
+    /// Color of the axis.
+    pub fn set_axis_color(&mut self, color: Rgb<u8>) {
+        self.axis_color = color;
+    }
+
+    /// Color of the axis.
+    pub fn axis_color(&self) -> Rgb<u8> {
+        self.axis_color
+    }
+
+    /// Position of the axis.
+    pub fn set_axis_position(&mut self, axis_position: DataBarAxis) {
+        self.axis_position = axis_position;
+    }
+
+    /// Position of the axis.
+    pub fn axis_position(&self) -> DataBarAxis {
+        self.axis_position
+    }
+
+    /// Fill the bar with a gradient.
+    pub fn set_gradient(&mut self, gradient: bool) {
+        self.gradient = gradient;
+    }
+
+    /// Fill the bar with a gradient.
+    pub fn gradient(&self) -> bool {
+        self.gradient
+    }
+
+    /// Show the cell value too.
+    pub fn set_show_value(&mut self, show_value: bool) {
+        self.show_value = show_value;
+    }
+
+    /// Show the cell value too.
+    pub fn show_value(&self) -> bool {
+        self.show_value
+    }
+
+    /// Minimum length of the bar in percent of the cell width.
+    pub fn set_min_length(&mut self, min_length: f64) {
+        self.min_length = min_length;
+    }
+
+    /// Minimum length of the bar in percent of the cell width.
+    pub fn min_length(&self) -> f64 {
+        self.min_length
+    }
+
+    /// Maximum length of the bar in percent of the cell width.
+    pub fn set_max_length(&mut self, max_length: f64) {
+        self.max_length = max_length;
+    }
+
+    /// Maximum length of the bar in percent of the cell width.
+    pub fn max_length(&self) -> f64 {
+        self.max_length
+    }
+}
+
+/// The kinds of conditional formats.
+#[derive(Debug, Clone, PartialEq, GetSize)]
+pub enum ConditionalFormatKind {
+    /// Data bar.
+    DataBar(DataBar),
+    /// Color scale.
+    ColorScale(ColorScale),
+}
+
+/// A conditional format for a range of cells.
+#[derive(Debug, Clone, PartialEq, GetSize)]
+pub struct ConditionalFormat {
+    range: CellRange,
+    format: ConditionalFormatKind,
+}
+
+impl ConditionalFormat {
+    /// Data bar for the range.
+    pub fn new_data_bar(range: CellRange, data_bar: DataBar) -> Self {
+        Self {
+            range,
+            format: ConditionalFormatKind::DataBar(data_bar),
+        }
+    }
+
+    /// Color scale for the range.
+    pub fn new_color_scale(range: CellRange, color_scale: ColorScale) -> Self {
+        Self {
+            range,
+            format: ConditionalFormatKind::ColorScale(color_scale),
+        }
+    }
+
+    /// Target range.
+    pub fn set_range(&mut self, range: CellRange) {
+        self.range = range;
+    }
+
+    /// Target range.
+    pub fn range(&self) -> &CellRange {
+        &self.range
+    }
+
+    /// Format.
+    pub fn format(&self) -> &ConditionalFormatKind {
+        &self.format
+    }
+
+    /// Format.
+    pub fn format_mut(&mut self) -> &mut ConditionalFormatKind {
+        &mut self.format
+    }
+}
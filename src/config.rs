@@ -172,6 +172,30 @@ impl ConfigMap {
 
         &mut self.values.get_mut(idx).unwrap().1
     }
+
+    /// Changes the key of a ConfigItem. An existing ConfigItem with
+    /// the new key is replaced.
+    pub(crate) fn rename<S, T>(&mut self, old: S, new: T)
+    where
+        S: AsRef<str>,
+        T: AsRef<str>,
+    {
+        if !self.key_index.contains_key(old.as_ref()) {
+            return;
+        }
+
+        let values = std::mem::take(&mut self.values);
+        self.key_index.clear();
+        for (name, item) in values {
+            if name == new.as_ref() {
+                continue;
+            } else if name == old.as_ref() {
+                self.insert(new.as_ref(), item);
+            } else {
+                self.insert(name, item);
+            }
+        }
+    }
 }
 
 pub(crate) struct ConfigIter<'a> {
@@ -454,6 +478,20 @@ impl ConfigItem {
         }
     }
 
+    /// Recursive get for any ConfigItem.
+    pub(crate) fn get_rec_mut<S>(&mut self, names: &[S]) -> Option<&mut ConfigItem>
+    where
+        S: AsRef<str>,
+    {
+        if let Some((name, rest)) = names.split_first() {
+            let map = self.as_map_mut()?;
+            let idx = *map.key_index.get(name.as_ref())?;
+            map.values[idx].1.get_rec_mut(rest)
+        } else {
+            Some(self)
+        }
+    }
+
     /// Recursive get for only the ConfigValue leaves.
     pub(crate) fn get_value_rec<S>(&self, names: &[S]) -> Option<&ConfigValue>
     where
@@ -535,6 +573,18 @@ impl Config {
     {
         self.config.create_path(names)
     }
+
+    /// Changes a key in the map-like ConfigItem at the path.
+    pub(crate) fn rename_key<S, T, U>(&mut self, names: &[S], old: T, new: U)
+    where
+        S: AsRef<str>,
+        T: AsRef<str>,
+        U: AsRef<str>,
+    {
+        if let Some(map) = self.config.get_rec_mut(names).and_then(|v| v.as_map_mut()) {
+            map.rename(old, new);
+        }
+    }
 }
 
 #[cfg(test)]
@@ -605,6 +655,32 @@ mod tests {
         assert_eq!(v, &ConfigItem::Entry(ConfigMap::new()));
     }
 
+    #[test]
+    fn test_rename_key() {
+        let mut config = setup_config();
+        config.rename_key(
+            &["ooo:configuration-settings", "ScriptConfiguration"],
+            "Tabelle1",
+            "Data",
+        );
+        assert_eq!(
+            config.get_value(&[
+                "ooo:configuration-settings",
+                "ScriptConfiguration",
+                "Data",
+                "CodeName"
+            ]),
+            Some(&ConfigValue::String("Tabelle1".to_string()))
+        );
+        assert!(config
+            .get(&[
+                "ooo:configuration-settings",
+                "ScriptConfiguration",
+                "Tabelle1"
+            ])
+            .is_none());
+    }
+
     #[test]
     #[should_panic]
     fn test_create_path() {
@@ -0,0 +1,224 @@
+//!
+//! DDE links. A DDE link fetches data from another application or
+//! document. The DDE() function in a formula refers to the link via
+//! application, topic and item.
+//!
+//! ```
+//! use spreadsheet_ods::dde::DdeLink;
+//! use spreadsheet_ods::WorkBook;
+//!
+//! let mut book = WorkBook::new_empty();
+//! let mut link = DdeLink::new("soffice", "file:///data/prices.ods", "Sheet1.A1:B10");
+//! link.set_automatic_update(true);
+//! book.add_dde_link(link);
+//! ```
+//!
+
+use get_size2::GetSize;
+use std::convert::TryFrom;
+use std::fmt::{Display, Formatter};
+
+use crate::xmltree::{XmlContent, XmlTag};
+use crate::OdsError;
+
+/// How the data of a DDE link is converted. office:conversion-mode
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, GetSize)]
+pub enum DdeConversionMode {
+    /// Use the default number format of the receiving application.
+    #[default]
+    IntoDefaultStyleDataStyle,
+    /// Numbers are in english notation.
+    IntoEnglishNumber,
+    /// Everything is kept as text.
+    KeepText,
+}
+
+impl TryFrom<&str> for DdeConversionMode {
+    type Error = OdsError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value {
+            "into-default-style-data-style" => Ok(DdeConversionMode::IntoDefaultStyleDataStyle),
+            "into-english-number" => Ok(DdeConversionMode::IntoEnglishNumber),
+            "keep-text" => Ok(DdeConversionMode::KeepText),
+            _ => Err(OdsError::Parse(
+                "invalid office:conversion-mode",
+                Some(value.to_string()),
+            )),
+        }
+    }
+}
+
+impl Display for DdeConversionMode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DdeConversionMode::IntoDefaultStyleDataStyle => {
+                write!(f, "into-default-style-data-style")
+            }
+            DdeConversionMode::IntoEnglishNumber => write!(f, "into-english-number"),
+            DdeConversionMode::KeepText => write!(f, "keep-text"),
+        }
+    }
+}
+
+/// One DDE link of the workbook. table:dde-link
+#[derive(Clone, Debug, Default, PartialEq, GetSize)]
+pub struct DdeLink {
+    name: Option<String>,
+    application: String,
+    topic: String,
+    item: String,
+    conversion_mode: DdeConversionMode,
+    automatic_update: bool,
+    /// Last known data of the link as table:table.
+    cache: Option<XmlTag>,
+}
+
+impl DdeLink {
+    /// New link. For links to other spreadsheets the application is
+    /// "soffice", the topic the url of the file and the item a cell range
+    /// in the form "Sheet1.A1:B2".
+    pub fn new<S: Into<String>, T: Into<String>, U: Into<String>>(
+        application: S,
+        topic: T,
+        item: U,
+    ) -> Self {
+        Self {
+            name: None,
+            application: application.into(),
+            topic: topic.into(),
+            item: item.into(),
+            conversion_mode: Default::default(),
+            automatic_update: false,
+            cache: None,
+        }
+    }
+
+    /// Optional name of the link.
+    pub fn set_name<S: Into<String>>(&mut self, name: Option<S>) {
+        self.name = name.map(|v| v.into());
+    }
+
+    /// Optional name of the link.
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    /// Application that provides the data.
+    pub fn set_application<S: Into<String>>(&mut self, application: S) {
+        self.application = application.into();
+    }
+
+    /// Application that provides the data.
+    pub fn application(&self) -> &str {
+        &self.application
+    }
+
+    /// Topic, usually the linked file.
+    pub fn set_topic<S: Into<String>>(&mut self, topic: S) {
+        self.topic = topic.into();
+    }
+
+    /// Topic, usually the linked file.
+    pub fn topic(&self) -> &str {
+        &self.topic
+    }
+
+    /// Item within the topic.
+    pub fn set_item<S: Into<String>>(&mut self, item: S) {
+        self.item = item.into();
+    }
+
+    /// Item within the topic.
+    pub fn item(&self) -> &str {
+        &self.item
+    }
+
+    /// Conversion of the data.
+    pub fn set_conversion_mode(&mut self, mode: DdeConversionMode) {
+        self.conversion_mode = mode;
+    }
+
+    /// Conversion of the data.
+    pub fn conversion_mode(&self) -> DdeConversionMode {
+        self.conversion_mode
+    }
+
+    /// Update the data whenever the source changes.
+    pub fn set_automatic_update(&mut self, automatic_update: bool) {
+        self.automatic_update = automatic_update;
+    }
+
+    /// Update the data whenever the source changes.
+    pub fn automatic_update(&self) -> bool {
+        self.automatic_update
+    }
+
+    /// Last known data of the link. This is a table:table element.
+    pub fn cache(&self) -> Option<&XmlTag> {
+        self.cache.as_ref()
+    }
+
+    /// Removes the cached data. The application has to fetch it again.
+    pub fn clear_cache(&mut self) {
+        self.cache = None;
+    }
+
+    /// Converts from a table:dde-link.
+    pub(crate) fn from_xml(tag: &XmlTag) -> Result<Self, OdsError> {
+        let mut link = DdeLink::default();
+        for content in tag.content() {
+            match content {
+                XmlContent::Tag(t) if t.name() == "office:dde-source" => {
+                    link.name = t.get_attr("office:name").map(|v| v.to_string());
+                    link.application = t
+                        .get_attr("office:dde-application")
+                        .unwrap_or_default()
+                        .to_string();
+                    link.topic = t
+                        .get_attr("office:dde-topic")
+                        .unwrap_or_default()
+                        .to_string();
+                    link.item = t
+                        .get_attr("office:dde-item")
+                        .unwrap_or_default()
+                        .to_string();
+                    if let Some(v) = t.get_attr("office:conversion-mode") {
+                        link.conversion_mode = DdeConversionMode::try_from(v)?;
+                    }
+                    if let Some(v) = t.get_attr("office:automatic-update") {
+                        link.automatic_update = v == "true";
+                    }
+                }
+                XmlContent::Tag(t) if t.name() == "table:table" => {
+                    link.cache = Some(t.clone());
+                }
+                _ => {}
+            }
+        }
+        Ok(link)
+    }
+
+    /// Converts to a table:dde-link.
+    pub(crate) fn to_xml(&self) -> XmlTag {
+        let mut source = XmlTag::new("office:dde-source");
+        if let Some(name) = &self.name {
+            source.set_attr("office:name", name.as_str());
+        }
+        source.set_attr("office:dde-application", self.application.as_str());
+        source.set_attr("office:dde-topic", self.topic.as_str());
+        source.set_attr("office:dde-item", self.item.as_str());
+        if self.conversion_mode != DdeConversionMode::default() {
+            source.set_attr("office:conversion-mode", self.conversion_mode.to_string());
+        }
+        if self.automatic_update {
+            source.set_attr("office:automatic-update", "true");
+        }
+
+        let mut tag = XmlTag::new("table:dde-link").tag(source);
+        if let Some(cache) = &self.cache {
+            tag.add_tag(cache.clone());
+        }
+        tag
+    }
+}
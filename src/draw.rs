@@ -3,11 +3,14 @@
 //!
 
 use crate::attrmap2::AttrMap2;
+use crate::refs::format_cellranges;
 use crate::style::units::RelativeScale;
 use crate::style::{GraphicStyleRef, ParagraphStyleRef};
 use crate::text::{TextP, TextTag};
+use crate::workbook_::EventListener;
 use crate::xlink::{XLinkActuate, XLinkShow, XLinkType};
-use crate::{CellRef, Length, OdsError};
+use crate::xmltree::XmlTag;
+use crate::{CellRange, CellRef, HashMap, Length, OdsError};
 use base64::Engine;
 use chrono::NaiveDateTime;
 use get_size2::GetSize;
@@ -134,6 +137,10 @@ impl Annotation {
         self.text = text;
     }
 
+    pub(crate) fn text_mut(&mut self) -> &mut Vec<TextTag> {
+        &mut self.text
+    }
+
     draw_caption_point_x!(attr);
     draw_caption_point_y!(attr);
     draw_class_names!(attr);
@@ -235,13 +242,20 @@ pub struct DrawFrame {
     attr: AttrMap2,
     /// ...
     content: Vec<DrawFrameContent>,
+    /// Macros bound to events of the frame.
+    event_listener: HashMap<String, EventListener>,
 }
 
 /// Draw-frame content data.
+///
+/// More kinds of content may be added, matches need a wildcard arm.
 #[derive(Debug, Clone, GetSize)]
+#[non_exhaustive]
 pub enum DrawFrameContent {
     /// Image
     Image(DrawImage),
+    /// Embedded object
+    Object(DrawObject),
 }
 
 impl DrawFrame {
@@ -290,6 +304,25 @@ impl DrawFrame {
         self.title = None;
     }
 
+    /// Binds an event of the frame, e.g. a click, to a macro.
+    pub fn add_event_listener(&mut self, e: EventListener) {
+        self.event_listener.insert(e.event_name().to_string(), e);
+    }
+
+    /// Removes the listener for the event.
+    pub fn remove_event_listener(&mut self, event_name: &str) -> Option<EventListener> {
+        self.event_listener.remove(event_name)
+    }
+
+    /// Event listeners of the frame.
+    pub fn iter_event_listeners(&self) -> impl Iterator<Item = &EventListener> {
+        self.event_listener.values()
+    }
+
+    pub(crate) fn event_listeners(&self) -> &HashMap<String, EventListener> {
+        &self.event_listener
+    }
+
     /// Frame content.
     pub fn set_content(&mut self, content: Vec<DrawFrameContent>) {
         self.content = content;
@@ -315,6 +348,12 @@ impl DrawFrame {
         &mut self.content
     }
 
+    /// Sets width and height to the natural size of an image.
+    pub fn set_natural_size(&mut self, size: &ImageSize) {
+        self.set_width(size.natural_width());
+        self.set_height(size.natural_height());
+    }
+
     draw_name!(attr);
     draw_caption_id!(attr);
     draw_class_names!(attr);
@@ -411,6 +450,12 @@ impl DrawImage {
         self.binary_data = None;
     }
 
+    /// Natural size of the embedded image. Supports PNG, JPEG and GIF.
+    #[cfg(feature = "image_size")]
+    pub fn image_size(&self) -> Result<ImageSize, OdsError> {
+        ImageSize::from_image(&self.get_binary()?)
+    }
+
     /// Text
     pub fn get_text(&self) -> &Vec<TextTag> {
         &self.text
@@ -431,6 +476,10 @@ impl DrawImage {
         self.text = text;
     }
 
+    pub(crate) fn text_mut(&mut self) -> &mut Vec<TextTag> {
+        &mut self.text
+    }
+
     draw_filter_name!(attr);
     draw_mime_type!(attr);
     xlink_actuate!(attr);
@@ -439,3 +488,229 @@ impl DrawImage {
     xlink_type!(attr);
     xml_id!(attr);
 }
+
+/// The <draw:object> element represents an object that is embedded in the
+/// document, e.g. another ODF document like a text document or a formula.
+///
+/// The object is stored in a sub-directory of the package and referenced by
+/// xlink:href. [WorkBook::add_object](crate::WorkBook::add_object) creates
+/// the necessary parts and returns a DrawFrame.
+///
+/// A frame usually contains a draw:image after the object as replacement
+/// for applications that can't render the object.
+#[derive(Debug, Clone, Default, GetSize)]
+pub struct DrawObject {
+    attr: AttrMap2,
+    /// Inline content instead of xlink:href.
+    content: Vec<XmlTag>,
+}
+
+impl DrawObject {
+    /// New.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Object stored in the given directory of the package, e.g. "./Object 1".
+    pub fn new_href<S: Into<String>>(href: S) -> Self {
+        let mut object = Self::new();
+        object.set_xlink_href(href);
+        object.set_xlink_type(XLinkType::Simple);
+        object.set_xlink_show(XLinkShow::Embed);
+        object.set_xlink_actuate(XLinkActuate::OnLoad);
+        object
+    }
+
+    /// Allows access to all attributes of the style itself.
+    pub fn attrmap(&self) -> &AttrMap2 {
+        &self.attr
+    }
+
+    /// Allows access to all attributes of the style itself.
+    pub fn attrmap_mut(&mut self) -> &mut AttrMap2 {
+        &mut self.attr
+    }
+
+    /// Inline content of the object.
+    pub fn content(&self) -> &Vec<XmlTag> {
+        &self.content
+    }
+
+    /// Inline content of the object.
+    pub fn content_mut(&mut self) -> &mut Vec<XmlTag> {
+        &mut self.content
+    }
+
+    draw_notify_on_update_of_ranges!(attr);
+    xlink_actuate!(attr);
+    xlink_href!(attr);
+    xlink_show!(attr);
+    xlink_type!(attr);
+    xml_id!(attr);
+}
+
+/// Size of an image in pixel and its resolution.
+///
+/// The natural size is the size in pixel divided by the resolution.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ImageSize {
+    /// Width in pixel.
+    pub width: u32,
+    /// Height in pixel.
+    pub height: u32,
+    /// Horizontal resolution in dpi.
+    pub dpi_x: f64,
+    /// Vertical resolution in dpi.
+    pub dpi_y: f64,
+}
+
+impl ImageSize {
+    /// Default resolution if the image doesn't contain one.
+    pub const DEFAULT_DPI: f64 = 96.0;
+
+    /// Image size with the default resolution.
+    pub fn new(width: u32, height: u32) -> Self {
+        Self {
+            width,
+            height,
+            dpi_x: Self::DEFAULT_DPI,
+            dpi_y: Self::DEFAULT_DPI,
+        }
+    }
+
+    /// Reads the size from the image data. Supports PNG, JPEG and GIF.
+    #[cfg(feature = "image_size")]
+    pub fn from_image(data: &[u8]) -> Result<Self, OdsError> {
+        imagesize::image_size(data)
+    }
+
+    /// Natural width.
+    pub fn natural_width(&self) -> Length {
+        Length::In(self.width as f64 / self.dpi_x)
+    }
+
+    /// Natural height.
+    pub fn natural_height(&self) -> Length {
+        Length::In(self.height as f64 / self.dpi_y)
+    }
+
+    /// Ratio width/height of the natural size.
+    ///
+    /// Returns None if the width, height or resolution is zero.
+    pub fn aspect_ratio(&self) -> Option<f64> {
+        let ratio = (self.width as f64 / self.dpi_x) / (self.height as f64 / self.dpi_y);
+        if ratio.is_finite() && ratio > 0.0 {
+            Some(ratio)
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(feature = "image_size")]
+mod imagesize {
+    use crate::draw::ImageSize;
+    use crate::OdsError;
+
+    fn be16(data: &[u8], pos: usize) -> Option<u16> {
+        Some(u16::from_be_bytes(data.get(pos..pos + 2)?.try_into().ok()?))
+    }
+
+    fn be32(data: &[u8], pos: usize) -> Option<u32> {
+        Some(u32::from_be_bytes(data.get(pos..pos + 4)?.try_into().ok()?))
+    }
+
+    fn le16(data: &[u8], pos: usize) -> Option<u16> {
+        Some(u16::from_le_bytes(data.get(pos..pos + 2)?.try_into().ok()?))
+    }
+
+    pub(crate) fn image_size(data: &[u8]) -> Result<ImageSize, OdsError> {
+        let size = if data.starts_with(b"\x89PNG\r\n\x1a\n") {
+            png_size(data)
+        } else if data.starts_with(&[0xFF, 0xD8]) {
+            jpeg_size(data)
+        } else if data.starts_with(b"GIF8") {
+            gif_size(data)
+        } else {
+            return Err(OdsError::Ods("Unknown image format".to_string()));
+        };
+        size.ok_or_else(|| OdsError::Ods("Invalid image data".to_string()))
+    }
+
+    fn png_size(data: &[u8]) -> Option<ImageSize> {
+        // IHDR is always the first chunk.
+        if data.get(12..16)? != b"IHDR" {
+            return None;
+        }
+        let mut size = ImageSize::new(be32(data, 16)?, be32(data, 20)?);
+
+        // look for the physical pixel dimensions.
+        let mut pos = 8;
+        while let Some(len) = be32(data, pos) {
+            let tag = data.get(pos + 4..pos + 8)?;
+            if tag == b"pHYs" {
+                let ppu_x = be32(data, pos + 8)?;
+                let ppu_y = be32(data, pos + 12)?;
+                // unit is meter
+                if *data.get(pos + 16)? == 1 && ppu_x > 0 && ppu_y > 0 {
+                    size.dpi_x = ppu_x as f64 * 0.0254;
+                    size.dpi_y = ppu_y as f64 * 0.0254;
+                }
+                break;
+            } else if tag == b"IDAT" || tag == b"IEND" {
+                break;
+            }
+            pos += 12 + len as usize;
+        }
+
+        Some(size)
+    }
+
+    fn jpeg_size(data: &[u8]) -> Option<ImageSize> {
+        let mut dpi = None;
+
+        let mut pos = 2;
+        loop {
+            // skip fill bytes
+            while *data.get(pos)? == 0xFF && *data.get(pos + 1)? == 0xFF {
+                pos += 1;
+            }
+            if *data.get(pos)? != 0xFF {
+                return None;
+            }
+            let marker = *data.get(pos + 1)?;
+            let len = be16(data, pos + 2)? as usize;
+            match marker {
+                // JFIF
+                0xE0 if data.get(pos + 4..pos + 9)? == b"JFIF\0" => {
+                    let unit = *data.get(pos + 11)?;
+                    let x = be16(data, pos + 12)? as f64;
+                    let y = be16(data, pos + 14)? as f64;
+                    match unit {
+                        1 => dpi = Some((x, y)),
+                        2 => dpi = Some((x * 2.54, y * 2.54)),
+                        _ => {}
+                    }
+                }
+                // start of frame, except DHT, JPG and DAC.
+                0xC0..=0xCF if marker != 0xC4 && marker != 0xC8 && marker != 0xCC => {
+                    let mut size =
+                        ImageSize::new(be16(data, pos + 7)? as u32, be16(data, pos + 5)? as u32);
+                    if let Some((x, y)) = dpi {
+                        if x > 0.0 && y > 0.0 {
+                            size.dpi_x = x;
+                            size.dpi_y = y;
+                        }
+                    }
+                    return Some(size);
+                }
+                _ => {}
+            }
+            pos += 2 + len;
+        }
+    }
+
+    fn gif_size(data: &[u8]) -> Option<ImageSize> {
+        Some(ImageSize::new(le16(data, 6)? as u32, le16(data, 8)? as u32))
+    }
+}
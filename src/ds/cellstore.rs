@@ -0,0 +1,437 @@
+//! Storage for the cell data of a sheet.
+//!
+//! The sparse variant is a BTreeMap, the dense variant stores each row
+//! as a Vec of optional cells. Both are iterated in (row, col) order.
+
+use std::collections::{btree_map, BTreeMap};
+use std::fmt::{Debug, Formatter};
+use std::iter::{Enumerate, FusedIterator};
+use std::ops::{Bound, RangeBounds};
+use std::{fmt, slice};
+
+use get_size2::GetSize;
+
+use crate::cell_::CellData;
+use crate::sheet_::CellStorage;
+
+type Key = (u32, u32);
+
+/// Cell data, either sparse or dense.
+#[derive(Debug, Clone, GetSize)]
+pub(crate) enum CellStore {
+    Sparse(BTreeMap<Key, CellData>),
+    Dense(DenseStore),
+}
+
+impl Default for CellStore {
+    fn default() -> Self {
+        CellStore::Sparse(BTreeMap::new())
+    }
+}
+
+/// One Vec per row. Rows are only as long as the last used column.
+#[derive(Debug, Clone, Default, GetSize)]
+pub(crate) struct DenseStore {
+    rows: Vec<Vec<Option<CellData>>>,
+    len: usize,
+}
+
+impl DenseStore {
+    fn cell_mut(&mut self, (row, col): Key) -> &mut Option<CellData> {
+        let (row, col) = (row as usize, col as usize);
+        if self.rows.len() <= row {
+            self.rows.resize_with(row + 1, Vec::new);
+        }
+        let cells = &mut self.rows[row];
+        if cells.len() <= col {
+            cells.resize_with(col + 1, || None);
+        }
+        &mut cells[col]
+    }
+
+    fn get(&self, (row, col): &Key) -> Option<&CellData> {
+        self.rows
+            .get(*row as usize)
+            .and_then(|v| v.get(*col as usize))
+            .and_then(|v| v.as_ref())
+    }
+
+    fn get_mut(&mut self, (row, col): &Key) -> Option<&mut CellData> {
+        self.rows
+            .get_mut(*row as usize)
+            .and_then(|v| v.get_mut(*col as usize))
+            .and_then(|v| v.as_mut())
+    }
+
+    /// Exclusive end of the used area as (row, col) key.
+    fn end_key(&self) -> Key {
+        (self.rows.len() as u32, 0)
+    }
+}
+
+/// Start and exclusive end of a range in (row, col) order.
+fn key_bounds<R: RangeBounds<Key>>(range: &R, end: Key) -> (Key, Key) {
+    fn next_key((row, col): Key) -> Key {
+        if col == u32::MAX {
+            (row.saturating_add(1), 0)
+        } else {
+            (row, col + 1)
+        }
+    }
+
+    let start = match range.start_bound() {
+        Bound::Included(k) => *k,
+        Bound::Excluded(k) => next_key(*k),
+        Bound::Unbounded => (0, 0),
+    };
+    let end = match range.end_bound() {
+        Bound::Included(k) => next_key(*k).min(end),
+        Bound::Excluded(k) => (*k).min(end),
+        Bound::Unbounded => end,
+    };
+    (start, end)
+}
+
+impl CellStore {
+    /// New empty storage of the given kind.
+    pub(crate) fn new(storage: CellStorage) -> Self {
+        match storage {
+            CellStorage::Sparse => CellStore::Sparse(BTreeMap::new()),
+            CellStorage::Dense => CellStore::Dense(DenseStore::default()),
+        }
+    }
+
+    /// Kind of storage.
+    pub(crate) fn storage(&self) -> CellStorage {
+        match self {
+            CellStore::Sparse(_) => CellStorage::Sparse,
+            CellStore::Dense(_) => CellStorage::Dense,
+        }
+    }
+
+    /// Moves all cells to a storage of the given kind.
+    pub(crate) fn convert(&mut self, storage: CellStorage) {
+        if self.storage() != storage {
+            let old = std::mem::replace(self, CellStore::new(storage));
+            match old {
+                CellStore::Sparse(map) => {
+                    for (k, v) in map {
+                        self.insert(k, v);
+                    }
+                }
+                CellStore::Dense(dense) => {
+                    for (row, cells) in dense.rows.into_iter().enumerate() {
+                        for (col, v) in cells.into_iter().enumerate() {
+                            if let Some(v) = v {
+                                self.insert((row as u32, col as u32), v);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        match self {
+            CellStore::Sparse(map) => map.len(),
+            CellStore::Dense(dense) => dense.len,
+        }
+    }
+
+    pub(crate) fn get(&self, key: &Key) -> Option<&CellData> {
+        match self {
+            CellStore::Sparse(map) => map.get(key),
+            CellStore::Dense(dense) => dense.get(key),
+        }
+    }
+
+    pub(crate) fn get_mut(&mut self, key: &Key) -> Option<&mut CellData> {
+        match self {
+            CellStore::Sparse(map) => map.get_mut(key),
+            CellStore::Dense(dense) => dense.get_mut(key),
+        }
+    }
+
+    pub(crate) fn contains_key(&self, key: &Key) -> bool {
+        self.get(key).is_some()
+    }
+
+    pub(crate) fn insert(&mut self, key: Key, cell: CellData) -> Option<CellData> {
+        match self {
+            CellStore::Sparse(map) => map.insert(key, cell),
+            CellStore::Dense(dense) => {
+                let old = dense.cell_mut(key).replace(cell);
+                if old.is_none() {
+                    dense.len += 1;
+                }
+                old
+            }
+        }
+    }
+
+    pub(crate) fn remove(&mut self, key: &Key) -> Option<CellData> {
+        match self {
+            CellStore::Sparse(map) => map.remove(key),
+            CellStore::Dense(dense) => {
+                let old = dense
+                    .rows
+                    .get_mut(key.0 as usize)
+                    .and_then(|v| v.get_mut(key.1 as usize))
+                    .and_then(|v| v.take());
+                if old.is_some() {
+                    dense.len -= 1;
+                }
+                old
+            }
+        }
+    }
+
+    pub(crate) fn entry(&mut self, key: Key) -> Entry<'_> {
+        match self {
+            CellStore::Sparse(map) => Entry::Sparse(map.entry(key)),
+            CellStore::Dense(dense) => Entry::Dense(dense, key),
+        }
+    }
+
+    pub(crate) fn iter(&self) -> Range<'_> {
+        self.range(..)
+    }
+
+    pub(crate) fn range<R: RangeBounds<Key>>(&self, range: R) -> Range<'_> {
+        match self {
+            CellStore::Sparse(map) => Range::Sparse(map.range(range)),
+            CellStore::Dense(dense) => {
+                let (front, back) = key_bounds(&range, dense.end_key());
+                Range::Dense(DenseRange {
+                    rows: &dense.rows,
+                    front,
+                    back,
+                })
+            }
+        }
+    }
+
+    pub(crate) fn range_mut<R: RangeBounds<Key>>(&mut self, range: R) -> RangeMut<'_> {
+        match self {
+            CellStore::Sparse(map) => RangeMut::Sparse(map.range_mut(range)),
+            CellStore::Dense(dense) => {
+                let (start, end) = key_bounds(&range, dense.end_key());
+                RangeMut::Dense(DenseRangeMut {
+                    rows: dense.rows.iter_mut().enumerate(),
+                    cells: None,
+                    start,
+                    end,
+                })
+            }
+        }
+    }
+
+    pub(crate) fn keys(&self) -> impl Iterator<Item = Key> + '_ {
+        self.iter().map(|(k, _)| k)
+    }
+
+    pub(crate) fn values_mut(&mut self) -> impl Iterator<Item = &mut CellData> {
+        self.range_mut(..).map(|(_, v)| v)
+    }
+
+    pub(crate) fn first_key_value(&self) -> Option<(Key, &CellData)> {
+        self.iter().next()
+    }
+
+    pub(crate) fn last_key_value(&self) -> Option<(Key, &CellData)> {
+        self.iter().next_back()
+    }
+}
+
+/// Entry for one cell.
+pub(crate) enum Entry<'a> {
+    Sparse(btree_map::Entry<'a, Key, CellData>),
+    Dense(&'a mut DenseStore, Key),
+}
+
+impl<'a> Entry<'a> {
+    pub(crate) fn or_default(self) -> &'a mut CellData {
+        match self {
+            Entry::Sparse(entry) => entry.or_default(),
+            Entry::Dense(dense, key) => {
+                if dense.get(&key).is_none() {
+                    dense.len += 1;
+                }
+                dense.cell_mut(key).get_or_insert_with(CellData::default)
+            }
+        }
+    }
+}
+
+/// Iterates a range of cells in (row, col) order.
+#[derive(Clone, Debug)]
+pub(crate) enum Range<'a> {
+    Sparse(btree_map::Range<'a, Key, CellData>),
+    Dense(DenseRange<'a>),
+}
+
+impl FusedIterator for Range<'_> {}
+
+impl<'a> Iterator for Range<'a> {
+    type Item = (Key, &'a CellData);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self {
+            Range::Sparse(range) => range.next().map(|(k, v)| (*k, v)),
+            Range::Dense(range) => range.next(),
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match self {
+            Range::Sparse(range) => range.size_hint(),
+            Range::Dense(_) => (0, None),
+        }
+    }
+}
+
+impl DoubleEndedIterator for Range<'_> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        match self {
+            Range::Sparse(range) => range.next_back().map(|(k, v)| (*k, v)),
+            Range::Dense(range) => range.next_back(),
+        }
+    }
+}
+
+/// Range over the dense storage. Front is inclusive, back is exclusive.
+#[derive(Clone)]
+pub(crate) struct DenseRange<'a> {
+    rows: &'a [Vec<Option<CellData>>],
+    front: Key,
+    back: Key,
+}
+
+impl Debug for DenseRange<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DenseRange")
+            .field("front", &self.front)
+            .field("back", &self.back)
+            .finish()
+    }
+}
+
+impl<'a> DenseRange<'a> {
+    fn next(&mut self) -> Option<(Key, &'a CellData)> {
+        while self.front < self.back {
+            let (row, col) = self.front;
+            let cells = &self.rows[row as usize];
+            if col as usize >= cells.len() {
+                self.front = (row + 1, 0);
+                continue;
+            }
+            self.front = (row, col + 1);
+            if let Some(cell) = &cells[col as usize] {
+                return Some(((row, col), cell));
+            }
+        }
+        None
+    }
+
+    fn next_back(&mut self) -> Option<(Key, &'a CellData)> {
+        while self.front < self.back {
+            let (row, col) = self.back;
+            if col == 0 {
+                let len = self.rows[row as usize - 1].len() as u32;
+                self.back = (row - 1, len);
+                continue;
+            }
+            let cells = &self.rows[row as usize];
+            let col = col.min(cells.len() as u32);
+            if col == 0 {
+                self.back = (row, 0);
+                continue;
+            }
+            self.back = (row, col - 1);
+            if let Some(cell) = &cells[col as usize - 1] {
+                return Some(((row, col - 1), cell));
+            }
+        }
+        None
+    }
+}
+
+/// Iterates a range of cells mutably in (row, col) order.
+pub(crate) enum RangeMut<'a> {
+    Sparse(btree_map::RangeMut<'a, Key, CellData>),
+    Dense(DenseRangeMut<'a>),
+}
+
+impl Debug for RangeMut<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            RangeMut::Sparse(range) => range.fmt(f),
+            RangeMut::Dense(range) => f
+                .debug_struct("DenseRangeMut")
+                .field("start", &range.start)
+                .field("end", &range.end)
+                .finish(),
+        }
+    }
+}
+
+impl FusedIterator for RangeMut<'_> {}
+
+impl<'a> Iterator for RangeMut<'a> {
+    type Item = (Key, &'a mut CellData);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self {
+            RangeMut::Sparse(range) => range.next().map(|(k, v)| (*k, v)),
+            RangeMut::Dense(range) => range.next(),
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match self {
+            RangeMut::Sparse(range) => range.size_hint(),
+            RangeMut::Dense(_) => (0, None),
+        }
+    }
+}
+
+/// Mutable range over the dense storage.
+pub(crate) struct DenseRangeMut<'a> {
+    rows: Enumerate<slice::IterMut<'a, Vec<Option<CellData>>>>,
+    cells: Option<(u32, Enumerate<slice::IterMut<'a, Option<CellData>>>)>,
+    start: Key,
+    end: Key,
+}
+
+impl<'a> DenseRangeMut<'a> {
+    fn next(&mut self) -> Option<(Key, &'a mut CellData)> {
+        loop {
+            if let Some((row, cells)) = &mut self.cells {
+                let row = *row;
+                for (col, cell) in cells.by_ref() {
+                    let key = (row, col as u32);
+                    if key < self.start {
+                        continue;
+                    }
+                    if key >= self.end {
+                        return None;
+                    }
+                    if let Some(cell) = cell {
+                        return Some((key, cell));
+                    }
+                }
+            }
+
+            let (row, cells) = self.rows.next()?;
+            let row = row as u32;
+            if row < self.start.0 {
+                continue;
+            }
+            if (row, 0) >= self.end {
+                return None;
+            }
+            self.cells = Some((row, cells.iter_mut().enumerate()));
+        }
+    }
+}
@@ -1 +1,2 @@
+pub(crate) mod cellstore;
 pub(crate) mod detach;
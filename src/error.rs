@@ -17,6 +17,36 @@ pub(crate) trait AsStatic<T: ?Sized> {
 /// Result type.
 pub type OdsResult<T> = Result<T, OdsError>;
 
+/// Location of a read error within the xml.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct XmlLocation {
+    /// Part of the package, e.g. "content.xml". "fods" for flat files.
+    pub part: String,
+    /// Byte position in the part after the last parsed event.
+    pub byte: u64,
+    /// Line, starting with 1. 0 if not known.
+    pub line: u64,
+    /// Column in bytes, starting with 1. 0 if not known.
+    pub column: u64,
+    /// Open elements at the error position, outermost first.
+    /// Empty if not known.
+    pub path: Vec<String>,
+}
+
+impl Display for XmlLocation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), std::fmt::Error> {
+        if self.line > 0 {
+            write!(f, "{}:{}:{}", self.part, self.line, self.column)?;
+        } else {
+            write!(f, "{} at byte {}", self.part, self.byte)?;
+        }
+        if !self.path.is_empty() {
+            write!(f, " in {}", self.path.join("/"))?;
+        }
+        Ok(())
+    }
+}
+
 #[derive(Debug)]
 #[allow(missing_docs)]
 pub enum OdsError {
@@ -33,6 +63,21 @@ pub enum OdsError {
     Chrono(chrono::format::ParseError),
     SystemTime(std::time::SystemTimeError),
     Base64(base64::DecodeError),
+    /// Reading or writing was cancelled.
+    Cancelled,
+}
+
+impl OdsError {
+    /// Adds the location in the xml to the message of the error.
+    /// Errors without a message are not changed.
+    pub(crate) fn located(self, loc: &XmlLocation) -> OdsError {
+        match self {
+            OdsError::Ods(msg) => OdsError::Ods(format!("{} at {}", msg, loc)),
+            OdsError::Parse(e, Some(v)) => OdsError::Parse(e, Some(format!("{} at {}", v, loc))),
+            OdsError::Parse(e, None) => OdsError::Parse(e, Some(format!("at {}", loc))),
+            err => err,
+        }
+    }
 }
 
 impl Display for OdsError {
@@ -51,6 +96,7 @@ impl Display for OdsError {
             OdsError::SystemTime(e) => write!(f, "SystemTime {}", e)?,
             OdsError::Utf8(e) => write!(f, "UTF8 {}", e)?,
             OdsError::Base64(e) => write!(f, "Base64 {}", e)?,
+            OdsError::Cancelled => write!(f, "Cancelled")?,
         }
 
         Ok(())
@@ -73,6 +119,7 @@ impl Error for OdsError {
             OdsError::SystemTime(e) => Some(e),
             OdsError::Utf8(e) => Some(e),
             OdsError::Base64(e) => Some(e),
+            OdsError::Cancelled => None,
         }
     }
 }
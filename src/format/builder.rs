@@ -257,12 +257,25 @@ impl<'vf, T: ValueFormatTrait> PartScientificBuilder<'vf, T> {
     /// attribute.
     /// The default value for this attribute is 1.
     #[must_use]
-    pub fn expontent_interval(mut self, v: u8) -> Self {
+    pub fn exponent_interval(mut self, v: u8) -> Self {
         self.part
             .set_attr("number:exponent-interval", v.to_string());
         self
     }
 
+    /// Use exponent_interval instead.
+    #[deprecated]
+    #[must_use]
+    pub fn expontent_interval(self, v: u8) -> Self {
+        self.exponent_interval(v)
+    }
+
+    /// Engineering notation, the exponent is a multiple of 3.
+    #[must_use]
+    pub fn engineering(self) -> Self {
+        self.exponent_interval(3)
+    }
+
     /// The number:forced-exponent-sign attribute specifies whether the sign of the exponent for a
     /// scientific number is always displayed.
     ///
@@ -306,6 +319,13 @@ impl<'vf, T: ValueFormatTrait> PartScientificBuilder<'vf, T> {
         self
     }
 
+    /// Sets decimal_places and min_decimal_places to the same value,
+    /// which in effect always displays the same number of decimals.
+    #[must_use]
+    pub fn fixed_decimal_places(self, v: u8) -> Self {
+        self.decimal_places(v).min_decimal_places(v)
+    }
+
     /// The number:min-exponent-digits attribute specifies the minimum number of digits to use to
     /// display an exponent.
     #[must_use]
@@ -432,7 +452,7 @@ impl<'vf, T: ValueFormatTrait> PartFractionBuilder<'vf, T> {
     #[must_use]
     pub fn min_integer_digits(mut self, v: u8) -> Self {
         self.part
-            .set_attr("number:min-numerator-digits", v.to_string());
+            .set_attr("number:min-integer-digits", v.to_string());
         self
     }
 
@@ -444,6 +464,50 @@ impl<'vf, T: ValueFormatTrait> PartFractionBuilder<'vf, T> {
             .set_attr("number:min-numerator-digits", v.to_string());
         self
     }
+
+    /// Denominator with up to v digits, like "?/??" in a format code.
+    /// Sets number:min-denominator-digits and number:max-denominator-value.
+    #[must_use]
+    pub fn denominator_digits(self, v: u8) -> Self {
+        self.min_denominator_digits(v)
+            .max_denominator(10i64.saturating_pow(v as u32) - 1)
+    }
+
+    /// Predefined denominator 2.
+    #[must_use]
+    pub fn halves(self) -> Self {
+        self.denominator(2).min_denominator_digits(1)
+    }
+
+    /// Predefined denominator 4.
+    #[must_use]
+    pub fn quarters(self) -> Self {
+        self.denominator(4).min_denominator_digits(1)
+    }
+
+    /// Predefined denominator 8.
+    #[must_use]
+    pub fn eighths(self) -> Self {
+        self.denominator(8).min_denominator_digits(1)
+    }
+
+    /// Predefined denominator 16.
+    #[must_use]
+    pub fn sixteenths(self) -> Self {
+        self.denominator(16).min_denominator_digits(2)
+    }
+
+    /// Predefined denominator 10.
+    #[must_use]
+    pub fn tenths(self) -> Self {
+        self.denominator(10).min_denominator_digits(2)
+    }
+
+    /// Predefined denominator 100.
+    #[must_use]
+    pub fn hundredths(self) -> Self {
+        self.denominator(100).min_denominator_digits(3)
+    }
 }
 
 /// The number:currency-symbol element specifies whether a currency symbol is displayed in
@@ -1,9 +1,11 @@
-use crate::format::FormatNumberStyle;
+use crate::color::Rgb;
+use crate::condition::ValueCondition;
+use crate::format::{FormatNumberStyle, ValueFormatTrait, ValueStyleMap};
 use crate::{
     ValueFormatBoolean, ValueFormatCurrency, ValueFormatDateTime, ValueFormatNumber,
-    ValueFormatPercentage, ValueFormatTimeDuration,
+    ValueFormatPercentage, ValueFormatRef, ValueFormatTimeDuration, WorkBook,
 };
-use icu_locid::Locale;
+use icu_locid::{locale, Locale};
 
 /// Creates a new number format.
 pub fn create_loc_boolean_format<S: AsRef<str>>(name: S, locale: Locale) -> ValueFormatBoolean {
@@ -334,3 +336,203 @@ pub fn create_time_interval_format<S: AsRef<str>>(name: S) -> ValueFormatTimeDur
     v.part_seconds().style(FormatNumberStyle::Long).build();
     v
 }
+
+/// High-level builder for number formats.
+///
+/// Assembles the FormatParts for the common cases: thousands separator,
+/// decimal places, minimum integer digits and negative numbers in red.
+/// The decimal and grouping separators are taken from the locale.
+///
+/// ```
+/// use spreadsheet_ods::{WorkBook, ValueFormatNumber};
+/// use spreadsheet_ods::format::NumberFormatBuilder;
+/// use icu_locid::locale;
+///
+/// let mut book = WorkBook::new_empty();
+/// let fref = NumberFormatBuilder::de_de("num_de")
+///     .decimal_places(3)
+///     .negative_red(true)
+///     .build_into(&mut book);
+///
+/// let fref2 = ValueFormatNumber::builder("num_us", locale!("en_US"))
+///     .thousands_separator(true)
+///     .build_into(&mut book);
+/// ```
+#[derive(Debug, Clone)]
+pub struct NumberFormatBuilder {
+    name: String,
+    locale: Locale,
+    decimal_places: u8,
+    fixed_decimal_places: bool,
+    grouping: bool,
+    min_integer_digits: u8,
+    negative_red: bool,
+}
+
+impl NumberFormatBuilder {
+    /// New builder. Defaults to 2 fixed decimal places, no thousands
+    /// separator and 1 minimum integer digit.
+    pub fn new<S: AsRef<str>>(name: S, locale: Locale) -> Self {
+        Self {
+            name: name.as_ref().to_string(),
+            locale,
+            decimal_places: 2,
+            fixed_decimal_places: true,
+            grouping: false,
+            min_integer_digits: 1,
+            negative_red: false,
+        }
+    }
+
+    /// Preset for a locale: 2 decimal places and thousands separator.
+    pub fn preset<S: AsRef<str>>(name: S, locale: Locale) -> Self {
+        Self::new(name, locale).thousands_separator(true)
+    }
+
+    /// Preset for de_DE. 1.234,56
+    pub fn de_de<S: AsRef<str>>(name: S) -> Self {
+        Self::preset(name, locale!("de_DE"))
+    }
+
+    /// Preset for de_AT. 1.234,56
+    pub fn de_at<S: AsRef<str>>(name: S) -> Self {
+        Self::preset(name, locale!("de_AT"))
+    }
+
+    /// Preset for de_CH. 1'234.56
+    pub fn de_ch<S: AsRef<str>>(name: S) -> Self {
+        Self::preset(name, locale!("de_CH"))
+    }
+
+    /// Preset for en_US. 1,234.56
+    pub fn en_us<S: AsRef<str>>(name: S) -> Self {
+        Self::preset(name, locale!("en_US"))
+    }
+
+    /// Preset for en_GB. 1,234.56
+    pub fn en_gb<S: AsRef<str>>(name: S) -> Self {
+        Self::preset(name, locale!("en_GB"))
+    }
+
+    /// Preset for fr_FR. 1 234,56
+    pub fn fr_fr<S: AsRef<str>>(name: S) -> Self {
+        Self::preset(name, locale!("fr_FR"))
+    }
+
+    /// Preset for it_IT. 1.234,56
+    pub fn it_it<S: AsRef<str>>(name: S) -> Self {
+        Self::preset(name, locale!("it_IT"))
+    }
+
+    /// Preset for es_ES. 1.234,56
+    pub fn es_es<S: AsRef<str>>(name: S) -> Self {
+        Self::preset(name, locale!("es_ES"))
+    }
+
+    /// Use a thousands separator.
+    pub fn thousands_separator(mut self, grouping: bool) -> Self {
+        self.grouping = grouping;
+        self
+    }
+
+    /// Always show this number of decimal places.
+    pub fn decimal_places(mut self, decimal_places: u8) -> Self {
+        self.decimal_places = decimal_places;
+        self.fixed_decimal_places = true;
+        self
+    }
+
+    /// Show at most this number of decimal places, trailing zeros are dropped.
+    pub fn max_decimal_places(mut self, decimal_places: u8) -> Self {
+        self.decimal_places = decimal_places;
+        self.fixed_decimal_places = false;
+        self
+    }
+
+    /// Minimum number of integer digits, padded with leading zeros.
+    pub fn min_integer_digits(mut self, digits: u8) -> Self {
+        self.min_integer_digits = digits;
+        self
+    }
+
+    /// Show negative numbers in red.
+    ///
+    /// This needs a second format for the positive numbers. It is named
+    /// after the main format with a suffix "P0". With
+    /// [build_into](NumberFormatBuilder::build_into) the suffix is
+    /// counted up until the name is not used by another format.
+    pub fn negative_red(mut self, red: bool) -> Self {
+        self.negative_red = red;
+        self
+    }
+
+    fn create_format(&self, name: &str) -> ValueFormatNumber {
+        let mut v = ValueFormatNumber::new_localized(name, self.locale.clone());
+        self.push_number(&mut v);
+        v
+    }
+
+    fn push_number(&self, v: &mut ValueFormatNumber) {
+        v.part_number()
+            .min_integer_digits(self.min_integer_digits)
+            .if_then(self.fixed_decimal_places, |p| {
+                p.fixed_decimal_places(self.decimal_places)
+            })
+            .if_then(!self.fixed_decimal_places, |p| {
+                p.decimal_places(self.decimal_places)
+            })
+            .if_then(self.grouping, |p| p.grouping())
+            .build();
+    }
+
+    /// Builds the format. If negative_red is set, this returns the
+    /// positive sub-format too. Both must be added to the workbook.
+    ///
+    /// Use [build_into](NumberFormatBuilder::build_into) if the format
+    /// has no name yet.
+    pub fn build(self) -> (ValueFormatNumber, Option<ValueFormatNumber>) {
+        let pos_name = format!("{}P0", self.name);
+        self.build_with(pos_name)
+    }
+
+    fn build_with(self, pos_name: String) -> (ValueFormatNumber, Option<ValueFormatNumber>) {
+        if self.negative_red {
+            let positive = self.create_format(&pos_name);
+
+            let mut v = ValueFormatNumber::new_localized(&self.name, self.locale.clone());
+            v.set_color(Rgb::new(255, 0, 0));
+            v.part_text("-").build();
+            self.push_number(&mut v);
+            v.push_stylemap(ValueStyleMap::new(ValueCondition::value_ge(0), pos_name));
+
+            (v, Some(positive))
+        } else {
+            (self.create_format(&self.name), None)
+        }
+    }
+
+    /// Builds the format and adds it to the workbook.
+    /// Unnamed formats are assigned an automatic name.
+    pub fn build_into(mut self, book: &mut WorkBook) -> ValueFormatRef {
+        if self.name.is_empty() {
+            self.name = book.unused_format_name("val_number");
+        }
+        let pos_name = if self.negative_red {
+            book.unused_format_name(&format!("{}P", self.name))
+        } else {
+            String::new()
+        };
+        let (v, positive) = self.build_with(pos_name);
+        if let Some(positive) = positive {
+            book.add_number_format(positive);
+        }
+        book.add_number_format(v)
+    }
+}
+
+impl ValueFormatNumber {
+    /// High-level builder for a number format.
+    pub fn builder<S: AsRef<str>>(name: S, locale: Locale) -> NumberFormatBuilder {
+        NumberFormatBuilder::new(name, locale)
+    }
+}
@@ -0,0 +1,213 @@
+//!
+//! ISO 4217 currency data for creating currency formats.
+//!
+
+use crate::{OdsError, ValueFormatCurrency};
+use icu_locid::Locale;
+
+/// Information about a currency.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Currency {
+    code: &'static str,
+    symbol: &'static str,
+    local_symbol: &'static str,
+    decimals: u8,
+    locale: &'static str,
+    regions: &'static [&'static str],
+}
+
+impl Currency {
+    /// ISO 4217 code.
+    pub fn code(&self) -> &'static str {
+        self.code
+    }
+
+    /// Symbol that is unambiguous outside of the currency's home regions.
+    pub fn symbol(&self) -> &'static str {
+        self.symbol
+    }
+
+    /// Symbol that is used within the currency's home regions.
+    pub fn local_symbol(&self) -> &'static str {
+        self.local_symbol
+    }
+
+    /// Symbol for use with the given locale.
+    pub fn symbol_for(&self, locale: &Locale) -> &'static str {
+        if self.is_local(locale) {
+            self.local_symbol
+        } else {
+            self.symbol
+        }
+    }
+
+    /// Number of minor unit digits.
+    pub fn decimals(&self) -> u8 {
+        self.decimals
+    }
+
+    /// Primary locale of the currency.
+    pub fn locale(&self) -> Locale {
+        self.locale.parse().expect("valid locale")
+    }
+
+    /// Is this the currency of the locale's region.
+    pub fn is_local(&self, locale: &Locale) -> bool {
+        match locale.id.region {
+            Some(region) => self.regions.contains(&region.as_str()),
+            None => false,
+        }
+    }
+}
+
+macro_rules! currency {
+    ($code:literal, $symbol:literal, $local:literal, $decimals:literal, $locale:literal, [$($region:literal),*]) => {
+        Currency {
+            code: $code,
+            symbol: $symbol,
+            local_symbol: $local,
+            decimals: $decimals,
+            locale: $locale,
+            regions: &[$($region),*],
+        }
+    };
+}
+
+/// Common currencies.
+static CURRENCIES: &[Currency] = &[
+    currency!("AED", "AED", "AED", 2, "ar-AE", ["AE"]),
+    currency!("ARS", "ARS", "$", 2, "es-AR", ["AR"]),
+    currency!("AUD", "A$", "$", 2, "en-AU", ["AU"]),
+    currency!("BGN", "BGN", "лв.", 2, "bg-BG", ["BG"]),
+    currency!("BHD", "BHD", "BHD", 3, "ar-BH", ["BH"]),
+    currency!("BRL", "R$", "R$", 2, "pt-BR", ["BR"]),
+    currency!("CAD", "CA$", "$", 2, "en-CA", ["CA"]),
+    currency!("CHF", "CHF", "CHF", 2, "de-CH", ["CH", "LI"]),
+    currency!("CLP", "CLP", "$", 0, "es-CL", ["CL"]),
+    currency!("CNY", "CN¥", "¥", 2, "zh-CN", ["CN"]),
+    currency!("CZK", "CZK", "Kč", 2, "cs-CZ", ["CZ"]),
+    currency!("DKK", "DKK", "kr.", 2, "da-DK", ["DK"]),
+    currency!(
+        "EUR",
+        "€",
+        "€",
+        2,
+        "de-DE",
+        [
+            "AT", "BE", "CY", "DE", "EE", "ES", "FI", "FR", "GR", "HR", "IE", "IT", "LT", "LU",
+            "LV", "MT", "NL", "PT", "SI", "SK"
+        ]
+    ),
+    currency!("GBP", "£", "£", 2, "en-GB", ["GB"]),
+    currency!("HKD", "HK$", "HK$", 2, "zh-HK", ["HK"]),
+    currency!("HUF", "HUF", "Ft", 2, "hu-HU", ["HU"]),
+    currency!("IDR", "IDR", "Rp", 2, "id-ID", ["ID"]),
+    currency!("ILS", "₪", "₪", 2, "he-IL", ["IL"]),
+    currency!("INR", "₹", "₹", 2, "hi-IN", ["IN"]),
+    currency!("ISK", "ISK", "kr", 0, "is-IS", ["IS"]),
+    currency!("JPY", "JP¥", "¥", 0, "ja-JP", ["JP"]),
+    currency!("KRW", "₩", "₩", 0, "ko-KR", ["KR"]),
+    currency!("KWD", "KWD", "KWD", 3, "ar-KW", ["KW"]),
+    currency!("MXN", "MX$", "$", 2, "es-MX", ["MX"]),
+    currency!("NOK", "NOK", "kr", 2, "nb-NO", ["NO"]),
+    currency!("NZD", "NZ$", "$", 2, "en-NZ", ["NZ"]),
+    currency!("PHP", "₱", "₱", 2, "en-PH", ["PH"]),
+    currency!("PLN", "PLN", "zł", 2, "pl-PL", ["PL"]),
+    currency!("RON", "RON", "lei", 2, "ro-RO", ["RO"]),
+    currency!("RUB", "RUB", "₽", 2, "ru-RU", ["RU"]),
+    currency!("SAR", "SAR", "SAR", 2, "ar-SA", ["SA"]),
+    currency!("SEK", "SEK", "kr", 2, "sv-SE", ["SE"]),
+    currency!("SGD", "S$", "$", 2, "en-SG", ["SG"]),
+    currency!("THB", "THB", "฿", 2, "th-TH", ["TH"]),
+    currency!("TRY", "TRY", "₺", 2, "tr-TR", ["TR"]),
+    currency!("TWD", "NT$", "$", 2, "zh-TW", ["TW"]),
+    currency!("UAH", "UAH", "₴", 2, "uk-UA", ["UA"]),
+    currency!("USD", "US$", "$", 2, "en-US", ["US", "EC", "PR", "SV"]),
+    currency!("VND", "₫", "₫", 0, "vi-VN", ["VN"]),
+    currency!("ZAR", "ZAR", "R", 2, "en-ZA", ["ZA"]),
+];
+
+/// Looks up the currency for an ISO 4217 code.
+pub fn currency(iso_code: &str) -> Option<&'static Currency> {
+    CURRENCIES
+        .iter()
+        .find(|v| v.code.eq_ignore_ascii_case(iso_code))
+}
+
+/// Returns the symbol for an ISO 4217 code.
+pub fn currency_symbol(iso_code: &str) -> Option<&'static str> {
+    currency(iso_code).map(|v| v.symbol)
+}
+
+/// Placement of the currency symbol for a locale. Returns (prefix, space).
+fn symbol_placement(locale: &Locale) -> (bool, bool) {
+    let language = locale.id.language.as_str();
+    let region = locale.id.region.as_ref().map(|v| v.as_str());
+    match (language, region) {
+        ("de", Some("AT" | "CH" | "LI")) => (true, true),
+        ("nl", _) => (true, true),
+        ("pt", Some("BR")) => (true, true),
+        ("es", Some(region)) if region != "ES" => (true, false),
+        (
+            "bg" | "ca" | "cs" | "da" | "de" | "el" | "es" | "et" | "fi" | "fr" | "he" | "hr"
+            | "hu" | "is" | "it" | "lt" | "lv" | "nb" | "nn" | "no" | "pl" | "pt" | "ro" | "ru"
+            | "sk" | "sl" | "sr" | "sv" | "uk" | "vi",
+            _,
+        ) => (false, true),
+        _ => (true, false),
+    }
+}
+
+/// Creates a currency format for the ISO 4217 code.
+///
+/// The symbol placement and spacing follow the conventions of the locale,
+/// the number of decimals is the one of the currency.
+///
+/// ```
+/// use spreadsheet_ods::format::currency_format;
+/// use icu_locid::locale;
+///
+/// let v = currency_format(locale!("de_DE"), "EUR").unwrap();  // 1.234,56 €
+/// let v = currency_format(locale!("en_US"), "JPY").unwrap();  // JP¥1,235
+/// ```
+pub fn currency_format(locale: Locale, iso_code: &str) -> Result<ValueFormatCurrency, OdsError> {
+    let currency = currency(iso_code)
+        .ok_or_else(|| OdsError::Parse("unknown currency code", Some(iso_code.to_string())))?;
+
+    let symbol = currency.symbol_for(&locale);
+    let symbol_locale = if currency.is_local(&locale) {
+        locale.clone()
+    } else {
+        currency.locale()
+    };
+    let (prefix, mut space) = symbol_placement(&locale);
+    if prefix && symbol.ends_with(|c: char| c.is_alphabetic()) {
+        space = true;
+    }
+
+    let mut v = ValueFormatCurrency::new_localized("", locale);
+    if prefix {
+        v.part_currency()
+            .locale(symbol_locale.clone())
+            .symbol(symbol)
+            .build();
+        if space {
+            v.part_text(" ").build();
+        }
+    }
+    v.part_number()
+        .min_integer_digits(1)
+        .fixed_decimal_places(currency.decimals)
+        .grouping()
+        .build();
+    if !prefix {
+        if space {
+            v.part_text(" ").build();
+        }
+        v.part_currency()
+            .locale(symbol_locale)
+            .symbol(symbol)
+            .build();
+    }
+    Ok(v)
+}
@@ -0,0 +1,558 @@
+//!
+//! Renders a value with a value format as the text that is displayed
+//! in a cell.
+//!
+
+use crate::attrmap2::AttrMap2;
+use crate::format::{FormatPart, FormatPartType, ValueFormatTrait};
+use crate::locale::{localized_format_or_default, LocalizedValueFormat};
+use crate::sheet_::value_text;
+use crate::{Value, ValueType};
+use chrono::{Datelike, Duration, NaiveDate, NaiveTime, Timelike};
+use icu_locid::subtags::{Language, Region};
+use icu_locid::{LanguageIdentifier, Locale};
+
+/// Text for the value formatted with the parts of the value format.
+/// The stylemaps are not considered.
+pub(crate) fn format_value<T: ValueFormatTrait>(vf: &T, value: &Value) -> String {
+    render(vf, value, false)
+}
+
+/// Text for the value formatted with the value format or with the
+/// first sub-format whose stylemap condition matches.
+pub(crate) fn format_value_with<'a, T, F>(vf: &T, value: &Value, lookup: F) -> String
+where
+    T: ValueFormatTrait + 'a,
+    F: Fn(&str) -> Option<&'a T>,
+{
+    if let (Some(stylemaps), Some(v)) = (vf.stylemaps(), serial_number(value)) {
+        let mut positive = false;
+        for stylemap in stylemaps {
+            let Some((op, bound)) = parse_condition(&stylemap.condition().to_string()) else {
+                continue;
+            };
+            let matches = match op {
+                ">=" => v >= bound,
+                "<=" => v <= bound,
+                "!=" | "<>" => v != bound,
+                "=" => v == bound,
+                "<" => v < bound,
+                _ => v > bound,
+            };
+            if matches {
+                if let Some(sub) = lookup(stylemap.applied_style()) {
+                    // the sub-format for negative values has its own sign.
+                    let negative = matches!(op, "<" | "<=") && bound <= 0.0;
+                    return render(sub, value, negative);
+                }
+            }
+            positive |= matches!(op, ">=" | ">") && bound == 0.0;
+        }
+        // the format for negative values if the positive ones have a sub-format.
+        return render(vf, value, positive);
+    }
+    render(vf, value, false)
+}
+
+/// Splits a condition "value()>=0" into operator and value.
+fn parse_condition(cond: &str) -> Option<(&'static str, f64)> {
+    let cond = cond.trim().strip_prefix("value()")?.trim_start();
+    let (op, bound) = [">=", "<=", "!=", "<>", "=", "<", ">"]
+        .iter()
+        .find_map(|op| cond.strip_prefix(op).map(|v| (*op, v)))?;
+    Some((op, bound.trim().parse().ok()?))
+}
+
+/// Locale of the value format.
+fn format_locale(attr: &AttrMap2) -> Option<Locale> {
+    let language = Language::try_from_bytes(attr.attr("number:language")?.as_bytes()).ok()?;
+    let region = attr
+        .attr("number:country")
+        .and_then(|v| Region::try_from_bytes(v.as_bytes()).ok());
+    Some(Locale::from(LanguageIdentifier::from((
+        language, None, region,
+    ))))
+}
+
+/// The value as days since 30.12.1899 for dates and times.
+fn serial_number(value: &Value) -> Option<f64> {
+    match value {
+        Value::Boolean(v) => Some(if *v { 1.0 } else { 0.0 }),
+        Value::DateTime(_) | Value::Date(_) | Value::Time(_) | Value::TimeDuration(_) => {
+            Some(serial_millis(value)? as f64 / 86_400_000.0)
+        }
+        _ => value.as_f64_opt(),
+    }
+}
+
+/// The value as milliseconds since 30.12.1899 for dates and times.
+fn serial_millis(value: &Value) -> Option<i64> {
+    let base = NaiveDate::from_ymd_opt(1899, 12, 30)
+        .expect("valid date")
+        .and_time(NaiveTime::MIN);
+    match value {
+        Value::DateTime(_) | Value::Date(_) => {
+            Some((value.as_datetime_opt()? - base).num_milliseconds())
+        }
+        Value::Time(_) | Value::TimeDuration(_) => {
+            Some(value.as_timeduration_opt()?.num_milliseconds())
+        }
+        _ => value
+            .as_f64_opt()
+            .map(|v| (v * 86_400_000.0).round())
+            .filter(|v| v.abs() < i64::MAX as f64)
+            .map(|v| v as i64),
+    }
+}
+
+fn is_time_part(part: &FormatPart) -> bool {
+    matches!(
+        part.part_type(),
+        FormatPartType::Hours | FormatPartType::Minutes | FormatPartType::Seconds
+    )
+}
+
+fn is_date_part(part: &FormatPart) -> bool {
+    matches!(
+        part.part_type(),
+        FormatPartType::Day
+            | FormatPartType::Month
+            | FormatPartType::Year
+            | FormatPartType::Era
+            | FormatPartType::DayOfWeek
+            | FormatPartType::WeekOfYear
+            | FormatPartType::Quarter
+    )
+}
+
+fn render<T: ValueFormatTrait>(vf: &T, value: &Value, abs: bool) -> String {
+    let locale = localized_format_or_default(format_locale(vf.attrmap()));
+    let parts = vf.parts();
+
+    match value {
+        Value::Empty => String::new(),
+        Value::Error(v) => v.clone(),
+        Value::Text(_) | Value::TextXml(_) => {
+            let text = value.as_cow_str_or("");
+            if parts
+                .iter()
+                .any(|v| v.part_type() == FormatPartType::TextContent)
+            {
+                let mut buf = String::new();
+                for part in parts {
+                    match part.part_type() {
+                        FormatPartType::TextContent => buf.push_str(&text),
+                        FormatPartType::Text => buf.push_str(content(part)),
+                        _ => {}
+                    }
+                }
+                buf
+            } else {
+                text.into_owned()
+            }
+        }
+        _ if parts.is_empty() => value_text(value),
+        _ if parts.iter().any(|v| is_time_part(v) || is_date_part(v)) => {
+            match serial_millis(value).and_then(|v| render_datetime(vf, v, locale)) {
+                Some(v) => v,
+                None => value_text(value),
+            }
+        }
+        _ => match serial_number(value) {
+            Some(v) => render_number(vf, value, v, abs, locale),
+            None => value_text(value),
+        },
+    }
+}
+
+fn content(part: &FormatPart) -> &str {
+    part.content().map(|v| v.as_str()).unwrap_or("")
+}
+
+fn attr_u32(part: &FormatPart, name: &str) -> Option<u32> {
+    part.attr_def(name, "").parse().ok()
+}
+
+fn is_long(part: &FormatPart) -> bool {
+    part.attr_def("number:style", "") == "long"
+}
+
+/// Number formats, percentages, currencies and booleans.
+fn render_number<T: ValueFormatTrait>(
+    vf: &T,
+    value: &Value,
+    v: f64,
+    abs: bool,
+    locale: &dyn LocalizedValueFormat,
+) -> String {
+    let v = if vf.value_type() == ValueType::Percentage {
+        v * 100.0
+    } else {
+        v
+    };
+    let mut negative = v < 0.0 && !abs;
+
+    let mut buf = String::new();
+    for part in vf.parts() {
+        match part.part_type() {
+            FormatPartType::Number
+            | FormatPartType::ScientificNumber
+            | FormatPartType::Fraction => {
+                let digits = match part.part_type() {
+                    FormatPartType::Number => number_digits(part, v.abs(), locale),
+                    FormatPartType::ScientificNumber => scientific_digits(part, v.abs(), locale),
+                    _ => fraction_digits(part, v.abs(), locale),
+                };
+                // no sign if it rounds to zero.
+                if negative && digits.chars().any(|c| c.is_ascii_digit() && c != '0') {
+                    buf.push('-');
+                }
+                negative = false;
+                buf.push_str(&digits);
+            }
+            FormatPartType::CurrencySymbol => match (part.content(), value) {
+                (Some(symbol), _) => buf.push_str(symbol),
+                (None, Value::Currency(_, currency)) => buf.push_str(currency),
+                _ => {}
+            },
+            FormatPartType::Boolean => {
+                let (t, f) = locale.boolean_names();
+                buf.push_str(if v != 0.0 { t } else { f });
+            }
+            FormatPartType::Text => buf.push_str(content(part)),
+            FormatPartType::TextContent => buf.push_str(&value_text(value)),
+            _ => {}
+        }
+    }
+    buf
+}
+
+/// Integer digits with min-integer-digits, grouping and embedded text.
+fn integer_digits(part: &FormatPart, int: &str, locale: &dyn LocalizedValueFormat) -> String {
+    let min_digits = attr_u32(part, "number:min-integer-digits").unwrap_or(0) as usize;
+    let grouping = part.attr_def("number:grouping", "") == "true";
+
+    let int = int.trim_start_matches('0');
+    let int = if int.len() < min_digits {
+        format!("{}{}", "0".repeat(min_digits - int.len()), int)
+    } else {
+        int.to_string()
+    };
+
+    // built from the right.
+    let mut buf = Vec::new();
+    for (i, c) in int.chars().rev().enumerate() {
+        if grouping && i > 0 && i % 3 == 0 {
+            buf.push(locale.grouping_separator().to_string());
+        }
+        buf.push(c.to_string());
+        if part.position() == Some(i as i32 + 1) && i + 1 < int.len() {
+            buf.push(content(part).to_string());
+        }
+    }
+    if part
+        .position()
+        .is_some_and(|v| v > 0 && v as usize >= int.len())
+    {
+        buf.push(content(part).to_string());
+    }
+    buf.reverse();
+    buf.concat()
+}
+
+/// Number with decimal places.
+fn number_digits(part: &FormatPart, v: f64, locale: &dyn LocalizedValueFormat) -> String {
+    let factor = part
+        .attr_def("number:display-factor", "")
+        .parse::<f64>()
+        .ok()
+        .filter(|v| *v != 0.0)
+        .unwrap_or(1.0);
+    // without decimal places the number is shown as needed.
+    let (decimals, min_decimals) = match attr_u32(part, "number:decimal-places") {
+        Some(decimals) => (
+            decimals,
+            attr_u32(part, "number:min-decimal-places")
+                .unwrap_or(decimals)
+                .min(decimals),
+        ),
+        None => (10, 0),
+    };
+
+    decimal_digits(part, v / factor, decimals, min_decimals, locale)
+}
+
+fn decimal_digits(
+    part: &FormatPart,
+    v: f64,
+    decimals: u32,
+    min_decimals: u32,
+    locale: &dyn LocalizedValueFormat,
+) -> String {
+    let text = format!("{:.*}", decimals as usize, round(v, decimals));
+    let (int, frac) = text.split_once('.').unwrap_or((&text, ""));
+
+    let mut buf = integer_digits(part, int, locale);
+    let replacement = part.attr_def("number:decimal-replacement", "");
+    if !frac.is_empty() && frac.chars().all(|c| c == '0') && !replacement.is_empty() {
+        buf.push(locale.decimal_separator());
+        buf.push_str(replacement);
+    } else {
+        let trimmed = frac.trim_end_matches('0');
+        let frac = &frac[..trimmed.len().max(min_decimals as usize)];
+        if !frac.is_empty() {
+            buf.push(locale.decimal_separator());
+            buf.push_str(frac);
+        }
+    }
+    buf
+}
+
+/// Rounds half away from zero like a spreadsheet. The digits beyond
+/// the precision of f64 are dropped first, so 1.005 rounds to 1.01.
+fn round(v: f64, decimals: u32) -> f64 {
+    let scale = 10f64.powi(decimals as i32);
+    let scaled = v * scale;
+    let scaled = format!("{:.14e}", scaled).parse().unwrap_or(scaled);
+    f64::round(scaled) / scale
+}
+
+/// Mantissa and exponent.
+fn scientific_digits(part: &FormatPart, v: f64, locale: &dyn LocalizedValueFormat) -> String {
+    let decimals = attr_u32(part, "number:decimal-places").unwrap_or(0);
+    let min_decimals = attr_u32(part, "number:min-decimal-places")
+        .unwrap_or(decimals)
+        .min(decimals);
+    let int_digits = attr_u32(part, "number:min-integer-digits")
+        .unwrap_or(1)
+        .max(1) as i32;
+
+    let mut exp = if v == 0.0 {
+        0
+    } else {
+        v.log10().floor() as i32 - (int_digits - 1)
+    };
+    let mut mantissa = v / 10f64.powi(exp);
+    if round(mantissa, decimals) >= 10f64.powi(int_digits) {
+        mantissa /= 10.0;
+        exp += 1;
+    }
+
+    let mut buf = decimal_digits(part, mantissa, decimals, min_decimals, locale);
+    buf.push('E');
+    if exp < 0 {
+        buf.push('-');
+    } else if part.attr_def("number:forced-exponent-sign", "true") != "false" {
+        buf.push('+');
+    }
+    let exp_digits = attr_u32(part, "number:min-exponent-digits").unwrap_or(1) as usize;
+    buf.push_str(&format!("{:0w$}", exp.abs(), w = exp_digits));
+    buf
+}
+
+/// Fraction with an optional integer part.
+fn fraction_digits(part: &FormatPart, v: f64, locale: &dyn LocalizedValueFormat) -> String {
+    let with_integer = attr_u32(part, "number:min-integer-digits").is_some();
+    let (mut int, frac) = if with_integer {
+        (v.trunc(), v.fract())
+    } else {
+        (0.0, v)
+    };
+
+    let (mut num, den) = if let Some(den) = attr_u32(part, "number:denominator-value")
+        .filter(|v| *v > 0)
+        .map(|v| v as i64)
+    {
+        ((frac * den as f64).round() as i64, den)
+    } else {
+        // limits the search, more digits are not displayed anyway.
+        let max_den = attr_u32(part, "number:max-denominator-value")
+            .filter(|v| *v > 0)
+            .map(|v| (v as i64).min(99_999))
+            .unwrap_or_else(|| {
+                let digits = attr_u32(part, "number:min-denominator-digits").unwrap_or(1);
+                10i64.pow(digits.clamp(1, 4)) - 1
+            });
+        let mut best = (frac.round() as i64, 1);
+        let mut best_err = (frac - best.0 as f64).abs();
+        for den in 2..=max_den {
+            let num = (frac * den as f64).round() as i64;
+            let err = (frac - num as f64 / den as f64).abs();
+            if err < best_err - f64::EPSILON {
+                best = (num, den);
+                best_err = err;
+            }
+        }
+        best
+    };
+    if with_integer && num == den {
+        int += 1.0;
+        num = 0;
+    }
+
+    let mut buf = String::new();
+    if with_integer {
+        buf.push_str(&integer_digits(part, &format!("{:.0}", int), locale));
+        if num == 0 {
+            if buf.is_empty() {
+                buf.push('0');
+            }
+            return buf;
+        }
+        if !buf.is_empty() {
+            buf.push(' ');
+        }
+    }
+    buf.push_str(&format!("{}/{}", num, den));
+    buf
+}
+
+/// Dates, times and durations. None if the date is out of range.
+fn render_datetime<T: ValueFormatTrait>(
+    vf: &T,
+    millis: i64,
+    locale: &dyn LocalizedValueFormat,
+) -> Option<String> {
+    let parts = vf.parts();
+
+    // round to the displayed precision of the seconds, without seconds
+    // the time is truncated.
+    let seconds = parts
+        .iter()
+        .find(|v| v.part_type() == FormatPartType::Seconds);
+    let decimals = seconds
+        .and_then(|v| attr_u32(v, "number:decimal-places"))
+        .unwrap_or(0)
+        .min(3);
+    let millis = if seconds.is_some() {
+        let precision = 10i64.pow(3 - decimals);
+        ((millis as f64 / precision as f64).round() as i64).checked_mul(precision)?
+    } else {
+        millis
+    };
+
+    let has_date = parts.iter().any(is_date_part);
+    let has_ampm = parts.iter().any(|v| v.part_type() == FormatPartType::AmPm);
+    // elapsed time for the largest time component.
+    let truncate = has_date
+        || has_ampm
+        || (vf.attrmap().attr("number:truncate-on-overflow") != Some("false")
+            && parts
+                .iter()
+                .all(|v| v.attr_def("number:truncate-on-overflow", "") != "false"));
+    let largest = [
+        FormatPartType::Hours,
+        FormatPartType::Minutes,
+        FormatPartType::Seconds,
+    ]
+    .into_iter()
+    .find(|t| parts.iter().any(|v| v.part_type() == *t));
+
+    let mut buf = String::new();
+    let (sign, millis) = if !has_date && millis < 0 {
+        ("-", -millis)
+    } else {
+        ("", millis)
+    };
+    buf.push_str(sign);
+
+    let base = NaiveDate::from_ymd_opt(1899, 12, 30)
+        .expect("valid date")
+        .and_time(NaiveTime::MIN);
+    // durations don't need a valid date.
+    let datetime = match Duration::try_milliseconds(millis).and_then(|v| base.checked_add_signed(v))
+    {
+        Some(v) => v,
+        None if has_date || has_ampm => return None,
+        None => base,
+    };
+    let day_millis = millis.rem_euclid(86_400_000);
+
+    let month_names = locale.month_names();
+    let day_names = locale.day_names();
+    let abbrev = |v: &str| v.chars().take(3).collect::<String>();
+    let two_digits = |part: &FormatPart, v: i64| {
+        if is_long(part) {
+            format!("{:02}", v)
+        } else {
+            v.to_string()
+        }
+    };
+    let time_value = |t: FormatPartType, unit: i64, modulo: i64| {
+        if !truncate && largest == Some(t) {
+            millis / unit
+        } else {
+            (day_millis / unit) % modulo
+        }
+    };
+
+    for part in parts {
+        match part.part_type() {
+            FormatPartType::Day => buf.push_str(&two_digits(part, datetime.day() as i64)),
+            FormatPartType::Month => {
+                let month = datetime.month0() as usize;
+                if part.attr_def("number:textual", "") == "true" {
+                    if is_long(part) {
+                        buf.push_str(month_names[month]);
+                    } else {
+                        buf.push_str(&abbrev(month_names[month]));
+                    }
+                } else {
+                    buf.push_str(&two_digits(part, month as i64 + 1));
+                }
+            }
+            FormatPartType::Year => {
+                if is_long(part) {
+                    buf.push_str(&format!("{:04}", datetime.year()));
+                } else {
+                    buf.push_str(&format!("{:02}", datetime.year().rem_euclid(100)));
+                }
+            }
+            FormatPartType::Era => buf.push_str(if datetime.year() > 0 { "AD" } else { "BC" }),
+            FormatPartType::DayOfWeek => {
+                let name = day_names[datetime.weekday().num_days_from_monday() as usize];
+                if is_long(part) {
+                    buf.push_str(name);
+                } else {
+                    buf.push_str(&abbrev(name));
+                }
+            }
+            FormatPartType::WeekOfYear => {
+                buf.push_str(&datetime.iso_week().week().to_string());
+            }
+            FormatPartType::Quarter => {
+                buf.push_str(&format!("Q{}", datetime.month0() / 3 + 1));
+            }
+            FormatPartType::Hours => {
+                let mut hours = time_value(FormatPartType::Hours, 3_600_000, 24);
+                if has_ampm {
+                    hours = (hours + 11) % 12 + 1;
+                }
+                buf.push_str(&two_digits(part, hours));
+            }
+            FormatPartType::Minutes => {
+                buf.push_str(&two_digits(
+                    part,
+                    time_value(FormatPartType::Minutes, 60_000, 60),
+                ));
+            }
+            FormatPartType::Seconds => {
+                buf.push_str(&two_digits(
+                    part,
+                    time_value(FormatPartType::Seconds, 1000, 60),
+                ));
+                if decimals > 0 {
+                    let frac = format!("{:03}", day_millis % 1000);
+                    buf.push(locale.decimal_separator());
+                    buf.push_str(&frac[..decimals as usize]);
+                }
+            }
+            FormatPartType::AmPm => {
+                buf.push_str(if datetime.hour() < 12 { "AM" } else { "PM" });
+            }
+            FormatPartType::Text => buf.push_str(content(part)),
+            _ => {}
+        }
+    }
+    Some(buf)
+}
@@ -0,0 +1,719 @@
+//!
+//! Conversion between Excel/Calc-style format codes and the parts of a value format.
+//!
+
+use crate::condition::ValueCondition;
+use crate::format::{FormatPart, FormatPartType, ValueFormatTrait, ValueStyleMap};
+use crate::{OdsError, ValueType};
+
+/// Named colors that can be used in a format code.
+const COLORS: [(&str, &str); 8] = [
+    ("BLACK", "#000000"),
+    ("BLUE", "#0000ff"),
+    ("CYAN", "#00ffff"),
+    ("GREEN", "#00ff00"),
+    ("MAGENTA", "#ff00ff"),
+    ("RED", "#ff0000"),
+    ("WHITE", "#ffffff"),
+    ("YELLOW", "#ffff00"),
+];
+
+/// Literal characters that need no quoting.
+const LITERALS: &str = " -+()/:$";
+
+/// One parsed section of a format code.
+#[derive(Default)]
+struct Section {
+    parts: Vec<FormatPart>,
+    text: String,
+    color: Option<&'static str>,
+    condition: Option<ValueCondition>,
+}
+
+impl Section {
+    fn push_text(&mut self, c: char) {
+        self.text.push(c);
+    }
+
+    fn push_part(&mut self, part: FormatPart) {
+        self.flush_text();
+        self.parts.push(part);
+    }
+
+    fn flush_text(&mut self) {
+        if !self.text.is_empty() {
+            let mut part = FormatPart::new(FormatPartType::Text);
+            part.set_content(std::mem::take(&mut self.text));
+            self.parts.push(part);
+        }
+    }
+
+    /// Last part that is not a text.
+    fn last_field(&self) -> Option<FormatPartType> {
+        self.parts
+            .iter()
+            .rev()
+            .map(|v| v.part_type())
+            .find(|v| *v != FormatPartType::Text)
+    }
+}
+
+fn err(msg: &'static str, fmt: &str) -> OdsError {
+    OdsError::Parse(msg, Some(fmt.to_string()))
+}
+
+/// Splits the format code into sections at ';'.
+fn split_sections(fmt: &str) -> Vec<Vec<char>> {
+    let mut sections = Vec::new();
+    let mut buf = Vec::new();
+    let mut quoted = false;
+    let mut bracket = false;
+    let mut escaped = false;
+    for c in fmt.chars() {
+        if escaped {
+            escaped = false;
+        } else if quoted {
+            quoted = c != '"';
+        } else if bracket {
+            bracket = c != ']';
+        } else if c == '"' {
+            quoted = true;
+        } else if c == '[' {
+            bracket = true;
+        } else if c == '\\' || c == '_' || c == '*' {
+            escaped = true;
+        } else if c == ';' {
+            sections.push(std::mem::take(&mut buf));
+            continue;
+        }
+        buf.push(c);
+    }
+    sections.push(buf);
+    sections
+}
+
+fn count_run(s: &[char], i: usize, c: char) -> usize {
+    s[i..]
+        .iter()
+        .take_while(|v| v.eq_ignore_ascii_case(&c))
+        .count()
+}
+
+fn is_digit_char(c: char) -> bool {
+    c == '#' || c == '0' || c == '?'
+}
+
+fn starts_with_ci(s: &[char], i: usize, pat: &str) -> bool {
+    let pat: Vec<char> = pat.chars().collect();
+    s.get(i..i + pat.len())
+        .map(|v| {
+            v.iter()
+                .zip(pat.iter())
+                .all(|(a, b)| a.eq_ignore_ascii_case(b))
+        })
+        .unwrap_or(false)
+}
+
+/// Date/time part with a short/long style.
+fn styled_part(ptype: FormatPartType, long: bool) -> FormatPart {
+    let mut part = FormatPart::new(ptype);
+    part.set_attr(
+        "number:style",
+        if long { "long" } else { "short" }.to_string(),
+    );
+    part
+}
+
+/// Is the m-run at i a minute? Looks back for an hour and ahead for a second.
+fn is_minute(section: &Section, s: &[char], i: usize) -> bool {
+    if section.last_field() == Some(FormatPartType::Hours) {
+        return true;
+    }
+    s[i..]
+        .iter()
+        .find(|v| v.is_ascii_alphabetic() && !v.eq_ignore_ascii_case(&'m'))
+        .map(|v| v.eq_ignore_ascii_case(&'s'))
+        .unwrap_or(false)
+}
+
+/// Returns (min-integer-digits, grouping) for the integer part.
+fn integer_digits(int: &[char]) -> (usize, bool) {
+    let zeros = int.iter().filter(|v| **v == '0' || **v == '?').count();
+    (zeros, int.contains(&','))
+}
+
+/// Parses a number, scientific number or fraction starting at i.
+fn parse_number(section: &mut Section, s: &[char], mut i: usize) -> usize {
+    let start = i;
+    while i < s.len() && (is_digit_char(s[i]) || s[i] == ',' || s[i] == '.') {
+        i += 1;
+    }
+    let run = &s[start..i];
+
+    // fraction
+    let mut k = i;
+    if k < s.len() && s[k] == ' ' {
+        k += 1;
+    }
+    let num_len = count_digits(s, k);
+    if !run.contains(&'.') && k + num_len < s.len() && s[k + num_len] == '/' && num_len > 0 {
+        let den_start = k + num_len + 1;
+        let den_len = s[den_start..]
+            .iter()
+            .take_while(|v| is_digit_char(**v) || v.is_ascii_digit())
+            .count();
+        if den_len > 0 {
+            let mut part = FormatPart::new(FormatPartType::Fraction);
+            let (zeros, grouping) = integer_digits(run);
+            part.set_attr("number:min-integer-digits", zeros.to_string());
+            if grouping {
+                part.set_attr("number:grouping", "true".to_string());
+            }
+            part.set_attr("number:min-numerator-digits", num_len.to_string());
+            let den: String = s[den_start..den_start + den_len].iter().collect();
+            if den.chars().all(|v| v.is_ascii_digit()) && !den.starts_with('0') {
+                part.set_attr("number:denominator-value", den);
+            } else {
+                part.set_attr("number:min-denominator-digits", den_len.to_string());
+            }
+            section.push_part(part);
+            return den_start + den_len;
+        }
+    }
+    if !run.contains(&'.') && i < s.len() && s[i] == '/' {
+        let den_len = s[i + 1..]
+            .iter()
+            .take_while(|v| is_digit_char(**v) || v.is_ascii_digit())
+            .count();
+        if den_len > 0 {
+            let mut part = FormatPart::new(FormatPartType::Fraction);
+            part.set_attr(
+                "number:min-numerator-digits",
+                count_digits(run, 0).to_string(),
+            );
+            let den: String = s[i + 1..i + 1 + den_len].iter().collect();
+            if den.chars().all(|v| v.is_ascii_digit()) && !den.starts_with('0') {
+                part.set_attr("number:denominator-value", den);
+            } else {
+                part.set_attr("number:min-denominator-digits", den_len.to_string());
+            }
+            section.push_part(part);
+            return i + 1 + den_len;
+        }
+    }
+
+    // trailing commas scale by 1000 each
+    let factor = run.iter().rev().take_while(|v| **v == ',').count() as u32;
+    let run = &run[..run.len() - factor as usize];
+    let (int, frac) = match run.iter().position(|v| *v == '.') {
+        Some(p) => (&run[..p], &run[p + 1..]),
+        None => (run, &run[run.len()..]),
+    };
+    let (zeros, grouping) = integer_digits(int);
+    let decimals = frac.iter().filter(|v| is_digit_char(**v)).count();
+    let min_decimals = frac.iter().filter(|v| **v == '0').count();
+
+    // scientific
+    if i + 1 < s.len()
+        && s[i].eq_ignore_ascii_case(&'e')
+        && (s[i + 1] == '+' || s[i + 1] == '-')
+        && count_digits(s, i + 2) > 0
+    {
+        let mut part = FormatPart::new(FormatPartType::ScientificNumber);
+        part.set_attr("number:min-integer-digits", zeros.to_string());
+        part.set_attr("number:decimal-places", decimals.to_string());
+        part.set_attr("number:min-decimal-places", min_decimals.to_string());
+        if grouping {
+            part.set_attr("number:grouping", "true".to_string());
+        }
+        part.set_attr(
+            "number:min-exponent-digits",
+            count_digits(s, i + 2).to_string(),
+        );
+        part.set_attr("number:forced-exponent-sign", (s[i + 1] == '+').to_string());
+        section.push_part(part);
+        return i + 2 + count_digits(s, i + 2);
+    }
+
+    let mut part = FormatPart::new(FormatPartType::Number);
+    part.set_attr("number:min-integer-digits", zeros.to_string());
+    part.set_attr("number:decimal-places", decimals.to_string());
+    part.set_attr("number:min-decimal-places", min_decimals.to_string());
+    if grouping {
+        part.set_attr("number:grouping", "true".to_string());
+    }
+    if factor > 0 {
+        part.set_attr("number:display-factor", 1000u64.pow(factor).to_string());
+    }
+    section.push_part(part);
+    i
+}
+
+fn count_digits(s: &[char], i: usize) -> usize {
+    s.get(i..)
+        .map(|v| v.iter().take_while(|v| is_digit_char(**v)).count())
+        .unwrap_or(0)
+}
+
+/// Parses the content of a [...] bracket.
+fn parse_bracket(section: &mut Section, content: &str, fmt: &str) -> Result<(), OdsError> {
+    let upper = content.to_ascii_uppercase();
+    if let Some(currency) = content.strip_prefix('$') {
+        let symbol = currency.split('-').next().unwrap_or_default();
+        if !symbol.is_empty() {
+            let mut part = FormatPart::new(FormatPartType::CurrencySymbol);
+            part.set_content(symbol);
+            section.push_part(part);
+        }
+    } else if let Some((_, color)) = COLORS.iter().find(|(name, _)| *name == upper) {
+        section.color = Some(color);
+    } else if upper.starts_with(['H', 'M', 'S']) && upper.chars().all(|c| upper.starts_with(c)) {
+        let ptype = match &upper[..1] {
+            "H" => FormatPartType::Hours,
+            "M" => FormatPartType::Minutes,
+            _ => FormatPartType::Seconds,
+        };
+        let mut part = styled_part(ptype, upper.len() > 1);
+        part.set_attr("number:truncate-on-overflow", "false".to_string());
+        section.push_part(part);
+    } else if upper.starts_with(['<', '>', '=']) {
+        let (op, value) = if let Some(v) = upper.strip_prefix("<=") {
+            ("<=", v)
+        } else if let Some(v) = upper.strip_prefix(">=") {
+            (">=", v)
+        } else if let Some(v) = upper.strip_prefix("<>") {
+            ("!=", v)
+        } else {
+            upper.split_at(1)
+        };
+        let value: f64 = value
+            .trim()
+            .parse()
+            .map_err(|_| err("invalid condition in format string", fmt))?;
+        section.condition = Some(match op {
+            "<" => ValueCondition::value_lt(value),
+            ">" => ValueCondition::value_gt(value),
+            "<=" => ValueCondition::value_le(value),
+            ">=" => ValueCondition::value_ge(value),
+            "!=" => ValueCondition::value_ne(value),
+            _ => ValueCondition::value_eq(value),
+        });
+    } else {
+        return Err(err("unknown bracket in format string", fmt));
+    }
+    Ok(())
+}
+
+/// Parses one section of a format code.
+fn parse_section(s: &[char], fmt: &str) -> Result<Section, OdsError> {
+    let mut section = Section::default();
+
+    let mut i = 0;
+    while i < s.len() {
+        let c = s[i];
+        match c {
+            '"' => {
+                let end = s[i + 1..]
+                    .iter()
+                    .position(|v| *v == '"')
+                    .ok_or_else(|| err("unterminated string in format string", fmt))?;
+                for v in &s[i + 1..i + 1 + end] {
+                    section.push_text(*v);
+                }
+                i += end + 2;
+            }
+            '\\' => {
+                if let Some(v) = s.get(i + 1) {
+                    section.push_text(*v);
+                }
+                i += 2;
+            }
+            '_' => {
+                section.push_text(' ');
+                i += 2;
+            }
+            '*' => {
+                let mut part = FormatPart::new(FormatPartType::FillCharacter);
+                part.set_content(s.get(i + 1).map(|v| v.to_string()).unwrap_or_default());
+                section.push_part(part);
+                i += 2;
+            }
+            '[' => {
+                let end = s[i + 1..]
+                    .iter()
+                    .position(|v| *v == ']')
+                    .ok_or_else(|| err("unterminated bracket in format string", fmt))?;
+                let content: String = s[i + 1..i + 1 + end].iter().collect();
+                parse_bracket(&mut section, &content, fmt)?;
+                i += end + 2;
+            }
+            '@' => {
+                section.push_part(FormatPart::new(FormatPartType::TextContent));
+                i += 1;
+            }
+            '#' | '0' | '?' => {
+                i = parse_number(&mut section, s, i);
+            }
+            '.' if count_digits(s, i + 1) > 0 => {
+                i = parse_number(&mut section, s, i);
+            }
+            _ if starts_with_ci(s, i, "GENERAL") || starts_with_ci(s, i, "STANDARD") => {
+                let mut part = FormatPart::new(FormatPartType::Number);
+                part.set_attr("number:min-integer-digits", "1".to_string());
+                section.push_part(part);
+                i += if starts_with_ci(s, i, "GENERAL") {
+                    7
+                } else {
+                    8
+                };
+            }
+            _ if starts_with_ci(s, i, "BOOLEAN") => {
+                section.push_part(FormatPart::new(FormatPartType::Boolean));
+                i += 7;
+            }
+            _ if starts_with_ci(s, i, "AM/PM") => {
+                section.push_part(FormatPart::new(FormatPartType::AmPm));
+                i += 5;
+            }
+            _ if starts_with_ci(s, i, "A/P") => {
+                section.push_part(FormatPart::new(FormatPartType::AmPm));
+                i += 3;
+            }
+            'y' | 'Y' | 'e' | 'E' => {
+                let n = count_run(s, i, c);
+                section.push_part(styled_part(FormatPartType::Year, n > 2));
+                i += n;
+            }
+            'd' | 'D' => {
+                let n = count_run(s, i, c);
+                section.push_part(match n {
+                    1 | 2 => styled_part(FormatPartType::Day, n == 2),
+                    _ => styled_part(FormatPartType::DayOfWeek, n > 3),
+                });
+                i += n;
+            }
+            'n' | 'N' => {
+                let n = count_run(s, i, c);
+                section.push_part(styled_part(FormatPartType::DayOfWeek, n > 2));
+                i += n;
+            }
+            'm' | 'M' => {
+                let n = count_run(s, i, c);
+                if n <= 2 && is_minute(&section, s, i + n) {
+                    section.push_part(styled_part(FormatPartType::Minutes, n == 2));
+                } else {
+                    let mut part = styled_part(FormatPartType::Month, n == 2 || n > 3);
+                    if n > 2 {
+                        part.set_attr("number:textual", "true".to_string());
+                    }
+                    section.push_part(part);
+                }
+                i += n;
+            }
+            'h' | 'H' => {
+                let n = count_run(s, i, c);
+                section.push_part(styled_part(FormatPartType::Hours, n > 1));
+                i += n;
+            }
+            's' | 'S' => {
+                let n = count_run(s, i, c);
+                let mut part = styled_part(FormatPartType::Seconds, n > 1);
+                i += n;
+                if i < s.len() && s[i] == '.' && count_digits(s, i + 1) > 0 {
+                    let decimals = count_digits(s, i + 1);
+                    part.set_attr("number:decimal-places", decimals.to_string());
+                    i += decimals + 1;
+                }
+                section.push_part(part);
+            }
+            'q' | 'Q' => {
+                let n = count_run(s, i, c);
+                section.push_part(styled_part(FormatPartType::Quarter, n > 1));
+                i += n;
+            }
+            'w' | 'W' => {
+                let n = count_run(s, i, c);
+                section.push_part(FormatPart::new(FormatPartType::WeekOfYear));
+                i += n;
+            }
+            'g' | 'G' => {
+                let n = count_run(s, i, c);
+                section.push_part(styled_part(FormatPartType::Era, n > 2));
+                i += n;
+            }
+            _ if c.is_alphabetic() => {
+                return Err(err("unknown token in format string", fmt));
+            }
+            _ => {
+                section.push_text(c);
+                i += 1;
+            }
+        }
+    }
+    section.flush_text();
+
+    Ok(section)
+}
+
+/// Parses a format code into a value format. See ValueFormatXX::from_format_string.
+pub(crate) fn parse_format_string<T, F>(
+    name: &str,
+    fmt: &str,
+    new: F,
+) -> Result<(T, Vec<T>), OdsError>
+where
+    T: ValueFormatTrait,
+    F: Fn(&str) -> T,
+{
+    let mut sections = split_sections(fmt)
+        .iter()
+        .map(|v| parse_section(v, fmt))
+        .collect::<Result<Vec<_>, _>>()?;
+    // A fourth section is for text values, which can't be expressed here.
+    sections.truncate(3);
+
+    let mut sub_formats = Vec::new();
+    let n = sections.len();
+    let mut main = None;
+    for (idx, section) in sections.into_iter().enumerate() {
+        let is_main = idx + 1 == n;
+        let mut vf = if is_main {
+            new(name)
+        } else {
+            new(&format!("{}P{}", name, idx))
+        };
+        if let Some(color) = section.color {
+            vf.textstyle_mut().set_attr("fo:color", color.to_string());
+        }
+        for part in section.parts {
+            vf.push_part(part);
+        }
+        if is_main {
+            main = Some(vf);
+        } else {
+            let condition = match section.condition {
+                Some(condition) => condition,
+                None if n == 2 => ValueCondition::value_ge(0),
+                None if idx == 0 => ValueCondition::value_gt(0),
+                None => ValueCondition::value_lt(0),
+            };
+            sub_formats.push((vf, condition));
+        }
+    }
+
+    let mut main = main.expect("at least one section");
+    for (vf, condition) in &sub_formats {
+        main.push_stylemap(ValueStyleMap::new(condition.clone(), vf.name()));
+    }
+
+    Ok((main, sub_formats.into_iter().map(|(v, _)| v).collect()))
+}
+
+fn attr_u32(part: &FormatPart, name: &str) -> Option<u32> {
+    part.attr_def(name, "").parse().ok()
+}
+
+fn is_long(part: &FormatPart) -> bool {
+    part.attr_def("number:style", "") == "long"
+}
+
+fn elapsed(part: &FormatPart, s: &str) -> String {
+    if part.attr_def("number:truncate-on-overflow", "") == "false" {
+        format!("[{}]", s)
+    } else {
+        s.to_string()
+    }
+}
+
+/// Integer part of a number pattern.
+fn integer_string(part: &FormatPart) -> String {
+    let zeros = attr_u32(part, "number:min-integer-digits").unwrap_or(0) as usize;
+    if part.attr_def("number:grouping", "") == "true" {
+        let width = zeros.max(4);
+        let mut buf = String::new();
+        for i in 0..width {
+            if i == width - 3 {
+                buf.push(',');
+            }
+            buf.push(if i < width - zeros { '#' } else { '0' });
+        }
+        buf
+    } else if zeros == 0 {
+        "#".to_string()
+    } else {
+        "0".repeat(zeros)
+    }
+}
+
+/// Decimal part of a number pattern.
+fn decimal_string(part: &FormatPart) -> String {
+    let decimals = attr_u32(part, "number:decimal-places").unwrap_or(0);
+    let min_decimals = attr_u32(part, "number:min-decimal-places")
+        .unwrap_or(decimals)
+        .min(decimals);
+    if decimals > 0 {
+        format!(
+            ".{}{}",
+            "0".repeat(min_decimals as usize),
+            "#".repeat((decimals - min_decimals) as usize)
+        )
+    } else {
+        String::new()
+    }
+}
+
+fn text_string(content: &str, value_type: ValueType) -> String {
+    let unquoted = |v: char| match value_type {
+        ValueType::Percentage => LITERALS.contains(v) || v == '%',
+        ValueType::DateTime | ValueType::TimeDuration => {
+            LITERALS.contains(v) || v == '.' || v == ','
+        }
+        _ => LITERALS.contains(v),
+    };
+    if content.chars().all(unquoted) {
+        content.to_string()
+    } else if content.contains('"') {
+        content.chars().map(|v| format!("\\{}", v)).collect()
+    } else {
+        format!("\"{}\"", content)
+    }
+}
+
+fn part_string(part: &FormatPart, value_type: ValueType) -> String {
+    match part.part_type() {
+        FormatPartType::Number => {
+            let mut buf = integer_string(part);
+            buf.push_str(&decimal_string(part));
+            if let Some(factor) = part
+                .attr_def("number:display-factor", "")
+                .parse::<f64>()
+                .ok()
+                .filter(|v| *v >= 1000.0)
+            {
+                buf.push_str(&",".repeat(factor.log(1000.0).round() as usize));
+            }
+            buf
+        }
+        FormatPartType::ScientificNumber => {
+            let mut buf = integer_string(part);
+            buf.push_str(&decimal_string(part));
+            buf.push('E');
+            buf.push(
+                if part.attr_def("number:forced-exponent-sign", "true") == "false" {
+                    '-'
+                } else {
+                    '+'
+                },
+            );
+            let exp = attr_u32(part, "number:min-exponent-digits").unwrap_or(1);
+            buf.push_str(&"0".repeat(exp as usize));
+            buf
+        }
+        FormatPartType::Fraction => {
+            let mut buf = String::new();
+            if attr_u32(part, "number:min-integer-digits").is_some() {
+                buf.push_str(&integer_string(part));
+                buf.push(' ');
+            }
+            let num = attr_u32(part, "number:min-numerator-digits").unwrap_or(1);
+            buf.push_str(&"?".repeat(num as usize));
+            buf.push('/');
+            match attr_u32(part, "number:denominator-value") {
+                Some(den) => buf.push_str(&den.to_string()),
+                None => {
+                    let den = attr_u32(part, "number:min-denominator-digits").unwrap_or(1);
+                    buf.push_str(&"?".repeat(den as usize));
+                }
+            }
+            buf
+        }
+        FormatPartType::FillCharacter => {
+            format!("*{}", part.content().map(|v| v.as_str()).unwrap_or(" "))
+        }
+        FormatPartType::CurrencySymbol => {
+            format!("[${}]", part.content().map(|v| v.as_str()).unwrap_or(""))
+        }
+        FormatPartType::Day => if is_long(part) { "DD" } else { "D" }.to_string(),
+        FormatPartType::Month => {
+            let textual = part.attr_def("number:textual", "") == "true";
+            match (textual, is_long(part)) {
+                (true, true) => "MMMM",
+                (true, false) => "MMM",
+                (false, true) => "MM",
+                (false, false) => "M",
+            }
+            .to_string()
+        }
+        FormatPartType::Year => if is_long(part) { "YYYY" } else { "YY" }.to_string(),
+        FormatPartType::Era => if is_long(part) { "GGG" } else { "G" }.to_string(),
+        FormatPartType::DayOfWeek => if is_long(part) { "DDDD" } else { "DDD" }.to_string(),
+        FormatPartType::WeekOfYear => "WW".to_string(),
+        FormatPartType::Quarter => if is_long(part) { "QQ" } else { "Q" }.to_string(),
+        FormatPartType::Hours => elapsed(part, if is_long(part) { "HH" } else { "H" }),
+        FormatPartType::Minutes => elapsed(part, if is_long(part) { "MM" } else { "M" }),
+        FormatPartType::Seconds => {
+            let mut buf = elapsed(part, if is_long(part) { "SS" } else { "S" });
+            if let Some(decimals) = attr_u32(part, "number:decimal-places").filter(|v| *v > 0) {
+                buf.push('.');
+                buf.push_str(&"0".repeat(decimals as usize));
+            }
+            buf
+        }
+        FormatPartType::AmPm => "AM/PM".to_string(),
+        FormatPartType::Boolean => "BOOLEAN".to_string(),
+        FormatPartType::Text => {
+            text_string(part.content().map(|v| v.as_str()).unwrap_or(""), value_type)
+        }
+        FormatPartType::TextContent => "@".to_string(),
+    }
+}
+
+/// Format code for the parts of one value format.
+pub(crate) fn format_string<T: ValueFormatTrait>(vf: &T) -> String {
+    let mut buf = String::new();
+    if let Some(color) = vf.textstyle().attr("fo:color") {
+        if let Some((name, _)) = COLORS.iter().find(|(_, v)| v.eq_ignore_ascii_case(color)) {
+            buf.push('[');
+            buf.push_str(name);
+            buf.push(']');
+        }
+    }
+    for part in vf.parts() {
+        buf.push_str(&part_string(part, vf.value_type()));
+    }
+    buf
+}
+
+/// Format code for a value format including the sub-formats of its stylemaps.
+pub(crate) fn format_string_with<'a, T, F>(vf: &T, lookup: F) -> String
+where
+    T: ValueFormatTrait + 'a,
+    F: Fn(&str) -> Option<&'a T>,
+{
+    let Some(stylemaps) = vf.stylemaps() else {
+        return format_string(vf);
+    };
+
+    let conditions: Vec<String> = stylemaps
+        .iter()
+        .map(|v| v.condition().to_string())
+        .collect();
+    let implicit = conditions == ["value()>=0"] || conditions == ["value()>0", "value()<0"];
+
+    let mut buf = String::new();
+    for stylemap in stylemaps {
+        if !implicit {
+            let cond = stylemap.condition().to_string();
+            let cond = cond.trim_start_matches("value()").replace("!=", "<>");
+            buf.push('[');
+            buf.push_str(&cond);
+            buf.push(']');
+        }
+        if let Some(sub) = lookup(stylemap.applied_style()) {
+            buf.push_str(&format_string(sub));
+        }
+        buf.push(';');
+    }
+    buf.push_str(&format_string(vf));
+    buf
+}
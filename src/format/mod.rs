@@ -36,10 +36,16 @@
 
 mod builder;
 mod create;
+mod currency;
+mod display;
+mod formatstring;
 mod stylemap;
 
 pub use builder::*;
 pub use create::*;
+pub use currency::*;
+pub(crate) use display::{format_value, format_value_with};
+pub(crate) use formatstring::{format_string, format_string_with, parse_format_string};
 pub use stylemap::*;
 
 use crate::attrmap2::AttrMap2;
@@ -55,7 +61,7 @@ use crate::style::ParseStyleAttr;
 use crate::style::{
     color_string, shadow_string, text_position, StyleOrigin, StyleUse, TextStyleRef,
 };
-use crate::{OdsError, ValueType};
+use crate::{OdsError, Value, ValueType};
 use core::borrow::Borrow;
 use get_size2::GetSize;
 use icu_locid::subtags::{Language, Region, Script};
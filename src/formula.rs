@@ -2,7 +2,13 @@
 //! For now defines functions to create cell references for formulas.
 //!
 
-use crate::refs::{CellRange, CellRef};
+use crate::refs::{
+    fmt_col_name, fmt_row_name, fmt_table_name, parse_cellrange, parse_cellref, parse_colrange,
+    parse_rowrange, CellRange, CellRef,
+};
+use crate::workbook_::{MAX_COLS, MAX_ROWS};
+use crate::HashMap;
+use std::cmp::min;
 
 /// Simple macro for formula.
 #[macro_export]
@@ -520,3 +526,896 @@ pub fn frangerefa_table<S: Into<String>>(
         .absolute()
         .to_formula()
 }
+
+/// Replaces the table names in the cell references of a formula.
+/// External references are not changed.
+pub(crate) fn rename_table_refs(formula: &str, renamed: &HashMap<String, String>) -> String {
+    let s = formula.chars().collect::<Vec<_>>();
+    let mut buf = String::with_capacity(formula.len());
+
+    let mut in_string = false;
+    let mut in_ref = false;
+    // At the start of a reference, where a table name can occur.
+    let mut at_start = false;
+    let mut i = 0;
+    while i < s.len() {
+        let c = s[i];
+        if in_string {
+            if c == '"' {
+                in_string = false;
+            }
+        } else if !in_ref {
+            match c {
+                '"' => in_string = true,
+                '[' => {
+                    in_ref = true;
+                    at_start = true;
+                }
+                _ => {}
+            }
+        } else if at_start {
+            at_start = false;
+
+            let abs = c == '$';
+            let j = if abs { i + 1 } else { i };
+            let (name, end) = if s.get(j) == Some(&'\'') {
+                let mut name = String::new();
+                let mut k = j + 1;
+                loop {
+                    match s.get(k) {
+                        None => break (None, k),
+                        Some('\'') if s.get(k + 1) == Some(&'\'') => {
+                            name.push('\'');
+                            k += 2;
+                        }
+                        Some('\'') => break (Some(name), k + 1),
+                        Some(c) => {
+                            name.push(*c);
+                            k += 1;
+                        }
+                    }
+                }
+            } else {
+                let mut k = j;
+                while k < s.len() && !matches!(s[k], '.' | ':' | ']') {
+                    k += 1;
+                }
+                (Some(s[j..k].iter().collect::<String>()), k)
+            };
+
+            let new_name = name
+                .filter(|_| s.get(end) == Some(&'.'))
+                .and_then(|v| renamed.get(&v));
+            if let Some(new_name) = new_name {
+                let _ = fmt_table_name(&mut buf, new_name, abs);
+            } else {
+                buf.extend(&s[i..end]);
+            }
+            i = end;
+            continue;
+        } else {
+            match c {
+                ']' => in_ref = false,
+                ':' => at_start = true,
+                _ => {}
+            }
+        }
+        buf.push(c);
+        i += 1;
+    }
+
+    buf
+}
+
+/// Returns the targets of all references to external files in a formula.
+/// This includes the topic of calls to DDE.
+///
+/// ```
+/// use spreadsheet_ods::formula::external_refs;
+///
+/// assert_eq!(
+///     external_refs("of:=['file:///tmp/a.ods'#$Sheet1.A1]*2"),
+///     vec!["file:///tmp/a.ods".to_string()]
+/// );
+/// assert_eq!(
+///     external_refs("of:=DDE(\"soffice\";\"file:///tmp/b.ods\";\"Sheet1.A1\")"),
+///     vec!["file:///tmp/b.ods".to_string()]
+/// );
+/// ```
+pub fn external_refs(formula: &str) -> Vec<String> {
+    let mut refs = Vec::new();
+    rewrite_external_refs(formula, |v| {
+        refs.push(v.to_string());
+        None
+    });
+    refs
+}
+
+/// Replaces the targets of references to external files in a formula,
+/// and the topic of calls to DDE.
+///
+/// The function is called with each target and returns the new target,
+/// or None to keep it.
+///
+/// ```
+/// use spreadsheet_ods::formula::rewrite_external_refs;
+///
+/// let f = rewrite_external_refs("of:=['file:///old/a.ods'#$Sheet1.A1]", |v| {
+///     v.strip_prefix("file:///old/")
+///         .map(|v| format!("file:///new/{}", v))
+/// });
+/// assert_eq!(f, "of:=['file:///new/a.ods'#$Sheet1.A1]");
+/// ```
+pub fn rewrite_external_refs<F>(formula: &str, mut f: F) -> String
+where
+    F: FnMut(&str) -> Option<String>,
+{
+    let s = formula.chars().collect::<Vec<_>>();
+    let mut buf = String::with_capacity(formula.len());
+
+    let mut in_string = false;
+    let mut i = 0;
+    while i < s.len() {
+        let c = s[i];
+        if in_string {
+            if c == '"' {
+                in_string = false;
+            }
+            buf.push(c);
+            i += 1;
+        } else if c == '"' {
+            in_string = true;
+            buf.push(c);
+            i += 1;
+        } else if c == '[' {
+            buf.push(c);
+            i += 1;
+
+            // 'iri'#
+            if s.get(i) == Some(&'\'') {
+                let mut iri = String::new();
+                let mut k = i + 1;
+                let closed = loop {
+                    match s.get(k) {
+                        None => break false,
+                        Some('\'') if s.get(k + 1) == Some(&'\'') => {
+                            iri.push('\'');
+                            k += 2;
+                        }
+                        Some('\'') => break true,
+                        Some(c) => {
+                            iri.push(*c);
+                            k += 1;
+                        }
+                    }
+                };
+                if closed && s.get(k + 1) == Some(&'#') {
+                    match f(&iri) {
+                        Some(new_iri) => {
+                            buf.push('\'');
+                            buf.push_str(&new_iri.replace('\'', "''"));
+                            buf.push('\'');
+                        }
+                        None => buf.extend(&s[i..=k]),
+                    }
+                    i = k + 1;
+                }
+            }
+
+            // copy the rest of the reference.
+            let end = min(ref_end(&s, i) + 1, s.len());
+            buf.extend(&s[i..end]);
+            i = end;
+        } else if let Some((topic, start, end)) = dde_topic(&s, i) {
+            // DDE(server; topic; item), the topic is the file.
+            buf.extend(&s[i..start]);
+            match f(&topic) {
+                Some(new_topic) => {
+                    buf.push('"');
+                    buf.push_str(&new_topic.replace('"', "\"\""));
+                    buf.push('"');
+                }
+                None => buf.extend(&s[start..end]),
+            }
+            i = end;
+        } else {
+            buf.push(c);
+            i += 1;
+        }
+    }
+
+    buf
+}
+
+/// Finds the topic of a call to DDE at i. The topic must be a string.
+/// Returns the topic and the start and end of the string literal.
+fn dde_topic(s: &[char], i: usize) -> Option<(String, usize, usize)> {
+    if i > 0 && is_ident(s[i - 1]) {
+        return None;
+    }
+    let name = s.get(i..i + 4)?.iter().collect::<String>();
+    if !name.eq_ignore_ascii_case("DDE(") {
+        return None;
+    }
+    let skip_ws = |mut k: usize| {
+        while s.get(k).is_some_and(|c| c.is_whitespace()) {
+            k += 1;
+        }
+        k
+    };
+    let (_, k) = parse_string(s, skip_ws(i + 4))?;
+    let k = skip_ws(k);
+    if s.get(k) != Some(&';') {
+        return None;
+    }
+    let start = skip_ws(k + 1);
+    let (topic, end) = parse_string(s, start)?;
+    Some((topic, start, end))
+}
+
+/// Parses a string literal at i. Returns the unquoted string and the
+/// position after the closing quote.
+fn parse_string(s: &[char], i: usize) -> Option<(String, usize)> {
+    if s.get(i) != Some(&'"') {
+        return None;
+    }
+    let mut text = String::new();
+    let mut k = i + 1;
+    loop {
+        match s.get(k) {
+            None => return None,
+            Some('"') if s.get(k + 1) == Some(&'"') => {
+                text.push('"');
+                k += 2;
+            }
+            Some('"') => return Some((text, k + 1)),
+            Some(c) => {
+                text.push(*c);
+                k += 1;
+            }
+        }
+    }
+}
+
+/// Converts an OpenFormula `of:=` formula to the plain form without
+/// namespace prefix that Google Sheets expects on import.
+///
+/// Cell references lose their brackets and use `!` as table separator,
+/// the parameter separator `;` becomes `,`. In inline arrays the column
+/// separator `;` becomes `,` and the row separator `|` becomes `;`.
+///
+/// ```
+/// use spreadsheet_ods::formula::formula_to_plain;
+///
+/// assert_eq!(formula_to_plain("of:=SUM([.A1:.B2];[$Sheet2.C3])"), "=SUM(A1:B2,Sheet2!C3)");
+/// assert_eq!(formula_to_plain("of:=SUM({1;2|3;4})"), "=SUM({1,2;3,4})");
+/// ```
+///
+/// Formulas with another namespace prefix are not changed.
+/// References to external files are left as they are.
+pub fn formula_to_plain(formula: &str) -> String {
+    let Some(formula) = formula.strip_prefix("of:") else {
+        return formula.to_string();
+    };
+
+    let s = formula.chars().collect::<Vec<_>>();
+    let mut buf = String::with_capacity(formula.len());
+
+    let mut in_string = false;
+    let mut in_array = false;
+    let mut i = 0;
+    while i < s.len() {
+        let c = s[i];
+        if in_string {
+            if c == '"' {
+                in_string = false;
+            }
+            buf.push(c);
+            i += 1;
+        } else {
+            match c {
+                '"' => {
+                    in_string = true;
+                    buf.push(c);
+                    i += 1;
+                }
+                '{' | '}' => {
+                    in_array = c == '{';
+                    buf.push(c);
+                    i += 1;
+                }
+                ';' => {
+                    buf.push(',');
+                    i += 1;
+                }
+                '|' if in_array => {
+                    buf.push(';');
+                    i += 1;
+                }
+                '[' => {
+                    let end = ref_end(&s, i + 1);
+                    let reference = s[i + 1..end].iter().collect::<String>();
+                    match plain_ref(&reference) {
+                        Some(plain) => buf.push_str(&plain),
+                        None => buf.extend(&s[i..min(end + 1, s.len())]),
+                    }
+                    i = end + 1;
+                }
+                _ => {
+                    buf.push(c);
+                    i += 1;
+                }
+            }
+        }
+    }
+
+    buf
+}
+
+/// Position of the closing bracket of a reference.
+fn ref_end(s: &[char], mut i: usize) -> usize {
+    let mut in_quote = false;
+    while i < s.len() {
+        match s[i] {
+            '\'' => in_quote = !in_quote,
+            ']' if !in_quote => break,
+            _ => {}
+        }
+        i += 1;
+    }
+    i
+}
+
+/// Splits at the given separator, outside of quotes.
+fn split_unquoted(s: &str, sep: char) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut in_quote = false;
+    let mut start = 0;
+    for (i, c) in s.char_indices() {
+        if c == '\'' {
+            in_quote = !in_quote;
+        } else if c == sep && !in_quote {
+            parts.push(&s[start..i]);
+            start = i + 1;
+        }
+    }
+    parts.push(&s[start..]);
+    parts
+}
+
+/// Converts the content of a bracketed reference.
+fn plain_ref(reference: &str) -> Option<String> {
+    let mut buf = String::new();
+    let mut last_table = None;
+    for (n, part) in split_unquoted(reference, ':').into_iter().enumerate() {
+        let (table, cell) = match split_unquoted(part, '.').as_slice() {
+            [table, cell] => (*table, *cell),
+            _ => return None,
+        };
+        // external reference
+        if table.contains('#') {
+            return None;
+        }
+        let table = table.trim_start_matches('$');
+        if n > 0 {
+            buf.push(':');
+        }
+        if !table.is_empty() && Some(table) != last_table {
+            if table.starts_with('\'') || table.chars().all(|c| c.is_alphanumeric() || c == '_') {
+                buf.push_str(table);
+            } else {
+                buf.push('\'');
+                buf.push_str(&table.replace('\'', "''"));
+                buf.push('\'');
+            }
+            buf.push('!');
+        }
+        if n == 0 {
+            last_table = Some(table);
+        }
+        buf.push_str(cell);
+    }
+    Some(buf)
+}
+
+/// Converts a formula in the plain form as written by Google Sheets
+/// to OpenFormula. This reverts [formula_to_plain].
+///
+/// ```
+/// use spreadsheet_ods::formula::formula_from_plain;
+///
+/// assert_eq!(formula_from_plain("=SUM(A1:B2,Sheet2!C3)"), "of:=SUM([.A1:.B2];[Sheet2.C3])");
+/// assert_eq!(formula_from_plain("=SUM({1,2;3,4})"), "of:=SUM({1;2|3;4})");
+/// ```
+///
+/// Formulas with a namespace prefix are not changed.
+pub fn formula_from_plain(formula: &str) -> String {
+    if !formula.starts_with('=') {
+        return formula.to_string();
+    }
+
+    let s = formula.chars().collect::<Vec<_>>();
+    let mut buf = String::with_capacity(formula.len() + 8);
+    buf.push_str("of:");
+
+    let mut in_string = false;
+    let mut in_array = false;
+    let mut i = 0;
+    while i < s.len() {
+        let c = s[i];
+        if in_string {
+            if c == '"' {
+                in_string = false;
+            }
+            buf.push(c);
+            i += 1;
+            continue;
+        }
+
+        let prev_ident = i > 0 && is_ident(s[i - 1]);
+        if !prev_ident && (c.is_alphanumeric() || c == '$' || c == '\'') {
+            if let Some(end) = parse_plain_range(&s, i, &mut buf) {
+                i = end;
+                continue;
+            }
+        }
+
+        match c {
+            '"' => {
+                in_string = true;
+                buf.push(c);
+            }
+            '{' | '}' => {
+                in_array = c == '{';
+                buf.push(c);
+            }
+            ',' => buf.push(';'),
+            ';' if in_array => buf.push('|'),
+            c if c.is_alphanumeric() || c == '_' => {
+                // copy the whole identifier or number.
+                while i < s.len() && is_ident(s[i]) {
+                    buf.push(s[i]);
+                    i += 1;
+                }
+                continue;
+            }
+            _ => buf.push(c),
+        }
+        i += 1;
+    }
+
+    buf
+}
+
+fn is_ident(c: char) -> bool {
+    c.is_alphanumeric() || matches!(c, '_' | '.' | '$')
+}
+
+/// Parses an optional table name followed by `!`.
+fn parse_plain_table(s: &[char], i: usize) -> Option<(String, usize)> {
+    let mut name = String::new();
+    let mut k = i;
+    if s.get(k) == Some(&'\'') {
+        k += 1;
+        loop {
+            match s.get(k) {
+                None => return None,
+                Some('\'') if s.get(k + 1) == Some(&'\'') => {
+                    name.push('\'');
+                    k += 2;
+                }
+                Some('\'') => {
+                    k += 1;
+                    break;
+                }
+                Some(c) => {
+                    name.push(*c);
+                    k += 1;
+                }
+            }
+        }
+    } else {
+        while k < s.len() && (s[k].is_alphanumeric() || matches!(s[k], '_' | '.')) {
+            name.push(s[k]);
+            k += 1;
+        }
+    }
+    if s.get(k) == Some(&'!') && !name.is_empty() {
+        Some((name, k + 1))
+    } else {
+        None
+    }
+}
+
+/// Parses `$A$1`, `$A` or `$1`. Returns the end and what was found.
+fn parse_plain_cell(s: &[char], mut i: usize) -> Option<(usize, bool, bool)> {
+    let start = i;
+    if s.get(i) == Some(&'$') {
+        i += 1;
+    }
+    let col_start = i;
+    while i < s.len() && s[i].is_ascii_alphabetic() {
+        i += 1;
+    }
+    let has_col = i > col_start && i - col_start <= 3;
+    if i > col_start && !has_col {
+        return None;
+    }
+    let row_start = if s.get(i) == Some(&'$') { i + 1 } else { i };
+    let mut k = row_start;
+    while k < s.len() && s[k].is_ascii_digit() {
+        k += 1;
+    }
+    let has_row = k > row_start;
+    if has_row {
+        i = k;
+    } else if !has_col || s.get(i) == Some(&'$') {
+        return None;
+    }
+    if i == start {
+        return None;
+    }
+    Some((i, has_col, has_row))
+}
+
+/// Parses a reference at i and writes it in OpenFormula syntax.
+fn parse_plain_range(s: &[char], i: usize, buf: &mut String) -> Option<usize> {
+    let (table, i0) = match parse_plain_table(s, i) {
+        Some((table, k)) => (Some(table), k),
+        None => (None, i),
+    };
+    let (end0, col0, row0) = parse_plain_cell(s, i0)?;
+
+    let mut second = None;
+    if s.get(end0) == Some(&':') {
+        let (table1, i1) = match parse_plain_table(s, end0 + 1) {
+            Some((table, k)) => (Some(table), k),
+            None => (None, end0 + 1),
+        };
+        if let Some((end1, col1, row1)) = parse_plain_cell(s, i1) {
+            if col0 == col1 && row0 == row1 {
+                second = Some((table1, i1, end1));
+            }
+        }
+    }
+
+    let end = second.as_ref().map(|v| v.2).unwrap_or(end0);
+    // Must not continue as an identifier or be a function call.
+    if s.get(end).copied().map(is_ident) == Some(true) || s.get(end) == Some(&'(') {
+        return None;
+    }
+    // Single columns or rows are names or numbers.
+    if second.is_none() && !(col0 && row0) {
+        return None;
+    }
+
+    buf.push('[');
+    if let Some(table) = &table {
+        let _ = fmt_table_name(buf, table, false);
+    }
+    buf.push('.');
+    buf.extend(&s[i0..end0]);
+    if let Some((table1, i1, end1)) = second {
+        buf.push(':');
+        if let Some(table1) = &table1 {
+            let _ = fmt_table_name(buf, table1, false);
+        }
+        buf.push('.');
+        buf.extend(&s[i1..end1]);
+    }
+    buf.push(']');
+
+    Some(end)
+}
+
+/// Replaces the names in a formula with the cell ranges they stand for.
+///
+/// The function is called for each name that is not a function and
+/// returns the range or None to keep the name. Names are identifiers
+/// outside of strings and references. See
+/// [WorkBook::expand_range_names](crate::WorkBook::expand_range_names)
+/// for the named ranges of a workbook.
+///
+/// ```
+/// use spreadsheet_ods::formula::expand_range_names;
+/// use spreadsheet_ods::CellRange;
+///
+/// let f = expand_range_names("of:=SUM(Sales)*Rate", |v| {
+///     (v == "Sales").then(|| CellRange::remote("Data", 0, 0, 9, 0).absolute())
+/// });
+/// assert_eq!(f, "of:=SUM([$Data.$A$1:.$A$10])*Rate");
+/// ```
+pub fn expand_range_names<F>(formula: &str, mut f: F) -> String
+where
+    F: FnMut(&str) -> Option<CellRange>,
+{
+    let s = formula.chars().collect::<Vec<_>>();
+    let mut buf = String::with_capacity(formula.len());
+
+    // namespace prefix
+    let mut i = match formula.find('=') {
+        Some(idx) => {
+            buf.push_str(&formula[..=idx]);
+            formula[..=idx].chars().count()
+        }
+        None => 0,
+    };
+
+    let mut in_string = false;
+    while i < s.len() {
+        let c = s[i];
+        if in_string {
+            if c == '"' {
+                in_string = false;
+            }
+            buf.push(c);
+            i += 1;
+        } else if c == '"' {
+            in_string = true;
+            buf.push(c);
+            i += 1;
+        } else if c == '[' {
+            let end = min(ref_end(&s, i + 1) + 1, s.len());
+            buf.extend(&s[i..end]);
+            i = end;
+        } else if (c.is_alphabetic() || c == '_') && !(i > 0 && is_ident(s[i - 1])) {
+            let mut end = i;
+            while end < s.len() && (s[end].is_alphanumeric() || matches!(s[end], '_' | '.')) {
+                end += 1;
+            }
+            let mut next = end;
+            while next < s.len() && s[next].is_whitespace() {
+                next += 1;
+            }
+            let name = s[i..end].iter().collect::<String>();
+            match f(&name) {
+                Some(range) if s.get(next) != Some(&'(') => buf.push_str(&range.to_formula()),
+                _ => buf.push_str(&name),
+            }
+            i = end;
+        } else {
+            buf.push(c);
+            i += 1;
+        }
+    }
+
+    buf
+}
+
+/// Replaces the cell references in a formula with names.
+///
+/// The function is called for each reference that can be read as cell
+/// range and returns the name or None to keep the reference. A single
+/// cell is given as a range with one cell. References to external files
+/// are never replaced.
+///
+/// ```
+/// use spreadsheet_ods::formula::collapse_range_names;
+/// use spreadsheet_ods::CellRange;
+///
+/// let sales = CellRange::remote("Data", 0, 0, 9, 0).absolute();
+/// let f = collapse_range_names("of:=SUM([$Data.$A$1:.$A$10])", |v| {
+///     (v == &sales).then(|| "Sales".to_string())
+/// });
+/// assert_eq!(f, "of:=SUM(Sales)");
+/// ```
+pub fn collapse_range_names<F>(formula: &str, mut f: F) -> String
+where
+    F: FnMut(&CellRange) -> Option<String>,
+{
+    let s = formula.chars().collect::<Vec<_>>();
+    let mut buf = String::with_capacity(formula.len());
+
+    let mut in_string = false;
+    let mut i = 0;
+    while i < s.len() {
+        let c = s[i];
+        if in_string {
+            if c == '"' {
+                in_string = false;
+            }
+            buf.push(c);
+            i += 1;
+        } else if c == '"' {
+            in_string = true;
+            buf.push(c);
+            i += 1;
+        } else if c == '[' {
+            let end = ref_end(&s, i + 1);
+            let reference = s[i + 1..end].iter().collect::<String>();
+            let name = CellRange::parse(&reference)
+                .ok()
+                .filter(|v| v.iri().is_none())
+                .and_then(|v| f(&v));
+            match name {
+                Some(name) => buf.push_str(&name),
+                None => buf.extend(&s[i..min(end + 1, s.len())]),
+            }
+            i = end + 1;
+        } else {
+            buf.push(c);
+            i += 1;
+        }
+    }
+
+    buf
+}
+
+/// Moves the relative cell references of a formula by the given number
+/// of rows and columns, as happens when a formula is copied to another
+/// cell. Absolute parts of a reference are not changed.
+///
+/// References that are moved outside of the sheet are replaced with
+/// the error #REF!.
+///
+/// ```
+/// use spreadsheet_ods::formula::shift_refs;
+///
+/// let f = shift_refs("of:=SUM([.A1:.$B$2])*[.C$1]+[Sheet2.$D4]", 2, 1);
+/// assert_eq!(f, "of:=SUM([.B3:.$B$2])*[.D$1]+[Sheet2.$D6]");
+/// assert_eq!(shift_refs("of:=[.A1]", -1, 0), "of:=#REF!");
+/// ```
+pub fn shift_refs(formula: &str, rows: i64, cols: i64) -> String {
+    map_refs(formula, |v| {
+        if v.abs {
+            Some(v.index)
+        } else if v.row {
+            shift_index(v.index, rows, MAX_ROWS)
+        } else {
+            shift_index(v.index, cols, MAX_COLS)
+        }
+    })
+}
+
+/// Moves a row/column index, None if it leaves the sheet.
+pub(crate) fn shift_index(v: u32, delta: i64, max: u32) -> Option<u32> {
+    let v = v as i64 + delta;
+    (0..max as i64).contains(&v).then_some(v as u32)
+}
+
+/// Writes a reference with new row and column numbers, in the order
+/// they appear. The file, the table names and all "$" are kept as they are.
+fn splice_ref(reference: &[char], rows: &[u32], cols: &[u32], buf: &mut String) {
+    let mut rows = rows.iter();
+    let mut cols = cols.iter();
+
+    let mut in_quote = false;
+    let mut i = 0;
+    while i < reference.len() {
+        let c = reference[i];
+        i += 1;
+        buf.push(c);
+        if in_quote {
+            in_quote = c != '\'';
+        } else if c == '\'' {
+            in_quote = true;
+        } else if c == '.' {
+            if reference.get(i) == Some(&'$') {
+                buf.push('$');
+                i += 1;
+            }
+            if reference.get(i).is_some_and(|v| v.is_ascii_alphabetic()) {
+                while reference.get(i).is_some_and(|v| v.is_ascii_alphabetic()) {
+                    i += 1;
+                }
+                if let Some(col) = cols.next() {
+                    let _ = fmt_col_name(buf, *col);
+                }
+                if reference.get(i) == Some(&'$') {
+                    buf.push('$');
+                    i += 1;
+                }
+            }
+            if reference.get(i).is_some_and(|v| v.is_ascii_digit()) {
+                while reference.get(i).is_some_and(|v| v.is_ascii_digit()) {
+                    i += 1;
+                }
+                if let Some(row) = rows.next() {
+                    let _ = fmt_row_name(buf, *row);
+                }
+            }
+        }
+    }
+}
+
+/// One row or column index of a reference, as given to map_refs().
+pub(crate) struct RefIndex<'a> {
+    /// Table of the reference.
+    pub(crate) table: Option<&'a str>,
+    /// Reference to an external file.
+    pub(crate) external: bool,
+    /// Row or column index.
+    pub(crate) row: bool,
+    pub(crate) index: u32,
+    pub(crate) abs: bool,
+}
+
+/// Calls the function for each row and column index of the references
+/// in a formula and replaces it with the result. If the function returns
+/// None for any part the reference is replaced with the error #REF!.
+pub(crate) fn map_refs<F>(formula: &str, mut f: F) -> String
+where
+    F: FnMut(RefIndex<'_>) -> Option<u32>,
+{
+    let s = formula.chars().collect::<Vec<_>>();
+    let mut buf = String::with_capacity(formula.len());
+
+    let mut in_string = false;
+    let mut i = 0;
+    while i < s.len() {
+        let c = s[i];
+        if in_string {
+            if c == '"' {
+                in_string = false;
+            }
+            buf.push(c);
+            i += 1;
+        } else if c == '"' {
+            in_string = true;
+            buf.push(c);
+            i += 1;
+        } else if c == '[' {
+            let end = ref_end(&s, i + 1);
+            let reference = s[i + 1..end].iter().collect::<String>();
+
+            let mut changed = false;
+            let mut part = |table: Option<&String>, external: bool, row: bool, index, abs| {
+                let v = f(RefIndex {
+                    table: table.map(|v| v.as_str()),
+                    external,
+                    row,
+                    index,
+                    abs,
+                });
+                changed |= v != Some(index);
+                v
+            };
+
+            let mapped = if let Ok(v) = parse_cellref(&reference) {
+                let (t, e) = (v.table().cloned(), v.iri().is_some());
+                part(t.as_ref(), e, true, v.row(), v.row_abs())
+                    .zip(part(t.as_ref(), e, false, v.col(), v.col_abs()))
+                    .map(|(r, c)| (vec![r], vec![c]))
+            } else if let Ok(v) = parse_cellrange(&reference) {
+                let (t, e) = (v.table().cloned(), v.iri().is_some());
+                part(t.as_ref(), e, true, v.row(), v.row_abs())
+                    .zip(part(t.as_ref(), e, false, v.col(), v.col_abs()))
+                    .zip(part(t.as_ref(), e, true, v.to_row(), v.to_row_abs()))
+                    .zip(part(t.as_ref(), e, false, v.to_col(), v.to_col_abs()))
+                    .map(|(((r, c), to_r), to_c)| (vec![r, to_r], vec![c, to_c]))
+            } else if let Ok(v) = parse_colrange(&reference) {
+                let (t, e) = (v.table().cloned(), v.iri().is_some());
+                part(t.as_ref(), e, false, v.col(), v.col_abs())
+                    .zip(part(t.as_ref(), e, false, v.to_col(), v.to_col_abs()))
+                    .map(|(c, to_c)| (Vec::new(), vec![c, to_c]))
+            } else if let Ok(v) = parse_rowrange(&reference) {
+                let (t, e) = (v.table().cloned(), v.iri().is_some());
+                part(t.as_ref(), e, true, v.row(), v.row_abs())
+                    .zip(part(t.as_ref(), e, true, v.to_row(), v.to_row_abs()))
+                    .map(|(r, to_r)| (vec![r, to_r], Vec::new()))
+            } else {
+                // unknown, keep it.
+                None
+            };
+
+            if changed {
+                match mapped {
+                    Some((rows, cols)) => {
+                        buf.push('[');
+                        splice_ref(&s[i + 1..end], &rows, &cols, &mut buf);
+                        buf.push(']');
+                    }
+                    None => buf.push_str("#REF!"),
+                }
+            } else {
+                buf.extend(&s[i..min(end + 1, s.len())]);
+            }
+            i = end + 1;
+        } else {
+            buf.push(c);
+            i += 1;
+        }
+    }
+
+    buf
+}
@@ -7,14 +7,18 @@ pub(crate) fn format_duration2(v: Duration) -> impl Display {
 
     impl Display for Tmp {
         fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-            write!(
-                f,
-                "PT{}H{}M{}.{}S",
-                self.0.num_hours(),
-                self.0.num_minutes() % 60,
-                self.0.num_seconds() % 60,
-                self.0.num_milliseconds() % 1000
-            )
+            let v = self.0.abs();
+            let secs = v.num_seconds();
+            if self.0 < Duration::zero() {
+                write!(f, "-")?;
+            }
+            write!(f, "PT{}H{}M{}", secs / 3600, (secs / 60) % 60, secs % 60)?;
+            let nanos = v.subsec_nanos();
+            if nanos > 0 {
+                let nanos = format!("{:09}", nanos);
+                write!(f, ".{}", nanos.trim_end_matches('0'))?;
+            }
+            write!(f, "S")
         }
     }
 
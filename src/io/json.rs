@@ -0,0 +1,415 @@
+//!
+//! JSON export and import of the workbook structure.
+//!
+
+use crate::io::format::format_duration2;
+use crate::io::parse::parse_duration;
+use crate::sheet_::{ColHeader, RowHeader};
+use crate::style::{CellStyle, StyleOrigin, StyleUse};
+use crate::{CellStyleRef, Length, OdsError, Sheet, Value, WorkBook};
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+const DATETIME_FORMAT: &str = "%Y-%m-%dT%H:%M:%S%.f";
+const DATE_FORMAT: &str = "%Y-%m-%d";
+const TIME_FORMAT: &str = "%H:%M:%S%.f";
+
+/// Version of the json schema.
+const JSON_VERSION: u32 = 1;
+
+#[derive(Serialize, Deserialize)]
+struct JsonBook {
+    version: u32,
+    #[serde(default)]
+    cellstyles: Vec<JsonCellStyle>,
+    #[serde(default)]
+    sheets: Vec<JsonSheet>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct JsonCellStyle {
+    name: String,
+    #[serde(default, rename = "use")]
+    styleuse: String,
+    #[serde(default)]
+    origin: String,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    style: BTreeMap<String, String>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    cell: BTreeMap<String, String>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    paragraph: BTreeMap<String, String>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    text: BTreeMap<String, String>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct JsonSheet {
+    name: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    columns: Vec<JsonHeader>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    rows: Vec<JsonHeader>,
+    #[serde(default)]
+    cells: Vec<JsonCell>,
+}
+
+/// Column or row.
+#[derive(Serialize, Deserialize)]
+struct JsonHeader {
+    index: u32,
+    #[serde(default = "one", skip_serializing_if = "is_one")]
+    span: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    size: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    cellstyle: Option<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct JsonCell {
+    row: u32,
+    col: u32,
+    #[serde(default, rename = "type", skip_serializing_if = "Option::is_none")]
+    value_type: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    value: Option<serde_json::Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    currency: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    formula: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    style: Option<String>,
+    #[serde(default = "one", skip_serializing_if = "is_one")]
+    row_span: u32,
+    #[serde(default = "one", skip_serializing_if = "is_one")]
+    col_span: u32,
+}
+
+fn one() -> u32 {
+    1
+}
+
+fn is_one(v: &u32) -> bool {
+    *v == 1
+}
+
+impl WorkBook {
+    /// Serializes the sheets, values and cell styles as JSON.
+    ///
+    /// The JSON is meant for diffing, test fixtures and tools in other
+    /// languages. It contains
+    ///
+    /// * "version": the schema version, currently 1.
+    /// * "cellstyles": the cell styles with "name", "use" (default, named,
+    ///   automatic), "origin" (content, styles) and the attributes as maps
+    ///   "style", "cell", "paragraph" and "text". The value format is
+    ///   given by name as "style:data-style-name" but is not included.
+    /// * "sheets": each with "name", "columns" and "rows" with "index",
+    ///   "span", "size" and "cellstyle", and "cells".
+    ///
+    /// A cell has "row", "col", optional "formula", "style", "row_span",
+    /// "col_span" and the value as "type" and "value":
+    ///
+    /// | type       | value                                  |
+    /// |------------|----------------------------------------|
+    /// | boolean    | true/false                             |
+    /// | number     | number                                 |
+    /// | decimal    | string with the exact decimal          |
+    /// | percentage | number, 1 is 100%                      |
+    /// | currency   | number, the currency code as "currency"|
+    /// | text       | string, text markup is reduced to text |
+    /// | datetime   | "2024-01-31T12:00:00"                  |
+    /// | date       | "2024-01-31"                           |
+    /// | time       | "12:00:00"                             |
+    /// | duration   | "PT1H30M0S"                            |
+    /// | error      | string, e.g. "#DIV/0!"                 |
+    ///
+    /// Numbers, percentages and currencies that are not finite are
+    /// written as the strings "NaN", "inf" and "-inf".
+    ///
+    /// Empty cells have no type. Everything else in the workbook is not
+    /// part of the JSON.
+    pub fn to_json(&self) -> Result<String, OdsError> {
+        let book = JsonBook {
+            version: JSON_VERSION,
+            cellstyles: self.iter_cellstyles().map(json_cellstyle).collect(),
+            sheets: self.iter_sheets().map(json_sheet).collect(),
+        };
+        serde_json::to_string_pretty(&book)
+            .map_err(|e| OdsError::Parse("json", Some(e.to_string())))
+    }
+
+    /// Creates a workbook from JSON as written by [WorkBook::to_json].
+    pub fn from_json(json: &str) -> Result<WorkBook, OdsError> {
+        let json: JsonBook =
+            serde_json::from_str(json).map_err(|e| OdsError::Parse("json", Some(e.to_string())))?;
+        if json.version > JSON_VERSION {
+            return Err(OdsError::Parse(
+                "unsupported json version",
+                Some(json.version.to_string()),
+            ));
+        }
+
+        let mut book = WorkBook::new_empty();
+        for style in json.cellstyles {
+            book.add_cellstyle(cellstyle_from_json(style)?);
+        }
+        for sheet in json.sheets {
+            book.push_sheet(sheet_from_json(sheet)?);
+        }
+        Ok(book)
+    }
+}
+
+fn attr_map<'a>(
+    attr: impl Iterator<Item = (&'a string_cache::DefaultAtom, &'a str)>,
+) -> BTreeMap<String, String> {
+    attr.map(|(k, v)| (k.to_string(), v.to_string())).collect()
+}
+
+fn json_cellstyle(style: &CellStyle) -> JsonCellStyle {
+    JsonCellStyle {
+        name: style.name().to_string(),
+        styleuse: match style.styleuse() {
+            StyleUse::Default => "default",
+            StyleUse::Named => "named",
+            StyleUse::Automatic => "automatic",
+        }
+        .to_string(),
+        origin: match style.origin() {
+            StyleOrigin::Content => "content",
+            StyleOrigin::Styles => "styles",
+        }
+        .to_string(),
+        style: attr_map(style.attrmap().iter()),
+        cell: attr_map(style.cellstyle().iter()),
+        paragraph: attr_map(style.paragraphstyle().iter()),
+        text: attr_map(style.textstyle().iter()),
+    }
+}
+
+fn cellstyle_from_json(json: JsonCellStyle) -> Result<CellStyle, OdsError> {
+    let mut style = CellStyle::new_empty();
+    style.set_name(json.name);
+    style.set_styleuse(match json.styleuse.as_str() {
+        "default" => StyleUse::Default,
+        "named" => StyleUse::Named,
+        "automatic" | "" => StyleUse::Automatic,
+        v => return Err(OdsError::Parse("unknown style use", Some(v.to_string()))),
+    });
+    style.set_origin(match json.origin.as_str() {
+        "content" | "" => StyleOrigin::Content,
+        "styles" => StyleOrigin::Styles,
+        v => return Err(OdsError::Parse("unknown style origin", Some(v.to_string()))),
+    });
+    for (k, v) in json.style {
+        style.attrmap_mut().set_attr(&k, v);
+    }
+    for (k, v) in json.cell {
+        style.cellstyle_mut().set_attr(&k, v);
+    }
+    for (k, v) in json.paragraph {
+        style.paragraphstyle_mut().set_attr(&k, v);
+    }
+    for (k, v) in json.text {
+        style.textstyle_mut().set_attr(&k, v);
+    }
+    Ok(style)
+}
+
+fn json_sheet(sheet: &Sheet) -> JsonSheet {
+    let columns = sheet
+        .col_header
+        .iter()
+        .filter(|(_, v)| v.width != Length::Default || v.cellstyle.is_some())
+        .map(|(col, v)| JsonHeader {
+            index: *col,
+            span: v.span,
+            size: (v.width != Length::Default).then(|| v.width.to_string()),
+            cellstyle: v.cellstyle.as_ref().map(|v| v.as_str().to_string()),
+        })
+        .collect();
+    let rows = sheet
+        .row_header
+        .iter()
+        .filter(|(_, v)| v.height != Length::Default || v.cellstyle.is_some())
+        .map(|(row, v)| JsonHeader {
+            index: *row,
+            span: v.span,
+            size: (v.height != Length::Default).then(|| v.height.to_string()),
+            cellstyle: v.cellstyle.as_ref().map(|v| v.as_str().to_string()),
+        })
+        .collect();
+
+    let mut cells = Vec::new();
+    for ((row, col), cell) in sheet.data.iter() {
+        let (value_type, value, currency) = json_value(&cell.value);
+        let (row_span, col_span) = cell.extra.as_ref().map(|v| v.span.into()).unwrap_or((1, 1));
+        cells.push(JsonCell {
+            row,
+            col,
+            value_type: value_type.map(|v| v.to_string()),
+            value,
+            currency,
+            formula: cell.formula.clone(),
+            style: cell.style.as_ref().map(|v| v.as_str().to_string()),
+            row_span,
+            col_span,
+        });
+    }
+
+    JsonSheet {
+        name: sheet.name().to_string(),
+        columns,
+        rows,
+        cells,
+    }
+}
+
+fn sheet_from_json(json: JsonSheet) -> Result<Sheet, OdsError> {
+    let mut sheet = Sheet::new(json.name);
+    for col in json.columns {
+        let header = ColHeader {
+            cellstyle: col.cellstyle.map(CellStyleRef::from),
+            width: parse_size(col.size.as_deref())?,
+            span: col.span.max(1),
+            ..Default::default()
+        };
+        sheet.col_header.insert(col.index, header);
+    }
+    for row in json.rows {
+        let header = RowHeader {
+            cellstyle: row.cellstyle.map(CellStyleRef::from),
+            height: parse_size(row.size.as_deref())?,
+            span: row.span.max(1),
+            ..Default::default()
+        };
+        sheet.row_header.insert(row.index, header);
+    }
+    for cell in json.cells {
+        let (row, col) = (cell.row, cell.col);
+        let value = value_from_json(
+            cell.value_type.as_deref(),
+            cell.value.as_ref(),
+            cell.currency.as_deref(),
+        )?;
+        sheet.set_value(row, col, value);
+        if let Some(formula) = cell.formula {
+            sheet.set_formula(row, col, formula);
+        }
+        if let Some(style) = cell.style {
+            sheet.set_cellstyle(row, col, &CellStyleRef::from(style));
+        }
+        if cell.row_span > 1 {
+            sheet.set_row_span(row, col, cell.row_span);
+        }
+        if cell.col_span > 1 {
+            sheet.set_col_span(row, col, cell.col_span);
+        }
+    }
+    Ok(sheet)
+}
+
+fn parse_size(size: Option<&str>) -> Result<Length, OdsError> {
+    use crate::style::ParseStyleAttr;
+    Ok(Length::parse_attr(size)?.unwrap_or_default())
+}
+
+fn json_value(
+    value: &Value,
+) -> (
+    Option<&'static str>,
+    Option<serde_json::Value>,
+    Option<String>,
+) {
+    use serde_json::Value as J;
+    match value {
+        Value::Empty => (None, None, None),
+        Value::Boolean(v) => (Some("boolean"), Some(J::from(*v)), None),
+        Value::Number(v) => (Some("number"), Some(json_number(*v)), None),
+        #[cfg(feature = "rust_decimal")]
+        Value::Decimal(v) => (Some("decimal"), Some(J::from(v.to_string())), None),
+        Value::Percentage(v) => (Some("percentage"), Some(json_number(*v)), None),
+        Value::Currency(v, c) => (Some("currency"), Some(json_number(*v)), Some(c.to_string())),
+        Value::Text(_) | Value::TextXml(_) => (
+            Some("text"),
+            Some(J::from(value.as_cow_str_or("").into_owned())),
+            None,
+        ),
+        Value::DateTime(v) => (
+            Some("datetime"),
+            Some(J::from(v.format(DATETIME_FORMAT).to_string())),
+            None,
+        ),
+        Value::Date(v) => (
+            Some("date"),
+            Some(J::from(v.format(DATE_FORMAT).to_string())),
+            None,
+        ),
+        Value::Time(v) => (
+            Some("time"),
+            Some(J::from(v.format(TIME_FORMAT).to_string())),
+            None,
+        ),
+        Value::TimeDuration(v) => (
+            Some("duration"),
+            Some(J::from(format_duration2(*v).to_string())),
+            None,
+        ),
+        Value::Error(v) => (Some("error"), Some(J::from(v.as_str())), None),
+    }
+}
+
+/// JSON has no NaN and infinity, they are written as strings.
+fn json_number(v: f64) -> serde_json::Value {
+    if v.is_finite() {
+        serde_json::Value::from(v)
+    } else {
+        serde_json::Value::from(v.to_string())
+    }
+}
+
+fn value_from_json(
+    value_type: Option<&str>,
+    value: Option<&serde_json::Value>,
+    currency: Option<&str>,
+) -> Result<Value, OdsError> {
+    let Some(value_type) = value_type else {
+        return Ok(Value::Empty);
+    };
+    let invalid = || OdsError::Parse("invalid json value", Some(format!("{:?}", value)));
+    let number = || {
+        value
+            .and_then(|v| {
+                v.as_f64().or_else(|| {
+                    v.as_str()
+                        .and_then(|v| v.parse::<f64>().ok())
+                        .filter(|v| !v.is_finite())
+                })
+            })
+            .ok_or_else(invalid)
+    };
+    let string = || value.and_then(|v| v.as_str()).ok_or_else(invalid);
+
+    Ok(match value_type {
+        "boolean" => Value::Boolean(value.and_then(|v| v.as_bool()).ok_or_else(invalid)?),
+        "number" => Value::Number(number()?),
+        #[cfg(feature = "rust_decimal")]
+        "decimal" => {
+            Value::Decimal(rust_decimal::Decimal::from_str_exact(string()?).map_err(|_| invalid())?)
+        }
+        #[cfg(not(feature = "rust_decimal"))]
+        "decimal" => Value::Number(string()?.parse()?),
+        "percentage" => Value::Percentage(number()?),
+        "currency" => Value::Currency(number()?, currency.unwrap_or_default().into()),
+        "text" => Value::Text(string()?.to_string()),
+        "datetime" => Value::DateTime(NaiveDateTime::parse_from_str(string()?, DATETIME_FORMAT)?),
+        "date" => Value::Date(NaiveDate::parse_from_str(string()?, DATE_FORMAT)?),
+        "time" => Value::Time(NaiveTime::parse_from_str(string()?, TIME_FORMAT)?),
+        "duration" => Value::TimeDuration(parse_duration(string()?.as_bytes())?),
+        "error" => Value::Error(string()?.to_string()),
+        v => return Err(OdsError::Parse("unknown value type", Some(v.to_string()))),
+    })
+}
@@ -1,17 +1,22 @@
-use crate::HashMap;
 use get_size2::GetSize;
 use std::borrow::Cow;
+use std::collections::BTreeMap;
 
 pub(crate) mod format;
+#[cfg(feature = "serde")]
+pub(crate) mod json;
 pub(crate) mod parse;
 pub(crate) mod read;
+#[cfg(feature = "validate_odf")]
+pub(crate) mod validate;
 pub(crate) mod write;
 
 mod xmlwriter;
 
 #[derive(Clone, Debug)]
 pub(crate) struct NamespaceMap {
-    map: HashMap<Cow<'static, str>, Cow<'static, str>>,
+    // sorted for a deterministic output.
+    map: BTreeMap<Cow<'static, str>, Cow<'static, str>>,
 }
 
 impl GetSize for NamespaceMap {}
@@ -16,6 +16,8 @@ use nom::combinator::{all_consuming, eof, opt};
 use nom::number::complete::double;
 use nom::sequence::{pair, preceded, terminated, tuple};
 use nom::AsChar;
+#[cfg(feature = "rust_decimal")]
+use rust_decimal::Decimal;
 use std::fmt::{Display, Formatter};
 use std::str::{from_utf8, from_utf8_unchecked};
 
@@ -96,6 +98,9 @@ pub(crate) fn parse_xlink_show(input: KSpan<'_>) -> Result<XLinkShow, OdsError>
     match input {
         b"new" => Ok(XLinkShow::New),
         b"replace" => Ok(XLinkShow::Replace),
+        b"embed" => Ok(XLinkShow::Embed),
+        b"other" => Ok(XLinkShow::Other),
+        b"none" => Ok(XLinkShow::None),
         _ => Err(OdsError::Parse(
             "invalid xlink:show",
             Some(from_utf8(input)?.to_string()),
@@ -169,6 +174,15 @@ pub(crate) fn parse_f64(input: KSpan<'_>) -> Result<f64, OdsError> {
     Ok(token_float(input)?)
 }
 
+/// Parse a decimal exactly. Accepts the scientific notation too.
+#[cfg(feature = "rust_decimal")]
+pub(crate) fn parse_decimal(input: KSpan<'_>) -> Result<Decimal, OdsError> {
+    let s = from_utf8(input)?;
+    Decimal::from_str_exact(s)
+        .or_else(|_| Decimal::from_scientific(s))
+        .map_err(|_| OdsError::Parse("invalid decimal", Some(s.to_string())))
+}
+
 /// Parse a XML Schema datetime.
 #[inline]
 pub(crate) fn parse_datetime(input: KSpan<'_>) -> Result<NaiveDateTime, OdsError> {
@@ -343,7 +357,8 @@ fn token_datetime(input: KSpan<'_>) -> KTokenResult<'_, NaiveDateTime> {
 
 #[inline(always)]
 fn token_duration(input: KSpan<'_>) -> KTokenResult<'_, Duration> {
-    let (_, (_, day, time)) = all_consuming(tuple((
+    let (_, (minus, _, day, time)) = all_consuming(tuple((
+        opt(byte(b'-')),
         byte(b'P'),
         // these do not occur?
         //opt(terminated(token_datepart, byte(b'Y'))),
@@ -391,6 +406,9 @@ fn token_duration(input: KSpan<'_>) -> KTokenResult<'_, Duration> {
             }
         }
     }
+    if minus.is_some() {
+        result = -result;
+    }
 
     Ok(result)
 }
@@ -434,11 +452,12 @@ pub(crate) fn byte(c: u8) -> impl Fn(KSpan<'_>) -> KTokenizerResult<'_, ()> {
 
 #[cfg(test)]
 mod tests {
+    use crate::io::format::format_duration2;
     use crate::io::parse::{
         parse_bool, parse_datetime, parse_duration, parse_f64, parse_i32, parse_u32, token_nano,
     };
     use crate::OdsError;
-    use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
+    use chrono::{Duration, NaiveDate, NaiveDateTime, NaiveTime};
 
     #[test]
     fn test_u32() -> Result<(), OdsError> {
@@ -544,6 +563,24 @@ mod tests {
             parse_duration(b"PT12H12M12.223S")?.num_milliseconds(),
             43932223
         );
+        assert_eq!(
+            parse_duration(b"-PT1H30M0.5S")?.num_milliseconds(),
+            -5400500
+        );
+
+        for v in [
+            Duration::milliseconds(43932223),
+            Duration::milliseconds(1050),
+            Duration::nanoseconds(-5400000000123),
+            Duration::zero(),
+        ] {
+            let s = format_duration2(v).to_string();
+            assert_eq!(parse_duration(s.as_bytes())?, v, "{}", s);
+        }
+        assert_eq!(
+            format_duration2(Duration::milliseconds(-5400500)).to_string(),
+            "-PT1H30M0.5S"
+        );
         Ok(())
     }
 
@@ -1,26 +1,39 @@
 use crate::sheet_::Header;
 use std::borrow::Cow;
+use std::cmp::min;
 use std::convert::{TryFrom, TryInto};
+use std::fmt::{Debug, Formatter};
 use std::fs::File;
 use std::io::{BufRead, BufReader, Cursor, Read, Seek, Write};
 use std::mem;
 use std::path::Path;
 use std::str::from_utf8;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
 
-use chrono::{Duration, NaiveDateTime};
+use chrono::{Duration, NaiveDateTime, NaiveTime};
 use quick_xml::events::attributes::Attribute;
 use quick_xml::events::{BytesStart, Event};
 use quick_xml::{Decoder, Reader};
+#[cfg(feature = "rust_decimal")]
+use rust_decimal::Decimal;
 use zip::ZipArchive;
 
 use crate::attrmap2::AttrMap2;
+use crate::basic::{BasicLibrary, BasicModule};
 use crate::cell_::CellData;
+use crate::color::Rgb;
 use crate::condition::{Condition, ValueCondition};
+use crate::conditional::{ColorScale, ConditionalFormat, DataBar, DataBarAxis, ScaleValue};
 use crate::config::{Config, ConfigItem, ConfigItemType, ConfigValue};
-use crate::draw::{Annotation, DrawFrame, DrawFrameContent, DrawImage};
+use crate::dde::DdeLink;
+use crate::draw::{Annotation, DrawFrame, DrawFrameContent, DrawImage, DrawObject};
 use crate::ds::detach::Detach;
-use crate::error::OdsError;
+use crate::error::{OdsError, XmlLocation};
 use crate::format::{FormatPart, FormatPartType, ValueFormatTrait, ValueStyleMap};
+use crate::formula::formula_from_plain;
+#[cfg(feature = "rust_decimal")]
+use crate::io::parse::parse_decimal;
 use crate::io::parse::{
     parse_bool, parse_currency, parse_datetime, parse_duration, parse_f64, parse_i16, parse_i32,
     parse_i64, parse_string, parse_u32, parse_visibility, parse_xlink_actuate, parse_xlink_show,
@@ -32,20 +45,23 @@ use crate::metadata::{
     MetaAutoReload, MetaDocumentStatistics, MetaHyperlinkBehaviour, MetaTemplate, MetaUserDefined,
     MetaValue,
 };
-use crate::refs::{parse_cellranges, parse_cellref};
-use crate::sheet::{Grouped, SplitMode};
-use crate::sheet_::{dedup_colheader, CellDataIter, CellDataIterMut, ColHeader, RowHeader};
+use crate::refs::{parse_cellrange, parse_cellranges, parse_cellref};
+use crate::sheet::{Grouped, PrintScale, SplitMode};
+use crate::sheet_::{
+    dedup_colheader, sort_groups, CellDataIter, CellDataIterMut, ColHeader, RowHeader,
+};
 use crate::style::stylemap::StyleMap;
 use crate::style::tabstop::TabStop;
+use crate::style::units::{Percent, PrintOrder, WritingMode};
 use crate::style::{
-    AnyStyleRef, ColStyle, ColStyleRef, FontFaceDecl, GraphicStyle, HeaderFooter, MasterPage,
-    MasterPageRef, PageStyle, ParagraphStyle, RowStyle, RowStyleRef, RubyStyle, StyleOrigin,
-    StyleUse, TableStyle, TableStyleRef, TextStyle,
+    AnyStyleRef, ColStyle, ColStyleRef, FontFaceDecl, FontFaceUri, GraphicStyle, HeaderFooter,
+    MasterPage, MasterPageRef, PageStyle, ParagraphStyle, ParseStyleAttr, RowStyle, RowStyleRef,
+    RubyStyle, StyleOrigin, StyleUse, TableStyle, TableStyleRef, TextStyle, Theme, ThemeColor,
 };
 use crate::text::{TextP, TextTag};
 use crate::validation::{MessageType, Validation, ValidationError, ValidationHelp, ValidationRef};
-use crate::workbook::{EventListener, Script};
-use crate::xmltree::XmlTag;
+use crate::workbook::{CalcSettings, EventListener, Script};
+use crate::xmltree::{XmlContent, XmlTag, MAX_TEXT_SPACES};
 use crate::{
     CellStyle, CellStyleRef, Length, Sheet, Value, ValueFormatBoolean, ValueFormatCurrency,
     ValueFormatDateTime, ValueFormatNumber, ValueFormatPercentage, ValueFormatText,
@@ -55,7 +71,7 @@ use crate::{
 type OdsXmlReader<'a> = quick_xml::Reader<&'a mut dyn BufRead>;
 
 /// Read options for ods-files.
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Clone)]
 pub struct OdsOptions {
     // parse the content only.
     content_only: bool,
@@ -63,6 +79,27 @@ pub struct OdsOptions {
     use_repeat_for_cells: bool,
     // ignore empty cells.
     ignore_empty_cells: bool,
+    // recover from invalid values.
+    lossy: bool,
+    // read float values as decimal.
+    #[cfg(feature = "rust_decimal")]
+    use_decimal: bool,
+    // max uncompressed size of a zip entry.
+    max_entry_size: Option<u64>,
+    // max repeat count for rows/cells with data.
+    max_repeat: Option<u32>,
+    // max number of sheets.
+    max_sheets: Option<usize>,
+    // only read these sheets.
+    sheets: Option<Vec<String>>,
+    // only values and formulas.
+    values_only: bool,
+    // convert formulas without namespace prefix.
+    plain_formulas: bool,
+    // keep the displayed text of cells.
+    display_text: bool,
+    // cancel reading.
+    cancel: Option<Arc<AtomicBool>>,
 }
 
 impl OdsOptions {
@@ -75,6 +112,20 @@ impl OdsOptions {
         self
     }
 
+    /// Reads only values and formulas.
+    ///
+    /// Implies content_only(). Additionally, the styles, scripts and
+    /// validations in content.xml are skipped without parsing, and cells
+    /// don't get their style, validation, annotations, images or any
+    /// unknown attributes and content.
+    ///
+    /// The result is not fit for writing it back.
+    pub fn values_only(mut self) -> Self {
+        self.content_only = true;
+        self.values_only = true;
+        self
+    }
+
     /// Parse everything.
     ///
     /// Reads styles and buffers extra files.
@@ -82,6 +133,7 @@ impl OdsOptions {
     /// should be used.
     pub fn read_styles(mut self) -> Self {
         self.content_only = false;
+        self.values_only = false;
         self
     }
 
@@ -131,6 +183,101 @@ impl OdsOptions {
         self
     }
 
+    /// Invalid cell and row data results in an error.
+    ///
+    /// The error contains the sheet, the row/col and the byte position
+    /// within the xml. This is the default.
+    pub fn strict(mut self) -> Self {
+        self.lossy = false;
+        self
+    }
+
+    /// Recovers from invalid cell and row data as far as possible.
+    ///
+    /// Attributes with invalid values are ignored. If the value of a cell
+    /// can't be read, the displayed text is used as a string value.
+    /// An unknown value-type is read as string too.
+    ///
+    /// Malformed xml is still an error.
+    pub fn lossy(mut self) -> Self {
+        self.lossy = true;
+        self
+    }
+
+    /// Reads float values as [Value::Decimal] with the exact value of
+    /// office:value. Percentages and currencies are still read as f64.
+    #[cfg(feature = "rust_decimal")]
+    pub fn use_decimal(mut self) -> Self {
+        self.use_decimal = true;
+        self
+    }
+
+    /// Converts formulas without a namespace prefix from the plain
+    /// syntax used by Google Sheets to OpenFormula. See
+    /// [formula_from_plain](crate::formula::formula_from_plain).
+    ///
+    /// Formulas with a namespace prefix like "of:" are not changed.
+    pub fn plain_formulas(mut self) -> Self {
+        self.plain_formulas = true;
+        self
+    }
+
+    /// Keeps the text that the application displayed for each cell
+    /// (the content of text:p) alongside the value. It is available via
+    /// [CellContentRef::display_text](crate::CellContentRef::display_text).
+    ///
+    /// The text is neither updated when the value changes nor written.
+    pub fn display_text(mut self) -> Self {
+        self.display_text = true;
+        self
+    }
+
+    /// Reading is cancelled with [OdsError::Cancelled] when the flag
+    /// is set. It is checked for each row.
+    pub fn cancel(mut self, cancel: Arc<AtomicBool>) -> Self {
+        self.cancel = Some(cancel);
+        self
+    }
+
+    /// Maximum uncompressed size of any entry in the zip file.
+    ///
+    /// Both the size given in the zip-header and the actual size
+    /// are checked. Exceeding it results in an error. Not limited by default.
+    pub fn max_entry_size(mut self, size: u64) -> Self {
+        self.max_entry_size = Some(size);
+        self
+    }
+
+    /// Maximum value of table:number-rows-repeated and
+    /// table:number-columns-repeated for rows and cells that are expanded
+    /// when reading. Repeated empty rows and cells are not affected.
+    ///
+    /// Exceeding it results in an error. Not limited by default.
+    ///
+    /// A paragraph with a text:s of more spaces is kept as xml instead
+    /// of plain text.
+    pub fn max_repeat(mut self, repeat: u32) -> Self {
+        self.max_repeat = Some(repeat);
+        self
+    }
+
+    /// Maximum number of sheets. Exceeding it results in an error.
+    /// Not limited by default.
+    pub fn max_sheets(mut self, sheets: usize) -> Self {
+        self.max_sheets = Some(sheets);
+        self
+    }
+
+    /// Only reads the sheets with the given names. The table data of all
+    /// other sheets is skipped without parsing it, they are not part of
+    /// the resulting workbook.
+    ///
+    /// Names that don't exist in the file are ignored.
+    pub fn sheets<S: AsRef<str>>(mut self, names: &[S]) -> Self {
+        self.sheets = Some(names.iter().map(|v| v.as_ref().to_string()).collect());
+        self
+    }
+
     /// Reads a .ods file.
     pub fn read_ods<T: Read + Seek>(&self, read: T) -> Result<WorkBook, OdsError> {
         let zip = ZipArchive::new(read)?;
@@ -141,12 +288,49 @@ impl OdsOptions {
         }
     }
 
+    /// Reads a .ods file via memory mapping.
+    ///
+    /// The zip-directory and the compressed data are read directly from
+    /// the mapped file, the xml parts are parsed while decompressing.
+    ///
+    /// The file must not be modified while it is read.
+    #[cfg(feature = "mmap")]
+    pub fn read_ods_mmap<P: AsRef<Path>>(&self, path: P) -> Result<WorkBook, OdsError> {
+        let file = File::open(path.as_ref())?;
+        // Safety: the map is only used for reading. A concurrent
+        // modification of the file is not guarded against, which is
+        // documented above.
+        let map = unsafe { memmap2::Mmap::map(&file)? };
+        self.read_ods(Cursor::new(&map[..]))
+    }
+
+    /// Reads a .ods file, but only the sheet names and attributes of
+    /// the sheets. The cell data is loaded when a sheet is first accessed.
+    ///
+    /// Styles, metadata and settings are always read, content_only()
+    /// has no effect here.
+    pub fn read_ods_lazy<T: Read + Seek>(&self, read: T) -> Result<LazyWorkBook<T>, OdsError> {
+        let zip = ZipArchive::new(read)?;
+        read_ods_impl_lazy(zip, self)
+    }
+
     /// Reads a flat .fods file.
-    pub fn read_fods<T: BufRead>(&self, mut read: T) -> Result<WorkBook, OdsError> {
+    pub fn read_fods<T: BufRead>(&self, read: T) -> Result<WorkBook, OdsError> {
+        self.read_fods_located(read, &|_| {})
+    }
+
+    /// Reads a flat .fods file. The location of an error is completed
+    /// with locate, if the data can be read again.
+    fn read_fods_located<T: BufRead>(
+        &self,
+        read: T,
+        locate: &dyn Fn(&mut XmlLocation),
+    ) -> Result<WorkBook, OdsError> {
+        let mut read = LineRead::new(read);
         if self.content_only {
-            read_fods_impl_content_only(&mut read, self)
+            read_fods_impl_content_only(&mut read, self, locate)
         } else {
-            read_fods_impl(&mut read, self)
+            read_fods_impl(&mut read, self, locate)
         }
     }
 }
@@ -168,10 +352,17 @@ pub fn read_ods<P: AsRef<Path>>(path: P) -> Result<WorkBook, OdsError> {
     OdsOptions::default().read_ods(read)
 }
 
+/// Reads an ODS-file via memory mapping. See [OdsOptions::read_ods_mmap].
+#[cfg(feature = "mmap")]
+pub fn read_ods_mmap<P: AsRef<Path>>(path: P) -> Result<WorkBook, OdsError> {
+    OdsOptions::default().read_ods_mmap(path)
+}
+
 /// Reads an FODS-file from a buffer
 pub fn read_fods_buf(buf: &[u8]) -> Result<WorkBook, OdsError> {
-    let mut read = Cursor::new(buf);
-    OdsOptions::default().read_fods(&mut read)
+    OdsOptions::default().read_fods_located(Cursor::new(buf), &|loc| {
+        complete_location(loc, buf);
+    })
 }
 
 /// Reads an FODS-file from a reader
@@ -183,7 +374,79 @@ pub fn read_fods_from<T: Read>(read: T) -> Result<WorkBook, OdsError> {
 /// Reads an FODS-file.
 pub fn read_fods<P: AsRef<Path>>(path: P) -> Result<WorkBook, OdsError> {
     let read = BufReader::new(File::open(path.as_ref())?);
-    OdsOptions::default().read_fods(read)
+    OdsOptions::default().read_fods_located(read, &|loc| {
+        // Read again up to the error position to find the path.
+        let mut data = Vec::new();
+        if let Ok(f) = File::open(path.as_ref()) {
+            if f.take(loc.byte).read_to_end(&mut data).is_ok() {
+                complete_location(loc, &data);
+            }
+        }
+    })
+}
+
+/// Counts the lines while reading a flat file, to find the line and
+/// column of a read error.
+struct LineRead<R> {
+    read: R,
+    /// Consumed bytes.
+    pos: u64,
+    /// Lines before pos.
+    lines: u64,
+    /// Start of the line at pos.
+    line_start: u64,
+}
+
+impl<R: BufRead> LineRead<R> {
+    fn new(read: R) -> Self {
+        Self {
+            read,
+            pos: 0,
+            lines: 0,
+            line_start: 0,
+        }
+    }
+
+    fn count(&mut self, data: &[u8]) {
+        for (i, c) in data.iter().enumerate() {
+            if *c == b'\n' {
+                self.lines += 1;
+                self.line_start = self.pos + i as u64 + 1;
+            }
+        }
+        self.pos += data.len() as u64;
+    }
+
+    /// Sets line and column, if the error occurred at the end of the
+    /// consumed data.
+    fn locate(&self, loc: &mut XmlLocation) {
+        if loc.byte == self.pos {
+            loc.line = self.lines + 1;
+            loc.column = self.pos - self.line_start + 1;
+        }
+    }
+}
+
+impl<R: BufRead> Read for LineRead<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let n = self.read.read(buf)?;
+        self.count(&buf[..n]);
+        Ok(n)
+    }
+}
+
+impl<R: BufRead> BufRead for LineRead<R> {
+    fn fill_buf(&mut self) -> std::io::Result<&[u8]> {
+        self.read.fill_buf()
+    }
+
+    fn consume(&mut self, amt: usize) {
+        if let Ok(buf) = self.read.fill_buf() {
+            let data = buf[..min(amt, buf.len())].to_vec();
+            self.count(&data);
+        }
+        self.read.consume(amt);
+    }
 }
 
 struct OdsContext {
@@ -195,6 +458,19 @@ struct OdsContext {
     content_only: bool,
     use_repeat_for_cells: bool,
     ignore_empty_cells: bool,
+    lossy: bool,
+    #[cfg(feature = "rust_decimal")]
+    use_decimal: bool,
+    max_entry_size: Option<u64>,
+    max_repeat: Option<u32>,
+    max_sheets: Option<usize>,
+    sheets: Option<Vec<String>>,
+    values_only: bool,
+    plain_formulas: bool,
+    display_text: bool,
+    cancel: Option<Arc<AtomicBool>>,
+    // only the table attributes are read, the data is skipped.
+    skip_tables: bool,
 
     buffers: Vec<Vec<u8>>,
     xml_buffer: Vec<XmlTag>,
@@ -210,6 +486,18 @@ impl OdsContext {
             content_only: options.content_only,
             use_repeat_for_cells: options.use_repeat_for_cells,
             ignore_empty_cells: options.ignore_empty_cells,
+            lossy: options.lossy,
+            #[cfg(feature = "rust_decimal")]
+            use_decimal: options.use_decimal,
+            max_entry_size: options.max_entry_size,
+            max_repeat: options.max_repeat,
+            max_sheets: options.max_sheets,
+            sheets: options.sheets.clone(),
+            values_only: options.values_only,
+            plain_formulas: options.plain_formulas,
+            display_text: options.display_text,
+            cancel: options.cancel.clone(),
+            skip_tables: false,
 
             buffers: vec![],
             xml_buffer: vec![],
@@ -218,6 +506,14 @@ impl OdsContext {
         }
     }
 
+    /// Fails if reading has been cancelled.
+    fn check_cancel(&self) -> Result<(), OdsError> {
+        match &self.cancel {
+            Some(cancel) if cancel.load(Ordering::Relaxed) => Err(OdsError::Cancelled),
+            _ => Ok(()),
+        }
+    }
+
     fn pop_xml_buf(&mut self) -> Vec<XmlTag> {
         mem::take(&mut self.xml_buffer)
     }
@@ -257,10 +553,47 @@ impl OdsContext {
     }
 }
 
-fn read_fods_impl(read: &mut dyn BufRead, options: &OdsOptions) -> Result<WorkBook, OdsError> {
+fn read_fods_impl<R: BufRead>(
+    read: &mut LineRead<R>,
+    options: &OdsOptions,
+    locate: &dyn Fn(&mut XmlLocation),
+) -> Result<WorkBook, OdsError> {
     let mut ctx = OdsContext::new(options);
-    let mut xml = quick_xml::Reader::from_reader(read);
+    read_fods_part(&mut ctx, read, locate, read_fods_content)?;
+
+    calculations(&mut ctx)?;
+
+    // We do some data duplication here, to make everything easier to use.
+    calc_derived(&mut ctx.book)?;
+
+    Ok(ctx.book)
+}
+
+/// Reads the flat file. Errors get the location within the file attached.
+fn read_fods_part<R: BufRead, F>(
+    ctx: &mut OdsContext,
+    read: &mut LineRead<R>,
+    locate: &dyn Fn(&mut XmlLocation),
+    read_fn: F,
+) -> Result<(), OdsError>
+where
+    F: FnOnce(&mut OdsContext, &mut OdsXmlReader<'_>) -> Result<(), OdsError>,
+{
+    let res = {
+        let read: &mut dyn BufRead = read;
+        let mut xml = quick_xml::Reader::from_reader(read);
+        read_fn(ctx, &mut xml).map_err(|e| (xml.buffer_position(), e))
+    };
 
+    res.map_err(|(byte, err)| {
+        xml_error("fods", byte, err, |loc| {
+            read.locate(loc);
+            locate(loc);
+        })
+    })
+}
+
+fn read_fods_content(ctx: &mut OdsContext, xml: &mut OdsXmlReader<'_>) -> Result<(), OdsError> {
     let mut buf = ctx.pop_buf();
     loop {
         let evt = xml.read_event_into(&mut buf)?;
@@ -270,7 +603,7 @@ fn read_fods_impl(read: &mut dyn BufRead, options: &OdsOptions) -> Result<WorkBo
 
         match &evt {
             Event::Start(xml_tag) if xml_tag.name().as_ref() == b"office:document" => {
-                let (version, xmlns) = read_namespaces_and_version(&mut ctx, xml_tag)?;
+                let (version, xmlns) = read_namespaces_and_version(ctx, xml_tag)?;
                 ctx.book.xmlns.insert("fods.xml".to_string(), xmlns);
                 if let Some(version) = version {
                     ctx.book.set_version(version);
@@ -279,28 +612,28 @@ fn read_fods_impl(read: &mut dyn BufRead, options: &OdsOptions) -> Result<WorkBo
             Event::End(xml_tag) if xml_tag.name().as_ref() == b"office:document" => {}
 
             Event::Start(xml_tag) if xml_tag.name().as_ref() == b"office:meta" => {
-                read_office_meta(&mut ctx, &mut xml)?;
+                read_office_meta(ctx, xml)?;
             }
             Event::Start(xml_tag) if xml_tag.name().as_ref() == b"office:settings" => {
-                read_office_settings(&mut ctx, &mut xml)?;
+                read_office_settings(ctx, xml)?;
             }
             Event::Start(xml_tag) if xml_tag.name().as_ref() == b"office:scripts" => {
-                read_scripts(&mut ctx, &mut xml)?
+                read_scripts(ctx, xml)?
             }
             Event::Start(xml_tag) if xml_tag.name().as_ref() == b"office:font-face-decls" => {
-                read_office_font_face_decls(&mut ctx, &mut xml, StyleOrigin::Content)?
+                read_office_font_face_decls(ctx, xml, StyleOrigin::Content)?
             }
             Event::Start(xml_tag) if xml_tag.name().as_ref() == b"office:styles" => {
-                read_office_styles(&mut ctx, &mut xml, StyleOrigin::Content)?
+                read_office_styles(ctx, xml, StyleOrigin::Content)?
             }
             Event::Start(xml_tag) if xml_tag.name().as_ref() == b"office:automatic-styles" => {
-                read_office_automatic_styles(&mut ctx, &mut xml, StyleOrigin::Content)?
+                read_office_automatic_styles(ctx, xml, StyleOrigin::Content)?
             }
             Event::Start(xml_tag) if xml_tag.name().as_ref() == b"office:master-styles" => {
-                read_office_master_styles(&mut ctx, &mut xml, StyleOrigin::Content)?
+                read_office_master_styles(ctx, xml, StyleOrigin::Content)?
             }
             Event::Start(xml_tag) if xml_tag.name().as_ref() == b"office:body" => {
-                read_office_body(&mut ctx, &mut xml)?;
+                read_office_body(ctx, xml)?;
             }
 
             Event::Decl(_) => {}
@@ -314,21 +647,26 @@ fn read_fods_impl(read: &mut dyn BufRead, options: &OdsOptions) -> Result<WorkBo
     }
     ctx.push_buf(buf);
 
-    calculations(&mut ctx)?;
-
-    // We do some data duplication here, to make everything easier to use.
-    calc_derived(&mut ctx.book)?;
-
-    Ok(ctx.book)
+    Ok(())
 }
 
-fn read_fods_impl_content_only(
-    read: &mut dyn BufRead,
+fn read_fods_impl_content_only<R: BufRead>(
+    read: &mut LineRead<R>,
     options: &OdsOptions,
+    locate: &dyn Fn(&mut XmlLocation),
 ) -> Result<WorkBook, OdsError> {
     let mut ctx = OdsContext::new(options);
-    let mut xml: quick_xml::Reader<&mut dyn BufRead> = quick_xml::Reader::from_reader(read);
+    read_fods_part(&mut ctx, read, locate, read_fods_content_only)?;
+
+    calculations(&mut ctx)?;
 
+    Ok(ctx.book)
+}
+
+fn read_fods_content_only(
+    ctx: &mut OdsContext,
+    xml: &mut OdsXmlReader<'_>,
+) -> Result<(), OdsError> {
     let mut buf = ctx.pop_buf();
     loop {
         let evt = xml.read_event_into(&mut buf)?;
@@ -338,7 +676,7 @@ fn read_fods_impl_content_only(
 
         match &evt {
             Event::Start(xml_tag) if xml_tag.name().as_ref() == b"office:body" => {
-                read_office_body(&mut ctx, &mut xml)?;
+                read_office_body(ctx, xml)?;
             }
             Event::Eof => {
                 break;
@@ -350,9 +688,7 @@ fn read_fods_impl_content_only(
     }
     ctx.push_buf(buf);
 
-    calculations(&mut ctx)?;
-
-    Ok(ctx.book)
+    Ok(())
 }
 
 /// Reads an ODS-file.
@@ -362,51 +698,61 @@ fn read_ods_impl<R: Read + Seek>(
 ) -> Result<WorkBook, OdsError> {
     let mut ctx = OdsContext::new(options);
 
-    if let Ok(z) = zip.by_name("META-INF/manifest.xml") {
-        let mut read = BufReader::new(z);
-        let read: &mut dyn BufRead = &mut read;
-        let mut xml = quick_xml::Reader::from_reader(read);
+    read_ods_package(&mut ctx, &mut zip)?;
 
-        read_ods_manifest(&mut ctx, &mut xml)?;
-    }
+    calculations(&mut ctx)?;
 
-    read_ods_extras(&mut ctx, &mut zip)?;
+    // We do some data duplication here, to make everything easier to use.
+    calc_derived(&mut ctx.book)?;
 
-    if let Ok(z) = zip.by_name("meta.xml") {
-        let mut read = BufReader::new(z);
-        let read: &mut dyn BufRead = &mut read;
-        let mut xml = quick_xml::Reader::from_reader(read);
+    Ok(ctx.book)
+}
 
-        read_ods_metadata(&mut ctx, &mut xml)?;
-    }
+/// Reads an ODS-file without the cell data.
+fn read_ods_impl_lazy<R: Read + Seek>(
+    mut zip: ZipArchive<R>,
+    options: &OdsOptions,
+) -> Result<LazyWorkBook<R>, OdsError> {
+    let mut ctx = OdsContext::new(options);
+    ctx.skip_tables = true;
 
-    if let Ok(z) = zip.by_name("settings.xml") {
-        let mut read = BufReader::new(z);
-        let read: &mut dyn BufRead = &mut read;
-        let mut xml = quick_xml::Reader::from_reader(read);
-        read_ods_settings(&mut ctx, &mut xml)?;
-    }
+    read_ods_package(&mut ctx, &mut zip)?;
 
-    if let Ok(z) = zip.by_name("styles.xml") {
-        let mut read = BufReader::new(z);
-        let read: &mut dyn BufRead = &mut read;
-        let mut xml = quick_xml::Reader::from_reader(read);
-        read_ods_styles(&mut ctx, &mut xml)?;
-    }
+    calc_derived(&mut ctx.book)?;
 
-    {
-        let mut read = BufReader::new(zip.by_name("content.xml")?);
-        let read: &mut dyn BufRead = &mut read;
-        let mut xml = quick_xml::Reader::from_reader(read);
-        read_ods_content(&mut ctx, &mut xml)?;
+    let loaded = vec![false; ctx.book.num_sheets()];
+    Ok(LazyWorkBook {
+        zip,
+        options: options.clone(),
+        book: ctx.book,
+        loaded,
+    })
+}
+
+/// Reads all parts of the package.
+fn read_ods_package<R: Read + Seek>(
+    ctx: &mut OdsContext,
+    zip: &mut ZipArchive<R>,
+) -> Result<(), OdsError> {
+    if zip.index_for_name("META-INF/manifest.xml").is_some() {
+        read_ods_part(ctx, zip, "META-INF/manifest.xml", read_ods_manifest)?;
     }
 
-    calculations(&mut ctx)?;
+    read_ods_extras(ctx, zip)?;
+    read_basic(ctx)?;
 
-    // We do some data duplication here, to make everything easier to use.
-    calc_derived(&mut ctx.book)?;
+    if zip.index_for_name("meta.xml").is_some() {
+        read_ods_part(ctx, zip, "meta.xml", read_ods_metadata)?;
+    }
+    if zip.index_for_name("settings.xml").is_some() {
+        read_ods_part(ctx, zip, "settings.xml", read_ods_settings)?;
+    }
+    if zip.index_for_name("styles.xml").is_some() {
+        read_ods_part(ctx, zip, "styles.xml", read_ods_styles)?;
+    }
+    read_ods_part(ctx, zip, "content.xml", read_ods_content)?;
 
-    Ok(ctx.book)
+    Ok(())
 }
 
 /// Reads an ODS-file.
@@ -416,18 +762,310 @@ fn read_ods_impl_content_only<R: Read + Seek>(
 ) -> Result<WorkBook, OdsError> {
     let mut ctx = OdsContext::new(options);
 
-    let mut read = BufReader::new(zip.by_name("content.xml")?);
-    let read: &mut dyn BufRead = &mut read;
-    let mut xml = quick_xml::Reader::from_reader(read);
-
     // todo: this still reads styles etc from content.xml
-    read_ods_content(&mut ctx, &mut xml)?;
+    read_ods_part(&mut ctx, &mut zip, "content.xml", read_ods_content)?;
 
     calculations(&mut ctx)?;
 
     Ok(ctx.book)
 }
 
+/// Reads one xml part of the package. Errors get the location
+/// within the part attached.
+fn read_ods_part<R: Read + Seek, F>(
+    ctx: &mut OdsContext,
+    zip: &mut ZipArchive<R>,
+    name: &str,
+    read_fn: F,
+) -> Result<(), OdsError>
+where
+    F: FnOnce(&mut OdsContext, &mut OdsXmlReader<'_>) -> Result<(), OdsError>,
+{
+    let res = {
+        let z = zip.by_name(name)?;
+        let mut read = BufReader::new(LimitRead::new(z, ctx.max_entry_size)?);
+        let read: &mut dyn BufRead = &mut read;
+        let mut xml = quick_xml::Reader::from_reader(read);
+        read_fn(ctx, &mut xml).map_err(|e| (xml.buffer_position(), e))
+    };
+
+    res.map_err(|(byte, err)| {
+        xml_error(name, byte, err, |loc| {
+            // Read again up to the error position to find line and path.
+            if let Ok(z) = zip.by_name(name) {
+                let mut data = Vec::new();
+                if z.take(loc.byte).read_to_end(&mut data).is_ok() {
+                    complete_location(loc, &data);
+                }
+            }
+        })
+    })
+}
+
+/// Adds the position in the xml to the error message.
+/// The location is completed by locate.
+fn xml_error<F>(part: &str, byte: u64, err: OdsError, locate: F) -> OdsError
+where
+    F: FnOnce(&mut XmlLocation),
+{
+    if matches!(err, OdsError::Cancelled) {
+        return err;
+    }
+    let mut loc = XmlLocation {
+        part: part.to_string(),
+        byte,
+        ..Default::default()
+    };
+    locate(&mut loc);
+    err.located(&loc)
+}
+
+/// Fills in line, column and the element path, given the xml data
+/// up to the error position.
+pub(crate) fn complete_location(loc: &mut XmlLocation, data: &[u8]) {
+    let data = &data[..min(data.len(), loc.byte as usize)];
+
+    loc.line = data.iter().filter(|v| **v == b'\n').count() as u64 + 1;
+    let line_start = data
+        .iter()
+        .rposition(|v| *v == b'\n')
+        .map(|v| v + 1)
+        .unwrap_or(0);
+    loc.column = (data.len() - line_start) as u64 + 1;
+
+    let mut path = Vec::new();
+    let mut xml = quick_xml::Reader::from_reader(data);
+    loop {
+        match xml.read_event() {
+            Ok(Event::Start(xml_tag)) => {
+                path.push(String::from_utf8_lossy(xml_tag.name().as_ref()).to_string());
+            }
+            Ok(Event::End(_)) => {
+                path.pop();
+            }
+            Ok(Event::Eof) | Err(_) => break,
+            Ok(_) => {}
+        }
+    }
+    loc.path = path;
+}
+
+/// A workbook that loads the cell data of a sheet when it is first accessed.
+///
+/// Styles, metadata, settings and the sheet names with their attributes
+/// are read up front. The zip-archive stays open to load the sheets later.
+/// Each load parses content.xml up to the requested sheet, but only the data
+/// of that sheet is kept.
+///
+/// ```
+/// use std::fs::File;
+/// use std::io::BufReader;
+/// use spreadsheet_ods::OdsOptions;
+///
+/// let f = BufReader::new(File::open("tests/test_read_libreoffice.ods").unwrap());
+/// let mut wb = OdsOptions::default().read_ods_lazy(f).unwrap();
+/// let idx = wb.sheet_idx("Tabelle1").unwrap();
+/// let sheet = wb.sheet(idx).unwrap();
+/// println!("{:?}", sheet.used_grid_size());
+/// ```
+pub struct LazyWorkBook<R: Read + Seek> {
+    zip: ZipArchive<R>,
+    options: OdsOptions,
+    book: WorkBook,
+    loaded: Vec<bool>,
+}
+
+impl<R: Read + Seek> Debug for LazyWorkBook<R> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LazyWorkBook")
+            .field("options", &self.options)
+            .field("book", &self.book)
+            .field("loaded", &self.loaded)
+            .finish()
+    }
+}
+
+impl<R: Read + Seek> LazyWorkBook<R> {
+    /// Number of sheets.
+    pub fn num_sheets(&self) -> usize {
+        self.book.num_sheets()
+    }
+
+    /// Finds the sheet index by the sheet-name.
+    pub fn sheet_idx<S: AsRef<str>>(&self, name: S) -> Option<usize> {
+        self.book.sheet_idx(name)
+    }
+
+    /// Name of the n-th sheet. Doesn't load the sheet.
+    pub fn sheet_name(&self, n: usize) -> &str {
+        self.book.sheet(n).name()
+    }
+
+    /// Has the cell data of the sheet been loaded?
+    pub fn is_loaded(&self, n: usize) -> bool {
+        self.loaded[n]
+    }
+
+    /// Returns the n-th sheet. Loads the cell data on first access.
+    ///
+    /// Panics
+    ///
+    /// Panics if n is out of bounds.
+    pub fn sheet(&mut self, n: usize) -> Result<&Sheet, OdsError> {
+        self.load(n)?;
+        Ok(self.book.sheet(n))
+    }
+
+    /// The workbook with everything read so far. Sheets that are
+    /// not loaded yet contain no cell data.
+    pub fn workbook(&self) -> &WorkBook {
+        &self.book
+    }
+
+    /// Loads all remaining sheets and returns the complete workbook.
+    pub fn into_workbook(mut self) -> Result<WorkBook, OdsError> {
+        for n in 0..self.book.num_sheets() {
+            self.load(n)?;
+        }
+        Ok(self.book)
+    }
+
+    fn load(&mut self, n: usize) -> Result<(), OdsError> {
+        if self.loaded[n] {
+            return Ok(());
+        }
+
+        let mut ctx = OdsContext::new(&self.options);
+        let name = self.book.sheet(n).name().clone();
+        read_ods_part(&mut ctx, &mut self.zip, "content.xml", |ctx, xml| {
+            read_named_table(ctx, xml, &name)
+        })?;
+        calculations(&mut ctx)?;
+
+        if ctx.book.num_sheets() != 1 {
+            return Err(OdsError::Ods(format!(
+                "sheet {} not found in content.xml",
+                name
+            )));
+        }
+        let mut sheet = ctx.book.remove_sheet(0);
+        calc_derived_sheet(&self.book, &mut sheet)?;
+        *self.book.sheet_mut(n) = sheet;
+        self.loaded[n] = true;
+
+        Ok(())
+    }
+}
+
+// Reads only the named table of content.xml. Everything else is skipped.
+fn read_named_table(
+    ctx: &mut OdsContext,
+    xml: &mut OdsXmlReader<'_>,
+    name: &str,
+) -> Result<(), OdsError> {
+    let mut buf = ctx.pop_buf();
+    loop {
+        let evt = xml.read_event_into(&mut buf)?;
+        match &evt {
+            Event::Start(xml_tag) if xml_tag.name().as_ref() == b"table:table" => {
+                let found = match xml_tag.try_get_attribute("table:name")? {
+                    Some(attr) => attr.decode_and_unescape_value(ctx.decoder)? == name,
+                    None => false,
+                };
+                if found {
+                    read_table(ctx, xml, xml_tag)?;
+                    break;
+                }
+                skip_xml(ctx, xml, xml_tag)?;
+            }
+            Event::Eof => {
+                break;
+            }
+            _ => {}
+        }
+        buf.clear();
+    }
+    ctx.push_buf(buf);
+
+    Ok(())
+}
+
+// Skips the element with all its content without parsing it.
+fn skip_xml(
+    ctx: &mut OdsContext,
+    xml: &mut OdsXmlReader<'_>,
+    super_tag: &BytesStart<'_>,
+) -> Result<(), OdsError> {
+    let mut buf = ctx.pop_buf();
+    xml.read_to_end_into(super_tag.name(), &mut buf)?;
+    ctx.push_buf(buf);
+    Ok(())
+}
+
+/// Fails if more than the allowed number of bytes is read from a
+/// zip entry. The size in the zip-header is not trustworthy.
+struct LimitRead<'a, R: Read> {
+    read: zip::read::ZipFile<'a, R>,
+    limit: Option<u64>,
+    count: u64,
+}
+
+impl<'a, R: Read> LimitRead<'a, R> {
+    fn new(read: zip::read::ZipFile<'a, R>, limit: Option<u64>) -> Result<Self, OdsError> {
+        if let Some(limit) = limit {
+            if read.size() > limit {
+                return Err(OdsError::Ods(format!(
+                    "{}: size {} exceeds the limit of {} bytes",
+                    read.name(),
+                    read.size(),
+                    limit
+                )));
+            }
+        }
+        Ok(Self {
+            read,
+            limit,
+            count: 0,
+        })
+    }
+}
+
+impl<R: Read> Read for LimitRead<'_, R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let n = self.read.read(buf)?;
+        self.count += n as u64;
+        if let Some(limit) = self.limit {
+            if self.count > limit {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::InvalidData,
+                    format!(
+                        "{}: size exceeds the limit of {} bytes",
+                        self.read.name(),
+                        limit
+                    ),
+                ));
+            }
+        }
+        Ok(n)
+    }
+}
+
+/// Reads all files of a package as (path, data).
+pub(crate) fn read_package_parts(buf: &[u8]) -> Result<Vec<(String, Vec<u8>)>, OdsError> {
+    let mut zip = ZipArchive::new(Cursor::new(buf))?;
+    let mut parts = Vec::new();
+    for i in 0..zip.len() {
+        let mut file = zip.by_index(i)?;
+        if file.is_dir() {
+            continue;
+        }
+        let mut data = Vec::new();
+        file.read_to_end(&mut data)?;
+        parts.push((file.name().to_string(), data));
+    }
+    Ok(parts)
+}
+
 fn read_ods_extras<R: Read + Seek>(
     ctx: &mut OdsContext,
     zip: &mut ZipArchive<R>,
@@ -438,13 +1076,159 @@ fn read_ods_extras<R: Read + Seek>(
             manifest.full_path.as_str(),
             "/" | "settings.xml" | "styles.xml" | "content.xml" | "meta.xml"
         ) {
-            let mut ze = zip.by_name(manifest.full_path.as_str())?;
+            let ze = zip.by_name(manifest.full_path.as_str())?;
+            let mut ze = LimitRead::new(ze, ctx.max_entry_size)?;
             let mut buf = Vec::new();
             ze.read_to_end(&mut buf)?;
             manifest.buffer = Some(buf);
         }
     }
-
+
+    Ok(())
+}
+
+/// Parses the Basic libraries from the Basic/ directory. The parsed parts
+/// are removed from the manifest, they are written from the libraries.
+/// Password protected libraries stay in the manifest as they are.
+fn read_basic(ctx: &mut OdsContext) -> Result<(), OdsError> {
+    let Some(container) = ctx.book.manifest.remove("Basic/script-lc.xml") else {
+        return Ok(());
+    };
+    let Some(container) = container.buffer else {
+        return Ok(());
+    };
+
+    let mut libraries = Vec::new();
+    parse_basic_part(&container, |name, attrs, _| {
+        if name == b"library:library" {
+            libraries.push(attrs);
+        }
+    })?;
+
+    for attrs in libraries {
+        let attr = |k: &str| attrs.iter().find(|(n, _)| n == k).map(|(_, v)| v.as_str());
+        let Some(name) = attr("library:name") else {
+            continue;
+        };
+        if attr("library:link") == Some("true") {
+            ctx.book.add_script_library(BasicLibrary::new_link(
+                name,
+                attr("xlink:href").unwrap_or_default(),
+            ));
+            continue;
+        }
+
+        let dir = format!("Basic/{}/", name);
+        let Some(buf) = ctx
+            .book
+            .manifest
+            .get(&format!("{}script-lb.xml", dir))
+            .and_then(|v| v.buffer.as_ref())
+        else {
+            continue;
+        };
+        let mut readonly = false;
+        let mut protected = false;
+        let mut elements = Vec::new();
+        parse_basic_part(buf, |name, attrs, _| {
+            let attr = |k: &str| attrs.iter().find(|(n, _)| n == k).map(|(_, v)| v.as_str());
+            if name == b"library:library" {
+                readonly = attr("library:readonly") == Some("true");
+                protected = attr("library:passwordprotected") == Some("true");
+            } else if name == b"library:element" {
+                if let Some(v) = attr("library:name") {
+                    elements.push(v.to_string());
+                }
+            }
+        })?;
+
+        if protected {
+            ctx.book
+                .add_script_library(BasicLibrary::new_protected(name, readonly));
+            continue;
+        }
+
+        let mut library = BasicLibrary::new(name);
+        library.set_readonly(readonly);
+        for element in elements {
+            let Some(buf) = ctx
+                .book
+                .manifest
+                .get(&format!("{}{}.xml", dir, element))
+                .and_then(|v| v.buffer.as_ref())
+            else {
+                continue;
+            };
+            let mut module = BasicModule::new(element, "");
+            parse_basic_part(buf, |name, attrs, text| {
+                if name == b"script:module" {
+                    for (k, v) in attrs {
+                        match k.as_str() {
+                            "script:language" => module.set_language(v),
+                            "script:moduleType" => module.set_module_type(v),
+                            _ => {}
+                        }
+                    }
+                    module.set_source(text);
+                }
+            })?;
+            library.add_module(module);
+        }
+        ctx.book.add_script_library(library);
+
+        ctx.book.manifest.retain(|k, _| !k.starts_with(&dir));
+    }
+
+    Ok(())
+}
+
+/// Parses one of the xml files of the Basic libraries. Calls f with the
+/// name, attributes and text content of each element.
+fn parse_basic_part<F>(buf: &[u8], mut f: F) -> Result<(), OdsError>
+where
+    F: FnMut(&[u8], Vec<(String, String)>, String),
+{
+    let mut xml = quick_xml::Reader::from_reader(buf);
+    let mut stack = Vec::new();
+    let mut buf = Vec::new();
+    loop {
+        let evt = xml.read_event_into(&mut buf)?;
+        match &evt {
+            Event::Start(xml_tag) | Event::Empty(xml_tag) => {
+                let mut attrs = Vec::new();
+                for attr in xml_tag.attributes().with_checks(false) {
+                    let attr = attr?;
+                    attrs.push((
+                        from_utf8(attr.key.as_ref())?.to_string(),
+                        attr.decode_and_unescape_value(xml.decoder())?.to_string(),
+                    ));
+                }
+                if matches!(evt, Event::Empty(_)) {
+                    f(xml_tag.name().as_ref(), attrs, String::new());
+                } else {
+                    stack.push((xml_tag.name().as_ref().to_vec(), attrs, String::new()));
+                }
+            }
+            Event::Text(text) => {
+                if let Some((_, _, content)) = stack.last_mut() {
+                    content.push_str(&text.unescape()?);
+                }
+            }
+            Event::CData(text) => {
+                if let Some((_, _, content)) = stack.last_mut() {
+                    content.push_str(from_utf8(text.as_ref())?);
+                }
+            }
+            Event::End(_) => {
+                if let Some((name, attrs, content)) = stack.pop() {
+                    f(&name, attrs, content);
+                }
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+        buf.clear();
+    }
     Ok(())
 }
 
@@ -502,7 +1286,7 @@ fn calculations(ctx: &mut OdsContext) -> Result<(), OdsError> {
         if ctx.use_repeat_for_cells {
             calc_repeat_sheet(ctx.book.sheet_mut(i))?;
         } else {
-            calc_cloned_sheet(ctx.book.sheet_mut(i))?;
+            calc_cloned_sheet(ctx.book.sheet_mut(i), ctx.max_repeat)?;
         }
     }
     Ok(())
@@ -523,11 +1307,7 @@ fn calc_repeat_sheet(sheet: &mut Sheet) -> Result<(), OdsError> {
 
     // clone by cell-repeat
     let mut it = CellDataIterMut::new(sheet.data.range_mut(..));
-    loop {
-        let Some(((row, col), data)) = it.next() else {
-            break;
-        };
-
+    while let Some(((row, col), data)) = it.next() {
         if data.repeat > 1 {
             let last_in_row = if let Some((next_row, _next_col)) = it.peek_cell() {
                 row != next_row
@@ -549,7 +1329,7 @@ fn calc_repeat_sheet(sheet: &mut Sheet) -> Result<(), OdsError> {
 }
 
 // Clone cell-data.
-fn calc_cloned_sheet(sheet: &mut Sheet) -> Result<(), OdsError> {
+fn calc_cloned_sheet(sheet: &mut Sheet, max_repeat: Option<u32>) -> Result<(), OdsError> {
     let mut cloned = Vec::new();
     let mut dropped = Vec::new();
 
@@ -564,7 +1344,10 @@ fn calc_cloned_sheet(sheet: &mut Sheet) -> Result<(), OdsError> {
     // duplicate by row-repeat
     for (row, rh) in sheet.row_header.iter().filter(|(_, v)| v.repeat > 1) {
         // get one row
-        let cit = CellDataIter::new(sheet.data.range((*row, 0)..(row + 1, 0)));
+        let mut cit = CellDataIter::new(sheet.data.range((*row, 0)..(row + 1, 0))).peekable();
+        if cit.peek().is_some() {
+            check_repeat(max_repeat, rh.repeat, &sheet.name, "row", *row, 0)?;
+        }
         for ((row, col), data) in cit {
             for i in 1..rh.repeat {
                 cloned.push((row + i, col, data.clone()));
@@ -583,11 +1366,7 @@ fn calc_cloned_sheet(sheet: &mut Sheet) -> Result<(), OdsError> {
     // clone by cell-repeat
 
     let mut it = CellDataIterMut::new(sheet.data.range_mut(..));
-    loop {
-        let Some(((row, col), data)) = it.next() else {
-            break;
-        };
-
+    while let Some(((row, col), data)) = it.next() {
         if data.repeat > 1 {
             let repeat = mem::replace(&mut data.repeat, 1);
 
@@ -602,6 +1381,7 @@ fn calc_cloned_sheet(sheet: &mut Sheet) -> Result<(), OdsError> {
                 continue;
             }
 
+            check_repeat(max_repeat, repeat, &sheet.name, "col", row, col)?;
             for i in 1..repeat {
                 cloned.push((row, col + i, data.clone()));
             }
@@ -617,6 +1397,24 @@ fn calc_cloned_sheet(sheet: &mut Sheet) -> Result<(), OdsError> {
     Ok(())
 }
 
+// Checks the repeat count against the limit.
+fn check_repeat(
+    max_repeat: Option<u32>,
+    repeat: u32,
+    sheet: &str,
+    kind: &str,
+    row: u32,
+    col: u32,
+) -> Result<(), OdsError> {
+    match max_repeat {
+        Some(max_repeat) if repeat > max_repeat => Err(OdsError::Ods(format!(
+            "{}: {}-repeat of {} for row/col {}/{} exceeds the limit of {}",
+            sheet, kind, repeat, row, col, max_repeat
+        ))),
+        _ => Ok(()),
+    }
+}
+
 // Sets some values from the styles on the corresponding data fields.
 fn calc_derived(book: &mut WorkBook) -> Result<(), OdsError> {
     let v = book
@@ -643,90 +1441,175 @@ fn calc_derived(book: &mut WorkBook) -> Result<(), OdsError> {
     if let Some(ConfigValue::Boolean(n)) = v {
         book.config_mut().show_page_breaks = *n;
     }
+    let v = book
+        .config
+        .get_value(&["ooo:view-settings", "Views", "0", "ShowPageBreakPreview"]);
+    if let Some(ConfigValue::Boolean(n)) = v {
+        book.config_mut().show_page_break_preview = *n;
+    }
 
     for i in 0..book.num_sheets() {
         let mut sheet = book.detach_sheet(i);
+        calc_derived_sheet(book, &mut sheet)?;
+        book.attach_sheet(sheet);
+    }
 
-        // Set the column widths.
-        for ch in sheet.col_header.values_mut() {
-            if let Some(style_name) = &ch.style {
-                if let Some(style) = book.colstyle(style_name) {
-                    if style.use_optimal_col_width()? {
-                        ch.width = Length::Default;
-                    } else {
-                        ch.width = style.col_width()?;
-                    }
+    Ok(())
+}
+
+// Sets the values from styles and settings for one sheet.
+fn calc_derived_sheet(book: &WorkBook, sheet: &mut Sheet) -> Result<(), OdsError> {
+    // Tab color and direction.
+    if let Some(style) = sheet.style.as_ref().and_then(|v| book.tablestyle(v)) {
+        sheet.tab_color = style.tab_color()?;
+        sheet.rtl = matches!(
+            style.writing_mode()?,
+            Some(WritingMode::RlTb | WritingMode::Rl)
+        );
+        sheet.masterpage = style
+            .attrmap()
+            .attr("style:master-page-name")
+            .map(MasterPageRef::from);
+    }
+
+    // Page order and scaling.
+    if let Some(pagestyle) = sheet
+        .masterpage
+        .as_ref()
+        .and_then(|v| book.masterpage(v))
+        .and_then(|v| v.pagestyle())
+        .and_then(|v| book.pagestyle(v))
+    {
+        sheet.print_page_order = match pagestyle.style().attr("style:print-page-order") {
+            Some("ltr") => Some(PrintOrder::Ltr),
+            Some("ttb") => Some(PrintOrder::Ttb),
+            _ => None,
+        };
+        sheet.print_scale = if let Some(Percent::Percent(v)) =
+            Percent::parse_attr(pagestyle.style().attr("style:scale-to"))?
+        {
+            Some(PrintScale::Percent(v))
+        } else if let Some(v) = pagestyle.style().attr("style:scale-to-pages") {
+            Some(PrintScale::Pages(v.parse()?))
+        } else {
+            None
+        };
+    }
+
+    // Set the column widths.
+    for ch in sheet.col_header.values_mut() {
+        if let Some(style_name) = &ch.style {
+            if let Some(style) = book.colstyle(style_name) {
+                if style.use_optimal_col_width()? {
+                    ch.width = Length::Default;
+                } else {
+                    ch.width = style.col_width()?;
                 }
+                ch.page_break = style.colstyle().attr("fo:break-before") == Some("page");
             }
         }
+    }
 
-        // Set the row heights
-        for rh in sheet.row_header.values_mut() {
-            if let Some(style_name) = &rh.style {
-                if let Some(style) = book.rowstyle(style_name) {
-                    if style.use_optimal_row_height()? {
-                        rh.height = Length::Default;
-                    } else {
-                        rh.height = style.row_height()?;
-                    }
+    // Set the row heights
+    for rh in sheet.row_header.values_mut() {
+        if let Some(style_name) = &rh.style {
+            if let Some(style) = book.rowstyle(style_name) {
+                if style.use_optimal_row_height()? {
+                    rh.height = Length::Default;
+                } else {
+                    rh.height = style.row_height()?;
                 }
+                rh.page_break = style.rowstyle().attr("fo:break-before") == Some("page");
             }
         }
+    }
 
-        let v = book.config.get(&[
-            "ooo:view-settings",
-            "Views",
-            "0",
-            "Tables",
-            sheet.name().as_str(),
-        ]);
+    // Time values with a date format or a format that wraps at 24 hours
+    // are a time of day.
+    for cell in sheet.data.values_mut() {
+        let Value::TimeDuration(d) = cell.value else {
+            continue;
+        };
+        if d < Duration::zero() || d >= Duration::days(1) {
+            continue;
+        }
+        let Some(format) = cell
+            .style
+            .as_ref()
+            .and_then(|v| book.cellstyle(v))
+            .and_then(|v| v.value_format())
+        else {
+            continue;
+        };
+        let time_of_day = book.datetime_format(format).is_some()
+            || book
+                .timeduration_format(format)
+                .is_some_and(|v| v.attrmap().attr("number:truncate-on-overflow") != Some("false"));
+        if time_of_day {
+            cell.value = Value::Time(NaiveTime::MIN + d);
+        }
+    }
 
-        if let Some(cc) = v {
-            if let Some(ConfigValue::Int(n)) = cc.get_value_rec(&["CursorPositionX"]) {
-                sheet.config_mut().cursor_x = *n as u32;
-            }
-            if let Some(ConfigValue::Int(n)) = cc.get_value_rec(&["CursorPositionY"]) {
-                sheet.config_mut().cursor_y = *n as u32;
-            }
-            if let Some(ConfigValue::Short(n)) = cc.get_value_rec(&["HorizontalSplitMode"]) {
-                sheet.config_mut().hor_split_mode = SplitMode::try_from(*n)?;
-            }
-            if let Some(ConfigValue::Short(n)) = cc.get_value_rec(&["VerticalSplitMode"]) {
-                sheet.config_mut().vert_split_mode = SplitMode::try_from(*n)?;
-            }
-            if let Some(ConfigValue::Int(n)) = cc.get_value_rec(&["HorizontalSplitPosition"]) {
-                sheet.config_mut().hor_split_pos = *n as u32;
-            }
-            if let Some(ConfigValue::Int(n)) = cc.get_value_rec(&["VerticalSplitPosition"]) {
-                sheet.config_mut().vert_split_pos = *n as u32;
-            }
-            if let Some(ConfigValue::Short(n)) = cc.get_value_rec(&["ActiveSplitRange"]) {
-                sheet.config_mut().active_split_range = *n;
-            }
-            if let Some(ConfigValue::Int(n)) = cc.get_value_rec(&["PositionLeft"]) {
-                sheet.config_mut().position_left = *n as u32;
-            }
-            if let Some(ConfigValue::Int(n)) = cc.get_value_rec(&["PositionRight"]) {
-                sheet.config_mut().position_right = *n as u32;
-            }
-            if let Some(ConfigValue::Int(n)) = cc.get_value_rec(&["PositionTop"]) {
-                sheet.config_mut().position_top = *n as u32;
-            }
-            if let Some(ConfigValue::Int(n)) = cc.get_value_rec(&["PositionBottom"]) {
-                sheet.config_mut().position_bottom = *n as u32;
-            }
-            if let Some(ConfigValue::Short(n)) = cc.get_value_rec(&["ZoomType"]) {
-                sheet.config_mut().zoom_type = *n;
-            }
-            if let Some(ConfigValue::Int(n)) = cc.get_value_rec(&["ZoomValue"]) {
-                sheet.config_mut().zoom_value = *n;
-            }
-            if let Some(ConfigValue::Boolean(n)) = cc.get_value_rec(&["ShowGrid"]) {
-                sheet.config_mut().show_grid = *n;
+    let v = book.config.get(&[
+        "ooo:view-settings",
+        "Views",
+        "0",
+        "Tables",
+        sheet.name().as_str(),
+    ]);
+
+    if let Some(cc) = v {
+        if let Some(ConfigValue::Int(n)) = cc.get_value_rec(&["CursorPositionX"]) {
+            sheet.config_mut().cursor_x = *n as u32;
+        }
+        if let Some(ConfigValue::Int(n)) = cc.get_value_rec(&["CursorPositionY"]) {
+            sheet.config_mut().cursor_y = *n as u32;
+        }
+        if let Some(ConfigValue::Short(n)) = cc.get_value_rec(&["HorizontalSplitMode"]) {
+            sheet.config_mut().hor_split_mode = SplitMode::try_from(*n)?;
+        }
+        if let Some(ConfigValue::Short(n)) = cc.get_value_rec(&["VerticalSplitMode"]) {
+            sheet.config_mut().vert_split_mode = SplitMode::try_from(*n)?;
+        }
+        if let Some(ConfigValue::Int(n)) = cc.get_value_rec(&["HorizontalSplitPosition"]) {
+            sheet.config_mut().hor_split_pos = *n as u32;
+        }
+        if let Some(ConfigValue::Int(n)) = cc.get_value_rec(&["VerticalSplitPosition"]) {
+            sheet.config_mut().vert_split_pos = *n as u32;
+        }
+        if let Some(ConfigValue::Short(n)) = cc.get_value_rec(&["ActiveSplitRange"]) {
+            sheet.config_mut().active_split_range = *n;
+        }
+        if let Some(ConfigValue::Int(n)) = cc.get_value_rec(&["PositionLeft"]) {
+            sheet.config_mut().position_left = *n as u32;
+        }
+        if let Some(ConfigValue::Int(n)) = cc.get_value_rec(&["PositionRight"]) {
+            sheet.config_mut().position_right = *n as u32;
+        }
+        if let Some(ConfigValue::Int(n)) = cc.get_value_rec(&["PositionTop"]) {
+            sheet.config_mut().position_top = *n as u32;
+        }
+        if let Some(ConfigValue::Int(n)) = cc.get_value_rec(&["PositionBottom"]) {
+            sheet.config_mut().position_bottom = *n as u32;
+        }
+        if let Some(ConfigValue::Short(n)) = cc.get_value_rec(&["ZoomType"]) {
+            sheet.config_mut().zoom_type = *n;
+        }
+        if let Some(ConfigValue::Int(n)) = cc.get_value_rec(&["ZoomValue"]) {
+            sheet.config_mut().zoom_value = *n;
+        }
+        if let Some(ConfigValue::Int(n)) = cc.get_value_rec(&["PageViewZoomValue"]) {
+            sheet.config_mut().page_view_zoom_value = *n;
+        }
+        if let Some(ConfigValue::Boolean(n)) = cc.get_value_rec(&["ShowGrid"]) {
+            sheet.config_mut().show_grid = *n;
+        }
+        if let Some(ConfigValue::Int(n)) = cc.get_value_rec(&["TabColor"]) {
+            // -1 is the automatic color.
+            if sheet.tab_color.is_none() && *n >= 0 {
+                sheet.tab_color = Some(Rgb::new((*n >> 16) as u8, (*n >> 8) as u8, *n as u8));
             }
         }
-
-        book.attach_sheet(sheet);
     }
 
     Ok(())
@@ -752,6 +1635,19 @@ fn read_ods_content(ctx: &mut OdsContext, xml: &mut OdsXmlReader<'_>) -> Result<
             }
             Event::End(xml_tag) if xml_tag.name().as_ref() == b"office:document-content" => {}
 
+            Event::Start(xml_tag)
+                if ctx.values_only
+                    && matches!(
+                        xml_tag.name().as_ref(),
+                        b"office:scripts"
+                            | b"office:font-face-decls"
+                            | b"office:styles"
+                            | b"office:automatic-styles"
+                            | b"office:master-styles"
+                    ) =>
+            {
+                skip_xml(ctx, xml, xml_tag)?
+            }
             Event::Empty(xml_tag) if xml_tag.name().as_ref() == b"office:scripts" => {}
             Event::Start(xml_tag) if xml_tag.name().as_ref() == b"office:scripts" => {
                 read_scripts(ctx, xml)?
@@ -805,6 +1701,15 @@ fn read_office_body(ctx: &mut OdsContext, xml: &mut OdsXmlReader<'_>) -> Result<
             Event::Start(xml_tag) if xml_tag.name().as_ref() == b"office:spreadsheet" => {}
             Event::End(xml_tag) if xml_tag.name().as_ref() == b"office:spreadsheet" => {}
 
+            Event::Start(xml_tag)
+                if ctx.values_only
+                    && xml_tag.name().as_ref() != b"table:table"
+                    && xml_tag.name().as_ref() != b"office:spreadsheet" =>
+            {
+                skip_xml(ctx, xml, xml_tag)?
+            }
+            Event::Empty(_) if ctx.values_only => {}
+
             Event::Start(xml_tag) if xml_tag.name().as_ref() == b"table:content-validations" => {
                 read_validations(ctx, xml)?
             }
@@ -812,10 +1717,14 @@ fn read_office_body(ctx: &mut OdsContext, xml: &mut OdsXmlReader<'_>) -> Result<
                 read_table(ctx, xml, xml_tag)?
             }
 
+            Event::Empty(xml_tag) | Event::Start(xml_tag)
+                if xml_tag.name().as_ref() == b"table:calculation-settings" =>
+            {
+                read_calc_settings(ctx, xml, xml_tag, empty_tag)?
+            }
             // from the prelude
             Event::Empty(xml_tag) | Event::Start(xml_tag)
-                if xml_tag.name().as_ref() == b"table:calculation-settings"
-                    || xml_tag.name().as_ref() == b"table:label-ranges"
+                if xml_tag.name().as_ref() == b"table:label-ranges"
                     || xml_tag.name().as_ref() == b"table:tracked-changes"
                     || xml_tag.name().as_ref() == b"text:alphabetical-index-auto-mark-file"
                     || xml_tag.name().as_ref() == b"text:dde-connection-decls"
@@ -831,17 +1740,27 @@ fn read_office_body(ctx: &mut OdsContext, xml: &mut OdsXmlReader<'_>) -> Result<
                 if xml_tag.name().as_ref() == b"table:consolidation"
                     || xml_tag.name().as_ref() == b"table:data-pilot-tables"
                     || xml_tag.name().as_ref() == b"table:database-ranges"
-                    || xml_tag.name().as_ref() == b"table:dde-links"
                     || xml_tag.name().as_ref() == b"table:named-expressions"
                     || xml_tag.name().as_ref() == b"calcext:conditional-formats" =>
             {
                 let v = read_xml(ctx, xml, xml_tag, empty_tag)?;
                 ctx.book.extra.push(v);
             }
+            Event::Empty(xml_tag) | Event::Start(xml_tag)
+                if xml_tag.name().as_ref() == b"table:dde-links" =>
+            {
+                let v = read_xml(ctx, xml, xml_tag, empty_tag)?;
+                for link in v.content() {
+                    if let XmlContent::Tag(link) = link {
+                        if link.name() == "table:dde-link" {
+                            ctx.book.dde_links.push(DdeLink::from_xml(link)?);
+                        }
+                    }
+                }
+            }
             // from the prelude
             Event::End(xml_tag)
-                if xml_tag.name().as_ref() == b"table:calculation-settings"
-                    || xml_tag.name().as_ref() == b"table:label-ranges"
+                if xml_tag.name().as_ref() == b"table:label-ranges"
                     || xml_tag.name().as_ref() == b"table:tracked-changes"
                     || xml_tag.name().as_ref() == b"text:alphabetical-index-auto-mark-file"
                     || xml_tag.name().as_ref() == b"text:dde-connection-decls"
@@ -853,7 +1772,6 @@ fn read_office_body(ctx: &mut OdsContext, xml: &mut OdsXmlReader<'_>) -> Result<
                 if xml_tag.name().as_ref() == b"table:consolidation"
                     || xml_tag.name().as_ref() == b"table:data-pilot-tables"
                     || xml_tag.name().as_ref() == b"table:database-ranges"
-                    || xml_tag.name().as_ref() == b"table:dde-links"
                     || xml_tag.name().as_ref() == b"table:named-expressions" => {}
 
             Event::Eof => {
@@ -920,6 +1838,27 @@ fn read_table(
 
     read_table_attr(ctx, &mut sheet, super_tag)?;
 
+    if let Some(sheets) = &ctx.sheets {
+        if !sheets.contains(&sheet.name) {
+            return skip_xml(ctx, xml, super_tag);
+        }
+    }
+
+    if let Some(max_sheets) = ctx.max_sheets {
+        if ctx.book.num_sheets() >= max_sheets {
+            return Err(OdsError::Ods(format!(
+                "number of sheets exceeds the limit of {}",
+                max_sheets
+            )));
+        }
+    }
+
+    if ctx.skip_tables {
+        skip_xml(ctx, xml, super_tag)?;
+        ctx.book.push_sheet(sheet);
+        return Ok(());
+    }
+
     // Cell
     let mut row: u32 = 0;
     let mut col: u32 = 0;
@@ -967,13 +1906,27 @@ fn read_table(
                     || xml_tag.name().as_ref() == b"office:forms"
                     || xml_tag.name().as_ref() == b"table:shapes" => {}
 
+            Event::Start(xml_tag) if xml_tag.name().as_ref() == b"office:event-listeners" => {
+                for v in read_event_listeners(ctx, xml)? {
+                    sheet.add_event_listener(v);
+                }
+            }
+            Event::Empty(xml_tag) if xml_tag.name().as_ref() == b"office:event-listeners" => {}
+
             // Epilogue
             Event::Start(xml_tag) | Event::Empty(xml_tag)
-                if xml_tag.name().as_ref() == b"table:named-expressions"
-                    || xml_tag.name().as_ref() == b"calcext:conditional-formats" =>
+                if xml_tag.name().as_ref() == b"table:named-expressions" =>
             {
                 sheet.extra.push(read_xml(ctx, xml, xml_tag, empty_tag)?);
             }
+            Event::Start(xml_tag) | Event::Empty(xml_tag)
+                if xml_tag.name().as_ref() == b"calcext:conditional-formats" =>
+            {
+                let v = read_xml(ctx, xml, xml_tag, empty_tag)?;
+                if let Some(v) = read_conditional_formats(&mut sheet, v) {
+                    sheet.extra.push(v);
+                }
+            }
             Event::End(xml_tag)
                 if xml_tag.name().as_ref() == b"table:named-expressions"
                     || xml_tag.name().as_ref() == b"calcext:conditional-formats" => {}
@@ -1051,8 +2004,10 @@ fn read_table(
                 // noop
             }
             Event::Start(xml_tag) if xml_tag.name().as_ref() == b"table:table-row" => {
+                ctx.check_cancel()?;
                 col = 0;
-                row_repeat = read_table_row_attr(ctx, &mut sheet, row, xml_tag)?;
+                row_repeat = read_table_row_attr(ctx, &mut sheet, row, xml_tag)
+                    .map_err(|e| position_error(&sheet, row, col, e))?;
             }
             Event::End(xml_tag) if xml_tag.name().as_ref() == b"table:table-row" => {
                 if col_data {
@@ -1072,7 +2027,8 @@ fn read_table(
                     || xml_tag.name().as_ref() == b"table:covered-table-cell" =>
             {
                 let (cell_repeat, have_data) =
-                    read_table_cell(ctx, xml, &mut sheet, row, col, xml_tag, empty_tag)?;
+                    read_table_cell(ctx, xml, &mut sheet, row, col, xml_tag, empty_tag)
+                        .map_err(|e| position_error(&sheet, row, col, e))?;
                 col += cell_repeat;
                 col_data |= have_data;
             }
@@ -1088,6 +2044,10 @@ fn read_table(
     ctx.push_colgroup_buf(col_group);
     ctx.push_rowgroup_buf(row_group);
 
+    // groups are collected in the order of their end-tags.
+    sort_groups(&mut sheet.group_cols);
+    sort_groups(&mut sheet.group_rows);
+
     ctx.book.push_sheet(sheet);
 
     Ok(())
@@ -1118,6 +2078,17 @@ fn read_table_attr(
                 let v = attr.decode_and_unescape_value(ctx.decoder)?;
                 sheet.print_ranges = parse_cellranges(v.as_ref())?;
             }
+            attr if attr.key.as_ref() == b"table:protected" => {
+                sheet.set_protected(parse_bool(&attr.value)?);
+            }
+            attr if attr.key.as_ref() == b"table:protection-key" => {
+                let v = attr.decode_and_unescape_value(ctx.decoder)?;
+                sheet.protection_key = Some(v.to_string());
+            }
+            attr if attr.key.as_ref() == b"table:protection-key-digest-algorithm" => {
+                let v = attr.decode_and_unescape_value(ctx.decoder)?;
+                sheet.protection_key_digest = Some(v.to_string());
+            }
             attr => {
                 unused_attr("read_table_attr", super_tag.name().as_ref(), &attr)?;
             }
@@ -1141,7 +2112,7 @@ fn read_table_row_attr(
         match attr? {
             // table:default-cell-style-name 19.615, table:visibility 19.749 and xml:id 19.914.
             attr if attr.key.as_ref() == b"table:number-rows-repeated" => {
-                row_repeat = parse_u32(&attr.value)?;
+                row_repeat = recover(ctx, parse_u32(&attr.value))?.unwrap_or(1);
             }
             attr if attr.key.as_ref() == b"table:style-name" => {
                 let name = attr.decode_and_unescape_value(ctx.decoder)?;
@@ -1154,11 +2125,18 @@ fn read_table_row_attr(
                     Some(CellStyleRef::from(name.as_ref()));
             }
             attr if attr.key.as_ref() == b"table:visibility" => {
-                let visible = parse_visibility(&attr.value)?;
-                row_header.get_or_insert_with(RowHeader::default).visible = visible;
+                if let Some(visible) = recover(ctx, parse_visibility(&attr.value))? {
+                    row_header.get_or_insert_with(RowHeader::default).visible = visible;
+                }
             }
             attr => {
-                unused_attr("read_table_row_attr", super_tag.name().as_ref(), &attr)?;
+                // Keep everything else for writing.
+                let k = from_utf8(attr.key.as_ref())?;
+                let v = attr.decode_and_unescape_value(ctx.decoder)?;
+                row_header
+                    .get_or_insert_with(RowHeader::default)
+                    .attr
+                    .set_attr(k, v.to_string());
             }
         }
     }
@@ -1282,11 +2260,15 @@ enum TextContent {
 struct ReadTableCell {
     val_type: ValueType,
     val_datetime: Option<NaiveDateTime>,
+    val_date_only: bool,
     val_duration: Option<Duration>,
     val_float: Option<f64>,
+    #[cfg(feature = "rust_decimal")]
+    val_decimal: Option<Decimal>,
     val_bool: Option<bool>,
     val_string: Option<String>,
     val_currency: Option<String>,
+    val_error: bool,
 
     content: TextContent,
 }
@@ -1313,21 +2295,25 @@ fn read_table_cell(
     let mut tc = ReadTableCell {
         val_type: ValueType::Empty,
         val_datetime: None,
+        val_date_only: false,
         val_duration: None,
         val_float: None,
+        #[cfg(feature = "rust_decimal")]
+        val_decimal: None,
         val_bool: None,
         val_string: None,
         val_currency: None,
+        val_error: false,
         content: TextContent::Empty,
     };
 
     for attr in super_tag.attributes().with_checks(false) {
         match attr? {
             attr if attr.key.as_ref() == b"table:number-columns-repeated" => {
-                repeat = parse_u32(&attr.value)?;
+                repeat = recover(ctx, parse_u32(&attr.value))?.unwrap_or(1);
             }
             attr if attr.key.as_ref() == b"table:number-rows-spanned" => {
-                let row_span = parse_u32(&attr.value)?;
+                let row_span = recover(ctx, parse_u32(&attr.value))?.unwrap_or(1);
                 if row_span > 1 {
                     cell.get_or_insert_with(CellData::default)
                         .extra_mut()
@@ -1336,7 +2322,7 @@ fn read_table_cell(
                 }
             }
             attr if attr.key.as_ref() == b"table:number-columns-spanned" => {
-                let col_span = parse_u32(&attr.value)?;
+                let col_span = recover(ctx, parse_u32(&attr.value))?.unwrap_or(1);
                 if col_span > 1 {
                     cell.get_or_insert_with(CellData::default)
                         .extra_mut()
@@ -1345,7 +2331,7 @@ fn read_table_cell(
                 }
             }
             attr if attr.key.as_ref() == b"table:number-matrix-rows-spanned" => {
-                let row_span = parse_u32(&attr.value)?;
+                let row_span = recover(ctx, parse_u32(&attr.value))?.unwrap_or(1);
                 if row_span > 1 {
                     cell.get_or_insert_with(CellData::default)
                         .extra_mut()
@@ -1354,7 +2340,7 @@ fn read_table_cell(
                 }
             }
             attr if attr.key.as_ref() == b"table:number-matrix-columns-spanned" => {
-                let col_span = parse_u32(&attr.value)?;
+                let col_span = recover(ctx, parse_u32(&attr.value))?.unwrap_or(1);
                 if col_span > 1 {
                     cell.get_or_insert_with(CellData::default)
                         .extra_mut()
@@ -1362,15 +2348,17 @@ fn read_table_cell(
                         .col_span = col_span;
                 }
             }
+            attr if ctx.values_only
+                && matches!(
+                    attr.key.as_ref(),
+                    b"table:content-validation-name" | b"table:style-name"
+                ) => {}
             attr if attr.key.as_ref() == b"table:content-validation-name" => {
                 let name = attr.decode_and_unescape_value(ctx.decoder)?;
                 cell.get_or_insert_with(CellData::default)
                     .extra_mut()
                     .validation_name = Some(ValidationRef::from(name.as_ref()));
             }
-            attr if attr.key.as_ref() == b"calcext:value-type" => {
-                // not used. office:value-type seems to be good enough.
-            }
             attr if attr.key.as_ref() == b"office:value-type" => {
                 cell.get_or_insert_with(CellData::default);
                 tc.val_type = match attr.value.as_ref() {
@@ -1381,6 +2369,7 @@ fn read_table_cell(
                     b"time" => ValueType::TimeDuration,
                     b"boolean" => ValueType::Boolean,
                     b"currency" => ValueType::Currency,
+                    _ if ctx.lossy => ValueType::Text,
                     other => {
                         return Err(OdsError::Parse(
                             "Unknown cell-type {:?}",
@@ -1391,19 +2380,24 @@ fn read_table_cell(
             }
             attr if attr.key.as_ref() == b"office:date-value" => {
                 cell.get_or_insert_with(CellData::default);
-                tc.val_datetime = Some(parse_datetime(&attr.value)?);
+                tc.val_datetime = recover(ctx, parse_datetime(&attr.value))?;
+                tc.val_date_only = !attr.value.contains(&b'T');
             }
             attr if attr.key.as_ref() == b"office:time-value" => {
                 cell.get_or_insert_with(CellData::default);
-                tc.val_duration = Some(parse_duration(&attr.value)?);
+                tc.val_duration = recover(ctx, parse_duration(&attr.value))?;
             }
             attr if attr.key.as_ref() == b"office:value" => {
                 cell.get_or_insert_with(CellData::default);
-                tc.val_float = Some(parse_f64(&attr.value)?);
+                tc.val_float = recover(ctx, parse_f64(&attr.value))?;
+                #[cfg(feature = "rust_decimal")]
+                if ctx.use_decimal {
+                    tc.val_decimal = recover(ctx, parse_decimal(&attr.value))?;
+                }
             }
             attr if attr.key.as_ref() == b"office:boolean-value" => {
                 cell.get_or_insert_with(CellData::default);
-                tc.val_bool = Some(parse_bool(&attr.value)?);
+                tc.val_bool = recover(ctx, parse_bool(&attr.value))?;
             }
             attr if attr.key.as_ref() == b"office:string-value" => {
                 cell.get_or_insert_with(CellData::default);
@@ -1411,19 +2405,37 @@ fn read_table_cell(
             }
             attr if attr.key.as_ref() == b"office:currency" => {
                 cell.get_or_insert_with(CellData::default);
-                tc.val_currency = Some(parse_currency(&attr.value)?);
+                tc.val_currency = recover(ctx, parse_currency(&attr.value))?;
+            }
+            attr if attr.key.as_ref() == b"calcext:value-type"
+                && attr.value.as_ref() == b"error" =>
+            {
+                cell.get_or_insert_with(CellData::default);
+                tc.val_error = true;
             }
             attr if attr.key.as_ref() == b"table:formula" => {
-                cell.get_or_insert_with(CellData::default).formula =
-                    Some(attr.decode_and_unescape_value(ctx.decoder)?.to_string());
+                let formula = attr.decode_and_unescape_value(ctx.decoder)?;
+                cell.get_or_insert_with(CellData::default).formula = if ctx.plain_formulas {
+                    Some(formula_from_plain(&formula))
+                } else {
+                    Some(formula.to_string())
+                };
             }
             attr if attr.key.as_ref() == b"table:style-name" => {
                 let name = attr.decode_and_unescape_value(ctx.decoder)?;
                 cell.get_or_insert_with(CellData::default).style =
                     Some(CellStyleRef::from(name.as_ref()));
             }
+            _ if ctx.values_only => {}
             attr => {
-                unused_attr("read_table_cell2", super_tag.name().as_ref(), &attr)?;
+                // Keep everything else for writing. This includes
+                // calcext:value-type.
+                let k = from_utf8(attr.key.as_ref())?;
+                let v = attr.decode_and_unescape_value(ctx.decoder)?;
+                cell.get_or_insert_with(CellData::default)
+                    .extra_mut()
+                    .attr
+                    .set_attr(k, v.to_string());
             }
         }
     }
@@ -1436,12 +2448,33 @@ fn read_table_cell(
                 println!(" read_table_cell {:?}", evt);
             }
             match &evt {
-                Event::Empty(xml_tag) if xml_tag.name().as_ref() == b"text:p" => {}
-                Event::Start(xml_tag) if xml_tag.name().as_ref() == b"text:p" => {
-                    let new_txt = read_text_or_tag(ctx, xml, xml_tag, false)?;
-                    tc.content = append_text(new_txt, tc.content);
+                Event::Start(xml_tag) | Event::Empty(xml_tag)
+                    if xml_tag.name().as_ref() == b"text:p" =>
+                {
+                    let empty_tag = matches!(evt, Event::Empty(_));
+                    let new_txt = match read_text_or_tag(ctx, xml, xml_tag, empty_tag)? {
+                        TextContent::Empty => TextContent::Text(String::new()),
+                        TextContent::Xml(tag) => match plain_paragraph(&tag, ctx.max_repeat) {
+                            Some(txt) => TextContent::Text(txt),
+                            None => TextContent::Xml(tag),
+                        },
+                        new_txt => new_txt,
+                    };
+                    tc.content = match (tc.content, new_txt) {
+                        // plain paragraphs are lines of one text.
+                        (TextContent::Text(mut txt), TextContent::Text(new_txt)) => {
+                            txt.push('\n');
+                            txt.push_str(&new_txt);
+                            TextContent::Text(txt)
+                        }
+                        (content, new_txt) => append_text(new_txt, content),
+                    };
                 }
 
+                Event::Start(xml_tag) if ctx.values_only => {
+                    skip_xml(ctx, xml, xml_tag)?;
+                }
+                Event::Empty(_) if ctx.values_only => {}
                 Event::Start(xml_tag) if xml_tag.name().as_ref() == b"office:annotation" => {
                     let annotation = read_annotation(ctx, xml, xml_tag)?;
                     cell.get_or_insert_with(CellData::default)
@@ -1455,6 +2488,15 @@ fn read_table_cell(
                         .draw_frames
                         .push(draw_frame);
                 }
+                Event::Start(xml_tag) | Event::Empty(xml_tag) => {
+                    // Keep any unknown content for writing.
+                    let empty_tag = matches!(evt, Event::Empty(_));
+                    let tag = read_xml(ctx, xml, xml_tag, empty_tag)?;
+                    cell.get_or_insert_with(CellData::default)
+                        .extra_mut()
+                        .xml
+                        .push(tag);
+                }
 
                 Event::End(xml_tag) if xml_tag.name() == super_tag.name() => {
                     break;
@@ -1473,8 +2515,26 @@ fn read_table_cell(
     }
 
     let have_data = if let Some(mut cell) = cell {
+        if ctx.display_text {
+            let text = match &tc.content {
+                TextContent::Empty => None,
+                TextContent::Text(txt) => Some(txt.clone()),
+                TextContent::Xml(xml) => Some(
+                    Value::TextXml(vec![xml.clone()])
+                        .as_cow_str_or("")
+                        .into_owned(),
+                ),
+                TextContent::XmlVec(vec) => {
+                    Some(Value::TextXml(vec.clone()).as_cow_str_or("").into_owned())
+                }
+            };
+            if text.is_some() {
+                cell.extra_mut().display_text = text;
+            }
+        }
+
         // composes a Value
-        set_value(tc, &mut cell)?;
+        set_value(tc, &mut cell, ctx.lossy)?;
 
         // store cell-data
         if ignore_cell(ctx, default_cellstyle, &cell) {
@@ -1507,6 +2567,39 @@ fn ignore_cell(
     false
 }
 
+/// A paragraph without attributes that contains only text, spaces, tabs
+/// and line-breaks is read as plain text. More spaces than max_repeat
+/// or MAX_TEXT_SPACES keep the paragraph as xml.
+fn plain_paragraph(tag: &XmlTag, max_repeat: Option<u32>) -> Option<String> {
+    let max_spaces = max_repeat.map_or(MAX_TEXT_SPACES, |v| (v as usize).min(MAX_TEXT_SPACES));
+    if !tag.attrmap().is_empty() {
+        return None;
+    }
+    let mut buf = String::new();
+    for content in tag.content() {
+        match content {
+            XmlContent::Text(txt) => buf.push_str(txt),
+            XmlContent::Tag(t) if !t.is_empty() => return None,
+            XmlContent::Tag(t) => match t.name() {
+                "text:s" if t.attrmap().iter().all(|(k, _)| k.as_ref() == "text:c") => {
+                    let count = match t.get_attr("text:c") {
+                        Some(v) => v.parse::<usize>().ok()?,
+                        None => 1,
+                    };
+                    if count > max_spaces {
+                        return None;
+                    }
+                    buf.push_str(&" ".repeat(count));
+                }
+                "text:tab" if t.attrmap().is_empty() => buf.push('\t'),
+                "text:line-break" if t.attrmap().is_empty() => buf.push('\n'),
+                _ => return None,
+            },
+        }
+    }
+    Some(buf)
+}
+
 fn append_text(new_txt: TextContent, mut content: TextContent) -> TextContent {
     // There can be multiple text:p elements within the cell.
     content = match content {
@@ -1571,7 +2664,14 @@ fn append_text(new_txt: TextContent, mut content: TextContent) -> TextContent {
 }
 
 #[inline(always)]
-fn set_value(tc: ReadTableCell, cell: &mut CellData) -> Result<(), OdsError> {
+fn set_value(tc: ReadTableCell, cell: &mut CellData, lossy: bool) -> Result<(), OdsError> {
+    if tc.val_error {
+        // The error code is only available as the displayed text.
+        // The value-type is whatever the application chose.
+        set_error_content(tc, cell);
+        return Ok(());
+    }
+
     match tc.val_type {
         ValueType::Empty => {
             // noop
@@ -1580,21 +2680,26 @@ fn set_value(tc: ReadTableCell, cell: &mut CellData) -> Result<(), OdsError> {
             if let Some(v) = tc.val_bool {
                 cell.value = Value::Boolean(v);
             } else {
-                return Err(OdsError::Parse("no boolean value", None));
+                return no_value(lossy, "no boolean value", tc.content, cell);
             }
         }
         ValueType::Number => {
+            #[cfg(feature = "rust_decimal")]
+            if let Some(v) = tc.val_decimal {
+                cell.value = Value::Decimal(v);
+                return Ok(());
+            }
             if let Some(v) = tc.val_float {
                 cell.value = Value::Number(v);
             } else {
-                return Err(OdsError::Parse("no float value", None));
+                return no_value(lossy, "no float value", tc.content, cell);
             }
         }
         ValueType::Percentage => {
             if let Some(v) = tc.val_float {
                 cell.value = Value::Percentage(v);
             } else {
-                return Err(OdsError::Parse("no float value", None));
+                return no_value(lossy, "no float value", tc.content, cell);
             }
         }
         ValueType::Currency => {
@@ -1605,44 +2710,35 @@ fn set_value(tc: ReadTableCell, cell: &mut CellData) -> Result<(), OdsError> {
                     cell.value = Value::Currency(v, "".into());
                 }
             } else {
-                return Err(OdsError::Parse("no float value", None));
+                return no_value(lossy, "no float value", tc.content, cell);
             }
         }
         ValueType::Text => {
             if let Some(v) = tc.val_string {
                 cell.value = Value::Text(v);
             } else {
-                match tc.content {
-                    TextContent::Empty => {
-                        // noop
-                    }
-                    TextContent::Text(txt) => {
-                        cell.value = Value::Text(txt);
-                    }
-                    TextContent::Xml(xml) => {
-                        cell.value = Value::TextXml(vec![xml]);
-                    }
-                    TextContent::XmlVec(vec) => {
-                        cell.value = Value::TextXml(vec);
-                    }
-                }
+                set_text_content(tc.content, cell);
             }
         }
-        ValueType::TextXml => {
+        ValueType::TextXml | ValueType::Date | ValueType::Time | ValueType::Error => {
             unreachable!();
         }
         ValueType::DateTime => {
             if let Some(v) = tc.val_datetime {
-                cell.value = Value::DateTime(v);
+                if tc.val_date_only {
+                    cell.value = Value::Date(v.date());
+                } else {
+                    cell.value = Value::DateTime(v);
+                }
             } else {
-                return Err(OdsError::Parse("no datetime value", None));
+                return no_value(lossy, "no datetime value", tc.content, cell);
             }
         }
         ValueType::TimeDuration => {
             if let Some(v) = tc.val_duration {
                 cell.value = Value::TimeDuration(v);
             } else {
-                return Err(OdsError::Parse("no duration value", None));
+                return no_value(lossy, "no duration value", tc.content, cell);
             }
         }
     }
@@ -1650,6 +2746,68 @@ fn set_value(tc: ReadTableCell, cell: &mut CellData) -> Result<(), OdsError> {
     Ok(())
 }
 
+// Uses the text content as string value.
+fn set_text_content(content: TextContent, cell: &mut CellData) {
+    match content {
+        TextContent::Empty => {
+            // noop
+        }
+        TextContent::Text(txt) => {
+            cell.value = Value::Text(txt);
+        }
+        TextContent::Xml(xml) => {
+            cell.value = Value::TextXml(vec![xml]);
+        }
+        TextContent::XmlVec(vec) => {
+            cell.value = Value::TextXml(vec);
+        }
+    }
+}
+
+// Uses the string-value or the text content as error code.
+fn set_error_content(tc: ReadTableCell, cell: &mut CellData) {
+    let code = match tc.val_string {
+        Some(v) if !v.is_empty() => v,
+        _ => match tc.content {
+            TextContent::Empty => String::new(),
+            TextContent::Text(txt) => txt,
+            TextContent::Xml(xml) => Value::TextXml(vec![xml]).as_cow_str_or("").into_owned(),
+            TextContent::XmlVec(vec) => Value::TextXml(vec).as_cow_str_or("").into_owned(),
+        },
+    };
+    cell.value = Value::Error(code);
+}
+
+// The value attribute for the value-type is missing or invalid.
+// Lossy mode falls back to the text content.
+fn no_value(
+    lossy: bool,
+    msg: &'static str,
+    content: TextContent,
+    cell: &mut CellData,
+) -> Result<(), OdsError> {
+    if lossy {
+        set_text_content(content, cell);
+        Ok(())
+    } else {
+        Err(OdsError::Parse(msg, None))
+    }
+}
+
+// Invalid values are dropped in lossy mode.
+fn recover<T>(ctx: &OdsContext, res: Result<T, OdsError>) -> Result<Option<T>, OdsError> {
+    match res {
+        Ok(v) => Ok(Some(v)),
+        Err(_) if ctx.lossy => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+// Adds the position within the xml to the error.
+fn position_error(sheet: &Sheet, row: u32, col: u32, err: OdsError) -> OdsError {
+    OdsError::Ods(format!("{} row/col {}/{}: {}", sheet.name(), row, col, err))
+}
+
 fn read_annotation(
     ctx: &mut OdsContext,
     xml: &mut OdsXmlReader<'_>,
@@ -1744,6 +2902,19 @@ fn read_draw_frame(
                     ctx, xml, xml_tag, empty_tag,
                 )?));
             }
+            Event::Empty(xml_tag) | Event::Start(xml_tag)
+                if xml_tag.name().as_ref() == b"draw:object" =>
+            {
+                draw_frame.push_content(DrawFrameContent::Object(read_draw_object(
+                    ctx, xml, xml_tag, empty_tag,
+                )?));
+            }
+            Event::Start(xml_tag) if xml_tag.name().as_ref() == b"office:event-listeners" => {
+                for v in read_event_listeners(ctx, xml)? {
+                    draw_frame.add_event_listener(v);
+                }
+            }
+            Event::Empty(xml_tag) if xml_tag.name().as_ref() == b"office:event-listeners" => {}
             Event::Empty(xml_tag) if xml_tag.name().as_ref() == b"svg:desc" => {}
             Event::Start(xml_tag) if xml_tag.name().as_ref() == b"svg:desc" => {
                 if let Some(v) = read_text(ctx, xml, xml_tag, empty_tag, parse_string)? {
@@ -1822,6 +2993,49 @@ fn read_image(
     Ok(draw_image)
 }
 
+fn read_draw_object(
+    ctx: &mut OdsContext,
+    xml: &mut OdsXmlReader<'_>,
+    super_tag: &BytesStart<'_>,
+    empty_tag: bool,
+) -> Result<DrawObject, OdsError> {
+    let mut draw_object = DrawObject::new();
+
+    copy_attr2(ctx, draw_object.attrmap_mut(), super_tag)?;
+
+    if !empty_tag {
+        let mut buf = ctx.pop_buf();
+        loop {
+            let evt = xml.read_event_into(&mut buf)?;
+            let empty_tag = matches!(evt, Event::Empty(_));
+            if cfg!(feature = "dump_xml") {
+                println!("read_draw_object {:?}", evt);
+            }
+            match &evt {
+                Event::End(xml_tag) if xml_tag.name().as_ref() == b"draw:object" => {
+                    break;
+                }
+                Event::Start(xml_tag) | Event::Empty(xml_tag) => {
+                    draw_object
+                        .content_mut()
+                        .push(read_xml(ctx, xml, xml_tag, empty_tag)?);
+                }
+                Event::Eof => {
+                    break;
+                }
+                _ => {
+                    unused_event("read_draw_object", &evt)?;
+                }
+            }
+
+            buf.clear();
+        }
+        ctx.push_buf(buf);
+    }
+
+    Ok(draw_object)
+}
+
 fn read_scripts(ctx: &mut OdsContext, xml: &mut OdsXmlReader<'_>) -> Result<(), OdsError> {
     let mut buf = ctx.pop_buf();
     loop {
@@ -1883,7 +3097,43 @@ fn read_script(
     Ok(script)
 }
 
-// reads the page-layout tag
+// reads the page-layout tag
+/// Reads the content of office:event-listeners.
+fn read_event_listeners(
+    ctx: &mut OdsContext,
+    xml: &mut OdsXmlReader<'_>,
+) -> Result<Vec<EventListener>, OdsError> {
+    let mut events = Vec::new();
+    let mut buf = ctx.pop_buf();
+    loop {
+        let evt = xml.read_event_into(&mut buf)?;
+        if cfg!(feature = "dump_xml") {
+            println!("read_event_listeners {:?}", evt);
+        }
+        match &evt {
+            Event::End(xml_tag) if xml_tag.name().as_ref() == b"office:event-listeners" => {
+                break;
+            }
+            Event::Start(xml_tag) | Event::Empty(xml_tag)
+                if xml_tag.name().as_ref() == b"script:event-listener" =>
+            {
+                events.push(read_event_listener(ctx, xml_tag)?);
+            }
+            Event::End(xml_tag) if xml_tag.name().as_ref() == b"script:event-listener" => {}
+            Event::Eof => {
+                break;
+            }
+            _ => {
+                unused_event("read_event_listeners", &evt)?;
+            }
+        }
+        buf.clear();
+    }
+    ctx.push_buf(buf);
+
+    Ok(events)
+}
+
 fn read_event_listener(
     ctx: &mut OdsContext,
     super_tag: &BytesStart<'_>,
@@ -1940,6 +3190,9 @@ fn read_office_font_face_decls(
             {
                 let name = copy_style_attr(ctx, font.attrmap_mut(), xml_tag)?;
                 font.set_name(name);
+                if let Event::Start(_) = evt {
+                    read_font_face_src(ctx, xml, &mut font)?;
+                }
                 ctx.book.add_font(font);
 
                 font = FontFaceDecl::new_empty();
@@ -1963,6 +3216,192 @@ fn read_office_font_face_decls(
     Ok(())
 }
 
+// reads the content of a style:font-face
+fn read_font_face_src(
+    ctx: &mut OdsContext,
+    xml: &mut OdsXmlReader<'_>,
+    font: &mut FontFaceDecl,
+) -> Result<(), OdsError> {
+    let mut uri = None;
+
+    let mut buf = ctx.pop_buf();
+    loop {
+        let evt = xml.read_event_into(&mut buf)?;
+        if cfg!(feature = "dump_xml") {
+            println!(" read_font_face_src {:?}", evt);
+        }
+        match &evt {
+            Event::Start(xml_tag) if xml_tag.name().as_ref() == b"svg:font-face-src" => {}
+            Event::End(xml_tag) if xml_tag.name().as_ref() == b"svg:font-face-src" => {}
+            Event::Start(xml_tag) | Event::Empty(xml_tag)
+                if xml_tag.name().as_ref() == b"svg:font-face-uri" =>
+            {
+                let mut v = FontFaceUri::default();
+                for attr in xml_tag.attributes().with_checks(false) {
+                    match attr? {
+                        attr if attr.key.as_ref() == b"xlink:href" => {
+                            v.set_href(attr.decode_and_unescape_value(ctx.decoder)?);
+                        }
+                        attr if attr.key.as_ref() == b"xlink:type" => {}
+                        attr => {
+                            unused_attr("read_font_face_src", xml_tag.name().as_ref(), &attr)?;
+                        }
+                    }
+                }
+                if let Event::Empty(_) = evt {
+                    font.add_src(v);
+                } else {
+                    uri = Some(v);
+                }
+            }
+            Event::End(xml_tag) if xml_tag.name().as_ref() == b"svg:font-face-uri" => {
+                if let Some(v) = uri.take() {
+                    font.add_src(v);
+                }
+            }
+            Event::Empty(xml_tag) if xml_tag.name().as_ref() == b"svg:font-face-format" => {
+                for attr in xml_tag.attributes().with_checks(false) {
+                    match attr? {
+                        attr if attr.key.as_ref() == b"svg:string" => {
+                            if let Some(uri) = uri.as_mut() {
+                                uri.set_format(Some(attr.decode_and_unescape_value(ctx.decoder)?));
+                            }
+                        }
+                        attr => {
+                            unused_attr("read_font_face_src", xml_tag.name().as_ref(), &attr)?;
+                        }
+                    }
+                }
+            }
+            Event::End(xml_tag) if xml_tag.name().as_ref() == b"style:font-face" => {
+                break;
+            }
+            Event::Eof => {
+                break;
+            }
+            _ => {
+                unused_event("read_font_face_src", &evt)?;
+            }
+        }
+
+        buf.clear();
+    }
+    ctx.push_buf(buf);
+
+    Ok(())
+}
+
+// reads a loext:theme
+fn read_theme(
+    ctx: &mut OdsContext,
+    xml: &mut OdsXmlReader<'_>,
+    super_tag: &BytesStart<'_>,
+) -> Result<(), OdsError> {
+    let mut theme = Theme::new("");
+    for attr in super_tag.attributes().with_checks(false) {
+        match attr? {
+            attr if attr.key.as_ref() == b"loext:name" => {
+                theme.set_name(attr.decode_and_unescape_value(ctx.decoder)?);
+            }
+            attr => {
+                unused_attr("read_theme", super_tag.name().as_ref(), &attr)?;
+            }
+        }
+    }
+
+    let mut buf = ctx.pop_buf();
+    loop {
+        let evt = xml.read_event_into(&mut buf)?;
+        if cfg!(feature = "dump_xml") {
+            println!(" read_theme {:?}", evt);
+        }
+        match &evt {
+            Event::Start(xml_tag) | Event::Empty(xml_tag)
+                if xml_tag.name().as_ref() == b"loext:theme-colors" =>
+            {
+                for attr in xml_tag.attributes().with_checks(false) {
+                    match attr? {
+                        attr if attr.key.as_ref() == b"loext:name" => {
+                            theme.set_colors_name(attr.decode_and_unescape_value(ctx.decoder)?);
+                        }
+                        attr => {
+                            unused_attr("read_theme", xml_tag.name().as_ref(), &attr)?;
+                        }
+                    }
+                }
+            }
+            Event::Start(xml_tag) | Event::Empty(xml_tag)
+                if xml_tag.name().as_ref() == b"loext:color" =>
+            {
+                let mut slot = None;
+                let mut color = None;
+                for attr in xml_tag.attributes().with_checks(false) {
+                    match attr? {
+                        attr if attr.key.as_ref() == b"loext:name" => {
+                            let v = attr.decode_and_unescape_value(ctx.decoder)?;
+                            slot = recover(ctx, v.parse::<ThemeColor>())?;
+                        }
+                        attr if attr.key.as_ref() == b"loext:color" => {
+                            color = Some(attr.decode_and_unescape_value(ctx.decoder)?.to_string());
+                        }
+                        attr => {
+                            unused_attr("read_theme", xml_tag.name().as_ref(), &attr)?;
+                        }
+                    }
+                }
+                if let (Some(slot), Some(color)) = (slot, color) {
+                    theme.set_color_str(slot, color);
+                }
+            }
+            Event::End(xml_tag) if xml_tag.name().as_ref() == b"loext:theme-colors" => {}
+            Event::End(xml_tag) if xml_tag.name().as_ref() == b"loext:color" => {}
+            Event::End(xml_tag) if xml_tag.name().as_ref() == b"loext:theme" => {
+                break;
+            }
+            Event::Eof => {
+                break;
+            }
+            _ => {
+                unused_event("read_theme", &evt)?;
+            }
+        }
+        buf.clear();
+    }
+    ctx.push_buf(buf);
+
+    ctx.book.set_theme(theme);
+
+    Ok(())
+}
+
+// reads a loext:char-complex-color or similar. Only references to
+// the theme are used.
+fn read_theme_color(
+    ctx: &mut OdsContext,
+    xml_tag: &BytesStart<'_>,
+) -> Result<Option<ThemeColor>, OdsError> {
+    let mut slot = None;
+    let mut theme = false;
+    for attr in xml_tag.attributes().with_checks(false) {
+        match attr? {
+            attr if attr.key.as_ref() == b"loext:theme-type" => {
+                let v = attr.decode_and_unescape_value(ctx.decoder)?;
+                // LibreOffice writes "none" for a plain color.
+                if v != "none" {
+                    slot = recover(ctx, v.parse::<ThemeColor>())?;
+                }
+            }
+            attr if attr.key.as_ref() == b"loext:color-type" => {
+                theme = attr.decode_and_unescape_value(ctx.decoder)? == "theme";
+            }
+            attr => {
+                unused_attr("read_theme_color", xml_tag.name().as_ref(), &attr)?;
+            }
+        }
+    }
+    Ok(if theme { slot } else { None })
+}
+
 // reads the page-layout tag
 fn read_page_style(
     ctx: &mut OdsContext,
@@ -2059,6 +3498,111 @@ fn read_page_style(
     Ok(())
 }
 
+// Reads the table:calculation-settings.
+fn read_calc_settings(
+    ctx: &mut OdsContext,
+    xml: &mut OdsXmlReader<'_>,
+    super_tag: &BytesStart<'_>,
+    empty_tag: bool,
+) -> Result<(), OdsError> {
+    let mut calc = CalcSettings::default();
+
+    for attr in super_tag.attributes().with_checks(false) {
+        match attr? {
+            attr if attr.key.as_ref() == b"table:case-sensitive" => {
+                calc.case_sensitive = parse_bool(&attr.value)?;
+            }
+            attr if attr.key.as_ref() == b"table:precision-as-shown" => {
+                calc.precision_as_shown = parse_bool(&attr.value)?;
+            }
+            attr if attr.key.as_ref() == b"table:search-criteria-must-apply-to-whole-cell" => {
+                calc.search_criteria_must_apply_to_whole_cell = parse_bool(&attr.value)?;
+            }
+            attr if attr.key.as_ref() == b"table:automatic-find-labels" => {
+                calc.automatic_find_labels = parse_bool(&attr.value)?;
+            }
+            attr if attr.key.as_ref() == b"table:use-regular-expressions" => {
+                calc.use_regular_expressions = parse_bool(&attr.value)?;
+            }
+            attr if attr.key.as_ref() == b"table:use-wildcards" => {
+                calc.use_wildcards = parse_bool(&attr.value)?;
+            }
+            attr if attr.key.as_ref() == b"table:null-year" => {
+                calc.null_year = parse_u32(&attr.value)?;
+            }
+            attr => {
+                unused_attr("read_calc_settings", super_tag.name().as_ref(), &attr)?;
+            }
+        }
+    }
+
+    if !empty_tag {
+        let mut buf = ctx.pop_buf();
+        loop {
+            let evt = xml.read_event_into(&mut buf)?;
+            if cfg!(feature = "dump_xml") {
+                println!(" read_calc_settings {:?}", evt);
+            }
+            match &evt {
+                Event::Start(xml_tag) | Event::Empty(xml_tag)
+                    if xml_tag.name().as_ref() == b"table:null-date" =>
+                {
+                    for attr in xml_tag.attributes().with_checks(false) {
+                        match attr? {
+                            attr if attr.key.as_ref() == b"table:date-value" => {
+                                calc.null_date = parse_datetime(&attr.value)?.date();
+                            }
+                            attr if attr.key.as_ref() == b"table:value-type" => {}
+                            attr => {
+                                unused_attr("read_calc_settings", xml_tag.name().as_ref(), &attr)?;
+                            }
+                        }
+                    }
+                }
+                Event::End(xml_tag) if xml_tag.name().as_ref() == b"table:null-date" => {}
+                Event::Start(xml_tag) | Event::Empty(xml_tag)
+                    if xml_tag.name().as_ref() == b"table:iteration" =>
+                {
+                    for attr in xml_tag.attributes().with_checks(false) {
+                        match attr? {
+                            attr if attr.key.as_ref() == b"table:status" => {
+                                calc.iteration = attr.value.as_ref() == b"enable";
+                            }
+                            attr if attr.key.as_ref() == b"table:steps" => {
+                                calc.iteration_steps = parse_u32(&attr.value)?;
+                            }
+                            // Google Sheets writes table:maximum-difference.
+                            attr if attr.key.as_ref() == b"table:minimum-difference"
+                                || attr.key.as_ref() == b"table:maximum-difference" =>
+                            {
+                                calc.iteration_min_difference = parse_f64(&attr.value)?;
+                            }
+                            attr => {
+                                unused_attr("read_calc_settings", xml_tag.name().as_ref(), &attr)?;
+                            }
+                        }
+                    }
+                }
+                Event::End(xml_tag) if xml_tag.name().as_ref() == b"table:iteration" => {}
+                Event::End(xml_tag) if xml_tag.name().as_ref() == super_tag.name().as_ref() => {
+                    break;
+                }
+                Event::Text(_) => (),
+                Event::Eof => break,
+                _ => {
+                    unused_event("read_calc_settings", &evt)?;
+                }
+            }
+            buf.clear();
+        }
+        ctx.push_buf(buf);
+    }
+
+    ctx.book.calc_settings = calc;
+
+    Ok(())
+}
+
 fn read_validations(ctx: &mut OdsContext, xml: &mut OdsXmlReader<'_>) -> Result<(), OdsError> {
     let mut valid = Validation::new();
 
@@ -2479,6 +4023,9 @@ fn read_office_styles(
             {
                 read_value_format(ctx, xml, origin, StyleUse::Named, xml_tag)?;
             }
+            Event::Start(xml_tag) if xml_tag.name().as_ref() == b"loext:theme" => {
+                read_theme(ctx, xml, xml_tag)?;
+            }
             Event::End(xml_tag) if xml_tag.name().as_ref() == b"office:styles" => {
                 break;
             }
@@ -3081,18 +4628,15 @@ fn read_tablestyle(
             match &evt {
                 Event::Start(xml_tag) | Event::Empty(xml_tag) => match xml_tag.name().as_ref() {
                     b"style:table-properties" => copy_attr2(ctx, style.tablestyle_mut(), xml_tag)?,
+                    b"style:map" => style.push_stylemap(read_stylemap(ctx, xml_tag)?),
                     _ => {
                         unused_event("read_table_style", &evt)?;
                     }
                 },
                 Event::Text(_) => (),
-                Event::End(xml_tag) => {
-                    if xml_tag.name().as_ref() == super_tag.name().as_ref() {
-                        ctx.book.add_tablestyle(style);
-                        break;
-                    } else {
-                        unused_event("read_table_style", &evt)?;
-                    }
+                Event::End(xml_tag) if xml_tag.name().as_ref() == super_tag.name().as_ref() => {
+                    ctx.book.add_tablestyle(style);
+                    break;
                 }
                 Event::Eof => break,
                 _ => {
@@ -3139,18 +4683,15 @@ fn read_rowstyle(
                     b"style:table-row-properties" => {
                         copy_attr2(ctx, style.rowstyle_mut(), xml_tag)?
                     }
+                    b"style:map" => style.push_stylemap(read_stylemap(ctx, xml_tag)?),
                     _ => {
                         unused_event("read_rowstyle", &evt)?;
                     }
                 },
                 Event::Text(_) => (),
-                Event::End(xml_tag) => {
-                    if xml_tag.name() == super_tag.name() {
-                        ctx.book.add_rowstyle(style);
-                        break;
-                    } else {
-                        unused_event("read_rowstyle", &evt)?;
-                    }
+                Event::End(xml_tag) if xml_tag.name() == super_tag.name() => {
+                    ctx.book.add_rowstyle(style);
+                    break;
                 }
                 Event::Eof => break,
                 _ => {
@@ -3196,18 +4737,15 @@ fn read_colstyle(
                     b"style:table-column-properties" => {
                         copy_attr2(ctx, style.colstyle_mut(), xml_tag)?
                     }
+                    b"style:map" => style.push_stylemap(read_stylemap(ctx, xml_tag)?),
                     _ => {
                         unused_event("read_colstyle", &evt)?;
                     }
                 },
                 Event::Text(_) => (),
-                Event::End(xml_tag) => {
-                    if xml_tag.name() == super_tag.name() {
-                        ctx.book.add_colstyle(style);
-                        break;
-                    } else {
-                        unused_event("read_colstyle", &evt)?;
-                    }
+                Event::End(xml_tag) if xml_tag.name() == super_tag.name() => {
+                    ctx.book.add_colstyle(style);
+                    break;
                 }
                 Event::Eof => break,
                 _ => {
@@ -3266,6 +4804,24 @@ fn read_cellstyle(
                 }
                 Event::End(xml_tag) if xml_tag.name().as_ref() == b"style:paragraph-properties" => {
                 }
+                Event::Start(xml_tag) | Event::Empty(xml_tag)
+                    if xml_tag.name().as_ref() == b"loext:char-complex-color" =>
+                {
+                    style.set_color_theme_slot(read_theme_color(ctx, xml_tag)?);
+                }
+                Event::Start(xml_tag) | Event::Empty(xml_tag)
+                    if xml_tag.name().as_ref() == b"loext:background-complex-color" =>
+                {
+                    style.set_background_color_theme_slot(read_theme_color(ctx, xml_tag)?);
+                }
+                Event::End(xml_tag)
+                    if matches!(
+                        xml_tag.name().as_ref(),
+                        b"style:table-cell-properties"
+                            | b"style:text-properties"
+                            | b"loext:char-complex-color"
+                            | b"loext:background-complex-color"
+                    ) => {}
                 // Event::Start(xml_tag) | Event::Empty(xml_tag)
                 //     if xml_tag.name().as_ref() == b"style:graphic-properties" =>
                 // {
@@ -3341,7 +4897,11 @@ fn read_paragraphstyle(
                 Event::End(xml_tag) if xml_tag.name().as_ref() == b"style:paragraph-properties" => {
                 }
                 // b"style:graphic-properties" => copy_attr(style.graphic_mut(), xml, xml_tag)?,
-                // b"style:map" => style.push_stylemap(read_stylemap(xml, xml_tag)?),
+                Event::Start(xml_tag) | Event::Empty(xml_tag)
+                    if xml_tag.name().as_ref() == b"style:map" =>
+                {
+                    style.push_stylemap(read_stylemap(ctx, xml_tag)?);
+                }
                 Event::Start(xml_tag) | Event::Empty(xml_tag)
                     if xml_tag.name().as_ref() == b"style:tab-stops" => {}
                 Event::End(xml_tag) if xml_tag.name().as_ref() == b"style:tab-stops" => {}
@@ -3404,6 +4964,11 @@ fn read_textstyle(
                 {
                     copy_attr2(ctx, style.textstyle_mut(), xml_tag)?;
                 }
+                Event::Start(xml_tag) | Event::Empty(xml_tag)
+                    if xml_tag.name().as_ref() == b"style:map" =>
+                {
+                    style.push_stylemap(read_stylemap(ctx, xml_tag)?);
+                }
                 Event::End(xml_tag) if xml_tag.name() == super_tag.name() => {
                     ctx.book.add_textstyle(style);
                     break;
@@ -4692,6 +6257,128 @@ fn read_config_item(
 }
 
 // Reads a part of the XML as XmlTag's.
+// Extracts the data bars and color scales. Everything else is
+// returned as is.
+fn read_conditional_formats(sheet: &mut Sheet, mut xml_tag: XmlTag) -> Option<XmlTag> {
+    let content = mem::take(xml_tag.content_mut());
+    for c in content {
+        match c {
+            XmlContent::Tag(tag) if tag.name() == "calcext:conditional-format" => {
+                // Anything unexpected is kept as xml.
+                match read_conditional_format(&tag) {
+                    Ok(Some(v)) => sheet.conditional_formats.push(v),
+                    Ok(None) | Err(_) => xml_tag.content_mut().push(XmlContent::Tag(tag)),
+                }
+            }
+            XmlContent::Text(_) => {}
+            c => xml_tag.content_mut().push(c),
+        }
+    }
+    if xml_tag.content().is_empty() && xml_tag.attrmap().is_empty() {
+        None
+    } else {
+        Some(xml_tag)
+    }
+}
+
+fn read_conditional_format(xml_tag: &XmlTag) -> Result<Option<ConditionalFormat>, OdsError> {
+    let mut tags = xml_tag.content().iter().filter_map(|v| match v {
+        XmlContent::Tag(v) => Some(v),
+        XmlContent::Text(_) => None,
+    });
+    let (Some(format), None) = (tags.next(), tags.next()) else {
+        return Ok(None);
+    };
+    if xml_tag.attrmap().len() != 1 {
+        return Ok(None);
+    }
+    let Some(range) = xml_tag.get_attr("calcext:target-range-address") else {
+        return Ok(None);
+    };
+    let range = parse_cellrange(range)?;
+
+    let entries = format
+        .content()
+        .iter()
+        .filter_map(|v| match v {
+            XmlContent::Tag(v) => Some(v),
+            XmlContent::Text(_) => None,
+        })
+        .collect::<Vec<_>>();
+
+    match format.name() {
+        "calcext:data-bar" => {
+            let mut data_bar = DataBar::new(Rgb::new(0, 0, 0));
+            for (k, v) in format.attrmap().iter() {
+                match k.as_ref() {
+                    "calcext:max-length" => data_bar.set_max_length(parse_f64(v.as_bytes())?),
+                    "calcext:min-length" => data_bar.set_min_length(parse_f64(v.as_bytes())?),
+                    "calcext:positive-color" => data_bar.set_positive_color(
+                        Rgb::<u8>::parse_attr_def(Some(v), data_bar.positive_color())?,
+                    ),
+                    "calcext:negative-color" => data_bar.set_negative_color(
+                        Rgb::<u8>::parse_attr_def(Some(v), data_bar.negative_color())?,
+                    ),
+                    "calcext:axis-color" => data_bar
+                        .set_axis_color(Rgb::<u8>::parse_attr_def(Some(v), data_bar.axis_color())?),
+                    "calcext:axis-position" => {
+                        data_bar.set_axis_position(DataBarAxis::try_from(v)?)
+                    }
+                    "calcext:gradient" => {
+                        data_bar.set_gradient(bool::parse_attr_def(Some(v), true)?)
+                    }
+                    "calcext:show-value" => {
+                        data_bar.set_show_value(bool::parse_attr_def(Some(v), true)?)
+                    }
+                    _ => return Ok(None),
+                }
+            }
+            let [min, max] = entries.as_slice() else {
+                return Ok(None);
+            };
+            for (entry, set) in [
+                (min, DataBar::set_min as fn(&mut DataBar, ScaleValue)),
+                (max, DataBar::set_max),
+            ] {
+                if entry.name() != "calcext:formatting-entry" || entry.attrmap().len() != 2 {
+                    return Ok(None);
+                }
+                let Some(value_type) = entry.get_attr("calcext:type") else {
+                    return Ok(None);
+                };
+                set(
+                    &mut data_bar,
+                    ScaleValue::parse(value_type, entry.get_attr("calcext:value"))?,
+                );
+            }
+            Ok(Some(ConditionalFormat::new_data_bar(range, data_bar)))
+        }
+        "calcext:color-scale" => {
+            if !format.attrmap().is_empty() {
+                return Ok(None);
+            }
+            let mut color_scale = ColorScale::new();
+            for entry in entries {
+                if entry.name() != "calcext:color-scale-entry" || entry.attrmap().len() != 3 {
+                    return Ok(None);
+                }
+                let (Some(value_type), Some(color)) = (
+                    entry.get_attr("calcext:type"),
+                    Rgb::<u8>::parse_attr(entry.get_attr("calcext:color"))?,
+                ) else {
+                    return Ok(None);
+                };
+                color_scale.push_entry(
+                    ScaleValue::parse(value_type, entry.get_attr("calcext:value"))?,
+                    color,
+                );
+            }
+            Ok(Some(ConditionalFormat::new_color_scale(range, color_scale)))
+        }
+        _ => Ok(None),
+    }
+}
+
 fn read_xml(
     ctx: &mut OdsContext,
     xml: &mut OdsXmlReader<'_>,
@@ -0,0 +1,328 @@
+//!
+//! A fast structural check of the generated xml against the rules
+//! of the ODF schema that matter most for interop.
+//!
+//! This is not a full RELAX NG validation. It checks
+//! * wellformedness and duplicate attributes,
+//! * that all namespace prefixes are declared,
+//! * the root element of each part and office:version,
+//! * the nesting of the table elements,
+//! * that columns come before rows in a table,
+//! * repeat and span attributes,
+//! * office:value-type and the matching value attributes of cells.
+//!
+
+use crate::error::{OdsError, XmlLocation};
+use crate::io::read::complete_location;
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Decoder;
+use std::io::{Read, Seek};
+use zip::ZipArchive;
+
+/// Validates the xml parts of an ODS package.
+///
+/// Checks content.xml, styles.xml, meta.xml and settings.xml.
+/// The error contains the location of the first violation.
+pub fn validate_ods<R: Read + Seek>(read: R) -> Result<(), OdsError> {
+    let mut zip = ZipArchive::new(read)?;
+
+    for part in ["content.xml", "styles.xml", "meta.xml", "settings.xml"] {
+        let mut data = Vec::new();
+        match zip.by_name(part) {
+            Ok(mut file) => {
+                file.read_to_end(&mut data)?;
+            }
+            Err(zip::result::ZipError::FileNotFound) if part != "content.xml" => continue,
+            Err(e) => return Err(e.into()),
+        }
+        validate_part(part, &data)?;
+    }
+
+    Ok(())
+}
+
+/// Validates a flat ODS file.
+///
+/// The error contains the location of the first violation.
+pub fn validate_fods(data: &[u8]) -> Result<(), OdsError> {
+    validate_part("fods", data)
+}
+
+/// Expected root element for each part.
+fn root_elem(part: &str) -> &'static str {
+    match part {
+        "content.xml" => "office:document-content",
+        "styles.xml" => "office:document-styles",
+        "meta.xml" => "office:document-meta",
+        "settings.xml" => "office:document-settings",
+        _ => "office:document",
+    }
+}
+
+/// Allowed parents of the table elements.
+fn allowed_parents(elem: &str) -> Option<&'static [&'static str]> {
+    match elem {
+        "table:table-cell" | "table:covered-table-cell" => Some(&["table:table-row"]),
+        "table:table-row" => Some(&[
+            "table:table",
+            "table:table-rows",
+            "table:table-row-group",
+            "table:table-header-rows",
+        ]),
+        "table:table-column" => Some(&[
+            "table:table",
+            "table:table-columns",
+            "table:table-column-group",
+            "table:table-header-columns",
+        ]),
+        "table:table-rows" | "table:table-header-rows" => {
+            Some(&["table:table", "table:table-row-group"])
+        }
+        "table:table-columns" | "table:table-header-columns" => {
+            Some(&["table:table", "table:table-column-group"])
+        }
+        "office:spreadsheet" => Some(&["office:body"]),
+        _ => None,
+    }
+}
+
+struct Validator<'a> {
+    part: &'a str,
+    data: &'a [u8],
+    /// Open elements.
+    stack: Vec<String>,
+    /// Declared namespace prefixes.
+    ns: Vec<String>,
+    /// Length of ns for each open element.
+    ns_len: Vec<usize>,
+    /// Has a row been seen, for each open table.
+    table_rows: Vec<bool>,
+    /// Root element seen.
+    root: bool,
+}
+
+impl<'a> Validator<'a> {
+    fn error(&self, byte: u64, msg: String) -> OdsError {
+        let mut loc = XmlLocation {
+            part: self.part.to_string(),
+            byte,
+            ..Default::default()
+        };
+        complete_location(&mut loc, self.data);
+        loc.path.clone_from(&self.stack);
+        OdsError::Ods(msg).located(&loc)
+    }
+
+    fn start(
+        &mut self,
+        byte: u64,
+        decoder: Decoder,
+        xml_tag: &BytesStart<'_>,
+        empty: bool,
+    ) -> Result<(), OdsError> {
+        let name = String::from_utf8_lossy(xml_tag.name().as_ref()).to_string();
+
+        // namespaces first, they can be declared on the element itself.
+        self.ns_len.push(self.ns.len());
+        let mut attrs = Vec::new();
+        for attr in xml_tag.attributes() {
+            let attr = attr.map_err(|e| self.error(byte, e.to_string()))?;
+            let key = String::from_utf8_lossy(attr.key.as_ref()).to_string();
+            let value = attr
+                .decode_and_unescape_value(decoder)
+                .map_err(|e| self.error(byte, e.to_string()))?
+                .to_string();
+            if let Some(prefix) = key.strip_prefix("xmlns:") {
+                self.ns.push(prefix.to_string());
+            }
+            attrs.push((key, value));
+        }
+
+        self.check_prefix(byte, &name)?;
+        for (key, _) in &attrs {
+            if key != "xmlns" && !key.starts_with("xmlns:") {
+                self.check_prefix(byte, key)?;
+            }
+        }
+
+        match self.stack.last() {
+            None => {
+                if self.root {
+                    return Err(self.error(byte, "more than one root element".to_string()));
+                }
+                self.root = true;
+                let root = root_elem(self.part);
+                if name != root {
+                    return Err(self.error(byte, format!("expected root {}", root)));
+                }
+                if !attrs.iter().any(|(k, _)| k == "office:version") {
+                    return Err(self.error(byte, "missing office:version".to_string()));
+                }
+            }
+            Some(parent) => {
+                if let Some(parents) = allowed_parents(&name) {
+                    if !parents.contains(&parent.as_str()) {
+                        return Err(self.error(byte, format!("{} is not allowed here", name)));
+                    }
+                }
+            }
+        }
+
+        match name.as_str() {
+            "table:table-row" => {
+                if let Some(rows) = self.table_rows.last_mut() {
+                    *rows = true;
+                }
+            }
+            "table:table-column" | "table:table-columns" | "table:table-column-group"
+                if self.table_rows.last() == Some(&true) =>
+            {
+                return Err(self.error(byte, format!("{} after the rows", name)));
+            }
+            _ => {}
+        }
+
+        for (key, value) in &attrs {
+            match key.as_str() {
+                "table:number-columns-repeated"
+                | "table:number-rows-repeated"
+                | "table:number-columns-spanned"
+                | "table:number-rows-spanned"
+                | "table:number-matrix-columns-spanned"
+                | "table:number-matrix-rows-spanned" => match value.parse::<u32>() {
+                    Ok(v) if v > 0 => {}
+                    _ => {
+                        return Err(self.error(byte, format!("invalid {}={:?}", key, value)));
+                    }
+                },
+                _ => {}
+            }
+        }
+
+        if name == "table:table-cell" || name == "table:covered-table-cell" {
+            self.check_value(byte, &attrs)?;
+        }
+
+        if empty {
+            self.ns.truncate(self.ns_len.pop().unwrap_or_default());
+        } else {
+            if name == "table:table" {
+                self.table_rows.push(false);
+            }
+            self.stack.push(name);
+        }
+
+        Ok(())
+    }
+
+    fn end(&mut self) {
+        if let Some(name) = self.stack.pop() {
+            if name == "table:table" {
+                self.table_rows.pop();
+            }
+        }
+        self.ns.truncate(self.ns_len.pop().unwrap_or_default());
+    }
+
+    fn check_prefix(&self, byte: u64, name: &str) -> Result<(), OdsError> {
+        if let Some((prefix, _)) = name.split_once(':') {
+            if prefix != "xml" && !self.ns.iter().any(|v| v == prefix) {
+                return Err(self.error(byte, format!("undeclared namespace prefix {}", prefix)));
+            }
+        }
+        Ok(())
+    }
+
+    fn check_value(&self, byte: u64, attrs: &[(String, String)]) -> Result<(), OdsError> {
+        let attr = |name: &str| {
+            attrs
+                .iter()
+                .find(|(k, _)| k == name)
+                .map(|(_, v)| v.as_str())
+        };
+
+        let Some(value_type) = attr("office:value-type") else {
+            return Ok(());
+        };
+        let required = match value_type {
+            "float" | "percentage" | "currency" => {
+                if let Some(value) = attr("office:value") {
+                    if !valid_double(value) {
+                        return Err(self.error(byte, format!("invalid office:value={:?}", value)));
+                    }
+                }
+                "office:value"
+            }
+            "date" => "office:date-value",
+            "time" => "office:time-value",
+            "boolean" => {
+                if let Some(value) = attr("office:boolean-value") {
+                    if value != "true" && value != "false" {
+                        return Err(
+                            self.error(byte, format!("invalid office:boolean-value={:?}", value))
+                        );
+                    }
+                }
+                "office:boolean-value"
+            }
+            "string" | "void" => return Ok(()),
+            _ => {
+                return Err(self.error(byte, format!("invalid office:value-type={:?}", value_type)));
+            }
+        };
+        if attr(required).is_none() {
+            return Err(self.error(byte, format!("missing {}", required)));
+        }
+
+        Ok(())
+    }
+}
+
+/// Lexical space of xsd:double.
+fn valid_double(value: &str) -> bool {
+    match value.parse::<f64>() {
+        Ok(v) if v.is_finite() => true,
+        Ok(_) => matches!(value, "NaN" | "INF" | "-INF"),
+        Err(_) => false,
+    }
+}
+
+/// Validates one xml part.
+pub(crate) fn validate_part(part: &str, data: &[u8]) -> Result<(), OdsError> {
+    let mut v = Validator {
+        part,
+        data,
+        stack: Vec::new(),
+        ns: Vec::new(),
+        ns_len: Vec::new(),
+        table_rows: Vec::new(),
+        root: false,
+    };
+
+    let mut xml = quick_xml::Reader::from_reader(data);
+    let mut buf = Vec::new();
+    loop {
+        let byte = xml.buffer_position();
+        let evt = xml
+            .read_event_into(&mut buf)
+            .map_err(|e| v.error(xml.error_position(), e.to_string()))?;
+        match &evt {
+            Event::Start(xml_tag) => v.start(byte, xml.decoder(), xml_tag, false)?,
+            Event::Empty(xml_tag) => v.start(byte, xml.decoder(), xml_tag, true)?,
+            Event::End(_) => v.end(),
+            Event::Text(_) | Event::CData(_) => {}
+            Event::Eof => break,
+            _ => {}
+        }
+        buf.clear();
+    }
+
+    if let Some(name) = v.stack.last() {
+        return Err(v.error(data.len() as u64, format!("{} is not closed", name)));
+    }
+    if !v.root {
+        return Err(v.error(0, format!("missing root {}", root_elem(part))));
+    }
+
+    Ok(())
+}
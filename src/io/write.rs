@@ -1,35 +1,47 @@
 use crate::cell_::CellData;
+use crate::conditional::ConditionalFormatKind;
 use crate::config::{ConfigItem, ConfigItemType, ConfigValue};
-use crate::draw::{Annotation, DrawFrame, DrawFrameContent, DrawImage};
+use crate::draw::{Annotation, DrawFrame, DrawFrameContent, DrawImage, DrawObject};
 use crate::error::OdsError;
 use crate::format::{FormatPartType, ValueFormatTrait};
+use crate::formula::{collapse_range_names, formula_from_plain, formula_to_plain};
 use crate::io::format::{format_duration2, format_validation_condition};
-use crate::io::xmlwriter::XmlWriter;
+#[cfg(feature = "validate_odf")]
+use crate::io::validate::validate_ods;
+use crate::io::xmlwriter::{Escape, XmlWriter};
 use crate::io::NamespaceMap;
 use crate::manifest::Manifest;
 use crate::metadata::MetaValue;
-use crate::refs::{format_cellranges, CellRange};
-use crate::sheet::Visibility;
-use crate::sheet_::{dedup_colheader, CellDataIter};
+use crate::refs::{format_cellranges, CellRange, CellRef};
+use crate::sheet::{PrintScale, Visibility};
+use crate::sheet_::{dedup_colheader, dedup_rowheader};
+use crate::style::color_string;
+use crate::style::stylemap::StyleMap;
+use crate::style::units::{PageBreak, Percent, WritingMode};
 use crate::style::{
     CellStyle, ColStyle, FontFaceDecl, GraphicStyle, HeaderFooter, MasterPage, MasterPageRef,
     PageStyle, PageStyleRef, ParagraphStyle, RowStyle, RubyStyle, StyleOrigin, StyleUse,
-    TableStyle, TextStyle,
+    TableStyle, TextStyle, Theme, ThemeColor,
 };
 use crate::validation::ValidationDisplay;
-use crate::workbook::{EventListener, Script};
+use crate::workbook::{CalcSettings, EventListener, Script};
+use crate::workbook_::{MAX_COLS, MAX_ROWS};
 use crate::xmltree::{XmlContent, XmlTag};
 use crate::HashMap;
 use crate::{Length, Sheet, Value, ValueType, WorkBook};
-use std::borrow::Cow;
+use chrono::NaiveTime;
+use std::borrow::{Borrow, Cow};
 use std::cmp::max;
 use std::collections::{BTreeMap, HashSet};
+use std::fmt::{self, Debug, Formatter};
 use std::fs::File;
-use std::io::{BufWriter, Cursor, Seek, Write};
+use std::io::{BufWriter, Cursor, Read, Seek, Write};
 use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
 use std::{io, mem};
 use zip::write::FileOptions;
-use zip::{CompressionMethod, ZipWriter};
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
 
 #[cfg(test)]
 mod tests;
@@ -37,17 +49,204 @@ mod tests;
 type OdsXmlWriter<'a> = XmlWriter<&'a mut dyn Write>;
 
 const DATETIME_FORMAT: &str = "%Y-%m-%dT%H:%M:%S%.f";
+const DATE_FORMAT: &str = "%Y-%m-%d";
+
+/// Extra tags of the workbook that are written before the tables.
+const BOOK_EXTRA_BEFORE: &[&str] = &[
+    "table:label-ranges",
+    "table:tracked-changes",
+    "text:alphabetical-index-auto-mark-file",
+    "text:dde-connection-decls",
+    "text:sequence-decls",
+    "text:user-field-decls",
+    "text:variable-decls",
+];
+/// Extra tags of the workbook that are written after the tables.
+const BOOK_EXTRA_AFTER: &[&str] = &[
+    "table:consolidation",
+    "table:data-pilot-tables",
+    "table:database-ranges",
+    "table:named-expressions",
+    "calcext:conditional-formats",
+];
+/// Extra tags of a sheet that are written before the columns.
+const SHEET_EXTRA_BEFORE: &[&str] = &[
+    "table:title",
+    "table:desc",
+    "table:table-source",
+    "office:dde-source",
+    "table:scenario",
+    "office:forms",
+    "table:shapes",
+];
+/// Extra tags of a sheet that are written after the table.
+const SHEET_EXTRA_AFTER: &[&str] = &["table:named-expressions", "calcext:conditional-formats"];
 
 #[allow(dead_code)]
 trait SeekWrite: Seek + Write {}
 
 impl<T> SeekWrite for T where T: Seek + Write {}
 
+/// Handling of non-finite numbers (NaN, Infinity) when writing.
+///
+/// There is no valid representation for these in office:value, and
+/// LibreOffice rejects a document containing office:value="NaN".
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum NonFiniteMode {
+    /// Writing fails with an error that names the offending cell.
+    #[default]
+    Error,
+    /// The cell is written without a value. Style, annotations etc are kept.
+    Empty,
+    /// The cell is written as an error value #N/A. If the cell has no
+    /// formula, the formula =NA() is added to make this work.
+    ErrorValue,
+}
+
+/// Handling of characters that are not allowed in XML 1.0 when writing.
+///
+/// These are the control characters 0x00-0x1F except tab, newline and
+/// carriage-return, and the non-characters U+FFFE and U+FFFF. A single
+/// one of them makes the file unreadable for LibreOffice.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum InvalidCharMode {
+    /// Writing fails with an error.
+    #[default]
+    Error,
+    /// The characters are dropped.
+    Strip,
+    /// The characters are replaced with U+FFFD.
+    Replace,
+}
+
+/// The application the file is written for.
+///
+/// Each application has its own quirks when reading ods. The target
+/// switches the known ones.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Target {
+    /// LibreOffice. Writes everything that is known.
+    #[default]
+    LibreOffice,
+    /// Microsoft Excel.
+    /// * No calcext attributes for cells.
+    /// * Formulas without a namespace prefix are converted to OpenFormula,
+    ///   see [formula_from_plain](crate::formula::formula_from_plain).
+    /// * Covered cells are written one by one, without repeat.
+    Excel,
+    /// Google Sheets.
+    /// * No calcext attributes for cells.
+    /// * Formulas are converted to the plain syntax without "of:" prefix,
+    ///   see [formula_to_plain](crate::formula::formula_to_plain).
+    /// * Covered cells are written one by one, without repeat.
+    GoogleSheets,
+}
+
+impl Target {
+    /// Write calcext attributes for cells.
+    fn calcext(self) -> bool {
+        self == Target::LibreOffice
+    }
+
+    /// Use table:number-columns-repeated for covered cells.
+    fn repeat_covered(self) -> bool {
+        self == Target::LibreOffice
+    }
+
+    /// Root entry first, all others sorted.
+    /// Namespace prefix of the formula.
+    fn formula(self, formula: &str) -> Cow<'_, str> {
+        match self {
+            Target::LibreOffice => Cow::Borrowed(formula),
+            Target::Excel => {
+                if formula.starts_with('=') {
+                    Cow::Owned(formula_from_plain(formula))
+                } else {
+                    Cow::Borrowed(formula)
+                }
+            }
+            Target::GoogleSheets => Cow::Owned(formula_to_plain(formula)),
+        }
+    }
+}
+
+/// Receives progress information while writing.
+///
+/// All methods return whether writing should go on. Returning false
+/// cancels writing with [OdsError::Cancelled].
+///
+/// The observer must be Send, so the write options can be moved to
+/// another thread.
+///
+/// ```
+/// use spreadsheet_ods::{OdsWriteOptions, Sheet, WorkBook, WriteObserver};
+///
+/// struct Progress;
+///
+/// impl WriteObserver for Progress {
+///     fn sheet_rows(&mut self, sheet: usize, row: u32) -> bool {
+///         println!("sheet {} row {}", sheet, row);
+///         true
+///     }
+/// }
+///
+/// let mut wb = WorkBook::new_empty();
+/// wb.push_sheet(Sheet::new("1"));
+/// let mut buf = std::io::Cursor::new(Vec::new());
+/// OdsWriteOptions::default()
+///     .observer(Progress)
+///     .write_ods(&mut wb, &mut buf)
+///     .unwrap();
+/// ```
+pub trait WriteObserver: Send {
+    /// Number of rows between two calls of sheet_rows().
+    fn row_step(&self) -> u32 {
+        1000
+    }
+
+    /// The sheet is about to be written. Rows is the number of used rows.
+    fn sheet_start(&mut self, sheet: usize, name: &str, rows: u32) -> bool {
+        let _ = (sheet, name, rows);
+        true
+    }
+
+    /// Writing the sheet has reached this row.
+    fn sheet_rows(&mut self, sheet: usize, row: u32) -> bool {
+        let _ = (sheet, row);
+        true
+    }
+
+    /// The sheet has been written.
+    fn sheet_end(&mut self, sheet: usize) -> bool {
+        let _ = sheet;
+        true
+    }
+}
+
+/// Holds the observer.
+struct Observer(Box<dyn WriteObserver + Send>);
+
+impl Debug for Observer {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str("WriteObserver")
+    }
+}
+
 /// Write options for ods-files.
 #[derive(Debug, Default)]
 pub struct OdsWriteOptions {
     method: CompressionMethod,
     level: Option<i64>,
+    non_finite: NonFiniteMode,
+    invalid_chars: InvalidCharMode,
+    fail_on_lossy: bool,
+    compress_rows: bool,
+    compress_cols: bool,
+    pretty: bool,
+    target: Target,
+    range_names: bool,
+    observer: Option<Observer>,
+    cancel: Option<Arc<AtomicBool>>,
 }
 
 impl OdsWriteOptions {
@@ -63,6 +262,115 @@ impl OdsWriteOptions {
         self
     }
 
+    /// Fail if there is any content that can't be written.
+    ///
+    /// Otherwise, extra xml tags that are not known at their position
+    /// are silently dropped. The error lists all the problems found.
+    pub fn fail_on_lossy(mut self, fail: bool) -> Self {
+        self.fail_on_lossy = fail;
+        self
+    }
+
+    /// Write runs of identical rows as a single row with
+    /// table:number-rows-repeated.
+    ///
+    /// Rows are identical if they have the same cells with the same value,
+    /// formula and style and the same row formatting. Rows with spans,
+    /// annotations, images or validations are never merged.
+    /// Reading the file expands the repeated rows again.
+    pub fn compress_rows(mut self, compress: bool) -> Self {
+        self.compress_rows = compress;
+        self
+    }
+
+    /// Write runs of identical cells within a row as a single cell with
+    /// table:number-columns-repeated.
+    ///
+    /// Cells are identical if they have the same value, formula and style.
+    /// Cells with spans, annotations, images or validations are never merged.
+    /// Reading the file expands the repeated cells again, unless
+    /// OdsOptions::use_repeat_for_cells() is used.
+    pub fn compress_cols(mut self, compress: bool) -> Self {
+        self.compress_cols = compress;
+        self
+    }
+
+    /// Handling of NaN and Infinity in number, percentage and currency values.
+    pub fn non_finite(mut self, mode: NonFiniteMode) -> Self {
+        self.non_finite = mode;
+        self
+    }
+
+    /// Handling of characters that are not allowed in XML 1.0.
+    /// Applies to all text written: cell text, formulas, metadata, styles etc.
+    ///
+    /// The default is to fail with an error.
+    pub fn invalid_chars(mut self, mode: InvalidCharMode) -> Self {
+        self.invalid_chars = mode;
+        self
+    }
+
+    /// Indents the xml of all parts by nesting level.
+    ///
+    /// The output is the same for the same workbook, which eases diffing
+    /// generated files. Whitespace that would change the content,
+    /// e.g. inside paragraphs, is not added. The files get a bit larger.
+    pub fn pretty(mut self, pretty: bool) -> Self {
+        self.pretty = pretty;
+        self
+    }
+
+    /// The application the file is written for. Switches the known
+    /// quirks of the application, see [Target].
+    ///
+    /// The default is LibreOffice.
+    pub fn target(mut self, target: Target) -> Self {
+        self.target = target;
+        self
+    }
+
+    /// References in formulas that cover the same cells as a named range
+    /// or database range, with the same "$" flags, are written as the
+    /// name. This keeps generated formulas readable. The formulas of the workbook are not changed.
+    ///
+    /// See [WorkBook::expand_range_names] for the reverse.
+    pub fn use_range_names(mut self, names: bool) -> Self {
+        self.range_names = names;
+        self
+    }
+
+    /// Progress information and cooperative cancellation while writing.
+    pub fn observer<O: WriteObserver + 'static>(mut self, observer: O) -> Self {
+        self.observer = Some(Observer(Box::new(observer)));
+        self
+    }
+
+    /// Writing is cancelled with [OdsError::Cancelled] when the flag
+    /// is set. It is checked for each row.
+    pub fn cancel(mut self, cancel: Arc<AtomicBool>) -> Self {
+        self.cancel = Some(cancel);
+        self
+    }
+
+    /// Fails if writing has been cancelled.
+    fn check_cancel(&self) -> Result<(), OdsError> {
+        match &self.cancel {
+            Some(cancel) if cancel.load(Ordering::Relaxed) => Err(OdsError::Cancelled),
+            _ => Ok(()),
+        }
+    }
+
+    /// Calls the observer if any. Returns an error if the observer
+    /// cancels writing.
+    fn observe(&mut self, f: impl FnOnce(&mut dyn WriteObserver) -> bool) -> Result<(), OdsError> {
+        if let Some(observer) = &mut self.observer {
+            if !f(observer.0.as_mut()) {
+                return Err(OdsError::Cancelled);
+            }
+        }
+        Ok(())
+    }
+
     /// Write the ods to the given writer.
     pub fn write_ods<T: Write + Seek>(
         self,
@@ -75,6 +383,36 @@ impl OdsWriteOptions {
 
         Ok(())
     }
+
+    /// Write the ods to the given writer, but check the xml against the
+    /// most important rules of the ODF schema first. See
+    /// [validate_ods](crate::validate_ods).
+    ///
+    /// Nothing is written if the check fails.
+    #[cfg(feature = "validate_odf")]
+    pub fn write_ods_validated<T: Write + Seek>(
+        self,
+        book: &mut WorkBook,
+        mut write: T,
+    ) -> Result<(), OdsError> {
+        let mut buf = Cursor::new(Vec::new());
+        self.write_ods(book, &mut buf)?;
+
+        let buf = buf.into_inner();
+        validate_ods(Cursor::new(buf.as_slice()))?;
+        write.write_all(&buf)?;
+
+        Ok(())
+    }
+
+    /// Write the fods to the given writer.
+    pub fn write_fods<T: Write>(self, book: &mut WorkBook, mut write: T) -> Result<(), OdsError> {
+        let write: &mut dyn Write = &mut write;
+
+        write_fods_impl(self, write, book)?;
+
+        Ok(())
+    }
 }
 
 /// Writes the ODS file into a supplied buffer.
@@ -121,20 +459,60 @@ pub fn write_ods<P: AsRef<Path>>(book: &mut WorkBook, ods_path: P) -> Result<(),
     Ok(())
 }
 
+/// Writes the ODS file, but checks the xml against the most important
+/// rules of the ODF schema first. See [OdsWriteOptions::write_ods_validated].
+///
+/// The file is not created if the check fails.
+#[cfg(feature = "validate_odf")]
+pub fn write_ods_validated<P: AsRef<Path>>(
+    book: &mut WorkBook,
+    ods_path: P,
+) -> Result<(), OdsError> {
+    let mut buf = Cursor::new(Vec::new());
+    OdsWriteOptions::default()
+        .compression_method(CompressionMethod::Deflated)
+        .write_ods_validated(book, &mut buf)?;
+
+    std::fs::write(ods_path, buf.into_inner())?;
+
+    Ok(())
+}
+
+/// Writes the ODS and returns the individual parts of the package.
+///
+/// The result is a list of (path, data) without the mimetype and the
+/// manifest. This is what is needed to embed the spreadsheet as an object
+/// in another document. The host document must list the parts in its
+/// own manifest and add an entry for the object directory with the
+/// media-type "application/vnd.oasis.opendocument.spreadsheet".
+pub fn write_ods_parts(book: &mut WorkBook) -> Result<Vec<(String, Vec<u8>)>, OdsError> {
+    let buf = write_ods_buf_uncompressed(book, Vec::new())?;
+
+    let mut zip = ZipArchive::new(Cursor::new(buf))?;
+    let mut parts = Vec::new();
+    for i in 0..zip.len() {
+        let mut file = zip.by_index(i)?;
+        if file.is_dir() || file.name() == "mimetype" || file.name() == "META-INF/manifest.xml" {
+            continue;
+        }
+        let mut data = Vec::new();
+        file.read_to_end(&mut data)?;
+        parts.push((file.name().to_string(), data));
+    }
+
+    Ok(parts)
+}
+
 /// Writes the FODS file into a supplied buffer.
 pub fn write_fods_buf(book: &mut WorkBook, mut buf: Vec<u8>) -> Result<Vec<u8>, OdsError> {
-    let write: &mut dyn Write = &mut buf;
-
-    write_fods_impl(write, book)?;
+    OdsWriteOptions::default().write_fods(book, &mut buf)?;
 
     Ok(buf)
 }
 
 /// Writes the FODS file to the given Write.
-pub fn write_fods_to<T: Write + Seek>(book: &mut WorkBook, mut write: T) -> Result<(), OdsError> {
-    let write: &mut dyn Write = &mut write;
-
-    write_fods_impl(write, book)?;
+pub fn write_fods_to<T: Write + Seek>(book: &mut WorkBook, write: T) -> Result<(), OdsError> {
+    OdsWriteOptions::default().write_fods(book, write)?;
 
     Ok(())
 }
@@ -142,23 +520,39 @@ pub fn write_fods_to<T: Write + Seek>(book: &mut WorkBook, mut write: T) -> Resu
 /// Writes the FODS file.
 pub fn write_fods<P: AsRef<Path>>(book: &mut WorkBook, fods_path: P) -> Result<(), OdsError> {
     let mut write = BufWriter::new(File::create(fods_path)?);
-    let write: &mut dyn Write = &mut write;
 
-    write_fods_impl(write, book)?;
+    OdsWriteOptions::default().write_fods(book, &mut write)?;
+
+    write.flush()?;
 
     Ok(())
 }
 
+/// Creates a xml writer for one part of the package.
+fn xml_writer<'a>(cfg: &OdsWriteOptions, write: &'a mut dyn Write) -> OdsXmlWriter<'a> {
+    XmlWriter::new(write)
+        .pretty(cfg.pretty)
+        .invalid_chars(cfg.invalid_chars)
+}
+
 /// Writes the ODS file.
 ///
-fn write_fods_impl(writer: &mut dyn Write, book: &mut WorkBook) -> Result<(), OdsError> {
-    sanity_checks(book)?;
+fn write_fods_impl(
+    mut cfg: OdsWriteOptions,
+    writer: &mut dyn Write,
+    book: &mut WorkBook,
+) -> Result<(), OdsError> {
+    sanity_checks(&cfg, book)?;
     calculations(book)?;
 
     convert(book)?;
 
-    let mut xml_out = XmlWriter::new(writer).line_break(true);
-    write_fods_content(book, &mut xml_out)?;
+    let mut xml_out = XmlWriter::new(writer)
+        .line_break(true)
+        .pretty(cfg.pretty)
+        .text_escape(Escape::Minimal)
+        .invalid_chars(cfg.invalid_chars);
+    write_fods_content(&mut cfg, book, &mut xml_out)?;
 
     Ok(())
 }
@@ -214,7 +608,11 @@ fn convert(book: &mut WorkBook) -> Result<(), OdsError> {
     Ok(())
 }
 
-fn write_fods_content(book: &mut WorkBook, xml_out: &mut OdsXmlWriter<'_>) -> Result<(), OdsError> {
+fn write_fods_content(
+    cfg: &mut OdsWriteOptions,
+    book: &mut WorkBook,
+    xml_out: &mut OdsXmlWriter<'_>,
+) -> Result<(), OdsError> {
     let xmlns = book
         .xmlns
         .entry("meta.xml".into())
@@ -331,7 +729,7 @@ fn write_fods_content(book: &mut WorkBook, xml_out: &mut OdsXmlWriter<'_>) -> Re
     write_office_styles(book, StyleOrigin::Content, xml_out)?;
     write_office_automatic_styles(book, StyleOrigin::Content, xml_out)?;
     write_office_master_styles(book, xml_out)?;
-    write_office_body(book, xml_out)?;
+    write_office_body(cfg, book, xml_out)?;
 
     xml_out.end_elem("office:document")?;
 
@@ -343,11 +741,11 @@ fn write_fods_content(book: &mut WorkBook, xml_out: &mut OdsXmlWriter<'_>) -> Re
 /// Writes the ODS file.
 ///
 fn write_ods_impl<W: Write + Seek>(
-    cfg: OdsWriteOptions,
+    mut cfg: OdsWriteOptions,
     mut zip_writer: ZipWriter<W>,
     book: &mut WorkBook,
 ) -> Result<(), OdsError> {
-    sanity_checks(book)?;
+    sanity_checks(&cfg, book)?;
     calculations(book)?;
 
     create_manifest(book)?;
@@ -365,7 +763,7 @@ fn write_ods_impl<W: Write + Seek>(
             .compression_method(cfg.method)
             .compression_level(cfg.level),
     )?;
-    write_ods_manifest(book, &mut XmlWriter::new(&mut zip_writer))?;
+    write_ods_manifest(book, &mut xml_writer(&cfg, &mut zip_writer))?;
 
     zip_writer.start_file(
         "meta.xml",
@@ -373,7 +771,7 @@ fn write_ods_impl<W: Write + Seek>(
             .compression_method(cfg.method)
             .compression_level(cfg.level),
     )?;
-    write_ods_metadata(book, &mut XmlWriter::new(&mut zip_writer))?;
+    write_ods_metadata(book, &mut xml_writer(&cfg, &mut zip_writer))?;
 
     zip_writer.start_file(
         "settings.xml",
@@ -381,7 +779,7 @@ fn write_ods_impl<W: Write + Seek>(
             .compression_method(cfg.method)
             .compression_level(cfg.level),
     )?;
-    write_ods_settings(book, &mut XmlWriter::new(&mut zip_writer))?;
+    write_ods_settings(book, &mut xml_writer(&cfg, &mut zip_writer))?;
 
     zip_writer.start_file(
         "styles.xml",
@@ -389,7 +787,7 @@ fn write_ods_impl<W: Write + Seek>(
             .compression_method(cfg.method)
             .compression_level(cfg.level),
     )?;
-    write_ods_styles(book, &mut XmlWriter::new(&mut zip_writer))?;
+    write_ods_styles(book, &mut xml_writer(&cfg, &mut zip_writer))?;
 
     zip_writer.start_file(
         "content.xml",
@@ -397,7 +795,8 @@ fn write_ods_impl<W: Write + Seek>(
             .compression_method(cfg.method)
             .compression_level(cfg.level),
     )?;
-    write_ods_content(book, &mut XmlWriter::new(&mut zip_writer))?;
+    let mut xml_out = xml_writer(&cfg, &mut zip_writer).text_escape(Escape::Minimal);
+    write_ods_content(&mut cfg, book, &mut xml_out)?;
 
     write_ods_extra(&cfg, &mut zip_writer, book)?;
 
@@ -407,13 +806,104 @@ fn write_ods_impl<W: Write + Seek>(
 }
 
 /// Sanity checks.
-fn sanity_checks(book: &mut WorkBook) -> Result<(), OdsError> {
+fn sanity_checks(cfg: &OdsWriteOptions, book: &mut WorkBook) -> Result<(), OdsError> {
     if book.sheets.is_empty() {
         return Err(OdsError::Ods("Workbook contains no sheets.".to_string()));
     }
+    if cfg.fail_on_lossy {
+        lossy_checks(book)?;
+    }
     Ok(())
 }
 
+/// Finds all content that would be dropped by the writer.
+fn lossy_checks(book: &WorkBook) -> Result<(), OdsError> {
+    let mut lost = Vec::new();
+
+    for tag in &book.extra {
+        if !BOOK_EXTRA_BEFORE.contains(&tag.name()) && !BOOK_EXTRA_AFTER.contains(&tag.name()) {
+            lost.push(format!("workbook: unsupported tag {}", tag.name()));
+        }
+    }
+    for sheet in book.iter_sheets() {
+        for tag in &sheet.extra {
+            if !SHEET_EXTRA_BEFORE.contains(&tag.name()) && !SHEET_EXTRA_AFTER.contains(&tag.name())
+            {
+                lost.push(format!("{}: unsupported tag {}", sheet.name, tag.name()));
+            }
+        }
+
+        // Only rows and columns up to the last cell are written.
+        let max_cell = sheet.used_grid_size();
+        for (row, rh) in &sheet.row_header {
+            let is_default = rh.style.is_none()
+                && rh.cellstyle.is_none()
+                && rh.visible == Visibility::Visible
+                && rh.height == Length::Default
+                && !rh.page_break;
+            if !is_default && row + rh.span > max_cell.0 {
+                lost.push(format!(
+                    "{}: row {} has formatting beyond the last row with data",
+                    sheet.name,
+                    max(*row, max_cell.0)
+                ));
+            }
+        }
+        for (col, ch) in &sheet.col_header {
+            let is_default = ch.style.is_none()
+                && ch.cellstyle.is_none()
+                && ch.visible == Visibility::Visible
+                && ch.width == Length::Default
+                && !ch.page_break;
+            if !is_default && col + ch.span > max_cell.1 {
+                lost.push(format!(
+                    "{}: column {} has formatting beyond the last column with data",
+                    sheet.name,
+                    max(*col, max_cell.1)
+                ));
+            }
+        }
+        for ((row, col), cell) in sheet.data.iter() {
+            if let Some(extra) = &cell.extra {
+                if row as u64 + extra.span.row_span as u64 > MAX_ROWS as u64
+                    || col as u64 + extra.span.col_span as u64 > MAX_COLS as u64
+                {
+                    lost.push(format!(
+                        "{}: merged cells at {} extend beyond the size of a sheet",
+                        sheet.name,
+                        CellRef::local(row, col)
+                    ));
+                }
+            }
+        }
+        for grp in &sheet.group_rows {
+            if grp.to >= max_cell.0 {
+                lost.push(format!(
+                    "{}: row group {}..{} ends beyond the last row with data",
+                    sheet.name, grp.from, grp.to
+                ));
+            }
+        }
+        if let Some(header_rows) = &sheet.header_rows {
+            if header_rows.to >= max_cell.0 {
+                lost.push(format!(
+                    "{}: header rows {}..{} end beyond the last row with data",
+                    sheet.name, header_rows.from, header_rows.to
+                ));
+            }
+        }
+    }
+
+    if lost.is_empty() {
+        Ok(())
+    } else {
+        Err(OdsError::Ods(format!(
+            "Content would be lost on write: {}",
+            lost.join("; ")
+        )))
+    }
+}
+
 /// Before write calculations.
 fn calculations(book: &mut WorkBook) -> Result<(), OdsError> {
     calc_metadata(book)?;
@@ -422,10 +912,317 @@ fn calculations(book: &mut WorkBook) -> Result<(), OdsError> {
     calc_row_header_styles(book)?;
     calc_col_header_styles(book)?;
     calc_col_headers(book)?;
+    calc_table_styles(book)?;
+    calc_print_settings(book)?;
+    calc_basic(book)?;
+
+    Ok(())
+}
+
+/// Creates the parts of the Basic/ directory from the basic libraries.
+fn calc_basic(book: &mut WorkBook) -> Result<(), OdsError> {
+    // password protected libraries are kept as they are.
+    let keep: Vec<String> = book
+        .basic
+        .iter()
+        .filter(|v| v.password_protected())
+        .map(|v| format!("Basic/{}/", v.name()))
+        .collect();
+    book.manifest.retain(|k, _| {
+        !k.starts_with("Basic/") || k == "Basic/" || keep.iter().any(|v| k.starts_with(v))
+    });
+
+    if book.basic.is_empty() {
+        book.manifest.remove("Basic/");
+        return Ok(());
+    }
+
+    let mut parts = Vec::new();
+
+    let mut buf = Vec::new();
+    let mut xml_out = XmlWriter::new(&mut buf);
+    xml_out.dtd("UTF-8")?;
+    xml_out.elem("library:libraries")?;
+    xml_out.attr_str("xmlns:library", "http://openoffice.org/2000/library")?;
+    xml_out.attr_str("xmlns:xlink", "http://www.w3.org/1999/xlink")?;
+    for library in &book.basic {
+        xml_out.empty("library:library")?;
+        xml_out.attr_esc("library:name", library.name())?;
+        if let Some(link) = library.link() {
+            xml_out.attr_esc("xlink:href", link)?;
+            xml_out.attr_str("xlink:type", "simple")?;
+            xml_out.attr_str("library:link", "true")?;
+            xml_out.attr("library:readonly", &library.readonly())?;
+        } else {
+            xml_out.attr_str("library:link", "false")?;
+        }
+    }
+    xml_out.end_elem("library:libraries")?;
+    xml_out.close()?;
+    parts.push(Manifest::with_buf("Basic/script-lc.xml", "text/xml", buf));
+
+    for library in &book.basic {
+        if library.link().is_some() || library.password_protected() {
+            continue;
+        }
+
+        let mut buf = Vec::new();
+        let mut xml_out = XmlWriter::new(&mut buf);
+        xml_out.dtd("UTF-8")?;
+        xml_out.elem("library:library")?;
+        xml_out.attr_str("xmlns:library", "http://openoffice.org/2000/library")?;
+        xml_out.attr_esc("library:name", library.name())?;
+        xml_out.attr("library:readonly", &library.readonly())?;
+        xml_out.attr_str("library:passwordprotected", "false")?;
+        for module in library.modules() {
+            xml_out.empty("library:element")?;
+            xml_out.attr_esc("library:name", module.name())?;
+        }
+        xml_out.end_elem("library:library")?;
+        xml_out.close()?;
+        parts.push(Manifest::with_buf(
+            format!("Basic/{}/script-lb.xml", library.name()),
+            "text/xml",
+            buf,
+        ));
+
+        for module in library.modules() {
+            let mut buf = Vec::new();
+            let mut xml_out = XmlWriter::new(&mut buf).text_escape(Escape::Cdata);
+            xml_out.dtd("UTF-8")?;
+            xml_out.elem("script:module")?;
+            xml_out.attr_str("xmlns:script", "http://openoffice.org/2000/script")?;
+            xml_out.attr_esc("script:name", module.name())?;
+            xml_out.attr_esc("script:language", module.language())?;
+            xml_out.attr_esc("script:moduleType", module.module_type())?;
+            xml_out.text_esc(module.source())?;
+            xml_out.end_elem("script:module")?;
+            xml_out.close()?;
+            parts.push(Manifest::with_buf(
+                format!("Basic/{}/{}.xml", library.name(), module.name()),
+                "text/xml",
+                buf,
+            ));
+        }
+    }
+
+    for part in parts {
+        book.add_manifest(part);
+    }
+
+    Ok(())
+}
+
+/// Transfer tab color and direction to the tablestyle of each sheet.
+fn calc_table_styles(book: &mut WorkBook) -> Result<(), OdsError> {
+    for i in 0..book.num_sheets() {
+        let mut sheet = book.detach_sheet(i);
+
+        let style = sheet.style.as_ref().and_then(|v| book.tablestyle(v));
+        let style_color = style.and_then(|v| v.tab_color().ok().flatten());
+        let style_rtl = style
+            .map(|v| {
+                matches!(
+                    v.writing_mode(),
+                    Ok(Some(WritingMode::RlTb | WritingMode::Rl))
+                )
+            })
+            .unwrap_or(false);
+
+        let style_masterpage = style.and_then(|v| v.attrmap().attr("style:master-page-name"));
+
+        // The style may be shared with other sheets, modify a copy.
+        if sheet.tab_color != style_color
+            || sheet.rtl != style_rtl
+            || (sheet.masterpage.is_some()
+                && sheet.masterpage.as_ref().map(|v| v.as_str()) != style_masterpage)
+        {
+            let mut style = style.cloned().unwrap_or_else(TableStyle::new_empty);
+            style.set_name("");
+            style.tablestyle_mut().clear_attr("tableooo:tab-color");
+            if let Some(color) = sheet.tab_color {
+                style.set_tab_color(color);
+            } else {
+                style.tablestyle_mut().clear_attr("table:tab-color");
+            }
+            if sheet.rtl {
+                style.set_writing_mode(WritingMode::RlTb);
+            } else if style_rtl {
+                style.tablestyle_mut().clear_attr("style:writing-mode");
+            }
+            if let Some(masterpage) = &sheet.masterpage {
+                style.set_master_page(masterpage);
+            }
+            sheet.style = Some(book.add_tablestyle(style));
+        }
+
+        book.attach_sheet(sheet);
+    }
 
     Ok(())
 }
 
+/// Transfer page order and scaling to the pagestyle of each sheet.
+/// If the pagestyle is used by other sheets too, the sheet gets its own
+/// copy of tablestyle, masterpage and pagestyle.
+fn calc_print_settings(book: &mut WorkBook) -> Result<(), OdsError> {
+    for i in 0..book.num_sheets() {
+        let sheet = book.sheet(i);
+        if sheet.print_page_order.is_none() && sheet.print_scale.is_none() {
+            continue;
+        }
+
+        let pagestyle_ref = used_pagestyle(book, sheet);
+        if let Some(pagestyle) = pagestyle_ref.as_ref().and_then(|v| book.pagestyle(v)) {
+            if print_settings_applied(sheet, pagestyle) {
+                continue;
+            }
+        }
+        let shared = match &pagestyle_ref {
+            Some(pagestyle_ref) => (0..book.num_sheets())
+                .filter(|j| *j != i)
+                .any(|j| used_pagestyle(book, book.sheet(j)).as_ref() == Some(pagestyle_ref)),
+            None => false,
+        };
+
+        let mut sheet = book.detach_sheet(i);
+
+        let pagestyle_ref = if shared {
+            copy_pagestyle(book, &mut sheet)
+        } else {
+            sheet_pagestyle(book, &mut sheet)
+        };
+        if let Some(pagestyle) = book.pagestyle_mut(&pagestyle_ref) {
+            if let Some(order) = sheet.print_page_order {
+                pagestyle.set_print_page_order(order);
+            }
+            match sheet.print_scale {
+                Some(PrintScale::Percent(v)) => {
+                    pagestyle.style_mut().clear_attr("style:scale-to-pages");
+                    pagestyle.style_mut().clear_attr("loext:scale-to-X");
+                    pagestyle.style_mut().clear_attr("loext:scale-to-Y");
+                    pagestyle.set_scale_to(Percent::Percent(v));
+                }
+                Some(PrintScale::Pages(v)) => {
+                    pagestyle.style_mut().clear_attr("style:scale-to");
+                    pagestyle.style_mut().clear_attr("loext:scale-to-X");
+                    pagestyle.style_mut().clear_attr("loext:scale-to-Y");
+                    pagestyle.set_scale_to_pages(v);
+                }
+                None => {}
+            }
+        }
+
+        book.attach_sheet(sheet);
+    }
+
+    Ok(())
+}
+
+/// The pagestyle via tablestyle -> masterpage -> pagestyle.
+fn used_pagestyle(book: &WorkBook, sheet: &Sheet) -> Option<PageStyleRef> {
+    let masterpage = book
+        .tablestyle(sheet.style()?)?
+        .attrmap()
+        .attr("style:master-page-name")?;
+    book.masterpage(masterpage)?.pagestyle().cloned()
+}
+
+/// Page order and scaling of the sheet are already set in the pagestyle.
+fn print_settings_applied(sheet: &Sheet, pagestyle: &PageStyle) -> bool {
+    let attr = |name: &str| pagestyle.style().attr(name);
+
+    let order = match sheet.print_page_order {
+        Some(order) => attr("style:print-page-order") == Some(order.to_string().as_str()),
+        None => true,
+    };
+    let scale = match sheet.print_scale {
+        Some(PrintScale::Percent(v)) => {
+            attr("style:scale-to") == Some(Percent::Percent(v).to_string().as_str())
+                && attr("style:scale-to-pages").is_none()
+        }
+        Some(PrintScale::Pages(v)) => {
+            attr("style:scale-to-pages") == Some(v.to_string().as_str())
+                && attr("style:scale-to").is_none()
+        }
+        None => true,
+    };
+    order && scale
+}
+
+/// Copies tablestyle, masterpage and pagestyle of the sheet, so they
+/// can be changed without affecting other sheets.
+fn copy_pagestyle(book: &mut WorkBook, sheet: &mut Sheet) -> PageStyleRef {
+    let mut tablestyle = sheet
+        .style()
+        .and_then(|v| book.tablestyle(v))
+        .cloned()
+        .unwrap_or_else(TableStyle::new_empty);
+    let mut masterpage = tablestyle
+        .attrmap()
+        .attr("style:master-page-name")
+        .and_then(|v| book.masterpage(v))
+        .cloned()
+        .unwrap_or_else(MasterPage::new_empty);
+    let mut pagestyle = masterpage
+        .pagestyle()
+        .and_then(|v| book.pagestyle(v))
+        .cloned()
+        .unwrap_or_else(PageStyle::new_empty);
+
+    pagestyle.set_name("");
+    let pagestyle_ref = book.add_pagestyle(pagestyle);
+    masterpage.set_name(String::new());
+    masterpage.set_display_name(String::new());
+    masterpage.set_pagestyle(&pagestyle_ref);
+    let masterpage_ref = book.add_masterpage(masterpage);
+    tablestyle.set_name("");
+    tablestyle.set_master_page(&masterpage_ref);
+    sheet.set_style(&book.add_tablestyle(tablestyle));
+    sheet.masterpage = Some(masterpage_ref);
+
+    pagestyle_ref
+}
+
+/// Finds the pagestyle for a sheet. Creates the chain
+/// tablestyle -> masterpage -> pagestyle if necessary.
+fn sheet_pagestyle(book: &mut WorkBook, sheet: &mut Sheet) -> PageStyleRef {
+    let tablestyle_ref = match sheet.style() {
+        Some(s) if book.tablestyle(s).is_some() => s.clone(),
+        _ => {
+            let s = book.add_tablestyle(TableStyle::new_empty());
+            sheet.set_style(&s);
+            s
+        }
+    };
+
+    let masterpage_ref = match book
+        .tablestyle(&tablestyle_ref)
+        .and_then(|v| v.attrmap().attr("style:master-page-name"))
+    {
+        Some(s) if book.masterpage(s).is_some() => MasterPageRef::from(s),
+        _ => {
+            let s = book.add_masterpage(MasterPage::new_empty());
+            if let Some(tablestyle) = book.tablestyle_mut(&tablestyle_ref) {
+                tablestyle.set_master_page(&s);
+            }
+            sheet.masterpage = Some(s.clone());
+            s
+        }
+    };
+
+    match book.masterpage(&masterpage_ref).and_then(|v| v.pagestyle()) {
+        Some(s) if book.pagestyle(s).is_some() => s.clone(),
+        _ => {
+            let s = book.add_pagestyle(PageStyle::new_empty());
+            if let Some(masterpage) = book.masterpage_mut(&masterpage_ref) {
+                masterpage.set_pagestyle(&s);
+            }
+            s
+        }
+    }
+}
+
 /// Compacting and normalizing column-headers.
 fn calc_col_headers(book: &mut WorkBook) -> Result<(), OdsError> {
     for i in 0..book.num_sheets() {
@@ -493,6 +1290,22 @@ fn calc_col_header_styles(book: &mut WorkBook) -> Result<(), OdsError> {
                 ch.style = Some(colstyle);
             }
 
+            // Page breaks need a separate style.
+            let style = ch.style.as_ref().and_then(|v| book.colstyle(v));
+            let style_break = style
+                .map(|v| v.colstyle().attr("fo:break-before") == Some("page"))
+                .unwrap_or(false);
+            if ch.page_break != style_break {
+                let mut style = style.cloned().unwrap_or_else(ColStyle::new_empty);
+                style.set_name("");
+                if ch.page_break {
+                    style.set_break_before(PageBreak::Page);
+                } else {
+                    style.colstyle_mut().clear_attr("fo:break-before");
+                }
+                ch.style = Some(book.add_colstyle(style));
+            }
+
             // Write back to the style.
             if let Some(style_name) = ch.style.as_ref() {
                 if let Some(style) = book.colstyle_mut(style_name) {
@@ -517,12 +1330,30 @@ fn calc_row_header_styles(book: &mut WorkBook) -> Result<(), OdsError> {
     for i in 0..book.num_sheets() {
         let mut sheet = book.detach_sheet(i);
 
+        dedup_rowheader(&mut sheet)?;
+
         for rh in sheet.row_header.values_mut() {
             if rh.height != Length::Default && rh.style.is_none() {
                 let rowstyle = book.add_rowstyle(RowStyle::new_empty());
                 rh.style = Some(rowstyle);
             }
 
+            // Page breaks need a separate style.
+            let style = rh.style.as_ref().and_then(|v| book.rowstyle(v));
+            let style_break = style
+                .map(|v| v.rowstyle().attr("fo:break-before") == Some("page"))
+                .unwrap_or(false);
+            if rh.page_break != style_break {
+                let mut style = style.cloned().unwrap_or_else(RowStyle::new_empty);
+                style.set_name("");
+                if rh.page_break {
+                    style.set_break_before(PageBreak::Page);
+                } else {
+                    style.rowstyle_mut().clear_attr("fo:break-before");
+                }
+                rh.style = Some(book.add_rowstyle(style));
+            }
+
             if let Some(style_name) = rh.style.as_ref() {
                 if let Some(style) = book.rowstyle_mut(style_name) {
                     if rh.height == Length::Default {
@@ -575,6 +1406,16 @@ fn calc_config(book: &mut WorkBook) -> Result<(), OdsError> {
     bc.insert("HasSheetTabs", book.config().has_sheet_tabs);
     bc.insert("ShowGrid", book.config().show_grid);
     bc.insert("ShowPageBreaks", book.config().show_page_breaks);
+    bc.insert(
+        "ShowPageBreakPreview",
+        book.config().show_page_break_preview,
+    );
+
+    // LibreOffice only keeps embedded fonts when saving with this flag.
+    if book.fonts.values().any(|v| !v.src().is_empty()) {
+        let bc = config.create_path(&[("ooo:configuration-settings", ConfigItemType::Set)]);
+        bc.insert("EmbedFonts", true);
+    }
 
     for i in 0..book.num_sheets() {
         let sheet = book.detach_sheet(i);
@@ -602,6 +1443,12 @@ fn calc_config(book: &mut WorkBook) -> Result<(), OdsError> {
         bc.insert("ZoomValue", sheet.config().zoom_value);
         bc.insert("PageViewZoomValue", sheet.config().page_view_zoom_value);
         bc.insert("ShowGrid", sheet.config().show_grid);
+        // -1 is the automatic color.
+        let tab_color = sheet
+            .tab_color
+            .map(|v| ((v.r as i32) << 16) | ((v.g as i32) << 8) | v.b as i32)
+            .unwrap_or(-1);
+        bc.insert("TabColor", tab_color);
 
         let bc = config.create_path(&[
             ("ooo:configuration-settings", ConfigItemType::Set),
@@ -707,7 +1554,10 @@ fn write_ods_manifest(book: &WorkBook, xml_out: &mut OdsXmlWriter<'_>) -> Result
     )?;
     xml_out.attr_esc("manifest:version", &book.version())?;
 
-    for manifest in book.manifest.values() {
+    // The root entry goes first, Excel requires it.
+    let mut manifests = sorted_values(&book.manifest);
+    manifests.sort_by_key(|v| v.full_path != "/");
+    for manifest in manifests {
         xml_out.empty("manifest:file-entry")?;
         xml_out.attr_esc("manifest:full-path", &manifest.full_path)?;
         if let Some(version) = &manifest.version {
@@ -946,17 +1796,25 @@ fn write_office_settings(book: &WorkBook, xml_out: &mut OdsXmlWriter<'_>) -> Res
     for (name, item) in book.config.iter() {
         match item {
             ConfigItem::Value(_) => {
-                panic!("office-settings must not contain config-item");
+                return Err(OdsError::Ods(
+                    "office-settings must not contain config-item".to_string(),
+                ))
             }
             ConfigItem::Set(_) => write_config_item_set(name, item, xml_out)?,
             ConfigItem::Vec(_) => {
-                panic!("office-settings must not contain config-item-map-index")
+                return Err(OdsError::Ods(
+                    "office-settings must not contain config-item-map-index".to_string(),
+                ))
             }
             ConfigItem::Map(_) => {
-                panic!("office-settings must not contain config-item-map-named")
+                return Err(OdsError::Ods(
+                    "office-settings must not contain config-item-map-named".to_string(),
+                ))
             }
             ConfigItem::Entry(_) => {
-                panic!("office-settings must not contain config-item-map-entry")
+                return Err(OdsError::Ods(
+                    "office-settings must not contain config-item-map-entry".to_string(),
+                ))
             }
         }
     }
@@ -980,7 +1838,9 @@ fn write_config_item_set(
             ConfigItem::Vec(_) => write_config_item_map_indexed(name, item, xml_out)?,
             ConfigItem::Map(_) => write_config_item_map_named(name, item, xml_out)?,
             ConfigItem::Entry(_) => {
-                panic!("config-item-set must not contain config-item-map-entry")
+                return Err(OdsError::Ods(
+                    "config-item-set must not contain config-item-map-entry".to_string(),
+                ))
             }
         }
     }
@@ -1005,13 +1865,19 @@ fn write_config_item_map_indexed(
             match item {
                 ConfigItem::Value(value) => write_config_item(name, value, xml_out)?,
                 ConfigItem::Set(_) => {
-                    panic!("config-item-map-index must not contain config-item-set")
+                    return Err(OdsError::Ods(
+                        "config-item-map-index must not contain config-item-set".to_string(),
+                    ))
                 }
                 ConfigItem::Vec(_) => {
-                    panic!("config-item-map-index must not contain config-item-map-index")
+                    return Err(OdsError::Ods(
+                        "config-item-map-index must not contain config-item-map-index".to_string(),
+                    ))
                 }
                 ConfigItem::Map(_) => {
-                    panic!("config-item-map-index must not contain config-item-map-named")
+                    return Err(OdsError::Ods(
+                        "config-item-map-index must not contain config-item-map-named".to_string(),
+                    ))
                 }
                 ConfigItem::Entry(_) => write_config_item_map_entry(None, item, xml_out)?,
             }
@@ -1039,13 +1905,19 @@ fn write_config_item_map_named(
         match item {
             ConfigItem::Value(value) => write_config_item(name, value, xml_out)?,
             ConfigItem::Set(_) => {
-                panic!("config-item-map-index must not contain config-item-set")
+                return Err(OdsError::Ods(
+                    "config-item-map-index must not contain config-item-set".to_string(),
+                ))
             }
             ConfigItem::Vec(_) => {
-                panic!("config-item-map-index must not contain config-item-map-index")
+                return Err(OdsError::Ods(
+                    "config-item-map-index must not contain config-item-map-index".to_string(),
+                ))
             }
             ConfigItem::Map(_) => {
-                panic!("config-item-map-index must not contain config-item-map-named")
+                return Err(OdsError::Ods(
+                    "config-item-map-index must not contain config-item-map-named".to_string(),
+                ))
             }
             ConfigItem::Entry(_) => write_config_item_map_entry(Some(name), item, xml_out)?,
         }
@@ -1073,7 +1945,10 @@ fn write_config_item_map_entry(
             ConfigItem::Vec(_) => write_config_item_map_indexed(name, item, xml_out)?,
             ConfigItem::Map(_) => write_config_item_map_named(name, item, xml_out)?,
             ConfigItem::Entry(_) => {
-                panic!("config:config-item-map-entry must not contain config-item-map-entry")
+                return Err(OdsError::Ods(
+                    "config:config-item-map-entry must not contain config-item-map-entry"
+                        .to_string(),
+                ))
             }
         }
     }
@@ -1245,7 +2120,11 @@ fn write_ods_styles(book: &mut WorkBook, xml_out: &mut OdsXmlWriter<'_>) -> Resu
     Ok(())
 }
 
-fn write_ods_content(book: &mut WorkBook, xml_out: &mut OdsXmlWriter<'_>) -> Result<(), OdsError> {
+fn write_ods_content(
+    cfg: &mut OdsWriteOptions,
+    book: &mut WorkBook,
+    xml_out: &mut OdsXmlWriter<'_>,
+) -> Result<(), OdsError> {
     let xmlns = book
         .xmlns
         .entry("content.xml".into())
@@ -1351,7 +2230,7 @@ fn write_ods_content(book: &mut WorkBook, xml_out: &mut OdsXmlWriter<'_>) -> Res
     write_office_font_face_decls(book, StyleOrigin::Content, xml_out)?;
     write_office_automatic_styles(book, StyleOrigin::Content, xml_out)?;
 
-    write_office_body(book, xml_out)?;
+    write_office_body(cfg, book, xml_out)?;
 
     xml_out.end_elem("office:document-content")?;
 
@@ -1360,54 +2239,118 @@ fn write_ods_content(book: &mut WorkBook, xml_out: &mut OdsXmlWriter<'_>) -> Res
     Ok(())
 }
 
-fn write_office_body(book: &WorkBook, xml_out: &mut OdsXmlWriter<'_>) -> Result<(), OdsError> {
+fn write_office_body(
+    cfg: &mut OdsWriteOptions,
+    book: &WorkBook,
+    xml_out: &mut OdsXmlWriter<'_>,
+) -> Result<(), OdsError> {
     xml_out.elem("office:body")?;
     xml_out.elem("office:spreadsheet")?;
 
+    write_calc_settings(book, xml_out)?;
+
     // extra tags. pass through only
     for tag in &book.extra {
-        if tag.name() == "table:calculation-settings"
-            || tag.name() == "table:label-ranges"
-            || tag.name() == "table:tracked-changes"
-            || tag.name() == "text:alphabetical-index-auto-mark-file"
-            || tag.name() == "text:dde-connection-decls"
-            || tag.name() == "text:sequence-decls"
-            || tag.name() == "text:user-field-decls"
-            || tag.name() == "text:variable-decls"
-        {
+        if BOOK_EXTRA_BEFORE.contains(&tag.name()) {
             write_xmltag(tag, xml_out)?;
         }
     }
 
     write_content_validations(book, xml_out)?;
 
-    for sheet in &book.sheets {
-        write_sheet(book, sheet, xml_out)?;
+    for (idx, sheet) in book.sheets.iter().enumerate() {
+        cfg.check_cancel()?;
+        cfg.observe(|v| v.sheet_start(idx, &sheet.name, sheet.used_grid_size().0))?;
+        write_sheet(cfg, book, idx, sheet, xml_out)?;
+        cfg.observe(|v| v.sheet_end(idx))?;
     }
 
     // extra tags. pass through only
     for tag in &book.extra {
-        if tag.name() == "table:consolidation"
-            || tag.name() == "table:data-pilot-tables"
-            || tag.name() == "table:database-ranges"
-            || tag.name() == "table:dde-links"
-            || tag.name() == "table:named-expressions"
-            || tag.name() == "calcext:conditional-formats"
-        {
+        if BOOK_EXTRA_AFTER.contains(&tag.name()) {
             write_xmltag(tag, xml_out)?;
         }
     }
 
+    if !book.dde_links.is_empty() {
+        let mut tag = XmlTag::new("table:dde-links");
+        for link in &book.dde_links {
+            tag.add_tag(link.to_xml());
+        }
+        write_xmltag(&tag, xml_out)?;
+    }
+
     xml_out.end_elem("office:spreadsheet")?;
     xml_out.end_elem("office:body")?;
     Ok(())
 }
 
 fn write_office_scripts(book: &WorkBook, xml_out: &mut OdsXmlWriter<'_>) -> Result<(), OdsError> {
-    xml_out.elem_if(!book.scripts.is_empty(), "office:scripts")?;
+    let has_scripts = !book.scripts.is_empty() || !book.event_listener.is_empty();
+    xml_out.elem_if(has_scripts, "office:scripts")?;
     write_scripts(&book.scripts, xml_out)?;
     write_event_listeners(&book.event_listener, xml_out)?;
-    xml_out.end_elem_if(!book.scripts.is_empty(), "office:scripts")?;
+    xml_out.end_elem_if(has_scripts, "office:scripts")?;
+    Ok(())
+}
+
+fn write_calc_settings(book: &WorkBook, xml_out: &mut OdsXmlWriter<'_>) -> Result<(), OdsError> {
+    let calc = book.calc_settings();
+    let def = CalcSettings::default();
+    if *calc == def {
+        return Ok(());
+    }
+
+    let null_date = calc.null_date != def.null_date;
+    let iteration = calc.iteration != def.iteration
+        || calc.iteration_steps != def.iteration_steps
+        || calc.iteration_min_difference != def.iteration_min_difference;
+
+    xml_out.elem_if(null_date || iteration, "table:calculation-settings")?;
+    if calc.case_sensitive != def.case_sensitive {
+        xml_out.attr("table:case-sensitive", &calc.case_sensitive)?;
+    }
+    if calc.precision_as_shown != def.precision_as_shown {
+        xml_out.attr("table:precision-as-shown", &calc.precision_as_shown)?;
+    }
+    if calc.search_criteria_must_apply_to_whole_cell != def.search_criteria_must_apply_to_whole_cell
+    {
+        xml_out.attr(
+            "table:search-criteria-must-apply-to-whole-cell",
+            &calc.search_criteria_must_apply_to_whole_cell,
+        )?;
+    }
+    if calc.automatic_find_labels != def.automatic_find_labels {
+        xml_out.attr("table:automatic-find-labels", &calc.automatic_find_labels)?;
+    }
+    if calc.use_regular_expressions != def.use_regular_expressions {
+        xml_out.attr(
+            "table:use-regular-expressions",
+            &calc.use_regular_expressions,
+        )?;
+    }
+    if calc.use_wildcards != def.use_wildcards {
+        xml_out.attr("table:use-wildcards", &calc.use_wildcards)?;
+    }
+    if calc.null_year != def.null_year {
+        xml_out.attr("table:null-year", &calc.null_year)?;
+    }
+    if null_date {
+        xml_out.empty("table:null-date")?;
+        xml_out.attr_str("table:value-type", "date")?;
+        xml_out.attr("table:date-value", &calc.null_date.format(DATE_FORMAT))?;
+    }
+    if iteration {
+        xml_out.empty("table:iteration")?;
+        xml_out.attr_str(
+            "table:status",
+            if calc.iteration { "enable" } else { "disable" },
+        )?;
+        xml_out.attr("table:steps", &calc.iteration_steps)?;
+        xml_out.attr("table:minimum-difference", &calc.iteration_min_difference)?;
+    }
+    xml_out.end_elem_if(null_date || iteration, "table:calculation-settings")?;
+
     Ok(())
 }
 
@@ -1418,7 +2361,7 @@ fn write_content_validations(
     if !book.validations.is_empty() {
         xml_out.elem("table:content-validations")?;
 
-        for valid in book.validations.values() {
+        for valid in sorted_values(&book.validations) {
             xml_out.elem("table:content-validation")?;
             xml_out.attr_esc("table:name", valid.name())?;
             xml_out.attr_esc("table:condition", &format_validation_condition(valid))?;
@@ -1481,6 +2424,27 @@ impl SplitCols {
     }
 }
 
+/// Splits the hidden ranges into single columns.
+fn split_single_hidden(out: &mut Vec<SplitCols>) {
+    if out.iter().all(|v| !v.hidden || v.col == v.col_to) {
+        return;
+    }
+    let split = mem::take(out);
+    for s in split {
+        if s.hidden {
+            for col in s.col..=s.col_to {
+                out.push(SplitCols {
+                    col,
+                    col_to: col,
+                    hidden: true,
+                });
+            }
+        } else {
+            out.push(s);
+        }
+    }
+}
+
 /// Is the cell (partially) hidden?
 fn split_hidden(ranges: &[CellRange], row: u32, col: u32, repeat: u32, out: &mut Vec<SplitCols>) {
     out.clear();
@@ -1574,10 +2538,21 @@ fn remove_outlived(ranges: &mut Vec<CellRange>, row: u32, col: u32) {
 }
 
 fn write_sheet(
+    cfg: &mut OdsWriteOptions,
     book: &WorkBook,
+    idx: usize,
     sheet: &Sheet,
     xml_out: &mut OdsXmlWriter<'_>,
 ) -> Result<(), OdsError> {
+    let mut repeats = Repeats::default();
+    if cfg.compress_cols {
+        repeats.cells = compress_cols(sheet);
+    }
+    if cfg.compress_rows {
+        repeats.rows = compress_rows(sheet, &repeats.cells);
+    }
+    let names = RangeNames::new(cfg, book, sheet);
+
     xml_out.elem("table:table")?;
     xml_out.attr_esc("table:name", &sheet.name)?;
     if let Some(style) = sheet.style.as_ref() {
@@ -1592,19 +2567,22 @@ fn write_sheet(
     if !sheet.display() {
         xml_out.attr_str("table:display", "false")?;
     }
+    if sheet.protected() {
+        xml_out.attr_str("table:protected", "true")?;
+    }
+    if let Some(key) = &sheet.protection_key {
+        xml_out.attr_esc("table:protection-key", key)?;
+    }
+    if let Some(digest) = &sheet.protection_key_digest {
+        xml_out.attr_esc("table:protection-key-digest-algorithm", digest)?;
+    }
 
     for tag in &sheet.extra {
-        if tag.name() == "table:title"
-            || tag.name() == "table:desc"
-            || tag.name() == "table:table-source"
-            || tag.name() == "office:dde-source"
-            || tag.name() == "table:scenario"
-            || tag.name() == "office:forms"
-            || tag.name() == "table:shapes"
-        {
+        if SHEET_EXTRA_BEFORE.contains(&tag.name()) {
             write_xmltag(tag, xml_out)?;
         }
     }
+    write_event_listeners(&sheet.event_listener, xml_out)?;
 
     let max_cell = sheet.used_grid_size();
 
@@ -1622,16 +2600,30 @@ fn write_sheet(
     let mut row_group_count = 0;
     let mut row_header = false;
 
-    let mut it = CellDataIter::new(sheet.data.range(..));
+    let row_step = cfg
+        .observer
+        .as_ref()
+        .map(|v| max(v.0.row_step(), 1))
+        .unwrap_or(u32::MAX);
+    let mut next_step = row_step;
+
+    let mut it = sheet
+        .data
+        .iter()
+        .filter(|((row, col), _)| !repeats.is_covered(*row, *col))
+        .peekable();
     while let Some(((cur_row, cur_col), cell)) = it.next() {
+        if cur_row >= next_step {
+            cfg.observe(|v| v.sheet_rows(idx, cur_row))?;
+            next_step = (cur_row / row_step)
+                .saturating_add(1)
+                .saturating_mul(row_step);
+        }
+
         // Row repeat count.
-        let cur_row_repeat = if let Some(row_header) = sheet.row_header.get(&cur_row) {
-            row_header.repeat
-        } else {
-            1
-        };
+        let cur_row_repeat = repeats.row_repeat(sheet, cur_row);
         // Cell repeat count.
-        let cur_col_repeat = cell.repeat;
+        let cur_col_repeat = repeats.cell_repeat(cur_row, cur_col, cell);
 
         // There may be a lot of gaps of any kind in our data.
         // In the XML format there is no cell identification, every gap
@@ -1640,9 +2632,9 @@ fn write_sheet(
 
         // For the repeat-counter we need to look forward.
         let (next_row, next_col, is_last_cell) = //_
-            if let Some((next_row, next_col)) = it.peek_cell()
+            if let Some(((next_row, next_col), _)) = it.peek()
         {
-            (next_row, next_col, false)
+            (*next_row, *next_col, false)
         } else {
             (max_cell.0, max_cell.1, true)
         };
@@ -1666,6 +2658,10 @@ fn write_sheet(
             cur_col - prev_col
         };
 
+        if backward_delta_row > 0 {
+            cfg.check_cancel()?;
+        }
+
         // After the first cell there is always an open row tag that
         // needs to be closed.
         if backward_delta_row > 0 && !first_cell {
@@ -1730,6 +2726,9 @@ fn write_sheet(
 
         // Split the current cell in visible/hidden ranges.
         split_hidden(&spans, cur_row, cur_col, cur_col_repeat, &mut split);
+        if !cfg.target.repeat_covered() {
+            split_single_hidden(&mut split);
+        }
 
         // Maybe span, only if visible. That nicely eliminates all double hides.
         // Only check for the start cell in case of repeat.
@@ -1739,9 +2738,16 @@ fn write_sheet(
             }
         }
 
+        if cfg.non_finite == NonFiniteMode::Error && is_non_finite(&cell.value) {
+            return Err(OdsError::Ods(format!(
+                "{}: non-finite number {:?} for row/col {}/{}.",
+                sheet.name, cell.value, cur_row, cur_col,
+            )));
+        }
+
         // And now to something completely different ...
         for s in &split {
-            write_cell(book, cell, s.hidden, s.repeat(), xml_out)?;
+            write_cell(cfg, book, &names, cell, s.hidden, s.repeat(), xml_out)?;
         }
 
         // There may be some blank cells until the next one.
@@ -1757,6 +2763,9 @@ fn write_sheet(
                     synth_delta_col,
                     &mut split,
                 );
+                if !cfg.target.repeat_covered() {
+                    split_single_hidden(&mut split);
+                }
                 for s in &split {
                     write_empty_cells(s.hidden, s.repeat(), xml_out)?;
                 }
@@ -1779,6 +2788,9 @@ fn write_sheet(
                     synth_delta_col,
                     &mut split,
                 );
+                if !cfg.target.repeat_covered() {
+                    split_single_hidden(&mut split);
+                }
                 for s in &split {
                     write_empty_cells(s.hidden, s.repeat(), xml_out)?;
                 }
@@ -1797,17 +2809,360 @@ fn write_sheet(
         prev_col = cur_col;
     }
 
-    xml_out.end_elem("table:table")?;
-
+    let mut conditional_formats = false;
     for tag in &sheet.extra {
-        if tag.name() == "table:named-expressions" || tag.name() == "calcext:conditional-formats" {
+        if tag.name() == "calcext:conditional-formats" {
+            write_conditional_formats(sheet, Some(tag), xml_out)?;
+            conditional_formats = true;
+        } else if SHEET_EXTRA_AFTER.contains(&tag.name()) {
             write_xmltag(tag, xml_out)?;
         }
     }
+    if !conditional_formats && !sheet.conditional_formats.is_empty() {
+        write_conditional_formats(sheet, None, xml_out)?;
+    }
+
+    xml_out.end_elem("table:table")?;
 
     Ok(())
 }
 
+/// Writes the data bars and color scales together with any
+/// calcext:conditional-formats that have been read.
+fn write_conditional_formats(
+    sheet: &Sheet,
+    extra: Option<&XmlTag>,
+    xml_out: &mut OdsXmlWriter<'_>,
+) -> Result<(), OdsError> {
+    xml_out.elem("calcext:conditional-formats")?;
+    if let Some(extra) = extra {
+        for (k, v) in extra.attrmap().iter() {
+            xml_out.attr_esc(k.as_ref(), v)?;
+        }
+        for c in extra.content() {
+            match c {
+                XmlContent::Text(t) => {
+                    xml_out.text_esc(t)?;
+                }
+                XmlContent::Tag(t) => {
+                    write_xmltag(t, xml_out)?;
+                }
+            }
+        }
+    }
+
+    for cond_format in &sheet.conditional_formats {
+        let mut range = cond_format.range().clone();
+        if range.table().is_none() {
+            range.set_table(sheet.name().as_str());
+        }
+        if range.to_table().is_none() {
+            range.set_to_table(sheet.name().as_str());
+        }
+
+        xml_out.elem("calcext:conditional-format")?;
+        xml_out.attr_esc("calcext:target-range-address", &range)?;
+        match cond_format.format() {
+            ConditionalFormatKind::DataBar(data_bar) => {
+                xml_out.elem("calcext:data-bar")?;
+                xml_out.attr("calcext:max-length", &data_bar.max_length())?;
+                xml_out.attr("calcext:min-length", &data_bar.min_length())?;
+                xml_out.attr(
+                    "calcext:negative-color",
+                    &color_string(data_bar.negative_color()),
+                )?;
+                xml_out.attr(
+                    "calcext:positive-color",
+                    &color_string(data_bar.positive_color()),
+                )?;
+                xml_out.attr("calcext:axis-color", &color_string(data_bar.axis_color()))?;
+                xml_out.attr("calcext:axis-position", &data_bar.axis_position())?;
+                xml_out.attr("calcext:gradient", &data_bar.gradient())?;
+                xml_out.attr("calcext:show-value", &data_bar.show_value())?;
+                for value in [data_bar.min(), data_bar.max()] {
+                    xml_out.empty("calcext:formatting-entry")?;
+                    xml_out.attr_esc("calcext:value", &value.value_str())?;
+                    xml_out.attr("calcext:type", value.type_str())?;
+                }
+                xml_out.end_elem("calcext:data-bar")?;
+            }
+            ConditionalFormatKind::ColorScale(color_scale) => {
+                xml_out.elem("calcext:color-scale")?;
+                for entry in color_scale.entries() {
+                    xml_out.empty("calcext:color-scale-entry")?;
+                    xml_out.attr_esc("calcext:value", &entry.value().value_str())?;
+                    xml_out.attr("calcext:type", entry.value().type_str())?;
+                    xml_out.attr("calcext:color", &color_string(entry.color()))?;
+                }
+                xml_out.end_elem("calcext:color-scale")?;
+            }
+        }
+        xml_out.end_elem("calcext:conditional-format")?;
+    }
+
+    xml_out.end_elem("calcext:conditional-formats")?;
+    Ok(())
+}
+
+// The reader resets large repeat values near the end of the sheet.
+const MAX_ROW_RUN: u32 = 1000;
+
+// Start row, length and the cells of the start row.
+type RowRun<'a> = (u32, u32, Vec<(u32, &'a CellData, u32)>);
+
+/// Finds runs of identical rows that can be written as one row with a
+/// repeat count. The cell repeats from compress_cols are taken into
+/// account.
+///
+/// Returns the start row and the length of each run.
+fn compress_rows(sheet: &Sheet, cell_repeats: &BTreeMap<(u32, u32), u32>) -> BTreeMap<u32, u32> {
+    // Rows touched by a cell-span are left alone.
+    let spans: Vec<(u32, u32)> = sheet
+        .data
+        .iter()
+        .filter_map(|((row, _), cell)| {
+            cell.extra
+                .as_ref()
+                .filter(|v| v.span.row_span > 1 || v.span.col_span > 1)
+                .map(|v| (row, row + v.span.row_span))
+        })
+        .collect();
+
+    let compressible = |row: u32, cells: &[(u32, &CellData, u32)]| {
+        cells.iter().all(|(_, cell, _)| cell.extra.is_none())
+            && sheet.row_header.get(&row).map(|v| v.repeat).unwrap_or(1) == 1
+            && !spans.iter().any(|(from, to)| (*from..*to).contains(&row))
+    };
+    // No group or print-header may start or end within a run.
+    let boundary = |row: u32| {
+        sheet
+            .group_rows
+            .iter()
+            .any(|v| v.from() == row || v.to() + 1 == row)
+            || sheet
+                .header_rows
+                .as_ref()
+                .map(|v| v.from == row || v.to + 1 == row)
+                .unwrap_or(false)
+    };
+    let same_header =
+        |row0: u32, row1: u32| match (sheet.valid_row_header(row0), sheet.valid_row_header(row1)) {
+            (None, None) => true,
+            (Some(h0), Some(h1)) => {
+                h0.style == h1.style
+                    && h0.cellstyle == h1.cellstyle
+                    && h0.visible == h1.visible
+                    && h0.height == h1.height
+                    && h0.page_break == h1.page_break
+                    && h0.attr == h1.attr
+            }
+            _ => false,
+        };
+    let same_cells = |cells0: &[(u32, &CellData, u32)], cells1: &[(u32, &CellData, u32)]| {
+        cells0.len() == cells1.len()
+            && cells0
+                .iter()
+                .zip(cells1.iter())
+                .all(|((c0, d0, n0), (c1, d1, n1))| {
+                    c0 == c1
+                        && d0.value == d1.value
+                        && d0.formula == d1.formula
+                        && d0.style == d1.style
+                        && n0 == n1
+                })
+    };
+    let covered = |row: u32, col: u32| {
+        cell_repeats
+            .range(..(row, col))
+            .next_back()
+            .map(|((r, c), len)| *r == row && col < c + len)
+            .unwrap_or(false)
+    };
+
+    let mut runs = Vec::new();
+    let mut run: Option<RowRun<'_>> = None;
+
+    let mut it = sheet
+        .data
+        .iter()
+        .filter(|((row, col), _)| !covered(*row, *col))
+        .map(|((row, col), cell)| {
+            let repeat = cell_repeats.get(&(row, col)).copied();
+            ((row, col), cell, repeat.unwrap_or(cell.repeat))
+        })
+        .peekable();
+    while let Some(((row, col), cell, repeat)) = it.next() {
+        let mut cells = vec![(col, cell, repeat)];
+        while let Some(((_, col), cell, repeat)) = it.next_if(|((r, _), _, _)| *r == row) {
+            cells.push((col, cell, repeat));
+        }
+
+        if !compressible(row, &cells) {
+            if let Some((start, len, _)) = run.take() {
+                runs.push((start, len));
+            }
+            continue;
+        }
+
+        match &mut run {
+            Some((start, len, start_cells))
+                if *start + *len == row
+                    && *len < MAX_ROW_RUN
+                    && !boundary(row)
+                    && same_header(*start, row)
+                    && same_cells(start_cells, &cells) =>
+            {
+                *len += 1;
+            }
+            _ => {
+                if let Some((start, len, _)) = run.replace((row, 1, cells)) {
+                    runs.push((start, len));
+                }
+            }
+        }
+    }
+    if let Some((start, len, _)) = run {
+        runs.push((start, len));
+    }
+    runs.retain(|(_, len)| *len > 1);
+    runs.into_iter().collect()
+}
+
+/// Named ranges of the sheet and the workbook, used to replace the
+/// references in formulas with their names.
+struct RangeNames<'a> {
+    sheet: &'a str,
+    names: Vec<(String, CellRange)>,
+}
+
+impl<'a> RangeNames<'a> {
+    fn new(cfg: &OdsWriteOptions, book: &WorkBook, sheet: &'a Sheet) -> Self {
+        let names = if cfg.range_names {
+            sheet
+                .range_names()
+                .into_iter()
+                .chain(book.range_names())
+                .collect()
+        } else {
+            Vec::new()
+        };
+        Self {
+            sheet: &sheet.name,
+            names,
+        }
+    }
+
+    /// Replaces the references in the formula with the names of named ranges.
+    fn collapse<'b>(&self, formula: &'b str) -> Cow<'b, str> {
+        if self.names.is_empty() {
+            return Cow::Borrowed(formula);
+        }
+        Cow::Owned(collapse_range_names(formula, |range| {
+            self.names
+                .iter()
+                .find(|(_, v)| self.same_area(v, range))
+                .map(|(name, _)| name.clone())
+        }))
+    }
+
+    /// Same area including the "$" flags.
+    fn same_area(&self, r0: &CellRange, r1: &CellRange) -> bool {
+        let single_table = |v: &CellRange| v.to_table().is_none() || v.to_table() == v.table();
+        single_table(r0)
+            && single_table(r1)
+            && r0.table().map(String::as_str).unwrap_or(self.sheet)
+                == r1.table().map(String::as_str).unwrap_or(self.sheet)
+            && r0.row() == r1.row()
+            && r0.col() == r1.col()
+            && r0.to_row() == r1.to_row()
+            && r0.to_col() == r1.to_col()
+            && r0.row_abs() == r1.row_abs()
+            && r0.col_abs() == r1.col_abs()
+            && r0.to_row_abs() == r1.to_row_abs()
+            && r0.to_col_abs() == r1.to_col_abs()
+    }
+}
+
+/// Finds runs of identical cells within a row that can be written as
+/// one cell with a repeat count.
+///
+/// Returns the start cell and the length of each run.
+fn compress_cols(sheet: &Sheet) -> BTreeMap<(u32, u32), u32> {
+    let compressible = |cell: &CellData| cell.extra.is_none() && cell.repeat == 1;
+    let same_cell = |d0: &CellData, d1: &CellData| {
+        d0.value == d1.value && d0.formula == d1.formula && d0.style == d1.style
+    };
+
+    // Row, start column, length.
+    let mut runs = Vec::new();
+    let mut run: Option<(u32, u32, u32, &CellData)> = None;
+
+    for ((row, col), cell) in sheet.data.iter() {
+        if !compressible(cell) {
+            if let Some((r, c, len, _)) = run.take() {
+                runs.push((r, c, len));
+            }
+            continue;
+        }
+
+        match &mut run {
+            Some((r, c, len, start_cell))
+                if *r == row && *c + *len == col && same_cell(start_cell, cell) =>
+            {
+                *len += 1;
+            }
+            _ => {
+                if let Some((r, c, len, _)) = run.replace((row, col, 1, cell)) {
+                    runs.push((r, c, len));
+                }
+            }
+        }
+    }
+    if let Some((r, c, len, _)) = run {
+        runs.push((r, c, len));
+    }
+    runs.retain(|(_, _, len)| *len > 1);
+    runs.into_iter().map(|(r, c, len)| ((r, c), len)).collect()
+}
+
+/// Row and cell repeats that are applied while writing, on top of
+/// those in the sheet. See compress_rows and compress_cols.
+#[derive(Default)]
+struct Repeats {
+    /// Start row and number of rows.
+    rows: BTreeMap<u32, u32>,
+    /// Start cell and number of cells.
+    cells: BTreeMap<(u32, u32), u32>,
+}
+
+impl Repeats {
+    /// The cell is part of a run that starts before it.
+    fn is_covered(&self, row: u32, col: u32) -> bool {
+        self.rows
+            .range(..row)
+            .next_back()
+            .map(|(r, len)| row < r + len)
+            .unwrap_or(false)
+            || self
+                .cells
+                .range(..(row, col))
+                .next_back()
+                .map(|((r, c), len)| *r == row && col < c + len)
+                .unwrap_or(false)
+    }
+
+    fn row_repeat(&self, sheet: &Sheet, row: u32) -> u32 {
+        match self.rows.get(&row) {
+            Some(len) => *len,
+            None => sheet.row_header.get(&row).map(|v| v.repeat).unwrap_or(1),
+        }
+    }
+
+    fn cell_repeat(&self, row: u32, col: u32, cell: &CellData) -> u32 {
+        self.cells.get(&(row, col)).copied().unwrap_or(cell.repeat)
+    }
+}
+
 fn write_empty_cells(
     hidden: bool,
     repeat: u32,
@@ -1819,7 +3174,7 @@ fn write_empty_cells(
         xml_out.empty("table:table-cell")?;
     }
     if repeat > 1 {
-        xml_out.attr("table:number-columns-repeated", &repeat)?;
+        xml_out.attr_num("table:number-columns-repeated", repeat)?;
     }
 
     Ok(())
@@ -1866,10 +3221,10 @@ fn write_start_current_row(
 
     // row
     xml_out.elem("table:table-row")?;
+    if cur_row_repeat > 1 {
+        xml_out.attr_num("table:number-rows-repeated", cur_row_repeat)?;
+    }
     if let Some(row_header) = sheet.valid_row_header(cur_row) {
-        if row_header.repeat > 1 {
-            xml_out.attr_esc("table:number-rows-repeated", &row_header.repeat)?;
-        }
         if let Some(rowstyle) = row_header.style.as_ref() {
             xml_out.attr_esc("table:style-name", rowstyle.as_str())?;
         }
@@ -1879,13 +3234,16 @@ fn write_start_current_row(
         if row_header.visible != Visibility::Visible {
             xml_out.attr_esc("table:visibility", &row_header.visible)?;
         }
+        for (k, v) in row_header.attr.iter() {
+            xml_out.attr_esc(k.as_ref(), v)?;
+        }
     }
 
     // Might not be the first column in this row.
     if backward_delta_col > 0 {
         xml_out.empty("table:table-cell")?;
         if backward_delta_col > 1 {
-            xml_out.attr_esc("table:number-columns-repeated", &backward_delta_col)?;
+            xml_out.attr_num("table:number-columns-repeated", backward_delta_col)?;
         }
     }
 
@@ -2003,7 +3361,26 @@ fn write_empty_rows_before(
             write_empty_row(sheet, r, 1, max_cell, xml_out)?;
         }
     } else {
-        write_empty_row(sheet, last_row, last_row_repeat, max_cell, xml_out)?;
+        // Split at the borders of the row-headers, otherwise the
+        // header data for these rows would be lost.
+        let end_row = last_row + last_row_repeat;
+        let mut r = last_row;
+        while r < end_row {
+            let next_row = match sheet.row_header.range(..=r).last() {
+                Some((base_row, row_header)) if base_row + row_header.span > r => {
+                    base_row + row_header.span
+                }
+                _ => sheet
+                    .row_header
+                    .range(r + 1..)
+                    .next()
+                    .map(|(base_row, _)| *base_row)
+                    .unwrap_or(end_row),
+            }
+            .min(end_row);
+            write_empty_row(sheet, r, next_row - r, max_cell, xml_out)?;
+            r = next_row;
+        }
     }
 
     Ok(())
@@ -2017,7 +3394,7 @@ fn write_empty_row(
     xml_out: &mut OdsXmlWriter<'_>,
 ) -> Result<(), OdsError> {
     xml_out.elem("table:table-row")?;
-    xml_out.attr("table:number-rows-repeated", &row_repeat)?;
+    xml_out.attr_num("table:number-rows-repeated", row_repeat)?;
     if let Some(row_header) = sheet.valid_row_header(cur_row) {
         if let Some(rowstyle) = row_header.style.as_ref() {
             xml_out.attr_esc("table:style-name", rowstyle.as_str())?;
@@ -2028,11 +3405,14 @@ fn write_empty_row(
         if row_header.visible != Visibility::Visible {
             xml_out.attr_esc("table:visibility", &row_header.visible)?;
         }
+        for (k, v) in row_header.attr.iter() {
+            xml_out.attr_esc(k.as_ref(), v)?;
+        }
     }
 
     // We fill the empty spaces completely up to max columns.
     xml_out.empty("table:table-cell")?;
-    xml_out.attr("table:number-columns-repeated", &max_cell.1)?;
+    xml_out.attr_num("table:number-columns-repeated", max_cell.1)?;
 
     xml_out.end_elem("table:table-row")?;
 
@@ -2079,7 +3459,7 @@ fn write_table_columns(
         xml_out.empty("table:table-column")?;
         let span = if let Some(col_header) = sheet.col_header.get(&c) {
             if col_header.span > 1 {
-                xml_out.attr_esc("table:number-columns-repeated", &col_header.span)?;
+                xml_out.attr_num("table:number-columns-repeated", col_header.span)?;
             }
             if let Some(style) = col_header.style.as_ref() {
                 xml_out.attr_esc("table:style-name", style.as_str())?;
@@ -2102,8 +3482,9 @@ fn write_table_columns(
             }
         }
 
+        // the column-header may span more than one column.
         for col_group in &sheet.group_cols {
-            if c == col_group.to() {
+            if col_group.to() >= c && col_group.to() < c + span {
                 xml_out.end_elem("table:table-column-group")?;
             }
         }
@@ -2115,9 +3496,19 @@ fn write_table_columns(
     Ok(())
 }
 
+/// NaN or Infinity in any of the numeric values.
+fn is_non_finite(value: &Value) -> bool {
+    match value {
+        Value::Number(v) | Value::Percentage(v) | Value::Currency(v, _) => !v.is_finite(),
+        _ => false,
+    }
+}
+
 #[allow(clippy::single_char_add_str)]
 fn write_cell(
+    cfg: &OdsWriteOptions,
     book: &WorkBook,
+    names: &RangeNames<'_>,
     cell: &CellData,
     is_hidden: bool,
     repeat: u32,
@@ -2129,21 +3520,46 @@ fn write_cell(
         "table:table-cell"
     };
 
-    let has_subs = cell.value != Value::Empty || cell.has_annotation() || cell.has_draw_frames();
+    // Non-finite numbers are replaced here. NonFiniteMode::Error is
+    // handled by the caller, who knows the cell position.
+    let non_finite = is_non_finite(&cell.value);
+    let error_value = non_finite && cfg.non_finite == NonFiniteMode::ErrorValue;
+    let na_value;
+    let value = if error_value {
+        na_value = Value::Error("#N/A".into());
+        &na_value
+    } else if non_finite {
+        &Value::Empty
+    } else {
+        &cell.value
+    };
+
+    let has_subs = *value != Value::Empty
+        || cell.has_annotation()
+        || cell.has_draw_frames()
+        || cell.extra.as_ref().map(|v| !v.xml.is_empty()) == Some(true);
     xml_out.elem_if(has_subs, tag)?;
 
     if let Some(formula) = &cell.formula {
-        xml_out.attr_esc("table:formula", formula)?;
+        let formula = names.collapse(formula);
+        xml_out.attr_esc("table:formula", &cfg.target.formula(&formula))?;
+    } else if error_value {
+        xml_out.attr_str("table:formula", "of:=NA()")?;
     }
 
     if repeat > 1 {
-        xml_out.attr_esc("table:number-columns-repeated", &repeat)?;
+        xml_out.attr_num("table:number-columns-repeated", repeat)?;
     }
 
     // Direct style oder value based default style.
+    // Without a value the declared formula type is used.
+    let value_type = match cell.extra.as_ref().and_then(|v| v.formula_type) {
+        Some(formula_type) if *value == Value::Empty => formula_type,
+        _ => value.value_type(),
+    };
     if let Some(style) = &cell.style {
         xml_out.attr_esc("table:style-name", style.as_str())?;
-    } else if let Some(style) = book.def_style(cell.value.value_type()) {
+    } else if let Some(style) = book.def_style(value_type) {
         xml_out.attr_esc("table:style-name", style.as_str())?;
     }
 
@@ -2155,18 +3571,32 @@ fn write_cell(
     // Spans
     if let Some(span) = cell.extra.as_ref().map(|v| v.span) {
         if span.row_span > 1 {
-            xml_out.attr_esc("table:number-rows-spanned", &span.row_span)?;
+            xml_out.attr_num("table:number-rows-spanned", span.row_span)?;
         }
         if span.col_span > 1 {
-            xml_out.attr_esc("table:number-columns-spanned", &span.col_span)?;
+            xml_out.attr_num("table:number-columns-spanned", span.col_span)?;
         }
     }
     if let Some(span) = cell.extra.as_ref().map(|v| v.matrix_span) {
         if span.row_span > 1 {
-            xml_out.attr_esc("table:number-matrix-rows-spanned", &span.row_span)?;
+            xml_out.attr_num("table:number-matrix-rows-spanned", span.row_span)?;
         }
         if span.col_span > 1 {
-            xml_out.attr_esc("table:number-matrix-columns-spanned", &span.col_span)?;
+            xml_out.attr_num("table:number-matrix-columns-spanned", span.col_span)?;
+        }
+    }
+
+    // Unknown attributes from reading.
+    if let Some(attr) = cell.extra.as_ref().map(|v| &v.attr) {
+        for (k, v) in attr.iter() {
+            if k.as_ref() == "calcext:value-type" && !calcext_matches(v, value) {
+                // stale after a change of the value.
+                continue;
+            }
+            if k.starts_with("calcext:") && !cfg.target.calcext() {
+                continue;
+            }
+            xml_out.attr_esc(k.as_ref(), v)?;
         }
     }
 
@@ -2180,12 +3610,20 @@ fn write_cell(
     //     None
     // };
 
-    match &cell.value {
+    match value {
         Value::Empty => {}
+        Value::Error(e) => {
+            xml_out.attr_str("office:value-type", "string")?;
+            xml_out.attr_str("office:string-value", "")?;
+            if cfg.target.calcext() {
+                xml_out.attr_str("calcext:value-type", "error")?;
+            }
+            xml_out.elem_text_esc("text:p", e)?;
+        }
         Value::Text(s) => {
             xml_out.attr_str("office:value-type", "string")?;
             for l in s.split('\n') {
-                xml_out.elem_text_esc("text:p", l)?;
+                write_text_p(l, xml_out)?;
             }
         }
         Value::TextXml(t) => {
@@ -2202,6 +3640,22 @@ fn write_cell(
             xml_out.text(&value)?;
             xml_out.end_elem("text:p")?;
         }
+        Value::Date(d) => {
+            xml_out.attr_str("office:value-type", "date")?;
+            let value = d.format(DATE_FORMAT);
+            xml_out.attr("office:date-value", &value)?;
+            xml_out.elem("text:p")?;
+            xml_out.text(&value)?;
+            xml_out.end_elem("text:p")?;
+        }
+        Value::Time(t) => {
+            xml_out.attr_str("office:value-type", "time")?;
+            let value = format_duration2(*t - NaiveTime::MIN);
+            xml_out.attr("office:time-value", &value)?;
+            xml_out.elem("text:p")?;
+            xml_out.text(&value)?;
+            xml_out.end_elem("text:p")?;
+        }
         Value::TimeDuration(d) => {
             xml_out.attr_str("office:value-type", "time")?;
             let value = format_duration2(*d);
@@ -2220,7 +3674,7 @@ fn write_cell(
         Value::Currency(v, c) => {
             xml_out.attr_str("office:value-type", "currency")?;
             xml_out.attr_esc("office:currency", c)?;
-            xml_out.attr("office:value", v)?;
+            xml_out.attr_num("office:value", *v)?;
             xml_out.elem("text:p")?;
             xml_out.text_esc(c)?;
             xml_out.text_str(" ")?;
@@ -2228,6 +3682,14 @@ fn write_cell(
             xml_out.end_elem("text:p")?;
         }
         Value::Number(v) => {
+            xml_out.attr_str("office:value-type", "float")?;
+            xml_out.attr_num("office:value", *v)?;
+            xml_out.elem("text:p")?;
+            xml_out.text(v)?;
+            xml_out.end_elem("text:p")?;
+        }
+        #[cfg(feature = "rust_decimal")]
+        Value::Decimal(v) => {
             xml_out.attr_str("office:value-type", "float")?;
             xml_out.attr("office:value", v)?;
             xml_out.elem("text:p")?;
@@ -2236,7 +3698,7 @@ fn write_cell(
         }
         Value::Percentage(v) => {
             xml_out.attr_str("office:value-type", "percentage")?;
-            xml_out.attr("office:value", v)?;
+            xml_out.attr_num("office:value", *v)?;
             xml_out.elem("text:p")?;
             xml_out.text(v)?;
             xml_out.end_elem("text:p")?;
@@ -2253,11 +3715,34 @@ fn write_cell(
         }
     }
 
+    if let Some(xml) = cell.extra.as_ref().map(|v| &v.xml) {
+        for tag in xml {
+            write_xmltag(tag, xml_out)?;
+        }
+    }
+
     xml_out.end_elem_if(has_subs, tag)?;
 
     Ok(())
 }
 
+/// Does a calcext:value-type still fit the cell value?
+fn calcext_matches(value_type: &str, value: &Value) -> bool {
+    matches!(
+        (value_type, value.value_type()),
+        ("float", ValueType::Number)
+            | ("percentage", ValueType::Percentage)
+            | ("currency", ValueType::Currency)
+            | ("date", ValueType::DateTime)
+            | ("date", ValueType::Date)
+            | ("time", ValueType::Time)
+            | ("time", ValueType::TimeDuration)
+            | ("boolean", ValueType::Boolean)
+            | ("string", ValueType::Text)
+            | ("string", ValueType::TextXml)
+    )
+}
+
 fn write_draw_frame(
     draw_frame: &DrawFrame,
     xml_out: &mut OdsXmlWriter<'_>,
@@ -2272,8 +3757,12 @@ fn write_draw_frame(
             DrawFrameContent::Image(img) => {
                 write_draw_image(img, xml_out)?;
             }
+            DrawFrameContent::Object(obj) => {
+                write_draw_object(obj, xml_out)?;
+            }
         }
     }
+    write_event_listeners(draw_frame.event_listeners(), xml_out)?;
 
     if let Some(desc) = draw_frame.desc() {
         xml_out.elem("svg:desc")?;
@@ -2315,6 +3804,24 @@ fn write_draw_image(
     Ok(())
 }
 
+fn write_draw_object(
+    draw_object: &DrawObject,
+    xml_out: &mut OdsXmlWriter<'_>,
+) -> Result<(), OdsError> {
+    xml_out.elem_if(!draw_object.content().is_empty(), "draw:object")?;
+    for (k, v) in draw_object.attrmap().iter() {
+        xml_out.attr_esc(k.as_ref(), v)?;
+    }
+
+    for content in draw_object.content() {
+        write_xmltag(content, xml_out)?;
+    }
+
+    xml_out.end_elem_if(!draw_object.content().is_empty(), "draw:object")?;
+
+    Ok(())
+}
+
 fn write_annotation(
     annotation: &Annotation,
     xml_out: &mut OdsXmlWriter<'_>,
@@ -2352,7 +3859,7 @@ fn write_scripts(scripts: &Vec<Script>, xml_out: &mut OdsXmlWriter<'_>) -> Resul
             write_xmlcontent(content, xml_out)?;
         }
 
-        xml_out.end_elem("/office:script")?;
+        xml_out.end_elem("office:script")?;
     }
 
     Ok(())
@@ -2362,15 +3869,21 @@ fn write_event_listeners(
     events: &HashMap<String, EventListener>,
     xml_out: &mut OdsXmlWriter<'_>,
 ) -> Result<(), OdsError> {
-    for event in events.values() {
+    xml_out.elem_if(!events.is_empty(), "office:event-listeners")?;
+    for event in sorted_values(events) {
         xml_out.empty("script:event-listener")?;
         xml_out.attr_esc("script:event-name", &event.event_name)?;
         xml_out.attr_esc("script:language", &event.script_lang)?;
-        xml_out.attr_esc("script:macro-name", &event.macro_name)?;
-        xml_out.attr_esc("xlink:actuate", &event.actuate)?;
-        xml_out.attr_esc("xlink:href", &event.href)?;
-        xml_out.attr_esc("xlink:type", &event.link_type)?;
+        if !event.macro_name.is_empty() {
+            xml_out.attr_esc("script:macro-name", &event.macro_name)?;
+        }
+        if !event.href.is_empty() {
+            xml_out.attr_esc("xlink:actuate", &event.actuate)?;
+            xml_out.attr_esc("xlink:href", &event.href)?;
+            xml_out.attr_esc("xlink:type", &event.link_type)?;
+        }
     }
+    xml_out.end_elem_if(!events.is_empty(), "office:event-listeners")?;
 
     Ok(())
 }
@@ -2391,12 +3904,34 @@ fn write_style_font_face(
     origin: StyleOrigin,
     xml_out: &mut OdsXmlWriter<'_>,
 ) -> Result<(), OdsError> {
-    for font in fonts.values().filter(|s| s.origin() == origin) {
-        xml_out.empty("style:font-face")?;
+    for font in sorted_values(fonts)
+        .into_iter()
+        .filter(|s| s.origin() == origin)
+    {
+        if font.src().is_empty() {
+            xml_out.empty("style:font-face")?;
+        } else {
+            xml_out.elem("style:font-face")?;
+        }
         xml_out.attr_esc("style:name", font.name())?;
         for (a, v) in font.attrmap().iter() {
             xml_out.attr_esc(a.as_ref(), v)?;
         }
+        if !font.src().is_empty() {
+            xml_out.elem("svg:font-face-src")?;
+            for uri in font.src() {
+                xml_out.elem_if(uri.format().is_some(), "svg:font-face-uri")?;
+                xml_out.attr_esc("xlink:href", uri.href())?;
+                xml_out.attr("xlink:type", "simple")?;
+                if let Some(format) = uri.format() {
+                    xml_out.empty("svg:font-face-format")?;
+                    xml_out.attr_esc("svg:string", format)?;
+                }
+                xml_out.end_elem_if(uri.format().is_some(), "svg:font-face-uri")?;
+            }
+            xml_out.end_elem("svg:font-face-src")?;
+            xml_out.end_elem("style:font-face")?;
+        }
     }
     Ok(())
 }
@@ -2407,6 +3942,9 @@ fn write_office_styles(
     xml_out: &mut OdsXmlWriter<'_>,
 ) -> Result<(), OdsError> {
     xml_out.elem("office:styles")?;
+    if let Some(theme) = &book.theme {
+        write_theme(theme, xml_out)?;
+    }
     write_styles(book, origin, StyleUse::Default, xml_out)?;
     write_styles(book, origin, StyleUse::Named, xml_out)?;
     write_valuestyles(book, origin, StyleUse::Named, xml_out)?;
@@ -2415,6 +3953,21 @@ fn write_office_styles(
     Ok(())
 }
 
+fn write_theme(theme: &Theme, xml_out: &mut OdsXmlWriter<'_>) -> Result<(), OdsError> {
+    xml_out.elem("loext:theme")?;
+    xml_out.attr_esc("loext:name", theme.name())?;
+    xml_out.elem("loext:theme-colors")?;
+    xml_out.attr_esc("loext:name", theme.colors_name())?;
+    for (slot, color) in theme.colors() {
+        xml_out.empty("loext:color")?;
+        xml_out.attr("loext:name", slot.as_str())?;
+        xml_out.attr_esc("loext:color", color)?;
+    }
+    xml_out.end_elem("loext:theme-colors")?;
+    xml_out.end_elem("loext:theme")?;
+    Ok(())
+}
+
 fn write_office_automatic_styles(
     book: &WorkBook,
     origin: StyleOrigin,
@@ -2444,61 +3997,60 @@ fn write_styles(
     styleuse: StyleUse,
     xml_out: &mut OdsXmlWriter<'_>,
 ) -> Result<(), OdsError> {
-    for style in book.colstyles.values() {
+    for style in sorted_values(&book.colstyles) {
         if style.origin() == origin && style.styleuse() == styleuse {
             write_colstyle(style, xml_out)?;
         }
     }
-    for style in book.rowstyles.values() {
+    for style in sorted_values(&book.rowstyles) {
         if style.origin() == origin && style.styleuse() == styleuse {
             write_rowstyle(style, xml_out)?;
         }
     }
-    for style in book.tablestyles.values() {
+    for style in sorted_values(&book.tablestyles) {
         if style.origin() == origin && style.styleuse() == styleuse {
             write_tablestyle(style, xml_out)?;
         }
     }
-    for style in book.cellstyles.values() {
+    for style in sorted_values(&book.cellstyles) {
         if style.origin() == origin && style.styleuse() == styleuse {
             write_cellstyle(style, xml_out)?;
         }
     }
-    for style in book.paragraphstyles.values() {
+    for style in sorted_values(&book.paragraphstyles) {
         if style.origin() == origin && style.styleuse() == styleuse {
             write_paragraphstyle(style, xml_out)?;
         }
     }
-    for style in book.textstyles.values() {
+    for style in sorted_values(&book.textstyles) {
         if style.origin() == origin && style.styleuse() == styleuse {
             write_textstyle(style, xml_out)?;
         }
     }
-    for style in book.rubystyles.values() {
+    for style in sorted_values(&book.rubystyles) {
         if style.origin() == origin && style.styleuse() == styleuse {
             write_rubystyle(style, xml_out)?;
         }
     }
-    for style in book.graphicstyles.values() {
+    for style in sorted_values(&book.graphicstyles) {
         if style.origin() == origin && style.styleuse() == styleuse {
             write_graphicstyle(style, xml_out)?;
         }
     }
 
-    // if let Some(stylemaps) = style.stylemaps() {
-    //     for sm in stylemaps {
-    //         xml_out.empty("style:map")?;
-    //         xml_out.attr_esc("style:condition", sm.condition())?;
-    //         xml_out.attr_esc("style:apply-style-name", sm.applied_style())?;
-    //         xml_out.attr_esc("style:base-cell-address", &sm.base_cell().to_string())?;
-    //     }
-    // }
-
     Ok(())
 }
 
+/// The values of the map sorted by key. Gives the same output for the
+/// same workbook.
+fn sorted_values<K: Borrow<str>, V>(map: &HashMap<K, V>) -> Vec<&V> {
+    let mut values: Vec<_> = map.iter().collect();
+    values.sort_by(|a, b| a.0.borrow().cmp(b.0.borrow()));
+    values.into_iter().map(|(_, v)| v).collect()
+}
+
 fn write_tablestyle(style: &TableStyle, xml_out: &mut OdsXmlWriter<'_>) -> Result<(), OdsError> {
-    let is_empty = style.tablestyle().is_empty();
+    let is_empty = style.tablestyle().is_empty() && style.stylemaps().is_none();
 
     if style.styleuse() == StyleUse::Default {
         xml_out.elem_if(!is_empty, "style:default-style")?;
@@ -2523,6 +4075,7 @@ fn write_tablestyle(style: &TableStyle, xml_out: &mut OdsXmlWriter<'_>) -> Resul
             xml_out.attr_esc(a.as_ref(), v)?;
         }
     }
+    write_stylemaps(style.stylemaps(), xml_out)?;
     if style.styleuse() == StyleUse::Default {
         xml_out.end_elem_if(!is_empty, "style:default-style")?;
     } else {
@@ -2533,7 +4086,7 @@ fn write_tablestyle(style: &TableStyle, xml_out: &mut OdsXmlWriter<'_>) -> Resul
 }
 
 fn write_rowstyle(style: &RowStyle, xml_out: &mut OdsXmlWriter<'_>) -> Result<(), OdsError> {
-    let is_empty = style.rowstyle().is_empty();
+    let is_empty = style.rowstyle().is_empty() && style.stylemaps().is_none();
 
     if style.styleuse() == StyleUse::Default {
         xml_out.elem_if(!is_empty, "style:default-style")?;
@@ -2558,6 +4111,7 @@ fn write_rowstyle(style: &RowStyle, xml_out: &mut OdsXmlWriter<'_>) -> Result<()
             xml_out.attr_esc(a.as_ref(), v)?;
         }
     }
+    write_stylemaps(style.stylemaps(), xml_out)?;
     if style.styleuse() == StyleUse::Default {
         xml_out.end_elem_if(!is_empty, "style:default-style")?;
     } else {
@@ -2568,7 +4122,7 @@ fn write_rowstyle(style: &RowStyle, xml_out: &mut OdsXmlWriter<'_>) -> Result<()
 }
 
 fn write_colstyle(style: &ColStyle, xml_out: &mut OdsXmlWriter<'_>) -> Result<(), OdsError> {
-    let is_empty = style.colstyle().is_empty();
+    let is_empty = style.colstyle().is_empty() && style.stylemaps().is_none();
 
     if style.styleuse() == StyleUse::Default {
         xml_out.elem_if(!is_empty, "style:default-style")?;
@@ -2593,6 +4147,7 @@ fn write_colstyle(style: &ColStyle, xml_out: &mut OdsXmlWriter<'_>) -> Result<()
             xml_out.attr_esc(a.as_ref(), v)?;
         }
     }
+    write_stylemaps(style.stylemaps(), xml_out)?;
     if style.styleuse() == StyleUse::Default {
         xml_out.end_elem_if(!is_empty, "style:default-style")?;
     } else {
@@ -2606,7 +4161,9 @@ fn write_cellstyle(style: &CellStyle, xml_out: &mut OdsXmlWriter<'_>) -> Result<
     let is_empty = style.cellstyle().is_empty()
         && style.paragraphstyle().is_empty()
         && style.textstyle().is_empty()
-        && style.stylemaps().is_none();
+        && style.stylemaps().is_none()
+        && style.color_theme().is_none()
+        && style.background_color_theme().is_none();
 
     if style.styleuse() == StyleUse::Default {
         xml_out.elem_if(!is_empty, "style:default-style")?;
@@ -2625,11 +4182,14 @@ fn write_cellstyle(style: &CellStyle, xml_out: &mut OdsXmlWriter<'_>) -> Result<
         }
     }
 
-    if !style.cellstyle().is_empty() {
-        xml_out.empty("style:table-cell-properties")?;
+    let theme = style.background_color_theme();
+    if !style.cellstyle().is_empty() || theme.is_some() {
+        xml_out.elem_if(theme.is_some(), "style:table-cell-properties")?;
         for (a, v) in style.cellstyle().iter() {
             xml_out.attr_esc(a.as_ref(), v)?;
         }
+        write_theme_color("loext:background-complex-color", theme, xml_out)?;
+        xml_out.end_elem_if(theme.is_some(), "style:table-cell-properties")?;
     }
     if !style.paragraphstyle().is_empty() {
         xml_out.empty("style:paragraph-properties")?;
@@ -2637,13 +4197,44 @@ fn write_cellstyle(style: &CellStyle, xml_out: &mut OdsXmlWriter<'_>) -> Result<
             xml_out.attr_esc(a.as_ref(), v)?;
         }
     }
-    if !style.textstyle().is_empty() {
-        xml_out.empty("style:text-properties")?;
+    let theme = style.color_theme();
+    if !style.textstyle().is_empty() || theme.is_some() {
+        xml_out.elem_if(theme.is_some(), "style:text-properties")?;
         for (a, v) in style.textstyle().iter() {
             xml_out.attr_esc(a.as_ref(), v)?;
         }
+        write_theme_color("loext:char-complex-color", theme, xml_out)?;
+        xml_out.end_elem_if(theme.is_some(), "style:text-properties")?;
+    }
+    write_stylemaps(style.stylemaps(), xml_out)?;
+    if style.styleuse() == StyleUse::Default {
+        xml_out.end_elem_if(!is_empty, "style:default-style")?;
+    } else {
+        xml_out.end_elem_if(!is_empty, "style:style")?;
     }
-    if let Some(stylemaps) = style.stylemaps() {
+
+    Ok(())
+}
+
+/// Reference to a color of the document theme.
+fn write_theme_color(
+    tag: &str,
+    slot: Option<ThemeColor>,
+    xml_out: &mut OdsXmlWriter<'_>,
+) -> Result<(), OdsError> {
+    if let Some(slot) = slot {
+        xml_out.empty(tag)?;
+        xml_out.attr("loext:theme-type", slot.as_str())?;
+        xml_out.attr_str("loext:color-type", "theme")?;
+    }
+    Ok(())
+}
+
+fn write_stylemaps(
+    stylemaps: Option<&Vec<StyleMap>>,
+    xml_out: &mut OdsXmlWriter<'_>,
+) -> Result<(), OdsError> {
+    if let Some(stylemaps) = stylemaps {
         for sm in stylemaps {
             xml_out.empty("style:map")?;
             xml_out.attr_esc("style:condition", sm.condition())?;
@@ -2653,12 +4244,6 @@ fn write_cellstyle(style: &CellStyle, xml_out: &mut OdsXmlWriter<'_>) -> Result<
             }
         }
     }
-    if style.styleuse() == StyleUse::Default {
-        xml_out.end_elem_if(!is_empty, "style:default-style")?;
-    } else {
-        xml_out.end_elem_if(!is_empty, "style:style")?;
-    }
-
     Ok(())
 }
 
@@ -2666,7 +4251,9 @@ fn write_paragraphstyle(
     style: &ParagraphStyle,
     xml_out: &mut OdsXmlWriter<'_>,
 ) -> Result<(), OdsError> {
-    let is_empty = style.paragraphstyle().is_empty() && style.textstyle().is_empty();
+    let is_empty = style.paragraphstyle().is_empty()
+        && style.textstyle().is_empty()
+        && style.stylemaps().is_none();
 
     if style.styleuse() == StyleUse::Default {
         xml_out.elem_if(!is_empty, "style:default-style")?;
@@ -2715,6 +4302,7 @@ fn write_paragraphstyle(
             xml_out.attr_esc(a.as_ref(), v)?;
         }
     }
+    write_stylemaps(style.stylemaps(), xml_out)?;
     if style.styleuse() == StyleUse::Default {
         xml_out.end_elem_if(!is_empty, "style:default-style")?;
     } else {
@@ -2725,7 +4313,7 @@ fn write_paragraphstyle(
 }
 
 fn write_textstyle(style: &TextStyle, xml_out: &mut OdsXmlWriter<'_>) -> Result<(), OdsError> {
-    let is_empty = style.textstyle().is_empty();
+    let is_empty = style.textstyle().is_empty() && style.stylemaps().is_none();
 
     if style.styleuse() == StyleUse::Default {
         xml_out.elem_if(!is_empty, "style:default-style")?;
@@ -2750,6 +4338,7 @@ fn write_textstyle(style: &TextStyle, xml_out: &mut OdsXmlWriter<'_>) -> Result<
             xml_out.attr_esc(a.as_ref(), v)?;
         }
     }
+    write_stylemaps(style.stylemaps(), xml_out)?;
     if style.styleuse() == StyleUse::Default {
         xml_out.end_elem_if(!is_empty, "style:default-style")?;
     } else {
@@ -2869,12 +4458,12 @@ fn write_valuestyle<T: ValueFormatTrait>(
     styleuse: StyleUse,
     xml_out: &mut OdsXmlWriter<'_>,
 ) -> Result<(), OdsError> {
-    for value_format in value_formats
-        .values()
+    for value_format in sorted_values(value_formats)
+        .into_iter()
         .filter(|s| s.origin() == origin && s.styleuse() == styleuse)
     {
         let tag = match value_format.value_type() {
-            ValueType::Empty => unreachable!(),
+            ValueType::Empty | ValueType::Error => unreachable!(),
             ValueType::Boolean => "number:boolean-style",
             ValueType::Number => "number:number-style",
             ValueType::Text => "number:text-style",
@@ -2882,7 +4471,8 @@ fn write_valuestyle<T: ValueFormatTrait>(
             ValueType::TimeDuration => "number:time-style",
             ValueType::Percentage => "number:percentage-style",
             ValueType::Currency => "number:currency-style",
-            ValueType::DateTime => "number:date-style",
+            ValueType::DateTime | ValueType::Date => "number:date-style",
+            ValueType::Time => "number:time-style",
         };
 
         xml_out.elem(tag)?;
@@ -2985,7 +4575,7 @@ fn write_pagestyles(
     styles: &HashMap<PageStyleRef, PageStyle>,
     xml_out: &mut OdsXmlWriter<'_>,
 ) -> Result<(), OdsError> {
-    for style in styles.values() {
+    for style in sorted_values(styles) {
         xml_out.elem("style:page-layout")?;
         xml_out.attr_esc("style:name", style.name())?;
         if let Some(master_page_usage) = &style.master_page_usage {
@@ -3027,7 +4617,7 @@ fn write_masterpage(
     masterpages: &HashMap<MasterPageRef, MasterPage>,
     xml_out: &mut OdsXmlWriter<'_>,
 ) -> Result<(), OdsError> {
-    for masterpage in masterpages.values() {
+    for masterpage in sorted_values(masterpages) {
         xml_out.elem("style:master-page")?;
         xml_out.attr_esc("style:name", masterpage.name())?;
         if !masterpage.display_name().is_empty() {
@@ -3131,6 +4721,59 @@ fn write_xmlcontent(x: &XmlContent, xml_out: &mut OdsXmlWriter<'_>) -> Result<()
     Ok(())
 }
 
+/// Writes one paragraph of plain text. Tabs and the spaces that would
+/// be collapsed by a reader are written as text:tab and text:s.
+fn write_text_p(text: &str, xml_out: &mut OdsXmlWriter<'_>) -> Result<(), OdsError> {
+    if !text.contains('\t')
+        && !text.starts_with(' ')
+        && !text.ends_with(' ')
+        && !text.contains("  ")
+    {
+        xml_out.elem_text_esc("text:p", text)?;
+        return Ok(());
+    }
+
+    xml_out.elem("text:p")?;
+    let mut start = 0;
+    let mut chars = text.char_indices().peekable();
+    while let Some((idx, c)) = chars.next() {
+        match c {
+            '\t' => {
+                xml_out.text_esc(&text[start..idx])?;
+                xml_out.empty("text:tab")?;
+                start = idx + 1;
+            }
+            ' ' => {
+                let mut count = 1;
+                while chars.next_if(|(_, c)| *c == ' ').is_some() {
+                    count += 1;
+                }
+                let end = idx + count;
+                // a single space between two characters is kept as is.
+                let literal = idx > start && end < text.len() && !text[end..].starts_with('\t');
+                if literal {
+                    count -= 1;
+                    xml_out.text_esc(&text[start..idx + 1])?;
+                } else {
+                    xml_out.text_esc(&text[start..idx])?;
+                }
+                if count > 0 {
+                    xml_out.empty("text:s")?;
+                    if count > 1 {
+                        xml_out.attr("text:c", &count)?;
+                    }
+                }
+                start = end;
+            }
+            _ => {}
+        }
+    }
+    xml_out.text_esc(&text[start..])?;
+    xml_out.end_elem("text:p")?;
+
+    Ok(())
+}
+
 fn write_xmltag(x: &XmlTag, xml_out: &mut OdsXmlWriter<'_>) -> Result<(), OdsError> {
     xml_out.elem_if(!x.is_empty(), x.name())?;
 
@@ -3160,7 +4803,7 @@ fn write_ods_extra<W: Write + Seek>(
     zip_writer: &mut ZipWriter<W>,
     book: &WorkBook,
 ) -> Result<(), OdsError> {
-    for manifest in book.manifest.values() {
+    for manifest in sorted_values(&book.manifest) {
         if !matches!(
             manifest.full_path.as_str(),
             "/" | "settings.xml" | "styles.xml" | "content.xml" | "meta.xml"
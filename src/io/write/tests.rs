@@ -1,6 +1,7 @@
+use crate::config::{ConfigItem, ConfigValue};
 use crate::io::write::calc_col_headers;
 use crate::sheet_::dedup_colheader;
-use crate::{Sheet, WorkBook};
+use crate::{write_ods_buf, OdsError, Sheet, WorkBook};
 use icu_locid::locale;
 
 fn setup_test_calc_col_headers() -> WorkBook {
@@ -25,12 +26,12 @@ fn setup_test_calc_col_headers() -> WorkBook {
 fn test_calc_col_headers() {
     let mut wb = setup_test_calc_col_headers();
     let sh0 = wb.sheet_mut(0);
-    sh0.set_header_cols(0, 1);
+    sh0.set_repeat_header_cols(0, 1).unwrap();
     calc_col_headers(&mut wb).unwrap();
 
     let sh0 = wb.sheet_mut(0);
-    assert!(sh0.col_header.get(&0).is_some());
-    assert!(sh0.col_header.get(&2).is_some());
+    assert!(sh0.col_header.contains_key(&0));
+    assert!(sh0.col_header.contains_key(&2));
     assert_eq!(sh0.col_header.get(&0).unwrap().span, 2);
     assert_eq!(sh0.col_header.get(&2).unwrap().span, 5);
 
@@ -41,9 +42,9 @@ fn test_calc_col_headers() {
     calc_col_headers(&mut wb).unwrap();
 
     let sh0 = wb.sheet_mut(0);
-    assert!(sh0.col_header.get(&0).is_some());
-    assert!(sh0.col_header.get(&1).is_some());
-    assert!(sh0.col_header.get(&4).is_some());
+    assert!(sh0.col_header.contains_key(&0));
+    assert!(sh0.col_header.contains_key(&1));
+    assert!(sh0.col_header.contains_key(&4));
     assert_eq!(sh0.col_header.get(&0).unwrap().span, 1);
     assert_eq!(sh0.col_header.get(&1).unwrap().span, 3);
     assert_eq!(sh0.col_header.get(&4).unwrap().span, 3);
@@ -54,8 +55,8 @@ fn test_calc_col_headers() {
     calc_col_headers(&mut wb).unwrap();
 
     let sh0 = wb.sheet_mut(0);
-    assert!(sh0.col_header.get(&0).is_some());
-    assert!(sh0.col_header.get(&4).is_some());
+    assert!(sh0.col_header.contains_key(&0));
+    assert!(sh0.col_header.contains_key(&4));
     assert_eq!(sh0.col_header.get(&0).unwrap().span, 4);
     assert_eq!(sh0.col_header.get(&4).unwrap().span, 3);
 
@@ -68,3 +69,13 @@ fn test_calc_col_headers() {
     let sh0 = wb.sheet_mut(0);
     assert!(sh0.col_header.is_empty());
 }
+
+#[test]
+fn test_invalid_config() {
+    let mut book = WorkBook::new_empty();
+    book.config
+        .insert("Broken", ConfigItem::from(ConfigValue::Boolean(true)));
+
+    let res = write_ods_buf(&mut book, Vec::new());
+    assert!(matches!(res, Err(OdsError::Ods(_))));
+}
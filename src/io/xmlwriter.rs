@@ -1,9 +1,46 @@
+use crate::io::write::InvalidCharMode;
 use std::fmt;
-use std::fmt::{Display, Formatter, Write as FmtWrite};
+use std::fmt::{Display, Formatter};
 use std::io::{self, Write};
 #[cfg(not(feature = "check_xml"))]
 use std::marker::PhantomData;
-use std::str::from_utf8_unchecked;
+
+/// The buffer is written to the underlying writer when it exceeds this size.
+const BUF_SIZE: usize = 64 * 1024;
+
+/// Numbers that are formatted without the Display machinery.
+pub(crate) trait XmlNumber {
+    fn write_num(&self, buf: &mut Vec<u8>);
+}
+
+macro_rules! xml_number_int {
+    ($($t:ty),*) => {
+        $(
+            impl XmlNumber for $t {
+                #[inline]
+                fn write_num(&self, buf: &mut Vec<u8>) {
+                    buf.extend_from_slice(itoa::Buffer::new().format(*self).as_bytes());
+                }
+            }
+        )*
+    };
+}
+
+xml_number_int!(u8, u16, u32, u64, usize, i8, i16, i32, i64, isize);
+
+impl XmlNumber for f64 {
+    #[inline]
+    fn write_num(&self, buf: &mut Vec<u8>) {
+        if self.is_finite() {
+            let mut fmt = ryu::Buffer::new();
+            let v = fmt.format_finite(*self);
+            // Same as Display for integral values.
+            buf.extend_from_slice(v.strip_suffix(".0").unwrap_or(v).as_bytes());
+        } else {
+            let _ = write!(buf, "{}", self);
+        }
+    }
+}
 
 #[derive(PartialEq)]
 enum Open {
@@ -67,17 +104,50 @@ impl Stack {
     }
 }
 
+/// Escaping of text and attribute values.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Escape {
+    /// Only &, < and >. Sufficient for text content.
+    Minimal,
+    /// All five predefined entities.
+    Full,
+    /// All five predefined entities, and tab, newline and carriage-return
+    /// as character references. Otherwise they are lost to the
+    /// attribute value normalization when reading.
+    Attr,
+    /// Text content as a CDATA section. A "]]>" in the text is split into
+    /// two sections. Not for attribute values.
+    Cdata,
+}
+
+/// Indentation state of an open element.
+#[derive(Debug, Clone, Copy)]
+struct Level {
+    // contains child elements.
+    children: bool,
+    // contains text.
+    mixed: bool,
+    // text:p or text:h. Whitespace inside is content.
+    paragraph: bool,
+}
+
 /// The XmlWriter himself
 pub(crate) struct XmlWriter<W: Write> {
     writer: Box<W>,
-    buf: String,
+    buf: Vec<u8>,
     stack: Stack,
     open: Open,
     line_break: bool,
+    pretty: bool,
+    levels: Vec<Level>,
+    // number of open paragraphs.
+    in_paragraph: usize,
+    text_escape: Escape,
+    attr_escape: Escape,
+    invalid_chars: InvalidCharMode,
 
     // short time temp space
     tmp: Vec<u8>,
-    tmp2: Vec<u8>,
 }
 
 impl<W: Write> fmt::Debug for XmlWriter<W> {
@@ -95,12 +165,17 @@ impl<W: Write> XmlWriter<W> {
     pub(crate) fn new(writer: W) -> XmlWriter<W> {
         XmlWriter {
             stack: Stack::new(),
-            buf: Default::default(),
+            buf: Vec::with_capacity(BUF_SIZE + 1024),
             writer: Box::new(writer),
             open: Open::None,
             line_break: false,
+            pretty: false,
+            levels: Default::default(),
+            in_paragraph: 0,
+            text_escape: Escape::Full,
+            attr_escape: Escape::Attr,
+            invalid_chars: InvalidCharMode::Error,
             tmp: Default::default(),
-            tmp2: Default::default(),
         }
     }
 
@@ -109,14 +184,40 @@ impl<W: Write> XmlWriter<W> {
         self
     }
 
+    /// Indents the elements by their nesting level. Elements that contain
+    /// text, and everything inside a paragraph is not indented, as the
+    /// whitespace would become part of the content.
+    ///
+    /// Replaces line_break.
+    pub(crate) fn pretty(mut self, pretty: bool) -> Self {
+        self.pretty = pretty;
+        if pretty {
+            self.line_break = false;
+        }
+        self
+    }
+
+    /// Handling of characters that are not allowed in XML 1.0.
+    pub(crate) fn invalid_chars(mut self, mode: InvalidCharMode) -> Self {
+        self.invalid_chars = mode;
+        self
+    }
+
+    /// Escaping for text content.
+    pub(crate) fn text_escape(mut self, escape: Escape) -> Self {
+        self.text_escape = escape;
+        self
+    }
+
     /// Write the DTD. You have to take care of the encoding
     /// on the underlying Write yourself.
     pub(crate) fn dtd(&mut self, encoding: &str) -> io::Result<()> {
-        self.buf.push_str("<?xml version=\"1.0\" encoding=\"");
-        self.buf.push_str(encoding);
-        self.buf.push_str("\" ?>\n");
-        if self.line_break {
-            self.buf.push('\n');
+        self.buf
+            .extend_from_slice(b"<?xml version=\"1.0\" encoding=\"");
+        self.buf.extend_from_slice(encoding.as_bytes());
+        self.buf.extend_from_slice(b"\" ?>\n");
+        if self.line_break && self.in_paragraph == 0 {
+            self.buf.push(b'\n');
         }
 
         Ok(())
@@ -129,19 +230,20 @@ impl<W: Write> XmlWriter<W> {
         text: &T,
     ) -> io::Result<()> {
         self.close_elem()?;
+        self.indent_start();
 
-        self.buf.push('<');
-        self.buf.push_str(name);
-        self.buf.push('>');
+        self.buf.push(b'<');
+        self.buf.extend_from_slice(name.as_bytes());
+        self.buf.push(b'>');
 
         let _ = write!(self.buf, "{}", text);
 
-        self.buf.push('<');
-        self.buf.push('/');
-        self.buf.push_str(name);
-        self.buf.push('>');
-        if self.line_break {
-            self.buf.push('\n');
+        self.buf.push(b'<');
+        self.buf.push(b'/');
+        self.buf.extend_from_slice(name.as_bytes());
+        self.buf.push(b'>');
+        if self.line_break && self.in_paragraph == 0 {
+            self.buf.push(b'\n');
         }
 
         Ok(())
@@ -155,19 +257,20 @@ impl<W: Write> XmlWriter<W> {
         text: &T,
     ) -> io::Result<()> {
         self.close_elem()?;
+        self.indent_start();
 
-        self.buf.push('<');
-        self.buf.push_str(name);
-        self.buf.push('>');
+        self.buf.push(b'<');
+        self.buf.extend_from_slice(name.as_bytes());
+        self.buf.push(b'>');
 
-        self.escape(text)?;
+        self.escape(text, self.text_escape)?;
 
-        self.buf.push('<');
-        self.buf.push('/');
-        self.buf.push_str(name);
-        self.buf.push('>');
-        if self.line_break {
-            self.buf.push('\n');
+        self.buf.push(b'<');
+        self.buf.push(b'/');
+        self.buf.extend_from_slice(name.as_bytes());
+        self.buf.push(b'>');
+        if self.line_break && self.in_paragraph == 0 {
+            self.buf.push(b'\n');
         }
 
         Ok(())
@@ -176,12 +279,13 @@ impl<W: Write> XmlWriter<W> {
     #[allow(dead_code)]
     pub(crate) fn comment(&mut self, comment: &str) -> io::Result<()> {
         self.close_elem()?;
+        self.indent_start();
 
-        self.buf.push_str("<!--");
-        self.buf.push_str(comment);
-        self.buf.push_str("-->");
-        if self.line_break {
-            self.buf.push('\n');
+        self.buf.extend_from_slice(b"<!--");
+        self.buf.extend_from_slice(comment.as_bytes());
+        self.buf.extend_from_slice(b"-->");
+        if self.line_break && self.in_paragraph == 0 {
+            self.buf.push(b'\n');
         }
 
         Ok(())
@@ -190,36 +294,41 @@ impl<W: Write> XmlWriter<W> {
     /// Begin an elem, make sure name contains only allowed chars
     pub(crate) fn elem(&mut self, name: &str) -> io::Result<()> {
         self.close_elem()?;
+        self.indent_start();
+        self.push_level(name);
 
         self.stack.push(name);
 
-        self.buf.push('<');
+        self.buf.push(b'<');
         self.open = Open::Elem;
-        self.buf.push_str(name);
+        self.buf.extend_from_slice(name.as_bytes());
         Ok(())
     }
 
     /// Begin an elem if has_content is true, otherwise begin a empty elem.
     pub(crate) fn elem_if(&mut self, has_content: bool, name: &str) -> io::Result<()> {
         self.close_elem()?;
+        self.indent_start();
 
         if has_content {
+            self.push_level(name);
             self.stack.push(name);
         }
 
-        self.buf.push('<');
+        self.buf.push(b'<');
         self.open = if has_content { Open::Elem } else { Open::Empty };
-        self.buf.push_str(name);
+        self.buf.extend_from_slice(name.as_bytes());
         Ok(())
     }
 
     /// Begin an empty elem
     pub(crate) fn empty(&mut self, name: &str) -> io::Result<()> {
         self.close_elem()?;
+        self.indent_start();
 
-        self.buf.push('<');
+        self.buf.push(b'<');
         self.open = Open::Empty;
-        self.buf.push_str(name);
+        self.buf.extend_from_slice(name.as_bytes());
         Ok(())
     }
 
@@ -228,18 +337,20 @@ impl<W: Write> XmlWriter<W> {
         match self.open {
             Open::None => {}
             Open::Elem => {
-                self.buf.push('>');
+                self.buf.push(b'>');
             }
             Open::Empty => {
-                self.buf.push('/');
-                self.buf.push('>');
-                if self.line_break {
-                    self.buf.push('\n');
+                self.buf.push(b'/');
+                self.buf.push(b'>');
+                if self.line_break && self.in_paragraph == 0 {
+                    self.buf.push(b'\n');
                 }
             }
         }
         self.open = Open::None;
-        self.write_buf()?;
+        if self.buf.len() >= BUF_SIZE {
+            self.write_buf()?;
+        }
         Ok(())
     }
 
@@ -247,17 +358,17 @@ impl<W: Write> XmlWriter<W> {
     /// For an escaping version use `attr_esc`
     pub(crate) fn attr_str(&mut self, name: &'static str, value: &'static str) -> io::Result<()> {
         if cfg!(feature = "check_xml") && self.open == Open::None {
-            panic!(
+            return Err(structure_err(format!(
                 "Attempted to write attr to elem, when no elem was opened, stack {:?}",
                 self.stack
-            );
+            )));
         }
-        self.buf.push(' ');
-        self.buf.push_str(name);
-        self.buf.push('=');
-        self.buf.push('"');
-        self.buf.push_str(value);
-        self.buf.push('"');
+        self.buf.push(b' ');
+        self.buf.extend_from_slice(name.as_bytes());
+        self.buf.push(b'=');
+        self.buf.push(b'"');
+        self.buf.extend_from_slice(value.as_bytes());
+        self.buf.push(b'"');
         Ok(())
     }
 
@@ -265,18 +376,36 @@ impl<W: Write> XmlWriter<W> {
     /// For an escaping version use `attr_esc`
     pub(crate) fn attr<T: Display + ?Sized>(&mut self, name: &str, value: &T) -> io::Result<()> {
         if cfg!(feature = "check_xml") && self.open == Open::None {
-            panic!(
+            return Err(structure_err(format!(
                 "Attempted to write attr to elem, when no elem was opened, stack {:?}",
                 self.stack
-            );
+            )));
         }
 
-        self.buf.push(' ');
-        self.buf.push_str(name);
-        self.buf.push('=');
-        self.buf.push('"');
+        self.buf.push(b' ');
+        self.buf.extend_from_slice(name.as_bytes());
+        self.buf.push(b'=');
+        self.buf.push(b'"');
         let _ = write!(self.buf, "{}", value);
-        self.buf.push('"');
+        self.buf.push(b'"');
+        Ok(())
+    }
+
+    /// Write a numeric attr, make sure name contains only allowed chars.
+    pub(crate) fn attr_num<N: XmlNumber>(&mut self, name: &str, value: N) -> io::Result<()> {
+        if cfg!(feature = "check_xml") && self.open == Open::None {
+            return Err(structure_err(format!(
+                "Attempted to write attr to elem, when no elem was opened, stack {:?}",
+                self.stack
+            )));
+        }
+
+        self.buf.push(b' ');
+        self.buf.extend_from_slice(name.as_bytes());
+        self.buf.push(b'=');
+        self.buf.push(b'"');
+        value.write_num(&mut self.buf);
+        self.buf.push(b'"');
         Ok(())
     }
 
@@ -287,107 +416,61 @@ impl<W: Write> XmlWriter<W> {
         value: &T,
     ) -> io::Result<()> {
         if cfg!(feature = "check_xml") && self.open == Open::None {
-            panic!(
+            return Err(structure_err(format!(
                 "Attempted to write attr to elem, when no elem was opened, stack {:?}",
                 self.stack
-            );
+            )));
         }
-        self.buf.push(' ');
+        self.buf.push(b' ');
         self.escape_name(name)?;
-        self.buf.push('=');
-        self.buf.push('"');
-        self.escape(value)?;
-        self.buf.push('"');
+        self.buf.push(b'=');
+        self.buf.push(b'"');
+        self.escape(value, self.attr_escape)?;
+        self.buf.push(b'"');
         Ok(())
     }
 
     /// Escape text
-    fn escape<T: Display + ?Sized>(&mut self, text: &T) -> io::Result<()> {
-        fn escape_impl(text: &[u8], tmp2: &mut Vec<u8>) {
-            tmp2.clear();
-            for c in text {
-                match c {
-                    b'"' => {
-                        let _ = write!(tmp2, "&quot;");
-                    }
-                    b'\'' => {
-                        let _ = write!(tmp2, "&apos;");
-                    }
-                    b'&' => {
-                        let _ = write!(tmp2, "&amp;");
-                    }
-                    b'<' => {
-                        let _ = write!(tmp2, "&lt;");
-                    }
-                    b'>' => {
-                        let _ = write!(tmp2, "&gt;");
-                    }
-                    _ => tmp2.push(*c),
-                };
-            }
-        }
-
+    fn escape<T: Display + ?Sized>(&mut self, text: &T, escape: Escape) -> io::Result<()> {
         self.tmp.clear();
         let _ = write!(self.tmp, "{}", text);
-        escape_impl(&self.tmp, &mut self.tmp2);
-        // Safety: this is always from a string buffer.
-        unsafe {
-            self.buf.push_str(from_utf8_unchecked(&self.tmp2));
+        if escape == Escape::Cdata {
+            if !self.tmp.is_empty() {
+                self.buf.extend_from_slice(b"<![CDATA[");
+                escape_into(&self.tmp, &mut self.buf, escape, false, self.invalid_chars)?;
+                self.buf.extend_from_slice(b"]]>");
+            }
+            Ok(())
+        } else {
+            escape_into(&self.tmp, &mut self.buf, escape, false, self.invalid_chars)
         }
-
-        Ok(())
     }
 
     /// Escape identifiers
     fn escape_name<T: Display + ?Sized>(&mut self, text: &T) -> io::Result<()> {
-        fn escape_impl(text: &[u8], tmp2: &mut Vec<u8>) {
-            tmp2.clear();
-            for c in text {
-                match c {
-                    b'"' => {
-                        let _ = write!(tmp2, "&quot;");
-                    }
-                    b'\'' => {
-                        let _ = write!(tmp2, "&apos;");
-                    }
-                    b'&' => {
-                        let _ = write!(tmp2, "&amp;");
-                    }
-                    b'<' => {
-                        let _ = write!(tmp2, "&lt;");
-                    }
-                    b'>' => {
-                        let _ = write!(tmp2, "&gt;");
-                    }
-                    b'\\' => {
-                        let _ = write!(tmp2, "\\\\");
-                    }
-                    _ => tmp2.push(*c),
-                };
-            }
-        }
-
         self.tmp.clear();
         let _ = write!(self.tmp, "{}", text);
-        escape_impl(&self.tmp, &mut self.tmp2);
-        // Safety: this is always from a string buffer.
-        unsafe {
-            self.buf.push_str(from_utf8_unchecked(&self.tmp2));
-        }
-
-        Ok(())
+        escape_into(
+            &self.tmp,
+            &mut self.buf,
+            Escape::Full,
+            true,
+            self.invalid_chars,
+        )
     }
 
     /// Write a text, doesn't escape the text.
     pub(crate) fn text_str(&mut self, text: &'static str) -> io::Result<()> {
         self.close_elem()?;
-        self.buf.push_str(text);
+        self.mark_text();
+        self.buf.extend_from_slice(text.as_bytes());
         Ok(())
     }
 
     /// Write a text, doesn't escape the text.
     pub(crate) fn text<T: Display + ?Sized>(&mut self, text: &T) -> io::Result<()> {
         self.close_elem()?;
+        self.mark_text();
         let _ = write!(self.buf, "{}", text);
         Ok(())
     }
@@ -395,7 +478,8 @@ impl<W: Write> XmlWriter<W> {
     /// Write a text, escapes the text automatically
     pub(crate) fn text_esc<T: Display + ?Sized>(&mut self, text: &T) -> io::Result<()> {
         self.close_elem()?;
-        self.escape(text)?;
+        self.mark_text();
+        self.escape(text, self.text_escape)?;
         Ok(())
     }
 
@@ -411,30 +495,101 @@ impl<W: Write> XmlWriter<W> {
             match self.stack.pop() {
                 Some(test) => {
                     if name != test {
-                        panic!(
+                        return Err(structure_err(format!(
                             "Attempted to close elem {} but the open was {}, stack {:?}",
                             name, test, self.stack
-                        )
+                        )));
                     }
                 }
-                None => panic!(
-                    "Attempted to close an elem, when none was open, stack {:?}",
-                    self.stack
-                ),
+                None => {
+                    return Err(structure_err(format!(
+                        "Attempted to close an elem, when none was open, stack {:?}",
+                        self.stack
+                    )))
+                }
             }
         }
 
-        self.buf.push('<');
-        self.buf.push('/');
-        self.buf.push_str(name);
-        self.buf.push('>');
-        if self.line_break {
-            self.buf.push('\n');
+        if !self.pretty && (name == "text:p" || name == "text:h") {
+            self.in_paragraph -= 1;
+        }
+        self.indent_end();
+
+        self.buf.push(b'<');
+        self.buf.push(b'/');
+        self.buf.extend_from_slice(name.as_bytes());
+        self.buf.push(b'>');
+        if self.line_break && self.in_paragraph == 0 {
+            self.buf.push(b'\n');
         }
 
         Ok(())
     }
 
+    /// Newline and indentation before a start tag.
+    #[inline]
+    fn indent_start(&mut self) {
+        if !self.pretty {
+            return;
+        }
+        let indent = match self.levels.last_mut() {
+            Some(level) => {
+                level.children = true;
+                !level.mixed && self.in_paragraph == 0
+            }
+            None => false,
+        };
+        if indent {
+            self.newline(self.levels.len());
+        }
+    }
+
+    /// Newline and indentation before an end tag.
+    #[inline]
+    fn indent_end(&mut self) {
+        if !self.pretty {
+            return;
+        }
+        if let Some(level) = self.levels.pop() {
+            if level.paragraph {
+                self.in_paragraph -= 1;
+            }
+            if level.children && !level.mixed && self.in_paragraph == 0 {
+                self.newline(self.levels.len());
+            }
+        }
+    }
+
+    fn newline(&mut self, depth: usize) {
+        self.buf.push(b'\n');
+        for _ in 0..depth {
+            self.buf.extend_from_slice(b"  ");
+        }
+    }
+
+    #[inline]
+    fn push_level(&mut self, name: &str) {
+        let paragraph = name == "text:p" || name == "text:h";
+        if paragraph {
+            self.in_paragraph += 1;
+        }
+        if !self.pretty {
+            return;
+        }
+        self.levels.push(Level {
+            children: false,
+            mixed: false,
+            paragraph,
+        });
+    }
+
+    #[inline]
+    fn mark_text(&mut self) {
+        if let Some(level) = self.levels.last_mut() {
+            level.mixed = true;
+        }
+    }
+
     /// End an elem. Writes the end-tag
     #[inline(always)]
     pub(crate) fn end_elem(&mut self, name: &str) -> io::Result<()> {
@@ -442,7 +597,7 @@ impl<W: Write> XmlWriter<W> {
     }
 
     fn write_buf(&mut self) -> io::Result<()> {
-        self.writer.write_all(self.buf.as_bytes())?;
+        self.writer.write_all(&self.buf)?;
         self.buf.clear();
         Ok(())
     }
@@ -452,11 +607,85 @@ impl<W: Write> XmlWriter<W> {
         self.write_buf()?;
 
         if cfg!(feature = "check_xml") && !self.stack.is_empty() {
-            panic!(
+            return Err(structure_err(format!(
                 "Attempted to close the xml, but there are open elements on the stack {:?}",
                 self.stack
-            )
+            )));
         }
         Ok(())
     }
 }
+
+/// Escapes the text into the buffer. Names additionally escape the backslash.
+///
+/// Characters that are not allowed in XML 1.0 are handled as given by
+/// the InvalidCharMode.
+fn escape_into(
+    text: &[u8],
+    buf: &mut Vec<u8>,
+    escape: Escape,
+    name: bool,
+    invalid: InvalidCharMode,
+) -> io::Result<()> {
+    let mut start = 0;
+    let mut i = 0;
+    while i < text.len() {
+        let (esc, n): (&[u8], usize) = match text[i] {
+            b'&' if escape != Escape::Cdata => (b"&amp;", 1),
+            b'<' if escape != Escape::Cdata => (b"&lt;", 1),
+            b'>' if escape != Escape::Cdata => (b"&gt;", 1),
+            b'>' if i >= 2 && &text[i - 2..i] == b"]]" => (b"]]><![CDATA[>", 1),
+            b'"' if matches!(escape, Escape::Full | Escape::Attr) => (b"&quot;", 1),
+            b'\'' if matches!(escape, Escape::Full | Escape::Attr) => (b"&apos;", 1),
+            b'\t' if escape == Escape::Attr => (b"&#9;", 1),
+            b'\n' if escape == Escape::Attr => (b"&#10;", 1),
+            b'\r' if escape == Escape::Attr => (b"&#13;", 1),
+            b'\\' if name => (b"\\\\", 1),
+            _ => match invalid_len(text, i) {
+                0 => {
+                    i += 1;
+                    continue;
+                }
+                n => match invalid {
+                    InvalidCharMode::Error => return Err(invalid_char(&text[i..i + n])),
+                    InvalidCharMode::Strip => (b"", n),
+                    InvalidCharMode::Replace => ("\u{FFFD}".as_bytes(), n),
+                },
+            },
+        };
+        buf.extend_from_slice(&text[start..i]);
+        buf.extend_from_slice(esc);
+        i += n;
+        start = i;
+    }
+    buf.extend_from_slice(&text[start..]);
+    Ok(())
+}
+
+/// Length of the character at position i, if it is not allowed in XML 1.0.
+/// These are the control characters except tab, newline and carriage-return,
+/// and the non-characters U+FFFE and U+FFFF.
+#[inline]
+fn invalid_len(text: &[u8], i: usize) -> usize {
+    match text[i] {
+        b'\t' | b'\n' | b'\r' => 0,
+        c if c < 0x20 => 1,
+        0xEF if matches!(text.get(i + 1..i + 3), Some([0xBF, 0xBE | 0xBF])) => 3,
+        _ => 0,
+    }
+}
+
+fn invalid_char(c: &[u8]) -> io::Error {
+    let c = String::from_utf8_lossy(c);
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!(
+            "character U+{:04X} is not allowed in xml",
+            c.chars().next().map(u32::from).unwrap_or_default()
+        ),
+    )
+}
+
+fn structure_err(msg: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidInput, msg)
+}
@@ -9,18 +9,26 @@ pub use crate::format::{
     ValueFormatBoolean, ValueFormatCurrency, ValueFormatDateTime, ValueFormatNumber,
     ValueFormatPercentage, ValueFormatRef, ValueFormatText, ValueFormatTimeDuration,
 };
+#[cfg(feature = "mmap")]
+pub use crate::io::read::read_ods_mmap;
 pub use crate::io::read::{
-    read_fods, read_fods_buf, read_fods_from, read_ods, read_ods_buf, read_ods_from, OdsOptions,
+    read_fods, read_fods_buf, read_fods_from, read_ods, read_ods_buf, read_ods_from, LazyWorkBook,
+    OdsOptions,
 };
+#[cfg(feature = "validate_odf")]
+pub use crate::io::validate::{validate_fods, validate_ods};
+#[cfg(feature = "validate_odf")]
+pub use crate::io::write::write_ods_validated;
 pub use crate::io::write::{
     write_fods, write_fods_buf, write_fods_to, write_ods, write_ods_buf,
-    write_ods_buf_uncompressed, write_ods_to, OdsWriteOptions,
+    write_ods_buf_uncompressed, write_ods_parts, write_ods_to, InvalidCharMode, NonFiniteMode,
+    OdsWriteOptions, Target, WriteObserver,
 };
 pub use crate::refs::{CCol, CRow, CellRange, CellRef, ColRange, RowRange};
 pub use crate::sheet_::Sheet;
 pub use crate::style::units::{Angle, Length};
 pub use crate::style::{CellStyle, CellStyleRef};
-pub use crate::value_::{Value, ValueType};
+pub use crate::value_::{IntoValue, Value, ValueType};
 pub use crate::workbook_::WorkBook;
 
 #[macro_use]
@@ -60,11 +68,14 @@ mod sheet_;
 mod value_;
 mod workbook_;
 
+pub mod basic;
 pub mod cell {
     //! Detail structs for a Cell.
     pub use crate::cell_::CellSpan;
 }
 pub mod condition;
+pub mod conditional;
+pub mod dde;
 pub mod defaultstyles;
 pub mod draw;
 pub mod format;
@@ -75,14 +86,20 @@ pub mod metadata;
 pub mod refs;
 pub mod sheet {
     //! Detail structs for a Sheet.
-    pub use crate::sheet_::{CellIter, Grouped, Range, SheetConfig, SplitMode, Visibility};
+    pub use crate::sheet_::{
+        CellIter, CellStorage, ClearOp, Grouped, Header, HtmlOptions, PrintScale, PrintSettings,
+        Range, SheetConfig, SortKey, SplitMode, SubtotalFn, Visibility, ZoomType,
+    };
 }
 pub mod style;
 pub mod text;
 pub mod validation;
 pub mod workbook {
     //! Detail structs for the WorkBook.
-    pub use crate::workbook_::{EventListener, Script, WorkBookConfig};
+    pub use crate::workbook_::{
+        CalcSettings, EventListener, RenameStrategy, Script, ScriptEvent, StyleInfo,
+        ValidationIssue, WorkBookConfig,
+    };
 }
 pub mod xlink;
 pub mod xmltree;
@@ -1,6 +1,6 @@
 use crate::defaultstyles::DefaultFormat;
 use crate::format::FormatNumberStyle;
-use crate::locale::LocalizedValueFormat;
+use crate::locale::{DateOrder, LocalizedValueFormat};
 use crate::{
     ValueFormatBoolean, ValueFormatCurrency, ValueFormatDateTime, ValueFormatNumber,
     ValueFormatPercentage, ValueFormatTimeDuration,
@@ -85,7 +85,7 @@ impl LocalizedValueFormat for LocaleDeAt {
     }
 
     fn time_of_day_format(&self) -> ValueFormatDateTime {
-        let mut v = ValueFormatDateTime::new_localized(DefaultFormat::datetime(), Self::LOCALE);
+        let mut v = ValueFormatDateTime::new_localized(DefaultFormat::time_of_day(), Self::LOCALE);
         v.part_hours().style(FormatNumberStyle::Long).build();
         v.part_text(":").build();
         v.part_minutes().style(FormatNumberStyle::Long).build();
@@ -95,7 +95,8 @@ impl LocalizedValueFormat for LocaleDeAt {
     }
 
     fn time_interval_format(&self) -> ValueFormatTimeDuration {
-        let mut v = ValueFormatTimeDuration::new_localized(DefaultFormat::datetime(), Self::LOCALE);
+        let mut v =
+            ValueFormatTimeDuration::new_localized(DefaultFormat::time_interval(), Self::LOCALE);
         v.set_truncate_on_overflow(false);
 
         v.part_hours().style(FormatNumberStyle::Long).build();
@@ -105,4 +106,49 @@ impl LocalizedValueFormat for LocaleDeAt {
         v.part_seconds().style(FormatNumberStyle::Long).build();
         v
     }
+
+    fn decimal_separator(&self) -> char {
+        ','
+    }
+
+    fn grouping_separator(&self) -> char {
+        '.'
+    }
+
+    fn date_order(&self) -> DateOrder {
+        DateOrder::Dmy
+    }
+
+    fn boolean_names(&self) -> (&'static str, &'static str) {
+        ("WAHR", "FALSCH")
+    }
+
+    fn month_names(&self) -> [&'static str; 12] {
+        [
+            "Jänner",
+            "Februar",
+            "März",
+            "April",
+            "Mai",
+            "Juni",
+            "Juli",
+            "August",
+            "September",
+            "Oktober",
+            "November",
+            "Dezember",
+        ]
+    }
+
+    fn day_names(&self) -> [&'static str; 7] {
+        [
+            "Montag",
+            "Dienstag",
+            "Mittwoch",
+            "Donnerstag",
+            "Freitag",
+            "Samstag",
+            "Sonntag",
+        ]
+    }
 }
@@ -1,6 +1,6 @@
 use crate::defaultstyles::DefaultFormat;
 use crate::format::FormatNumberStyle;
-use crate::locale::LocalizedValueFormat;
+use crate::locale::{DateOrder, LocalizedValueFormat};
 use crate::{
     ValueFormatBoolean, ValueFormatCurrency, ValueFormatDateTime, ValueFormatNumber,
     ValueFormatPercentage, ValueFormatTimeDuration,
@@ -108,4 +108,49 @@ impl LocalizedValueFormat for LocaleEnUs {
         v.part_seconds().style(FormatNumberStyle::Long).build();
         v
     }
+
+    fn decimal_separator(&self) -> char {
+        '.'
+    }
+
+    fn grouping_separator(&self) -> char {
+        ','
+    }
+
+    fn date_order(&self) -> DateOrder {
+        DateOrder::Mdy
+    }
+
+    fn boolean_names(&self) -> (&'static str, &'static str) {
+        ("TRUE", "FALSE")
+    }
+
+    fn month_names(&self) -> [&'static str; 12] {
+        [
+            "January",
+            "February",
+            "March",
+            "April",
+            "May",
+            "June",
+            "July",
+            "August",
+            "September",
+            "October",
+            "November",
+            "December",
+        ]
+    }
+
+    fn day_names(&self) -> [&'static str; 7] {
+        [
+            "Monday",
+            "Tuesday",
+            "Wednesday",
+            "Thursday",
+            "Friday",
+            "Saturday",
+            "Sunday",
+        ]
+    }
 }
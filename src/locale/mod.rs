@@ -40,6 +40,38 @@ pub(crate) trait LocalizedValueFormat: Sync {
     fn time_of_day_format(&self) -> ValueFormatDateTime;
     /// Default time interval format.
     fn time_interval_format(&self) -> ValueFormatTimeDuration;
+    /// Decimal separator.
+    fn decimal_separator(&self) -> char;
+    /// Thousands separator.
+    fn grouping_separator(&self) -> char;
+    /// Order of day, month and year in a short date.
+    fn date_order(&self) -> DateOrder;
+    /// Names for true and false.
+    fn boolean_names(&self) -> (&'static str, &'static str);
+    /// Names of the months, January first.
+    fn month_names(&self) -> [&'static str; 12];
+    /// Names of the weekdays, Monday first.
+    fn day_names(&self) -> [&'static str; 7];
+}
+
+/// Order of the parts of a date.
+///
+/// Dmy and Mdy are only used by the optional locales.
+#[cfg_attr(
+    not(all(
+        feature = "locale_en_US",
+        any(feature = "locale_cs_CZ", feature = "locale_de_AT")
+    )),
+    allow(dead_code)
+)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum DateOrder {
+    /// Day, month, year.
+    Dmy,
+    /// Month, day, year.
+    Mdy,
+    /// Year, month, day.
+    Ymd,
 }
 
 lazy_static! {
@@ -68,3 +100,16 @@ lazy_static! {
 pub(crate) fn localized_format(locale: Locale) -> Option<&'static dyn LocalizedValueFormat> {
     LOCALE_DATA.get(&locale).copied()
 }
+
+/// Returns the localized format for the locale, for its language or
+/// the default "en".
+pub(crate) fn localized_format_or_default(
+    locale: Option<Locale>,
+) -> &'static dyn LocalizedValueFormat {
+    locale
+        .and_then(|v| {
+            localized_format(v.clone()).or_else(|| localized_format(Locale::from(v.id.language)))
+        })
+        .or_else(|| localized_format(icu_locid::locale!("en")))
+        .expect("default locale")
+}
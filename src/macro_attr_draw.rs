@@ -218,3 +218,125 @@ macro_rules! draw_mime_type {
         }
     };
 }
+
+macro_rules! draw_notify_on_update_of_ranges {
+    ($acc:ident) => {
+        /// The draw:notify-on-update-of-ranges attribute specifies a list of cell ranges whose
+        /// changes are passed to the object, e.g. the source data of a chart.
+        pub fn set_draw_notify_on_update_of_ranges(&mut self, ranges: &[CellRange]) {
+            self.$acc.set_attr(
+                "draw:notify-on-update-of-ranges",
+                format_cellranges(ranges).to_string(),
+            );
+        }
+    };
+}
+
+macro_rules! draw_fill {
+    ($acc:ident) => {
+        /// The draw:fill attribute specifies the fill style for a graphic object.
+        pub fn set_fill(&mut self, fill: FillStyle) {
+            self.$acc.set_attr("draw:fill", fill.to_string());
+        }
+
+        /// Parses the fill style.
+        pub fn fill(&self) -> Result<Option<FillStyle>, OdsError> {
+            FillStyle::parse_attr(self.$acc.attr("draw:fill"))
+        }
+
+        /// The draw:fill-color attribute specifies the color of the fill for a graphic object.
+        /// It is used only if the draw:fill attribute has the value solid, so this sets
+        /// draw:fill too.
+        pub fn set_fill_color(&mut self, color: Rgb<u8>) {
+            self.$acc
+                .set_attr("draw:fill", FillStyle::Solid.to_string());
+            self.$acc.set_attr("draw:fill-color", color_string(color));
+        }
+
+        /// Parses the fill color.
+        pub fn fill_color(&self) -> Result<Option<Rgb<u8>>, OdsError> {
+            Rgb::parse_attr(self.$acc.attr("draw:fill-color"))
+        }
+    };
+}
+
+macro_rules! draw_opacity {
+    ($acc:ident) => {
+        /// The draw:opacity attribute specifies the opacity of the fill of a graphic object.
+        /// 0% is fully transparent, 100% is opaque.
+        pub fn set_opacity(&mut self, opacity: Percent) {
+            self.$acc.set_attr("draw:opacity", opacity.to_string());
+        }
+
+        /// Parses the opacity.
+        pub fn opacity(&self) -> Result<Option<Percent>, OdsError> {
+            Percent::parse_attr(self.$acc.attr("draw:opacity"))
+        }
+    };
+}
+
+macro_rules! draw_stroke {
+    ($acc:ident) => {
+        /// The draw:stroke attribute specifies the style of the stroke on the current object.
+        pub fn set_stroke(&mut self, stroke: StrokeStyle) {
+            self.$acc.set_attr("draw:stroke", stroke.to_string());
+        }
+
+        /// Parses the stroke style.
+        pub fn stroke(&self) -> Result<Option<StrokeStyle>, OdsError> {
+            StrokeStyle::parse_attr(self.$acc.attr("draw:stroke"))
+        }
+    };
+}
+
+macro_rules! draw_shadow {
+    ($acc:ident) => {
+        /// The draw:shadow attribute specifies whether a shadow is displayed.
+        pub fn set_shadow_visible(&mut self, visible: bool) {
+            self.$acc.set_attr(
+                "draw:shadow",
+                if visible { "visible" } else { "hidden" }.to_string(),
+            );
+        }
+
+        /// The draw:shadow attribute specifies whether a shadow is displayed.
+        pub fn shadow_visible(&self) -> bool {
+            self.$acc.attr("draw:shadow") == Some("visible")
+        }
+
+        /// The draw:shadow-color attribute specifies the color of the shadow.
+        pub fn set_shadow_color(&mut self, color: Rgb<u8>) {
+            self.$acc.set_attr("draw:shadow-color", color_string(color));
+        }
+
+        /// Parses the shadow color.
+        pub fn shadow_color(&self) -> Result<Option<Rgb<u8>>, OdsError> {
+            Rgb::parse_attr(self.$acc.attr("draw:shadow-color"))
+        }
+
+        /// The draw:shadow-offset-x and draw:shadow-offset-y attributes specify the offset
+        /// of the shadow from the object.
+        pub fn set_shadow_offset(&mut self, x: Length, y: Length) {
+            self.$acc.set_attr("draw:shadow-offset-x", x.to_string());
+            self.$acc.set_attr("draw:shadow-offset-y", y.to_string());
+        }
+
+        /// Parses the shadow offset.
+        pub fn shadow_offset(&self) -> Result<Option<(Length, Length)>, OdsError> {
+            let x = Length::parse_attr(self.$acc.attr("draw:shadow-offset-x"))?;
+            let y = Length::parse_attr(self.$acc.attr("draw:shadow-offset-y"))?;
+            Ok(x.zip(y))
+        }
+
+        /// The draw:shadow-opacity attribute specifies the opacity of the shadow.
+        pub fn set_shadow_opacity(&mut self, opacity: Percent) {
+            self.$acc
+                .set_attr("draw:shadow-opacity", opacity.to_string());
+        }
+
+        /// Parses the shadow opacity.
+        pub fn shadow_opacity(&self) -> Result<Option<Percent>, OdsError> {
+            Percent::parse_attr(self.$acc.attr("draw:shadow-opacity"))
+        }
+    };
+}
@@ -12,6 +12,11 @@ macro_rules! fo_background_color {
             self.$acc
                 .set_attr("fo:background-color", color_string(color));
         }
+
+        /// Parses the background color. Transparent is returned as None.
+        pub fn background_color(&self) -> Result<Option<Rgb<u8>>, OdsError> {
+            Rgb::<u8>::parse_attr(self.$acc.attr("fo:background-color"))
+        }
     };
 }
 
@@ -46,6 +51,31 @@ macro_rules! fo_border {
             self.$acc
                 .set_attr("fo:border-top", border_string(width, border, color));
         }
+
+        /// Parses the border style of all four sides.
+        pub fn border(&self) -> Result<Option<(Length, Border, Rgb<u8>)>, OdsError> {
+            parse_border(self.$acc.attr("fo:border"))
+        }
+
+        /// Parses the bottom border style.
+        pub fn border_bottom(&self) -> Result<Option<(Length, Border, Rgb<u8>)>, OdsError> {
+            parse_border(self.$acc.attr("fo:border-bottom"))
+        }
+
+        /// Parses the left border style.
+        pub fn border_left(&self) -> Result<Option<(Length, Border, Rgb<u8>)>, OdsError> {
+            parse_border(self.$acc.attr("fo:border-left"))
+        }
+
+        /// Parses the right border style.
+        pub fn border_right(&self) -> Result<Option<(Length, Border, Rgb<u8>)>, OdsError> {
+            parse_border(self.$acc.attr("fo:border-right"))
+        }
+
+        /// Parses the top border style.
+        pub fn border_top(&self) -> Result<Option<(Length, Border, Rgb<u8>)>, OdsError> {
+            parse_border(self.$acc.attr("fo:border-top"))
+        }
     };
 }
 
@@ -115,6 +145,31 @@ macro_rules! fo_padding {
             assert!(padding.is_positive());
             self.$acc.set_attr("fo:padding-top", padding.to_string());
         }
+
+        /// Parses the padding for all sides.
+        pub fn padding(&self) -> Result<Option<Length>, OdsError> {
+            Length::parse_attr(self.$acc.attr("fo:padding"))
+        }
+
+        /// Parses the bottom padding.
+        pub fn padding_bottom(&self) -> Result<Option<Length>, OdsError> {
+            Length::parse_attr(self.$acc.attr("fo:padding-bottom"))
+        }
+
+        /// Parses the left padding.
+        pub fn padding_left(&self) -> Result<Option<Length>, OdsError> {
+            Length::parse_attr(self.$acc.attr("fo:padding-left"))
+        }
+
+        /// Parses the right padding.
+        pub fn padding_right(&self) -> Result<Option<Length>, OdsError> {
+            Length::parse_attr(self.$acc.attr("fo:padding-right"))
+        }
+
+        /// Parses the top padding.
+        pub fn padding_top(&self) -> Result<Option<Length>, OdsError> {
+            Length::parse_attr(self.$acc.attr("fo:padding-top"))
+        }
     };
 }
 
@@ -127,6 +182,11 @@ macro_rules! fo_wrap_option {
         pub fn set_wrap_option(&mut self, wrap: WrapOption) {
             self.$acc.set_attr("fo:wrap-option", wrap.to_string());
         }
+
+        /// Parses the wrap option.
+        pub fn wrap_option(&self) -> Result<Option<WrapOption>, OdsError> {
+            WrapOption::parse_attr(self.$acc.attr("fo:wrap-option"))
+        }
     };
 }
 
@@ -464,6 +524,11 @@ macro_rules! fo_text_align {
         pub fn set_text_align(&mut self, align: TextAlign) {
             self.$acc.set_attr("fo:text-align", align.to_string());
         }
+
+        /// Parses the text alignment.
+        pub fn text_align(&self) -> Result<Option<TextAlign>, OdsError> {
+            TextAlign::parse_attr(self.$acc.attr("fo:text-align"))
+        }
     };
 }
 
@@ -525,6 +590,11 @@ macro_rules! fo_color {
         pub fn set_color(&mut self, color: Rgb<u8>) {
             self.$acc.set_attr("fo:color", color_string(color));
         }
+
+        /// Parses the font color.
+        pub fn color(&self) -> Result<Option<Rgb<u8>>, OdsError> {
+            Rgb::<u8>::parse_attr(self.$acc.attr("fo:color"))
+        }
     };
 }
 
@@ -573,6 +643,11 @@ macro_rules! fo_font_size {
             assert!(size.is_positive());
             self.$acc.set_attr("fo:font-size", size.to_string());
         }
+
+        /// Parses the font size.
+        pub fn font_size(&self) -> Result<Option<FontSize>, OdsError> {
+            FontSize::parse_attr(self.$acc.attr("fo:font-size"))
+        }
     };
 }
 macro_rules! fo_font_size_rel {
@@ -599,6 +674,11 @@ macro_rules! fo_font_style {
         pub fn set_font_italic(&mut self) {
             self.$acc.set_attr("fo:font-style", "italic".to_string());
         }
+
+        /// Parses the font style.
+        pub fn font_style(&self) -> Result<Option<FontStyle>, OdsError> {
+            FontStyle::parse_attr(self.$acc.attr("fo:font-style"))
+        }
     };
 }
 
@@ -615,6 +695,11 @@ macro_rules! fo_font_weight {
             self.$acc
                 .set_attr("fo:font-weight", FontWeight::Bold.to_string());
         }
+
+        /// Parses the font weight.
+        pub fn font_weight(&self) -> Result<Option<FontWeight>, OdsError> {
+            FontWeight::parse_attr(self.$acc.attr("fo:font-weight"))
+        }
     };
 }
 
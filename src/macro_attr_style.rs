@@ -72,6 +72,11 @@ macro_rules! style_cell_protect {
             self.$acc
                 .set_attr("style:cell-protect", protect.to_string());
         }
+
+        /// The style:cell-protect attribute specifies how a cell is protected.
+        pub fn cell_protect(&self) -> Result<Option<CellProtect>, OdsError> {
+            CellProtect::parse_attr(self.$acc.attr("style:cell-protect"))
+        }
     };
 }
 
@@ -231,6 +236,11 @@ macro_rules! style_rotation_angle {
             self.$acc
                 .set_attr("style:rotation-angle", angle.to_string());
         }
+
+        /// Parses the rotation angle.
+        pub fn rotation_angle(&self) -> Result<Option<Angle>, OdsError> {
+            Angle::parse_attr(self.$acc.attr("style:rotation-angle"))
+        }
     };
 }
 
@@ -281,6 +291,11 @@ macro_rules! style_shrink_to_fit {
             self.$acc
                 .set_attr("style:shrink-to-fit", shrink.to_string());
         }
+
+        /// Shrink to fit.
+        pub fn shrink_to_fit(&self) -> Result<bool, OdsError> {
+            bool::parse_attr_def(self.$acc.attr("style:shrink-to-fit"), false)
+        }
     };
 }
 
@@ -315,9 +330,14 @@ macro_rules! style_vertical_align {
         /// * middle: to the center of the line.
         /// * top: to the top of the line.
         pub fn set_vertical_align(&mut self, align: CellAlignVertical) {
-            self.cellstyle
+            self.$acc
                 .set_attr("style:vertical-align", align.to_string());
         }
+
+        /// Parses the vertical alignment.
+        pub fn vertical_align(&self) -> Result<Option<CellAlignVertical>, OdsError> {
+            CellAlignVertical::parse_attr(self.$acc.attr("style:vertical-align"))
+        }
     };
 }
 
@@ -650,6 +670,11 @@ macro_rules! style_writing_mode {
             self.$acc
                 .set_attr("style:writing-mode", writing_mode.to_string());
         }
+
+        /// Parses the writing mode.
+        pub fn writing_mode(&self) -> Result<Option<WritingMode>, OdsError> {
+            WritingMode::parse_attr(self.$acc.attr("style:writing-mode"))
+        }
     };
 }
 
@@ -718,6 +743,11 @@ macro_rules! style_font_name {
         pub fn set_font_name<S: Into<String>>(&mut self, name: S) {
             self.$acc.set_attr("style:font-name", name.into());
         }
+
+        /// Font name.
+        pub fn font_name(&self) -> Option<&str> {
+            self.$acc.attr("style:font-name")
+        }
     };
 }
 
@@ -761,6 +791,11 @@ macro_rules! style_font_name_asian {
         pub fn set_font_name_asian<S: Into<String>>(&mut self, name: S) {
             self.$acc.set_attr("style:font-name-asian", name.into());
         }
+
+        /// Font name.
+        pub fn font_name_asian(&self) -> Option<&str> {
+            self.$acc.attr("style:font-name-asian")
+        }
     };
 }
 
@@ -782,6 +817,11 @@ macro_rules! style_font_size_asian {
             self.$acc
                 .set_attr("style:font-size-asian", size.to_string());
         }
+
+        /// Parses the font size.
+        pub fn font_size_asian(&self) -> Result<Option<FontSize>, OdsError> {
+            FontSize::parse_attr(self.$acc.attr("style:font-size-asian"))
+        }
     };
 }
 
@@ -812,6 +852,11 @@ macro_rules! style_font_style_asian {
             self.$acc
                 .set_attr("style:font-style-asian", "italic".to_string());
         }
+
+        /// Parses the font style.
+        pub fn font_style_asian(&self) -> Result<Option<FontStyle>, OdsError> {
+            FontStyle::parse_attr(self.$acc.attr("style:font-style-asian"))
+        }
     };
 }
 
@@ -829,6 +874,11 @@ macro_rules! style_font_weight_asian {
             self.$acc
                 .set_attr("style:font-weight-asian", FontWeight::Bold.to_string());
         }
+
+        /// Parses the font weight.
+        pub fn font_weight_asian(&self) -> Result<Option<FontWeight>, OdsError> {
+            FontWeight::parse_attr(self.$acc.attr("style:font-weight-asian"))
+        }
     };
 }
 
@@ -888,6 +938,11 @@ macro_rules! style_font_name_complex {
         pub fn set_font_name_complex<S: Into<String>>(&mut self, name: S) {
             self.$acc.set_attr("style:font-name-complex", name.into());
         }
+
+        /// Font name.
+        pub fn font_name_complex(&self) -> Option<&str> {
+            self.$acc.attr("style:font-name-complex")
+        }
     };
 }
 
@@ -909,6 +964,11 @@ macro_rules! style_font_size_complex {
             self.$acc
                 .set_attr("style:font-size-complex", size.to_string());
         }
+
+        /// Parses the font size.
+        pub fn font_size_complex(&self) -> Result<Option<FontSize>, OdsError> {
+            FontSize::parse_attr(self.$acc.attr("style:font-size-complex"))
+        }
     };
 }
 macro_rules! style_font_size_rel_complex {
@@ -938,6 +998,11 @@ macro_rules! style_font_style_complex {
             self.$acc
                 .set_attr("style:font-style-complex", "italic".to_string());
         }
+
+        /// Parses the font style.
+        pub fn font_style_complex(&self) -> Result<Option<FontStyle>, OdsError> {
+            FontStyle::parse_attr(self.$acc.attr("style:font-style-complex"))
+        }
     };
 }
 
@@ -955,6 +1020,11 @@ macro_rules! style_font_weight_complex {
             self.$acc
                 .set_attr("style:font-weight-complex", FontWeight::Bold.to_string());
         }
+
+        /// Parses the font weight.
+        pub fn font_weight_complex(&self) -> Result<Option<FontWeight>, OdsError> {
+            FontWeight::parse_attr(self.$acc.attr("style:font-weight-complex"))
+        }
     };
 }
 
@@ -1087,6 +1157,40 @@ macro_rules! style_text_emphasize {
     };
 }
 
+macro_rules! style_ruby_align {
+    ($acc:ident) => {
+        /// The style:ruby-align attribute specifies the alignment of the ruby text relative to the
+        /// ruby base text. 20.344
+        ///
+        /// The defined values for the style:ruby-align attribute are:
+        /// * center: ruby text is centered.
+        /// * distribute-letter: ruby text is distributed across the base text, with no space
+        ///   before the first and after the last character.
+        /// * distribute-space: ruby text is distributed across the base text, with half a
+        ///   character space before the first and after the last character.
+        /// * left: ruby text is left aligned.
+        /// * right: ruby text is right aligned.
+        pub fn set_ruby_align(&mut self, align: RubyAlign) {
+            self.$acc.set_attr("style:ruby-align", align.to_string());
+        }
+    };
+}
+
+macro_rules! style_ruby_position {
+    ($acc:ident) => {
+        /// The style:ruby-position attribute specifies whether the ruby text is placed above or
+        /// below the ruby base text. 20.345
+        ///
+        /// The defined values for the style:ruby-position attribute are:
+        /// * above: ruby text is placed above the base text.
+        /// * below: ruby text is placed below the base text.
+        pub fn set_ruby_position(&mut self, position: RubyPosition) {
+            self.$acc
+                .set_attr("style:ruby-position", position.to_string());
+        }
+    };
+}
+
 macro_rules! style_text_line_through {
     ($acc:ident) => {
         /// The style:text-line-through-color attribute specifies the color that is used for linethrough text.
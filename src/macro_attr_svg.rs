@@ -113,3 +113,38 @@ macro_rules! svg_y {
         }
     };
 }
+
+macro_rules! svg_stroke {
+    ($acc:ident) => {
+        /// The svg:stroke-color attribute specifies the color of the stroke.
+        pub fn set_stroke_color(&mut self, color: Rgb<u8>) {
+            self.$acc.set_attr("svg:stroke-color", color_string(color));
+        }
+
+        /// Parses the stroke color.
+        pub fn stroke_color(&self) -> Result<Option<Rgb<u8>>, OdsError> {
+            Rgb::parse_attr(self.$acc.attr("svg:stroke-color"))
+        }
+
+        /// The svg:stroke-width attribute specifies the width of the stroke.
+        pub fn set_stroke_width(&mut self, width: Length) {
+            self.$acc.set_attr("svg:stroke-width", width.to_string());
+        }
+
+        /// Parses the stroke width.
+        pub fn stroke_width(&self) -> Result<Option<Length>, OdsError> {
+            Length::parse_attr(self.$acc.attr("svg:stroke-width"))
+        }
+
+        /// The svg:stroke-opacity attribute specifies the opacity of the stroke.
+        pub fn set_stroke_opacity(&mut self, opacity: Percent) {
+            self.$acc
+                .set_attr("svg:stroke-opacity", opacity.to_string());
+        }
+
+        /// Parses the stroke opacity.
+        pub fn stroke_opacity(&self) -> Result<Option<Percent>, OdsError> {
+            Percent::parse_attr(self.$acc.attr("svg:stroke-opacity"))
+        }
+    };
+}
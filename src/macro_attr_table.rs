@@ -55,6 +55,15 @@ macro_rules! table_tab_color {
         pub fn set_tab_color(&mut self, color: Rgb<u8>) {
             self.$acc.set_attr("table:tab-color", color_string(color));
         }
+
+        /// Parses the tab color. LibreOffice writes tableooo:tab-color instead
+        /// of table:tab-color, both are recognized.
+        pub fn tab_color(&self) -> Result<Option<Rgb<u8>>, OdsError> {
+            match self.$acc.attr("table:tab-color") {
+                Some(v) => Rgb::parse_attr(Some(v)),
+                None => Rgb::parse_attr(self.$acc.attr("tableooo:tab-color")),
+            }
+        }
     };
 }
 
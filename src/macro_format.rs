@@ -68,6 +68,53 @@ macro_rules! valueformat {
                 v
             }
 
+            /// Parses an Excel/Calc-style format code like "#,##0.00;[RED]-#,##0.00".
+            ///
+            /// Each section but the last becomes a sub-format named "{name}P0", "{name}P1", ...
+            /// that is referenced via a stylemap of the returned main format.
+            /// Without explicit conditions the sections are used for positive;negative or
+            /// positive;negative;zero values. A fourth section for text values is ignored.
+            /// The sub-formats must be added to the workbook too.
+            pub fn from_format_string<S: AsRef<str>>(
+                name: S,
+                format: &str,
+            ) -> Result<(Self, Vec<Self>), OdsError> {
+                $crate::format::parse_format_string(name.as_ref(), format, |v| Self::new_named(v))
+            }
+
+            /// Creates the format code for the parts of this format.
+            /// The stylemaps are not considered.
+            pub fn to_format_string(&self) -> String {
+                $crate::format::format_string(self)
+            }
+
+            /// Creates the format code for this format including the sub-formats
+            /// referenced by the stylemaps. The lookup resolves the sub-formats by name.
+            pub fn to_format_string_with<'a, F>(&self, lookup: F) -> String
+            where
+                F: Fn(&str) -> Option<&'a Self>,
+            {
+                $crate::format::format_string_with(self, lookup)
+            }
+
+            /// Formats the value with the parts of this format, the way it is
+            /// displayed in a cell. Decimal and thousands separators and the names
+            /// of months and weekdays are taken from the locale of the format.
+            /// The stylemaps are not considered.
+            pub fn format_value(&self, value: &Value) -> String {
+                $crate::format::format_value(self, value)
+            }
+
+            /// Formats the value with this format or with the sub-format of the
+            /// first stylemap whose condition matches. The lookup resolves the
+            /// sub-formats by name.
+            pub fn format_value_with<'a, F>(&self, value: &Value, lookup: F) -> String
+            where
+                F: Fn(&str) -> Option<&'a Self>,
+            {
+                $crate::format::format_value_with(self, value, lookup)
+            }
+
             number_locale!(attr);
             number_title!(attr);
             number_transliteration_locale!(attr);
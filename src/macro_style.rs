@@ -44,6 +44,26 @@ macro_rules! styles_styles2 {
     };
 }
 
+/// Stylemaps for conditional styles.
+macro_rules! style_stylemaps {
+    () => {
+        /// Adds a stylemap.
+        pub fn push_stylemap(&mut self, stylemap: StyleMap) {
+            self.stylemaps.get_or_insert_with(Vec::new).push(stylemap);
+        }
+
+        /// Returns the stylemaps
+        pub fn stylemaps(&self) -> Option<&Vec<StyleMap>> {
+            self.stylemaps.as_ref()
+        }
+
+        /// Returns the mutable stylemap.
+        pub fn stylemaps_mut(&mut self) -> &mut Vec<StyleMap> {
+            self.stylemaps.get_or_insert_with(Vec::new)
+        }
+    };
+}
+
 /// Generates a name reference for a style.
 macro_rules! style_ref2 {
     ($l:ident) => {
@@ -64,41 +84,66 @@ macro_rules! style_ref2 {
 }
 
 /// Generates a name reference for a style.
+///
+/// The name is interned, cells that use the same style share the string.
 macro_rules! style_ref2_base {
     ($l:ident) => {
         /// Reference
-        #[derive(Debug, Clone, Hash, PartialEq, Eq)]
+        #[derive(Clone, PartialEq, Eq)]
         pub struct $l {
-            pub(crate) id: String,
+            pub(crate) id: string_cache::DefaultAtom,
+        }
+
+        impl std::fmt::Debug for $l {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                f.debug_struct(stringify!($l))
+                    .field("id", &self.as_str())
+                    .finish()
+            }
         }
 
         impl GetSize for $l {
+            // The string is shared and counted for every reference,
+            // the sum is an upper bound.
             fn get_heap_size(&self) -> usize {
-                self.id.get_heap_size()
+                if self.id.is_dynamic() {
+                    self.id.len()
+                } else {
+                    0
+                }
+            }
+        }
+
+        // Must match the hash of the str for Borrow<str>.
+        impl std::hash::Hash for $l {
+            fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+                self.id.as_ref().hash(state)
             }
         }
 
         impl From<String> for $l {
             fn from(id: String) -> Self {
-                Self { id }
+                Self { id: id.into() }
             }
         }
 
         impl From<&String> for $l {
             fn from(id: &String) -> Self {
-                Self { id: id.clone() }
+                Self {
+                    id: id.as_str().into(),
+                }
             }
         }
 
         impl From<&str> for $l {
             fn from(id: &str) -> Self {
-                Self { id: id.to_string() }
+                Self { id: id.into() }
             }
         }
 
         impl Borrow<str> for $l {
             fn borrow(&self) -> &str {
-                self.id.borrow()
+                self.id.as_ref()
             }
         }
 
@@ -111,7 +156,7 @@ macro_rules! style_ref2_base {
         impl $l {
             /// Reference as str.
             pub fn as_str(&self) -> &str {
-                self.id.as_str()
+                self.id.as_ref()
             }
         }
     };
@@ -2,8 +2,11 @@
 //! Defines types for cell references.
 //!
 
+pub(crate) use crate::refs::format::{fmt_col_name, fmt_row_name};
+pub(crate) use crate::refs::format_refs::fmt_table_name;
 use crate::refs::format_refs::{
-    fmt_cell_range, fmt_cell_ref, fmt_col, fmt_col_range, fmt_row, fmt_row_range,
+    fmt_cell_range, fmt_cell_ref, fmt_col, fmt_col_range, fmt_r1c1, fmt_r1c1_table_name, fmt_row,
+    fmt_row_range,
 };
 use crate::refs::parser::CRCode::{CRCellRange, CRCellRef, CRColRange, CRRowRange};
 use crate::refs::parser::{CRCode, KTokenizerError};
@@ -11,6 +14,7 @@ use crate::OdsError;
 use get_size2::GetSize;
 use kparse::provider::StdTracker;
 use kparse::Track;
+use std::cmp::{max, min};
 use std::fmt;
 use std::fmt::{Display, Formatter};
 
@@ -297,6 +301,45 @@ impl CellRef {
         self.col.col_abs = true;
         self
     }
+
+    /// Parses a cell reference for user input.
+    ///
+    /// Besides the ODF syntax ".A1" or "$'Sheet one'.$A$1" this accepts
+    /// * the reference without the leading dot "A1",
+    /// * unquoted table names with spaces "Sheet one.$A$1",
+    /// * "!" as separator "Sheet1!A1",
+    /// * lowercase column names "a1",
+    /// * whitespace around the reference.
+    ///
+    /// The error contains the part of the input that couldn't be parsed.
+    pub fn parse(buf: &str) -> Result<Self, OdsError> {
+        let buf = buf.trim();
+        if let Ok(cell_ref) = parse_cellref(buf) {
+            return Ok(cell_ref);
+        }
+        let (table, cell) = relaxed_table(buf)?;
+        let (row, col) = relaxed_cell(cell)?;
+        Ok(CellRef {
+            iri: None,
+            table,
+            row,
+            col,
+        })
+    }
+
+    /// Returns the reference in R1C1 notation. Relative parts are given
+    /// as offset to the cell (row, col) that contains the reference,
+    /// e.g. "R[-1]C3". The table is separated with "!", the iri is
+    /// not included.
+    pub fn to_r1c1(&self, row: u32, col: u32) -> String {
+        let mut buf = String::new();
+        if let Some(table) = &self.table {
+            let _ = fmt_r1c1_table_name(&mut buf, table);
+            buf.push('!');
+        }
+        let _ = fmt_r1c1(&mut buf, &self.row, &self.col, row, col);
+        buf
+    }
 }
 
 impl TryFrom<&str> for CellRef {
@@ -632,6 +675,205 @@ impl CellRange {
     pub fn out_looped(&self, row: u32, col: u32) -> bool {
         row > self.to_row.row || row == self.to_row.row && col > self.to_col.col
     }
+
+    /// Parses a cell range for user input.
+    ///
+    /// Accepts the same relaxed syntax as [CellRef::parse] for both ends
+    /// of the range, e.g. "Sheet one.a1:b5". A single cell is read as
+    /// a range with one cell.
+    ///
+    /// The error contains the part of the input that couldn't be parsed.
+    pub fn parse(buf: &str) -> Result<Self, OdsError> {
+        let buf = buf.trim();
+        if let Ok(cell_range) = parse_cellrange(buf) {
+            return Ok(cell_range);
+        }
+        let (from, to) = match find_unquoted(buf, &[':']) {
+            Some(idx) => (buf[..idx].trim(), buf[idx + 1..].trim()),
+            None => (buf, buf),
+        };
+        let (table, from) = relaxed_table(from)?;
+        let (from_row, from_col) = relaxed_cell(from)?;
+        let (to_table, to) = relaxed_table(to)?;
+        let (to_row, to_col) = relaxed_cell(to)?;
+        Ok(CellRange {
+            iri: None,
+            from_table: table,
+            from_row,
+            from_col,
+            to_table,
+            to_row,
+            to_col,
+        })
+    }
+
+    /// Returns the range in R1C1 notation. Relative parts are given
+    /// as offset to the cell (row, col) that contains the reference,
+    /// e.g. "R1C1:R[2]C". The table is separated with "!", the iri is
+    /// not included.
+    pub fn to_r1c1(&self, row: u32, col: u32) -> String {
+        let mut buf = String::new();
+        if let Some(table) = &self.from_table {
+            let _ = fmt_r1c1_table_name(&mut buf, table);
+            if let Some(to_table) = &self.to_table {
+                if to_table != table {
+                    buf.push(':');
+                    let _ = fmt_r1c1_table_name(&mut buf, to_table);
+                }
+            }
+            buf.push('!');
+        }
+        let _ = fmt_r1c1(&mut buf, &self.from_row, &self.from_col, row, col);
+        buf.push(':');
+        let _ = fmt_r1c1(&mut buf, &self.to_row, &self.to_col, row, col);
+        buf
+    }
+
+    /// Swaps start and end of the range where necessary, so that
+    /// row <= to_row and col <= to_col.
+    pub fn normalize(&mut self) {
+        if self.from_row.row > self.to_row.row {
+            std::mem::swap(&mut self.from_row, &mut self.to_row);
+        }
+        if self.from_col.col > self.to_col.col {
+            std::mem::swap(&mut self.from_col, &mut self.to_col);
+        }
+    }
+
+    /// Returns the normalized range.
+    /// See [normalize](CellRange::normalize).
+    pub fn normalized(mut self) -> Self {
+        self.normalize();
+        self
+    }
+
+    /// Number of cells in the range.
+    pub fn cell_count(&self) -> u64 {
+        (self.to_row.row.abs_diff(self.from_row.row) as u64 + 1)
+            * (self.to_col.col.abs_diff(self.from_col.col) as u64 + 1)
+    }
+
+    /// Iterates all cells of the range, rows first.
+    /// The range should be normalized, otherwise this is empty.
+    pub fn iter(&self) -> impl Iterator<Item = (u32, u32)> {
+        let (col, to_col) = (self.from_col.col, self.to_col.col);
+        (self.from_row.row..=self.to_row.row)
+            .flat_map(move |row| (col..=to_col).map(move |col| (row, col)))
+    }
+
+    /// Does the range contain the cell. The table and iri of both
+    /// must be the same.
+    pub fn contains_ref(&self, cell: &CellRef) -> bool {
+        self.iri == cell.iri
+            && self.from_table == cell.table
+            && self.contains(cell.row(), cell.col())
+    }
+
+    /// Does the range contain the other range completely.
+    /// The table and iri of both must be the same.
+    pub fn contains_range(&self, other: &CellRange) -> bool {
+        self.same_table(other)
+            && self.contains(other.from_row.row, other.from_col.col)
+            && self.contains(other.to_row.row, other.to_col.col)
+    }
+
+    /// Do both ranges have at least one cell in common.
+    /// The table and iri of both must be the same.
+    pub fn intersects(&self, other: &CellRange) -> bool {
+        self.same_table(other)
+            && self.from_row.row <= other.to_row.row
+            && other.from_row.row <= self.to_row.row
+            && self.from_col.col <= other.to_col.col
+            && other.from_col.col <= self.to_col.col
+    }
+
+    /// The cells that are contained in both ranges.
+    /// The result keeps table, iri and absolute flags of self.
+    pub fn intersection(&self, other: &CellRange) -> Option<CellRange> {
+        if self.intersects(other) {
+            Some(self.with_bounds(
+                max(self.from_row.row, other.from_row.row),
+                max(self.from_col.col, other.from_col.col),
+                min(self.to_row.row, other.to_row.row),
+                min(self.to_col.col, other.to_col.col),
+            ))
+        } else {
+            None
+        }
+    }
+
+    /// The cells of this range that are not contained in the other
+    /// range. The result are up to four non-overlapping ranges, it is
+    /// empty if the other range covers this one.
+    /// The results keep table, iri and absolute flags of self.
+    pub fn subtract(&self, other: &CellRange) -> Vec<CellRange> {
+        let Some(cut) = self.intersection(other) else {
+            return vec![self.clone()];
+        };
+
+        let mut result = Vec::new();
+        // above and below span the full width.
+        if self.from_row.row < cut.from_row.row {
+            result.push(self.with_bounds(
+                self.from_row.row,
+                self.from_col.col,
+                cut.from_row.row - 1,
+                self.to_col.col,
+            ));
+        }
+        if cut.to_row.row < self.to_row.row {
+            result.push(self.with_bounds(
+                cut.to_row.row + 1,
+                self.from_col.col,
+                self.to_row.row,
+                self.to_col.col,
+            ));
+        }
+        // left and right only the rows of the intersection.
+        if self.from_col.col < cut.from_col.col {
+            result.push(self.with_bounds(
+                cut.from_row.row,
+                self.from_col.col,
+                cut.to_row.row,
+                cut.from_col.col - 1,
+            ));
+        }
+        if cut.to_col.col < self.to_col.col {
+            result.push(self.with_bounds(
+                cut.from_row.row,
+                cut.to_col.col + 1,
+                cut.to_row.row,
+                self.to_col.col,
+            ));
+        }
+        result
+    }
+
+    /// The cells that are contained in either range, as a list of
+    /// non-overlapping ranges. This is self followed by the parts of
+    /// the other range that are not in self.
+    pub fn union(&self, other: &CellRange) -> Vec<CellRange> {
+        let mut result = vec![self.clone()];
+        if self.same_table(other) {
+            result.extend(other.subtract(self));
+        } else {
+            result.push(other.clone());
+        }
+        result
+    }
+
+    fn same_table(&self, other: &CellRange) -> bool {
+        self.iri == other.iri && self.from_table == other.from_table
+    }
+
+    fn with_bounds(&self, row: u32, col: u32, to_row: u32, to_col: u32) -> Self {
+        let mut range = self.clone();
+        range.from_row.row = row;
+        range.from_col.col = col;
+        range.to_row.row = to_row;
+        range.to_col.col = to_col;
+        range
+    }
 }
 
 impl TryFrom<&str> for CellRange {
@@ -1106,6 +1348,43 @@ mod format_refs {
         Ok(())
     }
 
+    /// Appends one cell in R1C1 notation relative to (row, col).
+    pub(crate) fn fmt_r1c1(
+        f: &mut impl fmt::Write,
+        cell_row: &CRow,
+        cell_col: &CCol,
+        row: u32,
+        col: u32,
+    ) -> fmt::Result {
+        if cell_row.row_abs() {
+            write!(f, "R{}", cell_row.row() as u64 + 1)?;
+        } else if cell_row.row() == row {
+            write!(f, "R")?;
+        } else {
+            write!(f, "R[{}]", cell_row.row() as i64 - row as i64)?;
+        }
+        if cell_col.col_abs() {
+            write!(f, "C{}", cell_col.col() as u64 + 1)?;
+        } else if cell_col.col() == col {
+            write!(f, "C")?;
+        } else {
+            write!(f, "C[{}]", cell_col.col() as i64 - col as i64)?;
+        }
+        Ok(())
+    }
+
+    /// Appends the table-name for R1C1 notation, without the trailing "!".
+    pub(crate) fn fmt_r1c1_table_name(f: &mut impl fmt::Write, table_name: &str) -> fmt::Result {
+        if table_name.chars().all(|v| v.is_alphanumeric() || v == '_') {
+            write!(f, "{}", table_name)?;
+        } else {
+            write!(f, "'")?;
+            write!(f, "{}", &table_name.replace('\'', "''"))?;
+            write!(f, "'")?;
+        }
+        Ok(())
+    }
+
     /// Appends the IRI
     pub(crate) fn fmt_iri(f: &mut impl fmt::Write, iri: Option<&String>) -> fmt::Result {
         if let Some(iri) = iri {
@@ -1199,6 +1478,96 @@ pub fn parse_cellranges(buf: &str) -> Result<Option<Vec<CellRange>>, OdsError> {
     }
 }
 
+/// Position of the first of the chars that is not within single quotes.
+fn find_unquoted(buf: &str, chars: &[char]) -> Option<usize> {
+    let mut quoted = false;
+    for (idx, c) in buf.char_indices() {
+        if c == '\'' {
+            quoted = !quoted;
+        } else if !quoted && chars.contains(&c) {
+            return Some(idx);
+        }
+    }
+    None
+}
+
+/// Splits off the table name at the last "." or "!" outside of quotes.
+/// The table name can be quoted, a leading "$" is ignored.
+fn relaxed_table(buf: &str) -> Result<(Option<String>, &str), OdsError> {
+    let mut split = None;
+    let mut rest = buf;
+    while let Some(idx) = find_unquoted(rest, &['.', '!']) {
+        split = Some(buf.len() - rest.len() + idx);
+        rest = &rest[idx + 1..];
+    }
+    let Some(split) = split else {
+        return Ok((None, buf));
+    };
+
+    let table = buf[..split].trim();
+    let table = table.strip_prefix('$').unwrap_or(table);
+    let table = if let Some(quoted) = table.strip_prefix('\'') {
+        match quoted.strip_suffix('\'') {
+            Some(quoted) => quoted.replace("''", "'"),
+            None => return Err(OdsError::Parse("unterminated quote", Some(table.into()))),
+        }
+    } else {
+        table.to_string()
+    };
+
+    if table.is_empty() {
+        Ok((None, &buf[split + 1..]))
+    } else {
+        Ok((Some(table), &buf[split + 1..]))
+    }
+}
+
+/// Parses "$A$1", the column name can be lowercase.
+fn relaxed_cell(buf: &str) -> Result<(CRow, CCol), OdsError> {
+    let err = || OdsError::Parse("invalid cell reference", Some(buf.into()));
+
+    let rest = buf.trim();
+    let (col_abs, rest) = match rest.strip_prefix('$') {
+        Some(rest) => (true, rest),
+        None => (false, rest),
+    };
+    let col_len = rest
+        .find(|v: char| !v.is_ascii_alphabetic())
+        .unwrap_or(rest.len());
+    let (col_name, rest) = rest.split_at(col_len);
+    let (row_abs, row_name) = match rest.strip_prefix('$') {
+        Some(rest) => (true, rest),
+        None => (false, rest),
+    };
+    if col_name.is_empty() || row_name.is_empty() || !row_name.bytes().all(|v| v.is_ascii_digit()) {
+        return Err(err());
+    }
+
+    let mut col = 0u32;
+    for c in col_name.bytes() {
+        let v = (c.to_ascii_uppercase() - b'A' + 1) as u32;
+        col = col
+            .checked_mul(26)
+            .and_then(|col| col.checked_add(v))
+            .ok_or_else(err)?;
+    }
+    let row = row_name.parse::<u32>().map_err(|_| err())?;
+    if row == 0 {
+        return Err(err());
+    }
+
+    Ok((
+        CRow {
+            row_abs,
+            row: row - 1,
+        },
+        CCol {
+            col_abs,
+            col: col - 1,
+        },
+    ))
+}
+
 pub(crate) fn format_cellranges(v: &[CellRange]) -> impl Display + '_ {
     struct Tmp<'f>(&'f [CellRange]);
 
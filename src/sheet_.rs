@@ -2,24 +2,38 @@
 //! One sheet of the spreadsheet.
 //!
 
+use chrono::{Duration, NaiveDate, NaiveTime};
 use get_size2::GetSize;
+use std::borrow::Cow;
+use std::cmp::Ordering;
 use std::collections::{BTreeMap, Bound};
 use std::fmt::{Debug, Display, Formatter};
-use std::iter::FusedIterator;
+use std::iter::{FusedIterator, Peekable};
 use std::ops::RangeBounds;
 use std::{fmt, mem};
 
+use crate::attrmap2::AttrMap2;
 use crate::cell_::{CellContent, CellContentRef, CellData};
+use crate::color::Rgb;
+use crate::conditional::ConditionalFormat;
 use crate::draw::{Annotation, DrawFrame};
-use crate::style::{ColStyleRef, RowStyleRef, TableStyleRef};
+use crate::ds::cellstore::{self, CellStore};
+use crate::formula::{map_refs, shift_index, shift_refs};
+use crate::style::units::PrintOrder;
+use crate::style::{ColStyleRef, MasterPageRef, RowStyleRef, TableStyleRef};
 use crate::validation::ValidationRef;
-use crate::value_::Value;
+use crate::value_::{IntoValue, Value, ValueType};
+use crate::workbook_::{range_names, EventListener, MAX_COLS, MAX_ROWS};
 use crate::xmltree::XmlTag;
-use crate::{CellRange, CellStyleRef, Length, OdsError};
+use crate::{CellRange, CellRef, CellStyleRef, HashMap, Length, OdsError};
 
+mod html;
+mod infer;
 #[cfg(test)]
 mod tests;
 
+pub use html::HtmlOptions;
+
 /// Visibility of a column or row.
 #[derive(Debug, Clone, Copy, Eq, PartialEq, Default, GetSize)]
 #[allow(missing_docs)]
@@ -58,6 +72,10 @@ pub(crate) struct RowHeader {
     /// on reading.
     pub(crate) span: u32,
     pub(crate) height: Length,
+    /// Page break before this row.
+    pub(crate) page_break: bool,
+    /// Unknown attributes, kept for round-tripping.
+    pub(crate) attr: AttrMap2,
 }
 
 impl Default for RowHeader {
@@ -69,6 +87,8 @@ impl Default for RowHeader {
             repeat: 1,
             span: 1,
             height: Default::default(),
+            page_break: false,
+            attr: Default::default(),
         }
     }
 }
@@ -80,6 +100,8 @@ pub(crate) struct ColHeader {
     pub(crate) cellstyle: Option<CellStyleRef>,
     pub(crate) visible: Visibility,
     pub(crate) width: Length,
+    /// Page break before this column.
+    pub(crate) page_break: bool,
     /// Logical valid range for all the header values. Avoids duplication
     /// on reading.
     pub(crate) span: u32,
@@ -92,11 +114,26 @@ impl Default for ColHeader {
             cellstyle: None,
             visible: Default::default(),
             width: Default::default(),
+            page_break: false,
             span: 1,
         }
     }
 }
 
+/// Storage backend for the cell data of a sheet.
+///
+/// Sparse is a BTreeMap and works well for any kind of data. Dense
+/// stores each row as a vector and saves memory and iteration time for
+/// grids that are mostly filled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CellStorage {
+    /// Cells in a BTreeMap. This is the default.
+    #[default]
+    Sparse,
+    /// Cells in one vector per row.
+    Dense,
+}
+
 /// One sheet of the spreadsheet.
 ///
 /// Contains the data and the style-references. The can also be
@@ -107,23 +144,35 @@ pub struct Sheet {
     pub(crate) name: String,
     pub(crate) style: Option<TableStyleRef>,
 
-    pub(crate) data: BTreeMap<(u32, u32), CellData>,
+    pub(crate) data: CellStore,
 
     pub(crate) col_header: BTreeMap<u32, ColHeader>,
     pub(crate) row_header: BTreeMap<u32, RowHeader>,
 
     pub(crate) display: bool,
     pub(crate) print: bool,
+    #[get_size(ignore)]
+    pub(crate) tab_color: Option<Rgb<u8>>,
+    pub(crate) rtl: bool,
+    pub(crate) masterpage: Option<MasterPageRef>,
+    pub(crate) protected: bool,
+    pub(crate) protection_key: Option<String>,
+    pub(crate) protection_key_digest: Option<String>,
+    pub(crate) event_listener: HashMap<String, EventListener>,
 
     pub(crate) header_rows: Option<Header>,
     pub(crate) header_cols: Option<Header>,
     pub(crate) print_ranges: Option<Vec<CellRange>>,
+    pub(crate) print_page_order: Option<PrintOrder>,
+    pub(crate) print_scale: Option<PrintScale>,
 
     pub(crate) group_rows: Vec<Grouped>,
     pub(crate) group_cols: Vec<Grouped>,
 
     pub(crate) sheet_config: SheetConfig,
 
+    pub(crate) conditional_formats: Vec<ConditionalFormat>,
+
     pub(crate) extra: Vec<XmlTag>,
 }
 
@@ -143,13 +192,13 @@ impl<'a> IntoIterator for &'a Sheet {
 /// Iterator over cells.
 #[derive(Debug)]
 pub(crate) struct CellDataIter<'a> {
-    iter: std::collections::btree_map::Range<'a, (u32, u32), CellData>,
-    k_data: Option<&'a (u32, u32)>,
+    iter: cellstore::Range<'a>,
+    k_data: Option<(u32, u32)>,
     v_data: Option<&'a CellData>,
 }
 
 impl<'a> CellDataIter<'a> {
-    pub(crate) fn new(iter: std::collections::btree_map::Range<'a, (u32, u32), CellData>) -> Self {
+    pub(crate) fn new(iter: cellstore::Range<'a>) -> Self {
         Self {
             iter,
             k_data: None,
@@ -160,7 +209,7 @@ impl<'a> CellDataIter<'a> {
     /// Returns the (row,col) of the next cell.
     #[allow(dead_code)]
     pub(crate) fn peek_cell(&mut self) -> Option<(u32, u32)> {
-        self.k_data.copied()
+        self.k_data
     }
 
     fn load_next_data(&mut self) {
@@ -186,7 +235,7 @@ impl<'a> Iterator for CellDataIter<'a> {
 
         if let Some(k_data) = self.k_data {
             if let Some(v_data) = self.v_data {
-                let r = Some((*k_data, v_data));
+                let r = Some((k_data, v_data));
                 self.load_next_data();
                 r
             } else {
@@ -205,15 +254,13 @@ impl<'a> Iterator for CellDataIter<'a> {
 /// Iterator over cells.
 #[derive(Debug)]
 pub(crate) struct CellDataIterMut<'a> {
-    iter: std::collections::btree_map::RangeMut<'a, (u32, u32), CellData>,
-    k_data: Option<&'a (u32, u32)>,
+    iter: cellstore::RangeMut<'a>,
+    k_data: Option<(u32, u32)>,
     v_data: Option<&'a mut CellData>,
 }
 
 impl<'a> CellDataIterMut<'a> {
-    pub(crate) fn new(
-        iter: std::collections::btree_map::RangeMut<'a, (u32, u32), CellData>,
-    ) -> Self {
+    pub(crate) fn new(iter: cellstore::RangeMut<'a>) -> Self {
         Self {
             iter,
             k_data: None,
@@ -223,7 +270,7 @@ impl<'a> CellDataIterMut<'a> {
 
     /// Returns the (row,col) of the next cell.
     pub(crate) fn peek_cell(&mut self) -> Option<(u32, u32)> {
-        self.k_data.copied()
+        self.k_data
     }
 
     fn load_next_data(&mut self) {
@@ -249,7 +296,7 @@ impl<'a> Iterator for CellDataIterMut<'a> {
 
         if let Some(k_data) = self.k_data {
             if let Some(v_data) = self.v_data.take() {
-                let r = Some((*k_data, v_data));
+                let r = Some((k_data, v_data));
                 self.load_next_data();
                 r
             } else {
@@ -268,15 +315,15 @@ impl<'a> Iterator for CellDataIterMut<'a> {
 /// Iterator over cells.
 #[derive(Clone, Debug)]
 pub struct CellIter<'a> {
-    iter: std::collections::btree_map::Iter<'a, (u32, u32), CellData>,
-    k_data: Option<&'a (u32, u32)>,
+    iter: cellstore::Range<'a>,
+    k_data: Option<(u32, u32)>,
     v_data: Option<&'a CellData>,
 }
 
 impl CellIter<'_> {
     /// Returns the (row,col) of the next cell.
     pub fn peek_cell(&mut self) -> Option<(u32, u32)> {
-        self.k_data.copied()
+        self.k_data
     }
 
     fn load_next_data(&mut self) {
@@ -302,7 +349,7 @@ impl<'a> Iterator for CellIter<'a> {
 
         if let Some(k_data) = self.k_data {
             if let Some(v_data) = self.v_data {
-                let r = Some((*k_data, v_data.cell_content_ref()));
+                let r = Some((k_data, v_data.cell_content_ref()));
                 self.load_next_data();
                 r
             } else {
@@ -319,24 +366,30 @@ impl<'a> Iterator for CellIter<'a> {
 }
 
 struct IterRows<'a> {
-    iter: std::collections::btree_map::Range<'a, (u32, u32), CellData>,
+    iter: cellstore::Range<'a>,
     start: (u32, u32),
     end: (u32, u32),
     hint: usize,
 }
 
+/// Start and exclusive end of the rectangle covered by the range.
+fn rect_bounds<R: RangeBounds<(u32, u32)>>(sheet: &Sheet, range: &R) -> ((u32, u32), (u32, u32)) {
+    let start = match range.start_bound() {
+        Bound::Included((r, c)) => (*r, *c),
+        Bound::Excluded((r, c)) => (r + 1, c + 1),
+        Bound::Unbounded => (0, 0),
+    };
+    let end = match range.end_bound() {
+        Bound::Included((r, c)) => (r + 1, c + 1),
+        Bound::Excluded((r, c)) => (*r, *c),
+        Bound::Unbounded => sheet.used_grid_size(),
+    };
+    (start, end)
+}
+
 impl<'a> IterRows<'a> {
     fn new<R: RangeBounds<(u32, u32)>>(sheet: &'a Sheet, range: R) -> Self {
-        let start = match range.start_bound() {
-            Bound::Included((r, c)) => (*r, *c),
-            Bound::Excluded((r, c)) => (r + 1, c + 1),
-            Bound::Unbounded => (0, 0),
-        };
-        let end = match range.end_bound() {
-            Bound::Included((r, c)) => (r + 1, c + 1),
-            Bound::Excluded((r, c)) => (*r, *c),
-            Bound::Unbounded => sheet.used_grid_size(),
-        };
+        let (start, end) = rect_bounds(sheet, &range);
 
         Self {
             iter: sheet.data.range(range),
@@ -355,8 +408,8 @@ impl<'a> Iterator for IterRows<'a> {
     fn next(&mut self) -> Option<Self::Item> {
         loop {
             if let Some(((r, c), d)) = self.iter.next() {
-                if *r < self.end.0 && *c >= self.start.1 && *c < self.end.1 {
-                    return Some(((*r, *c), d.cell_content_ref()));
+                if r < self.end.0 && c >= self.start.1 && c < self.end.1 {
+                    return Some(((r, c), d.cell_content_ref()));
                 }
             } else {
                 return None;
@@ -390,16 +443,7 @@ impl FusedIterator for IterCols<'_> {}
 
 impl<'a> IterCols<'a> {
     fn new<R: RangeBounds<(u32, u32)>>(sheet: &'a Sheet, range: R) -> Self {
-        let start = match range.start_bound() {
-            Bound::Included((r, c)) => (*r, *c),
-            Bound::Excluded((r, c)) => (r + 1, c + 1),
-            Bound::Unbounded => (0, 0),
-        };
-        let end = match range.end_bound() {
-            Bound::Included((r, c)) => (r + 1, c + 1),
-            Bound::Excluded((r, c)) => (*r, *c),
-            Bound::Unbounded => sheet.used_grid_size(),
-        };
+        let (start, end) = rect_bounds(sheet, &range);
 
         Self {
             sheet,
@@ -454,10 +498,77 @@ impl<'a> Iterator for IterCols<'a> {
     }
 }
 
+/// Iterates all cells of a rectangle including the empty ones.
+struct IterRectAll<'a> {
+    sheet: &'a Sheet,
+    start: (u32, u32),
+    end: (u32, u32),
+    cell: (u32, u32),
+}
+
+impl FusedIterator for IterRectAll<'_> {}
+
+impl<'a> Iterator for IterRectAll<'a> {
+    type Item = ((u32, u32), CellContentRef<'a>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.cell.0 >= self.end.0 || self.start.1 >= self.end.1 {
+            return None;
+        }
+
+        let cell = self.cell;
+        let d = self
+            .sheet
+            .cell_ref(cell.0, cell.1)
+            .unwrap_or_else(CellContentRef::empty);
+
+        if self.cell.1 + 1 < self.end.1 {
+            self.cell.1 += 1;
+        } else {
+            self.cell.0 += 1;
+            self.cell.1 = self.start.1;
+        }
+
+        Some((cell, d))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = if self.cell.0 < self.end.0 && self.start.1 < self.end.1 {
+            let width = (self.end.1 - self.start.1) as usize;
+            (self.end.0 - self.cell.0) as usize * width - (self.cell.1 - self.start.1) as usize
+        } else {
+            0
+        };
+        (len, Some(len))
+    }
+}
+
+/// Iterates the used cells grouped by row.
+struct IterUsedRows<'a> {
+    iter: Peekable<cellstore::Range<'a>>,
+}
+
+impl FusedIterator for IterUsedRows<'_> {}
+
+impl<'a> Iterator for IterUsedRows<'a> {
+    type Item = (u32, Vec<(u32, CellContentRef<'a>)>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let ((row, col), d) = self.iter.next()?;
+
+        let mut cells = vec![(col, d.cell_content_ref())];
+        while let Some(((_, c), d)) = self.iter.next_if(|((r, _), _)| *r == row) {
+            cells.push((c, d.cell_content_ref()));
+        }
+
+        Some((row, cells))
+    }
+}
+
 /// Range iterator.
 #[derive(Clone, Debug)]
 pub struct Range<'a> {
-    range: std::collections::btree_map::Range<'a, (u32, u32), CellData>,
+    range: cellstore::Range<'a>,
 }
 
 impl FusedIterator for Range<'_> {}
@@ -467,7 +578,7 @@ impl<'a> Iterator for Range<'a> {
 
     fn next(&mut self) -> Option<Self::Item> {
         if let Some((k, v)) = self.range.next() {
-            Some((*k, v.cell_content_ref()))
+            Some((k, v.cell_content_ref()))
         } else {
             None
         }
@@ -481,7 +592,7 @@ impl<'a> Iterator for Range<'a> {
 impl DoubleEndedIterator for Range<'_> {
     fn next_back(&mut self) -> Option<Self::Item> {
         if let Some((k, v)) = self.range.next_back() {
-            Some((*k, v.cell_content_ref()))
+            Some((k, v.cell_content_ref()))
         } else {
             None
         }
@@ -514,6 +625,12 @@ impl Debug for Sheet {
         for v in &self.group_rows {
             writeln!(f, "group rows {:?}", v)?;
         }
+        for v in &self.conditional_formats {
+            writeln!(f, "conditional format {:?}", v)?;
+        }
+        for v in self.event_listener.values() {
+            writeln!(f, "event listener {:?}", v)?;
+        }
         for xtr in &self.extra {
             writeln!(f, "extras {:?}", xtr)?;
         }
@@ -540,20 +657,69 @@ impl Sheet {
     pub fn new<S: Into<String>>(name: S) -> Self {
         Sheet {
             name: name.into(),
-            data: BTreeMap::new(),
+            data: CellStore::default(),
             col_header: Default::default(),
             style: None,
             header_rows: None,
             header_cols: None,
             print_ranges: None,
+            print_page_order: None,
+            print_scale: None,
             group_rows: Default::default(),
             group_cols: Default::default(),
             sheet_config: Default::default(),
+            conditional_formats: Default::default(),
             extra: vec![],
             row_header: Default::default(),
             display: true,
             print: true,
+            tab_color: None,
+            rtl: false,
+            masterpage: None,
+            protected: false,
+            protection_key: None,
+            protection_key_digest: None,
+            event_listener: Default::default(),
+        }
+    }
+
+    /// Named ranges that are only valid for this sheet.
+    /// See [WorkBook::range_names](crate::WorkBook::range_names).
+    pub fn range_names(&self) -> Vec<(String, CellRange)> {
+        range_names(&self.extra)
+    }
+
+    /// Copies a cell range into a new sheet. The range is moved to A1.
+    ///
+    /// Cell data, column widths/styles and row heights/styles are copied.
+    /// Formulas are copied as they are, references are not adjusted.
+    pub fn extract_range(&self, range: &CellRange) -> Self {
+        let mut sheet = Sheet::new(self.name.as_str());
+        sheet.style = self.style.clone();
+
+        let (r0, c0) = (range.row(), range.col());
+        for ((r, c), d) in self.data.range((r0, c0)..=(range.to_row(), range.to_col())) {
+            if c >= c0 && c <= range.to_col() {
+                sheet.data.insert((r - r0, c - c0), d.clone());
+            }
+        }
+        for c in c0..=range.to_col() {
+            if let Some(ch) = self.valid_col_header(c) {
+                let mut ch = ch.clone();
+                ch.span = 1;
+                sheet.col_header.insert(c - c0, ch);
+            }
+        }
+        for r in r0..=range.to_row() {
+            if let Some(rh) = self.valid_row_header(r) {
+                let mut rh = rh.clone();
+                rh.span = 1;
+                rh.repeat = 1;
+                sheet.row_header.insert(r - r0, rh);
+            }
         }
+
+        sheet
     }
 
     /// Copy all the attributes but not the actual data.
@@ -561,21 +727,52 @@ impl Sheet {
         Self {
             name: self.name.clone(),
             style: self.style.clone(),
-            data: Default::default(),
+            data: CellStore::new(self.data.storage()),
             col_header: self.col_header.clone(),
             row_header: self.row_header.clone(),
             display: self.display,
             print: self.print,
+            tab_color: self.tab_color,
+            rtl: self.rtl,
+            masterpage: self.masterpage.clone(),
+            protected: self.protected,
+            protection_key: self.protection_key.clone(),
+            protection_key_digest: self.protection_key_digest.clone(),
+            event_listener: self.event_listener.clone(),
             header_rows: self.header_rows,
             header_cols: self.header_cols,
             print_ranges: self.print_ranges.clone(),
+            print_page_order: self.print_page_order,
+            print_scale: self.print_scale,
             group_rows: self.group_rows.clone(),
             group_cols: self.group_cols.clone(),
             sheet_config: Default::default(),
+            conditional_formats: self.conditional_formats.clone(),
             extra: self.extra.clone(),
         }
     }
 
+    /// Changes the storage backend for the cell data.
+    /// Existing cells are moved to the new storage.
+    ///
+    /// ```
+    /// use spreadsheet_ods::sheet::CellStorage;
+    /// use spreadsheet_ods::Sheet;
+    ///
+    /// let mut sheet = Sheet::new("1");
+    /// sheet.set_cell_storage(CellStorage::Dense);
+    /// sheet.set_value(0, 0, 1);
+    /// assert_eq!(sheet.cell_storage(), CellStorage::Dense);
+    /// ```
+    pub fn set_cell_storage(&mut self, storage: CellStorage) {
+        self.data.convert(storage);
+    }
+
+    /// Storage backend for the cell data.
+    pub fn cell_storage(&self) -> CellStorage {
+        self.data.storage()
+    }
+
     /// Iterate all cells.
     pub fn iter(&self) -> CellIter<'_> {
         self.into_iter()
@@ -608,6 +805,40 @@ impl Sheet {
         IterCols::new(self, range)
     }
 
+    /// Iterate the cells of a rectangular range row by row.
+    /// Only cells that contain some data are returned.
+    pub fn range_iter(
+        &self,
+        range: &CellRange,
+    ) -> impl Iterator<Item = ((u32, u32), CellContentRef<'_>)> {
+        IterRows::new(
+            self,
+            (range.row(), range.col())..=(range.to_row(), range.to_col()),
+        )
+    }
+
+    /// Iterate all cells of a rectangular range row by row.
+    /// Cells without data are returned as empty cells.
+    pub fn range_iter_all(
+        &self,
+        range: &CellRange,
+    ) -> impl Iterator<Item = ((u32, u32), CellContentRef<'_>)> {
+        IterRectAll {
+            sheet: self,
+            start: (range.row(), range.col()),
+            end: (range.to_row() + 1, range.to_col() + 1),
+            cell: (range.row(), range.col()),
+        }
+    }
+
+    /// Iterate all the rows with any data. Each row is returned with
+    /// its occupied cells as (column, content).
+    pub fn iter_used_rows(&self) -> impl Iterator<Item = (u32, Vec<(u32, CellContentRef<'_>)>)> {
+        IterUsedRows {
+            iter: self.data.iter().peekable(),
+        }
+    }
+
     /// Iterate a range of cells in lexical order.
     pub fn range<R: RangeBounds<(u32, u32)>>(
         &self,
@@ -723,6 +954,38 @@ impl Sheet {
         self.col_header.get_mut(&col).expect("col-header")
     }
 
+    // split the col-headers at the borders of from..=to and fill any gaps,
+    // so the col-headers in the range cover exactly from..=to.
+    pub(crate) fn create_split_col_header_range(
+        &mut self,
+        from: u32,
+        to: u32,
+    ) -> impl Iterator<Item = &mut ColHeader> {
+        assert!(from <= to);
+        self.create_split_col_header(from);
+        self.create_split_col_header(to);
+
+        let mut gaps = Vec::new();
+        let mut next = from;
+        for (base_col, col_header) in self.col_header.range(from..=to) {
+            if *base_col > next {
+                gaps.push((next, *base_col - next));
+            }
+            next = *base_col + col_header.span;
+        }
+        for (col, span) in gaps {
+            self.col_header.insert(
+                col,
+                ColHeader {
+                    span,
+                    ..Default::default()
+                },
+            );
+        }
+
+        self.col_header.range_mut(from..=to).map(|(_, v)| v)
+    }
+
     /// Column style.
     pub fn set_colstyle(&mut self, col: u32, style: &ColStyleRef) {
         self.create_split_col_header(col).style = Some(style.clone());
@@ -752,6 +1015,20 @@ impl Sheet {
         self.create_split_col_header(col).cellstyle = None;
     }
 
+    /// Default cell style for the columns from..=to.
+    pub fn set_col_cellstyle_range(&mut self, from: u32, to: u32, style: &CellStyleRef) {
+        for col_header in self.create_split_col_header_range(from, to) {
+            col_header.cellstyle = Some(style.clone());
+        }
+    }
+
+    /// Remove the default cell style for the columns from..=to.
+    pub fn clear_col_cellstyle_range(&mut self, from: u32, to: u32) {
+        for col_header in self.create_split_col_header_range(from, to) {
+            col_header.cellstyle = None;
+        }
+    }
+
     /// Returns the default cell style for this column.
     pub fn col_cellstyle(&self, col: u32) -> Option<&CellStyleRef> {
         if let Some(col_header) = self.valid_col_header(col) {
@@ -766,6 +1043,23 @@ impl Sheet {
         self.create_split_col_header(col).visible = visible;
     }
 
+    /// Visibility of the columns from..=to.
+    pub fn set_cols_visible(&mut self, from: u32, to: u32, visible: Visibility) {
+        for col_header in self.create_split_col_header_range(from, to) {
+            col_header.visible = visible;
+        }
+    }
+
+    /// Hides the columns from..=to.
+    pub fn hide_cols(&mut self, from: u32, to: u32) {
+        self.set_cols_visible(from, to, Visibility::Collapsed);
+    }
+
+    /// Shows the columns from..=to.
+    pub fn show_cols(&mut self, from: u32, to: u32) {
+        self.set_cols_visible(from, to, Visibility::Visible);
+    }
+
     /// Returns the default cell style for this column.
     pub fn col_visible(&self, col: u32) -> Visibility {
         if let Some(col_header) = self.valid_col_header(col) {
@@ -803,6 +1097,23 @@ impl Sheet {
         }
     }
 
+    /// Sets a manual page break before this column.
+    ///
+    /// When writing a column style with fo:break-before="page" is
+    /// created for this column.
+    pub fn set_col_page_break(&mut self, col: u32, page_break: bool) {
+        self.create_split_col_header(col).page_break = page_break;
+    }
+
+    /// Is there a manual page break before this column.
+    pub fn col_page_break(&self, col: u32) -> bool {
+        if let Some(ch) = self.valid_col_header(col) {
+            ch.page_break
+        } else {
+            false
+        }
+    }
+
     // find the row-header with the correct data.
     pub(crate) fn valid_row_header(&self, row: u32) -> Option<&RowHeader> {
         if let Some((base_row, row_header)) = self.row_header.range(..=row).last() {
@@ -846,11 +1157,11 @@ impl Sheet {
                 }
 
                 // back
-                if *base_row + base_span > row {
+                if *base_row + base_span > row + 1 {
                     let mut clone = row_header.clone();
                     clone.span = *base_row + base_span - (row + 1);
                     cloned.push((row + 1, clone));
-                } else if *base_row + base_span == row {
+                } else if *base_row + base_span == row + 1 {
                     // noop
                 } else {
                     unreachable!();
@@ -869,6 +1180,38 @@ impl Sheet {
         self.row_header.get_mut(&row).expect("row-header")
     }
 
+    // split the row-headers at the borders of from..=to and fill any gaps,
+    // so the row-headers in the range cover exactly from..=to.
+    pub(crate) fn create_split_row_header_range(
+        &mut self,
+        from: u32,
+        to: u32,
+    ) -> impl Iterator<Item = &mut RowHeader> {
+        assert!(from <= to);
+        self.create_split_row_header(from);
+        self.create_split_row_header(to);
+
+        let mut gaps = Vec::new();
+        let mut next = from;
+        for (base_row, row_header) in self.row_header.range(from..=to) {
+            if *base_row > next {
+                gaps.push((next, *base_row - next));
+            }
+            next = *base_row + row_header.span;
+        }
+        for (row, span) in gaps {
+            self.row_header.insert(
+                row,
+                RowHeader {
+                    span,
+                    ..Default::default()
+                },
+            );
+        }
+
+        self.row_header.range_mut(from..=to).map(|(_, v)| v)
+    }
+
     /// unstable internal method.
     pub fn _row_header_span(&self, row: u32) -> Option<u32> {
         self.row_header.get(&row).map(|v| v.span)
@@ -910,6 +1253,22 @@ impl Sheet {
         self.create_split_row_header(row).cellstyle = None;
     }
 
+    /// Default cell style for the rows from..=to.
+    ///
+    /// Only rows up to the last row with data are written.
+    pub fn set_row_cellstyle_range(&mut self, from: u32, to: u32, style: &CellStyleRef) {
+        for row_header in self.create_split_row_header_range(from, to) {
+            row_header.cellstyle = Some(style.clone());
+        }
+    }
+
+    /// Remove the default cell style for the rows from..=to.
+    pub fn clear_row_cellstyle_range(&mut self, from: u32, to: u32) {
+        for row_header in self.create_split_row_header_range(from, to) {
+            row_header.cellstyle = None;
+        }
+    }
+
     /// Returns the default cell style for this row.
     pub fn row_cellstyle(&self, row: u32) -> Option<&CellStyleRef> {
         if let Some(row_header) = self.valid_row_header(row) {
@@ -924,6 +1283,23 @@ impl Sheet {
         self.create_split_row_header(row).visible = visible;
     }
 
+    /// Visibility of the rows from..=to.
+    pub fn set_rows_visible(&mut self, from: u32, to: u32, visible: Visibility) {
+        for row_header in self.create_split_row_header_range(from, to) {
+            row_header.visible = visible;
+        }
+    }
+
+    /// Hides the rows from..=to.
+    pub fn hide_rows(&mut self, from: u32, to: u32) {
+        self.set_rows_visible(from, to, Visibility::Collapsed);
+    }
+
+    /// Shows the rows from..=to.
+    pub fn show_rows(&mut self, from: u32, to: u32) {
+        self.set_rows_visible(from, to, Visibility::Visible);
+    }
+
     /// Returns the default cell style for this row.
     pub fn row_visible(&self, row: u32) -> Visibility {
         if let Some(row_header) = self.valid_row_header(row) {
@@ -969,6 +1345,23 @@ impl Sheet {
         }
     }
 
+    /// Sets a manual page break before this row.
+    ///
+    /// When writing a row style with fo:break-before="page" is
+    /// created for this row.
+    pub fn set_row_page_break(&mut self, row: u32, page_break: bool) {
+        self.create_split_row_header(row).page_break = page_break;
+    }
+
+    /// Is there a manual page break before this row.
+    pub fn row_page_break(&self, row: u32) -> bool {
+        if let Some(rh) = self.valid_row_header(row) {
+            rh.page_break
+        } else {
+            false
+        }
+    }
+
     /// Returns the maximum used column in the column header.
     pub fn _col_header_len(&self) -> usize {
         self.col_header.len()
@@ -992,14 +1385,27 @@ impl Sheet {
     /// Returns a tuple of (max(row)+1, max(col)+1)
     pub fn used_grid_size(&self) -> (u32, u32) {
         let max = self.data.keys().fold((0, 0), |mut max, (r, c)| {
-            max.0 = u32::max(max.0, *r);
-            max.1 = u32::max(max.1, *c);
+            max.0 = u32::max(max.0, r);
+            max.1 = u32::max(max.1, c);
             max
         });
 
         (max.0 + 1, max.1 + 1)
     }
 
+    /// Returns the smallest range that contains all used cells.
+    /// None if the sheet is empty.
+    pub fn used_range(&self) -> Option<CellRange> {
+        let ((first_row, _), _) = self.data.first_key_value()?;
+        let ((last_row, _), _) = self.data.last_key_value()?;
+        let (min_col, max_col) = self
+            .data
+            .keys()
+            .fold((u32::MAX, 0), |(min, max), (_, c)| (min.min(c), max.max(c)));
+
+        Some(CellRange::local(first_row, min_col, last_row, max_col))
+    }
+
     /// Is the sheet displayed?
     pub fn set_display(&mut self, display: bool) {
         self.display = display;
@@ -1020,6 +1426,122 @@ impl Sheet {
         self.print
     }
 
+    /// Color of the sheet tab.
+    ///
+    /// When writing this is transferred to the TableStyle of the sheet.
+    /// If the sheet has no TableStyle yet, one is created.
+    pub fn set_tab_color(&mut self, color: Rgb<u8>) {
+        self.tab_color = Some(color);
+    }
+
+    /// Resets the sheet tab to the default color.
+    pub fn clear_tab_color(&mut self) {
+        self.tab_color = None;
+    }
+
+    /// Color of the sheet tab.
+    pub fn tab_color(&self) -> Option<Rgb<u8>> {
+        self.tab_color
+    }
+
+    /// Right-to-left layout of the sheet. Column A is at the right side.
+    ///
+    /// When writing this is transferred to the TableStyle of the sheet
+    /// as style:writing-mode. If the sheet has no TableStyle yet, one is
+    /// created.
+    pub fn set_rtl(&mut self, rtl: bool) {
+        self.rtl = rtl;
+    }
+
+    /// Right-to-left layout of the sheet.
+    pub fn rtl(&self) -> bool {
+        self.rtl
+    }
+
+    /// Master page used for printing this sheet. This allows different
+    /// headers and footers for each sheet.
+    ///
+    /// When writing this is transferred to the TableStyle of the sheet
+    /// as style:master-page-name. If the sheet has no TableStyle yet, one
+    /// is created.
+    pub fn set_masterpage(&mut self, masterpage: &MasterPageRef) {
+        self.masterpage = Some(masterpage.clone());
+    }
+
+    /// Removes the master page of the sheet. The master page of the
+    /// TableStyle is used as it is.
+    pub fn clear_masterpage(&mut self) {
+        self.masterpage = None;
+    }
+
+    /// Master page used for printing this sheet.
+    pub fn masterpage(&self) -> Option<&MasterPageRef> {
+        self.masterpage.as_ref()
+    }
+
+    /// The sheet is protected against changes.
+    ///
+    /// Without a protection key the protection can be removed without
+    /// a password.
+    ///
+    /// Which cells are protected is defined by the cell styles, see
+    /// [CellStyle::set_protected](crate::CellStyle::set_protected) and
+    /// [WorkBook::set_range_protected](crate::WorkBook::set_range_protected).
+    pub fn set_protected(&mut self, protected: bool) {
+        self.protected = protected;
+    }
+
+    /// The sheet is protected against changes.
+    pub fn protected(&self) -> bool {
+        self.protected
+    }
+
+    /// Hashed password for the sheet protection and the digest algorithm
+    /// used, e.g. "http://www.w3.org/2000/09/xmldsig#sha256".
+    ///
+    /// The key is the base64 encoded hash of the password.
+    pub fn set_protection_key<S: Into<String>, T: Into<String>>(&mut self, key: S, digest: T) {
+        self.protection_key = Some(key.into());
+        self.protection_key_digest = Some(digest.into());
+    }
+
+    /// Removes the protection key.
+    pub fn clear_protection_key(&mut self) {
+        self.protection_key = None;
+        self.protection_key_digest = None;
+    }
+
+    /// Hashed password for the sheet protection.
+    pub fn protection_key(&self) -> Option<&str> {
+        self.protection_key.as_deref()
+    }
+
+    /// Digest algorithm for the protection key.
+    pub fn protection_key_digest(&self) -> Option<&str> {
+        self.protection_key_digest.as_deref()
+    }
+
+    /// Binds an event of the sheet to a macro. Replaces an existing
+    /// listener for the same event.
+    pub fn add_event_listener(&mut self, e: EventListener) {
+        self.event_listener.insert(e.event_name.clone(), e);
+    }
+
+    /// Removes the listener for the event.
+    pub fn remove_event_listener(&mut self, event_name: &str) -> Option<EventListener> {
+        self.event_listener.remove(event_name)
+    }
+
+    /// Event listeners of the sheet.
+    pub fn iter_event_listeners(&self) -> impl Iterator<Item = &EventListener> {
+        self.event_listener.values()
+    }
+
+    /// Returns the listener for the event.
+    pub fn event_listener(&self, event_name: &str) -> Option<&EventListener> {
+        self.event_listener.get(event_name)
+    }
+
     /// Returns true if there is no SCell at the given position.
     pub fn is_empty(&self, row: u32, col: u32) -> bool {
         !self.data.contains_key(&(row, col))
@@ -1049,61 +1571,611 @@ impl Sheet {
             .map(CellData::into_cell_content)
     }
 
-    /// Add a new cell. Main use is for reading the spreadsheet.
-    pub(crate) fn add_cell_data(&mut self, row: u32, col: u32, cell: CellData) {
-        self.data.insert((row, col), cell);
-    }
-
-    /// Sets a value for the specified cell and provides a style at the same time.
-    #[inline]
-    pub fn set_styled<V: Into<Value>>(
-        &mut self,
-        row: u32,
-        col: u32,
-        value: V,
-        style: &CellStyleRef,
-    ) {
-        self.set_styled_value(row, col, value, style)
+    /// Clears the cells within the range. The range is used as a rectangle,
+    /// the table-name is ignored.
+    ///
+    /// Cells that are left without value, formula, style and any extra data
+    /// are removed.
+    pub fn clear_range(&mut self, range: &CellRange, op: ClearOp) {
+        let mut remove = Vec::new();
+        for ((row, col), cell) in self
+            .data
+            .range_mut((range.row(), range.col())..=(range.to_row(), range.to_col()))
+        {
+            if col < range.col() || col > range.to_col() {
+                continue;
+            }
+            match op {
+                ClearOp::Values => {
+                    if cell.formula.is_none() {
+                        cell.value = Value::Empty;
+                    }
+                }
+                ClearOp::Formulas => {
+                    if cell.formula.is_some() {
+                        cell.formula = None;
+                        cell.value = Value::Empty;
+                        if let Some(extra) = cell.extra.as_mut() {
+                            extra.formula_type = None;
+                        }
+                    }
+                }
+                ClearOp::Styles => {
+                    cell.style = None;
+                }
+                ClearOp::All => {}
+            }
+            if op == ClearOp::All || (cell.style.is_none() && cell.is_empty()) {
+                remove.push((row, col));
+            }
+        }
+        for k in remove {
+            self.data.remove(&k);
+        }
     }
 
-    /// Sets a value for the specified cell. Creates a new cell if necessary.
-    pub fn set_styled_value<V: Into<Value>>(
-        &mut self,
-        row: u32,
-        col: u32,
-        value: V,
-        style: &CellStyleRef,
-    ) {
-        let cell = self.data.entry((row, col)).or_default();
-        cell.value = value.into();
-        cell.style = Some(style.clone());
+    /// Fills the range with a series of values, starting with start and
+    /// incremented by step for each cell. The series runs down each column
+    /// of the range, or across if the range has only one row.
+    ///
+    /// Numbers, percentages, currencies and decimals are incremented by step,
+    /// dates, times and durations by step days. Other values are repeated.
+    /// A date, time or duration that would be out of range keeps the start
+    /// value. The cell styles are kept, formulas are removed.
+    pub fn fill_series<V: IntoValue>(&mut self, range: &CellRange, start: V, step: f64) {
+        let start = start.into_value();
+        for (row, col) in range.iter() {
+            let n = if range.row() == range.to_row() {
+                col - range.col()
+            } else {
+                row - range.row()
+            };
+            let cell = self.data.entry((row, col)).or_default();
+            cell.value = series_value(&start, n as f64 * step);
+            cell.formula = None;
+            if let Some(extra) = cell.extra.as_mut() {
+                extra.formula_type = None;
+            }
+        }
     }
 
-    /// Sets a value for the specified cell. Creates a new cell if necessary.
-    pub fn set_value<V: Into<Value>>(&mut self, row: u32, col: u32, value: V) {
-        let cell = self.data.entry((row, col)).or_default();
-        cell.value = value.into();
+    /// Copies the first row of the range to the other rows, like a drag-fill
+    /// downwards. Values, formulas, styles and validations are copied, the
+    /// relative references in formulas are moved to the new row. Empty cells
+    /// in the first row clear the cells below.
+    pub fn fill_down(&mut self, range: &CellRange) {
+        for col in range.col()..=range.to_col() {
+            let src = self.data.get(&(range.row(), col)).map(fill_copy);
+            for row in range.row() + 1..=range.to_row() {
+                match &src {
+                    Some(src) => {
+                        let mut cell = src.clone();
+                        if let Some(formula) = cell.formula.as_mut() {
+                            *formula = shift_refs(formula, (row - range.row()) as i64, 0);
+                        }
+                        self.data.insert((row, col), cell);
+                    }
+                    None => {
+                        self.data.remove(&(row, col));
+                    }
+                }
+            }
+        }
     }
 
-    /// Returns a value
-    pub fn value(&self, row: u32, col: u32) -> &Value {
-        if let Some(cell) = self.data.get(&(row, col)) {
-            &cell.value
-        } else {
-            &Value::Empty
+    /// Sorts the rows of the range by the given keys. The cells are moved
+    /// as a whole with values, formulas, styles and annotations. Cells
+    /// outside the range are not changed.
+    ///
+    /// Numbers and dates sort before text, empty cells are always last.
+    /// The sort is stable, rows with equal keys keep their order.
+    ///
+    /// Formulas are moved unchanged, see
+    /// [sort_range_shift_refs](Sheet::sort_range_shift_refs) for
+    /// the alternative.
+    pub fn sort_range(&mut self, range: &CellRange, keys: &[SortKey]) {
+        self.sort_range_impl(range, keys, false);
+    }
+
+    /// Sorts the rows of the range like [sort_range](Sheet::sort_range),
+    /// but the relative references in formulas are moved with the row.
+    /// A formula that refers to cells in its own row keeps doing so.
+    pub fn sort_range_shift_refs(&mut self, range: &CellRange, keys: &[SortKey]) {
+        self.sort_range_impl(range, keys, true);
+    }
+
+    fn sort_range_impl(&mut self, range: &CellRange, keys: &[SortKey], shift: bool) {
+        let range = range.clone().normalized();
+
+        let mut rows: Vec<(u32, Vec<(u32, CellData)>)> = (range.row()..=range.to_row())
+            .map(|row| (row, Vec::new()))
+            .collect();
+        let cells: Vec<(u32, u32)> = self
+            .data
+            .range((range.row(), range.col())..=(range.to_row(), range.to_col()))
+            .map(|(k, _)| k)
+            .filter(|(_, col)| *col >= range.col() && *col <= range.to_col())
+            .collect();
+        for (row, col) in cells {
+            if let Some(cell) = self.data.remove(&(row, col)) {
+                rows[(row - range.row()) as usize].1.push((col, cell));
+            }
         }
-    }
 
-    /// Sets a formula for the specified cell. Creates a new cell if necessary.
-    pub fn set_formula<V: Into<String>>(&mut self, row: u32, col: u32, formula: V) {
-        let cell = self.data.entry((row, col)).or_default();
-        cell.formula = Some(formula.into());
+        let from_rows = {
+            let mut sort_rows = rows
+                .iter()
+                .map(|(row, cells)| {
+                    let sort_values = keys
+                        .iter()
+                        .map(|key| {
+                            cells
+                                .iter()
+                                .find(|(col, _)| *col == key.col)
+                                .map(|(_, cell)| SortValue::new(&cell.value, key.case_sensitive))
+                                .unwrap_or(SortValue::Empty)
+                        })
+                        .collect::<Vec<_>>();
+                    (*row, sort_values)
+                })
+                .collect::<Vec<_>>();
+            sort_rows.sort_by(|(_, a), (_, b)| {
+                keys.iter()
+                    .enumerate()
+                    .map(|(idx, key)| a[idx].cmp(&b[idx], key.ascending))
+                    .find(|v| *v != Ordering::Equal)
+                    .unwrap_or(Ordering::Equal)
+            });
+            sort_rows
+                .into_iter()
+                .map(|(row, _)| row)
+                .collect::<Vec<_>>()
+        };
+
+        for (to_row, from_row) in (range.row()..).zip(from_rows) {
+            let cells = mem::take(&mut rows[(from_row - range.row()) as usize].1);
+            for (col, mut cell) in cells {
+                if shift && to_row != from_row {
+                    if let Some(formula) = cell.formula.as_mut() {
+                        *formula = shift_refs(formula, to_row as i64 - from_row as i64, 0);
+                    }
+                }
+                self.data.insert((to_row, col), cell);
+            }
+        }
     }
 
-    /// Removes the formula.
-    pub fn clear_formula(&mut self, row: u32, col: u32) {
+    /// Inserts count empty rows before the given row. Everything below
+    /// moves down: cells, row heights and styles, row groups, header rows
+    /// and print ranges. Merged cells and row groups that span the
+    /// position grow.
+    ///
+    /// References in the formulas and the ranges of the conditional
+    /// formats of this sheet are adjusted. References from other sheets
+    /// and validations are not, use [WorkBook::insert_rows] for this.
+    ///
+    /// [WorkBook::insert_rows]: crate::WorkBook::insert_rows
+    pub fn insert_rows(&mut self, row: u32, count: u32) {
+        if count == 0 {
+            return;
+        }
+        let moved = |v: u32| insert_row_index(v, row, count);
+
+        let keys: Vec<(u32, u32)> = self.data.range((row, 0)..).map(|(k, _)| k).collect();
+        for (r, c) in keys.into_iter().rev() {
+            if let Some(cell) = self.data.remove(&(r, c)) {
+                if let Some(r) = moved(r) {
+                    self.data.insert((r, c), cell);
+                }
+            }
+        }
+        for ((r, _), cell) in self.data.range_mut(..(row, 0)) {
+            if let Some(extra) = cell.extra.as_mut() {
+                if r + extra.span.row_span > row {
+                    extra.span.row_span += count;
+                }
+            }
+        }
+        let name = self.name.clone();
+        for cell in self.data.values_mut() {
+            if let Some(formula) = cell.formula.as_mut() {
+                *formula = insert_rows_refs(formula, &name, true, row, count);
+            }
+        }
+
+        for (r, mut header) in self.row_header.split_off(&row) {
+            if let Some(r) = moved(r) {
+                header.span = header.span.min(MAX_ROWS - r);
+                self.row_header.insert(r, header);
+            }
+        }
+        if let Some((r, header)) = self.row_header.range_mut(..row).last() {
+            if r + header.span > row {
+                header.span += count;
+                if header.repeat > 1 && r + header.repeat > row {
+                    header.repeat += count;
+                }
+            }
+        }
+
+        for grp in &mut self.group_rows {
+            grp.from = moved(grp.from).unwrap_or(MAX_ROWS - 1);
+            grp.to = moved(grp.to).unwrap_or(MAX_ROWS - 1);
+        }
+        if let Some(header) = self.header_rows.as_mut() {
+            header.from = moved(header.from).unwrap_or(MAX_ROWS - 1);
+            header.to = moved(header.to).unwrap_or(MAX_ROWS - 1);
+        }
+        let move_range = |range: &mut CellRange| {
+            let into_sheet = match range.table() {
+                Some(t) => *t == name,
+                None => true,
+            };
+            if into_sheet {
+                range.set_row(moved(range.row()).unwrap_or(MAX_ROWS - 1));
+                range.set_to_row(moved(range.to_row()).unwrap_or(MAX_ROWS - 1));
+            }
+        };
+        if let Some(print_ranges) = self.print_ranges.as_mut() {
+            print_ranges.iter_mut().for_each(move_range);
+        }
+        for format in self.conditional_formats.iter_mut() {
+            let mut range = format.range().clone();
+            move_range(&mut range);
+            format.set_range(range);
+        }
+    }
+
+    /// Inserts subtotal rows for the groups in the range and a grand total
+    /// row at the end. The range contains only the data rows, and rows
+    /// with equal values in group_col must be next to each other, e.g.
+    /// by using [sort_range](Sheet::sort_range) first.
+    ///
+    /// Each subtotal row gets a label in group_col and a SUBTOTAL() formula
+    /// for each of the total_cols. The label is the value of the group
+    /// followed by label, e.g. "Sum", the grand total row is labeled with
+    /// grand_total. The detail rows of each group are
+    /// grouped for an outline, and the whole result gets an outer group.
+    ///
+    /// Returns the range with the data, subtotals and grand total.
+    /// Fails if an existing row group or the header rows overlap the range.
+    pub fn subtotals(
+        &mut self,
+        range: &CellRange,
+        group_col: u32,
+        total_cols: &[u32],
+        function: SubtotalFn,
+        label: &str,
+        grand_total: &str,
+    ) -> Result<CellRange, OdsError> {
+        let range = range.clone().normalized();
+        if range.to_row() + 1 >= MAX_ROWS {
+            return Err(OdsError::Ods(format!(
+                "Subtotals for {} need rows beyond the end of the sheet.",
+                range
+            )));
+        }
+        for grp in &self.group_rows {
+            let contains = grp.from <= range.row() && grp.to >= range.to_row();
+            let disjunct = grp.to < range.row() || grp.from > range.to_row();
+            if !contains && !disjunct {
+                return Err(OdsError::Ods(format!(
+                    "Row group {}..{} overlaps the subtotal range {}.",
+                    grp.from, grp.to, range
+                )));
+            }
+        }
+        if let Some(header) = self.header_rows {
+            if header.to >= range.row() && header.from <= range.to_row() {
+                return Err(OdsError::Ods(format!(
+                    "Header rows {}..{} overlap the subtotal range {}.",
+                    header.from, header.to, range
+                )));
+            }
+        }
+
+        let mut groups: Vec<(u32, u32, Value)> = Vec::new();
+        for row in range.row()..=range.to_row() {
+            let value = self.value(row, group_col);
+            match groups.last_mut() {
+                Some((_, to, last)) if last == value => *to = row,
+                _ => groups.push((row, row, value.clone())),
+            }
+        }
+
+        let mut outline = Vec::new();
+        let mut offset = 0;
+        for (from, to, value) in groups {
+            let (from, to) = (from + offset, to + offset);
+            self.insert_rows(to + 1, 1);
+            self.set_subtotal_row(
+                to + 1,
+                (from, to),
+                group_col,
+                total_cols,
+                function,
+                format!("{} {}", value_text(&value), label),
+            );
+            outline.push((from, to));
+            offset += 1;
+        }
+        let last = range.to_row() + offset;
+        self.insert_rows(last + 1, 1);
+        self.set_subtotal_row(
+            last + 1,
+            (range.row(), last),
+            group_col,
+            total_cols,
+            function,
+            grand_total.to_string(),
+        );
+
+        self.add_row_group(range.row(), last);
+        for (from, to) in outline {
+            self.add_row_group(from, to);
+        }
+
+        let mut result = range;
+        result.set_to_row(last + 1);
+        Ok(result)
+    }
+
+    fn set_subtotal_row(
+        &mut self,
+        row: u32,
+        (from, to): (u32, u32),
+        group_col: u32,
+        total_cols: &[u32],
+        function: SubtotalFn,
+        label: String,
+    ) {
+        self.set_value(row, group_col, label);
+        for col in total_cols {
+            self.set_formula(
+                row,
+                *col,
+                format!(
+                    "of:=SUBTOTAL({};{})",
+                    function as u8,
+                    CellRange::local(from, *col, to, *col).to_formula()
+                ),
+            );
+        }
+    }
+
+    /// Add a new cell. Main use is for reading the spreadsheet.
+    pub(crate) fn add_cell_data(&mut self, row: u32, col: u32, cell: CellData) {
+        self.data.insert((row, col), cell);
+    }
+
+    /// Sets a value for the specified cell and provides a style at the same time.
+    #[inline]
+    pub fn set_styled<V: IntoValue>(&mut self, row: u32, col: u32, value: V, style: &CellStyleRef) {
+        self.set_styled_value(row, col, value, style)
+    }
+
+    /// Sets a value for the specified cell. Creates a new cell if necessary.
+    pub fn set_styled_value<V: IntoValue>(
+        &mut self,
+        row: u32,
+        col: u32,
+        value: V,
+        style: &CellStyleRef,
+    ) {
+        let cell = self.data.entry((row, col)).or_default();
+        cell.value = value.into_value();
+        cell.style = Some(style.clone());
+    }
+
+    /// Sets a value for the specified cell. Creates a new cell if necessary.
+    ///
+    /// If the value has a preferred cell-style (see [IntoValue]) and the
+    /// cell has no style yet, this style is set too.
+    pub fn set_value<V: IntoValue>(&mut self, row: u32, col: u32, value: V) {
+        let cell = self.data.entry((row, col)).or_default();
+        if cell.style.is_none() {
+            cell.style = value.cell_style();
+        }
+        cell.value = value.into_value();
+    }
+
+    /// Sets a value like [set_value](Sheet::set_value), but rejects
+    /// numbers, percentages and currencies that are NaN or infinite.
+    /// The cell is not changed in this case.
+    ///
+    /// Without this check non-finite numbers are handled when writing,
+    /// see [NonFiniteMode](crate::NonFiniteMode).
+    pub fn try_set_value<V: IntoValue>(
+        &mut self,
+        row: u32,
+        col: u32,
+        value: V,
+    ) -> Result<(), OdsError> {
+        let style = value.cell_style();
+        let value = value.into_value();
+        if let Value::Number(v) | Value::Percentage(v) | Value::Currency(v, _) = &value {
+            if !v.is_finite() {
+                return Err(OdsError::Ods(format!(
+                    "{}: non-finite number {} for row/col {}/{}.",
+                    self.name, v, row, col
+                )));
+            }
+        }
+        let cell = self.data.entry((row, col)).or_default();
+        if cell.style.is_none() {
+            cell.style = style;
+        }
+        cell.value = value;
+        Ok(())
+    }
+
+    /// Sets a value that is repeated over the next columns.
+    ///
+    /// The value is stored only once and written with
+    /// table:number-columns-repeated. The following cells in the row
+    /// must be empty, otherwise writing fails.
+    ///
+    /// When reading the file again the cells are expanded, unless
+    /// OdsOptions::use_repeat_for_cells() is used.
+    pub fn set_value_repeated<V: Into<Value>>(
+        &mut self,
+        row: u32,
+        col: u32,
+        value: V,
+        repeat: u32,
+    ) {
+        let cell = self.data.entry((row, col)).or_default();
+        cell.value = value.into();
+        cell.repeat = repeat;
+    }
+
+    /// Sets a rectangular block of values, starting at row/col.
+    ///
+    /// Accepts anything that looks like a 2D array, e.g. `&[&[T]]`,
+    /// `&[Vec<T>]` or `&[[T; N]]`. Existing styles etc. are kept.
+    ///
+    /// ```
+    /// use spreadsheet_ods::Sheet;
+    ///
+    /// let mut sheet = Sheet::new("1");
+    /// sheet.set_block(1, 1, &[[1, 2, 3], [4, 5, 6]]);
+    /// assert_eq!(sheet.value(2, 3).as_i32_or(0), 6);
+    /// ```
+    pub fn set_block<T, R>(&mut self, row: u32, col: u32, block: &[R])
+    where
+        T: Clone + Into<Value>,
+        R: AsRef<[T]>,
+    {
+        for (r, values) in block.iter().enumerate() {
+            for (c, value) in values.as_ref().iter().enumerate() {
+                let cell = self
+                    .data
+                    .entry((row + r as u32, col + c as u32))
+                    .or_default();
+                cell.value = value.clone().into();
+            }
+        }
+    }
+
+    /// Returns a value
+    pub fn value(&self, row: u32, col: u32) -> &Value {
+        if let Some(cell) = self.data.get(&(row, col)) {
+            &cell.value
+        } else {
+            &Value::Empty
+        }
+    }
+
+    /// Returns the paragraphs of a text cell. See [Value::paragraphs].
+    pub fn paragraphs(&self, row: u32, col: u32) -> Vec<String> {
+        self.value(row, col).paragraphs()
+    }
+
+    /// Sets a text value with one paragraph per entry.
+    /// See [Value::from_paragraphs].
+    pub fn set_paragraphs<I, S>(&mut self, row: u32, col: u32, paragraphs: I)
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let cell = self.data.entry((row, col)).or_default();
+        cell.value = Value::from_paragraphs(paragraphs);
+    }
+
+    /// Returns the value converted to the requested type.
+    /// Fails if the value has another type, an empty cell fails too.
+    ///
+    /// ```
+    /// use spreadsheet_ods::Sheet;
+    ///
+    /// let mut sheet = Sheet::new("1");
+    /// sheet.set_value(0, 0, 42);
+    /// let v: f64 = sheet.value_as(0, 0).unwrap();
+    /// assert_eq!(v, 42.0);
+    /// assert!(sheet.value_as::<String>(0, 0).is_err());
+    /// ```
+    pub fn value_as<'a, T>(&'a self, row: u32, col: u32) -> Result<T, OdsError>
+    where
+        T: TryFrom<&'a Value, Error = OdsError>,
+    {
+        T::try_from(self.value(row, col))
+    }
+
+    /// Sets a formula for the specified cell. Creates a new cell if necessary.
+    pub fn set_formula<V: Into<String>>(&mut self, row: u32, col: u32, formula: V) {
+        let cell = self.data.entry((row, col)).or_default();
+        cell.formula = Some(formula.into());
+    }
+
+    /// Sets a formula together with its last computed result.
+    ///
+    /// The result is written as the cell value, so applications that
+    /// don't recalculate on load show it right away.
+    ///
+    /// ```
+    /// use spreadsheet_ods::{Sheet, Value};
+    ///
+    /// let mut sheet = Sheet::new("1");
+    /// sheet.set_formula_result(0, 0, "of:=1+1", 2);
+    /// let (formula, value) = sheet.formula_result(0, 0).unwrap();
+    /// assert_eq!(formula, "of:=1+1");
+    /// assert_eq!(value, &Value::Number(2.0));
+    /// ```
+    pub fn set_formula_result<F: Into<String>, V: Into<Value>>(
+        &mut self,
+        row: u32,
+        col: u32,
+        formula: F,
+        value: V,
+    ) {
+        let cell = self.data.entry((row, col)).or_default();
+        cell.formula = Some(formula.into());
+        cell.value = value.into();
+    }
+
+    /// Returns the formula and its cached result, if the cell has a formula.
+    /// The result is Value::Empty if none is known.
+    pub fn formula_result(&self, row: u32, col: u32) -> Option<(&String, &Value)> {
+        if let Some(c) = self.data.get(&(row, col)) {
+            c.formula.as_ref().map(|f| (f, &c.value))
+        } else {
+            None
+        }
+    }
+
+    /// Sets a formula with a declared result type.
+    ///
+    /// If the cell has no explicit style and no cached value, the writer
+    /// attaches the default style for this value-type. This way the result
+    /// is formatted correctly after the first recalculation.
+    pub fn set_formula_typed<V: Into<String>>(
+        &mut self,
+        row: u32,
+        col: u32,
+        formula: V,
+        value_type: ValueType,
+    ) {
+        let cell = self.data.entry((row, col)).or_default();
+        cell.formula = Some(formula.into());
+        cell.extra_mut().formula_type = Some(value_type);
+    }
+
+    /// Returns the declared result type of the formula.
+    pub fn formula_type(&self, row: u32, col: u32) -> Option<ValueType> {
+        if let Some(CellData { extra: Some(c), .. }) = self.data.get(&(row, col)) {
+            c.formula_type
+        } else {
+            None
+        }
+    }
+
+    /// Removes the formula.
+    pub fn clear_formula(&mut self, row: u32, col: u32) {
         if let Some(cell) = self.data.get_mut(&(row, col)) {
             cell.formula = None;
+            if let Some(extra) = cell.extra.as_mut() {
+                extra.formula_type = None;
+            }
         }
     }
 
@@ -1137,6 +2209,24 @@ impl Sheet {
         cell.style = Some(style.clone());
     }
 
+    /// Sets the cell-style for all cells in the range whose value matches
+    /// the predicate. The range is used as a rectangle like in iter_rows().
+    ///
+    /// Only existing cells are checked. This is an alternative to conditional
+    /// styles when the data is static.
+    pub fn style_where<R, F>(&mut self, range: R, mut predicate: F, style: &CellStyleRef)
+    where
+        R: RangeBounds<(u32, u32)>,
+        F: FnMut(&Value) -> bool,
+    {
+        let (start, end) = rect_bounds(self, &range);
+        for ((r, c), cell) in self.data.range_mut(range) {
+            if r < end.0 && c >= start.1 && c < end.1 && predicate(&cell.value) {
+                cell.style = Some(style.clone());
+            }
+        }
+    }
+
     /// Removes the cell-style.
     pub fn clear_cellstyle(&mut self, row: u32, col: u32) {
         if let Some(cell) = self.data.get_mut(&(row, col)) {
@@ -1153,6 +2243,19 @@ impl Sheet {
         }
     }
 
+    /// Returns the cell style that applies to this cell. This is
+    /// the cell style itself, the default cell style of the row or
+    /// the default cell style of the column, in this order.
+    ///
+    /// The workbook default styles per value type are not considered here,
+    /// use [WorkBook::effective_cellstyle](crate::WorkBook::effective_cellstyle)
+    /// for those.
+    pub fn effective_cellstyle(&self, row: u32, col: u32) -> Option<&CellStyleRef> {
+        self.cellstyle(row, col)
+            .or_else(|| self.row_cellstyle(row))
+            .or_else(|| self.col_cellstyle(col))
+    }
+
     /// Sets a content-validation for this cell.
     pub fn set_validation(&mut self, row: u32, col: u32, validation: &ValidationRef) {
         let cell = self.data.entry((row, col)).or_default();
@@ -1206,6 +2309,137 @@ impl Sheet {
         }
     }
 
+    /// Merges the cells of the range. The top-left cell becomes the anchor
+    /// of the merged region, its row and column span are set accordingly.
+    ///
+    /// The content of all other cells in the range is cleared, only their
+    /// styles are kept. Merged regions that start within the range are
+    /// dissolved.
+    pub fn merge(&mut self, range: &CellRange) {
+        self.unmerge(range);
+
+        let (row, col) = (range.row(), range.col());
+        let covered: Vec<_> = self
+            .data
+            .range((row, col)..=(range.to_row(), range.to_col()))
+            .map(|(k, _)| k)
+            .filter(|(r, c)| range.contains(*r, *c) && (*r, *c) != (row, col))
+            .collect();
+        for k in covered {
+            if let Some(cell) = self.data.get_mut(&k) {
+                if cell.style.is_some() {
+                    cell.value = Value::Empty;
+                    cell.formula = None;
+                    cell.extra = None;
+                } else {
+                    self.data.remove(&k);
+                }
+            }
+        }
+
+        let cell = self.data.entry((row, col)).or_default();
+        let span = &mut cell.extra_mut().span;
+        span.set_row_span(range.to_row() - row + 1);
+        span.set_col_span(range.to_col() - col + 1);
+    }
+
+    /// Resets the span of all cells in the range that are the anchor of
+    /// a merged region. The cleared content of the covered cells is not
+    /// restored.
+    pub fn unmerge(&mut self, range: &CellRange) {
+        for ((r, c), cell) in self
+            .data
+            .range_mut((range.row(), range.col())..=(range.to_row(), range.to_col()))
+        {
+            if range.contains(r, c) {
+                if let Some(extra) = cell.extra.as_mut() {
+                    extra.span = Default::default();
+                }
+            }
+        }
+    }
+
+    /// Returns the merged region that contains the cell, if any.
+    /// This works for the anchor cell as well as for the covered cells.
+    pub fn merged_region(&self, row: u32, col: u32) -> Option<CellRange> {
+        // Only cells above and to the left can be the anchor. Rows
+        // without data are skipped.
+        let mut r = row;
+        loop {
+            let found = self
+                .data
+                .range((r, 0)..=(r, col))
+                .rev()
+                .find_map(|((r, c), cell)| {
+                    let span = &cell.extra.as_ref()?.span;
+                    if span.is_empty() {
+                        return None;
+                    }
+                    let range = CellRange::origin_span(r, c, span.into());
+                    range.contains(row, col).then_some(range)
+                });
+            if found.is_some() {
+                return found;
+            }
+            match self.data.range(..(r, 0)).next_back() {
+                Some(((prev, _), _)) => r = prev,
+                None => return None,
+            }
+        }
+    }
+
+    /// Returns a value. For a cell that is covered by a merged region,
+    /// the value of the anchor cell is returned.
+    pub fn merged_value(&self, row: u32, col: u32) -> &Value {
+        match self.merged_region(row, col) {
+            Some(range) => self.value(range.row(), range.col()),
+            None => self.value(row, col),
+        }
+    }
+
+    /// Truncates merged regions that would corrupt the layout of the
+    /// sheet. These are regions that extend beyond the maximum size of
+    /// a sheet and regions that overlap an earlier one.
+    ///
+    /// A region whose anchor cell is covered by an earlier region is
+    /// dissolved, otherwise the columns are reduced until it doesn't
+    /// overlap anymore. Earlier means earlier in row-major order.
+    ///
+    /// Returns the number of changed regions.
+    pub fn fix_spans(&mut self) -> usize {
+        let mut count = 0;
+        let mut merged: Vec<CellRange> = Vec::new();
+        for ((row, col), cell) in self.data.range_mut(..) {
+            let Some(extra) = cell.extra.as_mut() else {
+                continue;
+            };
+            if extra.span.is_empty() {
+                continue;
+            }
+
+            let row_span = extra.span.row_span.min(MAX_ROWS.saturating_sub(row).max(1));
+            let mut col_span = extra.span.col_span.min(MAX_COLS.saturating_sub(col).max(1));
+            if merged.iter().any(|v| v.contains(row, col)) {
+                extra.span = Default::default();
+                count += 1;
+                continue;
+            }
+            let range = CellRange::origin_span(row, col, (row_span, col_span));
+            for v in merged.iter().filter(|v| v.intersects(&range)) {
+                col_span = col_span.min(v.col() - col);
+            }
+            if (row_span, col_span) != (extra.span.row_span, extra.span.col_span) {
+                extra.span.set_row_span(row_span);
+                extra.span.set_col_span(col_span);
+                count += 1;
+            }
+            if !extra.span.is_empty() {
+                merged.push(CellRange::origin_span(row, col, (row_span, col_span)));
+            }
+        }
+        count
+    }
+
     /// Sets the rowspan of the cell. Must be greater than 0.
     pub fn set_matrix_row_span(&mut self, row: u32, col: u32, span: u32) {
         let cell = self.data.entry((row, col)).or_default();
@@ -1273,6 +2507,72 @@ impl Sheet {
         cell.extra_mut().draw_frames.push(draw_frame);
     }
 
+    /// Size of a cell range, calculated from the column widths and row heights.
+    ///
+    /// Columns and rows without an explicit size use the LibreOffice
+    /// defaults of 2.258cm and 0.452cm.
+    pub fn range_size(&self, range: &CellRange) -> (Length, Length) {
+        let width = (range.col()..=range.to_col())
+            .map(|c| self.col_width(c).to_cm().unwrap_or(DEFAULT_COL_WIDTH_CM))
+            .sum::<f64>();
+        let height = (range.row()..=range.to_row())
+            .map(|r| self.row_height(r).to_cm().unwrap_or(DEFAULT_ROW_HEIGHT_CM))
+            .sum::<f64>();
+        (Length::Cm(width), Length::Cm(height))
+    }
+
+    /// Adds a drawframe that covers exactly the given cell range.
+    ///
+    /// The frame is anchored at the top-left cell and its size is calculated
+    /// via range_size(). The end-cell-address is set too, so the frame
+    /// resizes with the cells.
+    pub fn add_draw_frame_cover(&mut self, range: &CellRange, mut draw_frame: DrawFrame) {
+        let (width, height) = self.range_size(range);
+        draw_frame.svg_x(Length::Cm(0.0));
+        draw_frame.svg_y(Length::Cm(0.0));
+        draw_frame.set_width(width);
+        draw_frame.set_height(height);
+        draw_frame.set_table_end_cell_address(CellRef::remote(
+            self.name.as_str(),
+            range.to_row() + 1,
+            range.to_col() + 1,
+        ));
+        draw_frame.set_table_end_x(Length::Cm(0.0));
+        draw_frame.set_table_end_y(Length::Cm(0.0));
+        self.add_draw_frame(range.row(), range.col(), draw_frame);
+    }
+
+    /// Adds a drawframe that fits into the given cell range and keeps the
+    /// aspect ratio (width/height). The frame is anchored at the top-left
+    /// cell.
+    ///
+    /// The aspect ratio can be taken from [ImageSize::aspect_ratio](crate::draw::ImageSize::aspect_ratio).
+    /// If it is not a positive number, or the range has no width or height,
+    /// the frame gets the size of the range.
+    pub fn add_draw_frame_fit(
+        &mut self,
+        range: &CellRange,
+        aspect_ratio: f64,
+        mut draw_frame: DrawFrame,
+    ) {
+        let (width, height) = self.range_size(range);
+        let width = width.to_cm().unwrap_or_default();
+        let height = height.to_cm().unwrap_or_default();
+        let (width, height) =
+            if !(aspect_ratio.is_finite() && aspect_ratio > 0.0) || width <= 0.0 || height <= 0.0 {
+                (width, height)
+            } else if width / height > aspect_ratio {
+                (height * aspect_ratio, height)
+            } else {
+                (width, width / aspect_ratio)
+            };
+        draw_frame.svg_x(Length::Cm(0.0));
+        draw_frame.svg_y(Length::Cm(0.0));
+        draw_frame.set_width(Length::Cm(width));
+        draw_frame.set_height(Length::Cm(height));
+        self.add_draw_frame(range.row(), range.col(), draw_frame);
+    }
+
     /// Removes all drawframes.
     pub fn clear_draw_frames(&mut self, row: u32, col: u32) {
         if let Some(cell) = self.data.get_mut(&(row, col)) {
@@ -1300,6 +2600,20 @@ impl Sheet {
 
     /// Defines a range of rows as header rows.
     /// These rows are repeated when printing on multiple pages.
+    ///
+    /// Fails if from is greater than to, if the rows exceed the size of
+    /// a sheet or if a row group starts or ends within the header rows.
+    /// The header rows can be part of a row group, but they can't
+    /// contain one.
+    pub fn set_repeat_header_rows(&mut self, from: u32, to: u32) -> Result<(), OdsError> {
+        check_header("rows", from, to, MAX_ROWS, &self.group_rows)?;
+        self.header_rows = Some(Header { from, to });
+        Ok(())
+    }
+
+    /// Defines a range of rows as header rows.
+    /// These rows are repeated when printing on multiple pages.
+    #[deprecated = "use set_repeat_header_rows()"]
     pub fn set_header_rows(&mut self, row_start: u32, row_end: u32) {
         self.header_rows = Some(Header {
             from: row_start,
@@ -1320,6 +2634,20 @@ impl Sheet {
 
     /// Defines a range of columns as header columns.
     /// These columns are repeated when printing on multiple pages.
+    ///
+    /// Fails if from is greater than to, if the columns exceed the size
+    /// of a sheet or if a column group starts or ends within the header
+    /// columns. The header columns can be part of a column group, but
+    /// they can't contain one.
+    pub fn set_repeat_header_cols(&mut self, from: u32, to: u32) -> Result<(), OdsError> {
+        check_header("columns", from, to, MAX_COLS, &self.group_cols)?;
+        self.header_cols = Some(Header { from, to });
+        Ok(())
+    }
+
+    /// Defines a range of columns as header columns.
+    /// These columns are repeated when printing on multiple pages.
+    #[deprecated = "use set_repeat_header_cols()"]
     pub fn set_header_cols(&mut self, col_start: u32, col_end: u32) {
         self.header_cols = Some(Header {
             from: col_start,
@@ -1338,9 +2666,16 @@ impl Sheet {
         self.header_cols
     }
 
-    /// Print ranges.
-    pub fn add_print_range(&mut self, range: CellRange) {
+    /// Adds a print range. Only the print ranges are printed, instead of
+    /// the whole sheet.
+    ///
+    /// The range is normalized. It fails if the range belongs to another
+    /// sheet or exceeds the maximum size of a sheet. Print ranges outside
+    /// the used area are reported by [WorkBook::validate](crate::WorkBook::validate).
+    pub fn add_print_range(&mut self, range: CellRange) -> Result<(), OdsError> {
+        let range = check_print_range(&self.name, range)?;
         self.print_ranges.get_or_insert_with(Vec::new).push(range);
+        Ok(())
     }
 
     /// Remove print ranges.
@@ -1353,6 +2688,170 @@ impl Sheet {
         self.print_ranges.as_ref()
     }
 
+    /// Adds a data bar or color scale.
+    pub fn add_conditional_format(&mut self, format: ConditionalFormat) {
+        self.conditional_formats.push(format);
+    }
+
+    /// Removes all data bars and color scales.
+    pub fn clear_conditional_formats(&mut self) {
+        self.conditional_formats.clear();
+    }
+
+    /// Data bars and color scales.
+    pub fn conditional_formats(&self) -> &[ConditionalFormat] {
+        &self.conditional_formats
+    }
+
+    /// Data bars and color scales.
+    pub fn conditional_formats_mut(&mut self) -> &mut Vec<ConditionalFormat> {
+        &mut self.conditional_formats
+    }
+
+    /// Returns all the print settings of this sheet.
+    ///
+    /// Page order and scaling are read from the PageStyle of the sheet.
+    pub fn print_settings(&self) -> PrintSettings {
+        PrintSettings {
+            print_ranges: self.print_ranges.clone().unwrap_or_default(),
+            header_rows: self.header_rows,
+            header_cols: self.header_cols,
+            page_order: self.print_page_order,
+            scale: self.print_scale,
+        }
+    }
+
+    /// Sets all the print settings of this sheet.
+    ///
+    /// Print ranges and header rows/columns are stored with the sheet.
+    /// Page order and scaling are properties of the page layout. When
+    /// writing they are transferred to the PageStyle used by this sheet.
+    /// If the sheet has no TableStyle/MasterPage/PageStyle yet, they
+    /// are created. If the PageStyle is used by other sheets too, this
+    /// sheet gets its own copy.
+    ///
+    /// Fails with the same checks as [add_print_range](Self::add_print_range),
+    /// [set_repeat_header_rows](Self::set_repeat_header_rows) and
+    /// [set_repeat_header_cols](Self::set_repeat_header_cols). Nothing
+    /// is changed in that case.
+    pub fn set_print_settings(&mut self, print: PrintSettings) -> Result<(), OdsError> {
+        if let Some(header) = print.header_rows {
+            check_header("rows", header.from, header.to, MAX_ROWS, &self.group_rows)?;
+        }
+        if let Some(header) = print.header_cols {
+            check_header(
+                "columns",
+                header.from,
+                header.to,
+                MAX_COLS,
+                &self.group_cols,
+            )?;
+        }
+        let print_ranges = print
+            .print_ranges
+            .into_iter()
+            .map(|range| check_print_range(&self.name, range))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        self.print_ranges = if print_ranges.is_empty() {
+            None
+        } else {
+            Some(print_ranges)
+        };
+        self.header_rows = print.header_rows;
+        self.header_cols = print.header_cols;
+        self.print_page_order = print.page_order;
+        self.print_scale = print.scale;
+        Ok(())
+    }
+
+    /// Position of the cell cursor when the document is opened.
+    ///
+    /// Fails if the position is outside of the maximum sheet size.
+    pub fn set_cursor(&mut self, row: u32, col: u32) -> Result<(), OdsError> {
+        check_position(row, col)?;
+        let cfg = self.config_mut();
+        cfg.cursor_x = col;
+        cfg.cursor_y = row;
+        Ok(())
+    }
+
+    /// Position of the cell cursor as (row, col).
+    pub fn cursor(&self) -> (u32, u32) {
+        (self.config().cursor_y, self.config().cursor_x)
+    }
+
+    /// First visible row and column when the document is opened.
+    ///
+    /// With a split or frozen columns/rows this is the position in the
+    /// bottom-right part of the window.
+    ///
+    /// Fails if the position is outside of the maximum sheet size.
+    pub fn set_scroll_position(&mut self, row: u32, col: u32) -> Result<(), OdsError> {
+        check_position(row, col)?;
+        let cfg = self.config_mut();
+        if cfg.hor_split_mode == SplitMode::None {
+            cfg.position_left = col;
+        } else {
+            cfg.position_right = col;
+        }
+        cfg.position_bottom = row;
+        Ok(())
+    }
+
+    /// First visible row and column as (row, col).
+    pub fn scroll_position(&self) -> (u32, u32) {
+        let cfg = self.config();
+        if cfg.hor_split_mode == SplitMode::None {
+            (cfg.position_bottom, cfg.position_left)
+        } else {
+            (cfg.position_bottom, cfg.position_right)
+        }
+    }
+
+    /// Zoom in percent for the normal view. This also sets the
+    /// zoom-type to [ZoomType::Percent].
+    ///
+    /// Fails if the zoom is outside of 20%..=600%.
+    pub fn set_zoom(&mut self, percent: u16) -> Result<(), OdsError> {
+        check_zoom(percent)?;
+        let cfg = self.config_mut();
+        cfg.zoom_type = ZoomType::Percent as i16;
+        cfg.zoom_value = percent as i32;
+        Ok(())
+    }
+
+    /// Zoom in percent for the normal view.
+    pub fn zoom(&self) -> u16 {
+        self.config().zoom_value as u16
+    }
+
+    /// How the zoom is determined.
+    pub fn set_zoom_type(&mut self, zoom_type: ZoomType) {
+        self.config_mut().zoom_type = zoom_type as i16;
+    }
+
+    /// How the zoom is determined. Unknown values of
+    /// [SheetConfig::zoom_type] are returned as [ZoomType::Percent].
+    pub fn zoom_type(&self) -> ZoomType {
+        ZoomType::try_from(self.config().zoom_type).unwrap_or_default()
+    }
+
+    /// Zoom in percent for the page-break view. See
+    /// [WorkBook::set_page_break_view](crate::WorkBook::set_page_break_view).
+    ///
+    /// Fails if the zoom is outside of 20%..=600%.
+    pub fn set_page_view_zoom(&mut self, percent: u16) -> Result<(), OdsError> {
+        check_zoom(percent)?;
+        self.config_mut().page_view_zoom_value = percent as i32;
+        Ok(())
+    }
+
+    /// Zoom in percent for the page-break view.
+    pub fn page_view_zoom(&self) -> u16 {
+        self.config().page_view_zoom_value as u16
+    }
+
     /// Split horizontally on a cell boundary. The splitting is fixed in
     /// position.
     pub fn split_col_header(&mut self, col: u32) {
@@ -1371,6 +2870,74 @@ impl Sheet {
         self.config_mut().cursor_y = row + 1;
     }
 
+    /// Freezes the rows above row and the columns left of col. This is
+    /// the same as Window/Freeze Cells in LibreOffice with the cursor at
+    /// row/col.
+    ///
+    /// A row or col of 0 means no frozen rows/columns. freeze_at(0, 0)
+    /// removes any split.
+    pub fn freeze_at(&mut self, row: u32, col: u32) {
+        let cfg = self.config_mut();
+
+        if col > 0 {
+            cfg.hor_split_mode = SplitMode::Heading;
+            cfg.hor_split_pos = col;
+        } else {
+            cfg.hor_split_mode = SplitMode::None;
+            cfg.hor_split_pos = 0;
+        }
+        cfg.position_left = 0;
+        cfg.position_right = col;
+
+        if row > 0 {
+            cfg.vert_split_mode = SplitMode::Heading;
+            cfg.vert_split_pos = row;
+        } else {
+            cfg.vert_split_mode = SplitMode::None;
+            cfg.vert_split_pos = 0;
+        }
+        cfg.position_top = 0;
+        cfg.position_bottom = row;
+
+        // focus is on the bottom-left/bottom-right quadrant.
+        cfg.active_split_range = if col > 0 { 3 } else { 2 };
+
+        cfg.cursor_x = col;
+        cfg.cursor_y = row;
+    }
+
+    /// Splits the window at the pixel position x/y. This is the same as
+    /// Window/Split Window in LibreOffice. Other than with
+    /// [freeze_at](Sheet::freeze_at) the split can be moved around and
+    /// all parts of the window can be scrolled.
+    ///
+    /// An x or y of 0 means no split in this direction.
+    /// split_at_pixels(0, 0) removes any split.
+    pub fn split_at_pixels(&mut self, x: u32, y: u32) {
+        let cfg = self.config_mut();
+
+        if x > 0 {
+            cfg.hor_split_mode = SplitMode::Split;
+            cfg.hor_split_pos = x;
+        } else {
+            cfg.hor_split_mode = SplitMode::None;
+            cfg.hor_split_pos = 0;
+            cfg.position_right = 0;
+        }
+
+        if y > 0 {
+            cfg.vert_split_mode = SplitMode::Split;
+            cfg.vert_split_pos = y;
+        } else {
+            cfg.vert_split_mode = SplitMode::None;
+            cfg.vert_split_pos = 0;
+            cfg.position_top = 0;
+        }
+
+        // focus is on the bottom-left/bottom-right quadrant.
+        cfg.active_split_range = if x > 0 { 3 } else { 2 };
+    }
+
     /// Split horizontally with a pixel width. The split can be moved around.
     /// For more control look at SheetConfig.
     pub fn split_horizontal(&mut self, col: u32) {
@@ -1390,6 +2957,7 @@ impl Sheet {
     /// Panic
     ///
     /// Column groups can be contained within another, but they can't overlap.
+    /// The header columns can be part of a group, but they can't contain one.
     /// From must be less than or equal to.
     pub fn add_col_group(&mut self, from: u32, to: u32) {
         assert!(from <= to);
@@ -1397,7 +2965,26 @@ impl Sheet {
         for v in &self.group_cols {
             assert!(grp.contains(v) || v.contains(&grp) || grp.disjunct(v));
         }
+        if let Some(header) = self.header_cols {
+            let header = Grouped::new(header.from, header.to, true);
+            assert!(grp.contains(&header) || grp.disjunct(&header));
+        }
         self.group_cols.push(grp);
+        sort_groups(&mut self.group_cols);
+    }
+
+    /// Add a column group and set its collapsed state in one go.
+    /// Groups can be nested to create an outline.
+    ///
+    /// Panic
+    ///
+    /// Column groups can be contained within another, but they can't overlap.
+    /// From must be less than or equal to.
+    pub fn group_cols(&mut self, from: u32, to: u32, collapsed: bool) {
+        self.add_col_group(from, to);
+        if collapsed {
+            self.set_col_group_displayed(from, to, false);
+        }
     }
 
     /// Remove a column group.
@@ -1460,6 +3047,7 @@ impl Sheet {
     /// Panic
     ///
     /// Row groups can be contained within another, but they can't overlap.
+    /// The header rows can be part of a group, but they can't contain one.
     /// From must be less than or equal to.
     pub fn add_row_group(&mut self, from: u32, to: u32) {
         assert!(from <= to);
@@ -1467,7 +3055,26 @@ impl Sheet {
         for v in &self.group_rows {
             assert!(grp.contains(v) || v.contains(&grp) || grp.disjunct(v));
         }
+        if let Some(header) = self.header_rows {
+            let header = Grouped::new(header.from, header.to, true);
+            assert!(grp.contains(&header) || grp.disjunct(&header));
+        }
         self.group_rows.push(grp);
+        sort_groups(&mut self.group_rows);
+    }
+
+    /// Add a row group and set its collapsed state in one go.
+    /// Groups can be nested to create an outline.
+    ///
+    /// Panic
+    ///
+    /// Row groups can be contained within another, but they can't overlap.
+    /// From must be less than or equal to.
+    pub fn group_rows(&mut self, from: u32, to: u32, collapsed: bool) {
+        self.add_row_group(from, to);
+        if collapsed {
+            self.set_row_group_displayed(from, to, false);
+        }
     }
 
     /// Remove a row group.
@@ -1521,10 +3128,56 @@ impl Sheet {
     }
 }
 
+/// Default column width of LibreOffice.
+const DEFAULT_COL_WIDTH_CM: f64 = 2.258;
+/// Default row height of LibreOffice.
+const DEFAULT_ROW_HEIGHT_CM: f64 = 0.452;
+
+/// Scaling for printing.
+#[derive(Debug, Clone, Copy, PartialEq, GetSize)]
+pub enum PrintScale {
+    /// Scale to a percentage. 100 means no scaling.
+    Percent(f64),
+    /// Scale to fit the given number of pages.
+    Pages(u32),
+}
+
+/// Print settings of a sheet.
+///
+/// ```
+/// use spreadsheet_ods::{CellRange, Sheet};
+/// use spreadsheet_ods::sheet::{Header, PrintScale, PrintSettings};
+/// use spreadsheet_ods::style::units::PrintOrder;
+///
+/// let mut sheet = Sheet::new("1");
+/// sheet.set_print_settings(PrintSettings {
+///     print_ranges: vec![CellRange::local(0, 0, 99, 9)],
+///     header_rows: Some(Header { from: 0, to: 0 }),
+///     page_order: Some(PrintOrder::Ttb),
+///     scale: Some(PrintScale::Pages(2)),
+///     ..Default::default()
+/// }).unwrap();
+/// ```
+#[derive(Debug, Clone, Default, GetSize)]
+pub struct PrintSettings {
+    /// Print ranges.
+    pub print_ranges: Vec<CellRange>,
+    /// Rows repeated on each printed page.
+    pub header_rows: Option<Header>,
+    /// Columns repeated on each printed page.
+    pub header_cols: Option<Header>,
+    /// Page order if the data doesn't fit on one page.
+    pub page_order: Option<PrintOrder>,
+    /// Scaling.
+    pub scale: Option<PrintScale>,
+}
+
 /// Describes header rows/columns.
-#[derive(Debug, Copy, Clone, Default, GetSize)]
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, GetSize)]
 pub struct Header {
+    /// First row/column.
     pub from: u32,
+    /// Last row/column. Inclusive.
     pub to: u32,
 }
 
@@ -1596,10 +3249,16 @@ impl Grouped {
     }
 }
 
+/// Sorts the groups in nesting order. Outer groups come before the groups
+/// they contain, which is the order the start-tags must be written.
+pub(crate) fn sort_groups(groups: &mut [Grouped]) {
+    groups.sort_by(|a, b| a.from.cmp(&b.from).then(b.to.cmp(&a.to)));
+}
+
 /// There are two ways a sheet can be split. There are fixed column/row header
 /// like splits, and there is a moveable split.
 ///
-#[derive(Clone, Copy, Debug, GetSize)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, GetSize)]
 #[allow(missing_docs)]
 pub enum SplitMode {
     None = 0,
@@ -1620,6 +3279,295 @@ impl TryFrom<i16> for SplitMode {
     }
 }
 
+/// Value at offset delta of a series.
+fn series_value(start: &Value, delta: f64) -> Value {
+    // Days as duration, None if it's out of range.
+    let duration = || {
+        let ms = delta * 86_400_000.0;
+        if ms.is_finite() && ms.abs() < i64::MAX as f64 {
+            Duration::try_milliseconds(ms as i64)
+        } else {
+            None
+        }
+    };
+    match start {
+        Value::Number(v) => Value::Number(v + delta),
+        #[cfg(feature = "rust_decimal")]
+        Value::Decimal(v) => Value::Decimal(
+            rust_decimal::Decimal::try_from(delta)
+                .ok()
+                .and_then(|d| v.checked_add(d))
+                .unwrap_or(*v),
+        ),
+        Value::Percentage(v) => Value::Percentage(v + delta),
+        Value::Currency(v, c) => Value::Currency(v + delta, c.clone()),
+        Value::DateTime(v) => Value::DateTime(
+            duration()
+                .and_then(|d| v.checked_add_signed(d))
+                .unwrap_or(*v),
+        ),
+        Value::Date(v) => Value::Date(
+            duration()
+                .and_then(|d| v.checked_add_signed(d))
+                .unwrap_or(*v),
+        ),
+        Value::Time(v) => Value::Time(duration().map(|d| *v + d).unwrap_or(*v)),
+        Value::TimeDuration(v) => {
+            Value::TimeDuration(duration().and_then(|d| v.checked_add(&d)).unwrap_or(*v))
+        }
+        v => v.clone(),
+    }
+}
+
+/// Copy of the cell data for fill_down(). Spans, annotations and
+/// drawings are not copied. The value of a formula is not valid at
+/// the new place.
+fn fill_copy(cell: &CellData) -> CellData {
+    let mut copy = CellData {
+        value: cell.value.clone(),
+        formula: cell.formula.clone(),
+        style: cell.style.clone(),
+        repeat: 1,
+        extra: None,
+    };
+    if copy.formula.is_some() {
+        copy.value = Value::Empty;
+    }
+    if let Some(extra) = &cell.extra {
+        if extra.validation_name.is_some() || extra.formula_type.is_some() {
+            let copy_extra = copy.extra_mut();
+            copy_extra.validation_name = extra.validation_name.clone();
+            copy_extra.formula_type = extra.formula_type;
+        }
+    }
+    copy
+}
+
+/// Sort key for Sheet::sort_range().
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SortKey {
+    col: u32,
+    ascending: bool,
+    case_sensitive: bool,
+}
+
+impl SortKey {
+    /// Sort ascending by the given column of the sheet.
+    pub fn asc(col: u32) -> Self {
+        Self {
+            col,
+            ascending: true,
+            case_sensitive: false,
+        }
+    }
+
+    /// Sort descending by the given column of the sheet.
+    pub fn desc(col: u32) -> Self {
+        Self {
+            col,
+            ascending: false,
+            case_sensitive: false,
+        }
+    }
+
+    /// Compare text case-sensitive. Default is false.
+    pub fn case_sensitive(mut self, case_sensitive: bool) -> Self {
+        self.case_sensitive = case_sensitive;
+        self
+    }
+
+    /// Column of the sheet.
+    pub fn col(&self) -> u32 {
+        self.col
+    }
+
+    /// Ascending order.
+    pub fn ascending(&self) -> bool {
+        self.ascending
+    }
+
+    /// Text is compared case-sensitive.
+    pub fn is_case_sensitive(&self) -> bool {
+        self.case_sensitive
+    }
+}
+
+/// Comparable form of a value for sorting.
+enum SortValue<'a> {
+    Number(f64),
+    Text(Cow<'a, str>),
+    Error(&'a str),
+    Empty,
+}
+
+impl<'a> SortValue<'a> {
+    fn new(value: &'a Value, case_sensitive: bool) -> Self {
+        let base = NaiveDate::from_ymd_opt(1899, 12, 30)
+            .expect("valid date")
+            .and_time(NaiveTime::MIN);
+        match value {
+            Value::Empty => SortValue::Empty,
+            Value::Boolean(v) => SortValue::Number(if *v { 1.0 } else { 0.0 }),
+            Value::Text(_) | Value::TextXml(_) => {
+                let text = value.as_cow_str_or("");
+                if case_sensitive {
+                    SortValue::Text(text)
+                } else {
+                    SortValue::Text(Cow::Owned(text.to_lowercase()))
+                }
+            }
+            Value::DateTime(_) | Value::Date(_) => SortValue::Number(
+                (value.as_datetime_or_default() - base).num_milliseconds() as f64 / 86_400_000.0,
+            ),
+            Value::Time(_) | Value::TimeDuration(_) => SortValue::Number(
+                value.as_timeduration_or_default().num_milliseconds() as f64 / 86_400_000.0,
+            ),
+            Value::Error(v) => SortValue::Error(v),
+            _ => SortValue::Number(value.as_f64_or_default()),
+        }
+    }
+
+    fn rank(&self) -> u8 {
+        match self {
+            SortValue::Number(_) => 0,
+            SortValue::Text(_) => 1,
+            SortValue::Error(_) => 2,
+            SortValue::Empty => 3,
+        }
+    }
+
+    /// Empty values are last, regardless of the order.
+    fn cmp(&self, other: &SortValue<'_>, ascending: bool) -> Ordering {
+        let ord = match (self, other) {
+            (SortValue::Empty, SortValue::Empty) => return Ordering::Equal,
+            (SortValue::Empty, _) => return Ordering::Greater,
+            (_, SortValue::Empty) => return Ordering::Less,
+            (SortValue::Number(a), SortValue::Number(b)) => a.total_cmp(b),
+            (SortValue::Text(a), SortValue::Text(b)) => a.cmp(b),
+            (SortValue::Error(a), SortValue::Error(b)) => a.cmp(b),
+            (a, b) => a.rank().cmp(&b.rank()),
+        };
+        if ascending {
+            ord
+        } else {
+            ord.reverse()
+        }
+    }
+}
+
+/// Aggregate function for Sheet::subtotals(). The discriminant is the
+/// function code for SUBTOTAL().
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SubtotalFn {
+    /// Average of the numbers.
+    Average = 1,
+    /// Count of the numbers.
+    Count = 2,
+    /// Count of the non-empty cells.
+    CountA = 3,
+    /// Maximum.
+    Max = 4,
+    /// Minimum.
+    Min = 5,
+    /// Sum of the numbers.
+    Sum = 9,
+}
+
+/// Row index after inserting count rows before row, None if it leaves
+/// the sheet.
+fn insert_row_index(v: u32, row: u32, count: u32) -> Option<u32> {
+    if v >= row {
+        shift_index(v, count as i64, MAX_ROWS)
+    } else {
+        Some(v)
+    }
+}
+
+/// Moves the row references into the sheet name after inserting count
+/// rows before row. Local references are moved too if local is set.
+pub(crate) fn insert_rows_refs(
+    formula: &str,
+    name: &str,
+    local: bool,
+    row: u32,
+    count: u32,
+) -> String {
+    map_refs(formula, |v| {
+        let into_sheet = match v.table {
+            Some(t) => t == name,
+            None => local,
+        };
+        if v.row && !v.external && into_sheet {
+            insert_row_index(v.index, row, count)
+        } else {
+            Some(v.index)
+        }
+    })
+}
+
+/// Plain text of a value without any value format.
+pub(crate) fn value_text(value: &Value) -> String {
+    match value {
+        Value::Empty => String::new(),
+        Value::Boolean(v) => if *v { "TRUE" } else { "FALSE" }.to_string(),
+        #[cfg(feature = "rust_decimal")]
+        Value::Decimal(v) => v.to_string(),
+        Value::Percentage(v) => format!("{}%", v * 100.0),
+        Value::Currency(v, c) => format!("{} {}", v, c),
+        Value::Text(_) | Value::TextXml(_) => value.as_cow_str_or("").into_owned(),
+        Value::DateTime(v) => v.to_string(),
+        Value::Date(v) => v.to_string(),
+        Value::Time(v) => v.to_string(),
+        Value::TimeDuration(v) => v.to_string(),
+        Value::Error(v) => v.clone(),
+        _ => value.as_f64_or_default().to_string(),
+    }
+}
+
+/// What is removed by Sheet::clear_range().
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ClearOp {
+    /// Values of cells without a formula.
+    Values,
+    /// Formulas together with their calculated values.
+    Formulas,
+    /// Cell styles.
+    Styles,
+    /// The complete cell including annotations, spans etc.
+    All,
+}
+
+/// How the zoom factor of a sheet is determined.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, GetSize)]
+pub enum ZoomType {
+    /// The zoom_value is used as percentage.
+    #[default]
+    Percent = 0,
+    /// Fit the used area into the window.
+    Optimal = 1,
+    /// Fit a whole page into the window.
+    WholePage = 2,
+    /// Fit the page width into the window.
+    PageWidth = 3,
+    /// Fit the page width without the page margins into the window.
+    PageWidthNoBorder = 4,
+}
+
+impl TryFrom<i16> for ZoomType {
+    type Error = OdsError;
+
+    fn try_from(n: i16) -> Result<Self, Self::Error> {
+        match n {
+            0 => Ok(ZoomType::Percent),
+            1 => Ok(ZoomType::Optimal),
+            2 => Ok(ZoomType::WholePage),
+            3 => Ok(ZoomType::PageWidth),
+            4 => Ok(ZoomType::PageWidthNoBorder),
+            _ => Err(OdsError::Ods(format!("Invalid zoom type {}", n))),
+        }
+    }
+}
+
 /// Per sheet configurations.
 #[derive(Clone, Debug, GetSize)]
 pub struct SheetConfig {
@@ -1667,12 +3615,11 @@ pub struct SheetConfig {
     /// SplitMode is Cell
     /// - The first visible row in the bottom quadrant.
     pub position_bottom: u32,
-    /// If 0 then zoom_value denotes a percentage.
-    /// If 2 then zoom_value is 50%???
+    /// How the zoom is determined, see [ZoomType].
     pub zoom_type: i16,
-    /// Value of zoom.
+    /// Zoom in percent for the normal view.
     pub zoom_value: i32,
-    /// Value of pageview zoom.
+    /// Zoom in percent for the page-break view.
     pub page_view_zoom_value: i32,
     /// Grid is showing.
     pub show_grid: bool,
@@ -1700,6 +3647,119 @@ impl Default for SheetConfig {
     }
 }
 
+/// Joins adjacent row-headers with the same values. Row-headers with a
+/// repeat are left alone.
+pub(crate) fn dedup_rowheader(sheet: &mut Sheet) -> Result<(), OdsError> {
+    fn limited_eq(rh1: &RowHeader, rh2: &RowHeader) -> bool {
+        rh1.repeat == 1
+            && rh2.repeat == 1
+            && rh1.style == rh2.style
+            && rh1.cellstyle == rh2.cellstyle
+            && rh1.visible == rh2.visible
+            && rh1.height == rh2.height
+            && rh1.page_break == rh2.page_break
+            && rh1.attr == rh2.attr
+    }
+
+    let row_header = mem::take(&mut sheet.row_header);
+
+    let mut new_row_header = BTreeMap::new();
+    let mut new = None;
+    for (row, header) in row_header {
+        match new.as_mut() {
+            None => {
+                new = Some((row, header));
+            }
+            Some((new_row, new_header)) => {
+                if *new_row + new_header.span == row && limited_eq(new_header, &header) {
+                    new_header.span += header.span;
+                } else {
+                    new_row_header
+                        .insert(mem::replace(new_row, row), mem::replace(new_header, header));
+                }
+            }
+        }
+    }
+    if let Some((new_row, new_header)) = new {
+        new_row_header.insert(new_row, new_header);
+    }
+
+    sheet.row_header = new_row_header;
+
+    Ok(())
+}
+
+/// Position must be within the maximum sheet size.
+fn check_position(row: u32, col: u32) -> Result<(), OdsError> {
+    if row >= MAX_ROWS || col >= MAX_COLS {
+        return Err(OdsError::Ods(format!(
+            "position {}/{} is outside of the sheet",
+            row, col
+        )));
+    }
+    Ok(())
+}
+
+fn check_zoom(percent: u16) -> Result<(), OdsError> {
+    if !(20..=600).contains(&percent) {
+        return Err(OdsError::Ods(format!(
+            "zoom {}% is outside of 20%..=600%",
+            percent
+        )));
+    }
+    Ok(())
+}
+
+/// Print ranges must be on the sheet and within the size of a sheet.
+fn check_print_range(name: &str, range: CellRange) -> Result<CellRange, OdsError> {
+    let range = range.normalized();
+    for table in [range.table(), range.to_table()].into_iter().flatten() {
+        if table != name {
+            return Err(OdsError::Ods(format!(
+                "print range {} is not on sheet {}",
+                range, name
+            )));
+        }
+    }
+    if range.to_row() >= MAX_ROWS || range.to_col() >= MAX_COLS {
+        return Err(OdsError::Ods(format!(
+            "print range {} exceeds the size of a sheet",
+            range
+        )));
+    }
+    Ok(range)
+}
+
+/// Header rows/columns must be ordered and nest with the groups.
+fn check_header(
+    what: &str,
+    from: u32,
+    to: u32,
+    max: u32,
+    groups: &[Grouped],
+) -> Result<(), OdsError> {
+    if from > to || to >= max {
+        return Err(OdsError::Ods(format!(
+            "invalid header {} {}..={}",
+            what, from, to
+        )));
+    }
+    let header = Grouped::new(from, to, true);
+    for grp in groups {
+        if !grp.contains(&header) && !grp.disjunct(&header) {
+            return Err(OdsError::Ods(format!(
+                "header {} {}..={} overlap the group {}..={}",
+                what,
+                from,
+                to,
+                grp.from(),
+                grp.to()
+            )));
+        }
+    }
+    Ok(())
+}
+
 /// Cleanup repeat col-data.
 pub(crate) fn dedup_colheader(sheet: &mut Sheet) -> Result<(), OdsError> {
     fn limited_eq(ch1: &ColHeader, ch2: &ColHeader) -> bool {
@@ -1707,6 +3767,7 @@ pub(crate) fn dedup_colheader(sheet: &mut Sheet) -> Result<(), OdsError> {
             && ch1.cellstyle == ch2.cellstyle
             && ch1.visible == ch2.visible
             && ch1.width == ch2.width
+            && ch1.page_break == ch2.page_break
     }
 
     let col_header = mem::take(&mut sheet.col_header);
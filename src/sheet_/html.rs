@@ -0,0 +1,264 @@
+//!
+//! Export of a cell range as html table.
+//!
+
+use crate::sheet_::Visibility;
+use crate::style::CellStyle;
+use crate::{CellRange, Sheet, Value, WorkBook};
+use std::collections::HashSet;
+use std::fmt::Write;
+
+/// Options for Sheet::to_html().
+#[derive(Clone, Debug)]
+pub struct HtmlOptions {
+    styles: bool,
+    hidden: bool,
+    class: Option<String>,
+}
+
+impl Default for HtmlOptions {
+    fn default() -> Self {
+        Self {
+            styles: true,
+            hidden: false,
+            class: None,
+        }
+    }
+}
+
+impl HtmlOptions {
+    /// Write the cell styles, column widths and row heights as inline css.
+    /// Default is true.
+    pub fn styles(mut self, styles: bool) -> Self {
+        self.styles = styles;
+        self
+    }
+
+    /// Include hidden rows and columns. Default is false.
+    pub fn hidden(mut self, hidden: bool) -> Self {
+        self.hidden = hidden;
+        self
+    }
+
+    /// Class attribute for the table.
+    pub fn class<S: Into<String>>(mut self, class: S) -> Self {
+        self.class = Some(class.into());
+        self
+    }
+}
+
+impl Sheet {
+    /// Renders the cell range as html table.
+    ///
+    /// The styles are taken from the workbook. Colors, borders, fonts
+    /// and alignment of the cell styles are written as inline css,
+    /// merged cells get a rowspan/colspan. The values are formatted
+    /// with their value format (see [WorkBook::display_value]).
+    ///
+    /// ```
+    /// use spreadsheet_ods::sheet::HtmlOptions;
+    /// use spreadsheet_ods::{CellRange, Sheet, WorkBook};
+    ///
+    /// let mut sheet = Sheet::new("1");
+    /// sheet.set_value(0, 0, "a<b");
+    /// sheet.set_value(0, 1, 1);
+    ///
+    /// let html = sheet.to_html(
+    ///     &WorkBook::new_empty(),
+    ///     &CellRange::local(0, 0, 0, 1),
+    ///     HtmlOptions::default().styles(false),
+    /// );
+    /// assert_eq!(html, "<table>\n<tr><td>a&lt;b</td><td>1</td></tr>\n</table>\n");
+    /// ```
+    pub fn to_html(&self, book: &WorkBook, range: &CellRange, options: HtmlOptions) -> String {
+        let range = range.clone().normalized();
+        let rows = (range.row()..=range.to_row())
+            .filter(|v| options.hidden || self.row_visible(*v) == Visibility::Visible)
+            .collect::<Vec<_>>();
+        let cols = (range.col()..=range.to_col())
+            .filter(|v| options.hidden || self.col_visible(*v) == Visibility::Visible)
+            .collect::<Vec<_>>();
+
+        let mut buf = String::new();
+        buf.push_str("<table");
+        if let Some(class) = &options.class {
+            let _ = write!(buf, " class=\"{}\"", escape(class));
+        }
+        if options.styles {
+            buf.push_str(" style=\"border-collapse:collapse\"");
+        }
+        buf.push_str(">\n");
+
+        if options.styles {
+            buf.push_str("<colgroup>");
+            for col in &cols {
+                let width = self.col_width(*col);
+                if width != Default::default() {
+                    let _ = write!(buf, "<col style=\"width:{}\">", width);
+                } else {
+                    buf.push_str("<col>");
+                }
+            }
+            buf.push_str("</colgroup>\n");
+        }
+
+        let mut covered = HashSet::new();
+        for row in &rows {
+            buf.push_str("<tr");
+            if options.styles {
+                let height = self.row_height(*row);
+                if height != Default::default() {
+                    let _ = write!(buf, " style=\"height:{}\"", height);
+                }
+            }
+            buf.push('>');
+            for col in &cols {
+                if covered.contains(&(*row, *col)) {
+                    continue;
+                }
+
+                buf.push_str("<td");
+                let (row_span, col_span) = (self.row_span(*row, *col), self.col_span(*row, *col));
+                if row_span > 1 || col_span > 1 {
+                    let to_row = (row + row_span - 1).min(range.to_row());
+                    let to_col = (col + col_span - 1).min(range.to_col());
+                    for r in *row..=to_row {
+                        for c in *col..=to_col {
+                            covered.insert((r, c));
+                        }
+                    }
+                    let row_span = rows.iter().filter(|v| (*row..=to_row).contains(v)).count();
+                    let col_span = cols.iter().filter(|v| (*col..=to_col).contains(v)).count();
+                    if row_span > 1 {
+                        let _ = write!(buf, " rowspan=\"{}\"", row_span);
+                    }
+                    if col_span > 1 {
+                        let _ = write!(buf, " colspan=\"{}\"", col_span);
+                    }
+                }
+
+                let value = self.value(*row, *col);
+                if options.styles {
+                    let style = book
+                        .effective_cellstyle_of(self, *row, *col)
+                        .and_then(|v| book.cellstyle(v));
+                    let css = cell_css(book, style, value);
+                    if !css.is_empty() {
+                        let _ = write!(buf, " style=\"{}\"", escape(&css));
+                    }
+                }
+                buf.push('>');
+                let text = book.display_value_of(self, *row, *col);
+                buf.push_str(&escape(&text).replace('\n', "<br>"));
+                buf.push_str("</td>");
+            }
+            buf.push_str("</tr>\n");
+        }
+        buf.push_str("</table>\n");
+
+        buf
+    }
+}
+
+/// Css for the cell style. Attributes are looked up in the parent
+/// styles too.
+fn cell_css(book: &WorkBook, style: Option<&CellStyle>, value: &Value) -> String {
+    let attr = |name: &str| {
+        let mut style = style;
+        // parent chain, with a limit against cycles.
+        for _ in 0..16 {
+            let s = style?;
+            if let Some(v) = s
+                .cellstyle()
+                .attr(name)
+                .or_else(|| s.paragraphstyle().attr(name))
+                .or_else(|| s.textstyle().attr(name))
+            {
+                return Some(v);
+            }
+            style = s
+                .attrmap()
+                .attr("style:parent-style-name")
+                .and_then(|v| book.cellstyle(v));
+        }
+        None
+    };
+
+    let mut css = String::new();
+    if let Some(v) = attr("fo:background-color").filter(|v| *v != "transparent") {
+        let _ = write!(css, "background-color:{};", v);
+    }
+    if let Some(v) = attr("fo:color") {
+        let _ = write!(css, "color:{};", v);
+    }
+    if let Some(v) = attr("style:font-name") {
+        let _ = write!(css, "font-family:'{}';", v.replace('\'', ""));
+    }
+    for (name, prop) in [
+        ("fo:font-size", "font-size"),
+        ("fo:font-weight", "font-weight"),
+        ("fo:font-style", "font-style"),
+    ] {
+        if let Some(v) = attr(name) {
+            let _ = write!(css, "{}:{};", prop, v);
+        }
+    }
+    let underline = attr("style:text-underline-style").is_some_and(|v| v != "none");
+    let strike = attr("style:text-line-through-style").is_some_and(|v| v != "none");
+    match (underline, strike) {
+        (true, true) => css.push_str("text-decoration:underline line-through;"),
+        (true, false) => css.push_str("text-decoration:underline;"),
+        (false, true) => css.push_str("text-decoration:line-through;"),
+        (false, false) => {}
+    }
+    match attr("fo:text-align") {
+        Some("start") => css.push_str("text-align:left;"),
+        Some("end") => css.push_str("text-align:right;"),
+        Some(v) => {
+            let _ = write!(css, "text-align:{};", v);
+        }
+        None => {
+            // same as the default alignment of a spreadsheet.
+            if !matches!(
+                value,
+                Value::Empty | Value::Text(_) | Value::TextXml(_) | Value::Error(_)
+            ) {
+                css.push_str("text-align:right;");
+            }
+        }
+    }
+    match attr("style:vertical-align") {
+        Some("top") => css.push_str("vertical-align:top;"),
+        Some("middle") => css.push_str("vertical-align:middle;"),
+        Some("bottom") => css.push_str("vertical-align:bottom;"),
+        _ => {}
+    }
+    if let Some(v) = attr("fo:border") {
+        let _ = write!(css, "border:{};", v);
+    }
+    for (name, prop) in [
+        ("fo:border-top", "border-top"),
+        ("fo:border-bottom", "border-bottom"),
+        ("fo:border-left", "border-left"),
+        ("fo:border-right", "border-right"),
+    ] {
+        if let Some(v) = attr(name) {
+            let _ = write!(css, "{}:{};", prop, v);
+        }
+    }
+    css
+}
+
+fn escape(s: &str) -> String {
+    let mut buf = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => buf.push_str("&amp;"),
+            '<' => buf.push_str("&lt;"),
+            '>' => buf.push_str("&gt;"),
+            '"' => buf.push_str("&quot;"),
+            c => buf.push(c),
+        }
+    }
+    buf
+}
@@ -0,0 +1,189 @@
+//!
+//! Type inference for text cells.
+//!
+
+use crate::locale::{localized_format, DateOrder, LocalizedValueFormat};
+use crate::{Sheet, Value};
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
+use icu_locid::{locale, Locale};
+
+impl Sheet {
+    /// Converts text cells that look like booleans, numbers, percentages,
+    /// dates or times into typed values. This is useful after importing
+    /// data as plain text, e.g. from a CSV file.
+    ///
+    /// The decimal and thousands separators, the order of the date parts
+    /// and the names of true and false are taken from the locale.
+    /// ISO dates "2024-01-31" are always recognized. Locales without
+    /// locale data fall back to "en". Cells with a formula and numbers
+    /// with leading zeros like "007" are not changed.
+    ///
+    /// Returns the number of converted cells.
+    ///
+    /// ```
+    /// use icu_locid::locale;
+    /// use spreadsheet_ods::{Sheet, Value};
+    ///
+    /// let mut sheet = Sheet::new("1");
+    /// sheet.set_value(0, 0, "1.234,5");
+    /// sheet.set_value(0, 1, "31.01.2024");
+    /// sheet.set_value(0, 2, "n/a");
+    /// # #[cfg(feature = "locale_de_AT")]
+    /// # {
+    /// assert_eq!(sheet.infer_types(locale!("de_AT")), 2);
+    /// assert_eq!(sheet.value(0, 0), &Value::Number(1234.5));
+    /// # }
+    /// ```
+    pub fn infer_types(&mut self, locale: Locale) -> usize {
+        let Some(format) = localized_format(locale).or_else(|| localized_format(locale!("en")))
+        else {
+            return 0;
+        };
+
+        let mut count = 0;
+        for cell in self.data.values_mut() {
+            if cell.formula.is_some() {
+                continue;
+            }
+            let Value::Text(text) = &cell.value else {
+                continue;
+            };
+            if let Some(value) = infer_value(text, format) {
+                cell.value = value;
+                count += 1;
+            }
+        }
+        count
+    }
+}
+
+fn infer_value(text: &str, format: &dyn LocalizedValueFormat) -> Option<Value> {
+    let text = text.trim();
+    if text.is_empty() {
+        return None;
+    }
+
+    let (t, f) = format.boolean_names();
+    if text.eq_ignore_ascii_case(t) {
+        return Some(Value::Boolean(true));
+    } else if text.eq_ignore_ascii_case(f) {
+        return Some(Value::Boolean(false));
+    }
+
+    let (dec, grp) = (format.decimal_separator(), format.grouping_separator());
+    if let Some(v) = text.strip_suffix('%') {
+        return parse_number(v.trim_end(), dec, grp).map(|v| Value::Percentage(v / 100.0));
+    }
+    if let Some(v) = parse_number(text, dec, grp) {
+        return Some(Value::Number(v));
+    }
+
+    let order = format.date_order();
+    if let Some((date, time)) = text.split_once([' ', 'T']) {
+        if let (Some(date), Some(time)) = (parse_date(date, order), parse_time(time.trim())) {
+            return Some(Value::DateTime(NaiveDateTime::new(date, time)));
+        }
+    }
+    if let Some(date) = parse_date(text, order) {
+        return Some(Value::Date(date));
+    }
+    parse_time(text).map(Value::Time)
+}
+
+/// Number with optional sign, thousands separators, decimal separator
+/// and exponent.
+fn parse_number(text: &str, dec: char, grp: char) -> Option<f64> {
+    let (sign, text) = match text.strip_prefix(['-', '+']) {
+        Some(v) => (&text[..1], v),
+        None => ("", text),
+    };
+    let (mantissa, exp) = match text.split_once(['e', 'E']) {
+        Some((m, e)) => (m, Some(e)),
+        None => (text, None),
+    };
+    let (int, frac) = match mantissa.split_once(dec) {
+        Some((i, f)) => (i, f),
+        None => (mantissa, ""),
+    };
+
+    let is_digits = |v: &str| v.chars().all(|c| c.is_ascii_digit());
+    // a non-breaking space is often written as plain space.
+    let groups = if grp == '\u{a0}' {
+        int.split([grp, ' ']).collect::<Vec<_>>()
+    } else {
+        int.split(grp).collect::<Vec<_>>()
+    };
+    if groups.len() > 1
+        && (groups[0].is_empty() || groups[0].len() > 3 || groups[1..].iter().any(|v| v.len() != 3))
+    {
+        return None;
+    }
+    let int = groups.concat();
+    if !is_digits(&int) || !is_digits(frac) || (int.is_empty() && frac.is_empty()) {
+        return None;
+    }
+    // leading zeros are codes like "007", not numbers.
+    if int.len() > 1 && int.starts_with('0') {
+        return None;
+    }
+
+    let mut buf = format!("{}{}.{}", sign, int, frac);
+    if let Some(exp) = exp {
+        let digits = exp.strip_prefix(['-', '+']).unwrap_or(exp);
+        if digits.is_empty() || !is_digits(digits) {
+            return None;
+        }
+        buf.push('e');
+        buf.push_str(exp);
+    }
+    buf.parse().ok()
+}
+
+/// Date in the order of the locale with '.', '/' or '-' as separator.
+/// Two digit years are taken as 1930 to 2029.
+fn parse_date(text: &str, order: DateOrder) -> Option<NaiveDate> {
+    let sep = text.chars().find(|c| matches!(c, '.' | '/' | '-'))?;
+    let parts = text.split(sep).collect::<Vec<_>>();
+    if parts.len() != 3
+        || parts
+            .iter()
+            .any(|v| v.is_empty() || v.len() > 4 || !v.chars().all(|c| c.is_ascii_digit()))
+    {
+        return None;
+    }
+
+    // ISO
+    let order = if parts[0].len() == 4 {
+        DateOrder::Ymd
+    } else {
+        order
+    };
+    let (y, m, d) = match order {
+        DateOrder::Dmy => (parts[2], parts[1], parts[0]),
+        DateOrder::Mdy => (parts[2], parts[0], parts[1]),
+        DateOrder::Ymd => (parts[0], parts[1], parts[2]),
+    };
+    let year = match y.len() {
+        2 => {
+            let y: i32 = y.parse().ok()?;
+            if y < 30 {
+                2000 + y
+            } else {
+                1900 + y
+            }
+        }
+        4 => y.parse().ok()?,
+        _ => return None,
+    };
+    if m.len() > 2 || d.len() > 2 {
+        return None;
+    }
+    NaiveDate::from_ymd_opt(year, m.parse().ok()?, d.parse().ok()?)
+}
+
+/// Time of day as H:MM, H:MM:SS or H:MM:SS.fff.
+fn parse_time(text: &str) -> Option<NaiveTime> {
+    NaiveTime::parse_from_str(text, "%H:%M:%S%.f")
+        .or_else(|_| NaiveTime::parse_from_str(text, "%H:%M"))
+        .ok()
+}
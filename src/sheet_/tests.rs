@@ -1,4 +1,4 @@
-use crate::sheet_::dedup_colheader;
+use crate::sheet_::{dedup_colheader, dedup_rowheader};
 use crate::Sheet;
 
 #[test]
@@ -16,3 +16,19 @@ fn test_dedup_colheader() {
 
     assert_eq!(sh.col_header.len(), 3);
 }
+
+#[test]
+fn test_dedup_rowheader() {
+    let mut sh = Sheet::new("one");
+
+    sh.set_row_height(0, cm!(1));
+    sh.set_row_height(1, cm!(1));
+    sh.set_row_height(2, cm!(1));
+    sh.set_row_height(4, cm!(1));
+    sh.set_row_height(5, cm!(2));
+
+    let _ = dedup_rowheader(&mut sh);
+
+    assert_eq!(sh.row_header.len(), 3);
+    assert_eq!(sh.row_header[&0].span, 3);
+}
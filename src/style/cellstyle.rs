@@ -3,19 +3,20 @@ use crate::color::Rgb;
 use crate::format::ValueFormatRef;
 use crate::style::stylemap::StyleMap;
 use crate::style::units::{
-    Angle, Border, CellAlignVertical, CellProtect, FontSize, FontStyle, FontVariant, FontWeight,
-    GlyphOrientation, Hyphenation, HyphenationLadderCount, Indent, Length, LetterSpacing,
-    LineBreak, LineHeight, LineMode, LineStyle, LineType, LineWidth, Margin, PageBreak, PageNumber,
-    ParaAlignVertical, Percent, PunctuationWrap, RotationAlign, TextAlign, TextAlignLast,
-    TextAlignSource, TextAutoSpace, TextCombine, TextCondition, TextDisplay, TextEmphasize,
-    TextEmphasizePosition, TextKeep, TextPosition, TextRelief, TextTransform, WrapOption,
-    WritingDirection, WritingMode,
+    Angle, Border, BorderLine, CellAlignVertical, CellProtect, FontSize, FontStyle, FontVariant,
+    FontWeight, GlyphOrientation, Hyphenation, HyphenationLadderCount, Indent, Length,
+    LetterSpacing, LineBreak, LineHeight, LineMode, LineStyle, LineType, LineWidth, Margin,
+    PageBreak, PageNumber, ParaAlignVertical, Percent, PunctuationWrap, RotationAlign, TextAlign,
+    TextAlignLast, TextAlignSource, TextAutoSpace, TextCombine, TextCondition, TextDisplay,
+    TextEmphasize, TextEmphasizePosition, TextKeep, TextPosition, TextRelief, TextTransform,
+    WrapOption, WritingDirection, WritingMode,
 };
 use crate::style::AnyStyleRef;
 use crate::style::{
-    border_line_width_string, border_string, color_string, shadow_string, text_position,
-    StyleOrigin, StyleUse, TextStyleRef,
+    border_line_width_string, border_string, color_string, parse_border, shadow_string,
+    text_position, ParseStyleAttr, StyleOrigin, StyleUse, TextStyleRef, Theme, ThemeColor,
 };
+use crate::OdsError;
 use core::borrow::Borrow;
 use get_size2::GetSize;
 use icu_locid::Locale;
@@ -65,6 +66,10 @@ pub struct CellStyle {
     textstyle: AttrMap2,
     /// Style maps
     stylemaps: Option<Vec<StyleMap>>,
+    /// Font color from the document theme.
+    color_theme: Option<ThemeColor>,
+    /// Background color from the document theme.
+    background_color_theme: Option<ThemeColor>,
 }
 
 styles_styles2!(CellStyle, CellStyleRef);
@@ -81,6 +86,8 @@ impl CellStyle {
             paragraphstyle: Default::default(),
             textstyle: Default::default(),
             stylemaps: None,
+            color_theme: None,
+            background_color_theme: None,
         }
     }
 
@@ -96,6 +103,8 @@ impl CellStyle {
             paragraphstyle: Default::default(),
             textstyle: Default::default(),
             stylemaps: None,
+            color_theme: None,
+            background_color_theme: None,
         };
         s.set_value_format(value_format);
         s
@@ -152,19 +161,67 @@ impl CellStyle {
         &mut self.textstyle
     }
 
-    /// Adds a stylemap.
-    pub fn push_stylemap(&mut self, stylemap: StyleMap) {
-        self.stylemaps.get_or_insert_with(Vec::new).push(stylemap);
+    style_stylemaps!();
+
+    /// Sets the same border on all four sides and removes any
+    /// per-side borders.
+    pub fn set_box_border(&mut self, line: BorderLine) {
+        for side in [
+            "fo:border-bottom",
+            "fo:border-left",
+            "fo:border-right",
+            "fo:border-top",
+        ] {
+            self.cellstyle.clear_attr(side);
+        }
+        self.cellstyle.set_attr("fo:border", line.to_string());
+    }
+
+    /// Uses a color of the document theme as font color. The color
+    /// itself is set too, for applications without theme support.
+    pub fn set_color_theme(&mut self, theme: &Theme, slot: ThemeColor) {
+        if let Some(color) = theme.color_str(slot) {
+            self.textstyle.set_attr("fo:color", color.to_string());
+        }
+        self.color_theme = Some(slot);
     }
 
-    /// Returns the stylemaps
-    pub fn stylemaps(&self) -> Option<&Vec<StyleMap>> {
-        self.stylemaps.as_ref()
+    /// Theme color used as font color.
+    pub fn color_theme(&self) -> Option<ThemeColor> {
+        self.color_theme
     }
 
-    /// Returns the mutable stylemap.
-    pub fn stylemaps_mut(&mut self) -> &mut Vec<StyleMap> {
-        self.stylemaps.get_or_insert_with(Vec::new)
+    /// Removes the reference to the theme, the font color stays.
+    pub fn clear_color_theme(&mut self) {
+        self.color_theme = None;
+    }
+
+    pub(crate) fn set_color_theme_slot(&mut self, slot: Option<ThemeColor>) {
+        self.color_theme = slot;
+    }
+
+    /// Uses a color of the document theme as background color. The color
+    /// itself is set too, for applications without theme support.
+    pub fn set_background_color_theme(&mut self, theme: &Theme, slot: ThemeColor) {
+        if let Some(color) = theme.color_str(slot) {
+            self.cellstyle
+                .set_attr("fo:background-color", color.to_string());
+        }
+        self.background_color_theme = Some(slot);
+    }
+
+    /// Theme color used as background color.
+    pub fn background_color_theme(&self) -> Option<ThemeColor> {
+        self.background_color_theme
+    }
+
+    /// Removes the reference to the theme, the background color stays.
+    pub fn clear_background_color_theme(&mut self) {
+        self.background_color_theme = None;
+    }
+
+    pub(crate) fn set_background_color_theme_slot(&mut self, slot: Option<ThemeColor>) {
+        self.background_color_theme = slot;
     }
 
     // Cell attributes.
@@ -227,6 +284,62 @@ impl CellStyle {
     style_text_autospace!(paragraphstyle);
     style_vertical_align_para!(paragraphstyle);
     // style_writing_mode!(paragraphstyle);
+
+    /// Writing mode of the text in the cell. LibreOffice uses this for the
+    /// text direction, WritingMode::RlTb for right-to-left text.
+    ///
+    /// The writing mode of the table-cell-properties is set with
+    /// set_writing_mode().
+    pub fn set_paragraph_writing_mode(&mut self, writing_mode: WritingMode) {
+        self.paragraphstyle
+            .set_attr("style:writing-mode", writing_mode.to_string());
+    }
+
+    /// Parses the writing mode of the text in the cell.
+    pub fn paragraph_writing_mode(&self) -> Result<Option<WritingMode>, OdsError> {
+        WritingMode::parse_attr(self.paragraphstyle.attr("style:writing-mode"))
+    }
+
+    /// Cells with this style can't be edited if the sheet is protected.
+    ///
+    /// Without style:cell-protect a cell is protected. To build a form
+    /// use set_protected(false) for the input cells and protect the
+    /// sheet with [Sheet::set_protected](crate::Sheet::set_protected).
+    ///
+    /// This changes style:cell-protect and keeps the formula-hidden part.
+    pub fn set_protected(&mut self, protected: bool) {
+        let protect = cell_protect(protected, self.hidden_formula());
+        self.set_cell_protect(protect);
+    }
+
+    /// Cells with this style can't be edited if the sheet is protected.
+    pub fn protected(&self) -> bool {
+        match self.cell_protect() {
+            Ok(Some(CellProtect::None | CellProtect::FormulaHidden)) => false,
+            Ok(Some(_)) | Ok(None) | Err(_) => true,
+        }
+    }
+
+    /// The formula of the cells is not shown if the sheet is protected.
+    ///
+    /// This changes style:cell-protect and keeps the protected part.
+    pub fn set_hidden_formula(&mut self, hidden: bool) {
+        let protect = cell_protect(self.protected(), hidden);
+        self.set_cell_protect(protect);
+    }
+
+    /// The formula of the cells is not shown if the sheet is protected.
+    pub fn hidden_formula(&self) -> bool {
+        matches!(
+            self.cell_protect(),
+            Ok(Some(
+                CellProtect::FormulaHidden
+                    | CellProtect::ProtectedFormulaHidden
+                    | CellProtect::HiddenAndProtected
+            ))
+        )
+    }
+
     style_writing_mode_automatic!(paragraphstyle);
     style_line_number!(paragraphstyle);
     style_number_lines!(paragraphstyle);
@@ -284,3 +397,12 @@ impl CellStyle {
 
     // TODO: background image
 }
+
+fn cell_protect(protected: bool, hidden: bool) -> CellProtect {
+    match (protected, hidden) {
+        (true, true) => CellProtect::ProtectedFormulaHidden,
+        (true, false) => CellProtect::Protected,
+        (false, true) => CellProtect::FormulaHidden,
+        (false, false) => CellProtect::None,
+    }
+}
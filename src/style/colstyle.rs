@@ -1,6 +1,7 @@
 use get_size2::GetSize;
 
 use crate::attrmap2::AttrMap2;
+use crate::style::stylemap::StyleMap;
 use crate::style::units::{Length, PageBreak};
 use crate::style::AnyStyleRef;
 use crate::style::ParseStyleAttr;
@@ -27,6 +28,8 @@ pub struct ColStyle {
     attr: AttrMap2,
     /// Column style properties
     colstyle: AttrMap2,
+    /// Style maps
+    stylemaps: Option<Vec<StyleMap>>,
 }
 
 styles_styles2!(ColStyle, ColStyleRef);
@@ -40,6 +43,7 @@ impl ColStyle {
             name: Default::default(),
             attr: Default::default(),
             colstyle: Default::default(),
+            stylemaps: None,
         }
     }
 
@@ -51,9 +55,12 @@ impl ColStyle {
             name: name.as_ref().to_string(),
             attr: Default::default(),
             colstyle: Default::default(),
+            stylemaps: None,
         }
     }
 
+    style_stylemaps!();
+
     /// Attributes
     pub fn attrmap(&self) -> &AttrMap2 {
         &self.attr
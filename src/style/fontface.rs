@@ -32,6 +32,8 @@ pub struct FontFaceDecl {
     origin: StyleOrigin,
     /// All other attributes.
     attr: AttrMap2,
+    /// Embedded font files. svg:font-face-src
+    src: Vec<FontFaceUri>,
 }
 
 impl FontFaceDecl {
@@ -41,6 +43,7 @@ impl FontFaceDecl {
             name: "".to_string(),
             origin: Default::default(),
             attr: Default::default(),
+            src: Default::default(),
         }
     }
 
@@ -50,6 +53,7 @@ impl FontFaceDecl {
             name: name.as_ref().to_string(),
             origin: StyleOrigin::Content,
             attr: Default::default(),
+            src: Default::default(),
         }
     }
 
@@ -89,6 +93,25 @@ impl FontFaceDecl {
         &mut self.attr
     }
 
+    /// Adds a font file. This is written as svg:font-face-uri
+    /// within svg:font-face-src.
+    ///
+    /// To embed a font use [WorkBook::embed_font](crate::WorkBook::embed_font),
+    /// which stores the font file in the package too.
+    pub fn add_src(&mut self, uri: FontFaceUri) {
+        self.src.push(uri);
+    }
+
+    /// Font files.
+    pub fn src(&self) -> &Vec<FontFaceUri> {
+        &self.src
+    }
+
+    /// Font files.
+    pub fn src_mut(&mut self) -> &mut Vec<FontFaceUri> {
+        &mut self.src
+    }
+
     style_font_family_generic!(attr);
     style_font_pitch!(attr);
     svg_font_family!(attr);
@@ -97,3 +120,42 @@ impl FontFaceDecl {
     svg_font_variant!(attr);
     svg_font_weight!(attr);
 }
+
+/// Reference to a font file. svg:font-face-uri
+#[derive(Clone, Debug, Default, PartialEq, GetSize)]
+pub struct FontFaceUri {
+    href: String,
+    /// svg:font-face-format, e.g. "truetype".
+    format: Option<String>,
+}
+
+impl FontFaceUri {
+    /// New reference to a font file. For embedded fonts this is the path
+    /// in the package, e.g. "Fonts/Corporate_1.ttf".
+    pub fn new<S: Into<String>>(href: S) -> Self {
+        Self {
+            href: href.into(),
+            format: None,
+        }
+    }
+
+    /// Path or url of the font file.
+    pub fn href(&self) -> &str {
+        &self.href
+    }
+
+    /// Path or url of the font file.
+    pub fn set_href<S: Into<String>>(&mut self, href: S) {
+        self.href = href.into();
+    }
+
+    /// Format of the font file: "truetype", "opentype", "woff", "woff2".
+    pub fn format(&self) -> Option<&str> {
+        self.format.as_deref()
+    }
+
+    /// Format of the font file: "truetype", "opentype", "woff", "woff2".
+    pub fn set_format<S: Into<String>>(&mut self, format: Option<S>) {
+        self.format = format.map(|v| v.into());
+    }
+}
@@ -1,17 +1,19 @@
 use crate::attrmap2::AttrMap2;
 use crate::style::units::{
-    FontSize, FontStyle, FontVariant, FontWeight, Hyphenation, HyphenationLadderCount, Indent,
-    LetterSpacing, LineBreak, LineHeight, LineMode, LineStyle, LineType, LineWidth, Margin,
-    PageBreak, PageNumber, ParaAlignVertical, Percent, PunctuationWrap, RotationScale, TextAlign,
-    TextAlignLast, TextAutoSpace, TextCombine, TextCondition, TextDisplay, TextEmphasize,
-    TextEmphasizePosition, TextKeep, TextPosition, TextRelief, TextTransform, WritingMode,
+    FillStyle, FontSize, FontStyle, FontVariant, FontWeight, Hyphenation, HyphenationLadderCount,
+    Indent, LetterSpacing, LineBreak, LineHeight, LineMode, LineStyle, LineType, LineWidth, Margin,
+    PageBreak, PageNumber, ParaAlignVertical, Percent, PunctuationWrap, RotationScale, StrokeStyle,
+    TextAlign, TextAlignLast, TextAutoSpace, TextCombine, TextCondition, TextDisplay,
+    TextEmphasize, TextEmphasizePosition, TextKeep, TextPosition, TextRelief, TextTransform,
+    WritingMode,
 };
 use crate::style::AnyStyleRef;
 use crate::style::{
-    border_line_width_string, border_string, color_string, shadow_string, text_position, Border,
-    Length, Rgb,
+    border_line_width_string, border_string, color_string, parse_border, shadow_string,
+    text_position, Border, Length, ParseStyleAttr, Rgb,
 };
 use crate::style::{StyleOrigin, StyleUse, TextStyleRef};
+use crate::OdsError;
 use get_size2::GetSize;
 use icu_locid::Locale;
 use std::borrow::Borrow;
@@ -108,6 +110,12 @@ impl GraphicStyle {
         &mut self.textstyle
     }
 
+    draw_fill!(graphicstyle);
+    draw_opacity!(graphicstyle);
+    draw_stroke!(graphicstyle);
+    svg_stroke!(graphicstyle);
+    draw_shadow!(graphicstyle);
+
     fo_background_color!(paragraphstyle);
     fo_border!(paragraphstyle);
     fo_break!(paragraphstyle);
@@ -1,6 +1,9 @@
 use crate::style::pagestyle::PageStyleRef;
-use crate::style::AnyStyleRef;
-use crate::text::TextTag;
+use crate::style::{AnyStyleRef, ParagraphStyleRef, TextStyleRef};
+use crate::text::{
+    MetaDate, MetaFileName, MetaPageCount, MetaPageNumber, MetaSheetName, MetaTime, MetaTitle,
+    TextP, TextS, TextSpan, TextTag,
+};
 use get_size2::GetSize;
 use std::borrow::Borrow;
 
@@ -383,4 +386,141 @@ impl HeaderFooter {
     pub fn content_mut(&mut self) -> &mut Vec<TextTag> {
         &mut self.content
     }
+
+    /// Adds to the left region. Builder style.
+    pub fn with_left<T: Into<TextTag>>(mut self, txt: T) -> Self {
+        self.region_left.push(txt.into());
+        self
+    }
+
+    /// Adds to the center region. Builder style.
+    pub fn with_center<T: Into<TextTag>>(mut self, txt: T) -> Self {
+        self.region_center.push(txt.into());
+        self
+    }
+
+    /// Adds to the right region. Builder style.
+    pub fn with_right<T: Into<TextTag>>(mut self, txt: T) -> Self {
+        self.region_right.push(txt.into());
+        self
+    }
+
+    /// Adds to the content, if there are no regions. Builder style.
+    pub fn with_content<T: Into<TextTag>>(mut self, txt: T) -> Self {
+        self.content.push(txt.into());
+        self
+    }
+}
+
+/// Builds one paragraph of header/footer text with the usual fields.
+///
+/// ```
+/// use spreadsheet_ods::style::{HeaderFooter, HeaderFooterText, MasterPage};
+///
+/// let mut mp = MasterPage::new("mp1");
+/// mp.set_footer(
+///     HeaderFooter::new()
+///         .with_left(HeaderFooterText::new().sheet_name())
+///         .with_right(
+///             HeaderFooterText::new()
+///                 .text("Page ")
+///                 .page_number()
+///                 .text(" of ")
+///                 .page_count(),
+///         ),
+/// );
+/// ```
+#[derive(Debug, Default)]
+pub struct HeaderFooterText {
+    p: TextP,
+}
+
+impl From<HeaderFooterText> for TextTag {
+    fn from(t: HeaderFooterText) -> Self {
+        t.p.into_xmltag()
+    }
+}
+
+impl HeaderFooterText {
+    /// Empty paragraph.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Paragraph style.
+    pub fn style_name(mut self, style: &ParagraphStyleRef) -> Self {
+        self.p = self.p.style_name(style);
+        self
+    }
+
+    /// Plain text.
+    pub fn text<S: Into<String>>(mut self, text: S) -> Self {
+        self.p = self.p.text(text);
+        self
+    }
+
+    /// Text with a text style.
+    pub fn styled_text<S: Into<String>>(mut self, text: S, style: &TextStyleRef) -> Self {
+        self.p = self.p.tag(TextSpan::new().style_name(style).text(text));
+        self
+    }
+
+    /// Spaces. Multiple spaces in plain text are collapsed otherwise.
+    pub fn spaces(mut self, count: u32) -> Self {
+        self.p = self.p.tag(TextS::new().count(count));
+        self
+    }
+
+    /// Current page number.
+    pub fn page_number(mut self) -> Self {
+        self.p = self.p.tag(MetaPageNumber::new().text("1"));
+        self
+    }
+
+    /// Total number of pages.
+    pub fn page_count(mut self) -> Self {
+        self.p = self.p.tag(MetaPageCount::new().text("1"));
+        self
+    }
+
+    /// Name of the sheet.
+    pub fn sheet_name(mut self) -> Self {
+        self.p = self.p.tag(MetaSheetName::new().text("???"));
+        self
+    }
+
+    /// File name.
+    pub fn file_name(mut self) -> Self {
+        self.p = self.p.tag(MetaFileName::new().text("???"));
+        self
+    }
+
+    /// Document title.
+    pub fn title(mut self) -> Self {
+        self.p = self.p.tag(MetaTitle::new().text("???"));
+        self
+    }
+
+    /// Current date.
+    pub fn date(mut self) -> Self {
+        self.p = self.p.tag(MetaDate::new());
+        self
+    }
+
+    /// Current time.
+    pub fn time(mut self) -> Self {
+        self.p = self.p.tag(MetaTime::new());
+        self
+    }
+
+    /// Any other text tag.
+    pub fn tag<T: Into<TextTag>>(mut self, tag: T) -> Self {
+        self.p = self.p.tag(tag);
+        self
+    }
+
+    /// Extracts the finished text:p.
+    pub fn into_xmltag(self) -> TextTag {
+        self.p.into_xmltag()
+    }
 }
@@ -107,6 +107,7 @@ pub use rowstyle::*;
 pub use ruby::*;
 pub use tablestyle::*;
 pub use textstyle::*;
+pub use theme::*;
 
 pub mod stylemap;
 pub mod tabstop;
@@ -123,6 +124,7 @@ mod rowstyle;
 mod ruby;
 mod tablestyle;
 mod textstyle;
+mod theme;
 
 // The <style:style> element has the following attributes:
 // ok: style:auto-update 19.467,
@@ -176,6 +178,43 @@ pub enum StyleUse {
     Automatic,
 }
 
+/// The style families as in style:family. Each family has its own
+/// XxxStyle struct.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, GetSize)]
+pub enum StyleFamily {
+    /// TableStyle
+    Table,
+    /// ColStyle
+    Column,
+    /// RowStyle
+    Row,
+    /// CellStyle
+    Cell,
+    /// ParagraphStyle
+    Paragraph,
+    /// TextStyle
+    Text,
+    /// RubyStyle
+    Ruby,
+    /// GraphicStyle
+    Graphic,
+}
+
+impl std::fmt::Display for StyleFamily {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StyleFamily::Table => write!(f, "table"),
+            StyleFamily::Column => write!(f, "table-column"),
+            StyleFamily::Row => write!(f, "table-row"),
+            StyleFamily::Cell => write!(f, "table-cell"),
+            StyleFamily::Paragraph => write!(f, "paragraph"),
+            StyleFamily::Text => write!(f, "text"),
+            StyleFamily::Ruby => write!(f, "ruby"),
+            StyleFamily::Graphic => write!(f, "graphic"),
+        }
+    }
+}
+
 // General style reference.
 style_ref2_base!(AnyStyleRef);
 
@@ -201,6 +240,22 @@ impl ParseStyleAttr<bool> for bool {
     }
 }
 
+impl ParseStyleAttr<Rgb<u8>> for Rgb<u8> {
+    fn parse_attr(attr: Option<&str>) -> Result<Option<Rgb<u8>>, OdsError> {
+        match attr {
+            None | Some("transparent") => Ok(None),
+            Some(s) => {
+                let v = s
+                    .strip_prefix('#')
+                    .filter(|v| v.len() == 6)
+                    .and_then(|v| u32::from_str_radix(v, 16).ok())
+                    .ok_or_else(|| OdsError::Parse("invalid color", Some(s.to_string())))?;
+                Ok(Some(Rgb::new((v >> 16) as u8, (v >> 8) as u8, v as u8)))
+            }
+        }
+    }
+}
+
 pub(crate) fn color_string(color: Rgb<u8>) -> String {
     format!("#{:02x}{:02x}{:02x}", color.r, color.g, color.b)
 }
@@ -229,6 +284,28 @@ pub(crate) fn border_string(width: Length, border: Border, color: Rgb<u8>) -> St
     )
 }
 
+pub(crate) fn parse_border(
+    attr: Option<&str>,
+) -> Result<Option<(Length, Border, Rgb<u8>)>, OdsError> {
+    if let Some(s) = attr {
+        let mut width = Length::Default;
+        let mut border = Border::None;
+        let mut color = Rgb::new(0, 0, 0);
+        for v in s.split_ascii_whitespace() {
+            if v.starts_with('#') {
+                color = Rgb::<u8>::parse_attr_def(Some(v), color)?;
+            } else if v.starts_with(|c: char| c.is_ascii_digit() || c == '.') {
+                width = Length::parse_attr_def(Some(v), width)?;
+            } else {
+                border = Border::parse_attr_def(Some(v), border)?;
+            }
+        }
+        Ok(Some((width, border, color)))
+    } else {
+        Ok(None)
+    }
+}
+
 pub(crate) fn border_line_width_string(inner: Length, space: Length, outer: Length) -> String {
     format!("{} {} {}", inner, space, outer)
 }
@@ -1,14 +1,15 @@
 use crate::attrmap2::AttrMap2;
 use crate::color::Rgb;
 use crate::style::units::{
-    Border, LengthPercent, Margin, MasterPageUsage, Percent, PrintCentering, PrintContent,
+    Border, LengthPercent, Margin, MasterPageUsage, Paper, Percent, PrintCentering, PrintContent,
     PrintOrder, PrintOrientation, StyleNumFormat, WritingMode,
 };
 use crate::style::AnyStyleRef;
 use crate::style::{
-    border_line_width_string, border_string, color_string, shadow_string, ParseStyleAttr,
+    border_line_width_string, border_string, color_string, parse_border, shadow_string,
+    ParseStyleAttr,
 };
-use crate::{Length, OdsResult};
+use crate::{Length, OdsError, OdsResult};
 use get_size2::GetSize;
 use std::borrow::Borrow;
 
@@ -125,6 +126,51 @@ impl PageStyle {
         &mut self.style
     }
 
+    /// Sets the page size and orientation for one of the common paper
+    /// sizes. The margins are not changed.
+    pub fn set_paper(&mut self, paper: Paper, orientation: PrintOrientation) {
+        let (width, height) = paper.size();
+        let (width, height) = match orientation {
+            PrintOrientation::Portrait => (width, height),
+            PrintOrientation::Landscape => (height, width),
+        };
+        self.set_page_width(width);
+        self.set_page_height(height);
+        self.set_print_orientation(orientation);
+    }
+
+    /// Sets the page size and orientation like [set_paper](Self::set_paper)
+    /// and all four margins to the same value. LibreOffice uses 2cm.
+    pub fn set_paper_with_margins(
+        &mut self,
+        paper: Paper,
+        orientation: PrintOrientation,
+        margin: Length,
+    ) {
+        self.set_paper(paper, orientation);
+        self.style.clear_attr("fo:margin");
+        for attr in [
+            "fo:margin-top",
+            "fo:margin-bottom",
+            "fo:margin-left",
+            "fo:margin-right",
+        ] {
+            self.style.set_attr(attr, margin.to_string());
+        }
+    }
+
+    /// Scales the printout to fit into w pages horizontally and h pages
+    /// vertically. A 0 means no limit in this direction.
+    ///
+    /// This is a LibreOffice extension, it replaces any
+    /// style:scale-to and style:scale-to-pages.
+    pub fn fit_to_pages(&mut self, w: u32, h: u32) {
+        self.style.clear_attr("style:scale-to");
+        self.style.clear_attr("style:scale-to-pages");
+        self.style.set_attr("loext:scale-to-X", w.to_string());
+        self.style.set_attr("loext:scale-to-Y", h.to_string());
+    }
+
     fo_page_height!(style);
     fo_page_width!(style);
     style_first_page_number!(style);
@@ -1,5 +1,6 @@
 use crate::attrmap2::AttrMap2;
 use crate::color::Rgb;
+use crate::style::stylemap::StyleMap;
 use crate::style::tabstop::TabStop;
 use crate::style::units::{
     Border, FontSize, FontStyle, FontVariant, FontWeight, Hyphenation, HyphenationLadderCount,
@@ -12,9 +13,10 @@ use crate::style::units::{
 use crate::style::AnyStyleRef;
 use crate::style::MasterPageRef;
 use crate::style::{
-    border_line_width_string, border_string, color_string, shadow_string, text_position,
-    StyleOrigin, StyleUse, TextStyleRef,
+    border_line_width_string, border_string, color_string, parse_border, shadow_string,
+    text_position, ParseStyleAttr, StyleOrigin, StyleUse, TextStyleRef,
 };
+use crate::OdsError;
 use get_size2::GetSize;
 use icu_locid::Locale;
 use std::borrow::Borrow;
@@ -42,6 +44,8 @@ pub struct ParagraphStyle {
     textstyle: AttrMap2,
     /// Tabstop data.
     tabstops: Option<Vec<TabStop>>,
+    /// Style maps
+    stylemaps: Option<Vec<StyleMap>>,
 }
 
 styles_styles2!(ParagraphStyle, ParagraphStyleRef);
@@ -57,6 +61,7 @@ impl ParagraphStyle {
             paragraphstyle: Default::default(),
             textstyle: Default::default(),
             tabstops: None,
+            stylemaps: None,
         }
     }
 
@@ -70,9 +75,12 @@ impl ParagraphStyle {
             paragraphstyle: Default::default(),
             textstyle: Default::default(),
             tabstops: None,
+            stylemaps: None,
         }
     }
 
+    style_stylemaps!();
+
     /// General attributes.
     pub fn attrmap(&self) -> &AttrMap2 {
         &self.attr
@@ -2,6 +2,7 @@ use crate::color::Rgb;
 use get_size2::GetSize;
 
 use crate::attrmap2::AttrMap2;
+use crate::style::stylemap::StyleMap;
 use crate::style::units::{Length, PageBreak, TextKeep};
 use crate::style::AnyStyleRef;
 use crate::style::ParseStyleAttr;
@@ -27,6 +28,8 @@ pub struct RowStyle {
     attr: AttrMap2,
     /// Table style properties
     rowstyle: AttrMap2,
+    /// Style maps
+    stylemaps: Option<Vec<StyleMap>>,
 }
 
 styles_styles2!(RowStyle, RowStyleRef);
@@ -40,6 +43,7 @@ impl RowStyle {
             name: Default::default(),
             attr: Default::default(),
             rowstyle: Default::default(),
+            stylemaps: None,
         }
     }
 
@@ -51,9 +55,12 @@ impl RowStyle {
             name: name.as_ref().to_string(),
             attr: Default::default(),
             rowstyle: Default::default(),
+            stylemaps: None,
         }
     }
 
+    style_stylemaps!();
+
     /// General attributes.
     pub fn attrmap(&self) -> &AttrMap2 {
         &self.attr
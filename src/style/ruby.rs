@@ -1,4 +1,5 @@
 use crate::attrmap2::AttrMap2;
+use crate::style::units::{RubyAlign, RubyPosition};
 use crate::style::AnyStyleRef;
 use crate::style::{StyleOrigin, StyleUse};
 use get_size2::GetSize;
@@ -67,4 +68,7 @@ impl RubyStyle {
     pub fn rubystyle_mut(&mut self) -> &mut AttrMap2 {
         &mut self.rubystyle
     }
+
+    style_ruby_align!(rubystyle);
+    style_ruby_position!(rubystyle);
 }
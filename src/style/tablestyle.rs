@@ -1,11 +1,15 @@
 use crate::attrmap2::AttrMap2;
 use crate::color::Rgb;
+use crate::style::stylemap::StyleMap;
 use crate::style::units::{
     Length, Margin, PageBreak, PageNumber, RelativeScale, TableAlign, TableBorderModel, TextKeep,
     WritingMode,
 };
 use crate::style::AnyStyleRef;
-use crate::style::{color_string, shadow_string, MasterPageRef, StyleOrigin, StyleUse};
+use crate::style::{
+    color_string, shadow_string, MasterPageRef, ParseStyleAttr, StyleOrigin, StyleUse,
+};
+use crate::OdsError;
 use core::borrow::Borrow;
 use get_size2::GetSize;
 
@@ -25,6 +29,8 @@ pub struct TableStyle {
     attr: AttrMap2,
     /// Table style properties
     tablestyle: AttrMap2,
+    /// Style maps
+    stylemaps: Option<Vec<StyleMap>>,
 }
 
 styles_styles2!(TableStyle, TableStyleRef);
@@ -38,6 +44,7 @@ impl TableStyle {
             name: Default::default(),
             attr: Default::default(),
             tablestyle: Default::default(),
+            stylemaps: None,
         }
     }
 
@@ -49,9 +56,12 @@ impl TableStyle {
             name: String::from(name.as_ref()),
             attr: Default::default(),
             tablestyle: Default::default(),
+            stylemaps: None,
         }
     }
 
+    style_stylemaps!();
+
     style_master_page!(attr);
 
     /// Access to all stored attributes.
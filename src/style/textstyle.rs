@@ -1,12 +1,16 @@
 use crate::attrmap2::AttrMap2;
 use crate::color::Rgb;
+use crate::style::stylemap::StyleMap;
 use crate::style::units::{
     Angle, FontSize, FontStyle, FontVariant, FontWeight, Length, LetterSpacing, LineMode,
     LineStyle, LineType, LineWidth, Percent, RotationScale, TextCombine, TextCondition,
     TextDisplay, TextEmphasize, TextEmphasizePosition, TextPosition, TextRelief, TextTransform,
 };
 use crate::style::AnyStyleRef;
-use crate::style::{color_string, shadow_string, text_position, StyleOrigin, StyleUse};
+use crate::style::{
+    color_string, shadow_string, text_position, ParseStyleAttr, StyleOrigin, StyleUse,
+};
+use crate::OdsError;
 use core::borrow::Borrow;
 use get_size2::GetSize;
 use icu_locid::Locale;
@@ -28,6 +32,8 @@ pub struct TextStyle {
     attr: AttrMap2,
     /// Specific attributes
     textstyle: AttrMap2,
+    /// Style maps
+    stylemaps: Option<Vec<StyleMap>>,
 }
 
 styles_styles2!(TextStyle, TextStyleRef);
@@ -41,6 +47,7 @@ impl TextStyle {
             name: Default::default(),
             attr: Default::default(),
             textstyle: Default::default(),
+            stylemaps: None,
         }
     }
 
@@ -52,9 +59,12 @@ impl TextStyle {
             name: name.as_ref().to_string(),
             attr: Default::default(),
             textstyle: Default::default(),
+            stylemaps: None,
         }
     }
 
+    style_stylemaps!();
+
     /// General attributes for the style.
     pub fn attrmap(&self) -> &AttrMap2 {
         &self.attr
@@ -0,0 +1,183 @@
+use crate::color::Rgb;
+use crate::style::color_string;
+use crate::OdsError;
+use get_size2::GetSize;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+/// The color slots of a document theme.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, GetSize)]
+#[allow(missing_docs)]
+pub enum ThemeColor {
+    Dark1,
+    Light1,
+    Dark2,
+    Light2,
+    Accent1,
+    Accent2,
+    Accent3,
+    Accent4,
+    Accent5,
+    Accent6,
+    Hyperlink,
+    FollowedHyperlink,
+}
+
+impl ThemeColor {
+    /// All color slots in document order.
+    pub const ALL: [ThemeColor; 12] = [
+        ThemeColor::Dark1,
+        ThemeColor::Light1,
+        ThemeColor::Dark2,
+        ThemeColor::Light2,
+        ThemeColor::Accent1,
+        ThemeColor::Accent2,
+        ThemeColor::Accent3,
+        ThemeColor::Accent4,
+        ThemeColor::Accent5,
+        ThemeColor::Accent6,
+        ThemeColor::Hyperlink,
+        ThemeColor::FollowedHyperlink,
+    ];
+
+    /// Name as used in the loext:name attribute.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ThemeColor::Dark1 => "dark1",
+            ThemeColor::Light1 => "light1",
+            ThemeColor::Dark2 => "dark2",
+            ThemeColor::Light2 => "light2",
+            ThemeColor::Accent1 => "accent1",
+            ThemeColor::Accent2 => "accent2",
+            ThemeColor::Accent3 => "accent3",
+            ThemeColor::Accent4 => "accent4",
+            ThemeColor::Accent5 => "accent5",
+            ThemeColor::Accent6 => "accent6",
+            ThemeColor::Hyperlink => "hyperlink",
+            ThemeColor::FollowedHyperlink => "followed-hyperlink",
+        }
+    }
+}
+
+impl Display for ThemeColor {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl FromStr for ThemeColor {
+    type Err = OdsError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        ThemeColor::ALL
+            .iter()
+            .find(|v| v.as_str() == s)
+            .copied()
+            .ok_or_else(|| OdsError::Parse("invalid theme color", Some(s.to_string())))
+    }
+}
+
+/// A document theme with a color palette. This is the <loext:theme> element
+/// that LibreOffice writes to styles.xml.
+///
+/// Styles use the colors by value:
+///
+/// ```
+/// use spreadsheet_ods::style::{CellStyle, Theme, ThemeColor};
+/// use spreadsheet_ods::color::Rgb;
+/// use spreadsheet_ods::WorkBook;
+///
+/// let mut theme = Theme::new("Corporate");
+/// theme.set_color(ThemeColor::Accent1, Rgb::new(0, 84, 159));
+///
+/// let mut st = CellStyle::new_empty();
+/// st.set_color(theme.color(ThemeColor::Accent1).expect("color"));
+///
+/// let mut book = WorkBook::new_empty();
+/// book.set_theme(theme);
+/// ```
+#[derive(Debug, Clone, Default, GetSize)]
+pub struct Theme {
+    name: String,
+    colors_name: String,
+    colors: Vec<(ThemeColor, String)>,
+}
+
+impl Theme {
+    /// New theme. The palette is named like the theme.
+    pub fn new<S: AsRef<str>>(name: S) -> Self {
+        Self {
+            name: name.as_ref().to_string(),
+            colors_name: name.as_ref().to_string(),
+            colors: Default::default(),
+        }
+    }
+
+    /// Default colors as used by LibreOffice.
+    pub fn new_default() -> Self {
+        let mut theme = Self::new("Office");
+        theme.set_colors_name("LibreOffice");
+        for (c, v) in ThemeColor::ALL.iter().zip([
+            0x000000, 0xffffff, 0x000000, 0xffffff, 0x18a303, 0x0369a3, 0xa33e03, 0x8e03a3,
+            0xc99c00, 0xc9211e, 0x0000ee, 0x551a8b,
+        ]) {
+            theme.set_color(*c, Rgb::new((v >> 16) as u8, (v >> 8) as u8, v as u8));
+        }
+        theme
+    }
+
+    /// Theme name.
+    pub fn set_name<S: AsRef<str>>(&mut self, name: S) {
+        self.name = name.as_ref().to_string();
+    }
+
+    /// Theme name.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Name of the color palette.
+    pub fn set_colors_name<S: AsRef<str>>(&mut self, name: S) {
+        self.colors_name = name.as_ref().to_string();
+    }
+
+    /// Name of the color palette.
+    pub fn colors_name(&self) -> &str {
+        &self.colors_name
+    }
+
+    /// Sets a palette color.
+    pub fn set_color(&mut self, slot: ThemeColor, color: Rgb<u8>) {
+        self.set_color_str(slot, color_string(color));
+    }
+
+    pub(crate) fn set_color_str(&mut self, slot: ThemeColor, color: String) {
+        if let Some((_, v)) = self.colors.iter_mut().find(|(c, _)| *c == slot) {
+            *v = color;
+        } else {
+            self.colors.push((slot, color));
+            self.colors.sort_by_key(|(c, _)| *c);
+        }
+    }
+
+    /// Returns a palette color.
+    pub fn color(&self, slot: ThemeColor) -> Option<Rgb<u8>> {
+        self.color_str(slot).and_then(|v| {
+            let v = u32::from_str_radix(v.trim_start_matches('#'), 16).ok()?;
+            Some(Rgb::new((v >> 16) as u8, (v >> 8) as u8, v as u8))
+        })
+    }
+
+    /// Returns a palette color as it is stored.
+    pub fn color_str(&self, slot: ThemeColor) -> Option<&str> {
+        self.colors
+            .iter()
+            .find(|(c, _)| *c == slot)
+            .map(|(_, v)| v.as_str())
+    }
+
+    /// Iterates the palette colors.
+    pub fn colors(&self) -> impl Iterator<Item = (ThemeColor, &str)> {
+        self.colors.iter().map(|(c, v)| (*c, v.as_str()))
+    }
+}
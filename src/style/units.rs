@@ -2,7 +2,8 @@
 //! All kinds of units for use in style attributes.
 //!
 
-use crate::style::ParseStyleAttr;
+use crate::color::Rgb;
+use crate::style::{color_string, ParseStyleAttr};
 use crate::OdsError;
 use get_size2::GetSize;
 use std::fmt::{Display, Formatter};
@@ -32,6 +33,24 @@ impl Display for Angle {
     }
 }
 
+impl ParseStyleAttr<Angle> for Angle {
+    fn parse_attr(attr: Option<&str>) -> Result<Option<Angle>, OdsError> {
+        if let Some(s) = attr {
+            if let Some(v) = s.strip_suffix("deg") {
+                Ok(Some(Angle::Deg(v.parse()?)))
+            } else if let Some(v) = s.strip_suffix("grad") {
+                Ok(Some(Angle::Grad(v.parse()?)))
+            } else if let Some(v) = s.strip_suffix("rad") {
+                Ok(Some(Angle::Rad(v.parse()?)))
+            } else {
+                Ok(Some(Angle::Deg(s.parse()?)))
+            }
+        } else {
+            Ok(None)
+        }
+    }
+}
+
 /// A (positive or negative) length, consisting of magnitude and unit, in conformance with the Units of
 /// Measure defined in §5.9.13 of XSL.
 #[derive(Debug, Clone, Copy, PartialEq, Default, GetSize)]
@@ -68,6 +87,22 @@ impl Length {
     }
 }
 
+impl Length {
+    /// Converts to cm. Returns None for Default and Em, as those
+    /// depend on context.
+    pub fn to_cm(&self) -> Option<f64> {
+        match self {
+            Length::Default => None,
+            Length::Cm(v) => Some(*v),
+            Length::Mm(v) => Some(*v / 10.0),
+            Length::In(v) => Some(*v * 2.54),
+            Length::Pt(v) => Some(*v * 2.54 / 72.0),
+            Length::Pc(v) => Some(*v * 2.54 / 6.0),
+            Length::Em(_) => None,
+        }
+    }
+}
+
 impl Display for Length {
     fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), std::fmt::Error> {
         match self {
@@ -121,6 +156,20 @@ impl Display for Percent {
     }
 }
 
+impl ParseStyleAttr<Percent> for Percent {
+    fn parse_attr(attr: Option<&str>) -> Result<Option<Percent>, OdsError> {
+        if let Some(s) = attr {
+            if let Some(v) = s.strip_suffix('%') {
+                Ok(Some(Percent::Percent(v.parse()?)))
+            } else {
+                Err(OdsError::Parse("invalid percent", Some(s.to_string())))
+            }
+        } else {
+            Ok(None)
+        }
+    }
+}
+
 /// Length or percentage.
 #[derive(Debug, Clone, Copy, PartialEq)]
 #[allow(missing_docs)]
@@ -363,10 +412,11 @@ impl ParseStyleAttr<MasterPageUsage> for MasterPageUsage {
 /// * right: text is right aligned with a tab stop.
 ///
 /// For a <style:tab-stop> 17.8 element the default value for this attribute is left.
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, Default)]
 #[allow(missing_docs)]
 pub enum TabStopType {
     Center,
+    #[default]
     Left,
     Right,
     Char,
@@ -383,12 +433,6 @@ impl Display for TabStopType {
     }
 }
 
-impl Default for TabStopType {
-    fn default() -> Self {
-        Self::Left
-    }
-}
-
 /// 19.534 svg:font-stretch
 ///
 /// See §20.8.3 of SVG.
@@ -462,6 +506,99 @@ impl Display for Border {
     }
 }
 
+impl ParseStyleAttr<Border> for Border {
+    fn parse_attr(attr: Option<&str>) -> Result<Option<Border>, OdsError> {
+        if let Some(attr) = attr {
+            match attr {
+                "none" => Ok(Some(Border::None)),
+                "hidden" => Ok(Some(Border::Hidden)),
+                "dotted" => Ok(Some(Border::Dotted)),
+                "dashed" => Ok(Some(Border::Dashed)),
+                "solid" => Ok(Some(Border::Solid)),
+                "double" => Ok(Some(Border::Double)),
+                "groove" => Ok(Some(Border::Groove)),
+                "ridge" => Ok(Some(Border::Ridge)),
+                "inset" => Ok(Some(Border::Inset)),
+                "outset" => Ok(Some(Border::Outset)),
+                _ => Err(OdsError::Parse(
+                    "invalid border style",
+                    Some(attr.to_string()),
+                )),
+            }
+        } else {
+            Ok(None)
+        }
+    }
+}
+
+/// A complete border line with width, line style and color.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BorderLine {
+    width: Length,
+    style: Border,
+    color: Rgb<u8>,
+}
+
+impl BorderLine {
+    /// Border line.
+    pub fn new(width: Length, style: Border, color: Rgb<u8>) -> Self {
+        Self {
+            width,
+            style,
+            color,
+        }
+    }
+
+    /// Thin solid line.
+    pub fn thin(color: Rgb<u8>) -> Self {
+        Self::new(Length::Pt(0.75), Border::Solid, color)
+    }
+
+    /// Thick solid line.
+    pub fn thick(color: Rgb<u8>) -> Self {
+        Self::new(Length::Pt(2.5), Border::Solid, color)
+    }
+
+    /// Width.
+    pub fn width(&self) -> Length {
+        self.width
+    }
+
+    /// Line style.
+    pub fn style(&self) -> Border {
+        self.style
+    }
+
+    /// Color.
+    pub fn color(&self) -> Rgb<u8> {
+        self.color
+    }
+}
+
+impl From<(Length, Border, Rgb<u8>)> for BorderLine {
+    fn from((width, style, color): (Length, Border, Rgb<u8>)) -> Self {
+        Self::new(width, style, color)
+    }
+}
+
+impl From<BorderLine> for (Length, Border, Rgb<u8>) {
+    fn from(value: BorderLine) -> Self {
+        (value.width, value.style, value.color)
+    }
+}
+
+impl Display for BorderLine {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), std::fmt::Error> {
+        write!(
+            f,
+            "{} {} {}",
+            self.width,
+            self.style,
+            color_string(self.color)
+        )
+    }
+}
+
 /// 20.184 fo:break-after, fo:break-before
 /// See §7.19.1 of XSL. The values odd-page and even-page are not supported.
 ///
@@ -542,6 +679,20 @@ impl Display for FontSize {
     }
 }
 
+impl ParseStyleAttr<FontSize> for FontSize {
+    fn parse_attr(attr: Option<&str>) -> Result<Option<FontSize>, OdsError> {
+        if let Some(s) = attr {
+            if s.ends_with('%') {
+                Ok(Percent::parse_attr(attr)?.map(FontSize::Percent))
+            } else {
+                Ok(Length::parse_attr(attr)?.map(FontSize::Length))
+            }
+        } else {
+            Ok(None)
+        }
+    }
+}
+
 /// 20.191 fo:font-style
 /// See §7.8.7 of XSL.
 ///
@@ -569,6 +720,24 @@ impl Display for FontStyle {
     }
 }
 
+impl ParseStyleAttr<FontStyle> for FontStyle {
+    fn parse_attr(attr: Option<&str>) -> Result<Option<FontStyle>, OdsError> {
+        if let Some(attr) = attr {
+            match attr {
+                "normal" => Ok(Some(FontStyle::Normal)),
+                "italic" => Ok(Some(FontStyle::Italic)),
+                "oblique" => Ok(Some(FontStyle::Oblique)),
+                _ => Err(OdsError::Parse(
+                    "invalid font style",
+                    Some(attr.to_string()),
+                )),
+            }
+        } else {
+            Ok(None)
+        }
+    }
+}
+
 /// 20.192 fo:font-variant
 ///
 /// See §7.8.8 of XSL.
@@ -637,6 +806,32 @@ impl Display for FontWeight {
     }
 }
 
+impl ParseStyleAttr<FontWeight> for FontWeight {
+    fn parse_attr(attr: Option<&str>) -> Result<Option<FontWeight>, OdsError> {
+        if let Some(attr) = attr {
+            match attr {
+                "normal" => Ok(Some(FontWeight::Normal)),
+                "bold" => Ok(Some(FontWeight::Bold)),
+                "100" => Ok(Some(FontWeight::W100)),
+                "200" => Ok(Some(FontWeight::W200)),
+                "300" => Ok(Some(FontWeight::W300)),
+                "400" => Ok(Some(FontWeight::W400)),
+                "500" => Ok(Some(FontWeight::W500)),
+                "600" => Ok(Some(FontWeight::W600)),
+                "700" => Ok(Some(FontWeight::W700)),
+                "800" => Ok(Some(FontWeight::W800)),
+                "900" => Ok(Some(FontWeight::W900)),
+                _ => Err(OdsError::Parse(
+                    "invalid font weight",
+                    Some(attr.to_string()),
+                )),
+            }
+        } else {
+            Ok(None)
+        }
+    }
+}
+
 /// 20.196 fo:hyphenation-keep
 ///
 /// See §7.15.1 of XSL.
@@ -879,6 +1074,29 @@ impl Display for TextAlign {
     }
 }
 
+impl ParseStyleAttr<TextAlign> for TextAlign {
+    fn parse_attr(attr: Option<&str>) -> Result<Option<TextAlign>, OdsError> {
+        if let Some(attr) = attr {
+            match attr {
+                "start" => Ok(Some(TextAlign::Start)),
+                "center" => Ok(Some(TextAlign::Center)),
+                "end" => Ok(Some(TextAlign::End)),
+                "justify" => Ok(Some(TextAlign::Justify)),
+                "inside" => Ok(Some(TextAlign::Inside)),
+                "outside" => Ok(Some(TextAlign::Outside)),
+                "left" => Ok(Some(TextAlign::Left)),
+                "right" => Ok(Some(TextAlign::Right)),
+                _ => Err(OdsError::Parse(
+                    "invalid text align",
+                    Some(attr.to_string()),
+                )),
+            }
+        } else {
+            Ok(None)
+        }
+    }
+}
+
 /// 20.224 fo:text-align-last
 ///
 /// See §7.15.10 of XSL.
@@ -998,6 +1216,23 @@ impl Display for WrapOption {
     }
 }
 
+impl ParseStyleAttr<WrapOption> for WrapOption {
+    fn parse_attr(attr: Option<&str>) -> Result<Option<WrapOption>, OdsError> {
+        if let Some(attr) = attr {
+            match attr {
+                "no-wrap" => Ok(Some(WrapOption::NoWrap)),
+                "wrap" => Ok(Some(WrapOption::Wrap)),
+                _ => Err(OdsError::Parse(
+                    "invalid wrap option",
+                    Some(attr.to_string()),
+                )),
+            }
+        } else {
+            Ok(None)
+        }
+    }
+}
+
 /// 20.253 style:cell-protect
 ///
 /// The style:cell-protect attribute specifies how a cell is protected.
@@ -1046,6 +1281,28 @@ impl Display for CellProtect {
     }
 }
 
+impl ParseStyleAttr<CellProtect> for CellProtect {
+    fn parse_attr(attr: Option<&str>) -> Result<Option<CellProtect>, OdsError> {
+        if let Some(attr) = attr {
+            match attr {
+                "formula-hidden" => Ok(Some(CellProtect::FormulaHidden)),
+                "hidden-and-protected" => Ok(Some(CellProtect::HiddenAndProtected)),
+                "none" => Ok(Some(CellProtect::None)),
+                "protected" => Ok(Some(CellProtect::Protected)),
+                "protected formula-hidden" | "formula-hidden protected" => {
+                    Ok(Some(CellProtect::ProtectedFormulaHidden))
+                }
+                _ => Err(OdsError::Parse(
+                    "invalid style:cell-protect",
+                    Some(attr.to_string()),
+                )),
+            }
+        } else {
+            Ok(None)
+        }
+    }
+}
+
 /// 20.263 style:direction
 ///
 /// The style:direction attribute specifies the direction of characters.
@@ -1268,7 +1525,7 @@ impl Display for PrintContent {
 ///   of rows.
 /// * ttb: create pages from the top row to the bottom row before continuing with the next set of
 ///   columns.
-#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+#[derive(Debug, Clone, Copy, Eq, PartialEq, GetSize)]
 #[allow(missing_docs)]
 pub enum PrintOrder {
     Ltr,
@@ -1308,6 +1565,44 @@ impl Display for PrintOrientation {
     }
 }
 
+/// Common paper sizes. Used with
+/// [PageStyle::set_paper](crate::style::PageStyle::set_paper).
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Paper {
+    /// 297mm x 420mm
+    A3,
+    /// 210mm x 297mm
+    A4,
+    /// 148mm x 210mm
+    A5,
+    /// 250mm x 353mm
+    B4,
+    /// 176mm x 250mm
+    B5,
+    /// 8.5in x 11in
+    Letter,
+    /// 8.5in x 14in
+    Legal,
+    /// 11in x 17in
+    Tabloid,
+}
+
+impl Paper {
+    /// Width and height in portrait orientation.
+    pub fn size(&self) -> (Length, Length) {
+        match self {
+            Paper::A3 => (Length::Mm(297.0), Length::Mm(420.0)),
+            Paper::A4 => (Length::Mm(210.0), Length::Mm(297.0)),
+            Paper::A5 => (Length::Mm(148.0), Length::Mm(210.0)),
+            Paper::B4 => (Length::Mm(250.0), Length::Mm(353.0)),
+            Paper::B5 => (Length::Mm(176.0), Length::Mm(250.0)),
+            Paper::Letter => (Length::In(8.5), Length::In(11.0)),
+            Paper::Legal => (Length::In(8.5), Length::In(14.0)),
+            Paper::Tabloid => (Length::In(11.0), Length::In(17.0)),
+        }
+    }
+}
+
 /// 20.335 style:punctuation-wrap
 ///
 /// The style:punctuation-wrap attribute specifies whether a punctuation mark, if one is
@@ -1571,6 +1866,65 @@ impl Display for TextEmphasizePosition {
     }
 }
 
+/// 20.344 style:ruby-align
+///
+/// The style:ruby-align attribute specifies the alignment of the ruby text relative to the
+/// ruby base text.
+///
+/// The defined values for the style:ruby-align attribute are:
+/// * center: ruby text is centered.
+/// * distribute-letter: ruby text is distributed across the base text, with no space
+///   before the first and after the last character.
+/// * distribute-space: ruby text is distributed across the base text, with half a
+///   character space before the first and after the last character.
+/// * left: ruby text is left aligned.
+/// * right: ruby text is right aligned.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+#[allow(missing_docs)]
+pub enum RubyAlign {
+    Center,
+    DistributeLetter,
+    DistributeSpace,
+    Left,
+    Right,
+}
+
+impl Display for RubyAlign {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), std::fmt::Error> {
+        match self {
+            RubyAlign::Center => write!(f, "center"),
+            RubyAlign::DistributeLetter => write!(f, "distribute-letter"),
+            RubyAlign::DistributeSpace => write!(f, "distribute-space"),
+            RubyAlign::Left => write!(f, "left"),
+            RubyAlign::Right => write!(f, "right"),
+        }
+    }
+}
+
+/// 20.345 style:ruby-position
+///
+/// The style:ruby-position attribute specifies whether the ruby text is placed above or
+/// below the ruby base text.
+///
+/// The defined values for the style:ruby-position attribute are:
+/// * above: ruby text is placed above the base text.
+/// * below: ruby text is placed below the base text.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+#[allow(missing_docs)]
+pub enum RubyPosition {
+    Above,
+    Below,
+}
+
+impl Display for RubyPosition {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), std::fmt::Error> {
+        match self {
+            RubyPosition::Above => write!(f, "above"),
+            RubyPosition::Below => write!(f, "below"),
+        }
+    }
+}
+
 /// Line modes for underline, overline, line-through.
 ///
 /// 20.372 style:text-line-through-mode
@@ -1894,6 +2248,25 @@ impl Display for CellAlignVertical {
     }
 }
 
+impl ParseStyleAttr<CellAlignVertical> for CellAlignVertical {
+    fn parse_attr(attr: Option<&str>) -> Result<Option<CellAlignVertical>, OdsError> {
+        if let Some(attr) = attr {
+            match attr {
+                "top" => Ok(Some(CellAlignVertical::Top)),
+                "middle" => Ok(Some(CellAlignVertical::Middle)),
+                "bottom" => Ok(Some(CellAlignVertical::Bottom)),
+                "automatic" => Ok(Some(CellAlignVertical::Automatic)),
+                _ => Err(OdsError::Parse(
+                    "invalid vertical align",
+                    Some(attr.to_string()),
+                )),
+            }
+        } else {
+            Ok(None)
+        }
+    }
+}
+
 /// 20.404 style:writing-mode
 ///
 /// See §7.27.7 of XSL with the additional value of page.
@@ -1930,6 +2303,29 @@ impl Display for WritingMode {
     }
 }
 
+impl ParseStyleAttr<WritingMode> for WritingMode {
+    fn parse_attr(attr: Option<&str>) -> Result<Option<WritingMode>, OdsError> {
+        if let Some(attr) = attr {
+            match attr {
+                "lr-tb" => Ok(Some(WritingMode::LrTb)),
+                "rl-tb" => Ok(Some(WritingMode::RlTb)),
+                "tb-rl" => Ok(Some(WritingMode::TbRl)),
+                "tb-lr" => Ok(Some(WritingMode::TbLr)),
+                "lr" => Ok(Some(WritingMode::Lr)),
+                "rl" => Ok(Some(WritingMode::Rl)),
+                "tb" => Ok(Some(WritingMode::Tb)),
+                "page" => Ok(Some(WritingMode::Page)),
+                _ => Err(OdsError::Parse(
+                    "invalid writing mode",
+                    Some(attr.to_string()),
+                )),
+            }
+        } else {
+            Ok(None)
+        }
+    }
+}
+
 /// 20.414 table:align
 ///
 /// The table:align attribute specifies the horizontal alignment of a table.
@@ -2035,3 +2431,98 @@ impl Display for TextDisplay {
         }
     }
 }
+
+/// draw:fill
+///
+/// The draw:fill attribute specifies the fill style for a graphic object.
+///
+/// The defined values for the draw:fill attribute are:
+/// * bitmap: the drawing object is filled with the bitmap specified by the draw:fill-image-name
+///   attribute.
+/// * gradient: the drawing object is filled with the gradient specified by the draw:fill-gradient-name
+///   attribute.
+/// * hatch: the drawing object is filled with the hatch specified by the draw:fill-hatch-name attribute.
+/// * none: the drawing object is not filled.
+/// * solid: the drawing object is filled with the color specified by the draw:fill-color attribute.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[allow(missing_docs)]
+pub enum FillStyle {
+    None,
+    Solid,
+    Bitmap,
+    Gradient,
+    Hatch,
+}
+
+impl Display for FillStyle {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FillStyle::None => write!(f, "none"),
+            FillStyle::Solid => write!(f, "solid"),
+            FillStyle::Bitmap => write!(f, "bitmap"),
+            FillStyle::Gradient => write!(f, "gradient"),
+            FillStyle::Hatch => write!(f, "hatch"),
+        }
+    }
+}
+
+impl ParseStyleAttr<FillStyle> for FillStyle {
+    fn parse_attr(attr: Option<&str>) -> Result<Option<FillStyle>, OdsError> {
+        if let Some(attr) = attr {
+            match attr {
+                "none" => Ok(Some(FillStyle::None)),
+                "solid" => Ok(Some(FillStyle::Solid)),
+                "bitmap" => Ok(Some(FillStyle::Bitmap)),
+                "gradient" => Ok(Some(FillStyle::Gradient)),
+                "hatch" => Ok(Some(FillStyle::Hatch)),
+                _ => Err(OdsError::Parse("invalid draw:fill", Some(attr.to_string()))),
+            }
+        } else {
+            Ok(None)
+        }
+    }
+}
+
+/// draw:stroke
+///
+/// The draw:stroke attribute specifies the style of the stroke on the current object.
+///
+/// The defined values for the draw:stroke attribute are:
+/// * dash: a dashed line, the dash is specified by the draw:stroke-dash attribute.
+/// * none: no stroke is drawn.
+/// * solid: a solid line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[allow(missing_docs)]
+pub enum StrokeStyle {
+    None,
+    Solid,
+    Dash,
+}
+
+impl Display for StrokeStyle {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StrokeStyle::None => write!(f, "none"),
+            StrokeStyle::Solid => write!(f, "solid"),
+            StrokeStyle::Dash => write!(f, "dash"),
+        }
+    }
+}
+
+impl ParseStyleAttr<StrokeStyle> for StrokeStyle {
+    fn parse_attr(attr: Option<&str>) -> Result<Option<StrokeStyle>, OdsError> {
+        if let Some(attr) = attr {
+            match attr {
+                "none" => Ok(Some(StrokeStyle::None)),
+                "solid" => Ok(Some(StrokeStyle::Solid)),
+                "dash" => Ok(Some(StrokeStyle::Dash)),
+                _ => Err(OdsError::Parse(
+                    "invalid draw:stroke",
+                    Some(attr.to_string()),
+                )),
+            }
+        } else {
+            Ok(None)
+        }
+    }
+}
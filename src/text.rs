@@ -23,7 +23,7 @@
 //! ```
 //!
 
-use crate::style::{ParagraphStyleRef, TextStyleRef};
+use crate::style::{ParagraphStyleRef, RubyStyleRef, TextStyleRef};
 use crate::xmltree::{XmlContent, XmlTag};
 use std::fmt::{Display, Formatter};
 
@@ -233,6 +233,39 @@ impl TextA {
     }
 }
 
+// The <text:ruby> element represents ruby text. Ruby text is a commentary or annotation for a base text,
+// as it is commonly used in East Asian typography. 6.4.13
+//
+// The <text:ruby> element contains a <text:ruby-base> and a <text:ruby-text> element.
+text_tag!(TextRuby, "text:ruby");
+
+// text:style-name 19.874.30.
+impl TextRuby {
+    /// The text:style-name attribute specifies a style for the ruby, which shall be a style
+    /// with family ruby.
+    pub fn style_name(mut self, name: &RubyStyleRef) -> Self {
+        self.xml
+            .set_attr("text:style-name", name.as_str().to_string());
+        self
+    }
+}
+
+// The <text:ruby-base> element contains the text that is to be annotated. 6.4.14
+text_tag!(TextRubyBase, "text:ruby-base");
+
+// The <text:ruby-text> element contains the annotation text. 6.4.15
+text_tag!(TextRubyText, "text:ruby-text");
+
+// text:style-name 19.874.31.
+impl TextRubyText {
+    /// The text:style-name attribute specifies a text style for the ruby text.
+    pub fn style_name(mut self, name: &TextStyleRef) -> Self {
+        self.xml
+            .set_attr("text:style-name", name.as_str().to_string());
+        self
+    }
+}
+
 // The <text:s> element is used to represent the [UNICODE] character “ “ (U+0020, SPACE).
 // This element shall be used to represent the second and all following “ “ (U+0020, SPACE)
 // characters in a sequence of “ “ (U+0020, SPACE) characters.
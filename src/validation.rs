@@ -6,15 +6,15 @@ use get_size2::GetSize;
 use std::convert::TryFrom;
 use std::fmt::{Display, Formatter};
 
-use crate::condition::Condition;
+use crate::condition::{Condition, ValidationCondition, Value};
 use crate::style::AnyStyleRef;
 use crate::text::TextTag;
-use crate::{CellRef, OdsError};
+use crate::{CellRange, CellRef, OdsError};
 use std::borrow::Borrow;
 use std::str::from_utf8;
 
 /// This defines how lists of entries are displayed to the user.
-#[derive(Copy, Clone, Debug, Default, GetSize)]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, GetSize)]
 pub enum ValidationDisplay {
     /// Don't show.
     NoDisplay,
@@ -246,6 +246,41 @@ impl Validation {
         }
     }
 
+    /// Validation that only allows the given values.
+    /// The values are shown as a dropdown list.
+    ///
+    /// ```
+    /// use spreadsheet_ods::validation::Validation;
+    /// use spreadsheet_ods::{Sheet, WorkBook};
+    ///
+    /// let mut book = WorkBook::new_empty();
+    /// let valid = book.add_validation(Validation::list_of(&["red", "green", "blue"]));
+    ///
+    /// let mut sheet = Sheet::new("one");
+    /// sheet.set_validation(0, 0, &valid);
+    /// book.push_sheet(sheet);
+    /// ```
+    pub fn list_of<'a, V>(values: &'a [V]) -> Self
+    where
+        Value: From<&'a V>,
+    {
+        let mut valid = Self::new();
+        valid.set_condition(Condition::content_is_in_list(values));
+        valid
+    }
+
+    /// Validation that only allows the values found in the cell-range.
+    /// The values are shown as a dropdown list.
+    ///
+    /// The range is converted to an absolute reference, otherwise it
+    /// would move relative to each cell the validation is applied to.
+    /// Use a range with a table-name if the values are in another sheet.
+    pub fn list_from_range(range: &CellRange) -> Self {
+        let mut valid = Self::new();
+        valid.set_condition(Condition::content_is_in_cellrange(range.clone().absolute()));
+        valid
+    }
+
     /// Validation name.
     pub fn set_name<S: AsRef<str>>(&mut self, name: S) {
         self.name = name.as_ref().to_string();
@@ -262,8 +297,14 @@ impl Validation {
     }
 
     /// Sets the condition that is checked for new values.
-    pub fn set_condition(&mut self, cond: Condition) {
-        self.condition = cond;
+    /// This can be a [Condition] or a [ValidationCondition].
+    pub fn set_condition<C: Into<Condition>>(&mut self, cond: C) {
+        self.condition = cond.into();
+    }
+
+    /// Condition for new values in its typed form.
+    pub fn typed_condition(&self) -> Result<ValidationCondition, OdsError> {
+        self.condition.parse()
     }
 
     /// Condition for new values.
@@ -303,6 +344,25 @@ impl Validation {
         self.display_list
     }
 
+    /// Shows the choices as dropdown list. The sort order is kept
+    /// if it is already shown.
+    pub fn set_dropdown(&mut self, dropdown: bool) {
+        match (dropdown, self.display_list) {
+            (true, ValidationDisplay::NoDisplay) => {
+                self.display_list = ValidationDisplay::Unsorted;
+            }
+            (false, _) => {
+                self.display_list = ValidationDisplay::NoDisplay;
+            }
+            _ => {}
+        }
+    }
+
+    /// Shows the choices as dropdown list.
+    pub fn dropdown(&self) -> bool {
+        self.display_list != ValidationDisplay::NoDisplay
+    }
+
     /// Error message.
     pub fn set_err(&mut self, err: Option<ValidationError>) {
         self.err = err;
@@ -2,11 +2,16 @@ use std::borrow::Cow;
 
 use chrono::{Duration, NaiveDate, NaiveDateTime, NaiveTime};
 use get_size2::GetSize;
+#[cfg(feature = "rust_decimal")]
 use rust_decimal::prelude::FromPrimitive;
+#[cfg(feature = "rust_decimal")]
 use rust_decimal::prelude::ToPrimitive;
+#[cfg(feature = "rust_decimal")]
 use rust_decimal::Decimal;
 
+use crate::style::CellStyleRef;
 use crate::text::TextTag;
+use crate::OdsError;
 
 /// Datatypes for the values. Only the discriminants of the Value enum.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, GetSize)]
@@ -20,7 +25,10 @@ pub enum ValueType {
     Text,
     TextXml,
     DateTime,
+    Date,
+    Time,
     TimeDuration,
+    Error,
 }
 
 /// Content-Values
@@ -31,12 +39,23 @@ pub enum Value {
     Empty,
     Boolean(bool),
     Number(f64),
+    /// Number that keeps the exact decimal value. Written as float.
+    #[cfg(feature = "rust_decimal")]
+    Decimal(Decimal),
     Percentage(f64),
     Currency(f64, Box<str>),
     Text(String),
     TextXml(Vec<TextTag>),
     DateTime(NaiveDateTime),
+    /// Date without time. Written without the time part.
+    Date(NaiveDate),
+    /// Time of day. Written as office:time-value like a TimeDuration,
+    /// and read as TimeDuration too.
+    Time(NaiveTime),
     TimeDuration(Duration),
+    /// Error result of a formula, e.g. "#DIV/0!" or "#N/A".
+    /// Written as calcext:value-type="error".
+    Error(String),
 }
 
 impl GetSize for Value {
@@ -45,12 +64,17 @@ impl GetSize for Value {
             Value::Empty => 0,
             Value::Boolean(_) => 0,
             Value::Number(_) => 0,
+            #[cfg(feature = "rust_decimal")]
+            Value::Decimal(_) => 0,
             Value::Percentage(_) => 0,
             Value::Currency(_, v) => v.get_heap_size(),
             Value::Text(v) => v.get_heap_size(),
             Value::TextXml(v) => v.get_heap_size(),
             Value::DateTime(_) => 0,
+            Value::Date(_) => 0,
+            Value::Time(_) => 0,
             Value::TimeDuration(_) => 0,
+            Value::Error(v) => v.get_heap_size(),
         }
     }
 }
@@ -62,12 +86,17 @@ impl Value {
             Value::Empty => ValueType::Empty,
             Value::Boolean(_) => ValueType::Boolean,
             Value::Number(_) => ValueType::Number,
+            #[cfg(feature = "rust_decimal")]
+            Value::Decimal(_) => ValueType::Number,
             Value::Percentage(_) => ValueType::Percentage,
             Value::Currency(_, _) => ValueType::Currency,
             Value::Text(_) => ValueType::Text,
             Value::TextXml(_) => ValueType::TextXml,
             Value::TimeDuration(_) => ValueType::TimeDuration,
             Value::DateTime(_) => ValueType::DateTime,
+            Value::Date(_) => ValueType::Date,
+            Value::Time(_) => ValueType::Time,
+            Value::Error(_) => ValueType::Error,
         }
     }
 
@@ -96,6 +125,8 @@ impl Value {
     pub fn as_i64_opt(&self) -> Option<i64> {
         match self {
             Value::Number(n) => Some(*n as i64),
+            #[cfg(feature = "rust_decimal")]
+            Value::Decimal(d) => d.to_i64(),
             Value::Percentage(p) => Some(*p as i64),
             Value::Currency(v, _) => Some(*v as i64),
             _ => None,
@@ -119,6 +150,8 @@ impl Value {
     pub fn as_u64_opt(&self) -> Option<u64> {
         match self {
             Value::Number(n) => Some(*n as u64),
+            #[cfg(feature = "rust_decimal")]
+            Value::Decimal(d) => d.to_u64(),
             Value::Percentage(p) => Some(*p as u64),
             Value::Currency(v, _) => Some(*v as u64),
             _ => None,
@@ -142,6 +175,8 @@ impl Value {
     pub fn as_i32_opt(&self) -> Option<i32> {
         match self {
             Value::Number(n) => Some(*n as i32),
+            #[cfg(feature = "rust_decimal")]
+            Value::Decimal(d) => d.to_i32(),
             Value::Percentage(p) => Some(*p as i32),
             Value::Currency(v, _) => Some(*v as i32),
             _ => None,
@@ -165,6 +200,8 @@ impl Value {
     pub fn as_u32_opt(&self) -> Option<u32> {
         match self {
             Value::Number(n) => Some(*n as u32),
+            #[cfg(feature = "rust_decimal")]
+            Value::Decimal(d) => d.to_u32(),
             Value::Percentage(p) => Some(*p as u32),
             Value::Currency(v, _) => Some(*v as u32),
             _ => None,
@@ -188,6 +225,8 @@ impl Value {
     pub fn as_i16_opt(&self) -> Option<i16> {
         match self {
             Value::Number(n) => Some(*n as i16),
+            #[cfg(feature = "rust_decimal")]
+            Value::Decimal(d) => d.to_i16(),
             Value::Percentage(p) => Some(*p as i16),
             Value::Currency(v, _) => Some(*v as i16),
             _ => None,
@@ -211,6 +250,8 @@ impl Value {
     pub fn as_u16_opt(&self) -> Option<u16> {
         match self {
             Value::Number(n) => Some(*n as u16),
+            #[cfg(feature = "rust_decimal")]
+            Value::Decimal(d) => d.to_u16(),
             Value::Percentage(p) => Some(*p as u16),
             Value::Currency(v, _) => Some(*v as u16),
             _ => None,
@@ -234,6 +275,8 @@ impl Value {
     pub fn as_i8_opt(&self) -> Option<i8> {
         match self {
             Value::Number(n) => Some(*n as i8),
+            #[cfg(feature = "rust_decimal")]
+            Value::Decimal(d) => d.to_i8(),
             Value::Percentage(p) => Some(*p as i8),
             Value::Currency(v, _) => Some(*v as i8),
             _ => None,
@@ -257,6 +300,8 @@ impl Value {
     pub fn as_u8_opt(&self) -> Option<u8> {
         match self {
             Value::Number(n) => Some(*n as u8),
+            #[cfg(feature = "rust_decimal")]
+            Value::Decimal(d) => d.to_u8(),
             Value::Percentage(p) => Some(*p as u8),
             Value::Currency(v, _) => Some(*v as u8),
             _ => None,
@@ -283,6 +328,7 @@ impl Value {
     pub fn as_decimal_opt(&self) -> Option<Decimal> {
         match self {
             Value::Number(n) => Decimal::from_f64(*n),
+            Value::Decimal(d) => Some(*d),
             Value::Currency(v, _) => Decimal::from_f64(*v),
             Value::Percentage(p) => Decimal::from_f64(*p),
             _ => None,
@@ -306,6 +352,8 @@ impl Value {
     pub fn as_f64_opt(&self) -> Option<f64> {
         match self {
             Value::Number(n) => Some(*n),
+            #[cfg(feature = "rust_decimal")]
+            Value::Decimal(d) => d.to_f64(),
             Value::Currency(v, _) => Some(*v),
             Value::Percentage(p) => Some(*p),
             _ => None,
@@ -322,6 +370,41 @@ impl Value {
         self.as_str_opt().unwrap_or(d)
     }
 
+    /// Returns the paragraphs of a text value. A plain text is split
+    /// at '\n', for markup text this is the text of each paragraph.
+    /// Other values have no paragraphs.
+    pub fn paragraphs(&self) -> Vec<String> {
+        match self {
+            Value::Text(s) => s.split('\n').map(|v| v.to_string()).collect(),
+            Value::TextXml(v) => v
+                .iter()
+                .map(|t| {
+                    let mut buf = String::new();
+                    t.extract_text(&mut buf);
+                    buf
+                })
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Creates a text value from a list of paragraphs. They are joined
+    /// with '\n' and written as one text:p each.
+    pub fn from_paragraphs<I, S>(paragraphs: I) -> Value
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let mut buf = String::new();
+        for (i, p) in paragraphs.into_iter().enumerate() {
+            if i > 0 {
+                buf.push('\n');
+            }
+            buf.push_str(p.as_ref());
+        }
+        Value::Text(buf)
+    }
+
     /// Return the content as str if the value is text or markup text.
     /// When the cell contains markup all the markup is removed, but
     /// line-breaks are kept as \n.
@@ -380,10 +463,17 @@ impl Value {
     pub fn as_timeduration_opt(&self) -> Option<Duration> {
         match self {
             Value::TimeDuration(td) => Some(*td),
+            Value::Time(t) => Some(*t - NaiveTime::MIN),
             _ => None,
         }
     }
 
+    /// Return the content as std::time::Duration if the value is a
+    /// TimeDuration that is not negative.
+    pub fn as_std_duration_opt(&self) -> Option<std::time::Duration> {
+        self.as_timeduration_opt().and_then(|v| v.to_std().ok())
+    }
+
     /// Return the content as NaiveDateTime if the value is a DateTime.
     /// Default otherwise.
     pub fn as_datetime_or_default(&self) -> NaiveDateTime {
@@ -401,31 +491,75 @@ impl Value {
     pub fn as_datetime_opt(&self) -> Option<NaiveDateTime> {
         match self {
             Value::DateTime(dt) => Some(*dt),
+            Value::Date(d) => Some(d.and_time(NaiveTime::MIN)),
+            Value::Time(t) => Some(NaiveDateTime::new(
+                NaiveDate::from_ymd_opt(1899, 12, 30).expect("valid date"),
+                *t,
+            )),
             _ => None,
         }
     }
 
-    /// Return the content as NaiveDate if the value is a DateTime.
+    /// Return the content as NaiveDate if the value is a Date or a DateTime.
     /// Default otherwise.
     pub fn as_date_or_default(&self) -> NaiveDate {
         self.as_date_opt().unwrap_or_default()
     }
 
-    /// Return the content as NaiveDate if the value is a DateTime.
+    /// Return the content as NaiveDate if the value is a Date or a DateTime.
     /// Default otherwise.
     pub fn as_date_or(&self, d: NaiveDate) -> NaiveDate {
         self.as_date_opt().unwrap_or(d)
     }
 
-    /// Return the content as an optional NaiveDateTime if the value is
-    /// a DateTime.
+    /// Return the content as an optional NaiveDate if the value is
+    /// a Date or a DateTime.
     pub fn as_date_opt(&self) -> Option<NaiveDate> {
         match self {
             Value::DateTime(dt) => Some(dt.date()),
+            Value::Date(d) => Some(*d),
+            _ => None,
+        }
+    }
+
+    /// Return the content as NaiveTime if the value is a Time, a DateTime
+    /// or a TimeDuration of less than a day. Default otherwise.
+    pub fn as_time_or_default(&self) -> NaiveTime {
+        self.as_time_opt().unwrap_or_default()
+    }
+
+    /// Return the content as NaiveTime if the value is a Time, a DateTime
+    /// or a TimeDuration of less than a day. Default otherwise.
+    pub fn as_time_or(&self, d: NaiveTime) -> NaiveTime {
+        self.as_time_opt().unwrap_or(d)
+    }
+
+    /// Return the content as an optional NaiveTime if the value is a Time,
+    /// a DateTime or a TimeDuration of less than a day.
+    pub fn as_time_opt(&self) -> Option<NaiveTime> {
+        match self {
+            Value::Time(t) => Some(*t),
+            Value::DateTime(dt) => Some(dt.time()),
+            Value::TimeDuration(td) if *td >= Duration::zero() && *td < Duration::days(1) => {
+                Some(NaiveTime::MIN + *td)
+            }
+            _ => None,
+        }
+    }
+
+    /// Return the error code if the value is an Error.
+    pub fn as_error_opt(&self) -> Option<&str> {
+        match self {
+            Value::Error(e) => Some(e.as_str()),
             _ => None,
         }
     }
 
+    /// Is this an error value.
+    pub fn is_error(&self) -> bool {
+        matches!(self, Value::Error(_))
+    }
+
     /// Returns the currency code or "" if the value is not a currency.
     pub fn currency(&self) -> &str {
         match self {
@@ -444,6 +578,12 @@ impl Value {
     pub fn new_percentage(value: f64) -> Self {
         Value::Percentage(value)
     }
+
+    /// Create an error value. The code is what the application
+    /// shows in the cell, e.g. "#DIV/0!".
+    pub fn new_error<S: Into<String>>(code: S) -> Self {
+        Value::Error(code.into())
+    }
 }
 
 /// currency value
@@ -462,6 +602,54 @@ macro_rules! percent {
     };
 }
 
+/// Conversion of user-defined types into a Value.
+///
+/// Besides the plain value a type can name its preferred cell-style.
+/// ODS attaches value formats via cell styles only, so the preferred
+/// ValueFormat is given as a CellStyle that uses this format.
+/// The style must be added to the WorkBook separately.
+///
+/// Sheet::set_value() applies the style if the cell has no style yet.
+/// Sheet::set_styled_value() always uses the given style.
+///
+/// This trait is implemented for everything that implements `Into<Value>`.
+///
+/// ```
+/// use spreadsheet_ods::{CellStyleRef, IntoValue, Sheet, Value};
+///
+/// struct Money(f64);
+///
+/// impl IntoValue for Money {
+///     fn into_value(self) -> Value {
+///         Value::new_currency("EUR", self.0)
+///     }
+///
+///     fn cell_style(&self) -> Option<CellStyleRef> {
+///         Some(CellStyleRef::from("money"))
+///     }
+/// }
+///
+/// let mut sh = Sheet::new("1");
+/// sh.set_value(0, 0, Money(12.5));
+/// assert_eq!(sh.value(0, 0), &Value::new_currency("EUR", 12.5));
+/// assert_eq!(sh.cellstyle(0, 0), Some(&CellStyleRef::from("money")));
+/// ```
+pub trait IntoValue {
+    /// Converts to a Value.
+    fn into_value(self) -> Value;
+
+    /// Preferred cell-style for the value.
+    fn cell_style(&self) -> Option<CellStyleRef> {
+        None
+    }
+}
+
+impl<T: Into<Value>> IntoValue for T {
+    fn into_value(self) -> Value {
+        self.into()
+    }
+}
+
 impl From<()> for Value {
     fn from(_: ()) -> Self {
         Value::Empty
@@ -531,7 +719,7 @@ impl From<Option<String>> for Value {
 #[cfg(feature = "rust_decimal")]
 impl From<Decimal> for Value {
     fn from(f: Decimal) -> Self {
-        Value::Number(f.to_f64().expect("decimal->f64 should not fail"))
+        Value::Decimal(f)
     }
 }
 
@@ -539,7 +727,7 @@ impl From<Decimal> for Value {
 impl From<Option<Decimal>> for Value {
     fn from(f: Option<Decimal>) -> Self {
         if let Some(f) = f {
-            Value::Number(f.to_f64().expect("decimal->f64 should not fail"))
+            Value::Decimal(f)
         } else {
             Value::Empty
         }
@@ -631,14 +819,14 @@ impl From<Option<NaiveDateTime>> for Value {
 
 impl From<NaiveDate> for Value {
     fn from(dt: NaiveDate) -> Self {
-        Value::DateTime(dt.and_hms_opt(0, 0, 0).unwrap())
+        Value::Date(dt)
     }
 }
 
 impl From<Option<NaiveDate>> for Value {
     fn from(dt: Option<NaiveDate>) -> Self {
         if let Some(dt) = dt {
-            Value::DateTime(dt.and_hms_opt(0, 0, 0).expect("valid time"))
+            Value::Date(dt)
         } else {
             Value::Empty
         }
@@ -647,20 +835,14 @@ impl From<Option<NaiveDate>> for Value {
 
 impl From<NaiveTime> for Value {
     fn from(ti: NaiveTime) -> Self {
-        Value::DateTime(NaiveDateTime::new(
-            NaiveDate::from_ymd_opt(1899, 12, 30).expect("valid date"),
-            ti,
-        ))
+        Value::Time(ti)
     }
 }
 
 impl From<Option<NaiveTime>> for Value {
     fn from(dt: Option<NaiveTime>) -> Self {
         if let Some(ti) = dt {
-            Value::DateTime(NaiveDateTime::new(
-                NaiveDate::from_ymd_opt(1899, 12, 30).expect("valid date"),
-                ti,
-            ))
+            Value::Time(ti)
         } else {
             Value::Empty
         }
@@ -673,6 +855,13 @@ impl From<Duration> for Value {
     }
 }
 
+/// Durations beyond the range of chrono::Duration are capped.
+impl From<std::time::Duration> for Value {
+    fn from(d: std::time::Duration) -> Self {
+        Value::TimeDuration(Duration::from_std(d).unwrap_or(Duration::MAX))
+    }
+}
+
 impl From<Option<Duration>> for Value {
     fn from(d: Option<Duration>) -> Self {
         if let Some(d) = d {
@@ -682,3 +871,62 @@ impl From<Option<Duration>> for Value {
         }
     }
 }
+
+/// Error for the TryFrom conversions.
+fn conversion_error(value: &Value, target: &str) -> OdsError {
+    OdsError::Ods(format!(
+        "Can't convert {:?} to {}",
+        value.value_type(),
+        target
+    ))
+}
+
+macro_rules! try_from_value {
+    ($l:ty, $opt:ident) => {
+        impl TryFrom<&Value> for $l {
+            type Error = OdsError;
+
+            fn try_from(value: &Value) -> Result<Self, Self::Error> {
+                value
+                    .$opt()
+                    .ok_or_else(|| conversion_error(value, stringify!($l)))
+            }
+        }
+    };
+}
+
+try_from_value!(f64, as_f64_opt);
+try_from_value!(i64, as_i64_opt);
+try_from_value!(u64, as_u64_opt);
+try_from_value!(i32, as_i32_opt);
+try_from_value!(u32, as_u32_opt);
+try_from_value!(NaiveDateTime, as_datetime_opt);
+try_from_value!(NaiveDate, as_date_opt);
+try_from_value!(NaiveTime, as_time_opt);
+try_from_value!(Duration, as_timeduration_opt);
+try_from_value!(std::time::Duration, as_std_duration_opt);
+#[cfg(feature = "rust_decimal")]
+try_from_value!(Decimal, as_decimal_opt);
+
+impl TryFrom<&Value> for bool {
+    type Error = OdsError;
+
+    fn try_from(value: &Value) -> Result<Self, Self::Error> {
+        match value {
+            Value::Boolean(v) => Ok(*v),
+            _ => Err(conversion_error(value, "bool")),
+        }
+    }
+}
+
+/// Markup text is converted as with [Value::as_cow_str_or].
+impl TryFrom<&Value> for String {
+    type Error = OdsError;
+
+    fn try_from(value: &Value) -> Result<Self, Self::Error> {
+        match value {
+            Value::Text(_) | Value::TextXml(_) => Ok(value.as_cow_str_or("").into_owned()),
+            _ => Err(conversion_error(value, "String")),
+        }
+    }
+}
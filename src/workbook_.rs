@@ -2,36 +2,48 @@
 //! Workbook
 //!
 
+use crate::attrmap2::AttrMap2;
 use get_size2::GetSize;
 use std::borrow::Borrow;
+use std::cmp::max;
+use std::collections::HashSet;
 use std::fmt;
 use std::fmt::Formatter;
 use std::hash::Hash;
 
+use chrono::NaiveDate;
 use icu_locid::{locale, Locale};
 
+use crate::basic::{BasicLibrary, BasicModule};
+use crate::condition::Condition;
 use crate::config::Config;
+use crate::dde::DdeLink;
 use crate::defaultstyles::{DefaultFormat, DefaultStyle};
+use crate::draw::{DrawFrame, DrawFrameContent, DrawImage, DrawObject};
 use crate::ds::detach::{Detach, Detached};
-use crate::format::ValueFormatTrait;
-use crate::io::read::default_settings;
+use crate::format::{ValueFormatTrait, ValueStyleMap};
+use crate::formula::{expand_range_names, external_refs, rename_table_refs, rewrite_external_refs};
+use crate::io::read::{default_settings, read_package_parts};
 use crate::io::NamespaceMap;
 use crate::manifest::Manifest;
 use crate::metadata::Metadata;
-use crate::sheet_::Sheet;
+use crate::sheet_::{insert_rows_refs, value_text, Sheet};
+use crate::style::stylemap::StyleMap;
+use crate::style::units::BorderLine;
 use crate::style::{
-    ColStyle, ColStyleRef, FontFaceDecl, GraphicStyle, GraphicStyleRef, MasterPage, MasterPageRef,
-    PageStyle, PageStyleRef, ParagraphStyle, ParagraphStyleRef, RowStyle, RowStyleRef, RubyStyle,
-    RubyStyleRef, TableStyle, TableStyleRef, TextStyle, TextStyleRef,
+    ColStyle, ColStyleRef, FontFaceDecl, FontFaceUri, GraphicStyle, GraphicStyleRef, HeaderFooter,
+    MasterPage, MasterPageRef, PageStyle, PageStyleRef, ParagraphStyle, ParagraphStyleRef,
+    RowStyle, RowStyleRef, RubyStyle, RubyStyleRef, StyleFamily, StyleOrigin, StyleUse, TableStyle,
+    TableStyleRef, TextStyle, TextStyleRef, Theme,
 };
 use crate::validation::{Validation, ValidationRef};
-use crate::value_::ValueType;
-use crate::xlink::{XLinkActuate, XLinkType};
+use crate::value_::{Value, ValueType};
+use crate::xlink::{XLinkActuate, XLinkShow, XLinkType};
 use crate::xmltree::{XmlContent, XmlTag};
 use crate::{
-    locale, CellStyle, CellStyleRef, HashMap, ValueFormatBoolean, ValueFormatCurrency,
-    ValueFormatDateTime, ValueFormatNumber, ValueFormatPercentage, ValueFormatRef, ValueFormatText,
-    ValueFormatTimeDuration,
+    locale, CellRange, CellRef, CellStyle, CellStyleRef, HashMap, OdsError, ValueFormatBoolean,
+    ValueFormatCurrency, ValueFormatDateTime, ValueFormatNumber, ValueFormatPercentage,
+    ValueFormatRef, ValueFormatText, ValueFormatTimeDuration,
 };
 
 /// Book is the main structure for the Spreadsheet.
@@ -46,11 +58,16 @@ pub struct WorkBook {
     /// FontDecl hold the style:font-face elements
     pub(crate) fonts: HashMap<String, FontFaceDecl>,
 
+    /// Document theme.
+    pub(crate) theme: Option<Theme>,
+
     /// Auto-Styles. Maps the prefix to a number.
     pub(crate) autonum: HashMap<String, u32>,
 
     /// Scripts
     pub(crate) scripts: Vec<Script>,
+    /// Basic libraries from the Basic/ directory.
+    pub(crate) basic: Vec<BasicLibrary>,
     pub(crate) event_listener: HashMap<String, EventListener>,
 
     /// Styles hold the style:style elements.
@@ -89,6 +106,10 @@ pub struct WorkBook {
     pub(crate) config: Detach<Config>,
     /// User modifiable config.
     pub(crate) workbook_config: WorkBookConfig,
+    /// table:calculation-settings
+    pub(crate) calc_settings: CalcSettings,
+    /// table:dde-links
+    pub(crate) dde_links: Vec<DdeLink>,
     /// Keeps all the namespaces.
     pub(crate) xmlns: HashMap<String, NamespaceMap>,
 
@@ -111,6 +132,9 @@ impl fmt::Debug for WorkBook {
         for s in self.fonts.values() {
             writeln!(f, "{:?}", s)?;
         }
+        if let Some(theme) = &self.theme {
+            writeln!(f, "{:?}", theme)?;
+        }
         for s in self.tablestyles.values() {
             writeln!(f, "{:?}", s)?;
         }
@@ -169,6 +193,9 @@ impl fmt::Debug for WorkBook {
             writeln!(f, "{:?}", s)?;
         }
         writeln!(f, "{:?}", &self.workbook_config)?;
+        for v in &self.basic {
+            writeln!(f, "{:?}", v)?;
+        }
         for v in self.manifest.values() {
             writeln!(f, "extras {:?}", v)?;
         }
@@ -180,6 +207,48 @@ impl fmt::Debug for WorkBook {
     }
 }
 
+/// Key for comparing attributes regardless of their order.
+fn attr_key(buf: &mut String, attr: &AttrMap2) {
+    let mut v: Vec<_> = attr.iter().collect();
+    v.sort();
+    for (k, v) in v {
+        buf.push_str(k);
+        buf.push('=');
+        buf.push_str(v);
+        buf.push('\u{1}');
+    }
+    buf.push('\u{2}');
+}
+
+/// Finds automatic styles with the same key and removes the duplicates.
+/// Returns a map from the removed style to the remaining one.
+fn dedup_styles<R, S>(
+    styles: &mut HashMap<R, S>,
+    is_auto: impl Fn(&S) -> bool,
+    key: impl Fn(&S) -> String,
+) -> HashMap<String, String>
+where
+    R: Borrow<str> + Hash + Eq + Clone,
+{
+    let mut names: Vec<(&R, &S)> = styles.iter().filter(|(_, v)| is_auto(v)).collect();
+    names.sort_by(|a, b| a.0.borrow().cmp(b.0.borrow()));
+
+    let mut canonical: HashMap<String, R> = HashMap::new();
+    let mut renamed = HashMap::new();
+    for (name, style) in names {
+        let k = key(style);
+        if let Some(target) = canonical.get(&k) {
+            renamed.insert(name.borrow().to_string(), target.borrow().to_string());
+        } else {
+            canonical.insert(k, name.clone());
+        }
+    }
+    for name in renamed.keys() {
+        styles.remove(name.as_str());
+    }
+    renamed
+}
+
 /// Autogenerate a stylename. Runs a counter with the prefix and
 /// checks for existence.
 fn auto_style_name2<K, V>(
@@ -235,6 +304,576 @@ fn auto_style_name<T>(
     style_name
 }
 
+/// Name access for everything that [WorkBook::append_workbook] copies
+/// over from the other workbook.
+trait AppendName {
+    fn append_name(&self) -> &str;
+    fn set_append_name(&mut self, name: &str);
+    /// Automatic names are always renamed on collision, others
+    /// are shared if they are identical.
+    fn is_auto(&self) -> bool;
+    /// Compares everything but the name. Only used for styles that
+    /// are not automatic.
+    fn same(&self, _other: &Self) -> bool {
+        false
+    }
+}
+
+/// Compares attributes regardless of their order.
+fn attr_eq(a: &AttrMap2, b: &AttrMap2) -> bool {
+    a.len() == b.len() && a.iter().all(|(k, v)| b.attr(k.as_ref()) == Some(v))
+}
+
+fn stylemaps_eq(a: Option<&Vec<StyleMap>>, b: Option<&Vec<StyleMap>>) -> bool {
+    let a = a.map(Vec::as_slice).unwrap_or_default();
+    let b = b.map(Vec::as_slice).unwrap_or_default();
+    a.len() == b.len()
+        && a.iter().zip(b).all(|(a, b)| {
+            a.condition().to_string() == b.condition().to_string()
+                && a.applied_style() == b.applied_style()
+                && a.base_cell() == b.base_cell()
+        })
+}
+
+fn value_stylemaps_eq(a: Option<&Vec<ValueStyleMap>>, b: Option<&Vec<ValueStyleMap>>) -> bool {
+    let a = a.map(Vec::as_slice).unwrap_or_default();
+    let b = b.map(Vec::as_slice).unwrap_or_default();
+    a.len() == b.len()
+        && a.iter().zip(b).all(|(a, b)| {
+            a.condition().to_string() == b.condition().to_string()
+                && a.applied_style() == b.applied_style()
+        })
+}
+
+fn headerfooter_eq(a: &HeaderFooter, b: &HeaderFooter) -> bool {
+    a.display() == b.display()
+        && a.left() == b.left()
+        && a.center() == b.center()
+        && a.right() == b.right()
+        && a.content() == b.content()
+}
+
+macro_rules! append_name_style {
+    ($($ty:ty, |$a:ident, $b:ident| $same:expr;)*) => {
+        $(
+            impl AppendName for $ty {
+                fn append_name(&self) -> &str {
+                    self.name()
+                }
+                fn set_append_name(&mut self, name: &str) {
+                    self.set_name(name);
+                }
+                fn is_auto(&self) -> bool {
+                    self.styleuse() == StyleUse::Automatic
+                }
+                fn same(&self, other: &Self) -> bool {
+                    let ($a, $b) = (self, other);
+                    $a.origin() == $b.origin()
+                        && $a.styleuse() == $b.styleuse()
+                        && attr_eq($a.attrmap(), $b.attrmap())
+                        && $same
+                }
+            }
+        )*
+    };
+}
+
+append_name_style!(
+    TableStyle, |a, b| attr_eq(a.tablestyle(), b.tablestyle())
+        && stylemaps_eq(a.stylemaps(), b.stylemaps());
+    RowStyle, |a, b| attr_eq(a.rowstyle(), b.rowstyle())
+        && stylemaps_eq(a.stylemaps(), b.stylemaps());
+    ColStyle, |a, b| attr_eq(a.colstyle(), b.colstyle())
+        && stylemaps_eq(a.stylemaps(), b.stylemaps());
+    CellStyle, |a, b| attr_eq(a.cellstyle(), b.cellstyle())
+        && attr_eq(a.paragraphstyle(), b.paragraphstyle())
+        && attr_eq(a.textstyle(), b.textstyle())
+        && stylemaps_eq(a.stylemaps(), b.stylemaps())
+        && a.color_theme() == b.color_theme()
+        && a.background_color_theme() == b.background_color_theme();
+    ParagraphStyle, |a, b| attr_eq(a.paragraphstyle(), b.paragraphstyle())
+        && attr_eq(a.textstyle(), b.textstyle())
+        && a.tabstops().map(Vec::len) == b.tabstops().map(Vec::len)
+        && a.tabstops().into_iter().flatten()
+            .zip(b.tabstops().into_iter().flatten())
+            .all(|(a, b)| attr_eq(a.attrmap(), b.attrmap()))
+        && stylemaps_eq(a.stylemaps(), b.stylemaps());
+    TextStyle, |a, b| attr_eq(a.textstyle(), b.textstyle())
+        && stylemaps_eq(a.stylemaps(), b.stylemaps());
+    RubyStyle, |a, b| attr_eq(a.rubystyle(), b.rubystyle());
+    GraphicStyle, |a, b| attr_eq(a.graphicstyle(), b.graphicstyle())
+        && attr_eq(a.paragraphstyle(), b.paragraphstyle())
+        && attr_eq(a.textstyle(), b.textstyle());
+);
+
+macro_rules! append_name_format {
+    ($($ty:ty),*) => {
+        $(
+            impl AppendName for $ty {
+                fn append_name(&self) -> &str {
+                    self.name()
+                }
+                fn set_append_name(&mut self, name: &str) {
+                    self.set_name(name);
+                }
+                fn is_auto(&self) -> bool {
+                    self.styleuse() == StyleUse::Automatic
+                }
+                fn same(&self, other: &Self) -> bool {
+                    self.origin() == other.origin()
+                        && self.styleuse() == other.styleuse()
+                        && attr_eq(self.attrmap(), other.attrmap())
+                        && attr_eq(self.textstyle(), other.textstyle())
+                        && self.parts().len() == other.parts().len()
+                        && self.parts().iter().zip(other.parts()).all(|(a, b)| {
+                            a.part_type() == b.part_type()
+                                && a.position() == b.position()
+                                && a.content() == b.content()
+                                && attr_eq(a.attrmap(), b.attrmap())
+                        })
+                        && value_stylemaps_eq(self.stylemaps(), other.stylemaps())
+                }
+            }
+        )*
+    };
+}
+
+append_name_format!(
+    ValueFormatBoolean,
+    ValueFormatNumber,
+    ValueFormatPercentage,
+    ValueFormatCurrency,
+    ValueFormatText,
+    ValueFormatDateTime,
+    ValueFormatTimeDuration
+);
+
+impl AppendName for PageStyle {
+    fn append_name(&self) -> &str {
+        self.name()
+    }
+    fn set_append_name(&mut self, name: &str) {
+        self.set_name(name);
+    }
+    fn is_auto(&self) -> bool {
+        true
+    }
+}
+
+impl AppendName for MasterPage {
+    fn append_name(&self) -> &str {
+        self.name()
+    }
+    fn set_append_name(&mut self, name: &str) {
+        self.set_name(name.to_string());
+    }
+    fn is_auto(&self) -> bool {
+        false
+    }
+    fn same(&self, other: &Self) -> bool {
+        self.display_name() == other.display_name()
+            && self.pagestyle() == other.pagestyle()
+            && self.next_masterpage() == other.next_masterpage()
+            && headerfooter_eq(self.header(), other.header())
+            && headerfooter_eq(self.header_first(), other.header_first())
+            && headerfooter_eq(self.header_left(), other.header_left())
+            && headerfooter_eq(self.footer(), other.footer())
+            && headerfooter_eq(self.footer_first(), other.footer_first())
+            && headerfooter_eq(self.footer_left(), other.footer_left())
+    }
+}
+
+impl AppendName for Validation {
+    fn append_name(&self) -> &str {
+        self.name()
+    }
+    fn set_append_name(&mut self, name: &str) {
+        self.set_name(name);
+    }
+    fn is_auto(&self) -> bool {
+        true
+    }
+}
+
+/// Finds new names for the styles of the other workbook that collide
+/// with existing ones. Identical named styles are not renamed and
+/// will be shared. `taken` contains all names of the namespace and
+/// gets the new names too.
+fn append_renames<R, S>(
+    styles: &HashMap<R, S>,
+    other: &HashMap<R, S>,
+    taken: &mut HashSet<String>,
+    autonum: &mut HashMap<String, u32>,
+    prefix: &str,
+    strategy: &RenameStrategy,
+) -> Result<HashMap<String, String>, OdsError>
+where
+    R: Borrow<str> + Hash + Eq,
+    S: AppendName,
+{
+    let mut names: Vec<&S> = other.values().collect();
+    names.sort_by(|a, b| a.append_name().cmp(b.append_name()));
+
+    let mut renamed = HashMap::new();
+    for style in names {
+        let name = style.append_name();
+        if taken.contains(name) {
+            if let Some(existing) = styles.get(name) {
+                if !style.is_auto() && existing.same(style) {
+                    continue;
+                }
+            }
+            let new_name = if style.is_auto() {
+                let mut cnt = autonum.get(prefix).map(|v| v + 1).unwrap_or_default();
+                let new_name = loop {
+                    let new_name = format!("{}{}", prefix, cnt);
+                    if !taken.contains(&new_name) && !other.contains_key(new_name.as_str()) {
+                        break new_name;
+                    }
+                    cnt += 1;
+                };
+                autonum.insert(prefix.to_string(), cnt);
+                new_name
+            } else {
+                strategy.rename(name, |v| taken.contains(v) || other.contains_key(v))?
+            };
+            taken.insert(new_name.clone());
+            renamed.insert(name.to_string(), new_name);
+        }
+    }
+    for name in other.keys() {
+        let name = name.borrow();
+        taken.insert(
+            renamed
+                .get(name)
+                .map(String::as_str)
+                .unwrap_or(name)
+                .to_string(),
+        );
+    }
+    Ok(renamed)
+}
+
+/// Moves the styles of the other workbook after renaming.
+/// Styles that are already there are shared.
+fn append_styles<R, S>(
+    styles: &mut HashMap<R, S>,
+    other: HashMap<R, S>,
+    renamed: &HashMap<String, String>,
+) where
+    R: Borrow<str> + Hash + Eq + for<'a> From<&'a str>,
+    S: AppendName,
+{
+    for (_, mut style) in other {
+        if let Some(new_name) = renamed.get(style.append_name()) {
+            style.set_append_name(new_name);
+        } else if styles.contains_key(style.append_name()) {
+            continue;
+        }
+        styles.insert(R::from(style.append_name()), style);
+    }
+}
+
+fn rename_str<R>(r: &mut Option<R>, renamed: &HashMap<String, String>)
+where
+    R: AsRef<str> + for<'a> From<&'a str>,
+{
+    if let Some(v) = r {
+        if let Some(target) = renamed.get(v.as_ref()) {
+            *v = R::from(target.as_str());
+        }
+    }
+}
+
+fn rename_attr(attr: &mut AttrMap2, name: &str, renamed: &HashMap<String, String>) {
+    if let Some(target) = attr.attr(name).and_then(|v| renamed.get(v)) {
+        attr.set_attr(name, target.clone());
+    }
+}
+
+fn rename_table(table: Option<&String>, renamed: &HashMap<String, String>) -> Option<String> {
+    table.and_then(|v| renamed.get(v)).cloned()
+}
+
+fn rename_range(range: &mut CellRange, renamed: &HashMap<String, String>) {
+    if let Some(table) = rename_table(range.table(), renamed) {
+        range.set_table(table);
+    }
+    if let Some(table) = rename_table(range.to_table(), renamed) {
+        range.set_to_table(table);
+    }
+}
+
+/// Changes the table names in formulas, print ranges and conditional formats.
+fn rename_sheet_refs(sheet: &mut Sheet, renamed: &HashMap<String, String>) {
+    if renamed.is_empty() {
+        return;
+    }
+    for cell in sheet.data.values_mut() {
+        if let Some(formula) = &cell.formula {
+            cell.formula = Some(rename_table_refs(formula, renamed));
+        }
+    }
+    if let Some(print_ranges) = &mut sheet.print_ranges {
+        for range in print_ranges {
+            rename_range(range, renamed);
+        }
+    }
+    for format in sheet.conditional_formats.iter_mut() {
+        let mut range = format.range().clone();
+        rename_range(&mut range, renamed);
+        format.set_range(range);
+    }
+    for tag in sheet.extra.iter_mut() {
+        rename_xml_refs(tag, renamed);
+    }
+}
+
+/// Changes the table names in the condition and the base cell.
+fn rename_validation_refs(valid: &mut Validation, renamed: &HashMap<String, String>) {
+    if let Some(table) = rename_table(valid.base_cell().table(), renamed) {
+        let mut base_cell = valid.base_cell().clone();
+        base_cell.set_table(table);
+        valid.set_base_cell(base_cell);
+    }
+    let condition = rename_table_refs(&valid.condition().to_string(), renamed);
+    valid.set_condition(Condition::new(condition));
+}
+
+/// Changes the table names in a space separated list of cell ranges.
+/// Quoted table names may contain spaces too.
+fn rename_address_list(address: &str, renamed: &HashMap<String, String>) -> String {
+    let mut result = String::new();
+    let mut quoted = false;
+    let mut start = 0;
+    for (i, c) in address.char_indices().chain([(address.len(), ' ')]) {
+        match c {
+            '\'' => quoted = !quoted,
+            ' ' if !quoted => {
+                if start > 0 {
+                    result.push(' ');
+                }
+                if start < i {
+                    let range = rename_table_refs(&format!("[{}]", &address[start..i]), renamed);
+                    result.push_str(&range[1..range.len() - 1]);
+                }
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    result
+}
+
+/// Changes the table names in named ranges, named expressions and
+/// database ranges.
+fn rename_xml_refs(tag: &mut XmlTag, renamed: &HashMap<String, String>) {
+    for name in [
+        "table:base-cell-address",
+        "table:cell-range-address",
+        "table:target-range-address",
+    ] {
+        if let Some(address) = tag.attrmap().attr(name) {
+            let address = rename_address_list(address, renamed);
+            tag.attrmap_mut().set_attr(name, address);
+        }
+    }
+    if let Some(expression) = tag.attrmap().attr("table:expression") {
+        let expression = rename_table_refs(expression, renamed);
+        tag.attrmap_mut().set_attr("table:expression", expression);
+    }
+    for content in tag.content_mut() {
+        if let XmlContent::Tag(tag) = content {
+            rename_xml_refs(tag, renamed);
+        }
+    }
+}
+
+/// Named ranges and database ranges in table:named-expressions and
+/// table:database-ranges.
+pub(crate) fn range_names(extra: &[XmlTag]) -> Vec<(String, CellRange)> {
+    let mut names = Vec::new();
+    for tag in extra {
+        let address = match tag.name() {
+            "table:named-expressions" => "table:cell-range-address",
+            "table:database-ranges" => "table:target-range-address",
+            _ => continue,
+        };
+        for content in tag.content() {
+            let XmlContent::Tag(tag) = content else {
+                continue;
+            };
+            let (Some(name), Some(address)) = (tag.get_attr("table:name"), tag.get_attr(address))
+            else {
+                continue;
+            };
+            // database ranges without a name in the ui.
+            if name.starts_with("__Anonymous_Sheet_DB__") {
+                continue;
+            }
+            if let Ok(range) = CellRange::parse(address) {
+                names.push((name.to_string(), range));
+            }
+        }
+    }
+    names
+}
+
+/// Style family referenced by a style attribute in text and xml.
+fn xml_style_family(tag: &str, attr: &str) -> Option<StyleFamily> {
+    match attr {
+        "text:style-name" => match tag {
+            "text:p" | "text:h" => Some(StyleFamily::Paragraph),
+            "text:ruby" => Some(StyleFamily::Ruby),
+            // list styles are not supported.
+            "text:list" | "text:list-item" => None,
+            _ => Some(StyleFamily::Text),
+        },
+        "text:visited-style-name" => Some(StyleFamily::Text),
+        "table:style-name" if tag == "table:table-cell" => Some(StyleFamily::Cell),
+        "draw:style-name" => Some(StyleFamily::Graphic),
+        "draw:text-style-name" => Some(StyleFamily::Paragraph),
+        _ => None,
+    }
+}
+
+/// Style references in the attributes of a draw frame or annotation.
+fn attr_style_refs(attr: &AttrMap2, add: &mut impl FnMut(StyleFamily, &str)) {
+    for (k, v) in attr.iter() {
+        if let Some(family) = xml_style_family("", k.as_ref()) {
+            add(family, v);
+        }
+    }
+}
+
+/// Style references in text and xml.
+fn xml_style_refs(tag: &XmlTag, add: &mut impl FnMut(StyleFamily, &str)) {
+    for (k, v) in tag.attrmap().iter() {
+        if let Some(family) = xml_style_family(tag.name(), k.as_ref()) {
+            add(family, v);
+        }
+    }
+    for content in tag.content() {
+        if let XmlContent::Tag(tag) = content {
+            xml_style_refs(tag, add);
+        }
+    }
+}
+
+/// Renames the style references in the attributes of a draw frame or
+/// annotation.
+fn attr_rename_styles(
+    tag: &str,
+    attr: &mut AttrMap2,
+    renamed: &HashMap<StyleFamily, HashMap<String, String>>,
+) {
+    let changes = attr
+        .iter()
+        .filter_map(|(k, v)| {
+            let family = xml_style_family(tag, k.as_ref())?;
+            let target = renamed.get(&family)?.get(v)?;
+            Some((k.to_string(), target.clone()))
+        })
+        .collect::<Vec<_>>();
+    for (k, v) in changes {
+        attr.set_attr(&k, v);
+    }
+}
+
+/// Renames the style references in text and xml.
+fn xml_rename_styles(tag: &mut XmlTag, renamed: &HashMap<StyleFamily, HashMap<String, String>>) {
+    let name = tag.name().to_string();
+    attr_rename_styles(&name, tag.attrmap_mut(), renamed);
+    for content in tag.content_mut() {
+        if let XmlContent::Tag(tag) = content {
+            xml_rename_styles(tag, renamed);
+        }
+    }
+}
+
+/// Renames the style references in a header or footer.
+fn hf_rename_styles(
+    hf: &mut HeaderFooter,
+    renamed: &HashMap<StyleFamily, HashMap<String, String>>,
+) {
+    for tag in hf.left_mut() {
+        xml_rename_styles(tag, renamed);
+    }
+    for tag in hf.center_mut() {
+        xml_rename_styles(tag, renamed);
+    }
+    for tag in hf.right_mut() {
+        xml_rename_styles(tag, renamed);
+    }
+    for tag in hf.content_mut() {
+        xml_rename_styles(tag, renamed);
+    }
+}
+
+/// Value format references in text and xml.
+fn xml_format_refs(tag: &XmlTag, formats: &mut HashSet<String>) {
+    if let Some(name) = tag.get_attr("style:data-style-name") {
+        formats.insert(name.to_string());
+    }
+    for content in tag.content() {
+        if let XmlContent::Tag(tag) = content {
+            xml_format_refs(tag, formats);
+        }
+    }
+}
+
+/// Value formats referenced by the conditions of other value formats.
+fn stylemap_refs<T: ValueFormatTrait>(map: &HashMap<String, T>, formats: &mut HashSet<String>) {
+    for format in map.values() {
+        for stylemap in format.stylemaps().into_iter().flatten() {
+            formats.insert(stylemap.applied_style().to_string());
+        }
+    }
+}
+
+/// Copies the entry with this name, if it is not there yet.
+/// Returns the number of copied entries.
+fn copy_ref<K, V>(to: &mut HashMap<K, V>, from: &HashMap<K, V>, name: &str) -> usize
+where
+    K: Borrow<str> + Hash + Eq + Clone,
+    V: Clone,
+{
+    if to.contains_key(name) {
+        return 0;
+    }
+    match from.get_key_value(name) {
+        Some((k, v)) => {
+            to.insert(k.clone(), v.clone());
+            1
+        }
+        None => 0,
+    }
+}
+
+/// Removes all value formats that are not in the set.
+fn retain_formats<T>(map: &mut HashMap<String, T>, formats: &HashSet<String>) -> usize {
+    let before = map.len();
+    map.retain(|k, _| formats.contains(k));
+    before - map.len()
+}
+
+/// Font references in the text properties of a style.
+fn font_refs(attr: &AttrMap2, fonts: &mut HashSet<String>) {
+    for key in [
+        "style:font-name",
+        "style:font-name-asian",
+        "style:font-name-complex",
+    ] {
+        if let Some(name) = attr.attr(key) {
+            fonts.insert(name.to_string());
+        }
+    }
+}
+
+fn style_names<R: Borrow<str>, S>(styles: &HashMap<R, S>) -> HashSet<String> {
+    styles.keys().map(|v| v.borrow().to_string()).collect()
+}
+
 impl Default for WorkBook {
     fn default() -> Self {
         WorkBook::new(locale!("en"))
@@ -250,8 +889,10 @@ impl WorkBook {
             sheets: Default::default(),
             version: "1.3".to_string(),
             fonts: Default::default(),
+            theme: None,
             autonum: Default::default(),
             scripts: Default::default(),
+            basic: Default::default(),
             event_listener: Default::default(),
             tablestyles: Default::default(),
             rowstyles: Default::default(),
@@ -274,6 +915,8 @@ impl WorkBook {
             validations: Default::default(),
             config: default_settings(),
             workbook_config: Default::default(),
+            calc_settings: Default::default(),
+            dde_links: Default::default(),
             extra: vec![],
             manifest: Default::default(),
             metadata: Default::default(),
@@ -281,6 +924,116 @@ impl WorkBook {
         }
     }
 
+    /// Creates a new workbook from a cell range of one sheet.
+    ///
+    /// The new workbook contains only this range, moved to A1.
+    /// The styles, formats, validations and page layouts used by the
+    /// range are copied too, so the result looks the same. See [Sheet::extract_range].
+    ///
+    /// Together with [write_ods_parts](crate::write_ods_parts) this can be
+    /// used to create an embedded spreadsheet object.
+    pub fn from_range(book: &WorkBook, sheet: usize, range: &CellRange) -> Self {
+        let sheet = book.sheet(sheet).extract_range(range);
+
+        let mut wb = WorkBook::new_empty();
+        wb.version = book.version.clone();
+        wb.theme = book.theme.clone();
+        wb.autonum = book.autonum.clone();
+        wb.def_styles = book.def_styles.clone();
+        wb.calc_settings = book.calc_settings.clone();
+        wb.dde_links = book.dde_links.clone();
+        wb.manifest = book.manifest.clone();
+        wb.xmlns = book.xmlns.clone();
+        wb.push_sheet(sheet);
+        wb.copy_referenced(book);
+        wb
+    }
+
+    /// Copies the styles, value formats, fonts, page layouts and
+    /// validations that are referenced in this workbook from the other.
+    fn copy_referenced(&mut self, other: &WorkBook) {
+        loop {
+            let mut copied = 0;
+            for (family, name) in self.style_refs().into_keys() {
+                copied += match family {
+                    StyleFamily::Table => {
+                        copy_ref(&mut self.tablestyles, &other.tablestyles, &name)
+                    }
+                    StyleFamily::Column => copy_ref(&mut self.colstyles, &other.colstyles, &name),
+                    StyleFamily::Row => copy_ref(&mut self.rowstyles, &other.rowstyles, &name),
+                    StyleFamily::Cell => copy_ref(&mut self.cellstyles, &other.cellstyles, &name),
+                    StyleFamily::Paragraph => {
+                        copy_ref(&mut self.paragraphstyles, &other.paragraphstyles, &name)
+                    }
+                    StyleFamily::Text => copy_ref(&mut self.textstyles, &other.textstyles, &name),
+                    StyleFamily::Ruby => copy_ref(&mut self.rubystyles, &other.rubystyles, &name),
+                    StyleFamily::Graphic => {
+                        copy_ref(&mut self.graphicstyles, &other.graphicstyles, &name)
+                    }
+                };
+            }
+            let masterpages = self
+                .tablestyles
+                .values()
+                .filter_map(|v| v.attrmap().attr("style:master-page-name"))
+                .map(|v| v.to_string())
+                .collect::<Vec<_>>();
+            for name in masterpages {
+                copied += copy_ref(&mut self.masterpages, &other.masterpages, &name);
+            }
+            if copied == 0 {
+                break;
+            }
+        }
+
+        loop {
+            let mut copied = 0;
+            for name in self.format_refs() {
+                copied += copy_ref(&mut self.formats_boolean, &other.formats_boolean, &name)
+                    + copy_ref(&mut self.formats_number, &other.formats_number, &name)
+                    + copy_ref(
+                        &mut self.formats_percentage,
+                        &other.formats_percentage,
+                        &name,
+                    )
+                    + copy_ref(&mut self.formats_currency, &other.formats_currency, &name)
+                    + copy_ref(&mut self.formats_text, &other.formats_text, &name)
+                    + copy_ref(&mut self.formats_datetime, &other.formats_datetime, &name)
+                    + copy_ref(
+                        &mut self.formats_timeduration,
+                        &other.formats_timeduration,
+                        &name,
+                    );
+            }
+            if copied == 0 {
+                break;
+            }
+        }
+
+        for name in self.font_refs() {
+            copy_ref(&mut self.fonts, &other.fonts, &name);
+        }
+        let pagestyles = self
+            .masterpages
+            .values()
+            .filter_map(|v| v.pagestyle())
+            .map(|v| v.as_str().to_string())
+            .collect::<Vec<_>>();
+        for name in pagestyles {
+            copy_ref(&mut self.pagestyles, &other.pagestyles, &name);
+        }
+        let validations = self
+            .sheets
+            .iter()
+            .flat_map(|v| v.data.iter().map(|(_, cell)| cell))
+            .filter_map(|v| v.extra.as_ref()?.validation_name.as_ref())
+            .map(|v| v.as_str().to_string())
+            .collect::<Vec<_>>();
+        for name in validations {
+            copy_ref(&mut self.validations, &other.validations, &name);
+        }
+    }
+
     /// Creates a new workbook, and initializes default styles according
     /// to the given locale.
     ///
@@ -343,7 +1096,9 @@ impl WorkBook {
         self.add_def_style(ValueType::Number, DefaultStyle::number());
         self.add_def_style(ValueType::Percentage, DefaultStyle::percent());
         self.add_def_style(ValueType::Currency, DefaultStyle::currency());
-        self.add_def_style(ValueType::DateTime, DefaultStyle::date());
+        self.add_def_style(ValueType::DateTime, DefaultStyle::datetime());
+        self.add_def_style(ValueType::Date, DefaultStyle::date());
+        self.add_def_style(ValueType::Time, DefaultStyle::time_of_day());
         self.add_def_style(ValueType::TimeDuration, DefaultStyle::time_interval());
     }
 
@@ -368,6 +1123,45 @@ impl WorkBook {
         &mut self.workbook_config
     }
 
+    /// Sheet that is shown when the document is opened.
+    ///
+    /// Fails if there is no sheet with this name.
+    pub fn set_active_sheet<S: AsRef<str>>(&mut self, name: S) -> Result<(), OdsError> {
+        let name = name.as_ref();
+        if self.sheet_idx(name).is_none() {
+            return Err(OdsError::Ods(format!("no sheet {}", name)));
+        }
+        self.workbook_config.active_table = name.to_string();
+        Ok(())
+    }
+
+    /// Index of the sheet that is shown when the document is opened.
+    pub fn active_sheet(&self) -> Option<usize> {
+        self.sheet_idx(&self.workbook_config.active_table)
+    }
+
+    /// Open the document in the page-break view. The zoom for this
+    /// view is set per sheet with
+    /// [Sheet::set_page_view_zoom](crate::Sheet::set_page_view_zoom).
+    pub fn set_page_break_view(&mut self, page_break_view: bool) {
+        self.workbook_config.show_page_break_preview = page_break_view;
+    }
+
+    /// Open the document in the page-break view.
+    pub fn page_break_view(&self) -> bool {
+        self.workbook_config.show_page_break_preview
+    }
+
+    /// Settings for the calculation of formulas.
+    pub fn calc_settings(&self) -> &CalcSettings {
+        &self.calc_settings
+    }
+
+    /// Settings for the calculation of formulas.
+    pub fn calc_settings_mut(&mut self) -> &mut CalcSettings {
+        &mut self.calc_settings
+    }
+
     /// Number of sheets.
     pub fn num_sheets(&self) -> usize {
         self.sheets.len()
@@ -436,6 +1230,137 @@ impl WorkBook {
         self.sheets.insert(i, sheet.into());
     }
 
+    /// Copies the sheet and inserts the copy after it. Returns the
+    /// index of the copy.
+    ///
+    /// References to the sheet itself in formulas, print ranges and
+    /// conditional formats are changed to the new name. Validations
+    /// with a base cell in the sheet are copied too.
+    ///
+    /// Panics
+    ///
+    /// Panics if n is out of bounds.
+    /// Panics if the sheet was detached.
+    pub fn clone_sheet<S: Into<String>>(&mut self, n: usize, new_name: S) -> usize {
+        let mut sheet = self.sheet(n).clone();
+        sheet.set_name(new_name);
+
+        let renamed_from = self.sheet(n).name().clone();
+        let renamed = HashMap::from_iter([(renamed_from.clone(), sheet.name().clone())]);
+        rename_sheet_refs(&mut sheet, &renamed);
+
+        let mut validations = HashMap::new();
+        for cell in sheet.data.values_mut() {
+            let Some(extra) = &mut cell.extra else {
+                continue;
+            };
+            let Some(valid_ref) = &extra.validation_name else {
+                continue;
+            };
+            if !validations.contains_key(valid_ref) {
+                let copy = match self.validation(valid_ref) {
+                    Some(valid) if valid.base_cell().table() == Some(&renamed_from) => {
+                        let mut valid = valid.clone();
+                        rename_validation_refs(&mut valid, &renamed);
+                        valid.set_name("");
+                        self.add_validation(valid)
+                    }
+                    _ => valid_ref.clone(),
+                };
+                validations.insert(valid_ref.clone(), copy);
+            }
+            extra.validation_name = validations.get(valid_ref).cloned();
+        }
+
+        self.insert_sheet(n + 1, sheet);
+        n + 1
+    }
+
+    /// Moves a sheet to a new position.
+    ///
+    /// Panics
+    ///
+    /// Panics if from or to are out of bounds.
+    pub fn move_sheet(&mut self, from: usize, to: usize) {
+        assert!(to < self.sheets.len());
+        let sheet = self.sheets.remove(from);
+        self.sheets.insert(to, sheet);
+    }
+
+    /// Renames a sheet and changes all references to the old name.
+    ///
+    /// This covers the formulas, print ranges and conditional formats of
+    /// all sheets, validations, named ranges and expressions, the active
+    /// table and the view settings of the sheet.
+    ///
+    /// Fails if another sheet already has the new name.
+    ///
+    /// Panics
+    ///
+    /// Panics if n is out of bounds.
+    /// Panics if any sheet is detached.
+    pub fn rename_sheet<S: Into<String>>(&mut self, n: usize, new_name: S) -> Result<(), OdsError> {
+        let new_name = new_name.into();
+        let old_name = self.sheet(n).name().clone();
+        if old_name == new_name {
+            return Ok(());
+        }
+        if self.sheet_idx(&new_name).is_some() {
+            return Err(OdsError::Ods(format!("Sheet already exists: {}", new_name)));
+        }
+
+        self.sheet_mut(n).set_name(new_name.as_str());
+
+        let renamed = HashMap::from_iter([(old_name.clone(), new_name.clone())]);
+        for sheet in self.sheets.iter_mut() {
+            rename_sheet_refs(sheet, &renamed);
+        }
+        for valid in self.validations.values_mut() {
+            rename_validation_refs(valid, &renamed);
+        }
+        for tag in self.extra.iter_mut() {
+            rename_xml_refs(tag, &renamed);
+        }
+
+        if self.workbook_config.active_table == old_name {
+            self.workbook_config.active_table = new_name.clone();
+        }
+        self.config.rename_key(
+            &["ooo:view-settings", "Views", "0", "Tables"],
+            &old_name,
+            &new_name,
+        );
+        self.config.rename_key(
+            &["ooo:configuration-settings", "ScriptConfiguration"],
+            &old_name,
+            &new_name,
+        );
+
+        Ok(())
+    }
+
+    /// Inserts count empty rows before the given row of the sheet n,
+    /// see [Sheet::insert_rows]. References to the sheet in the formulas
+    /// of the other sheets are adjusted too. Validations are not.
+    pub fn insert_rows(&mut self, n: usize, row: u32, count: u32) {
+        if count == 0 {
+            return;
+        }
+        self.sheet_mut(n).insert_rows(row, count);
+
+        let name = self.sheet(n).name().clone();
+        for (i, sheet) in self.sheets.iter_mut().enumerate() {
+            if i == n {
+                continue;
+            }
+            for cell in sheet.data.values_mut() {
+                if let Some(formula) = &cell.formula {
+                    cell.formula = Some(insert_rows_refs(formula, &name, false, row, count));
+                }
+            }
+        }
+    }
+
     /// Appends a sheet.
     pub fn push_sheet(&mut self, sheet: Sheet) {
         self.sheets.push(sheet.into());
@@ -450,85 +1375,1668 @@ impl WorkBook {
         self.sheets.remove(n).take()
     }
 
-    /// Scripts.
-    pub fn add_script(&mut self, v: Script) {
-        self.scripts.push(v);
+    /// Scripts.
+    pub fn add_script(&mut self, v: Script) {
+        self.scripts.push(v);
+    }
+
+    /// Scripts.
+    pub fn iter_scripts(&self) -> impl Iterator<Item = &Script> {
+        self.scripts.iter()
+    }
+
+    /// Scripts of office:scripts.
+    ///
+    /// The macros themselves are stored in the package, see
+    /// [script_libraries](WorkBook::script_libraries) for Basic and
+    /// [script_files](WorkBook::script_files) for other languages.
+    pub fn scripts(&self) -> &Vec<Script> {
+        &self.scripts
+    }
+
+    /// Scripts
+    pub fn scripts_mut(&mut self) -> &mut Vec<Script> {
+        &mut self.scripts
+    }
+
+    /// Adds a Basic module to the library. The library is created
+    /// if it doesn't exist.
+    pub fn add_script_module(&mut self, library: &str, module: BasicModule) {
+        if let Some(lib) = self.basic.iter_mut().find(|v| v.name() == library) {
+            lib.add_module(module);
+        } else {
+            let mut lib = BasicLibrary::new(library);
+            lib.add_module(module);
+            self.basic.push(lib);
+        }
+    }
+
+    /// Removes a Basic module from the library.
+    pub fn remove_script_module(&mut self, library: &str, module: &str) -> Option<BasicModule> {
+        self.basic
+            .iter_mut()
+            .find(|v| v.name() == library)?
+            .remove_module(module)
+    }
+
+    /// Returns the Basic module.
+    pub fn script_module(&self, library: &str, module: &str) -> Option<&BasicModule> {
+        self.script_library(library)?.module(module)
+    }
+
+    /// Adds a Basic library, replaces an existing one with the same name.
+    pub fn add_script_library(&mut self, library: BasicLibrary) {
+        if let Some(v) = self.basic.iter_mut().find(|v| v.name() == library.name()) {
+            *v = library;
+        } else {
+            self.basic.push(library);
+        }
+    }
+
+    /// Removes a Basic library.
+    pub fn remove_script_library(&mut self, library: &str) -> Option<BasicLibrary> {
+        let pos = self.basic.iter().position(|v| v.name() == library)?;
+        Some(self.basic.remove(pos))
+    }
+
+    /// Returns the Basic library.
+    pub fn script_library(&self, library: &str) -> Option<&BasicLibrary> {
+        self.basic.iter().find(|v| v.name() == library)
+    }
+
+    /// Basic libraries of the document. These are stored in the
+    /// Basic/ directory of the package.
+    ///
+    /// Script files in other languages are listed by
+    /// [script_files](WorkBook::script_files).
+    pub fn script_libraries(&self) -> &Vec<BasicLibrary> {
+        &self.basic
+    }
+
+    /// Basic libraries of the document.
+    pub fn script_libraries_mut(&mut self) -> &mut Vec<BasicLibrary> {
+        &mut self.basic
+    }
+
+    /// Script files of other languages than Basic, e.g. Python. These are
+    /// stored in the Scripts/ directory of the package and are kept as they
+    /// are.
+    pub fn script_files(&self) -> impl Iterator<Item = &Manifest> {
+        self.manifest
+            .values()
+            .filter(|v| v.full_path.starts_with("Scripts/") && !v.is_dir())
+    }
+
+    /// Adds a script file as "Scripts/{path}", e.g. "python/macros.py".
+    pub fn add_script_file<S: AsRef<str>, T: Into<String>>(
+        &mut self,
+        path: S,
+        media_type: T,
+        buf: Vec<u8>,
+    ) {
+        self.add_manifest(Manifest::with_buf(
+            format!("Scripts/{}", path.as_ref()),
+            media_type,
+            buf,
+        ));
+    }
+
+    /// Event-Listener
+    pub fn add_event_listener(&mut self, e: EventListener) {
+        self.event_listener.insert(e.event_name.clone(), e);
+    }
+
+    /// Event-Listener
+    pub fn remove_event_listener(&mut self, event_name: &str) -> Option<EventListener> {
+        self.event_listener.remove(event_name)
+    }
+
+    /// Event-Listener
+    pub fn iter_event_listeners(&self) -> impl Iterator<Item = &EventListener> {
+        self.event_listener.values()
+    }
+
+    /// Event-Listener
+    pub fn event_listener(&self, event_name: &str) -> Option<&EventListener> {
+        self.event_listener.get(event_name)
+    }
+
+    /// Event-Listener
+    pub fn event_listener_mut(&mut self, event_name: &str) -> Option<&mut EventListener> {
+        self.event_listener.get_mut(event_name)
+    }
+
+    /// Adds a default-style for all new values.
+    /// This information is only used when writing the data to the ODS file.
+    pub fn add_def_style(&mut self, value_type: ValueType, style: CellStyleRef) {
+        self.def_styles.insert(value_type, style);
+    }
+
+    /// Returns the default style name.
+    pub fn def_style(&self, value_type: ValueType) -> Option<&CellStyleRef> {
+        self.def_styles.get(&value_type)
+    }
+
+    /// Returns the cell style that applies to a cell.
+    ///
+    /// This is the cell style itself, then the default style for the
+    /// value type of the cell (see [WorkBook::add_def_style]),
+    /// then the default cell style of the row and last the default cell style
+    /// of the column.
+    pub fn effective_cellstyle(&self, sheet: usize, row: u32, col: u32) -> Option<&CellStyleRef> {
+        self.effective_cellstyle_of(self.sheet(sheet), row, col)
+    }
+
+    /// Same as effective_cellstyle() for a sheet that need not be part
+    /// of the workbook.
+    pub(crate) fn effective_cellstyle_of<'a>(
+        &'a self,
+        sheet: &'a Sheet,
+        row: u32,
+        col: u32,
+    ) -> Option<&'a CellStyleRef> {
+        sheet
+            .cellstyle(row, col)
+            .or_else(|| {
+                // Same as the writer: without a value the declared formula type is used.
+                let cell = sheet.data.get(&(row, col))?;
+                let value_type = match cell.extra.as_ref().and_then(|v| v.formula_type) {
+                    Some(formula_type) if cell.value == Value::Empty => formula_type,
+                    _ => cell.value.value_type(),
+                };
+                self.def_style(value_type)
+            })
+            .or_else(|| sheet.row_cellstyle(row))
+            .or_else(|| sheet.col_cellstyle(col))
+    }
+
+    /// Returns the text that is displayed for a cell.
+    ///
+    /// The value is formatted with the value format of the effective
+    /// cell style (see [WorkBook::effective_cellstyle]), including the
+    /// conditional sub-formats of its stylemaps. Without a value format
+    /// the value is returned as plain text.
+    pub fn display_value(&self, sheet: usize, row: u32, col: u32) -> String {
+        self.display_value_of(self.sheet(sheet), row, col)
+    }
+
+    /// Same as display_value() for a sheet that need not be part
+    /// of the workbook.
+    pub(crate) fn display_value_of(&self, sheet: &Sheet, row: u32, col: u32) -> String {
+        let value = sheet.value(row, col);
+
+        // the value format can be set in a parent style.
+        let mut style = self
+            .effective_cellstyle_of(sheet, row, col)
+            .and_then(|v| self.cellstyle(v));
+        let mut name = None;
+        for _ in 0..16 {
+            let Some(s) = style else {
+                break;
+            };
+            if let Some(v) = s.value_format() {
+                name = Some(v);
+                break;
+            }
+            style = s
+                .attrmap()
+                .attr("style:parent-style-name")
+                .and_then(|v| self.cellstyle(v));
+        }
+        let Some(name) = name else {
+            return value_text(value);
+        };
+
+        if let Some(vf) = self.formats_number.get(name) {
+            vf.format_value_with(value, |v| self.formats_number.get(v))
+        } else if let Some(vf) = self.formats_percentage.get(name) {
+            vf.format_value_with(value, |v| self.formats_percentage.get(v))
+        } else if let Some(vf) = self.formats_currency.get(name) {
+            vf.format_value_with(value, |v| self.formats_currency.get(v))
+        } else if let Some(vf) = self.formats_datetime.get(name) {
+            vf.format_value_with(value, |v| self.formats_datetime.get(v))
+        } else if let Some(vf) = self.formats_timeduration.get(name) {
+            vf.format_value_with(value, |v| self.formats_timeduration.get(v))
+        } else if let Some(vf) = self.formats_boolean.get(name) {
+            vf.format_value_with(value, |v| self.formats_boolean.get(v))
+        } else if let Some(vf) = self.formats_text.get(name) {
+            vf.format_value_with(value, |v| self.formats_text.get(v))
+        } else {
+            value_text(value)
+        }
+    }
+
+    /// Draws a border around the outside of the range.
+    ///
+    /// Each cell at the edge of the range gets a new automatic style that
+    /// is derived from its effective cell style (see [WorkBook::effective_cellstyle])
+    /// and adds the needed border sides. Cells with the same base style
+    /// and the same border sides share one style.
+    pub fn set_range_border(&mut self, sheet: usize, range: &CellRange, line: BorderLine) {
+        let mut derived = HashMap::<(Option<CellStyleRef>, [bool; 4]), CellStyleRef>::new();
+
+        for row in range.row()..=range.to_row() {
+            for col in range.col()..=range.to_col() {
+                // top, bottom, left, right
+                let sides = [
+                    row == range.row(),
+                    row == range.to_row(),
+                    col == range.col(),
+                    col == range.to_col(),
+                ];
+                if !sides.contains(&true) {
+                    continue;
+                }
+
+                let base = self.effective_cellstyle(sheet, row, col).cloned();
+                let key = (base, sides);
+                let style_ref = if let Some(style_ref) = derived.get(&key) {
+                    style_ref.clone()
+                } else {
+                    let mut style = self.derive_cellstyle(key.0.as_ref());
+                    let (width, border, color) = (line.width(), line.style(), line.color());
+                    if sides[0] {
+                        style.set_border_top(width, border, color);
+                    }
+                    if sides[1] {
+                        style.set_border_bottom(width, border, color);
+                    }
+                    if sides[2] {
+                        style.set_border_left(width, border, color);
+                    }
+                    if sides[3] {
+                        style.set_border_right(width, border, color);
+                    }
+                    let style_ref = self.add_cellstyle(style);
+                    derived.insert(key, style_ref.clone());
+                    style_ref
+                };
+                self.sheet_mut(sheet).set_cellstyle(row, col, &style_ref);
+            }
+        }
+    }
+
+    /// Allows or prevents changes to the cells of the range if the sheet
+    /// is protected.
+    ///
+    /// By default all cells are protected. For a form protect the sheet
+    /// with [Sheet::set_protected] and unprotect the input cells with this.
+    ///
+    /// Each cell gets an automatic style derived from its effective cell
+    /// style (see [WorkBook::effective_cellstyle]), like
+    /// [WorkBook::set_range_border].
+    ///
+    /// For whole rows or columns the default cell style of the rows or
+    /// columns is changed instead. Only the cells with data and, for
+    /// columns, the cells of rows with a default cell style get their own
+    /// style.
+    pub fn set_range_protected(&mut self, sheet: usize, range: &CellRange, protected: bool) {
+        let mut derived = HashMap::<Option<CellStyleRef>, CellStyleRef>::new();
+
+        let full_rows = range.col() == 0 && range.to_col() + 1 >= MAX_COLS;
+        let full_cols = range.row() == 0 && range.to_row() + 1 >= MAX_ROWS;
+
+        let cells: Vec<(u32, u32)> = if full_rows || full_cols {
+            let sh = self.sheet_mut(sheet);
+            let (from, to) = if full_rows {
+                (range.row(), range.to_row())
+            } else {
+                (range.col(), range.to_col())
+            };
+            let bases: Vec<Option<CellStyleRef>> = if full_rows {
+                sh.create_split_row_header_range(from, to)
+                    .map(|v| v.cellstyle.clone())
+                    .collect()
+            } else {
+                sh.create_split_col_header_range(from, to)
+                    .map(|v| v.cellstyle.clone())
+                    .collect()
+            };
+            let styles: Vec<CellStyleRef> = bases
+                .into_iter()
+                .map(|base| self.protected_style(&mut derived, base, protected))
+                .collect();
+
+            let sh = self.sheet_mut(sheet);
+            if full_rows {
+                for (header, style) in sh.create_split_row_header_range(from, to).zip(styles) {
+                    header.cellstyle = Some(style);
+                }
+            } else {
+                for (header, style) in sh.create_split_col_header_range(from, to).zip(styles) {
+                    header.cellstyle = Some(style);
+                }
+            }
+
+            let sh = self.sheet(sheet);
+            let mut cells: Vec<(u32, u32)> = sh
+                .data
+                .keys()
+                .filter(|(row, col)| range.contains(*row, *col))
+                .collect();
+            if !full_rows {
+                // The default of the row comes before the default of the column.
+                for (row, header) in sh.row_header.iter() {
+                    if header.cellstyle.is_some() {
+                        for row in *row..row + header.span {
+                            for col in range.col()..=range.to_col() {
+                                cells.push((row, col));
+                            }
+                        }
+                    }
+                }
+                cells.sort();
+                cells.dedup();
+            }
+            cells
+        } else {
+            range.iter().collect()
+        };
+
+        for (row, col) in cells {
+            let base = self.effective_cellstyle(sheet, row, col).cloned();
+            let style_ref = self.protected_style(&mut derived, base, protected);
+            self.sheet_mut(sheet).set_cellstyle(row, col, &style_ref);
+        }
+    }
+
+    /// Style derived from base with the given protection. Reuses the
+    /// styles in derived.
+    fn protected_style(
+        &mut self,
+        derived: &mut HashMap<Option<CellStyleRef>, CellStyleRef>,
+        base: Option<CellStyleRef>,
+        protected: bool,
+    ) -> CellStyleRef {
+        if let Some(style_ref) = derived.get(&base) {
+            style_ref.clone()
+        } else {
+            let mut style = self.derive_cellstyle(base.as_ref());
+            style.set_protected(protected);
+            let style_ref = self.add_cellstyle(style);
+            derived.insert(base, style_ref.clone());
+            style_ref
+        }
+    }
+
+    /// Can the cell be changed? This is false if the sheet is protected
+    /// and the effective cell style is protected. The parent styles are
+    /// considered too.
+    pub fn is_cell_protected(&self, sheet: usize, row: u32, col: u32) -> bool {
+        if !self.sheet(sheet).protected() {
+            return false;
+        }
+
+        let mut style = self
+            .effective_cellstyle(sheet, row, col)
+            .and_then(|v| self.cellstyle(v));
+        while let Some(s) = style {
+            if s.cellstyle().attr("style:cell-protect").is_some() {
+                return s.protected();
+            }
+            style = s
+                .attrmap()
+                .attr("style:parent-style-name")
+                .and_then(|v| self.cellstyle(v));
+        }
+        true
+    }
+
+    /// New automatic style for a cell that looks like the base style.
+    fn derive_cellstyle(&self, base: Option<&CellStyleRef>) -> CellStyle {
+        match base.map(|v| (v, self.cellstyle(v))) {
+            Some((_, Some(base))) if base.styleuse() == StyleUse::Automatic => {
+                let mut style = base.clone();
+                style.set_name("");
+                style
+            }
+            Some((base_ref, Some(base))) if base.styleuse() == StyleUse::Named => {
+                let mut style = CellStyle::new_empty();
+                style.set_parent_style(base_ref);
+                if let Some(value_format) = base.value_format() {
+                    style.set_value_format(&value_format.into());
+                }
+                style
+            }
+            _ => CellStyle::new_empty(),
+        }
+    }
+
+    /// Adds a font.
+    pub fn add_font(&mut self, font: FontFaceDecl) {
+        self.fonts.insert(font.name().to_string(), font);
+    }
+
+    /// Removes a font.
+    pub fn remove_font(&mut self, name: &str) -> Option<FontFaceDecl> {
+        self.fonts.remove(name)
+    }
+
+    /// Iterates the fonts.
+    pub fn iter_fonts(&self) -> impl Iterator<Item = &FontFaceDecl> {
+        self.fonts.values()
+    }
+
+    /// Returns the FontDecl.
+    pub fn font(&self, name: &str) -> Option<&FontFaceDecl> {
+        self.fonts.get(name)
+    }
+
+    /// Returns a mutable FontDecl.
+    pub fn font_mut(&mut self, name: &str) -> Option<&mut FontFaceDecl> {
+        self.fonts.get_mut(name)
+    }
+
+    /// Embeds a font file in the document. The document then renders
+    /// the same on machines where the font is not installed.
+    ///
+    /// The file is stored as Fonts/name_n.ext in the package and added to
+    /// the font declaration with this name. If there is no such font
+    /// declaration, one is created. Call this once for each file of the
+    /// font, e.g. regular, bold and italic.
+    ///
+    /// TrueType, OpenType, WOFF and WOFF2 files are recognized, other data
+    /// is an error.
+    pub fn embed_font<S: AsRef<str>>(&mut self, name: S, buf: Vec<u8>) -> Result<(), OdsError> {
+        let name = name.as_ref();
+        let Some((format, ext, media_type)) = font_file_format(&buf) else {
+            return Err(OdsError::Ods(format!("unknown font format for {}", name)));
+        };
+
+        let file_name = name.replace(['/', '\\'], "_");
+        let mut n = 1;
+        let path = loop {
+            let path = format!("Fonts/{}_{}.{}", file_name, n, ext);
+            if !self.manifest.contains_key(&path) {
+                break path;
+            }
+            n += 1;
+        };
+        self.add_manifest(Manifest::with_buf(path.clone(), media_type, buf));
+
+        if !self.fonts.contains_key(name) {
+            let mut font = FontFaceDecl::new(name);
+            font.set_font_family(name);
+            self.add_font(font);
+        }
+        if let Some(font) = self.fonts.get_mut(name) {
+            let mut uri = FontFaceUri::new(path);
+            uri.set_format(Some(format));
+            font.add_src(uri);
+        }
+
+        Ok(())
+    }
+
+    /// Sets the document theme.
+    pub fn set_theme(&mut self, theme: Theme) {
+        self.theme = Some(theme);
+    }
+
+    /// Removes the document theme.
+    pub fn clear_theme(&mut self) -> Option<Theme> {
+        self.theme.take()
+    }
+
+    /// Returns the document theme.
+    pub fn theme(&self) -> Option<&Theme> {
+        self.theme.as_ref()
+    }
+
+    /// Returns the document theme.
+    pub fn theme_mut(&mut self) -> Option<&mut Theme> {
+        self.theme.as_mut()
+    }
+
+    /// Merges automatic styles with identical attributes and rewrites
+    /// all references to the remaining style. This covers table, row,
+    /// column and cell styles. See [styles](WorkBook::styles) for what
+    /// counts as reference. Don't call this while a sheet is detached.
+    ///
+    /// Useful if a new style has been created for each cell.
+    /// Returns the number of removed styles.
+    pub fn optimize_styles(&mut self) -> usize {
+        let tablestyles = dedup_styles(
+            &mut self.tablestyles,
+            |v| v.styleuse() == StyleUse::Automatic,
+            |v| {
+                let mut k = format!("{:?}\u{2}{:?}\u{2}", v.origin(), v.stylemaps());
+                attr_key(&mut k, v.attrmap());
+                attr_key(&mut k, v.tablestyle());
+                k
+            },
+        );
+        let rowstyles = dedup_styles(
+            &mut self.rowstyles,
+            |v| v.styleuse() == StyleUse::Automatic,
+            |v| {
+                let mut k = format!("{:?}\u{2}{:?}\u{2}", v.origin(), v.stylemaps());
+                attr_key(&mut k, v.attrmap());
+                attr_key(&mut k, v.rowstyle());
+                k
+            },
+        );
+        let colstyles = dedup_styles(
+            &mut self.colstyles,
+            |v| v.styleuse() == StyleUse::Automatic,
+            |v| {
+                let mut k = format!("{:?}\u{2}{:?}\u{2}", v.origin(), v.stylemaps());
+                attr_key(&mut k, v.attrmap());
+                attr_key(&mut k, v.colstyle());
+                k
+            },
+        );
+        let cellstyles = dedup_styles(
+            &mut self.cellstyles,
+            |v| v.styleuse() == StyleUse::Automatic,
+            |v| {
+                let mut k = format!(
+                    "{:?}\u{2}{:?}\u{2}{:?}\u{2}{:?}\u{2}",
+                    v.origin(),
+                    v.stylemaps(),
+                    v.color_theme(),
+                    v.background_color_theme()
+                );
+                attr_key(&mut k, v.attrmap());
+                attr_key(&mut k, v.cellstyle());
+                attr_key(&mut k, v.paragraphstyle());
+                attr_key(&mut k, v.textstyle());
+                k
+            },
+        );
+
+        let count = tablestyles.len() + rowstyles.len() + colstyles.len() + cellstyles.len();
+        let renamed = HashMap::from([
+            (StyleFamily::Table, tablestyles),
+            (StyleFamily::Row, rowstyles),
+            (StyleFamily::Column, colstyles),
+            (StyleFamily::Cell, cellstyles),
+        ]);
+        self.rename_style_refs(&renamed);
+
+        count
+    }
+
+    /// Lists all styles of the workbook with the number of references
+    /// to each of them.
+    ///
+    /// References are counted from sheets, row and column headers, cells,
+    /// the text of cells and annotations, draw frames, header and footer
+    /// of the master pages, the default styles and from other styles as
+    /// parent or conditional style. Detached sheets are not counted.
+    ///
+    /// The result is sorted by family and name.
+    pub fn styles(&self) -> Vec<StyleInfo> {
+        let mut styles = Vec::new();
+        let mut push = |family, name: &str, origin, styleuse| {
+            styles.push(StyleInfo {
+                family,
+                name: name.to_string(),
+                origin,
+                styleuse,
+                refs: 0,
+            });
+        };
+        for v in self.tablestyles.values() {
+            push(StyleFamily::Table, v.name(), v.origin(), v.styleuse());
+        }
+        for v in self.colstyles.values() {
+            push(StyleFamily::Column, v.name(), v.origin(), v.styleuse());
+        }
+        for v in self.rowstyles.values() {
+            push(StyleFamily::Row, v.name(), v.origin(), v.styleuse());
+        }
+        for v in self.cellstyles.values() {
+            push(StyleFamily::Cell, v.name(), v.origin(), v.styleuse());
+        }
+        for v in self.paragraphstyles.values() {
+            push(StyleFamily::Paragraph, v.name(), v.origin(), v.styleuse());
+        }
+        for v in self.textstyles.values() {
+            push(StyleFamily::Text, v.name(), v.origin(), v.styleuse());
+        }
+        for v in self.rubystyles.values() {
+            push(StyleFamily::Ruby, v.name(), v.origin(), v.styleuse());
+        }
+        for v in self.graphicstyles.values() {
+            push(StyleFamily::Graphic, v.name(), v.origin(), v.styleuse());
+        }
+
+        let refs = self.style_refs();
+        for style in styles.iter_mut() {
+            style.refs = refs
+                .get(&(style.family, style.name.clone()))
+                .copied()
+                .unwrap_or_default();
+        }
+        styles.sort_by(|a, b| a.family.cmp(&b.family).then_with(|| a.name.cmp(&b.name)));
+        styles
+    }
+
+    /// Counts the references to each style. See [styles](WorkBook::styles).
+    fn style_refs(&self) -> HashMap<(StyleFamily, String), usize> {
+        let mut refs = HashMap::new();
+        let mut add = |family: StyleFamily, name: &str| {
+            *refs.entry((family, name.to_string())).or_insert(0usize) += 1;
+        };
+
+        let mut parent_refs = |family, attr: &AttrMap2| {
+            if let Some(parent) = attr.attr("style:parent-style-name") {
+                add(family, parent);
+            }
+            if let Some(next) = attr.attr("style:next-style-name") {
+                add(family, next);
+            }
+        };
+        for v in self.tablestyles.values() {
+            parent_refs(StyleFamily::Table, v.attrmap());
+        }
+        for v in self.colstyles.values() {
+            parent_refs(StyleFamily::Column, v.attrmap());
+        }
+        for v in self.rowstyles.values() {
+            parent_refs(StyleFamily::Row, v.attrmap());
+        }
+        for v in self.cellstyles.values() {
+            parent_refs(StyleFamily::Cell, v.attrmap());
+        }
+        for v in self.paragraphstyles.values() {
+            parent_refs(StyleFamily::Paragraph, v.attrmap());
+        }
+        for v in self.textstyles.values() {
+            parent_refs(StyleFamily::Text, v.attrmap());
+        }
+        for v in self.rubystyles.values() {
+            parent_refs(StyleFamily::Ruby, v.attrmap());
+        }
+        for v in self.graphicstyles.values() {
+            parent_refs(StyleFamily::Graphic, v.attrmap());
+        }
+
+        for style in self.cellstyles.values() {
+            for stylemap in style.stylemaps().into_iter().flatten() {
+                add(StyleFamily::Cell, stylemap.applied_style().as_str());
+            }
+        }
+        for style in self.def_styles.values() {
+            add(StyleFamily::Cell, style.as_str());
+        }
+        for masterpage in self.masterpages.values() {
+            for hf in [
+                masterpage.header(),
+                masterpage.header_first(),
+                masterpage.header_left(),
+                masterpage.footer(),
+                masterpage.footer_first(),
+                masterpage.footer_left(),
+            ] {
+                for tag in hf
+                    .left()
+                    .iter()
+                    .chain(hf.center())
+                    .chain(hf.right())
+                    .chain(hf.content())
+                {
+                    xml_style_refs(tag, &mut add);
+                }
+            }
+        }
+        for sheet in self.sheets.iter().filter(|v| !v.is_detached()) {
+            if let Some(style) = &sheet.style {
+                add(StyleFamily::Table, style.as_str());
+            }
+            for header in sheet.row_header.values() {
+                if let Some(style) = &header.style {
+                    add(StyleFamily::Row, style.as_str());
+                }
+                if let Some(style) = &header.cellstyle {
+                    add(StyleFamily::Cell, style.as_str());
+                }
+            }
+            for header in sheet.col_header.values() {
+                if let Some(style) = &header.style {
+                    add(StyleFamily::Column, style.as_str());
+                }
+                if let Some(style) = &header.cellstyle {
+                    add(StyleFamily::Cell, style.as_str());
+                }
+            }
+            for tag in &sheet.extra {
+                xml_style_refs(tag, &mut add);
+            }
+            for (_, cell) in sheet.data.iter() {
+                if let Some(style) = &cell.style {
+                    add(StyleFamily::Cell, style.as_str());
+                }
+                if let Value::TextXml(text) = &cell.value {
+                    for tag in text {
+                        xml_style_refs(tag, &mut add);
+                    }
+                }
+                let Some(extra) = &cell.extra else {
+                    continue;
+                };
+                if let Some(annotation) = &extra.annotation {
+                    attr_style_refs(annotation.attrmap(), &mut add);
+                    for tag in annotation.text() {
+                        xml_style_refs(tag, &mut add);
+                    }
+                }
+                for frame in &extra.draw_frames {
+                    attr_style_refs(frame.attrmap(), &mut add);
+                    for content in frame.content_ref() {
+                        match content {
+                            DrawFrameContent::Image(image) => {
+                                for tag in image.get_text() {
+                                    xml_style_refs(tag, &mut add);
+                                }
+                            }
+                            DrawFrameContent::Object(object) => {
+                                for tag in object.content() {
+                                    xml_style_refs(tag, &mut add);
+                                }
+                            }
+                        }
+                    }
+                }
+                for tag in &extra.xml {
+                    xml_style_refs(tag, &mut add);
+                }
+            }
+        }
+
+        refs
+    }
+
+    /// Renames the style references of each family. Covers the same
+    /// references as [style_refs](WorkBook::style_refs).
+    fn rename_style_refs(&mut self, renamed: &HashMap<StyleFamily, HashMap<String, String>>) {
+        let empty = HashMap::new();
+        let family = |f: StyleFamily| renamed.get(&f).unwrap_or(&empty);
+        let rename_parent = |attr: &mut AttrMap2, renamed: &HashMap<String, String>| {
+            rename_attr(attr, "style:parent-style-name", renamed);
+            rename_attr(attr, "style:next-style-name", renamed);
+        };
+
+        let table = family(StyleFamily::Table);
+        let col = family(StyleFamily::Column);
+        let row = family(StyleFamily::Row);
+        let cell = family(StyleFamily::Cell);
+        for v in self.tablestyles.values_mut() {
+            rename_parent(v.attrmap_mut(), table);
+        }
+        for v in self.colstyles.values_mut() {
+            rename_parent(v.attrmap_mut(), col);
+        }
+        for v in self.rowstyles.values_mut() {
+            rename_parent(v.attrmap_mut(), row);
+        }
+        for v in self.cellstyles.values_mut() {
+            rename_parent(v.attrmap_mut(), cell);
+        }
+        for v in self.paragraphstyles.values_mut() {
+            rename_parent(v.attrmap_mut(), family(StyleFamily::Paragraph));
+        }
+        for v in self.textstyles.values_mut() {
+            rename_parent(v.attrmap_mut(), family(StyleFamily::Text));
+        }
+        for v in self.rubystyles.values_mut() {
+            rename_parent(v.attrmap_mut(), family(StyleFamily::Ruby));
+        }
+        for v in self.graphicstyles.values_mut() {
+            rename_parent(v.attrmap_mut(), family(StyleFamily::Graphic));
+        }
+
+        if !cell.is_empty() {
+            for style in self.cellstyles.values_mut() {
+                if style.stylemaps().is_some() {
+                    for stylemap in style.stylemaps_mut() {
+                        if let Some(target) = cell.get(stylemap.applied_style().as_str()) {
+                            stylemap.set_applied_style(CellStyleRef::from(target.as_str()).into());
+                        }
+                    }
+                }
+            }
+            for style in self.def_styles.values_mut() {
+                if let Some(target) = cell.get(style.as_str()) {
+                    *style = CellStyleRef::from(target.as_str());
+                }
+            }
+        }
+        for masterpage in self.masterpages.values_mut() {
+            hf_rename_styles(masterpage.header_mut(), renamed);
+            hf_rename_styles(masterpage.header_first_mut(), renamed);
+            hf_rename_styles(masterpage.header_left_mut(), renamed);
+            hf_rename_styles(masterpage.footer_mut(), renamed);
+            hf_rename_styles(masterpage.footer_first_mut(), renamed);
+            hf_rename_styles(masterpage.footer_left_mut(), renamed);
+        }
+        for sheet in self.sheets.iter_mut() {
+            rename_str(&mut sheet.style, table);
+            for header in sheet.row_header.values_mut() {
+                rename_str(&mut header.style, row);
+                rename_str(&mut header.cellstyle, cell);
+            }
+            for header in sheet.col_header.values_mut() {
+                rename_str(&mut header.style, col);
+                rename_str(&mut header.cellstyle, cell);
+            }
+            for tag in sheet.extra.iter_mut() {
+                xml_rename_styles(tag, renamed);
+            }
+            for cell_data in sheet.data.values_mut() {
+                rename_str(&mut cell_data.style, cell);
+                if let Value::TextXml(text) = &mut cell_data.value {
+                    for tag in text {
+                        xml_rename_styles(tag, renamed);
+                    }
+                }
+                let Some(extra) = &mut cell_data.extra else {
+                    continue;
+                };
+                if let Some(annotation) = &mut extra.annotation {
+                    attr_rename_styles("", annotation.attrmap_mut(), renamed);
+                    for tag in annotation.text_mut() {
+                        xml_rename_styles(tag, renamed);
+                    }
+                }
+                for frame in &mut extra.draw_frames {
+                    attr_rename_styles("", frame.attrmap_mut(), renamed);
+                    for content in frame.content_mut() {
+                        match content {
+                            DrawFrameContent::Image(image) => {
+                                for tag in image.text_mut() {
+                                    xml_rename_styles(tag, renamed);
+                                }
+                            }
+                            DrawFrameContent::Object(object) => {
+                                for tag in object.content_mut() {
+                                    xml_rename_styles(tag, renamed);
+                                }
+                            }
+                        }
+                    }
+                }
+                for tag in &mut extra.xml {
+                    xml_rename_styles(tag, renamed);
+                }
+            }
+        }
+    }
+
+    /// Removes all automatic styles that are not referenced anywhere.
+    /// Named and default styles are kept, they are part of the style
+    /// list the user sees.
+    ///
+    /// See [styles](WorkBook::styles) for what counts as reference. Don't
+    /// call this while a sheet is detached.
+    ///
+    /// Returns the number of removed styles.
+    pub fn remove_unused_styles(&mut self) -> usize {
+        let mut count = 0;
+        loop {
+            let unused = self
+                .styles()
+                .into_iter()
+                .filter(|v| v.styleuse == StyleUse::Automatic && v.refs == 0)
+                .collect::<Vec<_>>();
+            if unused.is_empty() {
+                break;
+            }
+            for style in unused {
+                match style.family {
+                    StyleFamily::Table => {
+                        self.tablestyles.remove(style.name.as_str());
+                    }
+                    StyleFamily::Column => {
+                        self.colstyles.remove(style.name.as_str());
+                    }
+                    StyleFamily::Row => {
+                        self.rowstyles.remove(style.name.as_str());
+                    }
+                    StyleFamily::Cell => {
+                        self.cellstyles.remove(style.name.as_str());
+                    }
+                    StyleFamily::Paragraph => {
+                        self.paragraphstyles.remove(style.name.as_str());
+                    }
+                    StyleFamily::Text => {
+                        self.textstyles.remove(style.name.as_str());
+                    }
+                    StyleFamily::Ruby => {
+                        self.rubystyles.remove(style.name.as_str());
+                    }
+                    StyleFamily::Graphic => {
+                        self.graphicstyles.remove(style.name.as_str());
+                    }
+                }
+                count += 1;
+            }
+        }
+        count
     }
 
-    /// Scripts.
-    pub fn iter_scripts(&self) -> impl Iterator<Item = &Script> {
-        self.scripts.iter()
-    }
+    /// Removes value formats, fonts and page styles that are not
+    /// referenced anywhere.
+    ///
+    /// * Value formats are referenced by cell styles, by the conditions
+    ///   of other value formats and by fields in header and footer.
+    /// * Fonts are referenced by the text properties of any style or
+    ///   value format. Embedded font files are removed together with
+    ///   their font.
+    /// * Page styles are referenced by master pages.
+    ///
+    /// Styles that are not used still count as reference. Call
+    /// [remove_unused_styles](WorkBook::remove_unused_styles) first to
+    /// get rid of those too.
+    ///
+    /// Returns the number of removed formats, fonts and page styles.
+    pub fn purge_unreferenced(&mut self) -> usize {
+        let mut count = 0;
+
+        loop {
+            let formats = self.format_refs();
+            let removed = retain_formats(&mut self.formats_boolean, &formats)
+                + retain_formats(&mut self.formats_number, &formats)
+                + retain_formats(&mut self.formats_percentage, &formats)
+                + retain_formats(&mut self.formats_currency, &formats)
+                + retain_formats(&mut self.formats_text, &formats)
+                + retain_formats(&mut self.formats_datetime, &formats)
+                + retain_formats(&mut self.formats_timeduration, &formats);
+            if removed == 0 {
+                break;
+            }
+            count += removed;
+        }
 
-    /// Scripts
-    pub fn scripts(&self) -> &Vec<Script> {
-        &self.scripts
-    }
+        let fonts = self.font_refs();
+        let unused = self
+            .fonts
+            .keys()
+            .filter(|v| !fonts.contains(v.as_str()))
+            .cloned()
+            .collect::<Vec<_>>();
+        for name in unused {
+            if let Some(font) = self.fonts.remove(&name) {
+                for src in font.src() {
+                    self.manifest.remove(src.href());
+                }
+                count += 1;
+            }
+        }
 
-    /// Scripts
-    pub fn scripts_mut(&mut self) -> &mut Vec<Script> {
-        &mut self.scripts
+        let pagestyles = self
+            .masterpages
+            .values()
+            .filter_map(|v| v.pagestyle())
+            .cloned()
+            .collect::<HashSet<_>>();
+        let before = self.pagestyles.len();
+        self.pagestyles.retain(|k, _| pagestyles.contains(k));
+        count += before - self.pagestyles.len();
+
+        count
+    }
+
+    /// Value formats referenced by cell styles, fields in header and
+    /// footer and the conditions of other value formats.
+    fn format_refs(&self) -> HashSet<String> {
+        let mut formats = HashSet::new();
+        for style in self.cellstyles.values() {
+            if let Some(format) = style.value_format() {
+                formats.insert(format.to_string());
+            }
+        }
+        for masterpage in self.masterpages.values() {
+            for hf in [
+                masterpage.header(),
+                masterpage.header_first(),
+                masterpage.header_left(),
+                masterpage.footer(),
+                masterpage.footer_first(),
+                masterpage.footer_left(),
+            ] {
+                for tag in hf
+                    .left()
+                    .iter()
+                    .chain(hf.center())
+                    .chain(hf.right())
+                    .chain(hf.content())
+                {
+                    xml_format_refs(tag, &mut formats);
+                }
+            }
+        }
+        stylemap_refs(&self.formats_boolean, &mut formats);
+        stylemap_refs(&self.formats_number, &mut formats);
+        stylemap_refs(&self.formats_percentage, &mut formats);
+        stylemap_refs(&self.formats_currency, &mut formats);
+        stylemap_refs(&self.formats_text, &mut formats);
+        stylemap_refs(&self.formats_datetime, &mut formats);
+        stylemap_refs(&self.formats_timeduration, &mut formats);
+        formats
+    }
+
+    /// Fonts referenced by the text properties of styles and value formats.
+    fn font_refs(&self) -> HashSet<String> {
+        let mut fonts = HashSet::new();
+        for style in self.cellstyles.values() {
+            font_refs(style.textstyle(), &mut fonts);
+        }
+        for style in self.paragraphstyles.values() {
+            font_refs(style.textstyle(), &mut fonts);
+        }
+        for style in self.textstyles.values() {
+            font_refs(style.textstyle(), &mut fonts);
+        }
+        for style in self.graphicstyles.values() {
+            font_refs(style.textstyle(), &mut fonts);
+        }
+        for format in self.formats_boolean.values() {
+            font_refs(format.textstyle(), &mut fonts);
+        }
+        for format in self.formats_number.values() {
+            font_refs(format.textstyle(), &mut fonts);
+        }
+        for format in self.formats_percentage.values() {
+            font_refs(format.textstyle(), &mut fonts);
+        }
+        for format in self.formats_currency.values() {
+            font_refs(format.textstyle(), &mut fonts);
+        }
+        for format in self.formats_text.values() {
+            font_refs(format.textstyle(), &mut fonts);
+        }
+        for format in self.formats_datetime.values() {
+            font_refs(format.textstyle(), &mut fonts);
+        }
+        for format in self.formats_timeduration.values() {
+            font_refs(format.textstyle(), &mut fonts);
+        }
+        fonts
     }
 
-    /// Event-Listener
-    pub fn add_event_listener(&mut self, e: EventListener) {
-        self.event_listener.insert(e.event_name.clone(), e);
-    }
+    /// Checks the workbook for problems that would lead to a file that
+    /// is rejected or silently repaired by Calc.
+    ///
+    /// Checks for
+    /// * value formats of different kinds with the same name,
+    /// * references to styles, value formats, master pages and
+    ///   page styles that don't exist,
+    /// * overlapping merged cells,
+    /// * invalid or duplicate sheet names,
+    /// * repeats and spans that are zero or exceed the maximum
+    ///   size of a sheet,
+    /// * cells with a content validation that doesn't exist,
+    /// * print ranges outside the used area of the sheet.
+    ///
+    /// Detached sheets are not checked.
+    pub fn validate(&self) -> Vec<ValidationIssue> {
+        let mut issues = Vec::new();
+
+        let mut formats = HashSet::new();
+        for name in self
+            .formats_boolean
+            .keys()
+            .chain(self.formats_number.keys())
+            .chain(self.formats_percentage.keys())
+            .chain(self.formats_currency.keys())
+            .chain(self.formats_text.keys())
+            .chain(self.formats_datetime.keys())
+            .chain(self.formats_timeduration.keys())
+        {
+            if !formats.insert(name.as_str()) {
+                issues.push(ValidationIssue::DuplicateStyleName(name.clone()));
+            }
+        }
 
-    /// Event-Listener
-    pub fn remove_event_listener(&mut self, event_name: &str) -> Option<EventListener> {
-        self.event_listener.remove(event_name)
-    }
+        let mut dangling = |exists: bool, location: &dyn Fn() -> String, style: &str| {
+            if !exists {
+                issues.push(ValidationIssue::DanglingStyleRef {
+                    location: location(),
+                    style: style.to_string(),
+                });
+            }
+        };
+        for (value_type, style) in self.def_styles.iter() {
+            dangling(
+                self.cellstyles.contains_key(style),
+                &|| format!("default style for {:?}", value_type),
+                style.as_str(),
+            );
+        }
+        for style in self.cellstyles.values() {
+            if let Some(format) = style.value_format() {
+                dangling(
+                    formats.contains(format),
+                    &|| format!("cell style {}", style.name()),
+                    format,
+                );
+            }
+            if let Some(stylemaps) = style.stylemaps() {
+                for stylemap in stylemaps {
+                    let applied = stylemap.applied_style().as_str();
+                    dangling(
+                        self.cellstyles.contains_key(applied),
+                        &|| format!("condition of cell style {}", style.name()),
+                        applied,
+                    );
+                }
+            }
+        }
+        for style in self.tablestyles.values() {
+            if let Some(masterpage) = style.attrmap().attr("style:master-page-name") {
+                dangling(
+                    self.masterpages.contains_key(masterpage),
+                    &|| format!("table style {}", style.name()),
+                    masterpage,
+                );
+            }
+        }
+        for masterpage in self.masterpages.values() {
+            if let Some(pagestyle) = masterpage.pagestyle() {
+                dangling(
+                    self.pagestyles.contains_key(pagestyle),
+                    &|| format!("master page {}", masterpage.name()),
+                    pagestyle.as_str(),
+                );
+            }
+        }
+        for sheet in self.sheets.iter().filter(|v| !v.is_detached()) {
+            if let Some(style) = &sheet.style {
+                dangling(
+                    self.tablestyles.contains_key(style),
+                    &|| format!("sheet {}", sheet.name),
+                    style.as_str(),
+                );
+            }
+            for (row, header) in sheet.row_header.iter() {
+                let location = || format!("row {} of sheet {}", row + 1, sheet.name);
+                if let Some(style) = &header.style {
+                    dangling(
+                        self.rowstyles.contains_key(style),
+                        &location,
+                        style.as_str(),
+                    );
+                }
+                if let Some(style) = &header.cellstyle {
+                    dangling(
+                        self.cellstyles.contains_key(style),
+                        &location,
+                        style.as_str(),
+                    );
+                }
+            }
+            for (col, header) in sheet.col_header.iter() {
+                let location = || format!("column {} of sheet {}", col + 1, sheet.name);
+                if let Some(style) = &header.style {
+                    dangling(
+                        self.colstyles.contains_key(style),
+                        &location,
+                        style.as_str(),
+                    );
+                }
+                if let Some(style) = &header.cellstyle {
+                    dangling(
+                        self.cellstyles.contains_key(style),
+                        &location,
+                        style.as_str(),
+                    );
+                }
+            }
+            for ((row, col), cell) in sheet.data.iter() {
+                if let Some(style) = &cell.style {
+                    dangling(
+                        self.cellstyles.contains_key(style),
+                        &|| CellRef::remote(&sheet.name, row, col).to_string(),
+                        style.as_str(),
+                    );
+                }
+            }
+        }
 
-    /// Event-Listener
-    pub fn iter_event_listeners(&self) -> impl Iterator<Item = &EventListener> {
-        self.event_listener.values()
-    }
+        let mut sheet_names = HashSet::new();
+        for sheet in self.sheets.iter().filter(|v| !v.is_detached()) {
+            if !valid_sheet_name(&sheet.name) {
+                issues.push(ValidationIssue::InvalidSheetName(sheet.name.clone()));
+            }
+            if !sheet_names.insert(sheet.name.as_str()) {
+                issues.push(ValidationIssue::DuplicateSheetName(sheet.name.clone()));
+            }
 
-    /// Event-Listener
-    pub fn event_listener(&self, event_name: &str) -> Option<&EventListener> {
-        self.event_listener.get(event_name)
-    }
+            for (row, header) in sheet.row_header.iter() {
+                let end = *row as u64 + max(header.repeat, header.span) as u64;
+                if header.repeat == 0 || header.span == 0 || end > MAX_ROWS as u64 {
+                    issues.push(ValidationIssue::RepeatOutOfRange {
+                        location: format!("row {} of sheet {}", row + 1, sheet.name),
+                        repeat: max(header.repeat, header.span),
+                    });
+                }
+            }
+            for (col, header) in sheet.col_header.iter() {
+                if header.span == 0 || *col as u64 + header.span as u64 > MAX_COLS as u64 {
+                    issues.push(ValidationIssue::RepeatOutOfRange {
+                        location: format!("column {} of sheet {}", col + 1, sheet.name),
+                        repeat: header.span,
+                    });
+                }
+            }
 
-    /// Event-Listener
-    pub fn event_listener_mut(&mut self, event_name: &str) -> Option<&mut EventListener> {
-        self.event_listener.get_mut(event_name)
-    }
+            if let Some(print_ranges) = &sheet.print_ranges {
+                let used = sheet.used_range();
+                for range in print_ranges {
+                    let mut range = range.clone();
+                    range.set_table(sheet.name.as_str());
+                    let within = used.as_ref().is_some_and(|used| {
+                        used.contains(range.row(), range.col())
+                            && used.contains(range.to_row(), range.to_col())
+                    });
+                    if !within {
+                        issues.push(ValidationIssue::PrintRangeOutOfArea {
+                            sheet: sheet.name.clone(),
+                            range,
+                        });
+                    }
+                }
+            }
 
-    /// Adds a default-style for all new values.
-    /// This information is only used when writing the data to the ODS file.
-    pub fn add_def_style(&mut self, value_type: ValueType, style: CellStyleRef) {
-        self.def_styles.insert(value_type, style);
-    }
+            let mut merged = Vec::new();
+            for ((row, col), cell) in sheet.data.iter() {
+                if cell.repeat == 0
+                    || row >= MAX_ROWS
+                    || col as u64 + cell.repeat as u64 > MAX_COLS as u64
+                {
+                    issues.push(ValidationIssue::RepeatOutOfRange {
+                        location: CellRef::remote(&sheet.name, row, col).to_string(),
+                        repeat: cell.repeat,
+                    });
+                }
+                if let Some(extra) = &cell.extra {
+                    if let Some(validation) = &extra.validation_name {
+                        if !self.validations.contains_key(validation) {
+                            issues.push(ValidationIssue::UndefinedValidation {
+                                cell: CellRef::remote(&sheet.name, row, col),
+                                validation: validation.as_str().to_string(),
+                            });
+                        }
+                    }
+                    if extra.span.row_span > 1 || extra.span.col_span > 1 {
+                        merged.push((row, col, extra.span.row_span, extra.span.col_span));
+                    }
+                }
+            }
+            for (row, col, row_span, col_span) in merged.iter() {
+                if *row as u64 + *row_span as u64 > MAX_ROWS as u64
+                    || *col as u64 + *col_span as u64 > MAX_COLS as u64
+                {
+                    issues.push(ValidationIssue::SpanOutOfRange(CellRange::remote(
+                        &sheet.name,
+                        *row,
+                        *col,
+                        row.saturating_add(row_span - 1),
+                        col.saturating_add(col_span - 1),
+                    )));
+                }
+            }
+            for (i, (row, col, row_span, col_span)) in merged.iter().enumerate() {
+                for (row2, col2, row_span2, col_span2) in &merged[i + 1..] {
+                    if row < &(row2 + row_span2)
+                        && row2 < &(row + row_span)
+                        && col < &(col2 + col_span2)
+                        && col2 < &(col + col_span)
+                    {
+                        issues.push(ValidationIssue::OverlappingMerge(
+                            CellRange::remote(
+                                &sheet.name,
+                                *row,
+                                *col,
+                                row + row_span - 1,
+                                col + col_span - 1,
+                            ),
+                            CellRange::remote(
+                                &sheet.name,
+                                *row2,
+                                *col2,
+                                row2 + row_span2 - 1,
+                                col2 + col_span2 - 1,
+                            ),
+                        ));
+                    }
+                }
+            }
+        }
 
-    /// Returns the default style name.
-    pub fn def_style(&self, value_type: ValueType) -> Option<&CellStyleRef> {
-        self.def_styles.get(&value_type)
+        issues
     }
 
-    /// Adds a font.
-    pub fn add_font(&mut self, font: FontFaceDecl) {
-        self.fonts.insert(font.name().to_string(), font);
-    }
+    /// Appends the sheets of the other workbook.
+    ///
+    /// All styles, value formats, page layouts, validations and fonts
+    /// are copied too. Automatic styles and validations get a new name
+    /// if the name is already used. Named styles are shared if they are
+    /// identical, otherwise they are renamed with the strategy. Sheets
+    /// are renamed with the strategy too, and references to renamed
+    /// sheets in the formulas of the appended sheets are changed.
+    ///
+    /// The default styles and the settings of the other workbook are
+    /// not copied. Style references in the text of cells and in drawings
+    /// or annotations are not changed.
+    ///
+    /// Panics
+    ///
+    /// Panics if a sheet of the other workbook is detached.
+    pub fn append_workbook(
+        &mut self,
+        mut other: WorkBook,
+        strategy: RenameStrategy,
+    ) -> Result<(), OdsError> {
+        let mut taken: HashSet<String> = self.sheets.iter().map(|v| v.name().clone()).collect();
+        let other_sheets: HashSet<String> = other.sheets.iter().map(|v| v.name().clone()).collect();
+        let mut sheets = HashMap::new();
+        for sheet in other.sheets.iter() {
+            if taken.contains(sheet.name()) {
+                let new_name = strategy.rename(sheet.name(), |v| {
+                    taken.contains(v) || other_sheets.contains(v)
+                })?;
+                sheets.insert(sheet.name().clone(), new_name.clone());
+                taken.insert(new_name);
+            } else {
+                taken.insert(sheet.name().clone());
+            }
+        }
 
-    /// Removes a font.
-    pub fn remove_font(&mut self, name: &str) -> Option<FontFaceDecl> {
-        self.fonts.remove(name)
-    }
+        let autonum = &mut self.autonum;
+        let pagestyles = append_renames(
+            &self.pagestyles,
+            &other.pagestyles,
+            &mut style_names(&self.pagestyles),
+            autonum,
+            "page",
+            &strategy,
+        )?;
+        let masterpages = append_renames(
+            &self.masterpages,
+            &other.masterpages,
+            &mut style_names(&self.masterpages),
+            autonum,
+            "mp",
+            &strategy,
+        )?;
+        let tablestyles = append_renames(
+            &self.tablestyles,
+            &other.tablestyles,
+            &mut style_names(&self.tablestyles),
+            autonum,
+            "ta",
+            &strategy,
+        )?;
+        let rowstyles = append_renames(
+            &self.rowstyles,
+            &other.rowstyles,
+            &mut style_names(&self.rowstyles),
+            autonum,
+            "ro",
+            &strategy,
+        )?;
+        let colstyles = append_renames(
+            &self.colstyles,
+            &other.colstyles,
+            &mut style_names(&self.colstyles),
+            autonum,
+            "co",
+            &strategy,
+        )?;
+        let cellstyles = append_renames(
+            &self.cellstyles,
+            &other.cellstyles,
+            &mut style_names(&self.cellstyles),
+            autonum,
+            "ce",
+            &strategy,
+        )?;
+        let paragraphstyles = append_renames(
+            &self.paragraphstyles,
+            &other.paragraphstyles,
+            &mut style_names(&self.paragraphstyles),
+            autonum,
+            "para",
+            &strategy,
+        )?;
+        let textstyles = append_renames(
+            &self.textstyles,
+            &other.textstyles,
+            &mut style_names(&self.textstyles),
+            autonum,
+            "txt",
+            &strategy,
+        )?;
+        let rubystyles = append_renames(
+            &self.rubystyles,
+            &other.rubystyles,
+            &mut style_names(&self.rubystyles),
+            autonum,
+            "ruby",
+            &strategy,
+        )?;
+        let graphicstyles = append_renames(
+            &self.graphicstyles,
+            &other.graphicstyles,
+            &mut style_names(&self.graphicstyles),
+            autonum,
+            "gr",
+            &strategy,
+        )?;
+        let validations = append_renames(
+            &self.validations,
+            &other.validations,
+            &mut style_names(&self.validations),
+            autonum,
+            "val",
+            &strategy,
+        )?;
+
+        // All value formats share one namespace.
+        let mut taken = style_names(&self.formats_boolean);
+        taken.extend(style_names(&self.formats_number));
+        taken.extend(style_names(&self.formats_percentage));
+        taken.extend(style_names(&self.formats_currency));
+        taken.extend(style_names(&self.formats_text));
+        taken.extend(style_names(&self.formats_datetime));
+        taken.extend(style_names(&self.formats_timeduration));
+        let mut formats = HashMap::new();
+        formats.extend(append_renames(
+            &self.formats_boolean,
+            &other.formats_boolean,
+            &mut taken,
+            autonum,
+            "val_boolean",
+            &strategy,
+        )?);
+        formats.extend(append_renames(
+            &self.formats_number,
+            &other.formats_number,
+            &mut taken,
+            autonum,
+            "val_number",
+            &strategy,
+        )?);
+        formats.extend(append_renames(
+            &self.formats_percentage,
+            &other.formats_percentage,
+            &mut taken,
+            autonum,
+            "val_percentage",
+            &strategy,
+        )?);
+        formats.extend(append_renames(
+            &self.formats_currency,
+            &other.formats_currency,
+            &mut taken,
+            autonum,
+            "val_currency",
+            &strategy,
+        )?);
+        formats.extend(append_renames(
+            &self.formats_text,
+            &other.formats_text,
+            &mut taken,
+            autonum,
+            "val_text",
+            &strategy,
+        )?);
+        formats.extend(append_renames(
+            &self.formats_datetime,
+            &other.formats_datetime,
+            &mut taken,
+            autonum,
+            "val_datetime",
+            &strategy,
+        )?);
+        formats.extend(append_renames(
+            &self.formats_timeduration,
+            &other.formats_timeduration,
+            &mut taken,
+            autonum,
+            "val_timeduration",
+            &strategy,
+        )?);
+
+        // Change all references within the other workbook.
+        for style in other.masterpages.values_mut() {
+            let mut pagestyle = style.pagestyle().cloned();
+            rename_str(&mut pagestyle, &pagestyles);
+            if let Some(pagestyle) = pagestyle {
+                style.set_pagestyle(&pagestyle);
+            }
+            let mut next = style.next_masterpage().cloned();
+            rename_str(&mut next, &masterpages);
+            if let Some(next) = next {
+                style.set_next_masterpage(&next);
+            }
+        }
+        for style in other.tablestyles.values_mut() {
+            rename_attr(style.attrmap_mut(), "style:master-page-name", &masterpages);
+        }
+        for style in other.cellstyles.values_mut() {
+            rename_attr(style.attrmap_mut(), "style:data-style-name", &formats);
+        }
+        for style in other.paragraphstyles.values_mut() {
+            rename_attr(style.attrmap_mut(), "style:master-page-name", &masterpages);
+        }
+        let renamed = HashMap::from([
+            (StyleFamily::Table, tablestyles),
+            (StyleFamily::Row, rowstyles),
+            (StyleFamily::Column, colstyles),
+            (StyleFamily::Cell, cellstyles),
+            (StyleFamily::Paragraph, paragraphstyles),
+            (StyleFamily::Text, textstyles),
+            (StyleFamily::Ruby, rubystyles),
+            (StyleFamily::Graphic, graphicstyles),
+        ]);
+        other.rename_style_refs(&renamed);
+        fn rename_stylemaps<T: ValueFormatTrait>(
+            formats: &mut HashMap<String, T>,
+            renamed: &HashMap<String, String>,
+        ) {
+            for format in formats.values_mut() {
+                if format.stylemaps().is_some() {
+                    for stylemap in format.stylemaps_mut() {
+                        if let Some(target) = renamed.get(stylemap.applied_style()) {
+                            stylemap.set_applied_style(target);
+                        }
+                    }
+                }
+            }
+        }
+        rename_stylemaps(&mut other.formats_boolean, &formats);
+        rename_stylemaps(&mut other.formats_number, &formats);
+        rename_stylemaps(&mut other.formats_percentage, &formats);
+        rename_stylemaps(&mut other.formats_currency, &formats);
+        rename_stylemaps(&mut other.formats_text, &formats);
+        rename_stylemaps(&mut other.formats_datetime, &formats);
+        rename_stylemaps(&mut other.formats_timeduration, &formats);
+        if !sheets.is_empty() {
+            for valid in other.validations.values_mut() {
+                rename_validation_refs(valid, &sheets);
+            }
+        }
 
-    /// Iterates the fonts.
-    pub fn iter_fonts(&self) -> impl Iterator<Item = &FontFaceDecl> {
-        self.fonts.values()
-    }
+        append_styles(&mut self.pagestyles, other.pagestyles, &pagestyles);
+        append_styles(&mut self.masterpages, other.masterpages, &masterpages);
+        append_styles(
+            &mut self.tablestyles,
+            other.tablestyles,
+            &renamed[&StyleFamily::Table],
+        );
+        append_styles(
+            &mut self.rowstyles,
+            other.rowstyles,
+            &renamed[&StyleFamily::Row],
+        );
+        append_styles(
+            &mut self.colstyles,
+            other.colstyles,
+            &renamed[&StyleFamily::Column],
+        );
+        append_styles(
+            &mut self.cellstyles,
+            other.cellstyles,
+            &renamed[&StyleFamily::Cell],
+        );
+        append_styles(
+            &mut self.paragraphstyles,
+            other.paragraphstyles,
+            &renamed[&StyleFamily::Paragraph],
+        );
+        append_styles(
+            &mut self.textstyles,
+            other.textstyles,
+            &renamed[&StyleFamily::Text],
+        );
+        append_styles(
+            &mut self.rubystyles,
+            other.rubystyles,
+            &renamed[&StyleFamily::Ruby],
+        );
+        append_styles(
+            &mut self.graphicstyles,
+            other.graphicstyles,
+            &renamed[&StyleFamily::Graphic],
+        );
+        append_styles(&mut self.validations, other.validations, &validations);
+        append_styles(&mut self.formats_boolean, other.formats_boolean, &formats);
+        append_styles(&mut self.formats_number, other.formats_number, &formats);
+        append_styles(
+            &mut self.formats_percentage,
+            other.formats_percentage,
+            &formats,
+        );
+        append_styles(&mut self.formats_currency, other.formats_currency, &formats);
+        append_styles(&mut self.formats_text, other.formats_text, &formats);
+        append_styles(&mut self.formats_datetime, other.formats_datetime, &formats);
+        append_styles(
+            &mut self.formats_timeduration,
+            other.formats_timeduration,
+            &formats,
+        );
+        for (name, font) in other.fonts {
+            self.fonts.entry(name).or_insert(font);
+        }
 
-    /// Returns the FontDecl.
-    pub fn font(&self, name: &str) -> Option<&FontFaceDecl> {
-        self.fonts.get(name)
-    }
+        for mut sheet in other.sheets {
+            if let Some(new_name) = sheets.get(sheet.name()) {
+                sheet.set_name(new_name.as_str());
+            }
+            for cell in sheet.data.values_mut() {
+                if let Some(extra) = &mut cell.extra {
+                    rename_str(&mut extra.validation_name, &validations);
+                }
+            }
+            rename_sheet_refs(&mut sheet, &sheets);
+            self.sheets.push(sheet);
+        }
 
-    /// Returns a mutable FontDecl.
-    pub fn font_mut(&mut self, name: &str) -> Option<&mut FontFaceDecl> {
-        self.fonts.get_mut(name)
+        Ok(())
     }
 
     /// Adds a style.
@@ -839,6 +3347,28 @@ impl WorkBook {
         sref
     }
 
+    /// Autogenerates a name for a value format, that is not used by any
+    /// kind of value format.
+    pub(crate) fn unused_format_name(&mut self, prefix: &str) -> String {
+        let mut cnt = self.autonum.get(prefix).map(|v| v + 1).unwrap_or_default();
+        let name = loop {
+            let name = format!("{}{}", prefix, cnt);
+            if !self.formats_boolean.contains_key(&name)
+                && !self.formats_number.contains_key(&name)
+                && !self.formats_percentage.contains_key(&name)
+                && !self.formats_currency.contains_key(&name)
+                && !self.formats_text.contains_key(&name)
+                && !self.formats_datetime.contains_key(&name)
+                && !self.formats_timeduration.contains_key(&name)
+            {
+                break name;
+            }
+            cnt += 1;
+        };
+        self.autonum.insert(prefix.to_string(), cnt);
+        name
+    }
+
     /// Removes the format.
     pub fn remove_number_format(&mut self, name: &str) -> Option<ValueFormatNumber> {
         self.formats_number.remove(name)
@@ -1142,6 +3672,174 @@ impl WorkBook {
         self.validations.get_mut(name.as_ref())
     }
 
+    /// Adds a DDE link.
+    pub fn add_dde_link(&mut self, link: DdeLink) {
+        self.dde_links.push(link);
+    }
+
+    /// DDE links.
+    pub fn dde_links(&self) -> &[DdeLink] {
+        &self.dde_links
+    }
+
+    /// DDE links.
+    pub fn dde_links_mut(&mut self) -> &mut Vec<DdeLink> {
+        &mut self.dde_links
+    }
+
+    /// Adds a named range for the whole workbook, replaces an existing one
+    /// with the same name. The name can be used in formulas instead of
+    /// the reference.
+    ///
+    /// The range should have a table and absolute references, otherwise
+    /// it is relative to the cell where it is used.
+    pub fn add_named_range<S: Into<String>>(&mut self, name: S, range: &CellRange) {
+        let name = name.into();
+        let base_cell = CellRef::new_all(
+            None,
+            range.table().cloned(),
+            range.row_abs(),
+            range.row(),
+            range.col_abs(),
+            range.col(),
+        );
+        let named_range = XmlTag::new("table:named-range")
+            .attr("table:name", name.as_str())
+            .attr("table:base-cell-address", base_cell.to_string())
+            .attr("table:cell-range-address", range.to_string());
+
+        let idx = match self
+            .extra
+            .iter()
+            .position(|v| v.name() == "table:named-expressions")
+        {
+            Some(idx) => idx,
+            None => {
+                self.extra.push(XmlTag::new("table:named-expressions"));
+                self.extra.len() - 1
+            }
+        };
+        let named_expressions = &mut self.extra[idx];
+        named_expressions.content_mut().retain(|v| match v {
+            XmlContent::Tag(tag) => tag.get_attr("table:name") != Some(name.as_str()),
+            _ => true,
+        });
+        named_expressions.add_tag(named_range);
+    }
+
+    /// Returns the named range or database range with this name.
+    pub fn named_range(&self, name: &str) -> Option<CellRange> {
+        range_names(&self.extra)
+            .into_iter()
+            .find(|(v, _)| v == name)
+            .map(|(_, v)| v)
+    }
+
+    /// All named ranges and database ranges of the workbook.
+    /// Named ranges that are only valid for one sheet are listed
+    /// by [Sheet::range_names].
+    ///
+    /// Named expressions that are not a plain cell range are not included.
+    pub fn range_names(&self) -> Vec<(String, CellRange)> {
+        range_names(&self.extra)
+    }
+
+    /// Replaces the names of named ranges and database ranges in the
+    /// formula with the references. Names of the sheet at idx take
+    /// precedence over names of the workbook.
+    ///
+    /// The reverse happens when writing with
+    /// [OdsWriteOptions::use_range_names](crate::OdsWriteOptions::use_range_names).
+    pub fn expand_range_names(&self, idx: usize, formula: &str) -> String {
+        let names = self
+            .sheet(idx)
+            .range_names()
+            .into_iter()
+            .chain(self.range_names());
+        let names = names.collect::<Vec<_>>();
+        expand_range_names(formula, |name| {
+            names
+                .iter()
+                .find(|(v, _)| v == name)
+                .map(|(_, v)| v.clone())
+        })
+    }
+
+    /// Returns the targets of all links to external files, sorted and
+    /// without duplicates.
+    ///
+    /// These are the references to external files in formulas, the topics
+    /// of the DDE links and of DDE() calls in formulas and the sources of
+    /// linked sheets (table:table-source).
+    ///
+    /// A DDE() call is only examined if the topic is a string literal.
+    pub fn external_links(&self) -> Vec<String> {
+        let mut links = Vec::new();
+        for sheet in self.sheets.iter() {
+            for (_, cell) in sheet.data.iter() {
+                if let Some(formula) = &cell.formula {
+                    links.extend(external_refs(formula));
+                }
+            }
+            for tag in &sheet.extra {
+                if tag.name() == "table:table-source" {
+                    if let Some(href) = tag.get_attr("xlink:href") {
+                        links.push(href.to_string());
+                    }
+                }
+            }
+        }
+        for link in &self.dde_links {
+            links.push(link.topic().to_string());
+        }
+        links.sort();
+        links.dedup();
+        links
+    }
+
+    /// Replaces the targets of all links to external files. Useful when
+    /// the linked files move to another location.
+    ///
+    /// The function is called with each target, see [external_links](WorkBook::external_links),
+    /// and returns the new target or None to keep it.
+    ///
+    /// ```
+    /// use spreadsheet_ods::{Sheet, WorkBook};
+    ///
+    /// let mut book = WorkBook::new_empty();
+    /// let mut sheet = Sheet::new("1");
+    /// sheet.set_formula(0, 0, "of:=['file:///old/a.ods'#$Sheet1.A1]");
+    /// book.push_sheet(sheet);
+    ///
+    /// book.rewrite_external_links(|v| v.strip_prefix("file:///old/").map(|v| format!("file:///new/{}", v)));
+    /// assert_eq!(book.external_links(), vec!["file:///new/a.ods"]);
+    /// ```
+    pub fn rewrite_external_links<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&str) -> Option<String>,
+    {
+        for i in 0..self.sheets.len() {
+            let sheet = self.sheet_mut(i);
+            for cell in sheet.data.values_mut() {
+                if let Some(formula) = &cell.formula {
+                    cell.formula = Some(rewrite_external_refs(formula, &mut f));
+                }
+            }
+            for tag in sheet.extra.iter_mut() {
+                if tag.name() == "table:table-source" {
+                    if let Some(href) = tag.get_attr("xlink:href").and_then(&mut f) {
+                        tag.set_attr("xlink:href", href);
+                    }
+                }
+            }
+        }
+        for link in self.dde_links.iter_mut() {
+            if let Some(topic) = f(link.topic()) {
+                link.set_topic(topic);
+            }
+        }
+    }
+
     /// Adds a manifest entry, replaces an existing one with the same name.
     pub fn add_manifest(&mut self, manifest: Manifest) {
         self.manifest.insert(manifest.full_path.clone(), manifest);
@@ -1167,6 +3865,99 @@ impl WorkBook {
         self.manifest.get_mut(path)
     }
 
+    /// Embeds another document as object, e.g. a text document or a formula.
+    ///
+    /// The parts are the files of the document as (path, data), without
+    /// the mimetype and the manifest. This is what
+    /// [write_ods_parts](crate::write_ods_parts) returns. The media_type
+    /// is the mimetype of the document, e.g.
+    /// "application/vnd.oasis.opendocument.text".
+    ///
+    /// The parts are stored as "Object n/...". Applications that can't
+    /// render the object show the replacement image "ObjectReplacements/Object n"
+    /// instead. This is the "Thumbnails/thumbnail.png" of the parts, or a
+    /// gray placeholder image if there is none.
+    ///
+    /// Returns a DrawFrame that references the object. Set the position
+    /// and size of the frame and add it to a sheet with
+    /// [Sheet::add_draw_frame](crate::Sheet::add_draw_frame).
+    pub fn add_object<S: Into<String>>(
+        &mut self,
+        media_type: S,
+        parts: Vec<(String, Vec<u8>)>,
+    ) -> DrawFrame {
+        let mut n = 1;
+        let name = loop {
+            let name = format!("Object {}", n);
+            if !self.manifest.contains_key(&format!("{}/", name)) {
+                break name;
+            }
+            n += 1;
+        };
+
+        self.add_manifest(Manifest {
+            full_path: format!("{}/", name),
+            version: Some(self.version().clone()),
+            media_type: media_type.into(),
+            buffer: None,
+        });
+
+        let mut thumbnail = None;
+        for (path, buf) in parts {
+            match path.as_str() {
+                "mimetype" | "META-INF/manifest.xml" => {}
+                "Thumbnails/thumbnail.png" => {
+                    thumbnail = Some(buf);
+                }
+                _ if path.ends_with('/') => {}
+                _ => {
+                    let media_type = part_media_type(&path);
+                    self.add_manifest(Manifest::with_buf(
+                        format!("{}/{}", name, path),
+                        media_type,
+                        buf,
+                    ));
+                }
+            }
+        }
+
+        let mut frame = DrawFrame::new();
+        frame.push_content(DrawFrameContent::Object(DrawObject::new_href(format!(
+            "./{}",
+            name
+        ))));
+        let replacement = format!("ObjectReplacements/{}", name);
+        self.add_manifest(Manifest::with_buf(
+            replacement.as_str(),
+            "image/png",
+            thumbnail.unwrap_or_else(|| PLACEHOLDER_PNG.to_vec()),
+        ));
+
+        let mut image = DrawImage::new();
+        image.set_xlink_href(format!("./{}", replacement));
+        image.set_xlink_type(XLinkType::Simple);
+        image.set_xlink_show(XLinkShow::Embed);
+        image.set_xlink_actuate(XLinkActuate::OnLoad);
+        frame.push_content(DrawFrameContent::Image(image));
+        frame
+    }
+
+    /// Embeds a complete ODF package as object. The media type is taken
+    /// from the mimetype of the package.
+    ///
+    /// See [add_object](WorkBook::add_object).
+    pub fn add_object_package(&mut self, package: &[u8]) -> Result<DrawFrame, OdsError> {
+        let mut parts = read_package_parts(package)?;
+        let Some(pos) = parts.iter().position(|(path, _)| path == "mimetype") else {
+            return Err(OdsError::Ods("package without mimetype".to_string()));
+        };
+        let (_, media_type) = parts.remove(pos);
+        let media_type = String::from_utf8(media_type)
+            .map_err(|_| OdsError::Ods("invalid mimetype".to_string()))?;
+
+        Ok(self.add_object(media_type.trim(), parts))
+    }
+
     /// Gives access to meta-data.
     pub fn metadata(&self) -> &Metadata {
         &self.metadata
@@ -1187,21 +3978,238 @@ pub struct WorkBookConfig {
     pub show_grid: bool,
     /// Show page-breaks.
     pub show_page_breaks: bool,
+    /// Open the document in the page-break view instead of the normal view.
+    pub show_page_break_preview: bool,
     /// Are the sheet-tabs shown or not.
     pub has_sheet_tabs: bool,
 }
 
+/// One style of the workbook. See [WorkBook::styles].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StyleInfo {
+    /// Family of the style.
+    pub family: StyleFamily,
+    /// Name of the style.
+    pub name: String,
+    /// Styles.xml or content.xml.
+    pub origin: StyleOrigin,
+    /// Default, named or automatic style.
+    pub styleuse: StyleUse,
+    /// Number of references to this style.
+    pub refs: usize,
+}
+
+/// How [WorkBook::append_workbook] renames sheets and named styles
+/// that already exist.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum RenameStrategy {
+    /// Appends "_2", "_3", ... to the name.
+    #[default]
+    Suffix,
+    /// Prepends the string to the name. If this name exists too,
+    /// a suffix is appended.
+    Prefix(String),
+    /// Fails with an error.
+    Fail,
+}
+
+impl RenameStrategy {
+    /// Finds a new name that is not taken.
+    fn rename(&self, name: &str, taken: impl Fn(&str) -> bool) -> Result<String, OdsError> {
+        let base = match self {
+            RenameStrategy::Suffix => name.to_string(),
+            RenameStrategy::Prefix(prefix) => {
+                let new_name = format!("{}{}", prefix, name);
+                if !taken(&new_name) {
+                    return Ok(new_name);
+                }
+                new_name
+            }
+            RenameStrategy::Fail => {
+                return Err(OdsError::Ods(format!("Name already exists: {}", name)));
+            }
+        };
+        let mut cnt = 2;
+        loop {
+            let new_name = format!("{}_{}", base, cnt);
+            if !taken(&new_name) {
+                return Ok(new_name);
+            }
+            cnt += 1;
+        }
+    }
+}
+
+/// Maximum number of rows of a sheet.
+pub(crate) const MAX_ROWS: u32 = 1_048_576;
+/// Maximum number of columns of a sheet.
+pub(crate) const MAX_COLS: u32 = 16_384;
+
+/// Sheet names must not be empty, must not contain []*?:/\ and
+/// must not start or end with an apostrophe.
+fn valid_sheet_name(name: &str) -> bool {
+    !name.is_empty()
+        && !name.contains(['[', ']', '*', '?', ':', '/', '\\'])
+        && !name.starts_with('\'')
+        && !name.ends_with('\'')
+}
+
+/// A problem found by [WorkBook::validate].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ValidationIssue {
+    /// Value formats of different kinds use the same name.
+    DuplicateStyleName(String),
+    /// A style, value format, master page or page style is referenced
+    /// but doesn't exist.
+    DanglingStyleRef {
+        /// Where the reference is used.
+        location: String,
+        /// Name of the missing style.
+        style: String,
+    },
+    /// Two merged cell ranges overlap.
+    OverlappingMerge(CellRange, CellRange),
+    /// The sheet name is empty or contains a character that is not allowed.
+    InvalidSheetName(String),
+    /// More than one sheet has this name.
+    DuplicateSheetName(String),
+    /// A repeat or span is zero or exceeds the maximum size of a sheet.
+    RepeatOutOfRange {
+        /// Cell, row or column with the repeat.
+        location: String,
+        /// The repeat value.
+        repeat: u32,
+    },
+    /// A cell uses a content validation that doesn't exist.
+    UndefinedValidation {
+        /// The cell.
+        cell: CellRef,
+        /// Name of the missing validation.
+        validation: String,
+    },
+    /// A merged cell range extends beyond the maximum size of a sheet.
+    SpanOutOfRange(CellRange),
+    /// A print range is not within the used area of the sheet.
+    PrintRangeOutOfArea {
+        /// The sheet.
+        sheet: String,
+        /// The print range.
+        range: CellRange,
+    },
+}
+
+impl fmt::Display for ValidationIssue {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            ValidationIssue::DuplicateStyleName(name) => {
+                write!(f, "value format name {} is used more than once", name)
+            }
+            ValidationIssue::DanglingStyleRef { location, style } => {
+                write!(f, "{} references the missing style {}", location, style)
+            }
+            ValidationIssue::OverlappingMerge(r0, r1) => {
+                write!(f, "merged cells {} and {} overlap", r0, r1)
+            }
+            ValidationIssue::InvalidSheetName(name) => {
+                write!(f, "invalid sheet name {:?}", name)
+            }
+            ValidationIssue::DuplicateSheetName(name) => {
+                write!(f, "sheet name {:?} is used more than once", name)
+            }
+            ValidationIssue::RepeatOutOfRange { location, repeat } => {
+                write!(f, "repeat {} of {} is out of range", repeat, location)
+            }
+            ValidationIssue::UndefinedValidation { cell, validation } => {
+                write!(f, "{} uses the missing validation {}", cell, validation)
+            }
+            ValidationIssue::SpanOutOfRange(range) => {
+                write!(f, "merged cells {} exceed the size of a sheet", range)
+            }
+            ValidationIssue::PrintRangeOutOfArea { sheet, range } => {
+                write!(
+                    f,
+                    "print range {} is outside the used area of sheet {}",
+                    range, sheet
+                )
+            }
+        }
+    }
+}
+
 impl Default for WorkBookConfig {
     fn default() -> Self {
         Self {
             active_table: "".to_string(),
             show_grid: true,
             show_page_breaks: false,
+            show_page_break_preview: false,
             has_sheet_tabs: true,
         }
     }
 }
 
+/// Settings for the calculation of formulas. Maps to
+/// table:calculation-settings.
+///
+/// The defaults are the ones defined by the ODF specification. The
+/// element is only written if something differs from the defaults.
+///
+/// ```
+/// use chrono::NaiveDate;
+/// use spreadsheet_ods::WorkBook;
+///
+/// let mut book = WorkBook::new_empty();
+/// // 1904 date system.
+/// book.calc_settings_mut().null_date = NaiveDate::from_ymd_opt(1904, 1, 1).unwrap();
+/// book.calc_settings_mut().iteration = true;
+/// ```
+#[derive(Clone, Debug, PartialEq, GetSize)]
+pub struct CalcSettings {
+    /// Comparisons of text are case-sensitive.
+    pub case_sensitive: bool,
+    /// Calculations use the rounded values as displayed.
+    pub precision_as_shown: bool,
+    /// Search criteria must match the whole cell content.
+    pub search_criteria_must_apply_to_whole_cell: bool,
+    /// Find labels for the rows and columns automatically.
+    pub automatic_find_labels: bool,
+    /// Search criteria are regular expressions.
+    pub use_regular_expressions: bool,
+    /// Search criteria can contain the wildcards '?', '*' and '~'.
+    /// Takes precedence over use_regular_expressions.
+    pub use_wildcards: bool,
+    /// Two digit years are in the range null_year to null_year+99.
+    pub null_year: u32,
+    /// Day zero for the numeric value of dates. The 1904 date system
+    /// uses 1904-01-01.
+    #[get_size(ignore)]
+    pub null_date: NaiveDate,
+    /// Iterative calculation for circular references.
+    pub iteration: bool,
+    /// Maximum number of iteration steps.
+    pub iteration_steps: u32,
+    /// Iteration stops when the change is smaller than this.
+    pub iteration_min_difference: f64,
+}
+
+impl Default for CalcSettings {
+    fn default() -> Self {
+        Self {
+            case_sensitive: true,
+            precision_as_shown: false,
+            search_criteria_must_apply_to_whole_cell: true,
+            automatic_find_labels: true,
+            use_regular_expressions: true,
+            use_wildcards: false,
+            null_year: 1930,
+            null_date: NaiveDate::from_ymd_opt(1899, 12, 30).expect("valid date"),
+            iteration: false,
+            iteration_steps: 100,
+            iteration_min_difference: 0.001,
+        }
+    }
+}
+
 /// Script.
 #[derive(Debug, Default, Clone, GetSize)]
 pub struct Script {
@@ -1263,6 +4271,35 @@ impl EventListener {
         }
     }
 
+    /// Binds the event to a Basic macro of the document, e.g.
+    /// "Standard.Module1.Main".
+    pub fn new_basic<S: AsRef<str>>(event: ScriptEvent, macro_name: S) -> Self {
+        Self::new_script(
+            event,
+            format!(
+                "vnd.sun.star.script:{}?language=Basic&location=document",
+                macro_name.as_ref()
+            ),
+        )
+    }
+
+    /// Binds the event to a script url, e.g.
+    /// "vnd.sun.star.script:hello.py$hello?language=Python&location=document".
+    pub fn new_script<S: Into<String>>(event: ScriptEvent, href: S) -> Self {
+        Self {
+            event_name: event.to_string(),
+            script_lang: "ooo:script".to_string(),
+            href: href.into(),
+            link_type: XLinkType::Simple,
+            ..Default::default()
+        }
+    }
+
+    /// Typed event.
+    pub fn event(&self) -> ScriptEvent {
+        ScriptEvent::from(self.event_name.as_str())
+    }
+
     /// Name
     pub fn event_name(&self) -> &str {
         &self.event_name
@@ -1336,3 +4373,128 @@ impl Default for EventListener {
         }
     }
 }
+
+/// Events of the document, a sheet or a shape that can be bound to a macro.
+///
+/// The names are the ones used by LibreOffice.
+#[derive(Debug, Clone, PartialEq, Eq, GetSize)]
+pub enum ScriptEvent {
+    /// Document has been loaded.
+    Load,
+    /// Document is closed.
+    Unload,
+    /// New document from a template.
+    New,
+    /// Before saving.
+    Save,
+    /// Before saving with a new name.
+    SaveAs,
+    /// After saving.
+    SaveDone,
+    /// Before printing.
+    Print,
+    /// Document or sheet got the focus.
+    Focus,
+    /// Document or sheet lost the focus.
+    Unfocus,
+    /// Modified state changed.
+    ModifyChanged,
+    /// Selection changed.
+    Select,
+    /// Double click on a cell.
+    DoubleClick,
+    /// Right click on a cell.
+    RightClick,
+    /// Content of the sheet changed.
+    ContentChanged,
+    /// Formulas of the sheet have been calculated.
+    Calculated,
+    /// Any other event name.
+    Other(String),
+}
+
+impl From<&str> for ScriptEvent {
+    fn from(value: &str) -> Self {
+        match value {
+            "dom:load" => ScriptEvent::Load,
+            "dom:unload" => ScriptEvent::Unload,
+            "office:new" => ScriptEvent::New,
+            "office:save" => ScriptEvent::Save,
+            "office:save-as" => ScriptEvent::SaveAs,
+            "office:save-done" => ScriptEvent::SaveDone,
+            "office:print" => ScriptEvent::Print,
+            "dom:DOMFocusIn" => ScriptEvent::Focus,
+            "dom:DOMFocusOut" => ScriptEvent::Unfocus,
+            "office:modify-changed" => ScriptEvent::ModifyChanged,
+            "dom:select" => ScriptEvent::Select,
+            "office:dblclick" => ScriptEvent::DoubleClick,
+            "office:contextmenu" => ScriptEvent::RightClick,
+            "office:content-changed" => ScriptEvent::ContentChanged,
+            "office:calculated" => ScriptEvent::Calculated,
+            v => ScriptEvent::Other(v.to_string()),
+        }
+    }
+}
+
+impl fmt::Display for ScriptEvent {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            ScriptEvent::Load => write!(f, "dom:load"),
+            ScriptEvent::Unload => write!(f, "dom:unload"),
+            ScriptEvent::New => write!(f, "office:new"),
+            ScriptEvent::Save => write!(f, "office:save"),
+            ScriptEvent::SaveAs => write!(f, "office:save-as"),
+            ScriptEvent::SaveDone => write!(f, "office:save-done"),
+            ScriptEvent::Print => write!(f, "office:print"),
+            ScriptEvent::Focus => write!(f, "dom:DOMFocusIn"),
+            ScriptEvent::Unfocus => write!(f, "dom:DOMFocusOut"),
+            ScriptEvent::ModifyChanged => write!(f, "office:modify-changed"),
+            ScriptEvent::Select => write!(f, "dom:select"),
+            ScriptEvent::DoubleClick => write!(f, "office:dblclick"),
+            ScriptEvent::RightClick => write!(f, "office:contextmenu"),
+            ScriptEvent::ContentChanged => write!(f, "office:content-changed"),
+            ScriptEvent::Calculated => write!(f, "office:calculated"),
+            ScriptEvent::Other(v) => write!(f, "{}", v),
+        }
+    }
+}
+
+/// Replacement image for embedded objects without thumbnail.
+/// A single light gray pixel, scaled to the size of the frame.
+const PLACEHOLDER_PNG: &[u8] = &[
+    0x89, 0x50, 0x4E, 0x47, 0x0D, 0x0A, 0x1A, 0x0A, 0x00, 0x00, 0x00, 0x0D, 0x49, 0x48, 0x44, 0x52,
+    0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x01, 0x08, 0x00, 0x00, 0x00, 0x00, 0x3A, 0x7E, 0x9B,
+    0x55, 0x00, 0x00, 0x00, 0x0A, 0x49, 0x44, 0x41, 0x54, 0x78, 0xDA, 0x63, 0x38, 0x00, 0x00, 0x00,
+    0xC2, 0x00, 0xC1, 0xFF, 0xD6, 0x2D, 0xDC, 0x00, 0x00, 0x00, 0x00, 0x49, 0x45, 0x4E, 0x44, 0xAE,
+    0x42, 0x60, 0x82,
+];
+
+/// Media type for a part of an embedded document.
+fn part_media_type(path: &str) -> &'static str {
+    match path
+        .rsplit_once('.')
+        .map(|(_, ext)| ext.to_ascii_lowercase())
+    {
+        Some(ext) => match ext.as_str() {
+            "xml" => "text/xml",
+            "rdf" => "application/rdf+xml",
+            "png" => "image/png",
+            "jpg" | "jpeg" => "image/jpeg",
+            "gif" => "image/gif",
+            "svg" => "image/svg+xml",
+            _ => "",
+        },
+        None => "",
+    }
+}
+
+/// Format, file extension and media type of a font file.
+fn font_file_format(buf: &[u8]) -> Option<(&'static str, &'static str, &'static str)> {
+    match buf.get(0..4)? {
+        [0, 1, 0, 0] | b"true" => Some(("truetype", "ttf", "application/x-font-ttf")),
+        b"OTTO" => Some(("opentype", "otf", "application/x-font-otf")),
+        b"wOFF" => Some(("woff", "woff", "font/woff")),
+        b"wOF2" => Some(("woff2", "woff2", "font/woff2")),
+        _ => None,
+    }
+}
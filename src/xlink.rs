@@ -15,19 +15,26 @@ pub enum XLinkActuate {
 impl Display for XLinkActuate {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
-            XLinkActuate::OnLoad => write!(f, "OnLoad"),
-            XLinkActuate::OnRequest => write!(f, "OnRequest"),
+            XLinkActuate::OnLoad => write!(f, "onLoad"),
+            XLinkActuate::OnRequest => write!(f, "onRequest"),
         }
     }
 }
 
 /// See §5.6.1 of XLink.
 #[derive(Debug, Clone, Copy, GetSize)]
+#[non_exhaustive]
 pub enum XLinkShow {
     /// XLink
     New,
     /// XLink
     Replace,
+    /// XLink
+    Embed,
+    /// XLink
+    Other,
+    /// XLink
+    None,
 }
 
 impl Display for XLinkShow {
@@ -35,6 +42,9 @@ impl Display for XLinkShow {
         match self {
             XLinkShow::New => write!(f, "new"),
             XLinkShow::Replace => write!(f, "replace"),
+            XLinkShow::Embed => write!(f, "embed"),
+            XLinkShow::Other => write!(f, "other"),
+            XLinkShow::None => write!(f, "none"),
         }
     }
 }
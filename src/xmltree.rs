@@ -51,6 +51,9 @@ use crate::attrmap2::AttrMap2;
 use crate::text::TextP;
 use crate::OdsError;
 
+/// Upper limit for the spaces of a single text:s when it is expanded.
+pub(crate) const MAX_TEXT_SPACES: usize = 1000;
+
 /// Defines a XML tag and it's children.
 #[derive(Debug, Clone, Default, PartialEq, GetSize)]
 pub struct XmlTag {
@@ -207,12 +210,27 @@ impl XmlTag {
     }
 
     /// Extracts the plain text from this tag and its content.
+    /// The elements text:s, text:tab and text:line-break are converted
+    /// to spaces, tab and newline. Of a ruby only the base text is used.
+    ///
+    /// A text:s expands to at most 1000 spaces.
     pub fn extract_text(&self, buf: &mut String) {
         for c in &self.content {
             match c {
                 XmlContent::Text(t) => {
                     buf.push_str(t.as_str());
                 }
+                XmlContent::Tag(t) if t.name() == "text:s" => {
+                    let count = t.get_attr("text:c").and_then(|v| v.parse().ok());
+                    buf.push_str(&" ".repeat(count.unwrap_or(1).min(MAX_TEXT_SPACES)));
+                }
+                XmlContent::Tag(t) if t.name() == "text:tab" => {
+                    buf.push('\t');
+                }
+                XmlContent::Tag(t) if t.name() == "text:line-break" => {
+                    buf.push('\n');
+                }
+                XmlContent::Tag(t) if t.name() == "text:ruby-text" => {}
                 XmlContent::Tag(t) => {
                     t.extract_text(buf);
                 }
@@ -10,7 +10,7 @@ use spreadsheet_ods::metadata::Metadata;
 use spreadsheet_ods::sheet::{Grouped, SheetConfig};
 use spreadsheet_ods::style::{TableStyle, TableStyleRef};
 use spreadsheet_ods::text::TextTag;
-use spreadsheet_ods::{CellRange, ColRange, RowRange, Sheet, Value, WorkBook};
+use spreadsheet_ods::{CellRange, CellStyleRef, ColRange, RowRange, Sheet, Value, WorkBook};
 
 #[test]
 pub fn sizes() {
@@ -39,6 +39,7 @@ pub fn sizes() {
 pub fn sizes2() {
     println!("Sheet {}", size_of::<Sheet>());
     println!("TableStyleRef {}", size_of::<TableStyleRef>());
+    println!("CellStyleRef {}", size_of::<CellStyleRef>());
     // println!("CellData {}", size_of::<CellData>());
     // println!("CellDataExt {}", size_of::<CellDataExt>());
     // println!("ColHeader {}", size_of::<ColHeader>());
@@ -1,7 +1,13 @@
 mod lib_test;
 
 use lib_test::*;
-use spreadsheet_ods::{read_ods, OdsError};
+use spreadsheet_ods::color::Rgb;
+use spreadsheet_ods::draw::{DrawFrame, DrawFrameContent, ImageSize};
+use spreadsheet_ods::style::units::{FillStyle, Percent, StrokeStyle};
+use spreadsheet_ods::style::GraphicStyle;
+use spreadsheet_ods::{
+    cm, read_ods, read_ods_buf, write_ods_parts, CellRange, Length, OdsError, Sheet, WorkBook,
+};
 
 #[test]
 fn test_draw_image() -> Result<(), OdsError> {
@@ -32,3 +38,175 @@ fn test_images() -> Result<(), OdsError> {
 
     Ok(())
 }
+
+#[test]
+fn test_frame_geometry() {
+    let mut sh = Sheet::new("Sheet1");
+    sh.set_col_width(1, cm!(2));
+    sh.set_col_width(2, cm!(3));
+    sh.set_row_height(1, cm!(1));
+    sh.set_row_height(2, cm!(1));
+
+    let range = CellRange::local(1, 1, 2, 2);
+    assert_eq!(sh.range_size(&range), (cm!(5), cm!(2)));
+
+    sh.add_draw_frame_cover(&range, DrawFrame::new());
+    let frame = &sh.draw_frames(1, 1).expect("frame")[0];
+    assert_eq!(frame.attrmap().attr("svg:width"), Some("5cm"));
+    assert_eq!(frame.attrmap().attr("svg:height"), Some("2cm"));
+    assert_eq!(
+        frame.attrmap().attr("table:end-cell-address"),
+        Some("Sheet1.D4")
+    );
+
+    // 2:1 in a 5x2 range is limited by the height.
+    sh.add_draw_frame_fit(&range, 2.0, DrawFrame::new());
+    let frame = &sh.draw_frames(1, 1).expect("frame")[1];
+    assert_eq!(frame.attrmap().attr("svg:width"), Some("4cm"));
+    assert_eq!(frame.attrmap().attr("svg:height"), Some("2cm"));
+
+    let size = ImageSize::new(192, 96);
+    assert_eq!(size.natural_width(), Length::In(2.0));
+    assert_eq!(size.aspect_ratio(), Some(2.0));
+    assert_eq!(ImageSize::new(192, 0).aspect_ratio(), None);
+    assert_eq!(ImageSize::new(0, 96).aspect_ratio(), None);
+
+    // no aspect ratio, the frame covers the range.
+    sh.add_draw_frame_fit(&range, f64::NAN, DrawFrame::new());
+    let frame = &sh.draw_frames(1, 1).expect("frame")[2];
+    assert_eq!(frame.attrmap().attr("svg:width"), Some("5cm"));
+    assert_eq!(frame.attrmap().attr("svg:height"), Some("2cm"));
+}
+
+#[cfg(feature = "image_size")]
+#[test]
+fn test_image_size() -> Result<(), OdsError> {
+    let mut png = b"\x89PNG\r\n\x1a\n".to_vec();
+    png.extend_from_slice(&13u32.to_be_bytes());
+    png.extend_from_slice(b"IHDR");
+    png.extend_from_slice(&300u32.to_be_bytes());
+    png.extend_from_slice(&150u32.to_be_bytes());
+    png.extend_from_slice(&[8, 2, 0, 0, 0]);
+    png.extend_from_slice(&[0, 0, 0, 0]);
+    png.extend_from_slice(&9u32.to_be_bytes());
+    png.extend_from_slice(b"pHYs");
+    // 300 dpi
+    png.extend_from_slice(&11811u32.to_be_bytes());
+    png.extend_from_slice(&11811u32.to_be_bytes());
+    png.push(1);
+    png.extend_from_slice(&[0, 0, 0, 0]);
+
+    let size = ImageSize::from_image(&png)?;
+    assert_eq!((size.width, size.height), (300, 150));
+    assert!((size.dpi_x - 300.0).abs() < 0.01);
+
+    let mut gif = b"GIF89a".to_vec();
+    gif.extend_from_slice(&64u16.to_le_bytes());
+    gif.extend_from_slice(&32u16.to_le_bytes());
+    let size = ImageSize::from_image(&gif)?;
+    assert_eq!((size.width, size.height), (64, 32));
+
+    assert!(ImageSize::from_image(b"nothing").is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_draw_object() -> Result<(), OdsError> {
+    let mut embedded = WorkBook::new_empty();
+    let mut sh = Sheet::new("embedded");
+    sh.set_value(0, 0, 42);
+    embedded.push_sheet(sh);
+    let mut parts = write_ods_parts(&mut embedded)?;
+    parts.push((
+        "Thumbnails/thumbnail.png".to_string(),
+        vec![0x89, b'P', b'N', b'G'],
+    ));
+
+    let mut wb = WorkBook::new_empty();
+    let frame = wb.add_object("application/vnd.oasis.opendocument.spreadsheet", parts);
+    let mut sh = Sheet::new("1");
+    sh.add_draw_frame(1, 1, frame);
+    wb.push_sheet(sh);
+
+    let buf = test_write_odsbuf(&mut wb)?;
+    let mut wb = read_ods_buf(&buf)?;
+
+    let object = wb.manifest("Object 1/").expect("object");
+    assert_eq!(
+        object.media_type,
+        "application/vnd.oasis.opendocument.spreadsheet"
+    );
+    assert!(wb.manifest("Object 1/content.xml").is_some());
+    assert!(wb.manifest("ObjectReplacements/Object 1").is_some());
+
+    let frames = wb.sheet(0).draw_frames(1, 1).expect("frames");
+    let content = frames[0].content_ref();
+    assert!(matches!(&content[0], DrawFrameContent::Object(obj)
+            if obj.attrmap().attr("xlink:href") == Some("./Object 1")));
+    assert!(matches!(&content[1], DrawFrameContent::Image(_)));
+
+    let package = test_write_odsbuf(&mut embedded)?;
+    let frame = wb.add_object_package(&package)?;
+    assert!(
+        matches!(&frame.content_ref()[0], DrawFrameContent::Object(obj)
+            if obj.attrmap().attr("xlink:href") == Some("./Object 2"))
+    );
+    assert!(wb.manifest("Object 2/styles.xml").is_some());
+    // no thumbnail, gets a placeholder.
+    assert!(matches!(
+        &frame.content_ref()[1],
+        DrawFrameContent::Image(_)
+    ));
+    let replacement = wb
+        .manifest("ObjectReplacements/Object 2")
+        .expect("replacement");
+    assert!(replacement
+        .buffer
+        .as_ref()
+        .is_some_and(|v| v.starts_with(b"\x89PNG")));
+
+    Ok(())
+}
+
+#[test]
+fn test_graphicstyle() -> Result<(), OdsError> {
+    let mut wb = WorkBook::new_empty();
+
+    let mut gs = GraphicStyle::new("gs1");
+    gs.set_fill_color(Rgb::new(255, 255, 0));
+    gs.set_opacity(Percent::Percent(50.0));
+    gs.set_stroke(StrokeStyle::Solid);
+    gs.set_stroke_color(Rgb::new(0, 0, 255));
+    gs.set_stroke_width(Length::Mm(0.5));
+    gs.set_shadow_visible(true);
+    gs.set_shadow_color(Rgb::new(128, 128, 128));
+    gs.set_shadow_offset(Length::Mm(1.0), Length::Mm(1.0));
+    let gs = wb.add_graphicstyle(gs);
+
+    let mut frame = DrawFrame::new();
+    frame.set_draw_style_name(gs);
+    let mut sh = Sheet::new("1");
+    sh.add_draw_frame(1, 1, frame);
+    wb.push_sheet(sh);
+
+    let buf = test_write_odsbuf(&mut wb)?;
+    let wb = read_ods_buf(&buf)?;
+
+    let gs = wb.graphicstyle("gs1").expect("graphicstyle");
+    assert_eq!(gs.fill()?, Some(FillStyle::Solid));
+    assert_eq!(gs.fill_color()?, Some(Rgb::new(255, 255, 0)));
+    assert_eq!(gs.opacity()?, Some(Percent::Percent(50.0)));
+    assert_eq!(gs.stroke()?, Some(StrokeStyle::Solid));
+    assert_eq!(gs.stroke_color()?, Some(Rgb::new(0, 0, 255)));
+    assert_eq!(gs.stroke_width()?, Some(Length::Mm(0.5)));
+    assert!(gs.shadow_visible());
+    assert_eq!(gs.shadow_color()?, Some(Rgb::new(128, 128, 128)));
+    assert_eq!(
+        gs.shadow_offset()?,
+        Some((Length::Mm(1.0), Length::Mm(1.0)))
+    );
+    assert_eq!(gs.shadow_opacity()?, None);
+
+    Ok(())
+}
@@ -3,11 +3,15 @@ mod lib_test;
 use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
 use icu_locid::locale;
 use lib_test::*;
-use spreadsheet_ods::format::{FormatCalendarStyle, FormatNumberStyle};
+use spreadsheet_ods::format::{
+    currency, currency_format, currency_symbol, FormatCalendarStyle, FormatNumberStyle,
+    FormatPartType, NumberFormatBuilder, ValueFormatTrait,
+};
 use spreadsheet_ods::style::CellStyle;
 use spreadsheet_ods::{
-    OdsError, Sheet, ValueFormatBoolean, ValueFormatCurrency, ValueFormatDateTime,
-    ValueFormatNumber, ValueFormatPercentage, WorkBook,
+    read_ods_buf, OdsError, Sheet, Value, ValueFormatBoolean, ValueFormatCurrency,
+    ValueFormatDateTime, ValueFormatNumber, ValueFormatPercentage, ValueFormatTimeDuration,
+    WorkBook,
 };
 
 #[test]
@@ -127,3 +131,322 @@ fn write_format() -> Result<(), OdsError> {
         test_write_ods(&mut wb, path)
     }
 }
+
+#[test]
+fn test_number_format_builder() -> Result<(), OdsError> {
+    let mut wb = WorkBook::new_empty();
+    // the name of the helper format is taken.
+    wb.add_datetime_format(ValueFormatDateTime::new_named("n_deP0"));
+
+    let f_de = NumberFormatBuilder::de_de("n_de")
+        .decimal_places(3)
+        .negative_red(true)
+        .build_into(&mut wb);
+    let f_us = ValueFormatNumber::builder("", locale!("en_US"))
+        .thousands_separator(true)
+        .min_integer_digits(3)
+        .build_into(&mut wb);
+
+    let c_de = wb.add_cellstyle(CellStyle::new("c_de", &f_de));
+    let c_us = wb.add_cellstyle(CellStyle::new("c_us", &f_us));
+
+    let mut sh = Sheet::new("1");
+    sh.set_styled_value(0, 0, -1234.5f64, &c_de);
+    sh.set_styled_value(1, 0, 1234.5f64, &c_us);
+    wb.push_sheet(sh);
+
+    let buf = test_write_odsbuf(&mut wb)?;
+    let wb = read_ods_buf(&buf)?;
+
+    let v = wb.number_format("n_de").expect("n_de");
+    assert_eq!(v.locale(), Some(locale!("de_DE")));
+    assert_eq!(v.textstyle().attr("fo:color"), Some("#ff0000"));
+    assert_eq!(v.parts()[0].part_type(), FormatPartType::Text);
+    assert_eq!(v.parts()[1].attr_def("number:decimal-places", ""), "3");
+    assert_eq!(v.parts()[1].attr_def("number:grouping", ""), "true");
+    let maps = v.stylemaps().expect("stylemaps");
+    assert_eq!(maps[0].applied_style(), "n_deP1");
+    assert!(wb.datetime_format("n_deP0").is_some());
+
+    let v = wb.number_format("n_deP1").expect("n_deP1");
+    assert_eq!(v.parts().len(), 1);
+    assert_eq!(v.parts()[0].part_type(), FormatPartType::Number);
+
+    let v = wb.number_format(f_us.as_str()).expect("n_us");
+    assert_eq!(v.locale(), Some(locale!("en_US")));
+    assert_eq!(v.parts()[0].attr_def("number:min-integer-digits", ""), "3");
+    assert!(v.stylemaps().is_none());
+    assert_eq!(f_us.as_str(), "val_number0");
+    assert!(wb.number_format("val_number1").is_none());
+
+    Ok(())
+}
+
+#[test]
+fn test_format_string() -> Result<(), OdsError> {
+    let (v, sub) = ValueFormatNumber::from_format_string("n0", "#,##0.00;[RED]-#,##0.00")?;
+    assert_eq!(sub.len(), 1);
+    assert_eq!(sub[0].name(), "n0P0");
+    assert_eq!(sub[0].to_format_string(), "#,##0.00");
+    assert_eq!(v.to_format_string(), "[RED]-#,##0.00");
+    assert_eq!(v.textstyle().attr("fo:color"), Some("#ff0000"));
+    assert_eq!(
+        v.stylemaps().expect("stylemaps")[0].condition().to_string(),
+        "value()>=0"
+    );
+    assert_eq!(
+        v.to_format_string_with(|n| sub.iter().find(|v| v.name() == n)),
+        "#,##0.00;[RED]-#,##0.00"
+    );
+
+    let (v, sub) = ValueFormatNumber::from_format_string("n1", "[>=100]0.0;[<0]\"neg\" 0;0")?;
+    assert_eq!(
+        v.to_format_string_with(|n| sub.iter().find(|v| v.name() == n)),
+        "[>=100]0.0;[<0]\"neg \"0;0"
+    );
+
+    for f in [
+        "0.00E+00",
+        "# ?/?",
+        "??/16",
+        "0.0#,,",
+        "000",
+        "*-0",
+        "[$€]\\ #,##0.00",
+    ] {
+        let (v, _) = ValueFormatNumber::from_format_string("n", f)?;
+        assert_eq!(v.to_format_string(), f.replace('\\', ""), "{}", f);
+    }
+
+    let (v, _) = ValueFormatPercentage::from_format_string("p", "0.0%")?;
+    assert_eq!(v.to_format_string(), "0.0%");
+
+    let (v, _) = ValueFormatDateTime::from_format_string("d", "DD.MM.YYYY HH:MM:SS.00")?;
+    assert_eq!(v.parts()[2].part_type(), FormatPartType::Month);
+    assert_eq!(v.parts()[8].part_type(), FormatPartType::Minutes);
+    assert_eq!(v.to_format_string(), "DD.MM.YYYY HH:MM:SS.00");
+
+    let (v, _) = ValueFormatDateTime::from_format_string("d", "MMM D, YYYY h:mm AM/PM")?;
+    assert_eq!(v.to_format_string(), "MMM D, YYYY H:MM AM/PM");
+
+    assert!(ValueFormatNumber::from_format_string("x", "0 [FOO]").is_err());
+    assert!(ValueFormatNumber::from_format_string("x", "0 \"abc").is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_currency_format() -> Result<(), OdsError> {
+    assert_eq!(currency_symbol("EUR"), Some("€"));
+    assert_eq!(currency_symbol("usd"), Some("US$"));
+    assert_eq!(currency("JPY").map(|v| v.decimals()), Some(0));
+    assert!(currency("XXX").is_none());
+
+    let v = currency_format(locale!("de_DE"), "EUR")?;
+    assert_eq!(v.to_format_string(), "#,##0.00 [$€]");
+
+    let v = currency_format(locale!("en_US"), "USD")?;
+    assert_eq!(v.to_format_string(), "[$$]#,##0.00");
+
+    let v = currency_format(locale!("en_US"), "JPY")?;
+    assert_eq!(v.to_format_string(), "[$JP¥]#,##0");
+    assert_eq!(v.parts()[0].attr_def("number:country", ""), "JP");
+
+    let v = currency_format(locale!("en_US"), "CHF")?;
+    assert_eq!(v.to_format_string(), "[$CHF] #,##0.00");
+
+    let v = currency_format(locale!("de_AT"), "EUR")?;
+    assert_eq!(v.to_format_string(), "[$€] #,##0.00");
+
+    assert!(currency_format(locale!("de_AT"), "XXX").is_err());
+
+    let mut wb = WorkBook::new_empty();
+    let fref = wb.add_currency_format(currency_format(locale!("fr_FR"), "KWD")?);
+    assert_eq!(
+        wb.currency_format(fref.as_str())
+            .expect("format")
+            .to_format_string(),
+        "#,##0.000 [$KWD]"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_fraction_scientific() -> Result<(), OdsError> {
+    let mut wb = WorkBook::new_empty();
+
+    let mut v = ValueFormatNumber::new_named("frac1");
+    v.part_fraction()
+        .min_integer_digits(0)
+        .min_numerator_digits(1)
+        .denominator_digits(2)
+        .build();
+    wb.add_number_format(v);
+
+    let mut v = ValueFormatNumber::new_named("frac2");
+    v.part_fraction()
+        .min_numerator_digits(2)
+        .sixteenths()
+        .build();
+    wb.add_number_format(v);
+
+    let mut v = ValueFormatNumber::new_named("sci1");
+    v.part_scientific()
+        .min_integer_digits(1)
+        .fixed_decimal_places(3)
+        .min_exponent_digits(3)
+        .forced_exponent_sign(false)
+        .build();
+    wb.add_number_format(v);
+
+    let mut v = ValueFormatNumber::new_named("sci2");
+    v.part_scientific()
+        .min_integer_digits(3)
+        .decimal_places(2)
+        .min_exponent_digits(1)
+        .engineering()
+        .build();
+    wb.add_number_format(v);
+
+    wb.push_sheet(Sheet::new("1"));
+    let buf = test_write_odsbuf(&mut wb)?;
+    let wb = read_ods_buf(&buf)?;
+
+    let v = wb.number_format("frac1").expect("frac1");
+    let p = &v.parts()[0];
+    assert_eq!(p.part_type(), FormatPartType::Fraction);
+    assert_eq!(p.attr_def("number:min-integer-digits", ""), "0");
+    assert_eq!(p.attr_def("number:min-denominator-digits", ""), "2");
+    assert_eq!(p.attr_def("number:max-denominator-value", ""), "99");
+    assert_eq!(v.to_format_string(), "# ?/??");
+
+    let v = wb.number_format("frac2").expect("frac2");
+    let p = &v.parts()[0];
+    assert_eq!(p.attr_def("number:denominator-value", ""), "16");
+    assert_eq!(p.attr_def("number:min-integer-digits", "-"), "-");
+    assert_eq!(v.to_format_string(), "??/16");
+
+    let v = wb.number_format("sci1").expect("sci1");
+    let p = &v.parts()[0];
+    assert_eq!(p.part_type(), FormatPartType::ScientificNumber);
+    assert_eq!(p.attr_def("number:forced-exponent-sign", ""), "false");
+    assert_eq!(v.to_format_string(), "0.000E-000");
+
+    let v = wb.number_format("sci2").expect("sci2");
+    let p = &v.parts()[0];
+    assert_eq!(p.attr_def("number:exponent-interval", ""), "3");
+    assert_eq!(v.to_format_string(), "000.00E+0");
+
+    Ok(())
+}
+
+#[test]
+fn test_format_value() -> Result<(), OdsError> {
+    let (v, _) = ValueFormatNumber::from_format_string("n", "#,##0.00")?;
+    assert_eq!(v.format_value(&Value::Number(1234567.891)), "1,234,567.89");
+    assert_eq!(v.format_value(&Value::Number(-0.5)), "-0.50");
+    assert_eq!(v.format_value(&Value::Number(-0.001)), "0.00");
+    assert_eq!(v.format_value(&Value::Text("abc".into())), "abc");
+
+    let (v, _) = ValueFormatNumber::from_format_string("n", "0.0#")?;
+    assert_eq!(v.format_value(&Value::Number(2.0)), "2.0");
+    assert_eq!(v.format_value(&Value::Number(2.257)), "2.26");
+    assert_eq!(v.format_value(&Value::Number(1.005)), "1.01");
+
+    let (v, _) = ValueFormatNumber::from_format_string("n", "0.00E+00")?;
+    assert_eq!(v.format_value(&Value::Number(12345.0)), "1.23E+04");
+    assert_eq!(v.format_value(&Value::Number(0.000999)), "9.99E-04");
+
+    let (v, _) = ValueFormatNumber::from_format_string("n", "# ?/?")?;
+    assert_eq!(v.format_value(&Value::Number(1.25)), "1 1/4");
+    assert_eq!(v.format_value(&Value::Number(3.0)), "3");
+
+    let (v, _) = ValueFormatPercentage::from_format_string("p", "0.0%")?;
+    assert_eq!(v.format_value(&Value::Percentage(0.1234)), "12.3%");
+
+    let (v, sub) = ValueFormatNumber::from_format_string("n", "0.00;[RED]\"neg \"0.00")?;
+    let lookup = |n: &str| sub.iter().find(|v| v.name() == n);
+    assert_eq!(v.format_value_with(&Value::Number(1.5), lookup), "1.50");
+    assert_eq!(
+        v.format_value_with(&Value::Number(-1.5), lookup),
+        "neg 1.50"
+    );
+
+    let (mut v, _) = ValueFormatTimeDuration::from_format_string("t", "HH:MM")?;
+    let duration = Value::TimeDuration(chrono::Duration::minutes(26 * 60 + 5));
+    assert_eq!(v.format_value(&duration), "02:05");
+    v.set_truncate_on_overflow(false);
+    assert_eq!(v.format_value(&duration), "26:05");
+
+    // out of range for a date.
+    let (v, _) = ValueFormatDateTime::from_format_string("d", "DD.MM.YYYY HH:MM:SS")?;
+    assert_eq!(v.format_value(&Value::Number(1e12)), "1000000000000");
+    assert_eq!(
+        v.format_value(&Value::Number(-1e300)),
+        Value::Number(-1e300).as_f64_or_default().to_string()
+    );
+    assert_eq!(v.format_value(&Value::Number(f64::NAN)), "NaN");
+
+    let mut v = ValueFormatNumber::new_empty();
+    v.part_fraction().max_denominator(u32::MAX as i64).build();
+    assert_eq!(v.format_value(&Value::Number(0.25)), "1/4");
+
+    Ok(())
+}
+
+#[cfg(feature = "locale_de_AT")]
+#[test]
+fn test_format_value_locale() -> Result<(), OdsError> {
+    let mut v = ValueFormatNumber::new_localized("n", locale!("de_AT"));
+    v.part_number()
+        .min_integer_digits(1)
+        .decimal_places(2)
+        .grouping()
+        .build();
+    assert_eq!(v.format_value(&Value::Number(1234.5)), "1.234,50");
+
+    let (v, _) = ValueFormatDateTime::from_format_string("d", "DD.MM.YYYY HH:MM:SS")?;
+    let dt = NaiveDate::from_ymd_opt(2024, 3, 9)
+        .expect("date")
+        .and_hms_milli_opt(7, 5, 59, 600)
+        .expect("time");
+    assert_eq!(v.format_value(&Value::DateTime(dt)), "09.03.2024 07:06:00");
+    assert_eq!(v.format_value(&Value::Number(1.5)), "31.12.1899 12:00:00");
+
+    let (v, _) = ValueFormatDateTime::from_format_string("d", "DDDD, MMMM D, YY h:mm AM/PM")?;
+    assert_eq!(
+        v.format_value(&Value::DateTime(dt)),
+        "Saturday, March 9, 24 7:05 AM"
+    );
+
+    let mut v = ValueFormatDateTime::new_localized("d", locale!("de_AT"));
+    v.part_day().build();
+    v.part_text(". ").build();
+    v.part_month().textual().long_style().build();
+    assert_eq!(v.format_value(&Value::Date(dt.date())), "9. März");
+
+    let mut v = ValueFormatBoolean::new_localized("b", locale!("de_AT"));
+    v.part_boolean().build();
+    assert_eq!(v.format_value(&Value::Boolean(true)), "WAHR");
+
+    let mut wb = WorkBook::new(locale!("de_AT"));
+    let mut sh = Sheet::new("1");
+    sh.set_value(0, 0, 1234.5);
+    sh.set_value(0, 1, NaiveDate::from_ymd_opt(2024, 3, 9).expect("date"));
+    sh.set_value(0, 2, "text");
+    wb.push_sheet(sh);
+
+    let fref = wb.add_number_format(ValueFormatNumber::from_format_string("n", "0.0")?.0);
+    let sref = wb.add_cellstyle(CellStyle::new("num", &fref));
+    wb.sheet_mut(0).set_cellstyle(0, 3, &sref);
+    wb.sheet_mut(0).set_value(0, 3, 2.25);
+
+    assert_eq!(wb.display_value(0, 0, 0), "1234,50");
+    assert_eq!(wb.display_value(0, 0, 1), "09.03.2024");
+    assert_eq!(wb.display_value(0, 0, 2), "text");
+    assert_eq!(wb.display_value(0, 0, 3), "2.3");
+    assert_eq!(wb.display_value(0, 1, 0), "");
+
+    Ok(())
+}
@@ -0,0 +1,209 @@
+use spreadsheet_ods::formula::{
+    collapse_range_names, expand_range_names, external_refs, formula_from_plain, formula_to_plain,
+    rewrite_external_refs, shift_refs,
+};
+use spreadsheet_ods::{
+    read_ods_buf, CellRange, OdsError, OdsOptions, OdsWriteOptions, Sheet, Target, WorkBook,
+};
+use std::io::Cursor;
+
+#[test]
+fn test_formula_to_plain() {
+    assert_eq!(formula_to_plain("of:=[.A1]+1"), "=A1+1");
+    assert_eq!(formula_to_plain("of:=SUM([.$A$1:.B$2])"), "=SUM($A$1:B$2)");
+    assert_eq!(
+        formula_to_plain("of:=IF([.A1]>0;\"a;[.B1]\";[Sheet2.C3])"),
+        "=IF(A1>0,\"a;[.B1]\",Sheet2!C3)"
+    );
+    assert_eq!(
+        formula_to_plain("of:=SUM(['My Sheet'.A1:'My Sheet'.B2])"),
+        "=SUM('My Sheet'!A1:B2)"
+    );
+    assert_eq!(formula_to_plain("of:=[$Data-1.A1]"), "='Data-1'!A1");
+    assert_eq!(formula_to_plain("of:=SUM([.A:.B])"), "=SUM(A:B)");
+    assert_eq!(
+        formula_to_plain("of:=['file:///tmp/x.ods'#$Sheet1.A1]"),
+        "=['file:///tmp/x.ods'#$Sheet1.A1]"
+    );
+    assert_eq!(
+        formula_to_plain("of:=SUMPRODUCT({1;2|3;4};{\"a|b\"})"),
+        "=SUMPRODUCT({1,2;3,4},{\"a|b\"})"
+    );
+    assert_eq!(formula_to_plain("msoxl:=A1"), "msoxl:=A1");
+    assert_eq!(formula_to_plain("=A1"), "=A1");
+}
+
+#[test]
+fn test_formula_from_plain() {
+    assert_eq!(formula_from_plain("=A1+1"), "of:=[.A1]+1");
+    assert_eq!(
+        formula_from_plain("=SUM($A$1:B$2)"),
+        "of:=SUM([.$A$1:.B$2])"
+    );
+    assert_eq!(
+        formula_from_plain("=IF(A1>0,\"a,B1\",Sheet2!C3)"),
+        "of:=IF([.A1]>0;\"a,B1\";[Sheet2.C3])"
+    );
+    assert_eq!(
+        formula_from_plain("=SUM('My Sheet'!A1:B2)"),
+        "of:=SUM(['My Sheet'.A1:.B2])"
+    );
+    assert_eq!(
+        formula_from_plain("=SUM(A:B)+SUM(1:3)"),
+        "of:=SUM([.A:.B])+SUM([.1:.3])"
+    );
+    assert_eq!(
+        formula_from_plain("=LOG10(1.5E+3)*Total"),
+        "of:=LOG10(1.5E+3)*Total"
+    );
+    assert_eq!(
+        formula_from_plain("=SUMPRODUCT({1,2;3,4},A1:B2)"),
+        "of:=SUMPRODUCT({1;2|3;4};[.A1:.B2])"
+    );
+    assert_eq!(formula_from_plain("of:=[.A1]"), "of:=[.A1]");
+
+    for f in [
+        "of:=SUM([.A1:.B2];[Sheet2.C3])",
+        "of:=IF([.A1]>0;\"x\";['My Sheet'.B1])",
+        "of:=SUM({1;2|3;4})",
+    ] {
+        assert_eq!(formula_from_plain(&formula_to_plain(f)), f);
+    }
+}
+
+#[test]
+fn test_google_roundtrip() -> Result<(), OdsError> {
+    let mut wb = WorkBook::new_empty();
+    let mut sh = Sheet::new("1");
+    sh.set_value(0, 0, 1);
+    sh.set_formula(0, 1, "of:=SUM([.A1:.A3];2)");
+    wb.push_sheet(sh);
+
+    let mut buf = Cursor::new(Vec::new());
+    OdsWriteOptions::default()
+        .target(Target::GoogleSheets)
+        .write_ods(&mut wb, &mut buf)?;
+    let buf = buf.into_inner();
+
+    let wb2 = read_ods_buf(&buf)?;
+    assert_eq!(
+        wb2.sheet(0).formula(0, 1),
+        Some(&"=SUM(A1:A3,2)".to_string())
+    );
+
+    let wb2 = OdsOptions::default()
+        .plain_formulas()
+        .read_ods(Cursor::new(&buf))?;
+    assert_eq!(
+        wb2.sheet(0).formula(0, 1),
+        Some(&"of:=SUM([.A1:.A3];2)".to_string())
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_external_refs() {
+    assert_eq!(
+        external_refs("of:=['file:///a.ods'#$Sheet1.A1:.B2]+[.A1]+\"['x'#y]\""),
+        vec!["file:///a.ods".to_string()]
+    );
+    assert_eq!(
+        external_refs("of:=['file:///it''s.ods'#$'My Sheet'.A1]"),
+        vec!["file:///it's.ods".to_string()]
+    );
+    assert!(external_refs("of:=['My Sheet'.A1]").is_empty());
+
+    assert_eq!(
+        rewrite_external_refs("of:=['file:///a.ods'#$S.A1]+['file:///b.ods'#$S.A1]", |v| {
+            (v == "file:///a.ods").then(|| "file:///it's.ods".to_string())
+        }),
+        "of:=['file:///it''s.ods'#$S.A1]+['file:///b.ods'#$S.A1]"
+    );
+
+    let dde = "of:=DDE(\"soffice\"; \"file:///a.ods\";\"Sheet1.A1\")+dde(\"x\";[.A1];\"y\")";
+    assert_eq!(external_refs(dde), vec!["file:///a.ods".to_string()]);
+    assert_eq!(
+        rewrite_external_refs(dde, |v| Some(format!("{}\"x", v))),
+        "of:=DDE(\"soffice\"; \"file:///a.ods\"\"x\";\"Sheet1.A1\")+dde(\"x\";[.A1];\"y\")"
+    );
+    assert!(external_refs("of:=MYDDE(\"a\";\"b\";\"c\")+\"DDE(\"\"a\"\";\"\"b\"\")\"").is_empty());
+}
+
+#[test]
+fn test_range_names() -> Result<(), OdsError> {
+    let sales = CellRange::remote("Data", 0, 0, 9, 0).absolute();
+    assert_eq!(
+        expand_range_names("of:=SUM(Sales)+Sales.X+\"Sales\"+SALES(1)", |v| {
+            (v == "Sales" || v == "SALES").then(|| sales.clone())
+        }),
+        "of:=SUM([$Data.$A$1:.$A$10])+Sales.X+\"Sales\"+SALES(1)"
+    );
+    assert_eq!(
+        collapse_range_names("of:=SUM([Data.A1:.A10])+[.A1]+\"[.A1]\"", |v| {
+            (v.row() == 0 && v.to_row() == 9).then(|| "Sales".to_string())
+        }),
+        "of:=SUM(Sales)+[.A1]+\"[.A1]\""
+    );
+
+    let mut wb = WorkBook::new_empty();
+    let mut sh = Sheet::new("Data");
+    for i in 0..10 {
+        sh.set_value(i, 0, i);
+    }
+    sh.set_formula(0, 1, "of:=SUM([.$A$1:.$A$10])");
+    sh.set_formula(1, 1, "of:=SUM([.A1:.A9])");
+    sh.set_formula(2, 1, "of:=SUM([.A1:.A10])");
+    wb.push_sheet(sh);
+    wb.add_named_range("Sales", &sales);
+    wb.add_named_range("Sales", &sales);
+    assert_eq!(wb.range_names(), vec![("Sales".to_string(), sales.clone())]);
+
+    let mut buf = Cursor::new(Vec::new());
+    OdsWriteOptions::default()
+        .use_range_names(true)
+        .write_ods(&mut wb, &mut buf)?;
+    assert_eq!(
+        wb.sheet(0).formula(0, 1),
+        Some(&"of:=SUM([.$A$1:.$A$10])".to_string())
+    );
+
+    let wb = read_ods_buf(&buf.into_inner())?;
+    assert_eq!(wb.named_range("Sales"), Some(sales));
+    assert_eq!(
+        wb.sheet(0).formula(0, 1),
+        Some(&"of:=SUM(Sales)".to_string())
+    );
+    assert_eq!(
+        wb.sheet(0).formula(1, 1),
+        Some(&"of:=SUM([.A1:.A9])".to_string())
+    );
+    assert_eq!(
+        wb.sheet(0).formula(2, 1),
+        Some(&"of:=SUM([.A1:.A10])".to_string())
+    );
+    assert_eq!(
+        wb.expand_range_names(0, "of:=SUM(Sales)"),
+        "of:=SUM([$Data.$A$1:.$A$10])"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_shift_refs() {
+    assert_eq!(
+        shift_refs("of:=SUM([.A1:.B2];[.$C:.D];[.1:.$2])&\"[.A1]\"", 1, 1),
+        "of:=SUM([.B2:.C3];[.$C:.E];[.2:.$2])&\"[.A1]\""
+    );
+    assert_eq!(
+        shift_refs("of:=['file:///a.ods'#$Sheet1.A1]+['My Sheet'.B2]", 2, 1),
+        "of:=['file:///a.ods'#$Sheet1.B3]+['My Sheet'.C4]"
+    );
+    assert_eq!(shift_refs("of:=[.A1]+[.$A$1]", 0, -1), "of:=#REF!+[.$A$1]");
+    assert_eq!(shift_refs("of:=[Sheet2.D4]", 2, 1), "of:=[Sheet2.E6]");
+    assert_eq!(
+        shift_refs("of:=[Sheet2.$D4:$Sheet3.D$4]+['a.b'.Z9]", 2, 1),
+        "of:=[Sheet2.$D6:$Sheet3.E$4]+['a.b'.AA11]"
+    );
+}
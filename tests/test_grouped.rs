@@ -1,8 +1,8 @@
 mod lib_test;
 
 use lib_test::*;
-use spreadsheet_ods::sheet::Grouped;
-use spreadsheet_ods::{read_ods, OdsError, Sheet, WorkBook};
+use spreadsheet_ods::sheet::{Grouped, Visibility};
+use spreadsheet_ods::{cm, read_ods, OdsError, Sheet, WorkBook};
 
 #[test]
 fn test_write_group1() -> Result<(), OdsError> {
@@ -127,3 +127,51 @@ fn test_write_group6() {
     sh.add_row_group(1, 4);
     sh.add_row_group(2, 5);
 }
+
+#[test]
+fn test_write_group_outline() -> Result<(), OdsError> {
+    let mut wb = WorkBook::new_empty();
+
+    let mut sh = Sheet::new("Sheet1");
+    for r in 0..12 {
+        sh.set_value(r, 0, r);
+        sh.set_value(0, r, r);
+    }
+    // same width makes one col-header span the whole group.
+    for c in 0..12 {
+        sh.set_col_width(c, cm!(2));
+    }
+
+    // inner groups first, the outline must be sorted anyway.
+    sh.group_rows(2, 3, true);
+    sh.group_rows(5, 6, false);
+    sh.group_rows(1, 8, false);
+    sh.group_cols(2, 3, true);
+    sh.group_cols(1, 8, false);
+
+    assert_eq!(sh.row_group(0), Some(&Grouped::new(1, 8, true)));
+    assert_eq!(sh.row_group(1), Some(&Grouped::new(2, 3, false)));
+    assert_eq!(sh.row_visible(2), Visibility::Collapsed);
+
+    wb.push_sheet(sh);
+
+    test_write_ods(&mut wb, "test_out/test_grouped_3.ods")?;
+
+    let wb = read_ods("test_out/test_grouped_3.ods")?;
+    let sh = wb.sheet(0);
+
+    let v = sh.row_group_iter().cloned().collect::<Vec<_>>();
+    assert_eq!(
+        v,
+        vec![
+            Grouped::new(1, 8, true),
+            Grouped::new(2, 3, false),
+            Grouped::new(5, 6, true)
+        ]
+    );
+    let v = sh.col_group_iter().cloned().collect::<Vec<_>>();
+    assert_eq!(v, vec![Grouped::new(1, 8, true), Grouped::new(2, 3, false)]);
+    assert_eq!(sh.col_visible(3), Visibility::Collapsed);
+
+    Ok(())
+}
@@ -1,8 +1,8 @@
 mod lib_test;
 
 use lib_test::*;
-use spreadsheet_ods::sheet::Grouped;
-use spreadsheet_ods::{read_ods, OdsError, Sheet, WorkBook};
+use spreadsheet_ods::sheet::{Grouped, Header, PrintSettings};
+use spreadsheet_ods::{read_ods, CellRange, OdsError, Sheet, WorkBook};
 
 #[test]
 fn test_write_group1() -> Result<(), OdsError> {
@@ -20,7 +20,7 @@ fn test_write_group1() -> Result<(), OdsError> {
     sh.set_value(8, 0, 1);
     sh.set_value(9, 0, 1);
 
-    sh.set_header_rows(1, 3);
+    sh.set_repeat_header_rows(1, 3)?;
     sh.add_row_group(1, 3);
 
     wb.push_sheet(sh);
@@ -43,6 +43,7 @@ fn test_write_group1() -> Result<(), OdsError> {
 }
 
 #[test]
+#[allow(deprecated)]
 fn test_write_group2() -> Result<(), OdsError> {
     let mut wb = WorkBook::new_empty();
 
@@ -58,8 +59,9 @@ fn test_write_group2() -> Result<(), OdsError> {
     sh.set_value(8, 0, 1);
     sh.set_value(9, 0, 1);
 
-    sh.set_header_rows(0, 3);
+    // The checked setter rejects this, the writer must still handle it.
     sh.add_row_group(1, 3);
+    sh.set_header_rows(0, 3);
 
     wb.push_sheet(sh);
 
@@ -81,6 +83,7 @@ fn test_write_group2() -> Result<(), OdsError> {
 }
 
 #[test]
+#[allow(deprecated)]
 fn test_write_group3() -> Result<(), OdsError> {
     let mut wb = WorkBook::new_empty();
 
@@ -96,8 +99,9 @@ fn test_write_group3() -> Result<(), OdsError> {
     sh.set_value(8, 0, 1);
     sh.set_value(9, 0, 1);
 
-    sh.set_header_rows(0, 4);
+    // The checked setter rejects this, the writer must still handle it.
     sh.add_row_group(1, 3);
+    sh.set_header_rows(0, 4);
 
     wb.push_sheet(sh);
 
@@ -119,6 +123,7 @@ fn test_write_group3() -> Result<(), OdsError> {
 }
 
 #[test]
+#[allow(deprecated)]
 fn test_write_group4() -> Result<(), OdsError> {
     let mut wb = WorkBook::new_empty();
 
@@ -134,8 +139,9 @@ fn test_write_group4() -> Result<(), OdsError> {
     sh.set_value(8, 0, 1);
     sh.set_value(9, 0, 1);
 
-    sh.set_header_rows(2, 4);
+    // The checked setter rejects this, the writer must still handle it.
     sh.add_row_group(1, 3);
+    sh.set_header_rows(2, 4);
 
     wb.push_sheet(sh);
 
@@ -172,7 +178,7 @@ fn test_write_colgroup1() -> Result<(), OdsError> {
     sh.set_value(0, 8, 1);
     sh.set_value(0, 9, 1);
 
-    sh.set_header_cols(1, 3);
+    sh.set_repeat_header_cols(1, 3)?;
     sh.add_col_group(1, 3);
 
     wb.push_sheet(sh);
@@ -195,6 +201,7 @@ fn test_write_colgroup1() -> Result<(), OdsError> {
 }
 
 #[test]
+#[allow(deprecated)]
 fn test_write_colgroup2() -> Result<(), OdsError> {
     let mut wb = WorkBook::new_empty();
 
@@ -210,8 +217,9 @@ fn test_write_colgroup2() -> Result<(), OdsError> {
     sh.set_value(0, 8, 1);
     sh.set_value(0, 9, 1);
 
-    sh.set_header_cols(0, 3);
+    // The checked setter rejects this, the writer must still handle it.
     sh.add_col_group(1, 3);
+    sh.set_header_cols(0, 3);
 
     wb.push_sheet(sh);
 
@@ -233,6 +241,7 @@ fn test_write_colgroup2() -> Result<(), OdsError> {
 }
 
 #[test]
+#[allow(deprecated)]
 fn test_write_colgroup3() -> Result<(), OdsError> {
     let mut wb = WorkBook::new_empty();
 
@@ -248,8 +257,9 @@ fn test_write_colgroup3() -> Result<(), OdsError> {
     sh.set_value(0, 8, 1);
     sh.set_value(0, 9, 1);
 
-    sh.set_header_cols(0, 4);
+    // The checked setter rejects this, the writer must still handle it.
     sh.add_col_group(1, 3);
+    sh.set_header_cols(0, 4);
 
     wb.push_sheet(sh);
 
@@ -271,6 +281,7 @@ fn test_write_colgroup3() -> Result<(), OdsError> {
 }
 
 #[test]
+#[allow(deprecated)]
 fn test_write_colgroup4() -> Result<(), OdsError> {
     let mut wb = WorkBook::new_empty();
 
@@ -286,8 +297,9 @@ fn test_write_colgroup4() -> Result<(), OdsError> {
     sh.set_value(0, 8, 1);
     sh.set_value(0, 9, 1);
 
-    sh.set_header_cols(2, 4);
+    // The checked setter rejects this, the writer must still handle it.
     sh.add_col_group(1, 3);
+    sh.set_header_cols(2, 4);
 
     wb.push_sheet(sh);
 
@@ -307,3 +319,92 @@ fn test_write_colgroup4() -> Result<(), OdsError> {
 
     Ok(())
 }
+
+#[test]
+fn test_repeat_header_checks() -> Result<(), OdsError> {
+    let mut sh = Sheet::new("Sheet1");
+    sh.add_row_group(2, 5);
+    sh.add_col_group(0, 1);
+
+    assert!(sh.set_repeat_header_rows(3, 1).is_err());
+    assert!(sh.set_repeat_header_rows(0, 3).is_err());
+    assert!(sh.set_repeat_header_rows(4, 6).is_err());
+    assert!(sh.set_repeat_header_rows(0, 1_048_576).is_err());
+    assert_eq!(sh.header_rows(), None);
+    sh.set_repeat_header_rows(3, 4)?;
+    sh.set_repeat_header_rows(0, 1)?;
+    assert_eq!(sh.header_rows().map(|v| v.into()), Some((0, 1)));
+
+    assert!(sh.set_repeat_header_cols(1, 2).is_err());
+    sh.set_repeat_header_cols(0, 0)?;
+    assert_eq!(sh.header_cols().map(|v| v.into()), Some((0, 0)));
+
+    Ok(())
+}
+
+#[test]
+fn test_print_settings_checks() {
+    let mut sh = Sheet::new("Sheet1");
+    sh.add_row_group(2, 5);
+
+    let print = PrintSettings {
+        header_rows: Some(Header { from: 4, to: 6 }),
+        ..Default::default()
+    };
+    assert!(sh.set_print_settings(print).is_err());
+    let print = PrintSettings {
+        header_cols: Some(Header {
+            from: 0,
+            to: 16_384,
+        }),
+        ..Default::default()
+    };
+    assert!(sh.set_print_settings(print).is_err());
+    let print = PrintSettings {
+        print_ranges: vec![CellRange::remote("Sheet2", 0, 0, 9, 9)],
+        header_rows: Some(Header { from: 0, to: 1 }),
+        ..Default::default()
+    };
+    assert!(sh.set_print_settings(print).is_err());
+    let print = PrintSettings {
+        print_ranges: vec![CellRange::local(0, 0, 1_048_576, 9)],
+        ..Default::default()
+    };
+    assert!(sh.set_print_settings(print).is_err());
+    assert_eq!(sh.header_rows(), None);
+    assert_eq!(sh.print_ranges(), None);
+
+    let print = PrintSettings {
+        print_ranges: vec![CellRange::local(0, 0, 9, 9)],
+        header_rows: Some(Header { from: 3, to: 4 }),
+        ..Default::default()
+    };
+    assert!(sh.set_print_settings(print).is_ok());
+    assert_eq!(sh.header_rows().map(|v| v.into()), Some((3, 4)));
+    assert_eq!(sh.print_ranges(), Some(&vec![CellRange::local(0, 0, 9, 9)]));
+}
+
+#[test]
+#[should_panic]
+fn test_row_group_in_header() {
+    let mut sh = Sheet::new("Sheet1");
+    sh.set_repeat_header_rows(0, 3).expect("header");
+    sh.add_row_group(1, 2);
+}
+
+#[test]
+#[should_panic]
+fn test_col_group_over_header() {
+    let mut sh = Sheet::new("Sheet1");
+    sh.set_repeat_header_cols(2, 4).expect("header");
+    sh.add_col_group(3, 6);
+}
+
+#[test]
+fn test_group_around_header() {
+    let mut sh = Sheet::new("Sheet1");
+    sh.set_repeat_header_rows(1, 2).expect("header");
+    sh.add_row_group(0, 5);
+    sh.add_row_group(6, 8);
+    assert_eq!(sh.row_group_count(), 2);
+}
@@ -0,0 +1,76 @@
+#![cfg(feature = "serde")]
+
+use chrono::{Duration, NaiveDate};
+#[cfg(feature = "rust_decimal")]
+use rust_decimal::Decimal;
+use spreadsheet_ods::color::Rgb;
+use spreadsheet_ods::{cm, currency, percent, CellStyle, OdsError, Sheet, Value, WorkBook};
+
+#[test]
+fn test_json_roundtrip() -> Result<(), OdsError> {
+    let mut wb = WorkBook::new_empty();
+    let mut style = CellStyle::new_empty();
+    style.set_name("c1");
+    style.set_background_color(Rgb::new(255, 0, 0));
+    let style = wb.add_cellstyle(style);
+
+    let mut sh = Sheet::new("One");
+    sh.set_col_width(1, cm!(3));
+    sh.set_row_height(2, cm!(1));
+    sh.set_styled_value(0, 0, "text", &style);
+    sh.set_value(0, 1, 1.5);
+    sh.set_value(0, 2, true);
+    #[cfg(feature = "rust_decimal")]
+    sh.set_value(0, 3, Decimal::new(12345, 2));
+    sh.set_value(0, 4, f64::NAN);
+    sh.set_value(0, 5, f64::NEG_INFINITY);
+    sh.set_value(1, 4, percent!(f64::INFINITY));
+    sh.set_value(1, 0, percent!(0.25));
+    sh.set_value(1, 1, currency!("EUR", 10));
+    sh.set_value(
+        1,
+        2,
+        NaiveDate::from_ymd_opt(2024, 1, 31)
+            .expect("date")
+            .and_hms_opt(12, 30, 0)
+            .expect("time"),
+    );
+    sh.set_value(1, 3, NaiveDate::from_ymd_opt(2024, 2, 1).expect("date"));
+    sh.set_value(2, 0, Duration::minutes(90));
+    sh.set_value(2, 1, Value::Error("#N/A".to_string()));
+    sh.set_formula(2, 2, "of:=[.B1]*2");
+    sh.set_col_span(3, 0, 2);
+    wb.push_sheet(sh);
+
+    let json = wb.to_json()?;
+    assert!(json.contains("\"type\": \"currency\""));
+
+    let wb2 = WorkBook::from_json(&json)?;
+    assert_eq!(wb2.to_json()?, json);
+    let sh2 = wb2.sheet(0);
+    assert_eq!(sh2.name(), "One");
+    #[cfg(feature = "rust_decimal")]
+    assert_eq!(sh2.value(0, 3), &Value::Decimal(Decimal::new(12345, 2)));
+    assert!(matches!(sh2.value(0, 4), Value::Number(v) if v.is_nan()));
+    assert_eq!(sh2.value(0, 5), &Value::Number(f64::NEG_INFINITY));
+    assert_eq!(sh2.value(1, 4), &Value::Percentage(f64::INFINITY));
+    assert_eq!(sh2.value(1, 1), &Value::Currency(10.0, "EUR".into()));
+    assert_eq!(sh2.value(2, 0), &Value::TimeDuration(Duration::minutes(90)));
+    assert_eq!(sh2.formula(2, 2), Some(&"of:=[.B1]*2".to_string()));
+    assert_eq!(sh2.col_span(3, 0), 2);
+    assert_eq!(sh2.col_width(1), cm!(3));
+    assert_eq!(sh2.cellstyle(0, 0), Some(&style));
+    assert_eq!(
+        wb2.cellstyle("c1")
+            .and_then(|v| v.cellstyle().attr("fo:background-color")),
+        Some("#ff0000")
+    );
+
+    assert!(WorkBook::from_json("{\"version\": 99}").is_err());
+    assert!(WorkBook::from_json(
+        "{\"version\": 1, \"sheets\": [{\"name\": \"x\", \"cells\": [{\"row\": 0, \"col\": 0, \"type\": \"number\", \"value\": \"1\"}]}]}"
+    )
+    .is_err());
+
+    Ok(())
+}
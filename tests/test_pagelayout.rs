@@ -2,9 +2,11 @@ mod lib_test;
 
 use color::Rgb;
 use lib_test::*;
-use spreadsheet_ods::style::{MasterPage, PageStyle, TableStyle};
+use spreadsheet_ods::sheet::{Header, PrintScale, PrintSettings};
+use spreadsheet_ods::style::units::{Margin, Paper, PrintOrder, PrintOrientation};
+use spreadsheet_ods::style::{HeaderFooter, HeaderFooterText, MasterPage, PageStyle, TableStyle};
 use spreadsheet_ods::xmltree::XmlVec;
-use spreadsheet_ods::{cm, read_ods, OdsError, Sheet, WorkBook};
+use spreadsheet_ods::{cm, read_ods, read_ods_buf, CellRange, Length, OdsError, Sheet, WorkBook};
 
 #[test]
 fn test_pagelayout() -> Result<(), OdsError> {
@@ -49,3 +51,210 @@ fn test_crpagelayout() -> Result<(), OdsError> {
 
     Ok(())
 }
+
+#[test]
+fn test_print_settings() -> Result<(), OdsError> {
+    let mut wb = WorkBook::new_empty();
+
+    let mut sh = Sheet::new("1");
+    sh.set_value(0, 0, "print");
+    sh.set_value(1, 0, "header");
+    sh.set_print_settings(PrintSettings {
+        print_ranges: vec![CellRange::local(0, 0, 9, 9)],
+        header_rows: Some(Header { from: 0, to: 1 }),
+        header_cols: None,
+        page_order: Some(PrintOrder::Ttb),
+        scale: Some(PrintScale::Pages(2)),
+    })?;
+    wb.push_sheet(sh);
+
+    test_write_ods(&mut wb, "test_out/test_pagelayout_3.ods")?;
+
+    let wb = read_ods("test_out/test_pagelayout_3.ods")?;
+    let sh = wb.sheet(0);
+    let print = sh.print_settings();
+    assert_eq!(print.print_ranges, vec![CellRange::local(0, 0, 9, 9)]);
+    assert_eq!(print.header_rows, Some(Header { from: 0, to: 1 }));
+
+    let ts = wb
+        .tablestyle(sh.style().expect("style"))
+        .expect("tablestyle");
+    let mp = wb
+        .masterpage(ts.attrmap().attr("style:master-page-name").expect("mp"))
+        .expect("masterpage");
+    let ps = wb
+        .pagestyle(mp.pagestyle().expect("ps"))
+        .expect("pagestyle");
+    assert_eq!(ps.style().attr("style:print-page-order"), Some("ttb"));
+    assert_eq!(ps.style().attr("style:scale-to-pages"), Some("2"));
+    assert_eq!(print.page_order, Some(PrintOrder::Ttb));
+    assert_eq!(print.scale, Some(PrintScale::Pages(2)));
+
+    Ok(())
+}
+
+#[test]
+fn test_print_settings_shared() -> Result<(), OdsError> {
+    let mut wb = WorkBook::new_empty();
+
+    let ps = wb.add_pagestyle(PageStyle::new("ps1"));
+    let mut mp = MasterPage::new("mp1");
+    mp.set_pagestyle(&ps);
+    let mp = wb.add_masterpage(mp);
+    let mut ts = TableStyle::new("ts1");
+    ts.set_master_page(&mp);
+    let ts = wb.add_tablestyle(ts);
+
+    for name in ["1", "2"] {
+        let mut sh = Sheet::new(name);
+        sh.set_style(&ts);
+        wb.push_sheet(sh);
+    }
+    wb.sheet_mut(0).set_print_settings(PrintSettings {
+        scale: Some(PrintScale::Percent(50.0)),
+        ..Default::default()
+    })?;
+
+    let wb = read_ods_buf(&test_write_odsbuf(&mut wb)?)?;
+    assert_eq!(
+        wb.sheet(0).print_settings().scale,
+        Some(PrintScale::Percent(50.0))
+    );
+    assert_eq!(wb.sheet(1).print_settings().scale, None);
+    assert_eq!(wb.sheet(1).style(), Some(&ts));
+    assert_ne!(wb.sheet(0).style(), Some(&ts));
+    assert_eq!(
+        wb.pagestyle("ps1")
+            .expect("ps1")
+            .style()
+            .attr("style:scale-to"),
+        None
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_headerfooter_text() -> Result<(), OdsError> {
+    let mut wb = WorkBook::new_empty();
+
+    let mut mp = MasterPage::new("mp1");
+    mp.set_footer(
+        HeaderFooter::new()
+            .with_left(HeaderFooterText::new().sheet_name())
+            .with_center(HeaderFooterText::new().date().text(" ").time())
+            .with_right(
+                HeaderFooterText::new()
+                    .text("Page ")
+                    .page_number()
+                    .text(" of ")
+                    .page_count(),
+            ),
+    );
+    let mp = wb.add_masterpage(mp);
+
+    let mut ts = TableStyle::new("ts1");
+    ts.set_master_page(&mp);
+    let ts = wb.add_tablestyle(ts);
+
+    let mut sh = Sheet::new("1");
+    sh.set_style(&ts);
+    wb.push_sheet(sh);
+
+    test_write_ods(&mut wb, "test_out/test_pagelayout_4.ods")?;
+
+    let wb = read_ods("test_out/test_pagelayout_4.ods")?;
+    let mp = wb.masterpage("mp1").expect("masterpage");
+    let left = mp.footer().left()[0].to_string();
+    assert!(left.contains("text:sheet-name"));
+    let right = mp.footer().right()[0].to_string();
+    assert!(right.contains("Page "));
+    assert!(right.contains("text:page-number"));
+    assert!(right.contains("text:page-count"));
+
+    Ok(())
+}
+
+#[test]
+fn test_sheet_masterpage() -> Result<(), OdsError> {
+    let mut wb = WorkBook::new_empty();
+
+    let mut mp = MasterPage::new("mp_summary");
+    mp.set_footer(HeaderFooter::new().with_center(HeaderFooterText::new().text("Summary")));
+    let mp = wb.add_masterpage(mp);
+
+    let ts = wb.add_tablestyle(TableStyle::new("ts1"));
+
+    let mut sh = Sheet::new("1");
+    sh.set_value(0, 0, "summary");
+    sh.set_style(&ts);
+    sh.set_masterpage(&mp);
+    wb.push_sheet(sh);
+    let mut sh = Sheet::new("2");
+    sh.set_value(0, 0, "data");
+    sh.set_style(&ts);
+    wb.push_sheet(sh);
+
+    test_write_ods(&mut wb, "test_out/test_pagelayout_5.ods")?;
+
+    let wb = read_ods("test_out/test_pagelayout_5.ods")?;
+    assert_eq!(wb.sheet(0).masterpage(), Some(&mp));
+    assert_ne!(wb.sheet(0).style().map(|v| v.as_str()), Some("ts1"));
+    assert_eq!(wb.sheet(1).masterpage(), None);
+    assert_eq!(wb.sheet(1).style().map(|v| v.as_str()), Some("ts1"));
+
+    Ok(())
+}
+
+#[test]
+fn test_paper() -> Result<(), OdsError> {
+    let mut wb = WorkBook::new_empty();
+
+    let mut ps = PageStyle::new("ps1");
+    ps.set_paper(Paper::A4, PrintOrientation::Landscape);
+    ps.fit_to_pages(1, 0);
+    let ps = wb.add_pagestyle(ps);
+    let mut mp = MasterPage::new("mp1");
+    mp.set_pagestyle(&ps);
+    let mp = wb.add_masterpage(mp);
+
+    let mut sh = Sheet::new("1");
+    sh.set_value(0, 0, "wide");
+    sh.set_masterpage(&mp);
+    wb.push_sheet(sh);
+
+    test_write_ods(&mut wb, "test_out/test_pagelayout_6.ods")?;
+
+    let wb = read_ods("test_out/test_pagelayout_6.ods")?;
+    let ps = wb.pagestyle("ps1").expect("pagestyle");
+    assert_eq!(ps.style().attr("fo:page-width"), Some("297mm"));
+    assert_eq!(ps.style().attr("fo:page-height"), Some("210mm"));
+    assert_eq!(
+        ps.style().attr("style:print-orientation"),
+        Some("landscape")
+    );
+    assert_eq!(ps.style().attr("fo:margin-left"), None);
+
+    assert_eq!(ps.style().attr("loext:scale-to-X"), Some("1"));
+    assert_eq!(ps.style().attr("loext:scale-to-Y"), Some("0"));
+
+    let mut ps2 = PageStyle::new("ps2");
+    ps2.set_margin(Margin::Length(cm!(1)));
+    ps2.set_paper_with_margins(Paper::Letter, PrintOrientation::Portrait, cm!(2.5));
+    assert_eq!(ps2.style().attr("fo:page-width"), Some("8.5in"));
+    assert_eq!(ps2.style().attr("fo:page-height"), Some("11in"));
+    assert_eq!(ps2.style().attr("fo:margin"), None);
+    for attr in [
+        "fo:margin-top",
+        "fo:margin-bottom",
+        "fo:margin-left",
+        "fo:margin-right",
+    ] {
+        assert_eq!(ps2.style().attr(attr), Some("2.5cm"));
+    }
+
+    let (w, h) = Paper::Letter.size();
+    assert_eq!((w, h), (Length::In(8.5), Length::In(11.0)));
+
+    Ok(())
+}
@@ -1,4 +1,9 @@
-use spreadsheet_ods::{read_ods, OdsError};
+use spreadsheet_ods::defaultstyles::DefaultFormat;
+use spreadsheet_ods::draw::Annotation;
+use spreadsheet_ods::{
+    read_fods_buf, read_ods, write_ods_buf, CellStyle, OdsError, OdsOptions, Sheet, Value, WorkBook,
+};
+use std::io::Cursor;
 
 #[test]
 fn read_google() -> Result<(), OdsError> {
@@ -20,3 +25,269 @@ fn read_office365() -> Result<(), OdsError> {
     // dbg!(wb.sheet(0).cell_ref(1, 1));
     Ok(())
 }
+
+#[test]
+fn read_strict_lossy() -> Result<(), OdsError> {
+    let fods = r#"<?xml version="1.0" encoding="UTF-8"?>
+<office:document xmlns:office="urn:oasis:names:tc:opendocument:xmlns:office:1.0" xmlns:table="urn:oasis:names:tc:opendocument:xmlns:table:1.0" xmlns:text="urn:oasis:names:tc:opendocument:xmlns:text:1.0" office:version="1.3" office:mimetype="application/vnd.oasis.opendocument.spreadsheet">
+<office:body><office:spreadsheet><table:table table:name="T">
+<table:table-row>
+<table:table-cell office:value-type="float" office:value="1"><text:p>1</text:p></table:table-cell>
+<table:table-cell office:value-type="float" office:value="x2"><text:p>2</text:p></table:table-cell>
+<table:table-cell office:value-type="fancy"><text:p>3</text:p></table:table-cell>
+<table:table-cell table:number-columns-repeated="many" office:value-type="float" office:value="4"><text:p>4</text:p></table:table-cell>
+</table:table-row>
+</table:table></office:spreadsheet></office:body></office:document>"#;
+
+    match OdsOptions::default().read_fods(Cursor::new(fods.as_bytes())) {
+        Err(OdsError::Ods(msg)) => {
+            assert!(msg.starts_with("T row/col 0/1: "), "{}", msg);
+        }
+        _ => panic!("expected an error"),
+    }
+
+    let wb = OdsOptions::default()
+        .lossy()
+        .read_fods(Cursor::new(fods.as_bytes()))?;
+    let sh = wb.sheet(0);
+    assert_eq!(sh.value(0, 0), &Value::Number(1.0));
+    assert_eq!(sh.value(0, 1), &Value::Text("2".to_string()));
+    assert_eq!(sh.value(0, 2), &Value::Text("3".to_string()));
+    assert_eq!(sh.value(0, 3), &Value::Number(4.0));
+    assert_eq!(sh.value(0, 4), &Value::Empty);
+
+    Ok(())
+}
+
+#[test]
+fn read_limits() -> Result<(), OdsError> {
+    let fods = r#"<?xml version="1.0" encoding="UTF-8"?>
+<office:document xmlns:office="urn:oasis:names:tc:opendocument:xmlns:office:1.0" xmlns:table="urn:oasis:names:tc:opendocument:xmlns:table:1.0" xmlns:text="urn:oasis:names:tc:opendocument:xmlns:text:1.0" office:version="1.3" office:mimetype="application/vnd.oasis.opendocument.spreadsheet">
+<office:body><office:spreadsheet>
+<table:table table:name="T">
+<table:table-row table:number-rows-repeated="1000">
+<table:table-cell table:number-columns-repeated="10" office:value-type="float" office:value="1"><text:p>1</text:p></table:table-cell>
+</table:table-row>
+<table:table-row table:number-rows-repeated="1048000"><table:table-cell/></table:table-row>
+</table:table>
+<table:table table:name="U"><table:table-row><table:table-cell/></table:table-row></table:table>
+</office:spreadsheet></office:body></office:document>"#;
+
+    let wb = OdsOptions::default()
+        .max_repeat(1000)
+        .max_sheets(2)
+        .read_fods(Cursor::new(fods.as_bytes()))?;
+    assert_eq!(wb.sheet(0).value(999, 9), &Value::Number(1.0));
+
+    let res = OdsOptions::default()
+        .max_repeat(999)
+        .read_fods(Cursor::new(fods.as_bytes()));
+    assert!(matches!(res, Err(OdsError::Ods(_))));
+
+    let res = OdsOptions::default()
+        .max_sheets(1)
+        .read_fods(Cursor::new(fods.as_bytes()));
+    assert!(matches!(res, Err(OdsError::Ods(_))));
+
+    let buf = std::fs::read("tests/test_read_libreoffice.ods")?;
+    assert!(OdsOptions::default()
+        .max_entry_size(1000)
+        .read_ods(Cursor::new(&buf))
+        .is_err());
+    OdsOptions::default()
+        .max_entry_size(10_000_000)
+        .read_ods(Cursor::new(&buf))?;
+
+    Ok(())
+}
+
+#[test]
+fn read_error_location() {
+    let fods = r#"<?xml version="1.0" encoding="UTF-8"?>
+<office:document xmlns:office="urn:oasis:names:tc:opendocument:xmlns:office:1.0" xmlns:table="urn:oasis:names:tc:opendocument:xmlns:table:1.0" xmlns:text="urn:oasis:names:tc:opendocument:xmlns:text:1.0" office:version="1.3" office:mimetype="application/vnd.oasis.opendocument.spreadsheet">
+<office:body><office:spreadsheet><table:table table:name="T">
+<table:table-row>
+<table:table-cell office:value-type="float" office:value="x2"><text:p>2</text:p></table:table-cell>
+</table:table-row>
+</table:table></office:spreadsheet></office:body></office:document>"#;
+
+    let Err(err) = read_fods_buf(fods.as_bytes()) else {
+        panic!("expected an error");
+    };
+    let OdsError::Ods(msg) = &err else {
+        panic!("expected an error {}", err);
+    };
+    assert!(
+        msg.ends_with(
+            " at fods:5:63 in office:document/office:body/office:spreadsheet/\
+            table:table/table:table-row/table:table-cell"
+        ),
+        "{}",
+        msg
+    );
+
+    // a generic reader knows line and column, but not the open elements.
+    let Err(err) = OdsOptions::default().read_fods(Cursor::new(fods.as_bytes())) else {
+        panic!("expected an error");
+    };
+    assert!(err.to_string().contains(" at fods:5:63"), "{}", err);
+}
+
+#[test]
+fn read_lazy() -> Result<(), OdsError> {
+    let mut wb = WorkBook::new_empty();
+    for name in ["A", "B", "C"] {
+        let mut sh = Sheet::new(name);
+        sh.set_value(0, 0, name);
+        sh.set_value(5, 3, 42);
+        wb.push_sheet(sh);
+    }
+    let buf = write_ods_buf(&mut wb, Vec::new())?;
+
+    let mut lazy = OdsOptions::default().read_ods_lazy(Cursor::new(&buf))?;
+    assert_eq!(lazy.num_sheets(), 3);
+    assert_eq!(lazy.sheet_name(1), "B");
+    assert_eq!(lazy.sheet_idx("C"), Some(2));
+    assert!(!lazy.is_loaded(1));
+    assert_eq!(lazy.workbook().sheet(1).value(0, 0), &Value::Empty);
+
+    let sh = lazy.sheet(1)?;
+    assert_eq!(sh.value(0, 0).as_str_or(""), "B");
+    assert_eq!(sh.value(5, 3).as_i32_or(0), 42);
+    assert!(lazy.is_loaded(1));
+    assert!(!lazy.is_loaded(0));
+
+    let wb = lazy.into_workbook()?;
+    assert_eq!(wb.sheet(0).value(0, 0).as_str_or(""), "A");
+    assert_eq!(wb.sheet(2).value(0, 0).as_str_or(""), "C");
+
+    Ok(())
+}
+
+#[test]
+fn read_select_sheets() -> Result<(), OdsError> {
+    let mut wb = WorkBook::new_empty();
+    for name in ["Data", "Other", "Summary"] {
+        let mut sh = Sheet::new(name);
+        sh.set_value(0, 0, name);
+        wb.push_sheet(sh);
+    }
+    let buf = write_ods_buf(&mut wb, Vec::new())?;
+
+    let wb = OdsOptions::default()
+        .sheets(&["Summary", "Data", "Missing"])
+        .read_ods(Cursor::new(&buf))?;
+    assert_eq!(wb.num_sheets(), 2);
+    assert_eq!(wb.sheet(0).name(), "Data");
+    assert_eq!(wb.sheet(1).name(), "Summary");
+    assert_eq!(wb.sheet(1).value(0, 0).as_str_or(""), "Summary");
+
+    let mut lazy = OdsOptions::default()
+        .sheets(&["Summary"])
+        .read_ods_lazy(Cursor::new(&buf))?;
+    assert_eq!(lazy.num_sheets(), 1);
+    assert_eq!(lazy.sheet(0)?.value(0, 0).as_str_or(""), "Summary");
+
+    Ok(())
+}
+
+#[test]
+fn read_values_only() -> Result<(), OdsError> {
+    let mut wb = WorkBook::new_empty();
+    let style = wb.add_cellstyle(CellStyle::new("bold", &DefaultFormat::number()));
+    let mut sh = Sheet::new("S");
+    sh.set_styled_value(0, 0, 1, &style);
+    sh.set_formula(0, 1, "of:=[.A1]*2");
+    sh.set_value(0, 1, 2);
+    sh.set_value(1, 0, "text");
+    sh.set_annotation(1, 0, Annotation::new("note"));
+    wb.push_sheet(sh);
+    let buf = write_ods_buf(&mut wb, Vec::new())?;
+
+    let wb = OdsOptions::default()
+        .values_only()
+        .read_ods(Cursor::new(&buf))?;
+    let sh = wb.sheet(0);
+    assert_eq!(sh.value(0, 0), &Value::Number(1.0));
+    assert_eq!(sh.cellstyle(0, 0), None);
+    assert_eq!(sh.formula(0, 1), Some(&"of:=[.A1]*2".to_string()));
+    assert_eq!(sh.value(0, 1), &Value::Number(2.0));
+    assert_eq!(sh.value(1, 0).as_str_or(""), "text");
+    assert!(sh.annotation(1, 0).is_none());
+    assert!(wb.iter_cellstyles().all(|v| v.name() != "bold"));
+
+    Ok(())
+}
+
+#[test]
+fn read_display_text() -> Result<(), OdsError> {
+    let fods = r#"<?xml version="1.0" encoding="UTF-8"?>
+<office:document xmlns:office="urn:oasis:names:tc:opendocument:xmlns:office:1.0" xmlns:table="urn:oasis:names:tc:opendocument:xmlns:table:1.0" xmlns:text="urn:oasis:names:tc:opendocument:xmlns:text:1.0" office:version="1.3" office:mimetype="application/vnd.oasis.opendocument.spreadsheet">
+<office:body><office:spreadsheet><table:table table:name="T">
+<table:table-row>
+<table:table-cell office:value-type="float" office:value="1234.5"><text:p>1.234,50 €</text:p></table:table-cell>
+<table:table-cell office:value-type="date" office:date-value="2024-03-09"><text:p>Saturday,</text:p><text:p>March <text:span>9</text:span></text:p></table:table-cell>
+<table:table-cell office:value-type="float" office:value="1"/>
+</table:table-row>
+</table:table></office:spreadsheet></office:body></office:document>"#;
+
+    let wb = OdsOptions::default().read_fods(Cursor::new(fods.as_bytes()))?;
+    let cell = wb.sheet(0).cell_ref(0, 0).expect("cell");
+    assert_eq!(cell.display_text(), None);
+
+    let wb = OdsOptions::default()
+        .display_text()
+        .read_fods(Cursor::new(fods.as_bytes()))?;
+    let sh = wb.sheet(0);
+    let cell = sh.cell_ref(0, 0).expect("cell");
+    assert_eq!(cell.value(), &Value::Number(1234.5));
+    assert_eq!(cell.display_text().map(|v| v.as_str()), Some("1.234,50 €"));
+    let cell = sh.cell_ref(0, 1).expect("cell");
+    assert_eq!(
+        cell.display_text().map(|v| v.as_str()),
+        Some("Saturday,\nMarch 9")
+    );
+    assert_eq!(cell.to_owned().display_text(), cell.display_text());
+    let mut content = cell.to_owned();
+    content.set_display_text(None);
+    assert_eq!(content.display_text(), None);
+    let cell = sh.cell_ref(0, 2).expect("cell");
+    assert_eq!(cell.display_text(), None);
+
+    Ok(())
+}
+
+#[test]
+fn read_text_spaces() -> Result<(), OdsError> {
+    let fods = r#"<?xml version="1.0" encoding="UTF-8"?>
+<office:document xmlns:office="urn:oasis:names:tc:opendocument:xmlns:office:1.0" xmlns:table="urn:oasis:names:tc:opendocument:xmlns:table:1.0" xmlns:text="urn:oasis:names:tc:opendocument:xmlns:text:1.0" office:version="1.3" office:mimetype="application/vnd.oasis.opendocument.spreadsheet">
+<office:body><office:spreadsheet><table:table table:name="T">
+<table:table-row>
+<table:table-cell office:value-type="string"><text:p>a<text:s text:c="3"/>b</text:p></table:table-cell>
+<table:table-cell office:value-type="string"><text:p>a<text:s text:c="4000000000"/>b</text:p></table:table-cell>
+</table:table-row>
+</table:table></office:spreadsheet></office:body></office:document>"#;
+
+    let wb = OdsOptions::default().read_fods(Cursor::new(fods.as_bytes()))?;
+    let sh = wb.sheet(0);
+    assert_eq!(sh.value(0, 0), &Value::Text("a   b".to_string()));
+    assert!(matches!(sh.value(0, 1), Value::TextXml(_)));
+    assert_eq!(sh.value(0, 1).as_cow_str_or("").len(), 1002);
+
+    let wb = OdsOptions::default()
+        .max_repeat(2)
+        .read_fods(Cursor::new(fods.as_bytes()))?;
+    assert!(matches!(wb.sheet(0).value(0, 0), Value::TextXml(_)));
+
+    Ok(())
+}
+
+#[cfg(feature = "mmap")]
+#[test]
+fn read_mmap() -> Result<(), OdsError> {
+    let wb = spreadsheet_ods::read_ods_mmap("tests/test_read_libreoffice.ods")?;
+    let wb2 = read_ods("tests/test_read_libreoffice.ods")?;
+    assert_eq!(wb.num_sheets(), wb2.num_sheets());
+    assert_eq!(wb.sheet(0).used_grid_size(), wb2.sheet(0).used_grid_size());
+    Ok(())
+}
@@ -1 +1,123 @@
+use spreadsheet_ods::{CellRange, CellRef, OdsError};
 
+#[test]
+fn test_range_algebra() {
+    let r = CellRange::local(0, 0, 3, 3);
+    let s = CellRange::local(2, 2, 5, 5);
+
+    assert_eq!(r.cell_count(), 16);
+    assert!(r.intersects(&s));
+    assert_eq!(r.intersection(&s), Some(CellRange::local(2, 2, 3, 3)));
+    assert_eq!(r.intersection(&CellRange::local(4, 0, 4, 0)), None);
+    assert!(!r.intersects(&CellRange::remote("Sheet1", 0, 0, 3, 3)));
+
+    assert!(r.contains_ref(&CellRef::local(3, 3)));
+    assert!(!r.contains_ref(&CellRef::local(4, 3)));
+    assert!(!r.contains_ref(&CellRef::remote("Sheet1", 0, 0)));
+    assert!(r.contains_range(&CellRange::local(1, 1, 2, 2)));
+    assert!(!r.contains_range(&s));
+
+    assert_eq!(
+        r.subtract(&s),
+        vec![CellRange::local(0, 0, 1, 3), CellRange::local(2, 0, 3, 1)]
+    );
+    assert_eq!(
+        r.subtract(&CellRange::local(1, 1, 2, 2)),
+        vec![
+            CellRange::local(0, 0, 0, 3),
+            CellRange::local(3, 0, 3, 3),
+            CellRange::local(1, 0, 2, 0),
+            CellRange::local(1, 3, 2, 3),
+        ]
+    );
+    assert!(r.subtract(&CellRange::local(0, 0, 9, 9)).is_empty());
+    assert_eq!(r.subtract(&CellRange::local(5, 5, 6, 6)), vec![r.clone()]);
+
+    let u = r.union(&s);
+    assert_eq!(u.iter().map(|v| v.cell_count()).sum::<u64>(), 16 + 16 - 4);
+    for (i, a) in u.iter().enumerate() {
+        for b in &u[i + 1..] {
+            assert!(!a.intersects(b));
+        }
+    }
+    assert_eq!(r.union(&CellRange::local(1, 1, 2, 2)), vec![r.clone()]);
+
+    assert_eq!(
+        CellRange::local(1, 1, 2, 2).iter().collect::<Vec<_>>(),
+        vec![(1, 1), (1, 2), (2, 1), (2, 2)]
+    );
+
+    let mut n = CellRange::new();
+    n.set_row(5);
+    n.set_col(1);
+    n.set_to_col(3);
+    n.set_to_col_abs(true);
+    assert_eq!(n.cell_count(), 18);
+    n.normalize();
+    assert_eq!((n.row(), n.col(), n.to_row(), n.to_col()), (0, 1, 5, 3));
+    assert!(n.to_col_abs());
+    assert_eq!(n.iter().count(), 18);
+}
+
+#[test]
+fn test_parse_relaxed() -> Result<(), OdsError> {
+    assert_eq!(CellRef::parse(".A1")?, CellRef::local(0, 0));
+    assert_eq!(CellRef::parse(" a1 ")?, CellRef::local(0, 0));
+    assert_eq!(
+        CellRef::parse("Sheet one.$A$1")?,
+        CellRef::remote("Sheet one", 0, 0).absolute()
+    );
+    assert_eq!(
+        CellRef::parse("$'It''s.1'.B$3")?,
+        CellRef::remote("It's.1", 2, 1).absolute_row()
+    );
+    assert_eq!(
+        CellRef::parse("Sheet1!AA10")?,
+        CellRef::remote("Sheet1", 9, 26)
+    );
+    assert!(CellRef::parse("A0").is_err());
+    assert!(CellRef::parse("1A").is_err());
+    assert!(CellRef::parse("'Sheet.A1").is_err());
+    assert!(CellRef::parse("Sheet1.A1B").is_err());
+
+    assert_eq!(CellRange::parse("A1:b5")?, CellRange::local(0, 0, 4, 1));
+    assert_eq!(CellRange::parse("c3")?, CellRange::local(2, 2, 2, 2));
+    assert_eq!(
+        CellRange::parse("Sheet one.A1:B5")?,
+        CellRange::remote("Sheet one", 0, 0, 4, 1)
+    );
+    assert!(CellRange::parse("A1:").is_err());
+
+    for v in [
+        CellRef::local(5, 3),
+        CellRef::remote("Sheet one", 0, 0).absolute(),
+        CellRef::remote("a.b'c", 1000, 16383).absolute_col(),
+    ] {
+        assert_eq!(CellRef::parse(&v.to_string())?, v);
+    }
+    for v in [
+        CellRange::local(0, 0, 4, 1),
+        CellRange::remote("Sheet one", 1, 2, 3, 4).absolute(),
+    ] {
+        assert_eq!(CellRange::parse(&v.to_string())?, v);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_r1c1() {
+    assert_eq!(CellRef::local(0, 0).absolute().to_r1c1(5, 5), "R1C1");
+    assert_eq!(CellRef::local(4, 7).to_r1c1(5, 5), "R[-1]C[2]");
+    assert_eq!(CellRef::local(5, 0).absolute_col().to_r1c1(5, 5), "RC1");
+    assert_eq!(
+        CellRef::remote("Sheet one", 0, 0).absolute().to_r1c1(0, 0),
+        "'Sheet one'!R1C1"
+    );
+    assert_eq!(
+        CellRange::remote("Data", 0, 0, 9, 1)
+            .absolute_rows()
+            .to_r1c1(0, 1),
+        "Data!R1C[-1]:R10C"
+    );
+}
@@ -1,11 +1,24 @@
 mod lib_test;
 
+use chrono::{Duration, NaiveDate, NaiveDateTime, NaiveTime};
 use icu_locid::locale;
 use lib_test::*;
-use spreadsheet_ods::defaultstyles::DefaultFormat;
+#[cfg(feature = "rust_decimal")]
+use rust_decimal::Decimal;
+use spreadsheet_ods::color::Rgb;
+use spreadsheet_ods::conditional::{
+    ColorScale, ConditionalFormat, ConditionalFormatKind, DataBar, ScaleValue,
+};
+use spreadsheet_ods::defaultstyles::{DefaultFormat, DefaultStyle};
+use spreadsheet_ods::sheet::{
+    CellStorage, ClearOp, HtmlOptions, SortKey, SplitMode, SubtotalFn, Visibility, ZoomType,
+};
+use spreadsheet_ods::style::units::CellProtect;
+use spreadsheet_ods::workbook::ValidationIssue;
 use spreadsheet_ods::{
-    cm, currency, percent, read_ods, CellRange, CellStyle, CellStyleRef, OdsError, OdsOptions,
-    Sheet, Value, ValueType, WorkBook,
+    cm, currency, percent, read_fods_buf, read_ods, read_ods_buf, write_fods_buf, CellRange,
+    CellStyle, CellStyleRef, IntoValue, OdsError, OdsOptions, Sheet, Value, ValueFormatCurrency,
+    ValueType, WorkBook,
 };
 use std::fs::File;
 use std::io::BufReader;
@@ -153,9 +166,22 @@ fn test_print_range() -> Result<(), OdsError> {
             sh.set_value(i, j, i * j);
         }
     }
-    sh.add_print_range(CellRange::local(1, 1, 9, 9));
-    sh.add_print_range(CellRange::local(11, 11, 19, 19));
+    sh.add_print_range(CellRange::local(1, 1, 9, 9))?;
+    sh.add_print_range(CellRange::local(11, 11, 19, 19))?;
+    assert!(sh
+        .add_print_range(CellRange::remote("2", 1, 1, 9, 9))
+        .is_err());
+    assert!(sh
+        .add_print_range(CellRange::local(0, 0, 0, 20000))
+        .is_err());
     wb.push_sheet(sh);
+    assert_eq!(
+        wb.validate(),
+        vec![ValidationIssue::PrintRangeOutOfArea {
+            sheet: "1".to_string(),
+            range: CellRange::remote("1", 11, 11, 19, 19)
+        }]
+    );
 
     test_write_ods(&mut wb, "test_out/test_sheet_4.ods")?;
 
@@ -243,3 +269,1195 @@ fn test_cell_style() {
     let ss0 = wb.cellstyle(&s0).expect("style");
     assert_eq!(ss0.name(), "a21");
 }
+
+#[test]
+fn test_style_where() {
+    let mut sh = Sheet::new("1");
+    for r in 0..5 {
+        for c in 0..5 {
+            sh.set_value(r, c, (r as i32 - 2) * (c as i32 + 1));
+        }
+    }
+
+    let red = CellStyleRef::from("red");
+    sh.style_where((1, 1)..(4, 4), |v| v.as_f64_or(0.0) < 0.0, &red);
+
+    assert_eq!(sh.cellstyle(1, 1), Some(&red));
+    assert_eq!(sh.cellstyle(1, 3), Some(&red));
+    // outside the columns of the range.
+    assert_eq!(sh.cellstyle(1, 0), None);
+    assert_eq!(sh.cellstyle(1, 4), None);
+    // outside the rows of the range.
+    assert_eq!(sh.cellstyle(0, 1), None);
+    // not negative.
+    assert_eq!(sh.cellstyle(2, 1), None);
+    assert_eq!(sh.cellstyle(3, 1), None);
+}
+
+#[test]
+fn test_freeze_at() -> Result<(), OdsError> {
+    let mut wb = WorkBook::new_empty();
+
+    let mut sh = Sheet::new("Freeze");
+    sh.set_value(0, 0, 1);
+    sh.freeze_at(2, 1);
+    wb.push_sheet(sh);
+
+    test_write_ods(&mut wb, "test_out/test_sheet_7.ods")?;
+
+    let wb = read_ods("test_out/test_sheet_7.ods")?;
+    let cfg = wb.sheet(0).config();
+    assert_eq!(cfg.hor_split_mode, SplitMode::Heading);
+    assert_eq!(cfg.hor_split_pos, 1);
+    assert_eq!(cfg.vert_split_mode, SplitMode::Heading);
+    assert_eq!(cfg.vert_split_pos, 2);
+    assert_eq!(cfg.position_right, 1);
+    assert_eq!(cfg.position_bottom, 2);
+
+    let mut sh = Sheet::new("1");
+    sh.freeze_at(2, 1);
+    sh.freeze_at(0, 0);
+    assert_eq!(sh.config().hor_split_mode, SplitMode::None);
+    assert_eq!(sh.config().vert_split_mode, SplitMode::None);
+
+    Ok(())
+}
+
+#[test]
+fn test_split_at_pixels() -> Result<(), OdsError> {
+    let mut wb = WorkBook::new_empty();
+
+    let mut sh = Sheet::new("Split");
+    sh.set_value(0, 0, 1);
+    sh.split_at_pixels(300, 120);
+    wb.push_sheet(sh);
+
+    let buf = test_write_odsbuf(&mut wb)?;
+    let wb = read_ods_buf(&buf)?;
+    let cfg = wb.sheet(0).config();
+    assert_eq!(cfg.hor_split_mode, SplitMode::Split);
+    assert_eq!(cfg.hor_split_pos, 300);
+    assert_eq!(cfg.vert_split_mode, SplitMode::Split);
+    assert_eq!(cfg.vert_split_pos, 120);
+
+    let mut sh = Sheet::new("1");
+    sh.freeze_at(2, 1);
+    sh.split_at_pixels(0, 80);
+    assert_eq!(sh.config().hor_split_mode, SplitMode::None);
+    assert_eq!(sh.config().vert_split_mode, SplitMode::Split);
+    sh.split_at_pixels(0, 0);
+    assert_eq!(sh.config().vert_split_mode, SplitMode::None);
+
+    Ok(())
+}
+
+#[test]
+fn test_cursor() -> Result<(), OdsError> {
+    let mut wb = WorkBook::new_empty();
+    wb.push_sheet(Sheet::new("1"));
+    let mut sh = Sheet::new("2");
+    sh.set_value(0, 0, 1);
+    sh.set_cursor(20, 3)?;
+    sh.set_scroll_position(15, 2)?;
+    assert!(sh.set_cursor(0, 20_000).is_err());
+    wb.push_sheet(sh);
+    wb.set_active_sheet("2")?;
+    assert!(wb.set_active_sheet("3").is_err());
+
+    let buf = test_write_odsbuf(&mut wb)?;
+    let mut wb = read_ods_buf(&buf)?;
+    assert_eq!(wb.active_sheet(), Some(1));
+    assert_eq!(wb.sheet(1).cursor(), (20, 3));
+    assert_eq!(wb.sheet(1).scroll_position(), (15, 2));
+
+    let sh = wb.sheet_mut(1);
+    sh.freeze_at(1, 1);
+    sh.set_scroll_position(10, 5)?;
+    assert_eq!(sh.config().position_left, 0);
+    assert_eq!(sh.scroll_position(), (10, 5));
+
+    Ok(())
+}
+
+#[test]
+fn test_zoom() -> Result<(), OdsError> {
+    let mut wb = WorkBook::new_empty();
+    let mut sh = Sheet::new("1");
+    sh.set_zoom(150)?;
+    sh.set_page_view_zoom(80)?;
+    assert!(sh.set_zoom(10).is_err());
+    assert!(sh.set_page_view_zoom(1000).is_err());
+    wb.push_sheet(sh);
+    let mut sh = Sheet::new("2");
+    sh.set_zoom_type(ZoomType::PageWidth);
+    wb.push_sheet(sh);
+    let mut sh = Sheet::new("3");
+    sh.config_mut().zoom_type = 99;
+    wb.push_sheet(sh);
+    wb.set_page_break_view(true);
+
+    let buf = test_write_odsbuf(&mut wb)?;
+    let wb = read_ods_buf(&buf)?;
+    assert!(wb.page_break_view());
+    assert_eq!(wb.sheet(0).zoom_type(), ZoomType::Percent);
+    assert_eq!(wb.sheet(0).zoom(), 150);
+    assert_eq!(wb.sheet(0).page_view_zoom(), 80);
+    assert_eq!(wb.sheet(1).zoom_type(), ZoomType::PageWidth);
+    assert_eq!(wb.sheet(1).zoom(), 100);
+    // unknown values are kept.
+    assert_eq!(wb.sheet(2).config().zoom_type, 99);
+    assert_eq!(wb.sheet(2).zoom_type(), ZoomType::Percent);
+
+    Ok(())
+}
+
+struct Temperature(f64);
+
+impl IntoValue for Temperature {
+    fn into_value(self) -> Value {
+        Value::Number(self.0)
+    }
+}
+
+struct Euro(f64);
+
+impl IntoValue for Euro {
+    fn into_value(self) -> Value {
+        Value::new_currency("EUR", self.0)
+    }
+
+    fn cell_style(&self) -> Option<CellStyleRef> {
+        Some(CellStyleRef::from("euro"))
+    }
+}
+
+#[test]
+fn test_into_value() -> Result<(), OdsError> {
+    let mut sh = Sheet::new("1");
+
+    sh.set_value(0, 0, Temperature(21.5));
+    assert_eq!(sh.value(0, 0).as_f64_or(0.0), 21.5);
+    assert_eq!(sh.cellstyle(0, 0), None);
+
+    sh.set_styled_value(0, 1, Temperature(22.0), &CellStyleRef::from("celsius"));
+    assert_eq!(sh.value(0, 1).as_f64_or(0.0), 22.0);
+    assert_eq!(sh.cellstyle(0, 1), Some(&CellStyleRef::from("celsius")));
+
+    let euro = CellStyleRef::from("euro");
+    sh.set_value(1, 0, Euro(9.99));
+    assert_eq!(sh.value(1, 0), &Value::new_currency("EUR", 9.99));
+    assert_eq!(sh.cellstyle(1, 0), Some(&euro));
+    // an existing style is kept.
+    sh.set_cellstyle(1, 1, &CellStyleRef::from("bold"));
+    sh.set_value(1, 1, Euro(1.0));
+    assert_eq!(sh.cellstyle(1, 1), Some(&CellStyleRef::from("bold")));
+    // the explicit style wins.
+    sh.set_styled_value(1, 2, Euro(2.0), &CellStyleRef::from("celsius"));
+    assert_eq!(sh.cellstyle(1, 2), Some(&CellStyleRef::from("celsius")));
+
+    // the preferred style with its currency format, written and read back.
+    let mut wb = WorkBook::new_empty();
+    let mut fmt = ValueFormatCurrency::new_named("euro_fmt");
+    fmt.part_number().decimal_places(2).build();
+    fmt.part_currency().symbol("€").build();
+    let fmt = wb.add_currency_format(fmt);
+    wb.add_cellstyle(CellStyle::new("euro", &fmt));
+    let mut sh = Sheet::new("1");
+    sh.set_value(0, 0, Euro(9.5));
+    wb.push_sheet(sh);
+    let buf = test_write_odsbuf(&mut wb)?;
+    let wb = read_ods_buf(&buf)?;
+    assert_eq!(wb.sheet(0).cellstyle(0, 0), Some(&euro));
+    assert_eq!(
+        wb.cellstyle("euro").and_then(|v| v.value_format()),
+        Some("euro_fmt")
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_page_break() -> Result<(), OdsError> {
+    let mut wb = WorkBook::new_empty();
+
+    let mut sh = Sheet::new("1");
+    for r in 0..10 {
+        for c in 0..5 {
+            sh.set_value(r, c, r * c);
+        }
+    }
+    sh.set_row_height(4, cm!(1));
+    sh.set_row_height(5, cm!(1));
+    sh.set_row_page_break(5, true);
+    sh.set_col_page_break(3, true);
+    wb.push_sheet(sh);
+
+    test_write_ods(&mut wb, "test_out/test_sheet_8.ods")?;
+
+    let wb = read_ods("test_out/test_sheet_8.ods")?;
+    let sh = wb.sheet(0);
+    assert!(sh.row_page_break(5));
+    assert!(!sh.row_page_break(4));
+    assert!(!sh.row_page_break(6));
+    assert_eq!(sh.row_height(4), cm!(1));
+    assert_eq!(sh.row_height(5), cm!(1));
+    assert!(sh.col_page_break(3));
+    assert!(!sh.col_page_break(2));
+
+    Ok(())
+}
+
+#[test]
+fn test_range_iter() {
+    let mut sh = Sheet::new("1");
+    for r in 0..5 {
+        for c in 0..5 {
+            if (r + c) % 2 == 0 {
+                sh.set_value(r, c, r * 10 + c);
+            }
+        }
+    }
+
+    let range = CellRange::local(1, 1, 2, 3);
+    let cells: Vec<_> = sh.range_iter(&range).map(|(rc, _)| rc).collect();
+    assert_eq!(cells, vec![(1, 1), (1, 3), (2, 2)]);
+
+    let all: Vec<_> = sh
+        .range_iter_all(&range)
+        .map(|(rc, c)| (rc, c.value.as_u32_or(0)))
+        .collect();
+    assert_eq!(
+        all,
+        vec![
+            ((1, 1), 11),
+            ((1, 2), 0),
+            ((1, 3), 13),
+            ((2, 1), 0),
+            ((2, 2), 22),
+            ((2, 3), 0)
+        ]
+    );
+    assert_eq!(sh.range_iter_all(&range).size_hint(), (6, Some(6)));
+}
+
+#[test]
+fn test_used_rows() {
+    let mut sh = Sheet::new("1");
+    assert_eq!(sh.used_range(), None);
+
+    sh.set_value(2, 4, 1);
+    sh.set_value(2, 7, 2);
+    sh.set_value(5, 1, 3);
+    assert_eq!(sh.used_range(), Some(CellRange::local(2, 1, 5, 7)));
+
+    let rows: Vec<_> = sh
+        .iter_used_rows()
+        .map(|(r, cells)| (r, cells.iter().map(|(c, _)| *c).collect::<Vec<_>>()))
+        .collect();
+    assert_eq!(rows, vec![(2, vec![4, 7]), (5, vec![1])]);
+}
+
+#[test]
+fn test_set_block() {
+    let mut sh = Sheet::new("1");
+    let red = CellStyleRef::from("red");
+    sh.set_styled_value(1, 2, "old", &red);
+
+    let a: &[&str] = &["a", "b", "c"];
+    let b: &[&str] = &["d", "e"];
+    sh.set_block(1, 1, &[a, b]);
+
+    assert_eq!(sh.value(1, 1).as_str_or(""), "a");
+    assert_eq!(sh.value(1, 2).as_str_or(""), "b");
+    assert_eq!(sh.cellstyle(1, 2), Some(&red));
+    assert_eq!(sh.value(2, 2).as_str_or(""), "e");
+    assert_eq!(sh.value(2, 3), &Value::Empty);
+
+    sh.set_block(5, 0, &[vec![1.0, 2.0], vec![3.0, 4.0]]);
+    assert_eq!(sh.value(6, 1).as_f64_or(0.0), 4.0);
+}
+
+#[test]
+fn test_effective_cellstyle() {
+    let mut wb = WorkBook::new_empty();
+    let cell_st = wb.add_cellstyle(CellStyle::new("cell", &DefaultFormat::default()));
+    let row_st = wb.add_cellstyle(CellStyle::new("row", &DefaultFormat::default()));
+    let col_st = wb.add_cellstyle(CellStyle::new("col", &DefaultFormat::default()));
+    let num_st = wb.add_cellstyle(CellStyle::new("num", &DefaultFormat::number()));
+    wb.add_def_style(ValueType::Number, num_st.clone());
+
+    let mut sh = Sheet::new("1");
+    sh.set_row_cellstyle(1, &row_st);
+    sh.set_col_cellstyle(1, &col_st);
+    sh.set_styled_value(1, 1, "a", &cell_st);
+    sh.set_value(1, 2, "b");
+    sh.set_value(2, 1, 1);
+    sh.set_value(2, 2, "c");
+    wb.push_sheet(sh);
+
+    let sh = wb.sheet(0);
+    assert_eq!(sh.effective_cellstyle(1, 1), Some(&cell_st));
+    assert_eq!(sh.effective_cellstyle(1, 2), Some(&row_st));
+    assert_eq!(sh.effective_cellstyle(2, 1), Some(&col_st));
+    assert_eq!(sh.effective_cellstyle(5, 1), Some(&col_st));
+    assert_eq!(sh.effective_cellstyle(2, 2), None);
+
+    assert_eq!(wb.effective_cellstyle(0, 1, 1), Some(&cell_st));
+    assert_eq!(wb.effective_cellstyle(0, 2, 1), Some(&num_st));
+    assert_eq!(wb.effective_cellstyle(0, 1, 2), Some(&row_st));
+    assert_eq!(wb.effective_cellstyle(0, 2, 2), None);
+}
+
+#[test]
+fn test_merge() -> Result<(), OdsError> {
+    let mut sh = Sheet::new("1");
+    sh.set_value(1, 1, "anchor");
+    sh.set_value(1, 2, "covered");
+    sh.set_styled_value(2, 2, 42, &CellStyleRef::from("ce1"));
+    sh.set_value(4, 4, "outside");
+
+    let range = CellRange::local(1, 1, 2, 3);
+    sh.merge(&range);
+
+    assert_eq!(sh.row_span(1, 1), 2);
+    assert_eq!(sh.col_span(1, 1), 3);
+    assert!(sh.is_empty(1, 2));
+    assert_eq!(sh.value(2, 2), &Value::Empty);
+    assert_eq!(sh.cellstyle(2, 2), Some(&CellStyleRef::from("ce1")));
+    assert_eq!(sh.value(4, 4).as_str_or(""), "outside");
+
+    assert_eq!(sh.merged_region(1, 1), Some(range.clone()));
+    assert_eq!(sh.merged_region(2, 3), Some(range.clone()));
+    assert_eq!(sh.merged_region(3, 3), None);
+    assert_eq!(sh.merged_region(0, 0), None);
+    assert_eq!(sh.merged_value(2, 3).as_str_or(""), "anchor");
+
+    let low = CellRange::local(10, 0, 14, 1);
+    sh.set_value(10, 0, "low");
+    sh.merge(&low);
+    sh.set_value(12, 5, "right");
+    assert_eq!(sh.merged_region(14, 1), Some(low.clone()));
+    assert_eq!(sh.merged_region(14, 2), None);
+    sh.unmerge(&low);
+    assert_eq!(sh.value(2, 3), &Value::Empty);
+
+    let mut wb = WorkBook::new_empty();
+    wb.push_sheet(sh);
+    let buf = test_write_odsbuf(&mut wb)?;
+    let mut wb = read_ods_buf(&buf)?;
+    assert_eq!(wb.sheet(0).merged_region(2, 1), Some(range.clone()));
+
+    wb.sheet_mut(0).unmerge(&range);
+    assert_eq!(wb.sheet(0).merged_region(2, 1), None);
+    assert_eq!(wb.sheet(0).row_span(1, 1), 1);
+
+    Ok(())
+}
+
+#[test]
+fn test_hide_show_ranges() -> Result<(), OdsError> {
+    let mut sh = Sheet::new("1");
+    sh.set_row_height(5, cm!(1));
+    sh.hide_rows(3, 8);
+    sh.show_rows(6, 6);
+    sh.hide_cols(2, 4);
+
+    for row in 0..10 {
+        let expect = if (3..=8).contains(&row) && row != 6 {
+            Visibility::Collapsed
+        } else {
+            Visibility::Visible
+        };
+        assert_eq!(sh.row_visible(row), expect, "row {}", row);
+    }
+    assert_eq!(sh.row_height(5), cm!(1));
+    assert_eq!(sh.col_visible(1), Visibility::Visible);
+    assert_eq!(sh.col_visible(3), Visibility::Collapsed);
+    assert_eq!(sh.col_visible(5), Visibility::Visible);
+
+    sh.set_value(10, 10, 1);
+    let mut wb = WorkBook::new_empty();
+    wb.push_sheet(sh);
+    let buf = test_write_odsbuf(&mut wb)?;
+    let wb = read_ods_buf(&buf)?;
+    assert_eq!(wb.sheet(0).row_visible(4), Visibility::Collapsed);
+    assert_eq!(wb.sheet(0).row_visible(6), Visibility::Visible);
+    assert_eq!(wb.sheet(0).col_visible(4), Visibility::Collapsed);
+
+    Ok(())
+}
+
+#[test]
+fn test_cellstyle_ranges() -> Result<(), OdsError> {
+    let mut wb = WorkBook::new_empty();
+    let style = wb.add_cellstyle(CellStyle::new("bold", &DefaultFormat::default()));
+
+    let mut sh = Sheet::new("1");
+    sh.set_col_width(3, cm!(3));
+    sh.set_col_cellstyle_range(1, 5, &style);
+    sh.set_row_cellstyle(4, &style);
+    sh.set_row_cellstyle_range(2, 7, &style);
+    sh.clear_row_cellstyle_range(7, 7);
+
+    for col in 0..7 {
+        let expect = (1..=5).contains(&col).then_some(&style);
+        assert_eq!(sh.col_cellstyle(col), expect, "col {}", col);
+    }
+    assert_eq!(sh.col_width(3), cm!(3));
+    for row in 0..9 {
+        let expect = (2..=6).contains(&row).then_some(&style);
+        assert_eq!(sh.row_cellstyle(row), expect, "row {}", row);
+    }
+
+    sh.set_value(10, 10, 1);
+    wb.push_sheet(sh);
+    let buf = test_write_odsbuf(&mut wb)?;
+    let wb = read_ods_buf(&buf)?;
+    let sh = wb.sheet(0);
+    assert_eq!(sh.row_cellstyle(2), Some(&style));
+    assert_eq!(sh.row_cellstyle(6), Some(&style));
+    assert_eq!(sh.row_cellstyle(7), None);
+    // rows 2..=6 are written as one repeated row.
+    assert_eq!(sh._row_header_span(2), Some(5));
+    assert_eq!(sh.col_cellstyle(5), Some(&style));
+    assert_eq!(sh.col_cellstyle(6), None);
+
+    Ok(())
+}
+
+#[test]
+fn test_cell_protection() -> Result<(), OdsError> {
+    let mut wb = WorkBook::new_empty();
+    let mut named = CellStyle::new("input", &DefaultFormat::default());
+    named.set_protected(false);
+    named.set_hidden_formula(true);
+    assert!(!named.protected());
+    assert!(named.hidden_formula());
+    named.set_protected(true);
+    assert_eq!(
+        named.cell_protect()?,
+        Some(CellProtect::ProtectedFormulaHidden)
+    );
+    named.set_hidden_formula(false);
+    named.set_protected(false);
+    assert_eq!(named.cell_protect()?, Some(CellProtect::None));
+    let named = wb.add_cellstyle(named);
+
+    let mut sh = Sheet::new("1");
+    sh.set_protected(true);
+    sh.set_value(0, 0, "label");
+    sh.set_styled_value(1, 0, 1, &named);
+    wb.push_sheet(sh);
+    wb.set_range_protected(0, &CellRange::local(2, 0, 3, 0), false);
+
+    assert!(wb.is_cell_protected(0, 0, 0));
+    assert!(!wb.is_cell_protected(0, 1, 0));
+    assert!(!wb.is_cell_protected(0, 2, 0));
+    assert!(!wb.is_cell_protected(0, 3, 0));
+    assert!(wb.is_cell_protected(0, 4, 0));
+
+    let buf = test_write_odsbuf(&mut wb)?;
+    let mut wb = read_ods_buf(&buf)?;
+    assert!(wb.is_cell_protected(0, 0, 0));
+    assert!(!wb.is_cell_protected(0, 3, 0));
+    wb.sheet_mut(0).set_protected(false);
+    assert!(!wb.is_cell_protected(0, 0, 0));
+
+    // whole rows and columns change the defaults.
+    let mut wb = WorkBook::new_empty();
+    let mut sh = Sheet::new("1");
+    sh.set_protected(true);
+    sh.set_value(0, 2, "data");
+    sh.set_value(5, 0, "data");
+    wb.push_sheet(sh);
+    let header = wb.add_cellstyle(CellStyle::new_empty());
+    wb.sheet_mut(0).set_row_cellstyle(7, &header);
+    wb.set_range_protected(0, &CellRange::local(0, 2, 1_048_575, 3), false);
+    wb.set_range_protected(0, &CellRange::local(5, 0, 5, 16_383), false);
+    assert!(!wb.is_cell_protected(0, 0, 2));
+    assert!(!wb.is_cell_protected(0, 1_000_000, 3));
+    assert!(wb.is_cell_protected(0, 1_000_000, 4));
+    assert!(!wb.is_cell_protected(0, 5, 0));
+    assert!(!wb.is_cell_protected(0, 5, 10_000));
+    assert!(!wb.is_cell_protected(0, 7, 2));
+    assert!(wb.is_cell_protected(0, 7, 4));
+    assert_eq!(wb.sheet(0).cell_count(), 4);
+
+    Ok(())
+}
+
+#[test]
+fn test_conditional_formats() -> Result<(), OdsError> {
+    let mut sh = Sheet::new("Data");
+    for r in 0..10 {
+        sh.set_value(r, 0, r as f64 - 3.0);
+        sh.set_value(r, 1, r);
+    }
+
+    let mut data_bar = DataBar::new(Rgb::new(99, 142, 198));
+    data_bar.set_max(ScaleValue::Number(10.0));
+    data_bar.set_gradient(false);
+    sh.add_conditional_format(ConditionalFormat::new_data_bar(
+        CellRange::local(0, 0, 9, 0),
+        data_bar.clone(),
+    ));
+    let color_scale = ColorScale::new_3(
+        Rgb::new(255, 0, 0),
+        Rgb::new(255, 255, 0),
+        Rgb::new(0, 169, 51),
+    );
+    sh.add_conditional_format(ConditionalFormat::new_color_scale(
+        CellRange::local(0, 1, 9, 1),
+        color_scale.clone(),
+    ));
+
+    let mut wb = WorkBook::new_empty();
+    wb.push_sheet(sh);
+    let buf = test_write_odsbuf(&mut wb)?;
+    let mut wb = read_ods_buf(&buf)?;
+
+    let mut range = CellRange::remote("Data", 0, 0, 9, 0);
+    range.set_to_table("Data");
+    let formats = wb.sheet(0).conditional_formats();
+    assert_eq!(formats.len(), 2);
+    assert_eq!(formats[0].range(), &range);
+    assert_eq!(
+        formats[0].format(),
+        &ConditionalFormatKind::DataBar(data_bar)
+    );
+    assert_eq!(
+        formats[1].format(),
+        &ConditionalFormatKind::ColorScale(color_scale)
+    );
+
+    let buf = write_fods_buf(&mut wb, Vec::new())?;
+    let wb = read_fods_buf(&buf)?;
+    assert_eq!(wb.sheet(0).conditional_formats().len(), 2);
+
+    Ok(())
+}
+
+#[test]
+fn test_value_as() -> Result<(), OdsError> {
+    let mut sh = Sheet::new("1");
+    sh.set_value(0, 0, 42.5);
+    sh.set_value(0, 1, true);
+    sh.set_value(0, 2, "text");
+    sh.set_value(
+        0,
+        3,
+        NaiveDate::from_ymd_opt(2024, 2, 29)
+            .expect("date")
+            .and_hms_opt(12, 0, 0)
+            .expect("time"),
+    );
+    #[cfg(feature = "rust_decimal")]
+    sh.set_value(0, 4, Decimal::new(1005, 2));
+
+    assert_eq!(sh.value_as::<f64>(0, 0)?, 42.5);
+    assert_eq!(sh.value_as::<i64>(0, 0)?, 42);
+    assert!(sh.value_as::<bool>(0, 1)?);
+    assert_eq!(sh.value_as::<String>(0, 2)?, "text");
+    assert_eq!(
+        sh.value_as::<NaiveDate>(0, 3)?,
+        NaiveDate::from_ymd_opt(2024, 2, 29).expect("date")
+    );
+    assert_eq!(
+        sh.value_as::<NaiveDateTime>(0, 3)?.date(),
+        NaiveDate::from_ymd_opt(2024, 2, 29).expect("date")
+    );
+    #[cfg(feature = "rust_decimal")]
+    assert_eq!(sh.value_as::<Decimal>(0, 4)?, Decimal::new(1005, 2));
+
+    assert!(sh.value_as::<f64>(0, 2).is_err());
+    assert!(sh.value_as::<String>(0, 0).is_err());
+    assert!(sh.value_as::<bool>(5, 5).is_err());
+
+    Ok(())
+}
+
+#[cfg(feature = "rust_decimal")]
+#[test]
+fn test_decimal_roundtrip() -> Result<(), OdsError> {
+    let mut wb = WorkBook::new_empty();
+    let mut sh = Sheet::new("1");
+    let v: Decimal = "1234567.001".parse().expect("decimal");
+    sh.set_value(0, 0, v);
+    sh.set_value(0, 1, 0.1);
+    wb.push_sheet(sh);
+
+    let buf = test_write_odsbuf(&mut wb)?;
+
+    let wb = OdsOptions::default()
+        .use_decimal()
+        .read_ods(std::io::Cursor::new(&buf))?;
+    assert_eq!(wb.sheet(0).value(0, 0), &Value::Decimal(v));
+    assert_eq!(wb.sheet(0).value(0, 0).value_type(), ValueType::Number);
+    assert_eq!(
+        wb.sheet(0).value(0, 1),
+        &Value::Decimal("0.1".parse().expect("decimal"))
+    );
+
+    let wb = read_ods_buf(&buf)?;
+    assert_eq!(wb.sheet(0).value(0, 0), &Value::Number(1234567.001));
+
+    Ok(())
+}
+
+#[test]
+fn test_duration_roundtrip() -> Result<(), OdsError> {
+    let mut wb = WorkBook::new_empty();
+    let mut sh = Sheet::new("1");
+    sh.set_value(0, 0, std::time::Duration::from_millis(1050));
+    sh.set_value(0, 1, -Duration::milliseconds(5400500));
+    sh.set_value(0, 2, Duration::nanoseconds(1));
+    wb.push_sheet(sh);
+
+    let buf = test_write_odsbuf(&mut wb)?;
+    let wb = read_ods_buf(&buf)?;
+    let sh = wb.sheet(0);
+
+    assert_eq!(
+        sh.value(0, 0).as_timeduration_opt(),
+        Some(Duration::milliseconds(1050))
+    );
+    assert_eq!(
+        sh.value_as::<std::time::Duration>(0, 0)?,
+        std::time::Duration::from_millis(1050)
+    );
+    assert_eq!(
+        sh.value(0, 1).as_timeduration_opt(),
+        Some(-Duration::milliseconds(5400500))
+    );
+    assert_eq!(sh.value(0, 1).as_std_duration_opt(), None);
+    assert_eq!(
+        sh.value(0, 2).as_timeduration_opt(),
+        Some(Duration::nanoseconds(1))
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_date_time_values() -> Result<(), OdsError> {
+    let mut wb = WorkBook::new(locale!("en_US"));
+    let mut sh = Sheet::new("1");
+    let date = NaiveDate::from_ymd_opt(2024, 2, 29).expect("date");
+    let time = NaiveTime::from_hms_opt(13, 30, 15).expect("time");
+    sh.set_value(0, 0, date);
+    sh.set_value(0, 1, time);
+    sh.set_value(0, 2, date.and_time(time));
+    sh.set_value(0, 3, Duration::minutes(90));
+    wb.push_sheet(sh);
+
+    let buf = write_fods_buf(&mut wb, Vec::new())?;
+    let xml = String::from_utf8(buf.clone()).expect("utf8");
+    assert!(xml.contains(r#"office:date-value="2024-02-29""#));
+    assert!(xml.contains(r#"office:time-value="PT13H30M15S""#));
+    assert!(xml.contains(r#"office:date-value="2024-02-29T13:30:15""#));
+
+    let wb = read_fods_buf(&buf)?;
+    let sh = wb.sheet(0);
+    assert_eq!(sh.value(0, 0), &Value::Date(date));
+    assert_eq!(sh.value(0, 1), &Value::Time(time));
+    assert_eq!(sh.value(0, 2), &Value::DateTime(date.and_time(time)));
+    assert_eq!(sh.value(0, 3), &Value::TimeDuration(Duration::minutes(90)));
+    assert_eq!(wb.def_style(ValueType::Date), Some(&DefaultStyle::date()));
+    assert_eq!(
+        wb.def_style(ValueType::DateTime),
+        Some(&DefaultStyle::datetime())
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_dense_storage() -> Result<(), OdsError> {
+    let mut sh = Sheet::new("1");
+    sh.set_value(0, 3, "a");
+    sh.set_value(2, 1, "b");
+    sh.set_cell_storage(CellStorage::Dense);
+    assert_eq!(sh.cell_storage(), CellStorage::Dense);
+    assert_eq!(sh.value(2, 1), &Value::Text("b".to_string()));
+
+    for r in 0..5 {
+        for c in 0..3 {
+            sh.set_value(r + 5, c, r * 10 + c);
+        }
+    }
+    assert_eq!(sh.cell_count(), 17);
+    assert_eq!(sh.used_range(), Some(CellRange::local(0, 0, 9, 3)));
+
+    let keys: Vec<_> = sh.iter().map(|(k, _)| k).take(3).collect();
+    assert_eq!(keys, vec![(0, 3), (2, 1), (5, 0)]);
+    let keys: Vec<_> = sh.range((5, 1)..(6, 1)).map(|(k, _)| k).collect();
+    assert_eq!(keys, vec![(5, 1), (5, 2), (6, 0)]);
+    assert_eq!(sh.range(..=(5, 0)).last().map(|(k, _)| k), Some((5, 0)));
+
+    assert!(sh.remove_cell(2, 1).is_some());
+    assert!(sh.remove_cell(2, 1).is_none());
+    assert_eq!(sh.cell_count(), 16);
+
+    let mut wb = WorkBook::new_empty();
+    wb.push_sheet(sh);
+    let buf = write_fods_buf(&mut wb, Vec::new())?;
+    let wb = read_fods_buf(&buf)?;
+    let sh = wb.sheet(0);
+    assert_eq!(sh.cell_storage(), CellStorage::Sparse);
+    assert_eq!(sh.value(9, 2), &Value::Number(42.0));
+    assert_eq!(sh.cell_count(), 16);
+
+    Ok(())
+}
+
+#[test]
+fn test_sheet_props() -> Result<(), OdsError> {
+    let mut wb = WorkBook::new_empty();
+
+    let mut sh = Sheet::new("1");
+    sh.set_value(0, 0, 1);
+    sh.set_tab_color(Rgb::new(255, 0, 128));
+    sh.set_rtl(true);
+    sh.set_protected(true);
+    sh.set_protection_key("c2VjcmV0", "http://www.w3.org/2000/09/xmldsig#sha256");
+    wb.push_sheet(sh);
+
+    let mut sh = Sheet::new("2");
+    sh.set_value(0, 0, 2);
+    wb.push_sheet(sh);
+
+    let wb2 = read_ods_buf(&test_write_odsbuf(&mut wb)?)?;
+    let sh = wb2.sheet(0);
+    assert_eq!(sh.tab_color(), Some(Rgb::new(255, 0, 128)));
+    assert!(sh.rtl());
+    assert!(sh.protected());
+    assert_eq!(sh.protection_key(), Some("c2VjcmV0"));
+    assert_eq!(
+        sh.protection_key_digest(),
+        Some("http://www.w3.org/2000/09/xmldsig#sha256")
+    );
+    let sh = wb2.sheet(1);
+    assert_eq!(sh.tab_color(), None);
+    assert!(!sh.rtl());
+    assert!(!sh.protected());
+
+    // clearing doesn't touch the style of other sheets.
+    let mut wb = wb2;
+    wb.sheet_mut(0).clear_tab_color();
+    wb.sheet_mut(0).set_rtl(false);
+    wb.sheet_mut(1).set_tab_color(Rgb::new(0, 0, 255));
+    let wb = read_ods_buf(&test_write_odsbuf(&mut wb)?)?;
+    assert_eq!(wb.sheet(0).tab_color(), None);
+    assert!(!wb.sheet(0).rtl());
+    assert_eq!(wb.sheet(1).tab_color(), Some(Rgb::new(0, 0, 255)));
+
+    Ok(())
+}
+
+#[test]
+fn test_fix_spans() -> Result<(), OdsError> {
+    let mut sh = Sheet::new("1");
+    sh.set_value(9, 9, 1);
+    // beyond the used area is fine.
+    sh.set_row_span(8, 0, 5);
+    // beyond the size of a sheet.
+    sh.set_row_span(1_048_574, 0, 5);
+    // overlaps.
+    sh.set_col_span(0, 0, 4);
+    sh.set_row_span(0, 0, 2);
+    sh.set_col_span(1, 2, 2);
+    sh.set_col_span(1, 5, 3);
+    sh.set_row_span(0, 6, 3);
+    sh.set_row_span(0, 3, 3);
+
+    let mut wb = WorkBook::new_empty();
+    wb.push_sheet(sh);
+    let issues = wb.validate();
+    assert!(
+        issues.contains(&ValidationIssue::SpanOutOfRange(CellRange::remote(
+            "1", 1_048_574, 0, 1_048_578, 0
+        )))
+    );
+    assert!(!issues
+        .iter()
+        .any(|v| matches!(v, ValidationIssue::SpanOutOfRange(r) if r.row() == 8)));
+    assert!(issues.contains(&ValidationIssue::OverlappingMerge(
+        CellRange::remote("1", 0, 0, 1, 3),
+        CellRange::remote("1", 0, 3, 2, 3)
+    )));
+
+    let sh = wb.sheet_mut(0);
+    assert_eq!(sh.fix_spans(), 4);
+    assert_eq!((sh.row_span(8, 0), sh.col_span(8, 0)), (5, 1));
+    assert_eq!(
+        (sh.row_span(1_048_574, 0), sh.col_span(1_048_574, 0)),
+        (2, 1)
+    );
+    assert_eq!((sh.row_span(0, 3), sh.col_span(0, 3)), (1, 1));
+    assert_eq!((sh.row_span(1, 2), sh.col_span(1, 2)), (1, 1));
+    assert_eq!((sh.row_span(1, 5), sh.col_span(1, 5)), (1, 1));
+    assert_eq!((sh.row_span(0, 6), sh.col_span(0, 6)), (3, 1));
+    assert_eq!((sh.row_span(0, 0), sh.col_span(0, 0)), (2, 4));
+    assert_eq!(sh.fix_spans(), 0);
+    assert!(wb.validate().is_empty());
+
+    Ok(())
+}
+
+#[test]
+fn test_clear_range() {
+    let style = CellStyleRef::from("c1");
+
+    let mut sh = Sheet::new("1");
+    for r in 0..4 {
+        for c in 0..4 {
+            sh.set_styled_value(r, c, r * 10 + c, &style);
+        }
+    }
+    sh.set_formula(1, 1, "of:=[.A1]");
+    sh.set_formula(1, 2, "of:=[.A2]");
+
+    let range = CellRange::local(1, 1, 2, 2);
+    sh.clear_range(&range, ClearOp::Values);
+    assert_eq!(sh.value(1, 1), &Value::Number(11.0));
+    assert_eq!(sh.value(2, 2), &Value::Empty);
+    assert_eq!(sh.cellstyle(2, 2), Some(&style));
+    assert_eq!(sh.value(2, 3), &Value::Number(23.0));
+    assert_eq!(sh.value(3, 1), &Value::Number(31.0));
+
+    sh.clear_range(&range, ClearOp::Formulas);
+    assert_eq!(sh.formula(1, 1), None);
+    assert_eq!(sh.value(1, 1), &Value::Empty);
+    assert_eq!(sh.value(1, 3), &Value::Number(13.0));
+
+    sh.clear_range(&range, ClearOp::Styles);
+    assert!(sh.cell_ref(1, 1).is_none());
+    assert!(sh.cell_ref(2, 2).is_none());
+    assert_eq!(sh.cellstyle(1, 0), Some(&style));
+
+    sh.clear_range(&CellRange::local(0, 2, 3, 3), ClearOp::All);
+    assert!(sh.cell_ref(0, 3).is_none());
+    assert!(sh.cell_ref(3, 2).is_none());
+    assert_eq!(sh.value(3, 1), &Value::Number(31.0));
+    assert_eq!(sh.used_grid_size(), (4, 2));
+}
+
+#[test]
+fn test_fill_series() {
+    let style = CellStyleRef::from("c1");
+
+    let mut sh = Sheet::new("1");
+    sh.set_cellstyle(1, 0, &style);
+    sh.set_formula(2, 0, "of:=1");
+    sh.fill_series(&CellRange::local(0, 0, 3, 0), 1, 0.5);
+    assert_eq!(sh.value(0, 0), &Value::Number(1.0));
+    assert_eq!(sh.value(1, 0), &Value::Number(1.5));
+    assert_eq!(sh.cellstyle(1, 0), Some(&style));
+    assert_eq!(sh.value(3, 0), &Value::Number(2.5));
+    assert_eq!(sh.formula(2, 0), None);
+
+    let start = NaiveDate::from_ymd_opt(2024, 1, 29).expect("date");
+    sh.fill_series(&CellRange::local(0, 1, 0, 3), start, 7.0);
+    assert_eq!(
+        sh.value(0, 3),
+        &Value::Date(NaiveDate::from_ymd_opt(2024, 2, 12).expect("date"))
+    );
+    assert!(sh.cell_ref(1, 1).is_none());
+
+    sh.fill_series(&CellRange::local(0, 7, 2, 7), 0.0, -1e17);
+    assert_eq!(sh.value(2, 7), &Value::Number(-2e17));
+    sh.fill_series(&CellRange::local(0, 8, 2, 8), start, -1e17);
+    assert_eq!(sh.value(2, 8), &Value::Date(start));
+    sh.fill_series(&CellRange::local(0, 9, 1, 9), Duration::hours(1), f64::NAN);
+    assert_eq!(sh.value(1, 9), &Value::TimeDuration(Duration::hours(1)));
+
+    sh.set_value(0, 4, "x");
+    sh.set_value(0, 5, 1);
+    sh.set_formula(0, 5, "of:=[.A1]+[.$A$1]+[.B$1]");
+    sh.set_cellstyle(0, 5, &style);
+    sh.set_value(2, 6, 9);
+    sh.fill_down(&CellRange::local(0, 4, 2, 6));
+    assert_eq!(sh.value(2, 4), &Value::Text("x".to_string()));
+    assert_eq!(
+        sh.formula(1, 5),
+        Some(&"of:=[.A2]+[.$A$1]+[.B$1]".to_string())
+    );
+    assert_eq!(
+        sh.formula(2, 5),
+        Some(&"of:=[.A3]+[.$A$1]+[.B$1]".to_string())
+    );
+    assert_eq!(sh.value(2, 5), &Value::Empty);
+    assert_eq!(sh.cellstyle(2, 5), Some(&style));
+    assert!(sh.cell_ref(2, 6).is_none());
+}
+
+#[test]
+fn test_sort_range() {
+    let style = CellStyleRef::from("c1");
+
+    let mut sh = Sheet::new("1");
+    sh.set_value(0, 0, "name");
+    for (row, (name, n)) in [("b", 2), ("", 1), ("C", 3), ("a", 2), ("x", 5)]
+        .into_iter()
+        .enumerate()
+    {
+        let row = row as u32 + 1;
+        if !name.is_empty() {
+            sh.set_value(row, 0, name);
+        }
+        sh.set_value(row, 1, n);
+        sh.set_formula(row, 2, format!("of:=[.B{}]*2", row + 1));
+    }
+    sh.set_cellstyle(3, 0, &style);
+    sh.set_value(5, 3, "outside");
+
+    // header row and last row are outside.
+    let range = CellRange::local(1, 0, 4, 2);
+    sh.sort_range(&range, &[SortKey::desc(1), SortKey::asc(0)]);
+    let names = (1..=4).map(|r| sh.value(r, 0).clone()).collect::<Vec<_>>();
+    assert_eq!(
+        names,
+        vec![
+            Value::from("C"),
+            Value::from("a"),
+            Value::from("b"),
+            Value::Empty
+        ]
+    );
+    assert_eq!(sh.cellstyle(1, 0), Some(&style));
+    assert_eq!(sh.formula(1, 2), Some(&"of:=[.B4]*2".to_string()));
+    assert_eq!(sh.value(0, 0), &Value::from("name"));
+    assert_eq!(sh.value(5, 0), &Value::from("x"));
+    assert_eq!(sh.value(5, 3), &Value::from("outside"));
+
+    sh.sort_range_shift_refs(&range, &[SortKey::asc(0).case_sensitive(true)]);
+    let names = (1..=4).map(|r| sh.value(r, 0).clone()).collect::<Vec<_>>();
+    assert_eq!(
+        names,
+        vec![
+            Value::from("C"),
+            Value::from("a"),
+            Value::from("b"),
+            Value::Empty
+        ]
+    );
+    sh.sort_range_shift_refs(&range, &[SortKey::asc(0)]);
+    assert_eq!(sh.value(1, 0), &Value::from("a"));
+    // moved up one row.
+    assert_eq!(sh.formula(1, 2), Some(&"of:=[.B4]*2".to_string()));
+    assert_eq!(sh.value(3, 0), &Value::from("C"));
+    // moved down two rows.
+    assert_eq!(sh.formula(3, 2), Some(&"of:=[.B6]*2".to_string()));
+}
+
+#[test]
+fn test_insert_rows() -> Result<(), OdsError> {
+    let mut sh = Sheet::new("Data");
+    sh.set_value(0, 0, 1);
+    sh.set_value(2, 0, 2);
+    sh.set_formula(3, 0, "of:=SUM([.A1:.A3])+[.$A$3]+[Data.A1]+[Other.A3]");
+    sh.set_row_span(1, 1, 2);
+    sh.set_row_height(2, cm!(1));
+    sh.add_row_group(1, 3);
+    sh.add_print_range(CellRange::local(2, 0, 3, 0))?;
+    sh.add_conditional_format(ConditionalFormat::new_data_bar(
+        CellRange::local(1, 0, 3, 0),
+        DataBar::new(Rgb::new(99, 142, 198)),
+    ));
+
+    let mut other = Sheet::new("Other");
+    other.set_formula(0, 0, "of:=[Data.A3]+[.A3]+SUM([Data.A1:.A4])");
+
+    let mut wb = WorkBook::new_empty();
+    wb.push_sheet(sh);
+    wb.push_sheet(other);
+    wb.insert_rows(0, 2, 2);
+    assert_eq!(
+        wb.sheet(1).formula(0, 0),
+        Some(&"of:=[Data.A5]+[.A3]+SUM([Data.A1:.A6])".to_string())
+    );
+
+    let sh = wb.sheet(0);
+    assert_eq!(sh.value(0, 0), &Value::Number(1.0));
+    assert_eq!(sh.value(2, 0), &Value::Empty);
+    assert_eq!(sh.value(4, 0), &Value::Number(2.0));
+    assert_eq!(
+        sh.formula(5, 0),
+        Some(&"of:=SUM([.A1:.A5])+[.$A$5]+[Data.A1]+[Other.A3]".to_string())
+    );
+    assert_eq!(sh.row_span(1, 1), 4);
+    assert_eq!(sh.row_height(4), cm!(1));
+    assert_eq!(
+        sh.row_group_iter().next().map(|v| (v.from, v.to)),
+        Some((1, 5))
+    );
+    assert_eq!(sh.print_ranges(), Some(&vec![CellRange::local(4, 0, 5, 0)]));
+    assert_eq!(
+        sh.conditional_formats()[0].range(),
+        &CellRange::local(1, 0, 5, 0)
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_subtotals() -> Result<(), OdsError> {
+    let mut sh = Sheet::new("1");
+    sh.set_value(0, 0, "fruit");
+    sh.set_value(0, 1, "amount");
+    for (row, (name, n)) in [("apple", 1), ("apple", 2), ("pear", 3)]
+        .into_iter()
+        .enumerate()
+    {
+        sh.set_value(row as u32 + 1, 0, name);
+        sh.set_value(row as u32 + 1, 1, n);
+    }
+    sh.set_value(4, 0, "below");
+    sh.set_formula(4, 1, "of:=SUM([.B2:.B4])");
+
+    let range = sh.subtotals(
+        &CellRange::local(1, 0, 3, 1),
+        0,
+        &[1],
+        SubtotalFn::Sum,
+        "Summe",
+        "Gesamtergebnis",
+    )?;
+    assert_eq!(range, CellRange::local(1, 0, 6, 1));
+    assert_eq!(sh.value(3, 0), &Value::from("apple Summe"));
+    assert_eq!(
+        sh.formula(3, 1),
+        Some(&"of:=SUBTOTAL(9;[.B2:.B3])".to_string())
+    );
+    assert_eq!(sh.value(4, 0), &Value::from("pear"));
+    assert_eq!(
+        sh.formula(5, 1),
+        Some(&"of:=SUBTOTAL(9;[.B5:.B5])".to_string())
+    );
+    assert_eq!(sh.value(6, 0), &Value::from("Gesamtergebnis"));
+    assert_eq!(
+        sh.formula(6, 1),
+        Some(&"of:=SUBTOTAL(9;[.B2:.B6])".to_string())
+    );
+    assert_eq!(sh.value(7, 0), &Value::from("below"));
+    // inserted after the end of the range.
+    assert_eq!(sh.formula(7, 1), Some(&"of:=SUM([.B2:.B5])".to_string()));
+    assert_eq!(
+        sh.row_group_iter()
+            .map(|v| (v.from, v.to))
+            .collect::<Vec<_>>(),
+        vec![(1, 5), (1, 2), (4, 4)]
+    );
+
+    assert!(sh
+        .subtotals(
+            &CellRange::local(2, 0, 6, 1),
+            0,
+            &[1],
+            SubtotalFn::Sum,
+            "Sum",
+            "Grand Total"
+        )
+        .is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_to_html() {
+    let mut wb = WorkBook::new_empty();
+
+    let mut base = CellStyle::new_empty();
+    base.set_name("base");
+    base.set_background_color(Rgb::new(255, 0, 0));
+    let base = wb.add_cellstyle(base);
+    let mut bold = CellStyle::new_empty();
+    bold.set_name("bold");
+    bold.set_parent_style(&base);
+    bold.set_font_bold();
+    let bold = wb.add_cellstyle(bold);
+
+    let mut sh = Sheet::new("1");
+    sh.set_styled_value(0, 0, "x & y", &bold);
+    sh.set_col_span(0, 0, 2);
+    sh.set_value(0, 1, "covered");
+    sh.set_value(1, 0, 1);
+    sh.set_value(1, 1, "a\nb");
+    sh.set_value(2, 0, "hidden");
+    sh.set_row_visible(2, Visibility::Collapsed);
+
+    let html = sh.to_html(
+        &wb,
+        &CellRange::local(0, 0, 2, 1),
+        HtmlOptions::default().class("report"),
+    );
+    assert_eq!(
+        html,
+        "<table class=\"report\" style=\"border-collapse:collapse\">\n\
+        <colgroup><col><col></colgroup>\n\
+        <tr><td colspan=\"2\" style=\"background-color:#ff0000;font-weight:bold;\">x &amp; y</td></tr>\n\
+        <tr><td style=\"text-align:right;\">1</td><td>a<br>b</td></tr>\n\
+        </table>\n"
+    );
+}
+
+#[cfg(feature = "locale_en_US")]
+#[test]
+fn test_infer_types() {
+    let mut sh = Sheet::new("1");
+    let texts = [
+        "12",
+        " -1,234.5e2 ",
+        "12,34",
+        "15 %",
+        "true",
+        "2024-01-31",
+        "1/31/24",
+        "1/31/2024 13:45",
+        "8:30",
+        "007a",
+        "1.2.3",
+        "",
+        "007",
+        "0.5",
+    ];
+    for (col, text) in texts.iter().enumerate() {
+        sh.set_value(0, col as u32, *text);
+    }
+    sh.set_formula(1, 0, "of:=\"1\"");
+    sh.set_value(1, 0, "1");
+
+    assert_eq!(sh.infer_types(locale!("en_US")), 9);
+    assert_eq!(sh.value(0, 0), &Value::Number(12.0));
+    assert_eq!(sh.value(0, 1), &Value::Number(-123450.0));
+    assert_eq!(sh.value(0, 2), &Value::from("12,34"));
+    assert_eq!(sh.value(0, 3), &Value::Percentage(0.15));
+    assert_eq!(sh.value(0, 4), &Value::Boolean(true));
+    let date = NaiveDate::from_ymd_opt(2024, 1, 31).expect("date");
+    assert_eq!(sh.value(0, 5), &Value::Date(date));
+    assert_eq!(sh.value(0, 6), &Value::Date(date));
+    assert_eq!(
+        sh.value(0, 7),
+        &Value::DateTime(date.and_hms_opt(13, 45, 0).expect("time"))
+    );
+    assert_eq!(
+        sh.value(0, 8),
+        &Value::Time(NaiveTime::from_hms_opt(8, 30, 0).expect("time"))
+    );
+    assert_eq!(sh.value(0, 9), &Value::from("007a"));
+    assert_eq!(sh.value(0, 10), &Value::from("1.2.3"));
+    assert_eq!(sh.value(0, 12), &Value::from("007"));
+    assert_eq!(sh.value(0, 13), &Value::Number(0.5));
+    assert_eq!(sh.value(1, 0), &Value::from("1"));
+
+    let mut sh = Sheet::new("1");
+    sh.set_value(0, 0, "12,34");
+    sh.set_value(0, 1, "1/31/2024");
+    sh.set_value(0, 2, "falsch");
+    sh.set_value(0, 3, "1.234.567");
+    assert_eq!(sh.infer_types(locale!("de_AT")), 3);
+    assert_eq!(sh.value(0, 0), &Value::Number(12.34));
+    assert_eq!(sh.value(0, 1), &Value::from("1/31/2024"));
+    assert_eq!(sh.value(0, 2), &Value::Boolean(false));
+    assert_eq!(sh.value(0, 3), &Value::Number(1234567.0));
+}
@@ -5,14 +5,18 @@ use lib_test::*;
 use spreadsheet_ods::condition::Condition;
 use spreadsheet_ods::style::stylemap::StyleMap;
 use spreadsheet_ods::style::units::{
-    Border, CellAlignVertical, FontFamilyGeneric, FontPitch, FontWeight, Length, PageBreak,
-    ParaAlignVertical, RotationAlign, TextAlignSource, TextKeep, TextPosition, TextRelief,
-    TextTransform, WrapOption, WritingMode,
+    Border, BorderLine, CellAlignVertical, FontFamilyGeneric, FontPitch, FontStyle, FontWeight,
+    Length, PageBreak, ParaAlignVertical, RotationAlign, TextAlignSource, TextKeep, TextPosition,
+    TextRelief, TextTransform, WrapOption, WritingMode,
 };
 use spreadsheet_ods::style::{
-    CellStyle, ColStyle, FontFaceDecl, PageStyle, RowStyle, StyleOrigin, StyleUse, TableStyle,
+    CellStyle, ColStyle, FontFaceDecl, PageStyle, ParagraphStyle, RowStyle, StyleOrigin, StyleUse,
+    TableStyle, TextStyle, Theme, ThemeColor,
+};
+use spreadsheet_ods::{
+    cm, deg, mm, pt, read_fods_buf, read_ods_buf, write_fods_buf, CellRange, CellRef, OdsError,
+    Sheet, WorkBook,
 };
-use spreadsheet_ods::{cm, deg, mm, pt, CellRef, OdsError, Sheet, WorkBook};
 
 #[test]
 fn test_attr1() {
@@ -270,3 +274,293 @@ fn test_stylemap() -> Result<(), OdsError> {
 
     Ok(())
 }
+
+#[test]
+fn test_stylemap_families() -> Result<(), OdsError> {
+    let mut wb = WorkBook::new_empty();
+
+    let mut ro1 = RowStyle::new("ro1");
+    ro1.push_stylemap(StyleMap::new(Condition::content_eq(1), "ro2".into(), None));
+    let ro1 = wb.add_rowstyle(ro1);
+    wb.add_rowstyle(RowStyle::new("ro2"));
+
+    let mut ta1 = TableStyle::new("ta1");
+    ta1.push_stylemap(StyleMap::new(Condition::content_eq(1), "ta1".into(), None));
+    let ta1 = wb.add_tablestyle(ta1);
+
+    let mut co1 = ColStyle::new("co1");
+    co1.push_stylemap(StyleMap::new(Condition::content_eq(1), "co1".into(), None));
+    let co1 = wb.add_colstyle(co1);
+
+    let mut p1 = ParagraphStyle::new("p1");
+    p1.push_stylemap(StyleMap::new(
+        Condition::outline_level_eq(1),
+        "p2".into(),
+        None,
+    ));
+    let p1 = wb.add_paragraphstyle(p1);
+
+    let mut t1 = TextStyle::new("t1");
+    t1.push_stylemap(StyleMap::new(
+        Condition::list_level_eq(2),
+        "t2".into(),
+        None,
+    ));
+    let t1 = wb.add_textstyle(t1);
+
+    let mut sh = Sheet::new("s0");
+    sh.set_style(&ta1);
+    sh.set_rowstyle(0, &ro1);
+    sh.set_colstyle(0, &co1);
+    sh.set_value(0, 0, 1);
+    wb.push_sheet(sh);
+
+    let buf = test_write_odsbuf(&mut wb)?;
+    let wb = read_ods_buf(&buf)?;
+
+    let sm = wb
+        .rowstyle(&ro1)
+        .and_then(|v| v.stylemaps())
+        .expect("stylemap");
+    assert_eq!(sm[0].condition().to_string(), "cell-content()=1");
+    assert_eq!(sm[0].applied_style().as_str(), "ro2");
+    assert!(wb.tablestyle(&ta1).and_then(|v| v.stylemaps()).is_some());
+    assert!(wb.colstyle(&co1).and_then(|v| v.stylemaps()).is_some());
+    let sm = wb
+        .paragraphstyle(&p1)
+        .and_then(|v| v.stylemaps())
+        .expect("stylemap");
+    assert_eq!(sm[0].condition().to_string(), "outline-level()=1");
+    let sm = wb
+        .textstyle(&t1)
+        .and_then(|v| v.stylemaps())
+        .expect("stylemap");
+    assert_eq!(sm[0].condition().to_string(), "list-level()=2");
+
+    Ok(())
+}
+
+#[test]
+fn test_theme() -> Result<(), OdsError> {
+    let mut book = WorkBook::new_empty();
+    book.push_sheet(Sheet::new("1"));
+
+    let mut theme = Theme::new_default();
+    theme.set_name("Corporate");
+    theme.set_colors_name("Corporate Colors");
+    theme.set_color(ThemeColor::Accent1, Rgb::new(0, 84, 159));
+    book.set_theme(theme);
+
+    let mut st = CellStyle::new("themed", &"".into());
+    let theme = book.theme().expect("theme");
+    st.set_color_theme(theme, ThemeColor::Accent1);
+    st.set_background_color_theme(theme, ThemeColor::Light2);
+    book.add_cellstyle(st);
+
+    let buf = test_write_odsbuf(&mut book)?;
+    let book = read_ods_buf(&buf)?;
+
+    let st = book.cellstyle("themed").expect("style");
+    assert_eq!(st.color_theme(), Some(ThemeColor::Accent1));
+    assert_eq!(st.color()?, Some(Rgb::new(0, 84, 159)));
+    assert_eq!(st.background_color_theme(), Some(ThemeColor::Light2));
+    assert_eq!(st.background_color()?, Some(Rgb::new(255, 255, 255)));
+
+    let theme = book.theme().expect("theme");
+    assert_eq!(theme.name(), "Corporate");
+    assert_eq!(theme.colors_name(), "Corporate Colors");
+    assert_eq!(theme.colors().count(), 12);
+    assert_eq!(theme.color(ThemeColor::Accent1), Some(Rgb::new(0, 84, 159)));
+    assert_eq!(
+        theme.color_str(ThemeColor::FollowedHyperlink),
+        Some("#551a8b")
+    );
+
+    let mut book = book;
+    let buf = write_fods_buf(&mut book, Vec::new())?;
+    let xml = String::from_utf8(buf.clone()).expect("utf8");
+    assert!(xml.contains(
+        r#"<loext:char-complex-color loext:theme-type="accent1" loext:color-type="theme"/>"#
+    ));
+    let book = read_fods_buf(&buf)?;
+    assert_eq!(
+        book.theme().and_then(|v| v.color(ThemeColor::Accent1)),
+        Some(Rgb::new(0, 84, 159))
+    );
+    assert_eq!(
+        book.cellstyle("themed").and_then(|v| v.color_theme()),
+        Some(ThemeColor::Accent1)
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_cellstyle_getters() -> Result<(), OdsError> {
+    let mut book = WorkBook::new_empty();
+    book.push_sheet(Sheet::new("1"));
+
+    let mut st = CellStyle::new_empty();
+    st.set_background_color(Rgb::new(255, 204, 0));
+    st.set_color(Rgb::new(0, 0, 128));
+    st.set_border_top(pt!(0.5), Border::Solid, Rgb::new(255, 0, 0));
+    st.set_padding(mm!(1.5));
+    st.set_font_size(pt!(14));
+    st.set_font_bold();
+    st.set_font_italic();
+    st.set_font_name("Liberation Sans");
+    st.set_wrap_option(WrapOption::Wrap);
+    st.set_vertical_align(CellAlignVertical::Middle);
+    st.set_rotation_angle(deg!(90));
+    st.set_shrink_to_fit(true);
+    let st_ref = book.add_cellstyle(st);
+
+    let buf = test_write_odsbuf(&mut book)?;
+    let book = read_ods_buf(&buf)?;
+
+    let st = book.cellstyle(&st_ref).expect("style");
+    assert_eq!(st.background_color()?, Some(Rgb::new(255, 204, 0)));
+    assert_eq!(st.color()?, Some(Rgb::new(0, 0, 128)));
+    assert_eq!(
+        st.border_top()?,
+        Some((pt!(0.5), Border::Solid, Rgb::new(255, 0, 0)))
+    );
+    assert_eq!(st.border_bottom()?, None);
+    assert_eq!(st.padding()?, Some(mm!(1.5)));
+    assert_eq!(st.font_size()?, Some(pt!(14)));
+    assert_eq!(st.font_weight()?, Some(FontWeight::Bold));
+    assert_eq!(st.font_style()?, Some(FontStyle::Italic));
+    assert_eq!(st.font_name(), Some("Liberation Sans"));
+    assert_eq!(st.wrap_option()?, Some(WrapOption::Wrap));
+    assert_eq!(st.vertical_align()?, Some(CellAlignVertical::Middle));
+    assert_eq!(st.rotation_angle()?, Some(deg!(90)));
+    assert!(st.shrink_to_fit()?);
+
+    Ok(())
+}
+
+#[test]
+fn test_range_border() -> Result<(), OdsError> {
+    let mut book = WorkBook::new_empty();
+    book.push_sheet(Sheet::new("1"));
+
+    let mut st = CellStyle::new_empty();
+    st.set_box_border(BorderLine::thin(Rgb::new(0, 0, 0)));
+    assert_eq!(
+        st.border()?,
+        Some((pt!(0.75), Border::Solid, Rgb::new(0, 0, 0)))
+    );
+
+    let mut st = CellStyle::new_empty();
+    st.set_font_bold();
+    let bold = book.add_cellstyle(st);
+    book.sheet_mut(0).set_styled_value(1, 1, "bold", &bold);
+
+    let line = BorderLine::new(pt!(1), Border::Double, Rgb::new(0, 0, 255));
+    book.set_range_border(0, &CellRange::local(1, 1, 4, 4), line);
+
+    let sheet = book.sheet(0);
+    assert!(sheet.cellstyle(2, 2).is_none());
+    assert!(sheet.cellstyle(0, 0).is_none());
+    // both cells on the left edge share a style
+    assert_eq!(sheet.cellstyle(2, 1), sheet.cellstyle(3, 1));
+    assert_ne!(sheet.cellstyle(1, 1), sheet.cellstyle(1, 2));
+
+    let corner = book
+        .cellstyle(sheet.cellstyle(1, 1).expect("style"))
+        .expect("style");
+    assert_eq!(corner.font_weight()?, Some(FontWeight::Bold));
+    assert_eq!(corner.border_top()?, Some(line.into()));
+    assert_eq!(corner.border_left()?, Some(line.into()));
+    assert_eq!(corner.border_right()?, None);
+
+    let edge = book
+        .cellstyle(sheet.cellstyle(4, 2).expect("style"))
+        .expect("style");
+    assert_eq!(edge.border_bottom()?, Some(line.into()));
+    assert_eq!(edge.border_top()?, None);
+
+    test_write_odsbuf(&mut book)?;
+    Ok(())
+}
+
+#[test]
+fn test_writing_mode() -> Result<(), OdsError> {
+    let mut st = TableStyle::new_empty();
+    assert_eq!(st.writing_mode()?, None);
+    st.set_writing_mode(WritingMode::RlTb);
+    assert_eq!(st.writing_mode()?, Some(WritingMode::RlTb));
+    st.tablestyle_mut()
+        .set_attr("style:writing-mode", "xx".to_string());
+    assert!(st.writing_mode().is_err());
+
+    let mut book = WorkBook::new_empty();
+    let mut st = CellStyle::new_empty();
+    st.set_writing_mode(WritingMode::LrTb);
+    st.set_paragraph_writing_mode(WritingMode::RlTb);
+    assert_eq!(st.cellstyle().attr("style:writing-mode"), Some("lr-tb"));
+    assert_eq!(
+        st.paragraphstyle().attr("style:writing-mode"),
+        Some("rl-tb")
+    );
+    let st = book.add_cellstyle(st);
+
+    let mut sheet = Sheet::new("1");
+    sheet.set_rtl(true);
+    sheet.set_styled_value(0, 0, "שלום", &st);
+    book.push_sheet(sheet);
+
+    let book = read_ods_buf(&test_write_odsbuf(&mut book)?)?;
+    let sheet = book.sheet(0);
+    assert!(sheet.rtl());
+    let ts = book
+        .tablestyle(sheet.style().expect("style"))
+        .expect("style");
+    assert_eq!(ts.writing_mode()?, Some(WritingMode::RlTb));
+    let cs = book
+        .cellstyle(sheet.cellstyle(0, 0).expect("style"))
+        .expect("style");
+    assert_eq!(cs.writing_mode()?, Some(WritingMode::LrTb));
+    assert_eq!(cs.paragraph_writing_mode()?, Some(WritingMode::RlTb));
+
+    Ok(())
+}
+
+#[test]
+fn test_font_asian_complex() -> Result<(), OdsError> {
+    let mut book = WorkBook::new_empty();
+    book.add_font(FontFaceDecl::new("Noto Sans CJK JP"));
+    book.add_font(FontFaceDecl::new("Noto Naskh Arabic"));
+
+    let mut st = CellStyle::new_empty();
+    assert_eq!(st.font_name_asian(), None);
+    assert_eq!(st.font_size_complex()?, None);
+    st.set_font_name_asian("Noto Sans CJK JP");
+    st.set_font_attr_asian(pt!(12), true, false);
+    st.set_font_name_complex("Noto Naskh Arabic");
+    st.set_font_size_complex(pt!(11));
+    st.set_font_italic_complex();
+    let st_ref = book.add_cellstyle(st);
+
+    let mut sheet = Sheet::new("1");
+    sheet.set_styled_value(0, 0, "売上高", &st_ref);
+    sheet.set_styled_value(0, 1, "مرحبا", &st_ref);
+    book.push_sheet(sheet);
+
+    let book = read_ods_buf(&test_write_odsbuf(&mut book)?)?;
+    let st = book.cellstyle(&st_ref).expect("style");
+    assert_eq!(st.font_name_asian(), Some("Noto Sans CJK JP"));
+    assert_eq!(st.font_size_asian()?, Some(pt!(12)));
+    assert_eq!(st.font_weight_asian()?, Some(FontWeight::Bold));
+    assert_eq!(st.font_style_asian()?, None);
+    assert_eq!(st.font_name_complex(), Some("Noto Naskh Arabic"));
+    assert_eq!(st.font_size_complex()?, Some(pt!(11)));
+    assert_eq!(st.font_weight_complex()?, None);
+    assert_eq!(st.font_style_complex()?, Some(FontStyle::Italic));
+
+    let mut ts = TextStyle::new("t1");
+    ts.set_font_weight_complex(FontWeight::W700);
+    assert_eq!(ts.font_weight_complex()?, Some(FontWeight::W700));
+
+    Ok(())
+}
@@ -0,0 +1,96 @@
+#![cfg(feature = "validate_odf")]
+
+use spreadsheet_ods::draw::Annotation;
+use spreadsheet_ods::style::CellStyle;
+use spreadsheet_ods::{
+    read_ods, validate_fods, validate_ods, write_fods_buf, write_ods_buf, OdsError,
+    OdsWriteOptions, Sheet, ValueFormatRef, WorkBook,
+};
+use std::io::Cursor;
+
+#[test]
+fn test_validate_written() -> Result<(), OdsError> {
+    for path in [
+        "tests/test_read_libreoffice.ods",
+        "tests/test_read_google.ods",
+        "tests/test_read_office365.ods",
+        "tests/test_pagelayout.ods",
+        "tests/test_draw.ods",
+        "tests/test_config.ods",
+    ] {
+        let mut wb = read_ods(path)?;
+        let mut buf = Cursor::new(Vec::new());
+        OdsWriteOptions::default().write_ods_validated(&mut wb, &mut buf)?;
+        assert!(!buf.into_inner().is_empty());
+
+        let buf = write_fods_buf(&mut wb, Vec::new())?;
+        validate_fods(&buf)?;
+    }
+
+    let mut wb = WorkBook::new_empty();
+    let style = wb.add_cellstyle(CellStyle::new("bold", &ValueFormatRef::from("")));
+    let mut sh = Sheet::new("1");
+    sh.set_value(0, 0, 1.5);
+    sh.set_value(0, 1, true);
+    sh.set_value(0, 2, "text");
+    sh.set_styled(1, 0, 42, &style);
+    sh.set_formula(1, 1, "of:=[.A1]*2");
+    sh.set_col_span(2, 0, 2);
+    sh.set_annotation(3, 0, Annotation::new("note"));
+    sh.set_col_width(5, spreadsheet_ods::cm!(3));
+    wb.push_sheet(sh);
+
+    let buf = write_ods_buf(&mut wb, Vec::new())?;
+    validate_ods(Cursor::new(buf))?;
+
+    Ok(())
+}
+
+#[test]
+fn test_validate_errors() {
+    fn fods(body: &str) -> String {
+        format!(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<office:document xmlns:office="urn:oasis:names:tc:opendocument:xmlns:office:1.0" xmlns:table="urn:oasis:names:tc:opendocument:xmlns:table:1.0" office:version="1.3">
+<office:body><office:spreadsheet><table:table table:name="1">
+{}
+</table:table></office:spreadsheet></office:body></office:document>"#,
+            body
+        )
+    }
+
+    let ok = fods(r#"<table:table-column/><table:table-row><table:table-cell/></table:table-row>"#);
+    assert!(validate_fods(ok.as_bytes()).is_ok());
+
+    for (body, line) in [
+        (r#"<table:table-cell/>"#, 4),
+        (
+            r#"<table:table-row>
+<table:table-cell office:value-type="float"/></table:table-row>"#,
+            5,
+        ),
+        (
+            r#"<table:table-row>
+<table:table-cell office:value-type="float" office:value="inf"/></table:table-row>"#,
+            5,
+        ),
+        (r#"<table:table-row table:number-rows-repeated="0"/>"#, 4),
+        (
+            r#"<table:table-row/>
+<table:table-column/>"#,
+            5,
+        ),
+        (r#"<text:p/>"#, 4),
+        (r#"<table:table-row>"#, 5),
+    ] {
+        let err = validate_fods(fods(body).as_bytes()).expect_err(body);
+        assert!(
+            err.to_string().contains(&format!(" at fods:{}:", line)),
+            "{} {}",
+            body,
+            err
+        );
+    }
+
+    assert!(validate_fods(b"<office:document/>").is_err());
+}
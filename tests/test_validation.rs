@@ -1,9 +1,9 @@
 mod lib_test;
 
 use lib_test::*;
-use spreadsheet_ods::condition::Condition;
+use spreadsheet_ods::condition::{CompareOp, Condition, ValidationCondition, ValueCheck};
 use spreadsheet_ods::text::TextP;
-use spreadsheet_ods::validation::{Validation, ValidationError, ValidationHelp};
+use spreadsheet_ods::validation::{Validation, ValidationDisplay, ValidationError, ValidationHelp};
 use spreadsheet_ods::{CellRange, OdsError, Sheet, WorkBook};
 
 #[test]
@@ -137,3 +137,61 @@ fn test_validation1() -> Result<(), OdsError> {
 
     Ok(())
 }
+
+#[test]
+fn test_validation_list() -> Result<(), OdsError> {
+    let mut book = WorkBook::new_empty();
+
+    let mut sheet = Sheet::new("One");
+    sheet.set_value(0, 3, "red");
+    sheet.set_value(1, 3, "green");
+    sheet.set_value(2, 3, "blue");
+
+    let valid = Validation::list_of(&["red", "say \"green\""]);
+    assert_eq!(
+        valid.condition().to_string(),
+        r#"cell-content-is-in-list("red";"say ""green""")"#
+    );
+    let valid = book.add_validation(valid);
+    sheet.set_validation(0, 0, &valid);
+
+    let mut valid = Validation::list_from_range(&CellRange::remote("One", 0, 3, 2, 3));
+    assert_eq!(
+        valid.condition().to_string(),
+        "cell-content-is-in-list([$One.$D$1:.$D$3])"
+    );
+    assert!(valid.dropdown());
+    valid.set_dropdown(false);
+    assert_eq!(valid.display(), ValidationDisplay::NoDisplay);
+    valid.set_dropdown(true);
+    assert_eq!(valid.display(), ValidationDisplay::Unsorted);
+    let valid = book.add_validation(valid);
+    sheet.set_validation(0, 1, &valid);
+
+    book.push_sheet(sheet);
+
+    test_write_ods(&mut book, "test_out/test_validation_3.ods")?;
+
+    Ok(())
+}
+
+#[test]
+fn test_validation_typed() -> Result<(), OdsError> {
+    let mut valid = Validation::new();
+    valid.set_condition(ValidationCondition::WholeNumber(ValueCheck::Between(
+        "1".into(),
+        "10".into(),
+    )));
+    assert_eq!(
+        valid.condition().to_string(),
+        "cell-content-is-whole-number() and cell-content-is-between(1, 10)"
+    );
+
+    valid.set_condition(Condition::content_is_date_and(Condition::content_ge(45000)));
+    assert_eq!(
+        valid.typed_condition()?,
+        ValidationCondition::Date(ValueCheck::Compare(CompareOp::Ge, "45000".into()))
+    );
+
+    Ok(())
+}
@@ -1,4 +1,24 @@
-use spreadsheet_ods::{CellStyleRef, Sheet, ValueType, WorkBook};
+use chrono::NaiveDate;
+use icu_locid::locale;
+use spreadsheet_ods::basic::BasicModule;
+use spreadsheet_ods::color::Rgb;
+use spreadsheet_ods::condition::{Condition, ValueCondition};
+use spreadsheet_ods::dde::{DdeConversionMode, DdeLink};
+use spreadsheet_ods::draw::DrawFrame;
+use spreadsheet_ods::format::{ValueFormatTrait, ValueStyleMap};
+use spreadsheet_ods::style::{
+    CellStyle, ColStyle, FontFaceDecl, MasterPage, PageStyle, RowStyle, StyleFamily, StyleUse,
+    TextStyle,
+};
+use spreadsheet_ods::text::TextTag;
+use spreadsheet_ods::validation::Validation;
+use spreadsheet_ods::{
+    cm, read_fods_buf, read_ods, read_ods_buf, workbook::CalcSettings, workbook::EventListener,
+    workbook::RenameStrategy, workbook::ScriptEvent, workbook::ValidationIssue, write_fods_buf,
+    write_ods_buf, write_ods_parts, CellRange, CellRef, CellStyleRef, OdsError, Sheet,
+    ValueFormatNumber, ValueType, WorkBook,
+};
+use std::io::{Cursor, Read};
 
 #[test]
 fn test_workbook() {
@@ -29,3 +49,663 @@ fn test_def_style() {
     );
     assert!(wb.def_style(ValueType::Text).is_none());
 }
+
+#[test]
+fn test_from_range() -> Result<(), OdsError> {
+    let mut wb = WorkBook::new(locale!("en_US"));
+    let mut sh = Sheet::new("Report");
+    for r in 0..10 {
+        for c in 0..10 {
+            sh.set_value(r, c, r * 100 + c);
+        }
+    }
+    sh.set_col_width(3, cm!(4));
+    sh.set_row_height(2, cm!(2));
+
+    let mut st = CellStyle::new("used", &"".into());
+    st.set_font_bold();
+    let used = wb.add_cellstyle(st);
+    sh.set_cellstyle(3, 4, &used);
+    let mut st = CellStyle::new("unused", &"".into());
+    st.set_font_italic();
+    let unused = wb.add_cellstyle(st);
+    sh.set_cellstyle(9, 9, &unused);
+    wb.push_sheet(sh);
+
+    let mut part = WorkBook::from_range(&wb, 0, &CellRange::local(2, 3, 4, 5));
+    assert_eq!(part.num_sheets(), 1);
+    let sh = part.sheet(0);
+    assert_eq!(sh.value(0, 0).as_u32_or(0), 203);
+    assert_eq!(sh.value(2, 2).as_u32_or(0), 405);
+    assert_eq!(sh.used_grid_size(), (3, 3));
+    assert_eq!(sh.col_width(0), cm!(4));
+    assert_eq!(sh.row_height(0), cm!(2));
+    assert!(part.def_style(ValueType::Number).is_some());
+    assert!(part.cellstyle(&used).is_some());
+    assert!(part.cellstyle(&unused).is_none());
+
+    let parts = write_ods_parts(&mut part)?;
+    let names: Vec<_> = parts.iter().map(|(n, _)| n.as_str()).collect();
+    assert!(names.contains(&"content.xml"));
+    assert!(names.contains(&"styles.xml"));
+    assert!(!names.contains(&"mimetype"));
+    assert!(!names.contains(&"META-INF/manifest.xml"));
+
+    Ok(())
+}
+
+#[test]
+fn test_optimize_styles() {
+    let mut wb = WorkBook::new_empty();
+    let mut sh = Sheet::new("1");
+
+    for i in 0..10 {
+        let mut st = CellStyle::new_empty();
+        if i % 2 == 0 {
+            st.set_font_bold();
+            st.set_color(Rgb::new(255, 0, 0));
+        } else {
+            st.set_color(Rgb::new(255, 0, 0));
+            st.set_font_bold();
+        }
+        let sref = wb.add_cellstyle(st);
+        sh.set_styled_value(i, 0, i, &sref);
+        if i == 5 {
+            wb.add_def_style(ValueType::Number, sref);
+        }
+    }
+    let mut st = CellStyle::new_empty();
+    st.set_font_italic();
+    let italic = wb.add_cellstyle(st);
+    sh.set_styled_value(10, 0, 10, &italic);
+
+    let mut st = CellStyle::new_empty();
+    st.set_font_bold();
+    st.set_color(Rgb::new(255, 0, 0));
+    st.set_styleuse(StyleUse::Named);
+    st.set_name("named");
+    wb.add_cellstyle(st);
+
+    for i in 0..3 {
+        let mut st = RowStyle::new_empty();
+        st.set_row_height(cm!(1));
+        let rref = wb.add_rowstyle(st);
+        sh.set_rowstyle(i, &rref);
+    }
+    wb.push_sheet(sh);
+
+    assert_eq!(wb.optimize_styles(), 11);
+    assert_eq!(wb.iter_cellstyles().count(), 3);
+    assert_eq!(wb.iter_rowstyles().count(), 1);
+
+    let sh = wb.sheet(0);
+    let first = sh.cellstyle(0, 0).expect("style").clone();
+    for i in 1..10 {
+        assert_eq!(sh.cellstyle(i, 0), Some(&first));
+    }
+    assert_eq!(sh.cellstyle(10, 0), Some(&italic));
+    assert!(wb.cellstyle(&first).is_some());
+    assert_eq!(wb.def_style(ValueType::Number), Some(&first));
+    assert_eq!(sh.rowstyle(0), sh.rowstyle(2));
+    assert!(wb.rowstyle(sh.rowstyle(1).expect("rowstyle")).is_some());
+}
+
+#[test]
+fn test_remove_unused_styles() {
+    let mut wb = WorkBook::new_empty();
+    let mut sh = Sheet::new("1");
+
+    let mut st = CellStyle::new_empty();
+    st.set_font_bold();
+    let bold = wb.add_cellstyle(st);
+    sh.set_styled_value(0, 0, 1, &bold);
+    let mut st = CellStyle::new_empty();
+    st.set_font_italic();
+    wb.add_cellstyle(st);
+    let mut st = CellStyle::new_empty();
+    st.set_name("named");
+    st.set_styleuse(StyleUse::Named);
+    wb.add_cellstyle(st);
+
+    let mut st = TextStyle::new_empty();
+    st.set_font_bold();
+    let span = wb.add_textstyle(st);
+    sh.set_value(
+        1,
+        0,
+        TextTag::new("text:p")
+            .tag(TextTag::new("text:span").attr("text:style-name", span.as_str())),
+    );
+    wb.add_textstyle(TextStyle::new_empty());
+    wb.add_colstyle(ColStyle::new_empty());
+    wb.push_sheet(sh);
+
+    let styles = wb.styles();
+    let info = styles
+        .iter()
+        .find(|v| v.family == StyleFamily::Cell && v.name == bold.as_str())
+        .expect("style");
+    assert_eq!(info.refs, 1);
+    assert_eq!(info.styleuse, StyleUse::Automatic);
+    assert_eq!(
+        styles
+            .iter()
+            .filter(|v| v.family == StyleFamily::Text && v.refs == 1)
+            .count(),
+        1
+    );
+
+    assert_eq!(wb.remove_unused_styles(), 3);
+    assert!(wb.cellstyle(&bold).is_some());
+    assert!(wb.cellstyle("named").is_some());
+    assert!(wb.textstyle(&span).is_some());
+    assert_eq!(wb.iter_textstyles().count(), 1);
+    assert_eq!(wb.iter_colstyles().count(), 0);
+    assert_eq!(wb.remove_unused_styles(), 0);
+}
+
+#[test]
+fn test_purge_unreferenced() -> Result<(), OdsError> {
+    let mut wb = WorkBook::new_empty();
+    wb.push_sheet(Sheet::new("1"));
+
+    let mut neg = ValueFormatNumber::new_empty();
+    neg.part_text("-").build();
+    neg.part_number().decimal_places(2).build();
+    let neg = wb.add_number_format(neg);
+    let mut num = ValueFormatNumber::new_empty();
+    num.part_number().decimal_places(2).build();
+    num.push_stylemap(ValueStyleMap::new(
+        ValueCondition::value_lt(0),
+        neg.as_str(),
+    ));
+    let num = wb.add_number_format(num);
+    let mut unused = ValueFormatNumber::new_empty();
+    unused.part_number().decimal_places(4).build();
+    let unused = wb.add_number_format(unused);
+
+    let mut cs = CellStyle::new_empty();
+    cs.set_value_format(&num);
+    cs.set_font_name("Used Sans");
+    wb.add_cellstyle(cs);
+    wb.add_font(FontFaceDecl::new("Used Sans"));
+    wb.embed_font("Unused Sans", [&[0u8, 1, 0, 0][..], b"font"].concat())?;
+
+    let used_page = wb.add_pagestyle(PageStyle::new("used"));
+    wb.add_pagestyle(PageStyle::new("unused"));
+    let mut mp = MasterPage::new("default");
+    mp.set_pagestyle(&used_page);
+    wb.add_masterpage(mp);
+
+    assert_eq!(wb.purge_unreferenced(), 3);
+    assert!(wb.number_format(num.as_str()).is_some());
+    assert!(wb.number_format(neg.as_str()).is_some());
+    assert!(wb.number_format(unused.as_str()).is_none());
+    assert!(wb.font("Used Sans").is_some());
+    assert!(wb.font("Unused Sans").is_none());
+    assert!(wb.manifest("Fonts/Unused Sans_1.ttf").is_none());
+    assert!(wb.pagestyle(used_page.as_str()).is_some());
+    assert!(wb.pagestyle("unused").is_none());
+    assert_eq!(wb.purge_unreferenced(), 0);
+
+    Ok(())
+}
+
+#[test]
+fn test_append_workbook() -> Result<(), OdsError> {
+    let mut wb = WorkBook::new_empty();
+    let mut st = CellStyle::new_empty();
+    st.set_font_bold();
+    let bold = wb.add_cellstyle(st);
+    let mut st = CellStyle::new_empty();
+    st.set_font_bold();
+    st.set_styleuse(StyleUse::Named);
+    st.set_name("Heading");
+    wb.add_cellstyle(st);
+    let mut st = CellStyle::new_empty();
+    st.set_font_bold();
+    st.set_color(Rgb::new(0, 0, 255));
+    st.set_styleuse(StyleUse::Named);
+    st.set_name("Accent");
+    wb.add_cellstyle(st);
+    let mut sh = Sheet::new("Data");
+    sh.set_styled_value(0, 0, 1, &bold);
+    wb.push_sheet(sh);
+
+    let mut other = WorkBook::new_empty();
+    let mut st = CellStyle::new_empty();
+    st.set_font_italic();
+    let italic = other.add_cellstyle(st);
+    assert_eq!(italic, bold);
+    let mut st = CellStyle::new_empty();
+    st.set_font_bold();
+    st.set_styleuse(StyleUse::Named);
+    st.set_name("Heading");
+    other.add_cellstyle(st);
+    let mut st = CellStyle::new_empty();
+    st.set_font_italic();
+    st.set_styleuse(StyleUse::Named);
+    st.set_name("Default");
+    other.add_cellstyle(st);
+    // same attributes in a different order.
+    let mut st = CellStyle::new_empty();
+    st.set_color(Rgb::new(0, 0, 255));
+    st.set_font_bold();
+    st.set_styleuse(StyleUse::Named);
+    st.set_name("Accent");
+    other.add_cellstyle(st);
+    let mut sh = Sheet::new("Data");
+    sh.set_styled_value(0, 0, 2, &italic);
+    sh.set_formula(0, 1, "of:=['Data'.A1]+[.A1]");
+    other.push_sheet(sh);
+    let sh = Sheet::new("Data_2");
+    other.push_sheet(sh);
+
+    wb.clone()
+        .append_workbook(other.clone(), RenameStrategy::Fail)
+        .expect_err("fail");
+
+    wb.append_workbook(other, RenameStrategy::Suffix)?;
+
+    assert_eq!(wb.num_sheets(), 3);
+    assert_eq!(wb.sheet(1).name(), "Data_3");
+    assert_eq!(wb.sheet(2).name(), "Data_2");
+    assert_eq!(
+        wb.sheet(1).formula(0, 1).expect("formula"),
+        "of:=[Data_3.A1]+[.A1]"
+    );
+
+    let italic = wb.sheet(1).cellstyle(0, 0).expect("style").clone();
+    assert_ne!(italic, bold);
+    assert!(!wb.cellstyle(&italic).expect("style").textstyle().is_empty());
+    assert_eq!(wb.sheet(0).cellstyle(0, 0), Some(&bold));
+    assert!(wb.cellstyle("Heading").is_some());
+    assert!(wb.cellstyle("Heading_2").is_none());
+    assert!(wb.cellstyle("Default").is_some());
+    assert!(wb.cellstyle("Accent").is_some());
+    assert!(wb.cellstyle("Accent_2").is_none());
+
+    Ok(())
+}
+
+#[test]
+fn test_clone_sheet() {
+    let mut wb = WorkBook::new_empty();
+    wb.push_sheet(Sheet::new("Summary"));
+
+    let mut sh = Sheet::new("Jan");
+    sh.set_value(0, 0, 1);
+    sh.set_formula(0, 1, "of:=[Jan.A1]+[.A1]+[Summary.A1]");
+    sh.set_col_width(0, cm!(4));
+    sh.set_col_span(1, 1, 2);
+    let mut valid = Validation::new();
+    valid.set_condition(Condition::content_text_length_lt(5));
+    valid.set_base_cell(CellRef::remote("Jan", 2, 0));
+    let valid = wb.add_validation(valid);
+    sh.set_validation(2, 0, &valid);
+    wb.push_sheet(sh);
+    wb.push_sheet(Sheet::new("Mar"));
+
+    let idx = wb.clone_sheet(1, "Feb");
+    assert_eq!(idx, 2);
+    assert_eq!(wb.num_sheets(), 4);
+    assert_eq!(wb.sheet(2).name(), "Feb");
+    assert_eq!(wb.sheet(3).name(), "Mar");
+
+    let feb = wb.sheet(2);
+    assert_eq!(feb.value(0, 0).as_i32_or(0), 1);
+    assert_eq!(
+        feb.formula(0, 1).expect("formula"),
+        "of:=[Feb.A1]+[.A1]+[Summary.A1]"
+    );
+    assert_eq!(feb.col_width(0), cm!(4));
+    assert_eq!(feb.col_span(1, 1), 2);
+    let feb_valid = feb.validation(2, 0).expect("validation");
+    assert_ne!(feb_valid, &valid);
+    assert_eq!(
+        wb.validation(feb_valid).expect("validation").base_cell(),
+        &CellRef::remote("Feb", 2, 0)
+    );
+
+    let jan = wb.sheet(1);
+    assert_eq!(
+        jan.formula(0, 1).expect("formula"),
+        "of:=[Jan.A1]+[.A1]+[Summary.A1]"
+    );
+    assert_eq!(jan.validation(2, 0), Some(&valid));
+}
+
+#[test]
+fn test_rename_move_sheet() -> Result<(), OdsError> {
+    let mut wb = WorkBook::new_empty();
+    let mut sh = Sheet::new("Data");
+    sh.set_value(0, 0, 1);
+    sh.add_print_range(CellRange::remote("Data", 0, 0, 9, 9))?;
+    wb.push_sheet(sh);
+    let mut sh = Sheet::new("Summary");
+    sh.set_formula(0, 0, "of:=SUM([Data.A1:.A10])+[$'Data'.A1]+[.B1]");
+    let mut valid = Validation::new();
+    valid.set_condition(Condition::content_is_in_cellrange(CellRange::remote(
+        "Data", 0, 0, 9, 0,
+    )));
+    valid.set_base_cell(CellRef::remote("Summary", 1, 0));
+    let valid = wb.add_validation(valid);
+    sh.set_validation(1, 0, &valid);
+    wb.push_sheet(sh);
+    wb.config_mut().active_table = "Data".to_string();
+
+    wb.rename_sheet(0, "Summary").expect_err("exists");
+    wb.rename_sheet(0, "Input Data")?;
+
+    assert_eq!(wb.sheet(0).name(), "Input Data");
+    assert_eq!(
+        wb.sheet(1).formula(0, 0).expect("formula"),
+        "of:=SUM(['Input Data'.A1:.A10])+[$'Input Data'.A1]+[.B1]"
+    );
+    assert_eq!(
+        wb.sheet(0).print_ranges().expect("print_ranges")[0],
+        CellRange::remote("Input Data", 0, 0, 9, 9)
+    );
+    assert_eq!(
+        wb.validation(&valid)
+            .expect("validation")
+            .condition()
+            .to_string(),
+        Condition::content_is_in_cellrange(CellRange::remote("Input Data", 0, 0, 9, 0)).to_string()
+    );
+    assert_eq!(wb.config().active_table, "Input Data");
+
+    wb.move_sheet(0, 1);
+    assert_eq!(wb.sheet(0).name(), "Summary");
+    assert_eq!(wb.sheet(1).name(), "Input Data");
+
+    Ok(())
+}
+
+#[test]
+fn test_rename_sheet_range_list() -> Result<(), OdsError> {
+    let fods = r#"<?xml version="1.0" encoding="UTF-8"?>
+<office:document xmlns:office="urn:oasis:names:tc:opendocument:xmlns:office:1.0" xmlns:table="urn:oasis:names:tc:opendocument:xmlns:table:1.0" office:version="1.3" office:mimetype="application/vnd.oasis.opendocument.spreadsheet">
+<office:body><office:spreadsheet>
+<table:table table:name="Data"><table:table-row><table:table-cell/></table:table-row></table:table>
+<table:named-expressions>
+<table:named-range table:name="cols" table:base-cell-address="$Data.$A$1" table:cell-range-address="$Data.$A$1:.$A$3 $Data.$C$1:.$C$3 $'Other Data'.$A$1"/>
+</table:named-expressions>
+</office:spreadsheet></office:body></office:document>"#;
+
+    let mut wb = read_fods_buf(fods.as_bytes())?;
+    wb.rename_sheet(0, "New Data")?;
+
+    let buf = String::from_utf8(write_fods_buf(&mut wb, Vec::new())?).expect("utf8");
+    assert!(buf.contains(
+        r#"table:cell-range-address="$&apos;New Data&apos;.$A$1:.$A$3 $&apos;New Data&apos;.$C$1:.$C$3 $&apos;Other Data&apos;.$A$1""#
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn test_validate() -> Result<(), OdsError> {
+    for path in [
+        "tests/test_read_libreoffice.ods",
+        "tests/test_read_google.ods",
+        "tests/test_read_office365.ods",
+        "tests/test_pagelayout.ods",
+    ] {
+        let wb = read_ods(path)?;
+        assert_eq!(wb.validate(), Vec::new(), "{}", path);
+    }
+
+    let mut wb = WorkBook::new_empty();
+    let mut sh = Sheet::new("a:b");
+    sh.set_cellstyle(0, 0, &CellStyleRef::from("missing"));
+    sh.set_validation(1, 0, &"valid0".into());
+    sh.set_row_span(2, 0, 2);
+    sh.set_col_span(2, 0, 2);
+    sh.set_col_span(3, 1, 2);
+    sh.set_cell_repeat(5, 16380, 10);
+    wb.push_sheet(sh);
+    wb.push_sheet(Sheet::new("a:b"));
+
+    let issues = wb.validate();
+    assert!(issues.contains(&ValidationIssue::DanglingStyleRef {
+        location: CellRef::remote("a:b", 0, 0).to_string(),
+        style: "missing".to_string()
+    }));
+    assert!(issues.contains(&ValidationIssue::UndefinedValidation {
+        cell: CellRef::remote("a:b", 1, 0),
+        validation: "valid0".to_string()
+    }));
+    assert!(issues.contains(&ValidationIssue::OverlappingMerge(
+        CellRange::remote("a:b", 2, 0, 3, 1),
+        CellRange::remote("a:b", 3, 1, 3, 2)
+    )));
+    assert!(issues.contains(&ValidationIssue::RepeatOutOfRange {
+        location: CellRef::remote("a:b", 5, 16380).to_string(),
+        repeat: 10
+    }));
+    assert!(issues.contains(&ValidationIssue::InvalidSheetName("a:b".to_string())));
+    assert!(issues.contains(&ValidationIssue::DuplicateSheetName("a:b".to_string())));
+    assert_eq!(issues.len(), 7);
+
+    Ok(())
+}
+
+#[test]
+fn test_calc_settings() -> Result<(), OdsError> {
+    let wb = read_ods("tests/test_read_libreoffice.ods")?;
+    let calc = wb.calc_settings();
+    assert!(calc.case_sensitive);
+    assert!(!calc.automatic_find_labels);
+    assert!(!calc.use_regular_expressions);
+    assert!(calc.use_wildcards);
+    assert_eq!(
+        calc.null_date,
+        NaiveDate::from_ymd_opt(1899, 12, 30).unwrap()
+    );
+
+    let wb = read_ods("tests/test_read_google.ods")?;
+    assert!(!wb.calc_settings().case_sensitive);
+    assert_eq!(wb.calc_settings().iteration_min_difference, 0.0001);
+
+    let mut wb = WorkBook::new_empty();
+    wb.push_sheet(Sheet::new("1"));
+    assert_eq!(wb.calc_settings(), &CalcSettings::default());
+    let calc = wb.calc_settings_mut();
+    calc.null_date = NaiveDate::from_ymd_opt(1904, 1, 1).unwrap();
+    calc.precision_as_shown = true;
+    calc.null_year = 1950;
+    calc.iteration = true;
+    calc.iteration_steps = 20;
+    let expect = calc.clone();
+
+    let wb = read_ods_buf(&write_ods_buf(&mut wb, Vec::new())?)?;
+    assert_eq!(wb.calc_settings(), &expect);
+
+    Ok(())
+}
+
+#[test]
+fn test_external_links() -> Result<(), OdsError> {
+    let fods = r#"<?xml version="1.0" encoding="UTF-8"?>
+<office:document xmlns:office="urn:oasis:names:tc:opendocument:xmlns:office:1.0" xmlns:table="urn:oasis:names:tc:opendocument:xmlns:table:1.0" xmlns:xlink="http://www.w3.org/1999/xlink" office:version="1.3" office:mimetype="application/vnd.oasis.opendocument.spreadsheet">
+<office:body><office:spreadsheet>
+<table:table table:name="1">
+<table:table-source xlink:type="simple" xlink:href="file:///old/linked.ods" table:table-name="Sheet1"/>
+<table:table-row><table:table-cell table:formula="of:=['file:///old/a.ods'#$Sheet1.A1]+['file:///old/b.ods'#$Sheet1.A1]"/><table:table-cell table:formula="of:=DDE(&quot;soffice&quot;;&quot;file:///old/c.ods&quot;;&quot;Sheet1.A1&quot;)"/></table:table-row>
+</table:table>
+<table:dde-links><table:dde-link>
+<office:dde-source office:name="prices" office:dde-application="soffice" office:dde-topic="file:///old/a.ods" office:dde-item="Sheet1.A1" office:conversion-mode="keep-text" office:automatic-update="true"/>
+<table:table><table:table-column/><table:table-row><table:table-cell/></table:table-row></table:table>
+</table:dde-link></table:dde-links>
+</office:spreadsheet></office:body></office:document>"#;
+
+    let mut wb = read_fods_buf(fods.as_bytes())?;
+    assert_eq!(wb.dde_links().len(), 1);
+    let link = &wb.dde_links()[0];
+    assert_eq!(link.name(), Some("prices"));
+    assert_eq!(link.application(), "soffice");
+    assert_eq!(link.topic(), "file:///old/a.ods");
+    assert_eq!(link.item(), "Sheet1.A1");
+    assert_eq!(link.conversion_mode(), DdeConversionMode::KeepText);
+    assert!(link.automatic_update());
+    assert!(link.cache().is_some());
+
+    assert_eq!(
+        wb.external_links(),
+        vec![
+            "file:///old/a.ods",
+            "file:///old/b.ods",
+            "file:///old/c.ods",
+            "file:///old/linked.ods"
+        ]
+    );
+
+    wb.rewrite_external_links(|v| {
+        v.strip_prefix("file:///old/")
+            .map(|v| format!("file:///new/{}", v))
+    });
+    wb.add_dde_link(DdeLink::new("soffice", "file:///other.ods", "Sheet1.B2"));
+
+    let wb = read_ods_buf(&write_ods_buf(&mut wb, Vec::new())?)?;
+    assert_eq!(
+        wb.external_links(),
+        vec![
+            "file:///new/a.ods",
+            "file:///new/b.ods",
+            "file:///new/c.ods",
+            "file:///new/linked.ods",
+            "file:///other.ods"
+        ]
+    );
+    assert_eq!(
+        wb.sheet(0).formula(0, 0),
+        Some(&"of:=['file:///new/a.ods'#$Sheet1.A1]+['file:///new/b.ods'#$Sheet1.A1]".to_string())
+    );
+    assert_eq!(
+        wb.sheet(0).formula(0, 1),
+        Some(&"of:=DDE(\"soffice\";\"file:///new/c.ods\";\"Sheet1.A1\")".to_string())
+    );
+    assert!(wb.dde_links()[0].cache().is_some());
+    assert_eq!(wb.dde_links()[1].item(), "Sheet1.B2");
+
+    Ok(())
+}
+
+#[test]
+fn test_basic() -> Result<(), OdsError> {
+    let mut wb = WorkBook::new_empty();
+    wb.push_sheet(Sheet::new("1"));
+    let source = "Sub Main\n  If 1 < 2 And \"a\" <> \"b\" Then MsgBox \"&]]>\"\nEnd Sub\n";
+    wb.add_script_module("Standard", BasicModule::new("Module1", source));
+    wb.add_script_module("Tools", BasicModule::new("Util", "REM util\n"));
+    wb.add_script_file("python/hello.py", "", b"def hello():\n    pass\n".to_vec());
+
+    let buf = write_ods_buf(&mut wb, Vec::new())?;
+    let mut xml = String::new();
+    zip::ZipArchive::new(Cursor::new(&buf))?
+        .by_name("Basic/Standard/Module1.xml")?
+        .read_to_string(&mut xml)?;
+    assert!(xml.contains("<![CDATA[Sub Main"), "{}", xml);
+    let mut wb = read_ods_buf(&buf)?;
+
+    assert_eq!(wb.script_libraries().len(), 2);
+    let module = wb.script_module("Standard", "Module1").expect("module");
+    assert_eq!(module.source(), source);
+    assert_eq!(module.language(), "StarBasic");
+    assert!(wb.script_module("Tools", "Util").is_some());
+    assert!(wb.manifest("Basic/Standard/Module1.xml").is_none());
+    assert_eq!(wb.script_files().count(), 1);
+
+    assert!(wb.remove_script_module("Standard", "Module1").is_some());
+    wb.remove_script_library("Tools");
+    let buf = write_ods_buf(&mut wb, Vec::new())?;
+    let wb = read_ods_buf(&buf)?;
+    assert_eq!(wb.script_libraries().len(), 1);
+    assert!(wb
+        .script_library("Standard")
+        .expect("lib")
+        .modules()
+        .is_empty());
+    assert!(wb.manifest("Basic/Tools/script-lb.xml").is_none());
+
+    Ok(())
+}
+
+#[test]
+fn test_embed_font() -> Result<(), OdsError> {
+    let mut wb = WorkBook::new_empty();
+    wb.push_sheet(Sheet::new("1"));
+    let regular = [&[0u8, 1, 0, 0][..], b"regular"].concat();
+    let bold = [&[0u8, 1, 0, 0][..], b"bold"].concat();
+    wb.embed_font("Corporate Sans", regular.clone())?;
+    wb.embed_font("Corporate Sans", bold)?;
+    assert!(wb
+        .embed_font("Corporate Sans", b"no font".to_vec())
+        .is_err());
+
+    let mut cs = CellStyle::new_empty();
+    cs.set_font_name("Corporate Sans");
+    wb.add_cellstyle(cs);
+
+    let buf = write_ods_buf(&mut wb, Vec::new())?;
+    let wb = read_ods_buf(&buf)?;
+
+    let font = wb.font("Corporate Sans").expect("font");
+    assert_eq!(font.src().len(), 2);
+    assert_eq!(font.src()[0].href(), "Fonts/Corporate Sans_1.ttf");
+    assert_eq!(font.src()[0].format(), Some("truetype"));
+    let file = wb
+        .manifest("Fonts/Corporate Sans_1.ttf")
+        .expect("font file");
+    assert_eq!(file.media_type, "application/x-font-ttf");
+    assert_eq!(file.buffer.as_ref(), Some(&regular));
+    assert!(wb.manifest("Fonts/Corporate Sans_2.ttf").is_some());
+
+    Ok(())
+}
+
+#[test]
+fn test_event_listeners() -> Result<(), OdsError> {
+    let mut wb = WorkBook::new_empty();
+    wb.add_script_module(
+        "Standard",
+        BasicModule::new("Module1", "Sub Main\nEnd Sub\n"),
+    );
+    wb.add_event_listener(EventListener::new_basic(
+        ScriptEvent::Load,
+        "Standard.Module1.Main",
+    ));
+
+    let mut sh = Sheet::new("1");
+    sh.add_event_listener(EventListener::new_basic(
+        ScriptEvent::ContentChanged,
+        "Standard.Module1.Main",
+    ));
+    let mut frame = DrawFrame::new();
+    frame.add_event_listener(EventListener::new_basic(
+        ScriptEvent::Other("dom:click".to_string()),
+        "Standard.Module1.Main",
+    ));
+    sh.add_draw_frame(0, 0, frame);
+    wb.push_sheet(sh);
+
+    let buf = write_ods_buf(&mut wb, Vec::new())?;
+    let wb = read_ods_buf(&buf)?;
+
+    let evt = wb.event_listener("dom:load").expect("event");
+    assert_eq!(evt.event(), ScriptEvent::Load);
+    assert_eq!(
+        evt.href(),
+        "vnd.sun.star.script:Standard.Module1.Main?language=Basic&location=document"
+    );
+    let evt = wb
+        .sheet(0)
+        .event_listener("office:content-changed")
+        .expect("event");
+    assert_eq!(evt.event(), ScriptEvent::ContentChanged);
+    let frames = wb.sheet(0).draw_frames(0, 0).expect("frame");
+    assert_eq!(frames[0].iter_event_listeners().count(), 1);
+
+    Ok(())
+}
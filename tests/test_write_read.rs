@@ -1,13 +1,25 @@
 pub mod lib_test;
 
+use icu_locid::locale;
 use lib_test::*;
+use spreadsheet_ods::draw::Annotation;
 use spreadsheet_ods::sheet::SplitMode;
+use spreadsheet_ods::style::units::{
+    RubyAlign, RubyPosition, TextCombine, TextEmphasize, TextEmphasizePosition,
+};
+use spreadsheet_ods::style::{CellStyle, RubyStyle, TextStyle};
+use spreadsheet_ods::text::{TextP, TextRuby, TextRubyBase, TextRubyText, TextSpan};
+use spreadsheet_ods::xmltree::XmlContent;
 use spreadsheet_ods::{
-    read_ods, read_ods_buf, write_ods_buf, write_ods_to, OdsError, Sheet, ValueType, WorkBook,
+    cm, percent, read_fods_buf, read_ods, read_ods_buf, write_fods_buf, write_ods_buf,
+    write_ods_to, InvalidCharMode, NonFiniteMode, OdsError, OdsOptions, OdsWriteOptions, Sheet,
+    Target, Value, ValueType, WorkBook, WriteObserver,
 };
 use std::fs::File;
 use std::io::{Cursor, Read, Write};
 use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
 use std::time::Instant;
 
 pub fn timingr<E, R>(name: &str, mut fun: impl FnMut() -> Result<R, E>) -> Result<R, E> {
@@ -197,3 +209,792 @@ fn read_text() -> Result<(), OdsError> {
 
     Ok(())
 }
+
+#[test]
+fn test_write_non_finite() -> Result<(), OdsError> {
+    let mut wb = WorkBook::new_empty();
+    let mut sh = Sheet::new("1");
+    sh.set_value(0, 0, f64::NAN);
+    sh.set_value(0, 1, f64::INFINITY);
+    sh.set_value(0, 2, 1.5);
+    assert!(sh.try_set_value(1, 0, f64::NAN).is_err());
+    assert!(sh.try_set_value(1, 1, percent!(f64::NEG_INFINITY)).is_err());
+    assert!(sh.cell_ref(1, 0).is_none());
+    sh.try_set_value(1, 2, 2.5)?;
+    assert_eq!(sh.value(1, 2), &Value::Number(2.5));
+    wb.push_sheet(sh);
+
+    assert!(write_ods_buf(&mut wb, Vec::new()).is_err());
+
+    let mut buf = Cursor::new(Vec::new());
+    OdsWriteOptions::default()
+        .non_finite(NonFiniteMode::Empty)
+        .write_ods(&mut wb, &mut buf)?;
+    let wi = read_ods_buf(&buf.into_inner())?;
+    assert_eq!(wi.sheet(0).value(0, 0), &Value::Empty);
+    assert_eq!(wi.sheet(0).value(0, 1), &Value::Empty);
+    assert_eq!(wi.sheet(0).value(0, 2), &Value::Number(1.5));
+
+    let mut buf = Cursor::new(Vec::new());
+    OdsWriteOptions::default()
+        .non_finite(NonFiniteMode::ErrorValue)
+        .write_ods(&mut wb, &mut buf)?;
+    let wi = read_ods_buf(&buf.into_inner())?;
+    assert_eq!(
+        wi.sheet(0).formula(0, 0).map(|v| v.as_str()),
+        Some("of:=NA()")
+    );
+    assert_eq!(wi.sheet(0).value(0, 0), &Value::new_error("#N/A"));
+    assert_eq!(wi.sheet(0).value(0, 2), &Value::Number(1.5));
+
+    Ok(())
+}
+
+#[test]
+fn test_formula_typed() -> Result<(), OdsError> {
+    let mut wb = WorkBook::new(locale!("en_US"));
+    let mut sh = Sheet::new("1");
+    sh.set_value(0, 0, 10);
+    sh.set_formula_typed(0, 1, "of:=[.A1]*1.2", ValueType::Currency);
+    sh.set_formula(0, 2, "of:=[.A1]*1.2");
+    assert_eq!(sh.formula_type(0, 1), Some(ValueType::Currency));
+    assert_eq!(sh.formula_type(0, 2), None);
+
+    // survives a copy via CellContent.
+    let cell = sh.cell(0, 1).expect("cell");
+    assert_eq!(cell.formula_type(), Some(ValueType::Currency));
+    sh.add_cell(1, 1, cell);
+    assert_eq!(sh.formula_type(1, 1), Some(ValueType::Currency));
+    let mut cell = sh.cell(1, 1).expect("cell");
+    cell.clear_formula();
+    sh.add_cell(1, 1, cell);
+    assert_eq!(sh.formula_type(1, 1), None);
+    wb.push_sheet(sh);
+
+    test_write_ods(&mut wb, "test_out/test_write_read_formula_typed.ods")?;
+
+    let wb = read_ods("test_out/test_write_read_formula_typed.ods")?;
+    let sh = wb.sheet(0);
+    assert_eq!(sh.cellstyle(0, 1), wb.def_style(ValueType::Currency));
+    assert_eq!(sh.cellstyle(0, 2), None);
+
+    Ok(())
+}
+
+#[test]
+fn test_fail_on_lossy() -> Result<(), OdsError> {
+    let mut wb = WorkBook::new_empty();
+    let mut sh = Sheet::new("1");
+    sh.set_value(5, 5, 1);
+    sh.set_row_height(2, cm!(1));
+    wb.push_sheet(sh);
+
+    // everything can be written.
+    let mut buf = Cursor::new(Vec::new());
+    OdsWriteOptions::default()
+        .fail_on_lossy(true)
+        .write_ods(&mut wb, &mut buf)?;
+
+    wb.sheet_mut(0).set_row_height(10, cm!(1));
+    wb.sheet_mut(0).add_row_group(4, 8);
+
+    let mut buf = Cursor::new(Vec::new());
+    let res = OdsWriteOptions::default()
+        .fail_on_lossy(true)
+        .write_ods(&mut wb, &mut buf);
+    match res {
+        Err(OdsError::Ods(msg)) => {
+            assert!(msg.contains("row 10"));
+            assert!(msg.contains("row group 4..8"));
+        }
+        _ => panic!("expected an error"),
+    }
+
+    // default is to write anyway.
+    let mut buf = Cursor::new(Vec::new());
+    OdsWriteOptions::default().write_ods(&mut wb, &mut buf)?;
+
+    Ok(())
+}
+
+#[test]
+fn test_compress_rows() -> Result<(), OdsError> {
+    let mut wb = WorkBook::new_empty();
+    let mut sh = Sheet::new("1");
+    for r in 0..500 {
+        sh.set_value(r, 0, "x");
+        sh.set_value(r, 2, 42);
+    }
+    sh.set_value(500, 0, "y");
+    sh.set_value(501, 0, "y");
+    sh.set_row_height(501, cm!(1));
+    wb.push_sheet(sh);
+
+    let mut buf = Vec::new();
+    OdsWriteOptions::default()
+        .compress_rows(true)
+        .write_fods(&mut wb, &mut buf)?;
+    let xml = String::from_utf8(buf).expect("utf8");
+    assert!(xml.contains("table:number-rows-repeated=\"500\""));
+    assert_eq!(xml.matches("<table:table-row").count(), 3);
+
+    // the workbook itself is not changed.
+    assert_eq!(wb.sheet(0).value(499, 2), &Value::Number(42.0));
+
+    let mut buf = Cursor::new(Vec::new());
+    OdsWriteOptions::default()
+        .compress_rows(true)
+        .write_ods(&mut wb, &mut buf)?;
+    let wb2 = read_ods_buf(&buf.into_inner())?;
+    let sh2 = wb2.sheet(0);
+    assert_eq!(sh2.value(0, 0), &Value::Text("x".to_string()));
+    assert_eq!(sh2.value(499, 2), &Value::Number(42.0));
+    assert_eq!(sh2.value(501, 0), &Value::Text("y".to_string()));
+    assert_eq!(sh2.row_height(501), cm!(1));
+    assert_eq!(sh2.used_grid_size(), (502, 3));
+
+    Ok(())
+}
+
+#[test]
+fn test_compress_cols() -> Result<(), OdsError> {
+    let mut wb = WorkBook::new_empty();
+    let mut sh = Sheet::new("1");
+    for c in 0..100 {
+        sh.set_value(0, c, "x");
+    }
+    sh.set_value(0, 100, "y");
+    sh.set_value_repeated(1, 0, 42, 50);
+    wb.push_sheet(sh);
+
+    let mut buf = Vec::new();
+    OdsWriteOptions::default()
+        .compress_cols(true)
+        .write_fods(&mut wb, &mut buf)?;
+    let xml = String::from_utf8(buf).expect("utf8");
+    assert!(xml.contains("table:number-columns-repeated=\"100\""));
+    assert!(xml.contains("table:number-columns-repeated=\"50\""));
+
+    // the workbook itself is not changed.
+    assert_eq!(wb.sheet(0).value(0, 99), &Value::Text("x".to_string()));
+
+    let wb2 = read_fods_buf(xml.as_bytes())?;
+    let sh2 = wb2.sheet(0);
+    assert_eq!(sh2.value(0, 99), &Value::Text("x".to_string()));
+    assert_eq!(sh2.value(0, 100), &Value::Text("y".to_string()));
+    assert_eq!(sh2.value(1, 49), &Value::Number(42.0));
+    assert_eq!(sh2.cell_repeat(0, 0), 1);
+
+    let wb3 = OdsOptions::default()
+        .use_repeat_for_cells()
+        .read_fods(xml.as_bytes())?;
+    let sh3 = wb3.sheet(0);
+    assert_eq!(sh3.cell_repeat(0, 0), 100);
+    assert_eq!(sh3.cell_repeat(1, 0), 50);
+    assert_eq!(sh3.value(0, 99), &Value::Empty);
+
+    Ok(())
+}
+
+#[test]
+fn test_compress_rows_cols() -> Result<(), OdsError> {
+    let mut wb = WorkBook::new_empty();
+    let mut sh = Sheet::new("1");
+    for r in 0..20 {
+        for c in 0..10 {
+            sh.set_value(r, c, "x");
+        }
+    }
+    wb.push_sheet(sh);
+
+    let mut buf = Vec::new();
+    OdsWriteOptions::default()
+        .compress_rows(true)
+        .compress_cols(true)
+        .write_fods(&mut wb, &mut buf)?;
+    let xml = String::from_utf8(buf).expect("utf8");
+    assert!(xml.contains("table:number-rows-repeated=\"20\""));
+    assert!(xml.contains("table:number-columns-repeated=\"10\""));
+    assert_eq!(xml.matches("<table:table-cell").count(), 1);
+
+    // the workbook itself is not changed.
+    assert_eq!(wb.sheet(0).cell_repeat(0, 0), 1);
+    assert_eq!(wb.sheet(0).value(19, 9), &Value::Text("x".to_string()));
+
+    let wb2 = read_fods_buf(xml.as_bytes())?;
+    let sh2 = wb2.sheet(0);
+    assert_eq!(sh2.value(19, 9), &Value::Text("x".to_string()));
+    assert_eq!(sh2.used_grid_size(), (20, 10));
+
+    Ok(())
+}
+
+#[test]
+fn test_unknown_cell_attr() -> Result<(), OdsError> {
+    let fods = r#"<?xml version="1.0" encoding="UTF-8"?>
+<office:document xmlns:office="urn:oasis:names:tc:opendocument:xmlns:office:1.0" xmlns:table="urn:oasis:names:tc:opendocument:xmlns:table:1.0" xmlns:text="urn:oasis:names:tc:opendocument:xmlns:text:1.0" xmlns:calcext="urn:org:documentfoundation:names:experimental:calc:xmlns:calcext:1.0" xmlns:foo="urn:example:foo" office:version="1.3" office:mimetype="application/vnd.oasis.opendocument.spreadsheet">
+<office:body><office:spreadsheet><table:table table:name="1">
+<table:table-column/>
+<table:table-row foo:row="r1"><table:table-cell office:value-type="float" office:value="1" calcext:value-type="float" foo:cell="c1"><text:p>1</text:p><foo:note foo:id="n1">note</foo:note></table:table-cell></table:table-row>
+<table:table-row><table:table-cell office:value-type="float" office:value="2" calcext:value-type="float"><text:p>2</text:p></table:table-cell></table:table-row>
+</table:table></office:spreadsheet></office:body></office:document>"#;
+
+    let mut wb = read_fods_buf(fods.as_bytes())?;
+    // the value changes, calcext:value-type is stale.
+    wb.sheet_mut(0).set_value(1, 0, "two");
+    // copies and moves keep the unknown content.
+    let sh = wb.sheet_mut(0);
+    let copy = sh.cell(0, 0).expect("cell");
+    sh.add_cell(2, 0, copy);
+    let moved = sh.cell_ref(0, 0).expect("cell").to_owned();
+    sh.add_cell(3, 0, moved);
+
+    let mut buf = Vec::new();
+    OdsWriteOptions::default().write_fods(&mut wb, &mut buf)?;
+    let xml = String::from_utf8(buf).expect("utf8");
+
+    assert!(xml.contains(r#"foo:row="r1""#));
+    assert_eq!(xml.matches(r#"foo:cell="c1""#).count(), 3);
+    assert_eq!(
+        xml.matches(r#"<foo:note foo:id="n1">note</foo:note>"#)
+            .count(),
+        3
+    );
+    assert_eq!(xml.matches(r#"calcext:value-type="float""#).count(), 3);
+
+    Ok(())
+}
+
+#[test]
+fn test_error_value() -> Result<(), OdsError> {
+    let fods = r#"<?xml version="1.0" encoding="UTF-8"?>
+<office:document xmlns:office="urn:oasis:names:tc:opendocument:xmlns:office:1.0" xmlns:table="urn:oasis:names:tc:opendocument:xmlns:table:1.0" xmlns:text="urn:oasis:names:tc:opendocument:xmlns:text:1.0" xmlns:calcext="urn:org:documentfoundation:names:experimental:calc:xmlns:calcext:1.0" office:version="1.3" office:mimetype="application/vnd.oasis.opendocument.spreadsheet">
+<office:body><office:spreadsheet><table:table table:name="1">
+<table:table-column/>
+<table:table-row><table:table-cell table:formula="of:=1/0" office:value-type="string" office:string-value="" calcext:value-type="error"><text:p>#DIV/0!</text:p></table:table-cell></table:table-row>
+<table:table-row><table:table-cell table:formula="of:=NA()" office:value-type="float" office:value="0" calcext:value-type="error"><text:p>#N/A</text:p></table:table-cell></table:table-row>
+</table:table></office:spreadsheet></office:body></office:document>"#;
+
+    let mut wb = read_fods_buf(fods.as_bytes())?;
+    assert_eq!(wb.sheet(0).value(0, 0), &Value::new_error("#DIV/0!"));
+    assert_eq!(wb.sheet(0).value(1, 0), &Value::new_error("#N/A"));
+    assert_eq!(wb.sheet(0).value(1, 0).value_type(), ValueType::Error);
+
+    let mut buf = Vec::new();
+    OdsWriteOptions::default().write_fods(&mut wb, &mut buf)?;
+    let wi = read_fods_buf(&buf)?;
+    assert_eq!(wi.sheet(0).value(0, 0).as_error_opt(), Some("#DIV/0!"));
+    assert_eq!(wi.sheet(0).value(1, 0).as_error_opt(), Some("#N/A"));
+    assert_eq!(
+        wi.sheet(0).formula(0, 0).map(|v| v.as_str()),
+        Some("of:=1/0")
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_formula_result() -> Result<(), OdsError> {
+    let mut wb = WorkBook::new_empty();
+    let mut sh = Sheet::new("1");
+    sh.set_value(0, 0, 21);
+    sh.set_formula_result(0, 1, "of:=[.A1]*2", 42);
+    sh.set_formula_result(0, 2, "of:=1/0", Value::new_error("#DIV/0!"));
+    wb.push_sheet(sh);
+
+    let buf = write_ods_buf(&mut wb, Vec::new())?;
+    let wi = read_ods_buf(&buf)?;
+    let sh = wi.sheet(0);
+
+    let (formula, value) = sh.formula_result(0, 1).expect("formula");
+    assert_eq!(formula, "of:=[.A1]*2");
+    assert_eq!(value.as_i32_opt(), Some(42));
+    let (formula, value) = sh.formula_result(0, 2).expect("formula");
+    assert_eq!(formula, "of:=1/0");
+    assert_eq!(value, &Value::new_error("#DIV/0!"));
+    assert_eq!(sh.formula_result(0, 0), None);
+
+    Ok(())
+}
+
+#[test]
+fn test_float_roundtrip() -> Result<(), OdsError> {
+    let values = [
+        1.0,
+        -3.5,
+        0.1,
+        1e-7,
+        1.5e300,
+        123456789012345680.0,
+        f64::MIN_POSITIVE,
+    ];
+
+    let mut wb = WorkBook::new_empty();
+    let mut sh = Sheet::new("1");
+    for (i, v) in values.iter().enumerate() {
+        sh.set_value(i as u32, 0, *v);
+        sh.set_value(i as u32, 1, Value::Percentage(*v));
+    }
+    sh.set_value(0, 2, "repeat");
+    sh.set_cell_repeat(0, 2, 3);
+    wb.push_sheet(sh);
+
+    let buf = write_ods_buf(&mut wb, Vec::new())?;
+    let wb = read_ods_buf(&buf)?;
+    let sh = wb.sheet(0);
+    for (i, v) in values.iter().enumerate() {
+        assert_eq!(sh.value(i as u32, 0), &Value::Number(*v));
+        assert_eq!(sh.value(i as u32, 1), &Value::Percentage(*v));
+    }
+    assert_eq!(sh.value(0, 4).as_str_or(""), "repeat");
+
+    Ok(())
+}
+
+#[test]
+fn test_escape() -> Result<(), OdsError> {
+    let mut wb = WorkBook::new_empty();
+    let mut sh = Sheet::new("1");
+    sh.set_value(0, 0, "<a href=\"x\">'&'</a>");
+    sh.set_formula(0, 1, "of:=\"a\"&\n\t\"b\"");
+    wb.push_sheet(sh);
+
+    let buf = write_ods_buf(&mut wb, Vec::new())?;
+    let wb2 = read_ods_buf(&buf)?;
+    assert_eq!(wb2.sheet(0).value(0, 0), wb.sheet(0).value(0, 0));
+    assert_eq!(wb2.sheet(0).formula(0, 1), wb.sheet(0).formula(0, 1));
+
+    let buf = write_fods_buf(&mut wb, Vec::new())?;
+    let wb2 = read_fods_buf(&buf)?;
+    assert_eq!(wb2.sheet(0).value(0, 0), wb.sheet(0).value(0, 0));
+    assert_eq!(wb2.sheet(0).formula(0, 1), wb.sheet(0).formula(0, 1));
+
+    // not allowed in xml 1.0
+    wb.sheet_mut(0).set_value(1, 0, "vertical\u{0B}tab");
+    assert!(write_ods_buf(&mut wb, Vec::new()).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_invalid_chars() -> Result<(), OdsError> {
+    let mut wb = WorkBook::new_empty();
+    wb.metadata_mut().title = "title\u{1}".to_string();
+    let mut sh = Sheet::new("1");
+    sh.set_value(0, 0, "vertical\u{0B}tab\u{FFFF}");
+    wb.push_sheet(sh);
+
+    let mut buf = Cursor::new(Vec::new());
+    let res = OdsWriteOptions::default().write_ods(&mut wb, &mut buf);
+    assert!(res.is_err());
+
+    let mut buf = Cursor::new(Vec::new());
+    OdsWriteOptions::default()
+        .invalid_chars(InvalidCharMode::Strip)
+        .write_ods(&mut wb, &mut buf)?;
+    let wb2 = read_ods_buf(&buf.into_inner())?;
+    assert_eq!(wb2.sheet(0).value(0, 0).as_str_or(""), "verticaltab");
+    assert_eq!(wb2.metadata().title, "title");
+
+    let mut buf = Cursor::new(Vec::new());
+    OdsWriteOptions::default()
+        .invalid_chars(InvalidCharMode::Replace)
+        .write_ods(&mut wb, &mut buf)?;
+    let wb2 = read_ods_buf(&buf.into_inner())?;
+    assert_eq!(
+        wb2.sheet(0).value(0, 0).as_str_or(""),
+        "vertical\u{FFFD}tab\u{FFFD}"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_pretty() -> Result<(), OdsError> {
+    let mut wb = WorkBook::new_empty();
+    let mut sh = Sheet::new("1");
+    sh.set_value(0, 0, "  two  spaces ");
+    sh.set_value(1, 1, 42);
+    sh.set_annotation(1, 1, Annotation::new("note"));
+    wb.push_sheet(sh);
+
+    let mut buf = Vec::new();
+    OdsWriteOptions::default()
+        .pretty(true)
+        .write_fods(&mut wb, &mut buf)?;
+    let xml = String::from_utf8(buf.clone()).expect("utf8");
+    assert!(xml.contains("\n    <office:spreadsheet>"), "{}", xml);
+    assert!(
+        xml.contains("<text:p><text:s text:c=\"2\"/>two <text:s/>spaces<text:s/></text:p>"),
+        "{}",
+        xml
+    );
+
+    let wb2 = read_fods_buf(&buf)?;
+    assert_eq!(wb2.sheet(0).value(0, 0), wb.sheet(0).value(0, 0));
+    assert_eq!(wb2.sheet(0).value(1, 1), wb.sheet(0).value(1, 1));
+    assert!(wb2.sheet(0).annotation(1, 1).is_some());
+
+    let mut buf2 = Vec::new();
+    OdsWriteOptions::default()
+        .pretty(true)
+        .write_fods(&mut wb, &mut buf2)?;
+    assert_eq!(buf, buf2);
+
+    // styles come from maps with a different order each time.
+    let styled = || -> Result<Vec<u8>, OdsError> {
+        let mut wb = WorkBook::new_empty();
+        let mut sh = Sheet::new("1");
+        for i in 0..20 {
+            let mut style = CellStyle::new_empty();
+            style.set_name(format!("s{}", i));
+            let s = wb.add_cellstyle(style);
+            sh.set_styled_value(i, 0, i, &s);
+        }
+        wb.push_sheet(sh);
+        let mut buf = Vec::new();
+        OdsWriteOptions::default()
+            .pretty(true)
+            .write_fods(&mut wb, &mut buf)?;
+        Ok(buf)
+    };
+    assert_eq!(styled()?, styled()?);
+
+    let mut buf = Cursor::new(Vec::new());
+    OdsWriteOptions::default()
+        .pretty(true)
+        .write_ods(&mut wb, &mut buf)?;
+    let wb2 = read_ods_buf(&buf.into_inner())?;
+    assert_eq!(wb2.sheet(0).value(0, 0), wb.sheet(0).value(0, 0));
+
+    Ok(())
+}
+
+#[test]
+fn test_target() -> Result<(), OdsError> {
+    let mut wb = WorkBook::new_empty();
+    let mut sh = Sheet::new("1");
+    sh.set_value(0, 0, 1);
+    sh.set_formula(0, 1, "=A1*2");
+    sh.set_formula(0, 2, "of:=[.A1]*3");
+    sh.set_value(1, 0, Value::Error("#DIV/0!".into()));
+    sh.set_value(2, 0, "merged");
+    sh.set_col_span(2, 0, 4);
+    sh.set_value(2, 5, "x");
+    wb.push_sheet(sh);
+
+    let content = |target: Target, wb: &mut WorkBook| -> Result<String, OdsError> {
+        let mut buf = Vec::new();
+        OdsWriteOptions::default()
+            .target(target)
+            .write_fods(wb, &mut buf)?;
+        Ok(String::from_utf8(buf).expect("utf8"))
+    };
+
+    let xml = content(Target::LibreOffice, &mut wb)?;
+    assert!(xml.contains(r#"table:formula="=A1*2""#));
+    assert!(xml.contains(r#"table:formula="of:=[.A1]*3""#));
+    assert!(xml.contains(r#"calcext:value-type="error""#));
+    assert!(xml.contains(r#"<table:covered-table-cell table:number-columns-repeated="3"/>"#));
+
+    let xml = content(Target::Excel, &mut wb)?;
+    assert!(xml.contains(r#"table:formula="of:=[.A1]*2""#));
+    assert!(xml.contains(r#"table:formula="of:=[.A1]*3""#));
+    assert!(!xml.contains("calcext:value-type"));
+    assert!(!xml.contains(r#"<table:covered-table-cell table:number-columns-repeated"#));
+    assert_eq!(xml.matches("<table:covered-table-cell/>").count(), 3);
+
+    let xml = content(Target::GoogleSheets, &mut wb)?;
+    assert!(xml.contains(r#"table:formula="=A1*2""#));
+    assert!(xml.contains(r#"table:formula="=A1*3""#));
+    assert!(!xml.contains("calcext:value-type"));
+
+    let mut buf = Cursor::new(Vec::new());
+    OdsWriteOptions::default()
+        .target(Target::Excel)
+        .write_ods(&mut wb, &mut buf)?;
+    let mut zip = zip::ZipArchive::new(Cursor::new(buf.into_inner()))?;
+    assert_eq!(zip.by_index(0)?.name(), "mimetype");
+    let mut manifest = String::new();
+    zip.by_name("META-INF/manifest.xml")?
+        .read_to_string(&mut manifest)?;
+    let first = manifest.find("manifest:file-entry").expect("entry");
+    assert!(manifest[first..].starts_with(r#"manifest:file-entry manifest:full-path="/""#));
+
+    let wb2 = read_ods_buf(&zip.into_inner().into_inner())?;
+    assert_eq!(wb2.sheet(0).formula(0, 1), Some(&"of:=[.A1]*2".to_string()));
+    assert_eq!(wb2.sheet(0).col_span(2, 0), 4);
+
+    Ok(())
+}
+
+#[test]
+fn test_write_observer() -> Result<(), OdsError> {
+    struct Log {
+        log: Arc<Mutex<Vec<String>>>,
+        cancel_at: u32,
+    }
+
+    impl WriteObserver for Log {
+        fn row_step(&self) -> u32 {
+            10
+        }
+
+        fn sheet_start(&mut self, sheet: usize, name: &str, rows: u32) -> bool {
+            self.log
+                .lock()
+                .expect("log")
+                .push(format!("start {} {} {}", sheet, name, rows));
+            true
+        }
+
+        fn sheet_rows(&mut self, sheet: usize, row: u32) -> bool {
+            self.log
+                .lock()
+                .expect("log")
+                .push(format!("rows {} {}", sheet, row));
+            row < self.cancel_at
+        }
+
+        fn sheet_end(&mut self, sheet: usize) -> bool {
+            self.log.lock().expect("log").push(format!("end {}", sheet));
+            true
+        }
+    }
+
+    let mut wb = WorkBook::new_empty();
+    let mut sh = Sheet::new("a");
+    for r in 0..25 {
+        sh.set_value(r, 0, r);
+    }
+    wb.push_sheet(sh);
+    let mut sh = Sheet::new("b");
+    sh.set_value(0, 0, 1);
+    wb.push_sheet(sh);
+
+    let log = Arc::new(Mutex::new(Vec::new()));
+    let mut buf = Cursor::new(Vec::new());
+    OdsWriteOptions::default()
+        .observer(Log {
+            log: log.clone(),
+            cancel_at: u32::MAX,
+        })
+        .write_ods(&mut wb, &mut buf)?;
+    assert_eq!(
+        *log.lock().expect("log"),
+        vec![
+            "start 0 a 25",
+            "rows 0 10",
+            "rows 0 20",
+            "end 0",
+            "start 1 b 1",
+            "end 1"
+        ]
+    );
+
+    let log = Arc::new(Mutex::new(Vec::new()));
+    let mut buf = Cursor::new(Vec::new());
+    let res = OdsWriteOptions::default()
+        .observer(Log {
+            log: log.clone(),
+            cancel_at: 20,
+        })
+        .write_ods(&mut wb, &mut buf);
+    assert!(matches!(res, Err(OdsError::Cancelled)));
+    assert_eq!(
+        log.lock().expect("log").last().map(|v| v.as_str()),
+        Some("rows 0 20")
+    );
+
+    // the options can be moved to another thread.
+    let options = OdsWriteOptions::default().observer(Log {
+        log: log.clone(),
+        cancel_at: u32::MAX,
+    });
+    let buf = std::thread::spawn(move || -> Result<Vec<u8>, OdsError> {
+        let mut buf = Cursor::new(Vec::new());
+        options.write_ods(&mut wb, &mut buf)?;
+        Ok(buf.into_inner())
+    })
+    .join()
+    .expect("thread")?;
+    assert!(!buf.is_empty());
+
+    Ok(())
+}
+
+#[test]
+fn test_cancel() -> Result<(), OdsError> {
+    let mut wb = WorkBook::new_empty();
+    let mut sh = Sheet::new("a");
+    for r in 0..100 {
+        sh.set_value(r, 0, r);
+    }
+    wb.push_sheet(sh);
+
+    let cancel = Arc::new(AtomicBool::new(false));
+    let mut buf = Cursor::new(Vec::new());
+    OdsWriteOptions::default()
+        .cancel(cancel.clone())
+        .write_ods(&mut wb, &mut buf)?;
+    let buf = buf.into_inner();
+
+    let wb2 = OdsOptions::default()
+        .cancel(cancel.clone())
+        .read_ods(Cursor::new(&buf))?;
+    assert_eq!(wb2.sheet(0).value(99, 0).as_i32_or(0), 99);
+
+    cancel.store(true, Ordering::Relaxed);
+    let res = OdsOptions::default()
+        .cancel(cancel.clone())
+        .read_ods(Cursor::new(&buf));
+    assert!(matches!(res, Err(OdsError::Cancelled)));
+    let res = OdsOptions::default()
+        .cancel(cancel.clone())
+        .read_fods(Cursor::new(write_fods_buf(&mut wb, Vec::new())?));
+    assert!(matches!(res, Err(OdsError::Cancelled)));
+
+    let res = OdsWriteOptions::default()
+        .cancel(cancel.clone())
+        .write_ods(&mut wb, Cursor::new(Vec::new()));
+    assert!(matches!(res, Err(OdsError::Cancelled)));
+
+    Ok(())
+}
+
+#[test]
+fn test_text_whitespace() -> Result<(), OdsError> {
+    let texts = [
+        "a  b",
+        " lead",
+        "trail ",
+        "   ",
+        "tab\there",
+        "\t x \t",
+        "a b c",
+        "x     y",
+    ];
+
+    let mut wb = WorkBook::new_empty();
+    let mut sh = Sheet::new("1");
+    for (i, t) in texts.iter().enumerate() {
+        sh.set_value(i as u32, 0, *t);
+    }
+    wb.push_sheet(sh);
+
+    let buf = write_fods_buf(&mut wb, Vec::new())?;
+    let xml = String::from_utf8(buf.clone()).expect("utf8");
+    assert!(xml.contains("<text:p>a <text:s/>b</text:p>"));
+    assert!(xml.contains("<text:p><text:s/>lead</text:p>"));
+    assert!(xml.contains("<text:p>x <text:s text:c=\"4\"/>y</text:p>"));
+    assert!(xml.contains("<text:p>tab<text:tab/>here</text:p>"));
+    assert!(xml.contains("<text:p>a b c</text:p>"));
+
+    let wb_fods = read_fods_buf(&buf)?;
+    let buf = write_ods_buf(&mut wb, Vec::new())?;
+    let wb_ods = read_ods_buf(&buf)?;
+    for (i, t) in texts.iter().enumerate() {
+        let expected = Value::Text(t.to_string());
+        assert_eq!(wb_fods.sheet(0).value(i as u32, 0), &expected);
+        assert_eq!(wb_ods.sheet(0).value(i as u32, 0), &expected);
+    }
+
+    // as written by LibreOffice.
+    let fods = r#"<?xml version="1.0" encoding="UTF-8"?>
+<office:document xmlns:office="urn:oasis:names:tc:opendocument:xmlns:office:1.0" xmlns:table="urn:oasis:names:tc:opendocument:xmlns:table:1.0" xmlns:text="urn:oasis:names:tc:opendocument:xmlns:text:1.0" office:version="1.3" office:mimetype="application/vnd.oasis.opendocument.spreadsheet">
+<office:body><office:spreadsheet><table:table table:name="T">
+<table:table-row>
+<table:table-cell office:value-type="string"><text:p>a<text:s text:c="3"/>b<text:tab/>c<text:line-break/>d</text:p></table:table-cell>
+<table:table-cell office:value-type="string"><text:p>a<text:span>b</text:span><text:s/></text:p></table:table-cell>
+</table:table-row>
+</table:table></office:spreadsheet></office:body></office:document>"#;
+    let wb = read_fods_buf(fods.as_bytes())?;
+    assert_eq!(wb.sheet(0).value(0, 0), &Value::Text("a   b\tc\nd".into()));
+    assert_eq!(wb.sheet(0).value(0, 1).as_cow_str_or(""), "ab ");
+
+    Ok(())
+}
+
+#[test]
+fn test_text_paragraphs() -> Result<(), OdsError> {
+    let mut wb = WorkBook::new_empty();
+    let mut sh = Sheet::new("1");
+    sh.set_paragraphs(0, 0, ["first", "", "third"]);
+    sh.set_value(0, 1, "one\ntwo");
+    sh.set_value(0, 2, "");
+    wb.push_sheet(sh);
+
+    let buf = write_ods_buf(&mut wb, Vec::new())?;
+    let wb = read_ods_buf(&buf)?;
+    let sh = wb.sheet(0);
+    assert_eq!(sh.value(0, 0), &Value::Text("first\n\nthird".into()));
+    assert_eq!(sh.paragraphs(0, 0), vec!["first", "", "third"]);
+    assert_eq!(sh.value(0, 1), &Value::Text("one\ntwo".into()));
+    assert_eq!(sh.value(0, 2), &Value::Text("".into()));
+    assert!(sh.paragraphs(0, 3).is_empty());
+
+    let wb = read_ods("tests/test_write_read_3.ods")?;
+    let v = wb.sheet(0).value(0, 0);
+    assert_eq!(v.value_type(), ValueType::TextXml);
+    assert_eq!(v.paragraphs().join("\n"), v.as_cow_str_or(""));
+
+    Ok(())
+}
+
+#[test]
+fn test_text_ruby() -> Result<(), OdsError> {
+    let mut wb = WorkBook::new_empty();
+
+    let mut ruby = RubyStyle::new("ru1");
+    ruby.set_ruby_position(RubyPosition::Above);
+    ruby.set_ruby_align(RubyAlign::Center);
+    let ruby_ref = wb.add_rubystyle(ruby);
+
+    let mut emph = TextStyle::new("te1");
+    emph.set_text_emphasize(TextEmphasize::Dot, TextEmphasizePosition::Above);
+    emph.set_text_combine(TextCombine::Letters);
+    let emph_ref = wb.add_textstyle(emph);
+
+    let mut sh = Sheet::new("1");
+    sh.set_value(
+        0,
+        0,
+        TextP::new()
+            .tag(
+                TextRuby::new()
+                    .style_name(&ruby_ref)
+                    .tag(TextRubyBase::new().text("売上"))
+                    .tag(TextRubyText::new().text("うりあげ")),
+            )
+            .tag(TextSpan::new().style_name(&emph_ref).text("高"))
+            .into_xmltag(),
+    );
+    wb.push_sheet(sh);
+
+    let buf = write_ods_buf(&mut wb, Vec::new())?;
+    let wb = read_ods_buf(&buf)?;
+
+    let v = wb.sheet(0).value(0, 0);
+    assert_eq!(v.value_type(), ValueType::TextXml);
+    assert_eq!(v.as_cow_str_or(""), "売上高");
+    let Value::TextXml(xml) = v else {
+        unreachable!()
+    };
+    let XmlContent::Tag(ruby) = &xml[0].content()[0] else {
+        panic!("ruby expected")
+    };
+    assert_eq!(ruby.name(), "text:ruby");
+    assert_eq!(ruby.get_attr("text:style-name"), Some("ru1"));
+
+    let ruby = wb.rubystyle("ru1").expect("rubystyle");
+    assert_eq!(ruby.rubystyle().attr("style:ruby-position"), Some("above"));
+    assert_eq!(ruby.rubystyle().attr("style:ruby-align"), Some("center"));
+    let emph = wb.textstyle("te1").expect("textstyle");
+    assert_eq!(
+        emph.textstyle().attr("style:text-emphasize"),
+        Some("dot above")
+    );
+    assert_eq!(emph.textstyle().attr("style:text-combine"), Some("letters"));
+
+    Ok(())
+}